@@ -0,0 +1,145 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Derive macros for the `arrow::row::ArrowSerialize` and
+//! `arrow::row::ArrowDeserialize` traits, which convert between structs with named
+//! fields and record batches with a column for each field. The column of a field has
+//! the name of the field and the data type given by its `arrow::row::ArrowField`
+//! implementation.
+//!
+//! The macros are exported by the `arrow` crate with the `derive` feature.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, FieldsNamed};
+
+/// Derive `arrow::row::ArrowSerialize` for a struct with named fields
+#[proc_macro_derive(ArrowSerialize)]
+pub fn derive_arrow_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match named_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let schema_fields = fields.named.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let column = ident.to_string();
+        quote! {
+            ::arrow::datatypes::Field::new(
+                #column,
+                <#ty as ::arrow::row::ArrowField>::data_type(),
+                <#ty as ::arrow::row::ArrowField>::is_nullable(),
+            )
+        }
+    });
+    let columns = fields.named.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        quote! {
+            <#ty as ::arrow::row::ArrowField>::build_array(
+                rows.iter().map(|row| Some(&row.#ident)).collect(),
+            )?
+        }
+    });
+
+    let expanded = quote! {
+        impl #impl_generics ::arrow::row::ArrowSerialize for #name #ty_generics #where_clause {
+            fn schema() -> ::arrow::datatypes::Schema {
+                ::arrow::datatypes::Schema::new(vec![#(#schema_fields),*])
+            }
+
+            fn to_record_batch(
+                rows: &[Self],
+            ) -> ::arrow::error::Result<::arrow::record_batch::RecordBatch> {
+                ::arrow::record_batch::RecordBatch::try_new(
+                    ::std::sync::Arc::new(
+                        <Self as ::arrow::row::ArrowSerialize>::schema(),
+                    ),
+                    vec![#(#columns),*],
+                )
+            }
+        }
+    };
+    expanded.into()
+}
+
+/// Derive `arrow::row::ArrowDeserialize` for a struct with named fields
+#[proc_macro_derive(ArrowDeserialize)]
+pub fn derive_arrow_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match named_fields(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let idents: Vec<_> = fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let columns: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    // the variables holding the columns have a prefix so that they cannot shadow the
+    // other variables of the generated code
+    let arrays: Vec<_> = idents
+        .iter()
+        .map(|ident| Ident::new(&format!("__column_{}", ident), Span::call_site()))
+        .collect();
+
+    let expanded = quote! {
+        impl #impl_generics ::arrow::row::ArrowDeserialize for #name #ty_generics #where_clause {
+            fn from_record_batch(
+                batch: &::arrow::record_batch::RecordBatch,
+            ) -> ::arrow::error::Result<Vec<Self>> {
+                #(let #arrays = ::arrow::row::column(batch, #columns)?;)*
+                (0..batch.num_rows())
+                    .map(|__row| {
+                        Ok(#name {
+                            #(#idents: ::arrow::row::read_field(#arrays, __row, #columns)?),*
+                        })
+                    })
+                    .collect()
+            }
+        }
+    };
+    expanded.into()
+}
+
+/// The named fields of a struct, or an error for other items
+fn named_fields(input: &DeriveInput) -> Result<&FieldsNamed, Error> {
+    match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => Ok(fields),
+            _ => Err(Error::new(
+                Span::call_site(),
+                "Arrow derive macros require a struct with named fields",
+            )),
+        },
+        _ => Err(Error::new(
+            Span::call_site(),
+            "Arrow derive macros can only be derived for structs",
+        )),
+    }
+}
@@ -0,0 +1,107 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Int32Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::Result;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{ArrowDeserialize, ArrowSerialize};
+
+#[derive(ArrowSerialize, ArrowDeserialize, Debug, PartialEq)]
+struct Trip {
+    id: u64,
+    city: String,
+    passengers: Option<i32>,
+    fare: f64,
+    paid: bool,
+}
+
+#[test]
+fn derive_round_trip() -> Result<()> {
+    let trips = vec![
+        Trip {
+            id: 1,
+            city: "Berlin".to_string(),
+            passengers: Some(2),
+            fare: 12.5,
+            paid: true,
+        },
+        Trip {
+            id: 2,
+            city: "Paris".to_string(),
+            passengers: None,
+            fare: 8.0,
+            paid: false,
+        },
+    ];
+
+    let batch = Trip::to_record_batch(&trips)?;
+    assert_eq!(
+        batch.schema().as_ref(),
+        &Schema::new(vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("city", DataType::Utf8, false),
+            Field::new("passengers", DataType::Int32, true),
+            Field::new("fare", DataType::Float64, false),
+            Field::new("paid", DataType::Boolean, false),
+        ])
+    );
+    assert_eq!(Trip::from_record_batch(&batch)?, trips);
+    Ok(())
+}
+
+#[derive(ArrowDeserialize, Debug, Clone, PartialEq)]
+struct Passengers {
+    passengers: i32,
+    city: Option<String>,
+}
+
+#[test]
+fn derive_deserialize_by_column_name() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("city", DataType::Utf8, true),
+        Field::new("unused", DataType::Int32, false),
+        Field::new("passengers", DataType::Int32, true),
+    ]));
+    let batch = |passengers: Vec<Option<i32>>| {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["Rome"; passengers.len()])),
+                Arc::new(Int32Array::from(vec![0; passengers.len()])),
+                Arc::new(Int32Array::from(passengers)),
+            ],
+        )
+    };
+
+    // the null value of the second row requires an optional field
+    assert!(Passengers::from_record_batch(&batch(vec![Some(3), None])?).is_err());
+    let batches = vec![batch(vec![Some(3)])?, batch(vec![Some(3)])?];
+    assert_eq!(
+        Passengers::from_record_batches(&batches)?,
+        vec![
+            Passengers {
+                passengers: 3,
+                city: Some("Rome".to_string()),
+            };
+            2
+        ]
+    );
+    Ok(())
+}
@@ -34,6 +34,7 @@ use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 use crate::memory;
 use crate::util::bit_util;
+use crate::util::interval;
 
 /// Number of seconds in a day
 const SECONDS_IN_DAY: i64 = 86_400;
@@ -198,6 +199,41 @@ pub trait Array: fmt::Debug + Send + Sync + ArrayEqual + JsonEqual {
     fn null_count(&self) -> usize {
         self.data().null_count()
     }
+
+    /// Returns the total number of bytes of memory occupied by the buffers of this
+    /// array, including its null bitmap and the buffers of child arrays. Buffers are
+    /// counted with their full capacity, also when this array is a slice of them.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use arrow::array::{Array, Int32Array};
+    ///
+    /// let array = Int32Array::from(vec![1, 2, 3]);
+    /// let slice = array.slice(1, 2);
+    ///
+    /// assert!(array.get_buffer_memory_size() >= 3 * 4);
+    /// assert_eq!(slice.get_buffer_memory_size(), array.get_buffer_memory_size());
+    /// ```
+    fn get_buffer_memory_size(&self) -> usize {
+        self.data_ref().get_buffer_memory_size()
+    }
+
+    /// Returns the total number of bytes of memory occupied by this array, i.e. the
+    /// size of its buffers plus the size of the structs that hold them.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use arrow::array::{Array, Int32Array};
+    ///
+    /// let array = Int32Array::from(vec![1, 2, 3]);
+    ///
+    /// assert!(array.get_array_memory_size() > array.get_buffer_memory_size());
+    /// ```
+    fn get_array_memory_size(&self) -> usize {
+        self.data_ref().get_array_memory_size() + mem::size_of_val(self)
+    }
 }
 
 /// A reference-counted reference to a generic `Array`.
@@ -269,6 +305,7 @@ pub fn make_array(data: ArrayDataRef) -> ArrayRef {
         DataType::FixedSizeBinary(_) => {
             Arc::new(FixedSizeBinaryArray::from(data)) as ArrayRef
         }
+        DataType::Decimal(_, _) => Arc::new(Decimal128Array::from(data)) as ArrayRef,
         DataType::Utf8 => Arc::new(StringArray::from(data)) as ArrayRef,
         DataType::List(_) => Arc::new(ListArray::from(data)) as ArrayRef,
         DataType::Struct(_) => Arc::new(StructArray::from(data)) as ArrayRef,
@@ -470,6 +507,105 @@ impl<T: ArrowNumericType> PrimitiveArray<T> {
     pub fn builder(capacity: usize) -> PrimitiveBuilder<T> {
         PrimitiveBuilder::<T>::new(capacity)
     }
+
+    /// Creates a primitive array from an iterator of optional values that reports its
+    /// exact length, such as an iterator over a vector or a range.
+    ///
+    /// The value and null buffers are allocated once for the reported length and each
+    /// value is written without checking the capacity of the buffers, which makes this
+    /// faster than appending the values to a builder.
+    ///
+    /// # Safety
+    ///
+    /// The upper bound of the `size_hint` of the iterator must be the exact number of
+    /// items it returns. Returning more items results in undefined behavior, returning
+    /// fewer items panics.
+    pub unsafe fn from_trusted_len_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Option<T::Native>>,
+    {
+        let iter = iter.into_iter();
+        let len = iter
+            .size_hint()
+            .1
+            .expect("from_trusted_len_iter requires an upper bound");
+
+        let num_bytes = bit_util::ceil(len, 8);
+        let mut null_buf = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+        let mut val_buf = MutableBuffer::new(len * mem::size_of::<T::Native>());
+        val_buf.resize(len * mem::size_of::<T::Native>()).unwrap();
+
+        let null_ptr = null_buf.data_mut().as_mut_ptr();
+        let val_ptr = val_buf.data_mut().as_mut_ptr() as *mut T::Native;
+        let mut null_count = 0;
+        let mut i = 0;
+        for item in iter {
+            match item {
+                Some(v) => {
+                    bit_util::set_bit_raw(null_ptr, i);
+                    std::ptr::write(val_ptr.add(i), v);
+                }
+                None => {
+                    null_count += 1;
+                    std::ptr::write(val_ptr.add(i), T::default_value());
+                }
+            }
+            i += 1;
+        }
+        assert_eq!(
+            i, len,
+            "trusted iterator returned fewer items than reported"
+        );
+
+        let array_data = ArrayData::builder(T::get_data_type())
+            .len(len)
+            .add_buffer(val_buf.freeze())
+            .null_count(null_count)
+            .null_bit_buffer(null_buf.freeze())
+            .build();
+        PrimitiveArray::from(array_data)
+    }
+
+    /// Creates a primitive array from a buffer of values and an optional null bitmap
+    /// without copying them, such as buffers received through FFI.
+    ///
+    /// The length of the array is the number of values in `values`, whose memory must
+    /// be aligned to the native type. A set bit in `null_bit_buffer` marks a valid value.
+    /// Returns an error if the buffers do not fit together.
+    pub fn try_new(values: Buffer, null_bit_buffer: Option<Buffer>) -> Result<Self> {
+        let size = mem::size_of::<T::Native>();
+        if values.len() % size != 0 {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Buffer of {} bytes does not hold a whole number of {:?} values",
+                values.len(),
+                T::get_data_type()
+            )));
+        }
+        if !memory::is_aligned(values.raw_data(), mem::align_of::<T::Native>()) {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Buffer is not aligned to {:?} values",
+                T::get_data_type()
+            )));
+        }
+        let len = values.len() / size;
+        let mut builder = ArrayData::builder(T::get_data_type())
+            .len(len)
+            .add_buffer(values);
+        if let Some(null_bit_buffer) = null_bit_buffer {
+            validate_null_bit_buffer(&null_bit_buffer, len)?;
+            builder = builder.null_bit_buffer(null_bit_buffer);
+        }
+        Ok(PrimitiveArray::from(builder.build()))
+    }
+
+    /// Creates a primitive array that takes over the memory of a vector of values,
+    /// along with an optional null bitmap, without copying it.
+    pub fn try_from_vec(
+        values: Vec<T::Native>,
+        null_bit_buffer: Option<Buffer>,
+    ) -> Result<Self> {
+        Self::try_new(Buffer::from_vec(values), null_bit_buffer)
+    }
 }
 
 impl<T: ArrowTemporalType + ArrowNumericType> PrimitiveArray<T>
@@ -636,6 +772,46 @@ where
     }
 }
 
+impl IntervalYearMonthArray {
+    /// Returns the value as an ISO 8601 duration of years and months, such as `P1Y2M`
+    pub fn value_as_iso8601(&self, i: usize) -> String {
+        interval::format_year_month(self.value(i))
+    }
+}
+
+impl IntervalDayTimeArray {
+    /// Returns the number of days and milliseconds of a value
+    pub fn value_as_days_millis(&self, i: usize) -> (i32, i32) {
+        interval::day_time_parts(self.value(i))
+    }
+
+    /// Returns the value as an ISO 8601 duration of days and time, such as
+    /// `P3DT4H5M6.5S`
+    pub fn value_as_iso8601(&self, i: usize) -> String {
+        interval::format_day_time(self.value(i))
+    }
+}
+
+impl fmt::Debug for IntervalYearMonthArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PrimitiveArray<{:?}>\n[\n", self.data_type())?;
+        print_long_array(self, f, |array, index, f| {
+            write!(f, "{}", array.value_as_iso8601(index))
+        })?;
+        write!(f, "]")
+    }
+}
+
+impl fmt::Debug for IntervalDayTimeArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PrimitiveArray<{:?}>\n[\n", self.data_type())?;
+        print_long_array(self, f, |array, index, f| {
+            write!(f, "{}", array.value_as_iso8601(index))
+        })?;
+        write!(f, "]")
+    }
+}
+
 /// Specific implementation for Boolean arrays due to bit-packing
 impl PrimitiveArray<BooleanType> {
     pub fn new(length: usize, values: Buffer, null_count: usize, offset: usize) -> Self {
@@ -666,6 +842,83 @@ impl PrimitiveArray<BooleanType> {
     pub fn builder(capacity: usize) -> BooleanBuilder {
         BooleanBuilder::new(capacity)
     }
+
+    /// Creates a boolean array from an iterator of optional values that reports its
+    /// exact length, allocating the value and null bitmaps only once.
+    ///
+    /// # Safety
+    ///
+    /// The upper bound of the `size_hint` of the iterator must be the exact number of
+    /// items it returns. Returning more items results in undefined behavior, returning
+    /// fewer items panics.
+    pub unsafe fn from_trusted_len_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Option<bool>>,
+    {
+        let iter = iter.into_iter();
+        let len = iter
+            .size_hint()
+            .1
+            .expect("from_trusted_len_iter requires an upper bound");
+
+        let num_bytes = bit_util::ceil(len, 8);
+        let mut null_buf = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+        let mut val_buf = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+
+        let null_ptr = null_buf.data_mut().as_mut_ptr();
+        let val_ptr = val_buf.data_mut().as_mut_ptr();
+        let mut null_count = 0;
+        let mut i = 0;
+        for item in iter {
+            match item {
+                Some(v) => {
+                    bit_util::set_bit_raw(null_ptr, i);
+                    if v {
+                        bit_util::set_bit_raw(val_ptr, i);
+                    }
+                }
+                None => null_count += 1,
+            }
+            i += 1;
+        }
+        assert_eq!(
+            i, len,
+            "trusted iterator returned fewer items than reported"
+        );
+
+        let array_data = ArrayData::builder(DataType::Boolean)
+            .len(len)
+            .add_buffer(val_buf.freeze())
+            .null_count(null_count)
+            .null_bit_buffer(null_buf.freeze())
+            .build();
+        BooleanArray::from(array_data)
+    }
+
+    /// Creates a boolean array of `len` values from a bitmap of values and an optional
+    /// null bitmap without copying them. Returns an error if a bitmap holds fewer than
+    /// `len` bits.
+    pub fn try_new(
+        len: usize,
+        values: Buffer,
+        null_bit_buffer: Option<Buffer>,
+    ) -> Result<Self> {
+        if values.len() < bit_util::ceil(len, 8) {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Value bitmap of {} bytes is too short for {} values",
+                values.len(),
+                len
+            )));
+        }
+        let mut builder = ArrayData::builder(DataType::Boolean)
+            .len(len)
+            .add_buffer(values);
+        if let Some(null_bit_buffer) = null_bit_buffer {
+            validate_null_bit_buffer(&null_bit_buffer, len)?;
+            builder = builder.null_bit_buffer(null_bit_buffer);
+        }
+        Ok(BooleanArray::from(builder.build()))
+    }
 }
 
 impl fmt::Debug for PrimitiveArray<BooleanType> {
@@ -886,6 +1139,18 @@ impl From<Vec<Option<bool>>> for BooleanArray {
     }
 }
 
+/// Check that a null bitmap holds a bit for each of the `len` values of an array
+fn validate_null_bit_buffer(null_bit_buffer: &Buffer, len: usize) -> Result<()> {
+    if null_bit_buffer.len() < bit_util::ceil(len, 8) {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Null bitmap of {} bytes is too short for {} values",
+            null_bit_buffer.len(),
+            len
+        )));
+    }
+    Ok(())
+}
+
 /// Constructs a `PrimitiveArray` from an array data reference.
 impl<T: ArrowPrimitiveType> From<ArrayDataRef> for PrimitiveArray<T> {
     default fn from(data: ArrayDataRef) -> Self {
@@ -1296,6 +1561,136 @@ impl StringArray {
         self.data.buffers()[1].clone()
     }
 
+    /// Creates a string array from an iterator of optional values that reports its
+    /// exact length.
+    ///
+    /// The offset and null buffers are allocated once for the reported length, so only
+    /// the buffer holding the bytes of the values grows while iterating.
+    ///
+    /// # Safety
+    ///
+    /// The upper bound of the `size_hint` of the iterator must be the exact number of
+    /// items it returns. Returning more items results in undefined behavior, returning
+    /// fewer items panics.
+    pub unsafe fn from_trusted_len_iter<I, S>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Option<S>>,
+        S: AsRef<str>,
+    {
+        let iter = iter.into_iter();
+        let len = iter
+            .size_hint()
+            .1
+            .expect("from_trusted_len_iter requires an upper bound");
+
+        let num_bytes = bit_util::ceil(len, 8);
+        let mut null_buf = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+        let mut offsets_buf = MutableBuffer::new((len + 1) * mem::size_of::<i32>());
+        offsets_buf
+            .resize((len + 1) * mem::size_of::<i32>())
+            .unwrap();
+        let mut values_buf = MutableBuffer::new(0);
+
+        let null_ptr = null_buf.data_mut().as_mut_ptr();
+        let offsets_ptr = offsets_buf.data_mut().as_mut_ptr() as *mut i32;
+        std::ptr::write(offsets_ptr, 0);
+        let mut null_count = 0;
+        let mut i = 0;
+        for item in iter {
+            match item {
+                Some(v) => {
+                    bit_util::set_bit_raw(null_ptr, i);
+                    let bytes = v.as_ref().as_bytes();
+                    values_buf.reserve(values_buf.len() + bytes.len()).unwrap();
+                    values_buf.write_all(bytes).unwrap();
+                }
+                None => null_count += 1,
+            }
+            i += 1;
+            std::ptr::write(offsets_ptr.add(i), values_buf.len() as i32);
+        }
+        assert_eq!(
+            i, len,
+            "trusted iterator returned fewer items than reported"
+        );
+
+        let array_data = ArrayData::builder(DataType::Utf8)
+            .len(len)
+            .add_buffer(offsets_buf.freeze())
+            .add_buffer(values_buf.freeze())
+            .null_count(null_count)
+            .null_bit_buffer(null_buf.freeze())
+            .build();
+        StringArray::from(array_data)
+    }
+
+    /// Creates a string array from a buffer of `i32` offsets, a buffer of UTF-8 encoded
+    /// values and an optional null bitmap without copying them.
+    ///
+    /// The length of the array is one less than the number of offsets. Returns an error
+    /// if the offsets are not increasing, point outside of the values or split a value
+    /// that is not valid UTF-8.
+    pub fn try_new(
+        offsets: Buffer,
+        values: Buffer,
+        null_bit_buffer: Option<Buffer>,
+    ) -> Result<Self> {
+        if offsets.len() % mem::size_of::<i32>() != 0
+            || offsets.len() < mem::size_of::<i32>()
+            || !memory::is_aligned(offsets.raw_data(), mem::align_of::<i32>())
+        {
+            return Err(ArrowError::InvalidArgumentError(
+                "Offset buffer must hold at least one aligned i32 offset".to_string(),
+            ));
+        }
+        let offset_values = unsafe { offsets.typed_data::<i32>() };
+        let len = offset_values.len() - 1;
+        if offset_values[0] < 0 {
+            return Err(ArrowError::InvalidArgumentError(
+                "Offsets must not be negative".to_string(),
+            ));
+        }
+        for (i, window) in offset_values.windows(2).enumerate() {
+            let (start, end) = (window[0] as usize, window[1] as usize);
+            if window[1] < window[0] || end > values.len() {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "Offsets of value {} are out of bounds",
+                    i
+                )));
+            }
+            if std::str::from_utf8(&values.data()[start..end]).is_err() {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "Value {} is not valid UTF-8",
+                    i
+                )));
+            }
+        }
+
+        let mut builder = ArrayData::builder(DataType::Utf8)
+            .len(len)
+            .add_buffer(offsets)
+            .add_buffer(values);
+        if let Some(null_bit_buffer) = null_bit_buffer {
+            validate_null_bit_buffer(&null_bit_buffer, len)?;
+            builder = builder.null_bit_buffer(null_bit_buffer);
+        }
+        Ok(StringArray::from(builder.build()))
+    }
+
+    /// Creates a string array that takes over the memory of vectors of offsets and
+    /// values, along with an optional null bitmap, without copying them.
+    pub fn try_from_vecs(
+        offsets: Vec<i32>,
+        values: Vec<u8>,
+        null_bit_buffer: Option<Buffer>,
+    ) -> Result<Self> {
+        Self::try_new(
+            Buffer::from_vec(offsets),
+            Buffer::from_vec(values),
+            null_bit_buffer,
+        )
+    }
+
     #[inline]
     fn value_offset_at(&self, i: usize) -> i32 {
         unsafe { *self.value_offsets.get().offset(i as isize) }
@@ -1625,6 +2020,199 @@ impl Array for FixedSizeBinaryArray {
     }
 }
 
+/// An array of decimal values. Each value is a 128-bit two's complement integer in
+/// little-endian byte order, which is the decimal value multiplied by `10^scale`.
+pub struct Decimal128Array {
+    data: ArrayDataRef,
+    value_data: RawPtrBox<u8>,
+    precision: usize,
+    scale: usize,
+}
+
+impl Decimal128Array {
+    /// The number of bytes of each value
+    pub const VALUE_LENGTH: usize = 16;
+
+    /// Returns the unscaled integer of the element at index `i`.
+    pub fn value(&self, i: usize) -> i128 {
+        assert!(i < self.data.len(), "Decimal128Array out of bounds access");
+        let offset = i.checked_add(self.data.offset()).unwrap();
+        unsafe {
+            let pos = self
+                .value_data
+                .get()
+                .offset((offset * Self::VALUE_LENGTH) as isize);
+            std::ptr::read_unaligned(pos as *const i128)
+        }
+    }
+
+    /// Returns the element at index `i` formatted as a decimal number with `scale`
+    /// digits after the decimal point, e.g. "-12.30" for the value -1230 and scale 2.
+    pub fn value_as_string(&self, i: usize) -> String {
+        let value = self.value(i);
+        let digits = (value.wrapping_abs() as u128).to_string();
+        let digits = if digits.len() <= self.scale {
+            format!("{:0>width$}", digits, width = self.scale + 1)
+        } else {
+            digits
+        };
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale);
+        let sign = if value < 0 { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{}{}", sign, integer)
+        } else {
+            format!("{}{}.{}", sign, integer, fraction)
+        }
+    }
+
+    /// Returns the total number of digits of the values
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// Returns the number of digits after the decimal point of the values
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    /// Parses a decimal number such as "-12.3" into its unscaled integer with `scale`
+    /// digits after the decimal point, rounding any further digits half away from zero.
+    /// Returns `None` if the string is not a decimal number or the value does not fit
+    /// into `precision` digits.
+    pub fn parse_value(s: &str, precision: usize, scale: usize) -> Option<i128> {
+        let s = s.trim();
+        let (negative, digits) = match s.as_bytes().first()? {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = match digits.find('.') {
+            Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+            None => (digits, ""),
+        };
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let mut value: i128 = 0;
+        for b in
+            integer.bytes().chain(fraction.bytes().chain(
+                std::iter::repeat(b'0').take(scale.saturating_sub(fraction.len())),
+            ))
+        {
+            value = value.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+        }
+        // drop the digits beyond the scale, rounding half away from zero
+        let excess = fraction.len().saturating_sub(scale);
+        if excess > 0 {
+            let divisor = 10_i128.checked_pow(excess as u32)?;
+            let remainder = value % divisor;
+            value /= divisor;
+            if remainder * 2 >= divisor {
+                value += 1;
+            }
+        }
+        let value = if negative { -value } else { value };
+        if Self::fits_precision(value, precision) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Converts the unscaled integer `value` of a decimal with `from_scale` digits after
+    /// the decimal point to one with `to_scale` digits, rounding half away from zero.
+    /// Returns `None` if the result does not fit into `precision` digits.
+    pub fn rescale_value(
+        value: i128,
+        from_scale: usize,
+        to_scale: usize,
+        precision: usize,
+    ) -> Option<i128> {
+        let value = if to_scale >= from_scale {
+            value.checked_mul(10_i128.checked_pow((to_scale - from_scale) as u32)?)?
+        } else {
+            let divisor = match 10_i128.checked_pow((from_scale - to_scale) as u32) {
+                Some(divisor) => divisor,
+                // the value has fewer digits than are dropped
+                None => return Some(0),
+            };
+            let rounded = value / divisor;
+            if (value % divisor).abs() * 2 >= divisor {
+                rounded + value.signum()
+            } else {
+                rounded
+            }
+        };
+        if Self::fits_precision(value, precision) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the unscaled integer `value` has at most `precision` digits
+    pub fn fits_precision(value: i128, precision: usize) -> bool {
+        match 10_i128.checked_pow(precision as u32) {
+            Some(limit) => value > -limit && value < limit,
+            None => true,
+        }
+    }
+}
+
+impl From<ArrayDataRef> for Decimal128Array {
+    fn from(data: ArrayDataRef) -> Self {
+        assert_eq!(
+            data.buffers().len(),
+            1,
+            "Decimal128Array data should contain 1 buffer only (values)"
+        );
+        let value_data = data.buffers()[0].raw_data();
+        let (precision, scale) = match data.data_type() {
+            DataType::Decimal(precision, scale) => (*precision, *scale),
+            _ => panic!("Expected data type to be Decimal"),
+        };
+        Self {
+            data: data.clone(),
+            value_data: RawPtrBox::new(value_data),
+            precision,
+            scale,
+        }
+    }
+}
+
+impl fmt::Debug for Decimal128Array {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Decimal128Array<{}, {}>\n[\n",
+            self.precision, self.scale
+        )?;
+        print_long_array(self, f, |array, index, f| {
+            write!(f, "{}", array.value_as_string(index))
+        })?;
+        write!(f, "]")
+    }
+}
+
+impl Array for Decimal128Array {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn data(&self) -> ArrayDataRef {
+        self.data.clone()
+    }
+
+    fn data_ref(&self) -> &ArrayDataRef {
+        &self.data
+    }
+}
+
 /// A nested array type where each child (called *field*) is represented by a separate
 /// array.
 pub struct StructArray {
@@ -1837,7 +2425,7 @@ where
         let i = self.i;
         if i >= self.len {
             None
-        } else if self.data.is_null(i) {
+        } else if self.data.is_null(self.data.offset() + i) {
             self.i += 1;
             Some(None)
         } else {
@@ -1855,7 +2443,7 @@ where
         if i + n >= self.len {
             self.i = self.len;
             None
-        } else if self.data.is_null(i + n) {
+        } else if self.data.is_null(self.data.offset() + i + n) {
             self.i += n + 1;
             Some(None)
         } else {
@@ -2053,6 +2641,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_primitive_array_from_trusted_len_iter() {
+        let iter = (0..5).map(|i| if i % 2 == 0 { Some(i as i32) } else { None });
+        let arr = unsafe { Int32Array::from_trusted_len_iter(iter) };
+        assert!(arr.equals(&Int32Array::from(vec![
+            Some(0),
+            None,
+            Some(2),
+            None,
+            Some(4)
+        ])));
+        assert_eq!(2, arr.null_count());
+
+        let arr = unsafe { Float64Array::from_trusted_len_iter(vec![]) };
+        assert_eq!(0, arr.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "trusted iterator returned fewer items than reported")]
+    fn test_primitive_array_from_trusted_len_iter_too_short() {
+        // the upper bound of a filtered iterator is not its length
+        let iter = vec![Some(1), None, Some(3)]
+            .into_iter()
+            .filter(|v| v.is_some());
+        unsafe { Int32Array::from_trusted_len_iter(iter) };
+    }
+
+    #[test]
+    fn test_primitive_array_try_from_vec() {
+        let values = vec![1i64, 2, 3, 4];
+        let ptr = values.as_ptr() as *const u8;
+        let arr =
+            Int64Array::try_from_vec(values, Some(Buffer::from([0b1011_u8]))).unwrap();
+        // the values are not copied
+        assert_eq!(ptr, arr.values().raw_data());
+        assert_eq!(4, arr.len());
+        assert_eq!(1, arr.null_count());
+        assert!(arr.is_null(2));
+        assert_eq!(4, arr.value(3));
+
+        // the null bitmap must have a bit for each value
+        let values = vec![0i32; 9];
+        assert!(Int32Array::try_from_vec(values, Some(Buffer::from([255_u8]))).is_err());
+
+        // the buffer must hold whole values
+        assert!(Int32Array::try_new(Buffer::from(&[0u8; 6]), None).is_err());
+    }
+
     #[test]
     fn test_date64_array_from_vec_option() {
         // Test building a primitive array with null values
@@ -2135,7 +2771,7 @@ mod tests {
         assert!(arr.is_null(1));
         assert_eq!(-5, arr.value(2));
 
-        // a day_time interval contains days and milliseconds, but we do not yet have accessors for the values
+        // a day_time interval contains days and milliseconds in the upper and lower 32 bits
         let arr = IntervalDayTimeArray::from(vec![Some(1), None, Some(-5)]);
         assert_eq!(3, arr.len());
         assert_eq!(0, arr.offset());
@@ -2145,6 +2781,21 @@ mod tests {
         assert_eq!(-5, arr.value(2));
     }
 
+    #[test]
+    fn test_interval_array_values() {
+        let arr = IntervalYearMonthArray::from(vec![14, -3]);
+        assert_eq!("P1Y2M", arr.value_as_iso8601(0));
+        assert_eq!("-P3M", arr.value_as_iso8601(1));
+
+        let arr = IntervalDayTimeArray::from(vec![
+            interval::day_time_value(3, 6_500),
+            interval::day_time_value(0, 0),
+        ]);
+        assert_eq!((3, 6_500), arr.value_as_days_millis(0));
+        assert_eq!("P3DT6.5S", arr.value_as_iso8601(0));
+        assert_eq!("PT0S", arr.value_as_iso8601(1));
+    }
+
     #[test]
     fn test_duration_array_from_vec() {
         let arr = DurationSecondArray::from(vec![Some(1), None, Some(-5)]);
@@ -2320,6 +2971,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_interval_fmt_debug() {
+        let arr = IntervalYearMonthArray::from(vec![Some(14), None]);
+        assert_eq!(
+            "PrimitiveArray<Interval(YearMonth)>\n[\n  P1Y2M,\n  null,\n]",
+            format!("{:?}", arr)
+        );
+        let arr = IntervalDayTimeArray::from(vec![1 << 32]);
+        assert_eq!(
+            "PrimitiveArray<Interval(DayTime)>\n[\n  P1D,\n]",
+            format!("{:?}", arr)
+        );
+    }
+
     #[test]
     fn test_time32second_fmt_debug() {
         let arr: PrimitiveArray<Time32SecondType> = vec![7201, 60054].into();
@@ -2408,6 +3073,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_boolean_array_from_trusted_len_iter() {
+        let values = vec![Some(false), Some(true), None, Some(true)];
+        let arr = unsafe { BooleanArray::from_trusted_len_iter(values.clone()) };
+        assert_eq!(Buffer::from([10_u8]), arr.values());
+        assert_eq!(1, arr.null_count());
+        assert!(arr.equals(&BooleanArray::from(values)));
+    }
+
+    #[test]
+    fn test_boolean_array_try_new() {
+        let arr = BooleanArray::try_new(3, Buffer::from([0b110_u8]), None).unwrap();
+        assert_eq!(3, arr.len());
+        assert!(!arr.value(0));
+        assert!(arr.value(2));
+
+        assert!(BooleanArray::try_new(9, Buffer::from([0_u8]), None).is_err());
+    }
+
     #[test]
     fn test_boolean_array_builder() {
         // Test building a boolean array with ArrayData builder and offset
@@ -2894,6 +3578,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_string_array_from_trusted_len_iter() {
+        let values = vec![Some("hello"), None, Some(""), Some("parquet")];
+        let string_array = unsafe { StringArray::from_trusted_len_iter(values) };
+
+        assert_eq!(4, string_array.len());
+        assert_eq!(1, string_array.null_count());
+        assert_eq!("hello", string_array.value(0));
+        assert!(string_array.is_null(1));
+        assert_eq!(0, string_array.value_length(1));
+        assert_eq!("", string_array.value(2));
+        assert_eq!("parquet", string_array.value(3));
+        assert_eq!(5, string_array.value_offset(3));
+
+        // owned strings can be used as values as well
+        let values = vec![Some("a".to_string()), None];
+        let string_array = unsafe { StringArray::from_trusted_len_iter(values) };
+        assert_eq!("a", string_array.value(0));
+        assert!(string_array.is_null(1));
+    }
+
+    #[test]
+    fn test_string_array_try_from_vecs() {
+        let string_array = StringArray::try_from_vecs(
+            vec![0, 5, 5, 12],
+            b"helloparquet".to_vec(),
+            Some(Buffer::from([0b101_u8])),
+        )
+        .unwrap();
+        assert_eq!(3, string_array.len());
+        assert_eq!("hello", string_array.value(0));
+        assert!(string_array.is_null(1));
+        assert_eq!("parquet", string_array.value(2));
+
+        // offsets must be increasing and within the values
+        assert!(
+            StringArray::try_from_vecs(vec![0, 5, 3], b"hello".to_vec(), None).is_err()
+        );
+        assert!(StringArray::try_from_vecs(vec![0, 6], b"hello".to_vec(), None).is_err());
+        assert!(StringArray::try_from_vecs(vec![], vec![], None).is_err());
+
+        // a value must not split a multi-byte character
+        let values = "ß".as_bytes().to_vec();
+        assert!(StringArray::try_from_vecs(vec![0, 1, 2], values, None).is_err());
+    }
+
     #[test]
     fn test_nested_string_array() {
         let string_builder = StringBuilder::new(3);
@@ -3042,6 +3772,55 @@ mod tests {
         FixedSizeBinaryArray::from(list_array);
     }
 
+    #[test]
+    fn test_decimal_array() {
+        let values: Vec<u8> = [12345_i128, -5, 0, 100]
+            .iter()
+            .flat_map(|v| v.to_le_bytes().to_vec())
+            .collect();
+        let array_data = ArrayData::builder(DataType::Decimal(7, 3))
+            .len(3)
+            .offset(1)
+            .add_buffer(Buffer::from(&values[..]))
+            .build();
+        let decimal_array = Decimal128Array::from(array_data);
+        assert_eq!(3, decimal_array.len());
+        assert_eq!(7, decimal_array.precision());
+        assert_eq!(3, decimal_array.scale());
+        assert_eq!(-5, decimal_array.value(0));
+        assert_eq!(100, decimal_array.value(2));
+        assert_eq!("-0.005", decimal_array.value_as_string(0));
+        assert_eq!("0.000", decimal_array.value_as_string(1));
+        assert_eq!("0.100", decimal_array.value_as_string(2));
+        assert_eq!(
+            "Decimal128Array<7, 3>\n[\n  -0.005,\n  0.000,\n  0.100,\n]",
+            format!("{:?}", decimal_array)
+        );
+    }
+
+    #[test]
+    fn test_decimal_parse_value() {
+        assert_eq!(Some(1230), Decimal128Array::parse_value("12.3", 5, 2));
+        assert_eq!(Some(-1235), Decimal128Array::parse_value("-12.345", 5, 2));
+        assert_eq!(Some(1234), Decimal128Array::parse_value("+12.344", 5, 2));
+        assert_eq!(Some(50), Decimal128Array::parse_value(".5", 5, 2));
+        assert_eq!(Some(700), Decimal128Array::parse_value("7", 5, 2));
+        assert_eq!(None, Decimal128Array::parse_value("1234", 5, 2));
+        assert_eq!(None, Decimal128Array::parse_value("1.2.3", 5, 2));
+        assert_eq!(None, Decimal128Array::parse_value("-", 5, 2));
+        assert_eq!(None, Decimal128Array::parse_value("1e3", 5, 2));
+    }
+
+    #[test]
+    fn test_decimal_rescale_value() {
+        assert_eq!(Some(12300), Decimal128Array::rescale_value(123, 1, 3, 10));
+        assert_eq!(Some(-13), Decimal128Array::rescale_value(-125, 2, 1, 10));
+        assert_eq!(Some(12), Decimal128Array::rescale_value(124, 2, 1, 10));
+        assert_eq!(None, Decimal128Array::rescale_value(123, 0, 2, 4));
+        assert!(Decimal128Array::fits_precision(-99, 2));
+        assert!(!Decimal128Array::fits_precision(100, 2));
+    }
+
     #[test]
     #[should_panic(expected = "BinaryArray out of bounds access")]
     fn test_binary_array_get_value_index_out_of_bound() {
@@ -22,6 +22,7 @@
 
 use std::any::Any;
 use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
 use std::io::Write;
 use std::marker::PhantomData;
 use std::mem;
@@ -249,6 +250,19 @@ impl<T: ArrowPrimitiveType> BufferBuilderTrait<T> for BufferBuilder<T> {
     }
 }
 
+impl<T: ArrowNumericType> BufferBuilder<T> {
+    /// The values appended to the builder since it was last finished
+    fn typed_data(&self) -> &[T::Native] {
+        // the memory of a `MutableBuffer` is aligned to 64 bytes
+        unsafe {
+            std::slice::from_raw_parts(
+                self.buffer.raw_data() as *const T::Native,
+                self.len,
+            )
+        }
+    }
+}
+
 impl<T: ArrowPrimitiveType> BufferBuilder<T> {
     /// Writes a byte slice to the underlying buffer and updates the `len`, i.e. the
     /// number array elements in the builder.  Also, converts the `io::Result`
@@ -706,6 +720,13 @@ pub struct FixedSizeBinaryBuilder {
     builder: FixedSizeListBuilder<UInt8Builder>,
 }
 
+///  Array builder for `Decimal128Array`
+pub struct Decimal128Builder {
+    builder: FixedSizeBinaryBuilder,
+    precision: usize,
+    scale: usize,
+}
+
 pub trait BinaryArrayBuilder: ArrayBuilder {}
 
 impl BinaryArrayBuilder for BinaryBuilder {}
@@ -766,6 +787,33 @@ impl ArrayBuilder for StringBuilder {
     }
 }
 
+impl ArrayBuilder for Decimal128Builder {
+    /// Returns the builder as a non-mutable `Any` reference.
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    /// Returns the builder as a mutable `Any` reference.
+    fn as_any_mut(&mut self) -> &mut Any {
+        self
+    }
+
+    /// Returns the boxed builder as a box of `Any`.
+    fn into_box_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+
+    /// Returns the number of array slots in the builder
+    fn len(&self) -> usize {
+        self.builder.len()
+    }
+
+    /// Builds the array and reset this builder.
+    fn finish(&mut self) -> ArrayRef {
+        Arc::new(self.finish())
+    }
+}
+
 impl ArrayBuilder for FixedSizeBinaryBuilder {
     /// Returns the builder as a non-mutable `Any` reference.
     fn as_any(&self) -> &Any {
@@ -872,6 +920,14 @@ impl StringBuilder {
     pub fn finish(&mut self) -> StringArray {
         StringArray::from(self.builder.finish())
     }
+
+    /// The bytes of the value at index `i` among the values appended since the builder
+    /// was last finished
+    fn value_bytes(&self, i: usize) -> &[u8] {
+        let offsets = self.builder.offsets_builder.typed_data();
+        let values = self.builder.values_builder.values_builder.typed_data();
+        &values[offsets[i] as usize..offsets[i + 1] as usize]
+    }
 }
 
 impl FixedSizeBinaryBuilder {
@@ -911,6 +967,46 @@ impl FixedSizeBinaryBuilder {
     }
 }
 
+impl Decimal128Builder {
+    /// Creates a new `Decimal128Builder`, `capacity` is the number of values
+    pub fn new(capacity: usize, precision: usize, scale: usize) -> Self {
+        Self {
+            builder: FixedSizeBinaryBuilder::new(
+                capacity * Decimal128Array::VALUE_LENGTH,
+                Decimal128Array::VALUE_LENGTH as i32,
+            ),
+            precision,
+            scale,
+        }
+    }
+
+    /// Appends the unscaled integer of a decimal value into the builder.
+    pub fn append_value(&mut self, value: i128) -> Result<()> {
+        self.builder.append_value(&value.to_le_bytes())
+    }
+
+    /// Append a null value to the array.
+    pub fn append_null(&mut self) -> Result<()> {
+        self.builder.append_null()
+    }
+
+    /// Builds the `Decimal128Array` and reset this builder.
+    pub fn finish(&mut self) -> Decimal128Array {
+        let data = self.builder.finish().data();
+        let mut builder =
+            ArrayData::builder(DataType::Decimal(self.precision, self.scale))
+                .len(data.len())
+                .offset(data.offset())
+                .buffers(data.buffers().to_vec());
+        if let Some(null_buffer) = data.null_buffer() {
+            builder = builder
+                .null_count(data.null_count())
+                .null_bit_buffer(null_buffer.clone());
+        }
+        Decimal128Array::from(builder.build())
+    }
+}
+
 /// Array builder for Struct types.
 ///
 /// Note that callers should make sure that methods of all the child field builders are
@@ -1014,6 +1110,9 @@ impl StructBuilder {
             DataType::FixedSizeBinary(len) => {
                 Box::new(FixedSizeBinaryBuilder::new(capacity, *len))
             }
+            DataType::Decimal(precision, scale) => {
+                Box::new(Decimal128Builder::new(capacity, *precision, *scale))
+            }
             DataType::Utf8 => Box::new(StringBuilder::new(capacity)),
             DataType::Date32(DateUnit::Day) => Box::new(Date32Builder::new(capacity)),
             DataType::Date64(DateUnit::Millisecond) => {
@@ -1127,6 +1226,48 @@ impl Drop for StructBuilder {
     }
 }
 
+/// Hasher for the values of dictionary builders, using the FxHash algorithm of the Rust
+/// compiler. It is much faster than the default hasher for short values but does not
+/// protect against values crafted to collide.
+#[derive(Default)]
+struct DictionaryHasher {
+    hash: u64,
+}
+
+impl DictionaryHasher {
+    fn add_word(&mut self, word: u64) {
+        self.hash =
+            (self.hash.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+
+impl Hasher for DictionaryHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.add_word(u64::from_le_bytes(word));
+        }
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            let mut word = [0; 8];
+            word[..remainder.len()].copy_from_slice(remainder);
+            self.add_word(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_word(i);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+type DictionaryHashMap<T, K> = HashMap<T, K, BuildHasherDefault<DictionaryHasher>>;
+
 /// Array builder for `DictionaryArray`. For example to map a set of byte indices
 /// to f32 values. Note that the use of a `HashMap` here will not scale to very large
 /// arrays or result in an ordered dictionary.
@@ -1137,7 +1278,7 @@ where
 {
     keys_builder: PrimitiveBuilder<K>,
     values_builder: PrimitiveBuilder<V>,
-    map: HashMap<Box<[u8]>, K::Native>,
+    map: DictionaryHashMap<Box<[u8]>, K::Native>,
 }
 
 impl<K, V> PrimitiveDictionaryBuilder<K, V>
@@ -1153,7 +1294,20 @@ where
         Self {
             keys_builder: keys_builder,
             values_builder: values_builder,
-            map: HashMap::new(),
+            map: DictionaryHashMap::default(),
+        }
+    }
+
+    /// Creates a new `PrimitiveDictionaryBuilder` with room for `keys_capacity` values
+    /// of the array, of which `values_capacity` are distinct, without reallocating.
+    pub fn with_capacity(keys_capacity: usize, values_capacity: usize) -> Self {
+        Self {
+            keys_builder: PrimitiveBuilder::new(keys_capacity),
+            values_builder: PrimitiveBuilder::new(values_capacity),
+            map: DictionaryHashMap::with_capacity_and_hasher(
+                values_capacity,
+                Default::default(),
+            ),
         }
     }
 }
@@ -1198,19 +1352,19 @@ where
     /// if already present in the values array or a new index if the
     /// value is appended to the values array.
     pub fn append(&mut self, value: V::Native) -> Result<K::Native> {
-        if let Some(&key) = self.map.get(value.to_byte_slice()) {
-            // Append existing value.
-            self.keys_builder.append_value(key)?;
-            Ok(key)
-        } else {
-            // Append new value.
-            let key = K::Native::from_usize(self.values_builder.len())
-                .ok_or(ArrowError::DictionaryKeyOverflowError)?;
-            self.values_builder.append_value(value)?;
-            self.keys_builder.append_value(key as K::Native)?;
-            self.map.insert(value.to_byte_slice().into(), key);
-            Ok(key)
-        }
+        let key = self.get_or_insert(value)?;
+        self.keys_builder.append_value(key)?;
+        Ok(key)
+    }
+
+    /// Append a slice of primitive values to the array, which is faster than appending
+    /// them one by one
+    pub fn append_values(&mut self, values: &[V::Native]) -> Result<()> {
+        let keys = values
+            .iter()
+            .map(|v| self.get_or_insert(*v))
+            .collect::<Result<Vec<_>>>()?;
+        self.keys_builder.append_slice(&keys)
     }
 
     pub fn append_null(&mut self) -> Result<()> {
@@ -1223,18 +1377,35 @@ where
         let value_ref: ArrayRef = Arc::new(self.values_builder.finish());
         self.keys_builder.finish_dict(value_ref)
     }
+
+    /// Get the key of a value, appending it to the values if it is new
+    fn get_or_insert(&mut self, value: V::Native) -> Result<K::Native> {
+        if let Some(&key) = self.map.get(value.to_byte_slice()) {
+            return Ok(key);
+        }
+        let key = K::Native::from_usize(self.values_builder.len())
+            .ok_or(ArrowError::DictionaryKeyOverflowError)?;
+        self.values_builder.append_value(value)?;
+        self.map.insert(value.to_byte_slice().into(), key);
+        Ok(key)
+    }
 }
 
-/// Array builder for `DictionaryArray`. For example to map a set of byte indices
-/// to f32 values. Note that the use of a `HashMap` here will not scale to very large
-/// arrays or result in an ordered dictionary.
+/// Array builder for `DictionaryArray` with string values.
+///
+/// Each value is looked up by its hash, and the dictionary values are only compared
+/// when hashes are equal, so no copy of the distinct values is kept besides the values
+/// of the dictionary itself.
 pub struct StringDictionaryBuilder<K>
 where
     K: ArrowDictionaryKeyType,
 {
     keys_builder: PrimitiveBuilder<K>,
     values_builder: StringBuilder,
-    map: HashMap<Box<[u8]>, K::Native>,
+    /// The key of the first distinct value with each hash
+    map: DictionaryHashMap<u64, K::Native>,
+    /// The keys of the distinct values whose hash is equal to that of an earlier value
+    collisions: HashMap<Box<[u8]>, K::Native>,
 }
 
 impl<K> StringDictionaryBuilder<K>
@@ -1246,7 +1417,27 @@ where
         Self {
             keys_builder,
             values_builder,
-            map: HashMap::new(),
+            map: DictionaryHashMap::default(),
+            collisions: HashMap::new(),
+        }
+    }
+
+    /// Creates a new `StringDictionaryBuilder` with room for `keys_capacity` values of
+    /// the array, of which `values_capacity` are distinct and take up `data_capacity`
+    /// bytes, without reallocating.
+    pub fn with_capacity(
+        keys_capacity: usize,
+        values_capacity: usize,
+        data_capacity: usize,
+    ) -> Self {
+        Self {
+            keys_builder: PrimitiveBuilder::new(keys_capacity),
+            values_builder: StringBuilder::new(data_capacity),
+            map: DictionaryHashMap::with_capacity_and_hasher(
+                values_capacity,
+                Default::default(),
+            ),
+            collisions: HashMap::new(),
         }
     }
 }
@@ -1289,19 +1480,19 @@ where
     /// if already present in the values array or a new index if the
     /// value is appended to the values array.
     pub fn append(&mut self, value: &str) -> Result<K::Native> {
-        if let Some(&key) = self.map.get(value.as_bytes()) {
-            // Append existing value.
-            self.keys_builder.append_value(key)?;
-            Ok(key)
-        } else {
-            // Append new value.
-            let key = K::Native::from_usize(self.values_builder.len())
-                .ok_or(ArrowError::DictionaryKeyOverflowError)?;
-            self.values_builder.append_value(value)?;
-            self.keys_builder.append_value(key as K::Native)?;
-            self.map.insert(value.as_bytes().into(), key);
-            Ok(key)
-        }
+        let key = self.get_or_insert(value)?;
+        self.keys_builder.append_value(key)?;
+        Ok(key)
+    }
+
+    /// Append a slice of strings to the array, which is faster than appending them one
+    /// by one
+    pub fn append_values(&mut self, values: &[&str]) -> Result<()> {
+        let keys = values
+            .iter()
+            .map(|v| self.get_or_insert(v))
+            .collect::<Result<Vec<_>>>()?;
+        self.keys_builder.append_slice(&keys)
     }
 
     pub fn append_null(&mut self) -> Result<()> {
@@ -1311,9 +1502,45 @@ where
     /// Builds the `DictionaryArray` and reset this builder.
     pub fn finish(&mut self) -> DictionaryArray<K> {
         self.map.clear();
+        self.collisions.clear();
         let value_ref: ArrayRef = Arc::new(self.values_builder.finish());
         self.keys_builder.finish_dict(value_ref)
     }
+
+    /// Get the key of a value, appending it to the values if it is new
+    fn get_or_insert(&mut self, value: &str) -> Result<K::Native> {
+        let bytes = value.as_bytes();
+        let mut hasher = DictionaryHasher::default();
+        hasher.write(bytes);
+        let hash = hasher.finish();
+
+        match self.map.get(&hash) {
+            Some(&key) => {
+                if self.values_builder.value_bytes(key.to_usize().unwrap()) == bytes {
+                    return Ok(key);
+                }
+                if let Some(&key) = self.collisions.get(bytes) {
+                    return Ok(key);
+                }
+                let key = self.append_dictionary_value(value)?;
+                self.collisions.insert(bytes.into(), key);
+                Ok(key)
+            }
+            None => {
+                let key = self.append_dictionary_value(value)?;
+                self.map.insert(hash, key);
+                Ok(key)
+            }
+        }
+    }
+
+    /// Append a new distinct value to the values of the dictionary, returning its key
+    fn append_dictionary_value(&mut self, value: &str) -> Result<K::Native> {
+        let key = K::Native::from_usize(self.values_builder.len())
+            .ok_or(ArrowError::DictionaryKeyOverflowError)?;
+        self.values_builder.append_value(value)?;
+        Ok(key)
+    }
 }
 
 #[cfg(test)]
@@ -1924,6 +2151,25 @@ mod tests {
         assert_eq!(5, fixed_size_binary_array.value_length());
     }
 
+    #[test]
+    fn test_decimal_builder() {
+        let mut builder = Decimal128Builder::new(3, 38, 2);
+        builder
+            .append_value(-1_000_000_000_000_000_000_000)
+            .unwrap();
+        builder.append_null().unwrap();
+        builder.append_value(42).unwrap();
+        let decimal_array: Decimal128Array = builder.finish();
+
+        assert_eq!(&DataType::Decimal(38, 2), decimal_array.data_type());
+        assert_eq!(3, decimal_array.len());
+        assert_eq!(1, decimal_array.null_count());
+        assert!(decimal_array.is_null(1));
+        assert_eq!(-1_000_000_000_000_000_000_000, decimal_array.value(0));
+        assert_eq!("0.42", decimal_array.value_as_string(2));
+        assert_eq!(0, builder.len());
+    }
+
     #[test]
     fn test_string_array_builder_finish() {
         let mut builder = StringBuilder::new(10);
@@ -2202,6 +2448,45 @@ mod tests {
         assert_eq!(ava.value(1), "def");
     }
 
+    #[test]
+    fn test_string_dictionary_builder_append_values() {
+        let mut builder = StringDictionaryBuilder::<Int16Type>::with_capacity(6, 3, 10);
+        builder.append_values(&["abc", "def", "abc"]).unwrap();
+        builder.append_null().unwrap();
+        // "a" and "a\0" have the same hash, since values are padded with zeros
+        builder.append_values(&["a", "a\0", "a"]).unwrap();
+        let array = builder.finish();
+
+        let aks: Vec<_> = array.keys().collect();
+        assert_eq!(
+            aks,
+            vec![Some(0), Some(1), Some(0), None, Some(2), Some(3), Some(2)]
+        );
+        let av = array.values();
+        let ava: &StringArray = av.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(ava.len(), 4);
+        assert_eq!(ava.value(3), "a\0");
+
+        // the dictionary is reset by finish
+        builder.append("def").unwrap();
+        let array = builder.finish();
+        assert_eq!(array.keys().collect::<Vec<_>>(), vec![Some(0)]);
+    }
+
+    #[test]
+    fn test_primitive_dictionary_builder_append_values() {
+        let mut builder =
+            PrimitiveDictionaryBuilder::<UInt8Type, Int64Type>::with_capacity(4, 2);
+        builder.append_values(&[7, -1, 7, 7]).unwrap();
+        let array = builder.finish();
+
+        let aks: Vec<_> = array.keys().collect();
+        assert_eq!(aks, vec![Some(0), Some(1), Some(0), Some(0)]);
+        let av = array.values();
+        let ava: &Int64Array = av.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(ava.value_slice(0, 2), &[7, -1]);
+    }
+
     #[test]
     fn test_primitive_dictionary_overflow() {
         let key_builder = PrimitiveBuilder::<UInt8Type>::new(257);
@@ -18,7 +18,10 @@
 //! Contains `ArrayData`, a generic representation of Arrow array data which encapsulates
 //! common attributes and operations for Arrow array.
 
-use std::sync::Arc;
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::mem;
 
 use crate::bitmap::Bitmap;
 use crate::buffer::Buffer;
@@ -155,6 +158,48 @@ impl ArrayData {
     pub fn null_count(&self) -> usize {
         self.null_count
     }
+
+    /// Returns the total number of bytes of memory occupied by the buffers of this
+    /// array data, including the null bitmap and the buffers of child data. The full
+    /// capacity of a buffer is counted, even if this array data is a slice of it.
+    pub fn get_buffer_memory_size(&self) -> usize {
+        let mut size = 0;
+        self.for_each_buffer(&mut |buffer| size += buffer.capacity());
+        size
+    }
+
+    /// Returns the total number of bytes of memory occupied by this array data, i.e.
+    /// the size of its buffers plus the size of the `ArrayData` structs themselves.
+    pub fn get_array_memory_size(&self) -> usize {
+        self.get_buffer_memory_size() + self.get_struct_memory_size()
+    }
+
+    /// Returns the number of bytes occupied by this struct and the structs of its child
+    /// data, excluding the memory of the buffers
+    pub(crate) fn get_struct_memory_size(&self) -> usize {
+        mem::size_of::<Self>()
+            + self.buffers.len() * mem::size_of::<Buffer>()
+            + self.child_data.len() * mem::size_of::<ArrayDataRef>()
+            + self
+                .child_data
+                .iter()
+                .map(|child| child.get_struct_memory_size())
+                .sum::<usize>()
+    }
+
+    /// Calls `f` with every buffer of this array data, including the null bitmap and
+    /// the buffers of child data
+    pub(crate) fn for_each_buffer(&self, f: &mut dyn FnMut(&Buffer)) {
+        if let Some(bitmap) = &self.null_bitmap {
+            f(bitmap.buffer_ref());
+        }
+        for buffer in &self.buffers {
+            f(buffer);
+        }
+        for child in &self.child_data {
+            child.for_each_buffer(f);
+        }
+    }
 }
 
 /// Builder for `ArrayData` type
@@ -628,6 +628,41 @@ impl ArrayEqual for FixedSizeBinaryArray {
     }
 }
 
+impl ArrayEqual for Decimal128Array {
+    fn equals(&self, other: &dyn Array) -> bool {
+        if !base_equal(&self.data(), &other.data()) {
+            return false;
+        }
+
+        self.range_equals(other, 0, self.len(), 0)
+    }
+
+    fn range_equals(
+        &self,
+        other: &dyn Array,
+        start_idx: usize,
+        end_idx: usize,
+        other_start_idx: usize,
+    ) -> bool {
+        assert!(other_start_idx + (end_idx - start_idx) <= other.len());
+        let other = other.as_any().downcast_ref::<Decimal128Array>().unwrap();
+
+        let mut j = other_start_idx;
+        for i in start_idx..end_idx {
+            let is_null = self.is_null(i);
+            if is_null != other.is_null(j) {
+                return false;
+            }
+            if !is_null && self.value(i) != other.value(j) {
+                return false;
+            }
+            j += 1;
+        }
+
+        true
+    }
+}
+
 impl ArrayEqual for StructArray {
     fn equals(&self, other: &dyn Array) -> bool {
         if !base_equal(&self.data(), &other.data()) {
@@ -1046,6 +1081,39 @@ impl PartialEq<FixedSizeBinaryArray> for Value {
     }
 }
 
+impl JsonEqual for Decimal128Array {
+    fn equals_json(&self, json: &[&Value]) -> bool {
+        if self.len() != json.len() {
+            return false;
+        }
+
+        // the values are the unscaled integers as strings
+        (0..self.len()).all(|i| match json[i] {
+            JString(s) => self.is_valid(i) && s.parse::<i128>() == Ok(self.value(i)),
+            JNull => self.is_null(i),
+            _ => false,
+        })
+    }
+}
+
+impl PartialEq<Value> for Decimal128Array {
+    fn eq(&self, json: &Value) -> bool {
+        match json {
+            Value::Array(json_array) => self.equals_json_values(&json_array),
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<Decimal128Array> for Value {
+    fn eq(&self, arrow: &Decimal128Array) -> bool {
+        match self {
+            Value::Array(json_array) => arrow.equals_json_values(&json_array),
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1906,6 +1974,34 @@ mod tests {
         assert!(json_array.ne(&arrow_array));
     }
 
+    #[test]
+    fn test_decimal_equal() {
+        let decimal = |values: &[Option<i128>]| {
+            let mut builder = Decimal128Builder::new(values.len(), 10, 2);
+            for value in values {
+                match value {
+                    Some(v) => builder.append_value(*v).unwrap(),
+                    None => builder.append_null().unwrap(),
+                }
+            }
+            builder.finish()
+        };
+        let a = decimal(&[Some(100), None, Some(-250)]);
+        let b = decimal(&[Some(100), None, Some(-250)]);
+        assert!(a.equals(&b));
+        assert!(b.equals(&a));
+
+        let b = decimal(&[Some(100), None, Some(250)]);
+        assert!(!a.equals(&b));
+        assert!(a.range_equals(&b, 0, 2, 0));
+
+        let json_array: Value = serde_json::from_str(r#"["100", null, "-250"]"#).unwrap();
+        assert!(a.eq(&json_array));
+        assert!(json_array.eq(&a));
+        let json_array: Value = serde_json::from_str(r#"["100", null, "250"]"#).unwrap();
+        assert!(a.ne(&json_array));
+    }
+
     #[test]
     fn test_struct_json_equal() {
         // Test equal case
@@ -22,7 +22,9 @@ use crate::buffer::Buffer;
 use crate::error::Result;
 use crate::util::bit_util;
 
-use std::ops::{BitAnd, BitOr};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::{BitAnd, BitOr};
 
 #[derive(Debug, Clone)]
 pub struct Bitmap {
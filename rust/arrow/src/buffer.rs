@@ -21,18 +21,21 @@
 #[cfg(feature = "simd")]
 use packed_simd::u8x64;
 
-use std::cmp;
-use std::convert::AsRef;
-use std::fmt::{Debug, Formatter};
-use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
-use std::mem;
-use std::ops::{BitAnd, BitOr, Not};
-use std::slice::from_raw_parts;
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+use core::any::Any;
+use core::cmp;
+use core::convert::AsRef;
+use core::fmt::{Debug, Formatter};
+use core::mem;
+use core::ops::{BitAnd, BitOr, Not};
+use core::slice::from_raw_parts;
 #[cfg(feature = "simd")]
-use std::slice::from_raw_parts_mut;
-use std::sync::Arc;
+use core::slice::from_raw_parts_mut;
+#[cfg(feature = "std")]
+use std::io::{Error as IoError, ErrorKind, Result as IoResult, Write};
 
-use crate::array::{BufferBuilderTrait, UInt8BufferBuilder};
 use crate::datatypes::ArrowNativeType;
 use crate::error::{ArrowError, Result};
 use crate::memory;
@@ -64,6 +67,10 @@ struct BufferData {
     /// The capacity (num of bytes) of the buffer
     /// Invariant: len <= capacity
     capacity: usize,
+
+    /// The value that owns the memory when it was not allocated by Arrow, such as a
+    /// `Vec`, which frees the memory when it is dropped along with this object
+    foreign_owner: Option<Box<dyn Any + Send + Sync>>,
 }
 
 impl PartialEq for BufferData {
@@ -89,7 +96,7 @@ impl Drop for BufferData {
 }
 
 impl Debug for BufferData {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
         write!(
             f,
             "BufferData {{ ptr: {:?}, len: {}, capacity: {}, data: ",
@@ -98,7 +105,7 @@ impl Debug for BufferData {
 
         unsafe {
             f.debug_list()
-                .entries(std::slice::from_raw_parts(self.ptr, self.len).iter())
+                .entries(core::slice::from_raw_parts(self.ptr, self.len).iter())
                 .finish()?;
         }
 
@@ -170,6 +177,30 @@ impl Buffer {
             len,
             capacity,
             owned,
+            foreign_owner: None,
+        };
+        Buffer {
+            data: Arc::new(buf_data),
+            offset: 0,
+        }
+    }
+
+    /// Creates a buffer that takes over the memory of a vector without copying it.
+    ///
+    /// The memory is freed by the vector when the buffer is dropped. Note that the
+    /// memory is only aligned to the alignment of `T` rather than to a 64-byte boundary,
+    /// and that it cannot be converted back into a `MutableBuffer`.
+    pub fn from_vec<T: ArrowNativeType>(vec: Vec<T>) -> Self {
+        let len = vec.len() * mem::size_of::<T>();
+        let capacity = vec.capacity() * mem::size_of::<T>();
+        // moving the vector into the box does not move the memory it points to
+        let ptr = vec.as_ptr() as *const u8;
+        let buf_data = BufferData {
+            ptr,
+            len,
+            capacity,
+            owned: false,
+            foreign_owner: Some(Box::new(vec)),
         };
         Buffer {
             data: Arc::new(buf_data),
@@ -194,7 +225,7 @@ impl Buffer {
 
     /// Returns the byte slice stored in this buffer
     pub fn data(&self) -> &[u8] {
-        unsafe { std::slice::from_raw_parts(self.raw_data(), self.len()) }
+        unsafe { core::slice::from_raw_parts(self.raw_data(), self.len()) }
     }
 
     /// Returns a slice of this buffer, starting from `offset`.
@@ -217,6 +248,12 @@ impl Buffer {
         unsafe { self.data.ptr.offset(self.offset as isize) }
     }
 
+    /// Returns a pointer to the start of the memory region that this buffer and all its
+    /// slices share, which identifies the allocation when accounting for memory usage.
+    pub(crate) fn allocation_ptr(&self) -> *const u8 {
+        self.data.ptr
+    }
+
     /// View buffer as typed slice.
     ///
     /// # Safety
@@ -238,7 +275,32 @@ impl Buffer {
 
     /// Returns an empty buffer.
     pub fn empty() -> Self {
-        unsafe { Self::from_raw_parts(::std::ptr::null(), 0, 0) }
+        unsafe { Self::from_raw_parts(core::ptr::null(), 0, 0) }
+    }
+
+    /// Converts this buffer into a `MutableBuffer` that takes over its memory, so that
+    /// the memory can be reused without a new allocation.
+    ///
+    /// This is only possible when the buffer is the only reference to memory that it
+    /// owns and it is not a slice of another buffer. Otherwise the buffer is returned
+    /// unchanged as the error value.
+    pub fn into_mutable(self) -> core::result::Result<MutableBuffer, Buffer> {
+        if self.offset != 0 || !self.data.owned || self.data.ptr.is_null() {
+            return Err(self);
+        }
+        match Arc::try_unwrap(self.data) {
+            Ok(data) => {
+                let buffer = MutableBuffer {
+                    data: data.ptr as *mut u8,
+                    len: data.len,
+                    capacity: data.capacity,
+                };
+                // the memory is now freed by the mutable buffer
+                mem::forget(data);
+                Ok(buffer)
+            }
+            Err(data) => Err(Buffer { data, offset: 0 }),
+        }
     }
 }
 
@@ -293,6 +355,22 @@ where
     return result.freeze();
 }
 
+/// Helper function for the default `BitAnd` and `BitOr` implementations
+fn bitwise_bin_op_helper<F>(left: &Buffer, right: &Buffer, op: F) -> Buffer
+where
+    F: Fn(u8, u8) -> u8,
+{
+    let mut result = MutableBuffer::new(left.len()).with_bitset(left.len(), false);
+    for (r, (a, b)) in result
+        .data_mut()
+        .iter_mut()
+        .zip(left.data().iter().zip(right.data()))
+    {
+        *r = op(*a, *b);
+    }
+    result.freeze()
+}
+
 impl<'a, 'b> BitAnd<&'b Buffer> for &'a Buffer {
     type Output = Result<Buffer>;
 
@@ -312,17 +390,7 @@ impl<'a, 'b> BitAnd<&'b Buffer> for &'a Buffer {
         // Default implementation
         #[allow(unreachable_code)]
         {
-            let mut builder = UInt8BufferBuilder::new(self.len());
-            for i in 0..self.len() {
-                unsafe {
-                    builder
-                        .append(
-                            self.data().get_unchecked(i) & rhs.data().get_unchecked(i),
-                        )
-                        .unwrap();
-                }
-            }
-            Ok(builder.finish())
+            Ok(bitwise_bin_op_helper(&self, &rhs, |a, b| a & b))
         }
     }
 }
@@ -346,17 +414,7 @@ impl<'a, 'b> BitOr<&'b Buffer> for &'a Buffer {
         // Default implementation
         #[allow(unreachable_code)]
         {
-            let mut builder = UInt8BufferBuilder::new(self.len());
-            for i in 0..self.len() {
-                unsafe {
-                    builder
-                        .append(
-                            self.data().get_unchecked(i) | rhs.data().get_unchecked(i),
-                        )
-                        .unwrap();
-                }
-            }
-            Ok(builder.finish())
+            Ok(bitwise_bin_op_helper(&self, &rhs, |a, b| a | b))
         }
     }
 }
@@ -389,13 +447,12 @@ impl Not for &Buffer {
         // Default implementation
         #[allow(unreachable_code)]
         {
-            let mut builder = UInt8BufferBuilder::new(self.len());
-            for i in 0..self.len() {
-                unsafe {
-                    builder.append(!self.data().get_unchecked(i)).unwrap();
-                }
+            let mut result =
+                MutableBuffer::new(self.len()).with_bitset(self.len(), false);
+            for (r, a) in result.data_mut().iter_mut().zip(self.data()) {
+                *r = !a;
             }
-            builder.finish()
+            result.freeze()
         }
     }
 }
@@ -434,7 +491,7 @@ impl MutableBuffer {
         assert!(end <= self.capacity);
         let v = if val { 255 } else { 0 };
         unsafe {
-            std::ptr::write_bytes(self.data, v, end);
+            core::ptr::write_bytes(self.data, v, end);
             self.len = end;
         }
         self
@@ -448,7 +505,7 @@ impl MutableBuffer {
     pub fn set_null_bits(&mut self, start: usize, count: usize) {
         assert!(start + count <= self.capacity);
         unsafe {
-            std::ptr::write_bytes(self.data.offset(start as isize), 0, count);
+            core::ptr::write_bytes(self.data.offset(start as isize), 0, count);
         }
     }
 
@@ -511,12 +568,12 @@ impl MutableBuffer {
 
     /// Returns the data stored in this buffer as a slice.
     pub fn data(&self) -> &[u8] {
-        unsafe { std::slice::from_raw_parts(self.raw_data(), self.len()) }
+        unsafe { core::slice::from_raw_parts(self.raw_data(), self.len()) }
     }
 
     /// Returns the data stored in this buffer as a mutable slice.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        unsafe { std::slice::from_raw_parts_mut(self.raw_data() as *mut u8, self.len()) }
+        unsafe { core::slice::from_raw_parts_mut(self.raw_data() as *mut u8, self.len()) }
     }
 
     /// Returns a raw pointer for this buffer.
@@ -534,8 +591,9 @@ impl MutableBuffer {
             len: self.len,
             capacity: self.capacity,
             owned: true,
+            foreign_owner: None,
         };
-        std::mem::forget(self);
+        core::mem::forget(self);
         Buffer {
             data: Arc::new(buffer_data),
             offset: 0,
@@ -563,6 +621,7 @@ impl PartialEq for MutableBuffer {
     }
 }
 
+#[cfg(feature = "std")]
 impl Write for MutableBuffer {
     fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
         let remaining_capacity = self.capacity - self.len;
@@ -821,6 +880,47 @@ mod tests {
         assert_eq!("aaaa bbbb cccc dddd".as_bytes(), immutable_buf.data());
     }
 
+    #[test]
+    fn test_into_mutable() {
+        let mut buf = MutableBuffer::new(100);
+        buf.write(&[0, 1, 2, 3]).expect("write should be OK");
+        let ptr = buf.raw_data();
+        let buf = buf.freeze();
+
+        // the memory cannot be taken over while it is shared
+        let shared = buf.clone();
+        let buf = buf.into_mutable().expect_err("buffer should be shared");
+        drop(shared);
+        let buf = buf.into_mutable().expect("buffer should not be shared");
+        assert_eq!(ptr, buf.raw_data());
+        assert_eq!(&[0, 1, 2, 3], buf.data());
+        assert_eq!(128, buf.capacity());
+
+        // slices and memory that is not owned cannot be taken over either
+        let buf = buf.freeze();
+        assert!(buf.slice(1).into_mutable().is_err());
+        let unowned = unsafe { Buffer::from_unowned(buf.raw_data(), 4, 128) };
+        assert!(unowned.into_mutable().is_err());
+        assert!(Buffer::empty().into_mutable().is_err());
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let vec = vec![1i32, 2, 3];
+        let ptr = vec.as_ptr() as *const u8;
+        let buf = Buffer::from_vec(vec);
+        assert_eq!(ptr, buf.raw_data());
+        assert_eq!(12, buf.len());
+        assert_eq!(&[1, 2, 3], unsafe { buf.typed_data::<i32>() });
+
+        assert_eq!(8, buf.slice(4).len());
+
+        // the memory is still owned by the vector
+        assert!(buf.into_mutable().is_err());
+
+        assert!(Buffer::from_vec(Vec::<u8>::new()).is_empty());
+    }
+
     #[test]
     fn test_mutable_equal() -> Result<()> {
         let mut buf = MutableBuffer::new(1);
@@ -17,9 +17,11 @@
 
 //! Defines aggregations over Arrow arrays.
 
+use std::cmp::Ordering;
 use std::ops::Add;
 
-use crate::array::{Array, PrimitiveArray};
+use crate::array::{Array, BooleanArray, PrimitiveArray, StringArray};
+use crate::compute::Collation;
 use crate::datatypes::ArrowNumericType;
 
 /// Returns the minimum value in the array, according to the natural order.
@@ -63,6 +65,37 @@ where
     n
 }
 
+/// Returns the minimum value in the string array under a collation, ignoring null
+/// values. Of several values that are equal under the collation the first is returned.
+pub fn min_string(array: &StringArray, collation: Collation) -> Option<&str> {
+    min_max_string_helper(array, |a, b| collation.compare(a, b) == Ordering::Less)
+}
+
+/// Returns the maximum value in the string array under a collation, ignoring null
+/// values. Of several values that are equal under the collation the first is returned.
+pub fn max_string(array: &StringArray, collation: Collation) -> Option<&str> {
+    min_max_string_helper(array, |a, b| collation.compare(a, b) == Ordering::Greater)
+}
+
+/// Helper function to perform min/max lambda function on values from a string array.
+fn min_max_string_helper<F>(array: &StringArray, cmp: F) -> Option<&str>
+where
+    F: Fn(&str, &str) -> bool,
+{
+    let mut n: Option<&str> = None;
+    for i in 0..array.len() {
+        if array.is_null(i) {
+            continue;
+        }
+        let m = array.value(i);
+        match n {
+            Some(nn) if !cmp(m, nn) => {}
+            _ => n = Some(m),
+        }
+    }
+    n
+}
+
 /// Returns the sum of values in the array.
 ///
 /// Returns `None` if the array is empty or only contains null values.
@@ -97,6 +130,28 @@ where
     }
 }
 
+/// Returns whether any of the values in the array is `true`, ignoring null values.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn any(array: &BooleanArray) -> Option<bool> {
+    bool_helper(array, true)
+}
+
+/// Returns whether all of the values in the array are `true`, ignoring null values.
+///
+/// Returns `None` if the array is empty or only contains null values.
+pub fn all(array: &BooleanArray) -> Option<bool> {
+    bool_helper(array, false).map(|found_false| !found_false)
+}
+
+/// Helper function to search a boolean array for a non-null value equal to `target`
+fn bool_helper(array: &BooleanArray, target: bool) -> Option<bool> {
+    if array.null_count() == array.len() {
+        return None;
+    }
+    Some((0..array.len()).any(|i| array.is_valid(i) && array.value(i) == target))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +194,42 @@ mod tests {
         assert_eq!(5, min(&a).unwrap());
         assert_eq!(9, max(&a).unwrap());
     }
+
+    #[test]
+    fn test_string_array_min_max() {
+        let a = StringArray::from(vec![Some("b"), None, Some("C"), Some("a"), Some("B")]);
+        assert_eq!(Some("B"), min_string(&a, Collation::Binary));
+        assert_eq!(Some("b"), max_string(&a, Collation::Binary));
+        assert_eq!(Some("a"), min_string(&a, Collation::CaseInsensitive));
+        assert_eq!(Some("C"), max_string(&a, Collation::CaseInsensitive));
+
+        let a = StringArray::from(vec![None, None]);
+        assert_eq!(None, min_string(&a, Collation::Binary));
+    }
+
+    #[test]
+    fn test_boolean_array_any_all() {
+        let a = BooleanArray::from(vec![Some(true), None, Some(false)]);
+        assert_eq!(Some(true), any(&a));
+        assert_eq!(Some(false), all(&a));
+
+        let a = BooleanArray::from(vec![Some(true), None, Some(true)]);
+        assert_eq!(Some(true), any(&a));
+        assert_eq!(Some(true), all(&a));
+
+        let a = BooleanArray::from(vec![Some(false), None]);
+        assert_eq!(Some(false), any(&a));
+        assert_eq!(Some(false), all(&a));
+    }
+
+    #[test]
+    fn test_boolean_array_any_all_nulls() {
+        let a = BooleanArray::from(vec![None, None]);
+        assert_eq!(None, any(&a));
+        assert_eq!(None, all(&a));
+
+        let a = BooleanArray::from(Vec::<bool>::new());
+        assert_eq!(None, any(&a));
+        assert_eq!(None, all(&a));
+    }
 }
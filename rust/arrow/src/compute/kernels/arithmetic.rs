@@ -22,10 +22,8 @@
 //! `RUSTFLAGS="-C target-feature=+avx2"` for example.  See the documentation
 //! [here](https://doc.rust-lang.org/stable/core/arch/) for more information.
 
-#[cfg(feature = "simd")]
 use std::mem;
 use std::ops::{Add, Div, Mul, Sub};
-#[cfg(feature = "simd")]
 use std::slice::from_raw_parts_mut;
 use std::sync::Arc;
 
@@ -34,14 +32,11 @@ use num::{One, Zero};
 use crate::array::*;
 #[cfg(feature = "simd")]
 use crate::bitmap::Bitmap;
-use crate::buffer::Buffer;
-#[cfg(feature = "simd")]
 use crate::buffer::MutableBuffer;
 use crate::compute::util::apply_bin_op_to_option_bitmap;
 #[cfg(feature = "simd")]
 use crate::compute::util::simd_load_set_invalid;
 use crate::datatypes;
-use crate::datatypes::ToByteSlice;
 use crate::error::{ArrowError, Result};
 
 /// Helper function to perform math lambda function on values from two arrays. If either
@@ -52,6 +47,21 @@ pub fn math_op<T, F>(
     right: &PrimitiveArray<T>,
     op: F,
 ) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    F: Fn(T::Native, T::Native) -> Result<T::Native>,
+{
+    math_op_with_buffer(left, right, op, MutableBuffer::new(0))
+}
+
+/// Version of `math_op` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn math_op_with_buffer<T, F>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    op: F,
+    mut buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
     F: Fn(T::Native, T::Native) -> Result<T::Native>,
@@ -68,9 +78,12 @@ where
         |a, b| a & b,
     )?;
 
-    let mut values = Vec::with_capacity(left.len());
-    for i in 0..left.len() {
-        values.push(op(left.value(i), right.value(i))?);
+    buffer.resize(left.len() * mem::size_of::<T::Native>())?;
+    let values: &mut [T::Native] = unsafe {
+        from_raw_parts_mut(buffer.data_mut().as_mut_ptr() as *mut T::Native, left.len())
+    };
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = op(left.value(i), right.value(i))?;
     }
 
     let data = ArrayData::new(
@@ -79,7 +92,7 @@ where
         None,
         null_bit_buffer,
         left.offset(),
-        vec![Buffer::from(values.to_byte_slice())],
+        vec![buffer.freeze()],
         vec![],
     );
     Ok(PrimitiveArray::<T>::from(Arc::new(data)))
@@ -91,6 +104,7 @@ fn simd_math_op<T, F>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
     op: F,
+    mut result: MutableBuffer,
 ) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
@@ -114,7 +128,8 @@ where
 
     let lanes = T::lanes();
     let buffer_size = left.len() * mem::size_of::<T::Native>();
-    let mut result = MutableBuffer::new(buffer_size).with_bitset(buffer_size, false);
+    result.resize(buffer_size)?;
+    result.set_null_bits(0, result.capacity());
 
     for i in (0..left.len()).step_by(lanes) {
         let simd_left = T::load(left.value_slice(i, lanes));
@@ -149,6 +164,7 @@ where
 fn simd_divide<T>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
+    mut result: MutableBuffer,
 ) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
@@ -170,7 +186,8 @@ where
 
     let lanes = T::lanes();
     let buffer_size = left.len() * mem::size_of::<T::Native>();
-    let mut result = MutableBuffer::new(buffer_size).with_bitset(buffer_size, false);
+    result.resize(buffer_size)?;
+    result.set_null_bits(0, result.capacity());
 
     for i in (0..left.len()).step_by(lanes) {
         let right_no_invalid_zeros =
@@ -213,6 +230,24 @@ pub fn add<T>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
 ) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: Add<Output = T::Native>
+        + Sub<Output = T::Native>
+        + Mul<Output = T::Native>
+        + Div<Output = T::Native>
+        + Zero,
+{
+    add_with_buffer(left, right, MutableBuffer::new(0))
+}
+
+/// Version of `add` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn add_with_buffer<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
     T::Native: Add<Output = T::Native>
@@ -222,10 +257,10 @@ where
         + Zero,
 {
     #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
-    return simd_math_op(&left, &right, |a, b| a + b);
+    return simd_math_op(&left, &right, |a, b| a + b, buffer);
 
     #[allow(unreachable_code)]
-    math_op(left, right, |a, b| Ok(a + b))
+    math_op_with_buffer(left, right, |a, b| Ok(a + b), buffer)
 }
 
 /// Perform `left - right` operation on two arrays. If either left or right value is null
@@ -234,6 +269,24 @@ pub fn subtract<T>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
 ) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: Add<Output = T::Native>
+        + Sub<Output = T::Native>
+        + Mul<Output = T::Native>
+        + Div<Output = T::Native>
+        + Zero,
+{
+    subtract_with_buffer(left, right, MutableBuffer::new(0))
+}
+
+/// Version of `subtract` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn subtract_with_buffer<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
     T::Native: Add<Output = T::Native>
@@ -243,10 +296,10 @@ where
         + Zero,
 {
     #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
-    return simd_math_op(&left, &right, |a, b| a - b);
+    return simd_math_op(&left, &right, |a, b| a - b, buffer);
 
     #[allow(unreachable_code)]
-    math_op(left, right, |a, b| Ok(a - b))
+    math_op_with_buffer(left, right, |a, b| Ok(a - b), buffer)
 }
 
 /// Perform `left * right` operation on two arrays. If either left or right value is null
@@ -255,6 +308,24 @@ pub fn multiply<T>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
 ) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: Add<Output = T::Native>
+        + Sub<Output = T::Native>
+        + Mul<Output = T::Native>
+        + Div<Output = T::Native>
+        + Zero,
+{
+    multiply_with_buffer(left, right, MutableBuffer::new(0))
+}
+
+/// Version of `multiply` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn multiply_with_buffer<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
     T::Native: Add<Output = T::Native>
@@ -264,10 +335,10 @@ where
         + Zero,
 {
     #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
-    return simd_math_op(&left, &right, |a, b| a * b);
+    return simd_math_op(&left, &right, |a, b| a * b, buffer);
 
     #[allow(unreachable_code)]
-    math_op(left, right, |a, b| Ok(a * b))
+    math_op_with_buffer(left, right, |a, b| Ok(a * b), buffer)
 }
 
 /// Perform `left / right` operation on two arrays. If either left or right value is null
@@ -277,6 +348,25 @@ pub fn divide<T>(
     left: &PrimitiveArray<T>,
     right: &PrimitiveArray<T>,
 ) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: Add<Output = T::Native>
+        + Sub<Output = T::Native>
+        + Mul<Output = T::Native>
+        + Div<Output = T::Native>
+        + Zero
+        + One,
+{
+    divide_with_buffer(left, right, MutableBuffer::new(0))
+}
+
+/// Version of `divide` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn divide_with_buffer<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
 where
     T: datatypes::ArrowNumericType,
     T::Native: Add<Output = T::Native>
@@ -287,16 +377,240 @@ where
         + One,
 {
     #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
-    return simd_divide(&left, &right);
+    return simd_divide(&left, &right, buffer);
 
     #[allow(unreachable_code)]
-    math_op(left, right, |a, b| {
-        if b.is_zero() {
-            Err(ArrowError::DivideByZero)
-        } else {
-            Ok(a / b)
+    math_op_with_buffer(
+        left,
+        right,
+        |a, b| {
+            if b.is_zero() {
+                Err(ArrowError::DivideByZero)
+            } else {
+                Ok(a / b)
+            }
+        },
+        buffer,
+    )
+}
+
+/// Native types whose remainder reports a division by zero or an overflow, such as
+/// `i32::MIN % -1`, as an error instead of panicking
+pub trait CheckedRem: Copy + Zero + One + PartialEq {
+    /// The divisor whose remainder can overflow, `-1` for signed integers
+    fn overflowing_divisor() -> Option<Self>;
+
+    /// Computes `self % rhs`, or an error if `rhs` is zero or the remainder overflows
+    fn checked_remainder(self, rhs: Self) -> Result<Self>;
+}
+
+macro_rules! checked_rem_integer {
+    ($native_ty:ty, $overflowing_divisor:expr) => {
+        impl CheckedRem for $native_ty {
+            fn overflowing_divisor() -> Option<Self> {
+                $overflowing_divisor
+            }
+
+            fn checked_remainder(self, rhs: Self) -> Result<Self> {
+                if rhs == 0 {
+                    return Err(ArrowError::DivideByZero);
+                }
+                self.checked_rem(rhs).ok_or_else(|| {
+                    ArrowError::ComputeError(format!(
+                        "Overflow in the remainder of {} % {}",
+                        self, rhs
+                    ))
+                })
+            }
+        }
+    };
+}
+
+checked_rem_integer!(i8, Some(-1));
+checked_rem_integer!(i16, Some(-1));
+checked_rem_integer!(i32, Some(-1));
+checked_rem_integer!(i64, Some(-1));
+checked_rem_integer!(u8, None);
+checked_rem_integer!(u16, None);
+checked_rem_integer!(u32, None);
+checked_rem_integer!(u64, None);
+
+macro_rules! checked_rem_float {
+    ($native_ty:ty) => {
+        impl CheckedRem for $native_ty {
+            fn overflowing_divisor() -> Option<Self> {
+                None
+            }
+
+            fn checked_remainder(self, rhs: Self) -> Result<Self> {
+                if rhs == 0.0 {
+                    Err(ArrowError::DivideByZero)
+                } else {
+                    Ok(self % rhs)
+                }
+            }
+        }
+    };
+}
+
+checked_rem_float!(f32);
+checked_rem_float!(f64);
+
+/// SIMD vectorized version of `modulus`, which like `simd_divide` sets the divisors of
+/// null slots and padding to `1` before checking for zeros. The lanes of a chunk with
+/// a divisor that can overflow are checked one at a time.
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
+fn simd_modulus<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    mut result: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: CheckedRem,
+{
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform math operation on arrays of different length".to_string(),
+        ));
+    }
+
+    let null_bit_buffer = apply_bin_op_to_option_bitmap(
+        left.data().null_bitmap(),
+        right.data().null_bitmap(),
+        |a, b| a & b,
+    )?;
+    let bitmap = null_bit_buffer.map(Bitmap::from);
+
+    let lanes = T::lanes();
+    let buffer_size = left.len() * mem::size_of::<T::Native>();
+    result.resize(buffer_size)?;
+    result.set_null_bits(0, result.capacity());
+
+    for i in (0..left.len()).step_by(lanes) {
+        let right_no_invalid_zeros =
+            unsafe { simd_load_set_invalid(right, &bitmap, i, lanes, T::Native::one()) };
+        let is_zero = T::eq(T::init(T::Native::zero()), right_no_invalid_zeros);
+        if T::mask_any(is_zero) {
+            return Err(ArrowError::DivideByZero);
+        }
+        if let Some(divisor) = T::Native::overflowing_divisor() {
+            if T::mask_any(T::eq(T::init(divisor), right_no_invalid_zeros)) {
+                for j in i..left.len().min(i + lanes) {
+                    if bitmap.as_ref().map_or(true, |b| b.is_set(j)) {
+                        left.value(j).checked_remainder(right.value(j))?;
+                    }
+                }
+            }
         }
-    })
+        let simd_left = T::load(left.value_slice(i, lanes));
+        let simd_result = T::bin_op(simd_left, right_no_invalid_zeros, |a, b| a % b);
+
+        let result_slice: &mut [T::Native] = unsafe {
+            from_raw_parts_mut(
+                (result.data_mut().as_mut_ptr() as *mut T::Native).offset(i as isize),
+                lanes,
+            )
+        };
+        T::write(simd_result, result_slice);
+    }
+
+    let null_bit_buffer = bitmap.and_then(|b| Some(b.bits));
+
+    let data = ArrayData::new(
+        T::get_data_type(),
+        left.len(),
+        None,
+        null_bit_buffer,
+        left.offset(),
+        vec![result.freeze()],
+        vec![],
+    );
+    Ok(PrimitiveArray::<T>::from(Arc::new(data)))
+}
+
+/// Perform `left % right` operation on two arrays. If either left or right value is null
+/// then the result is also null. If any right hand value is zero then the result of this
+/// operation will be `Err(ArrowError::DivideByZero)`, and if a remainder overflows, as
+/// `i32::MIN % -1` does, it will be an `Err(ArrowError::ComputeError)`.
+pub fn modulus<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: CheckedRem,
+{
+    modulus_with_buffer(left, right, MutableBuffer::new(0))
+}
+
+/// Version of `modulus` that writes the values of the result into the given buffer,
+/// whose memory is reused if it is large enough.
+pub fn modulus_with_buffer<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: CheckedRem,
+{
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
+    return simd_modulus(&left, &right, buffer);
+
+    #[allow(unreachable_code)]
+    scalar_modulus(&left, &right, buffer)
+}
+
+/// Scalar version of `modulus`, which skips the values of null slots as they are
+/// undefined and may be zero
+#[cfg_attr(
+    all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"),
+    allow(dead_code)
+)]
+fn scalar_modulus<T>(
+    left: &PrimitiveArray<T>,
+    right: &PrimitiveArray<T>,
+    mut buffer: MutableBuffer,
+) -> Result<PrimitiveArray<T>>
+where
+    T: datatypes::ArrowNumericType,
+    T::Native: CheckedRem,
+{
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot perform math operation on arrays of different length".to_string(),
+        ));
+    }
+
+    let null_bit_buffer = apply_bin_op_to_option_bitmap(
+        left.data().null_bitmap(),
+        right.data().null_bitmap(),
+        |a, b| a & b,
+    )?;
+
+    buffer.resize(left.len() * mem::size_of::<T::Native>())?;
+    let values: &mut [T::Native] = unsafe {
+        from_raw_parts_mut(buffer.data_mut().as_mut_ptr() as *mut T::Native, left.len())
+    };
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = if left.is_null(i) || right.is_null(i) {
+            T::default_value()
+        } else {
+            left.value(i).checked_remainder(right.value(i))?
+        };
+    }
+
+    let data = ArrayData::new(
+        T::get_data_type(),
+        left.len(),
+        None,
+        null_bit_buffer,
+        left.offset(),
+        vec![buffer.freeze()],
+        vec![],
+    );
+    Ok(PrimitiveArray::<T>::from(Arc::new(data)))
 }
 
 #[cfg(test)]
@@ -398,6 +712,47 @@ mod tests {
         assert_eq!(1.0, c.value(2));
     }
 
+    #[test]
+    fn test_primitive_array_modulus() {
+        let a = Int32Array::from(vec![Some(15), Some(-7), None, Some(9)]);
+        let b = Int32Array::from(vec![Some(4), Some(3), Some(2), Some(9)]);
+        let c = modulus(&a, &b).unwrap();
+        assert_eq!(3, c.value(0));
+        // the sign of the result follows the dividend
+        assert_eq!(-1, c.value(1));
+        assert!(c.is_null(2));
+        assert_eq!(0, c.value(3));
+
+        let a = Float64Array::from(vec![7.5]);
+        let b = Float64Array::from(vec![2.0]);
+        assert_eq!(1.5, modulus(&a, &b).unwrap().value(0));
+
+        let b = Float64Array::from(vec![0.0]);
+        assert_eq!(
+            ArrowError::DivideByZero,
+            modulus(&a, &b).err().expect("modulus by zero should fail")
+        );
+
+        // the value of the null divisor is zero, but it is not divided by
+        let a = Int32Array::from(vec![Some(15), Some(7), None]);
+        let b = Int32Array::from(vec![None, Some(-1), Some(0)]);
+        let c = modulus(&a, &b).unwrap();
+        assert!(c.is_null(0));
+        assert_eq!(0, c.value(1));
+        assert!(c.is_null(2));
+
+        let a = Int32Array::from(vec![Some(5), Some(i32::min_value())]);
+        let b = Int32Array::from(vec![Some(2), Some(-1)]);
+        assert_eq!(
+            ArrowError::ComputeError(
+                "Overflow in the remainder of -2147483648 % -1".to_string()
+            ),
+            modulus(&a, &b)
+                .err()
+                .expect("overflowing modulus should fail")
+        );
+    }
+
     #[test]
     fn test_primitive_array_add_with_nulls() {
         let a = Int32Array::from(vec![Some(5), None, Some(7), None]);
@@ -409,4 +764,27 @@ mod tests {
         assert_eq!(true, c.is_null(3));
         assert_eq!(13, c.value(2));
     }
+
+    #[test]
+    fn test_primitive_array_with_buffer() {
+        let a = Int32Array::from(vec![5, 6, 7, 8, 9]);
+        let b = Int32Array::from(vec![1, 2, 1, 2, 1]);
+        let c = multiply_with_buffer(&a, &b, MutableBuffer::new(0)).unwrap();
+        assert_eq!(vec![5, 12, 7, 16, 9], c.value_slice(0, 5).to_vec());
+
+        // the memory of a result that is no longer referenced can be written to again
+        let buffer = c.data().buffers()[0].clone();
+        let ptr = buffer.raw_data();
+        drop(c);
+        let buffer = buffer.into_mutable().unwrap();
+        let c = subtract_with_buffer(&a, &b, buffer).unwrap();
+        assert_eq!(ptr, c.data().buffers()[0].raw_data());
+        assert_eq!(vec![4, 4, 6, 6, 8], c.value_slice(0, 5).to_vec());
+
+        let buffer = MutableBuffer::new(0);
+        assert!(
+            divide_with_buffer(&a, &Int32Array::from(vec![1, 0, 1, 0, 1]), buffer)
+                .is_err()
+        );
+    }
 }
@@ -15,7 +15,9 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Defines boolean kernels on Arrow `BooleanArray`'s, e.g. `AND`, `OR` and `NOT`.
+//! Defines boolean kernels on Arrow `BooleanArray`'s, e.g. `AND`, `OR` and `NOT`, and
+//! kernels that produce or consume the null masks of arrays of any type, e.g. `IS NULL`
+//! and `NULLIF`.
 //!
 //! These kernels can leverage SIMD if available on your system.  Currently no runtime
 //! detection is provided, you should enable the specific SIMD intrinsics using
@@ -24,11 +26,12 @@
 
 use std::sync::Arc;
 
-use crate::array::{Array, ArrayData, BooleanArray};
-use crate::buffer::Buffer;
+use crate::array::{make_array, Array, ArrayData, ArrayRef, BooleanArray};
+use crate::buffer::{Buffer, MutableBuffer};
 use crate::compute::util::apply_bin_op_to_option_bitmap;
 use crate::datatypes::DataType;
 use crate::error::{ArrowError, Result};
+use crate::util::bit_util;
 
 /// Helper function to implement binary kernels
 fn binary_boolean_kernel<F>(
@@ -97,9 +100,93 @@ pub fn not(left: &BooleanArray) -> Result<BooleanArray> {
     Ok(BooleanArray::from(Arc::new(data)))
 }
 
+/// Returns a non-null `BooleanArray` with whether each value of the array is null.
+///
+/// The output is computed from the null bitmap of the array as a whole rather than
+/// by checking each value.
+pub fn is_null(input: &dyn Array) -> Result<BooleanArray> {
+    null_mask_kernel(input, |bitmap| !bitmap, false)
+}
+
+/// Returns a non-null `BooleanArray` with whether each value of the array is not null.
+///
+/// The null bitmap of the array is reused as the values of the output, so this does
+/// not copy any data.
+pub fn is_not_null(input: &dyn Array) -> Result<BooleanArray> {
+    null_mask_kernel(input, |bitmap| bitmap.clone(), true)
+}
+
+/// Helper function to implement `is_null` and `is_not_null`, which builds the values of
+/// the output from the null bitmap of the input with `op`, or sets them all to
+/// `no_nulls` if the input has no null bitmap
+fn null_mask_kernel<F>(input: &dyn Array, op: F, no_nulls: bool) -> Result<BooleanArray>
+where
+    F: Fn(&Buffer) -> Buffer,
+{
+    let len = input.len();
+    let data = input.data();
+    let (values, offset) = match data.null_bitmap() {
+        Some(bitmap) => (op(bitmap.buffer_ref()), input.offset()),
+        None => {
+            let num_bytes = bit_util::ceil(len, 8);
+            let values = MutableBuffer::new(num_bytes).with_bitset(num_bytes, no_nulls);
+            (values.freeze(), 0)
+        }
+    };
+    let data = ArrayData::new(
+        DataType::Boolean,
+        len,
+        Some(0),
+        None,
+        offset,
+        vec![values],
+        vec![],
+    );
+    Ok(BooleanArray::from(Arc::new(data)))
+}
+
+/// Copies the array and sets the values to null where `condition` is true, as in SQL's
+/// `NULLIF`. Where `condition` is false or null, the values of the array are kept.
+///
+/// Only the null bitmap is rebuilt; the values and children of the array are shared
+/// with the input.
+pub fn nullif(input: &ArrayRef, condition: &BooleanArray) -> Result<ArrayRef> {
+    if input.len() != condition.len() {
+        return Err(ArrowError::ComputeError(
+            "Inputs to nullif must have the same length".to_string(),
+        ));
+    }
+    let data = input.data();
+    let offset = data.offset();
+    let num_bytes = bit_util::ceil(offset + input.len(), 8);
+    let mut null_bit_buffer = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    let mut null_count = 0;
+    {
+        let null_bits = null_bit_buffer.data_mut();
+        for i in 0..input.len() {
+            if input.is_valid(i) && !(condition.is_valid(i) && condition.value(i)) {
+                bit_util::set_bit(null_bits, offset + i);
+            } else {
+                null_count += 1;
+            }
+        }
+    }
+    let data = ArrayData::new(
+        data.data_type().clone(),
+        input.len(),
+        Some(null_count),
+        Some(null_bit_buffer.freeze()),
+        offset,
+        data.buffers().to_vec(),
+        data.child_data().to_vec(),
+    );
+    Ok(make_array(Arc::new(data)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::array::{Int32Array, StringArray};
 
     #[test]
     fn test_bool_array_and() {
@@ -154,4 +241,78 @@ mod tests {
         assert_eq!(true, c.is_null(2));
         assert_eq!(false, c.is_null(3));
     }
+
+    #[test]
+    fn test_is_null() {
+        let a = Int32Array::from(vec![Some(1), None, Some(3), None]);
+        let res = is_null(&a).unwrap();
+        assert_eq!(0, res.null_count());
+        assert_eq!(vec![false, true, false, true], bool_values(&res));
+
+        let res = is_not_null(&a).unwrap();
+        assert_eq!(0, res.null_count());
+        assert_eq!(vec![true, false, true, false], bool_values(&res));
+    }
+
+    #[test]
+    fn test_is_null_without_null_bitmap() {
+        let a = StringArray::from(vec!["a", "b", "c"]);
+        assert_eq!(
+            vec![false, false, false],
+            bool_values(&is_null(&a).unwrap())
+        );
+        assert_eq!(
+            vec![true, true, true],
+            bool_values(&is_not_null(&a).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_is_null_sliced() {
+        let a = Int32Array::from(vec![None, Some(1), None, Some(3), None]);
+        let sliced = a.slice(1, 3);
+        let res = is_null(sliced.as_ref()).unwrap();
+        assert_eq!(vec![false, true, false], bool_values(&res));
+    }
+
+    #[test]
+    fn test_nullif() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            Some(3),
+            Some(4),
+            Some(5),
+        ]));
+        let condition = BooleanArray::from(vec![
+            Some(true),
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+        ]);
+        let res = nullif(&a, &condition).unwrap();
+        let res = res.as_any().downcast_ref::<Int32Array>().unwrap();
+        let expected = Int32Array::from(vec![None, None, Some(3), Some(4), None]);
+        assert!(res.equals(&expected));
+        assert_eq!(3, res.null_count());
+    }
+
+    #[test]
+    fn test_nullif_strings() {
+        let a: ArrayRef = Arc::new(StringArray::from(vec![Some("a"), Some("b"), None]));
+        let condition = BooleanArray::from(vec![false, true, false]);
+        let res = nullif(&a, &condition).unwrap();
+        let res = res.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("a", res.value(0));
+        assert!(res.is_null(1));
+        assert!(res.is_null(2));
+
+        let short = BooleanArray::from(vec![true]);
+        assert!(nullif(&a, &short).is_err());
+    }
+
+    fn bool_values(array: &BooleanArray) -> Vec<bool> {
+        (0..array.len()).map(|i| array.value(i)).collect()
+    }
 }
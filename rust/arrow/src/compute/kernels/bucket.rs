@@ -0,0 +1,194 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels that assign numeric values to equal-width buckets, such as to
+//! compute histograms.
+
+use num::ToPrimitive;
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+/// A number of buckets of equal width that divide the range between two bounds, as
+/// used by the SQL function `WIDTH_BUCKET`.
+///
+/// The buckets are numbered from 1 to `num_buckets`. Values before the first bound
+/// are in bucket 0, and values at or after the second bound are in bucket
+/// `num_buckets + 1`. The first bound may be larger than the second, in which case the
+/// buckets are numbered in descending order of the values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidthBuckets {
+    low: f64,
+    high: f64,
+    num_buckets: u32,
+}
+
+impl WidthBuckets {
+    /// Create buckets between two bounds, which must be finite and different. The number
+    /// of buckets must be positive and less than `u32::max_value()`.
+    pub fn try_new(low: f64, high: f64, num_buckets: u32) -> Result<Self> {
+        if !low.is_finite() || !high.is_finite() || low == high {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Bucket bounds must be finite and different, found {} and {}",
+                low, high
+            )));
+        }
+        if num_buckets == 0 || num_buckets == u32::max_value() {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Invalid number of buckets {}",
+                num_buckets
+            )));
+        }
+        Ok(Self {
+            low,
+            high,
+            num_buckets,
+        })
+    }
+
+    /// The number of buckets between the bounds
+    pub fn num_buckets(&self) -> u32 {
+        self.num_buckets
+    }
+
+    /// Get the bucket of a value, or `None` if it is NaN
+    pub fn bucket(&self, value: f64) -> Option<u32> {
+        if value.is_nan() {
+            return None;
+        }
+        // the position of the value as a fraction of the range, which is negative
+        // before the first bound in both directions
+        let position = (value - self.low) / (self.high - self.low);
+        Some(if position < 0.0 {
+            0
+        } else if position >= 1.0 {
+            self.num_buckets + 1
+        } else {
+            // rounding may place a value just below the second bound past the last
+            // bucket
+            let bucket = (position * self.num_buckets as f64) as u32 + 1;
+            bucket.min(self.num_buckets)
+        })
+    }
+}
+
+/// Get the bucket of each value of an array, where null and NaN values are null
+pub fn width_bucket<T>(
+    array: &PrimitiveArray<T>,
+    buckets: &WidthBuckets,
+) -> Result<UInt32Array>
+where
+    T: ArrowNumericType,
+    T::Native: ToPrimitive,
+{
+    let mut builder = UInt32Builder::new(array.len());
+    for i in 0..array.len() {
+        let bucket = if array.is_null(i) {
+            None
+        } else {
+            array.value(i).to_f64().and_then(|v| buckets.bucket(v))
+        };
+        match bucket {
+            Some(bucket) => builder.append_value(bucket)?,
+            None => builder.append_null()?,
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Count the values of an array in each bucket, including the buckets before and after
+/// the bounds, so that the returned vector has `num_buckets + 2` counts. Null and NaN
+/// values are not counted.
+pub fn histogram<T>(array: &PrimitiveArray<T>, buckets: &WidthBuckets) -> Vec<u64>
+where
+    T: ArrowNumericType,
+    T::Native: ToPrimitive,
+{
+    let mut counts = vec![0; buckets.num_buckets() as usize + 2];
+    for i in 0..array.len() {
+        if array.is_valid(i) {
+            if let Some(bucket) = array.value(i).to_f64().and_then(|v| buckets.bucket(v))
+            {
+                counts[bucket as usize] += 1;
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_buckets() {
+        let buckets = WidthBuckets::try_new(0.0, 10.0, 5).unwrap();
+        assert_eq!(buckets.bucket(-0.5), Some(0));
+        assert_eq!(buckets.bucket(0.0), Some(1));
+        assert_eq!(buckets.bucket(1.99), Some(1));
+        assert_eq!(buckets.bucket(2.0), Some(2));
+        assert_eq!(buckets.bucket(9.999_999_999_999_998), Some(5));
+        assert_eq!(buckets.bucket(10.0), Some(6));
+        assert_eq!(buckets.bucket(std::f64::INFINITY), Some(6));
+        assert_eq!(buckets.bucket(std::f64::NAN), None);
+
+        // the buckets of a descending range are numbered from the first bound
+        let buckets = WidthBuckets::try_new(10.0, 0.0, 5).unwrap();
+        assert_eq!(buckets.bucket(10.5), Some(0));
+        assert_eq!(buckets.bucket(10.0), Some(1));
+        assert_eq!(buckets.bucket(0.5), Some(5));
+        assert_eq!(buckets.bucket(0.0), Some(6));
+
+        assert!(WidthBuckets::try_new(1.0, 1.0, 5).is_err());
+        assert!(WidthBuckets::try_new(0.0, std::f64::NAN, 5).is_err());
+        assert!(WidthBuckets::try_new(0.0, 1.0, 0).is_err());
+    }
+
+    #[test]
+    fn test_width_bucket() {
+        let buckets = WidthBuckets::try_new(0.0, 100.0, 4).unwrap();
+        let a = Int32Array::from(vec![Some(5), None, Some(25), Some(99), Some(100)]);
+        let b = width_bucket(&a, &buckets).unwrap();
+        assert_eq!(b.value(0), 1);
+        assert!(b.is_null(1));
+        assert_eq!(b.value(2), 2);
+        assert_eq!(b.value(3), 4);
+        assert_eq!(b.value(4), 5);
+
+        let a = Float64Array::from(vec![std::f64::NAN, -1.0]);
+        let b = width_bucket(&a, &buckets).unwrap();
+        assert!(b.is_null(0));
+        assert_eq!(b.value(1), 0);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let buckets = WidthBuckets::try_new(0.0, 4.0, 2).unwrap();
+        let a = Float64Array::from(vec![
+            Some(-1.0),
+            Some(0.0),
+            Some(1.5),
+            None,
+            Some(2.0),
+            Some(3.9),
+            Some(4.0),
+            Some(std::f64::NAN),
+        ]);
+        assert_eq!(histogram(&a, &buckets), vec![1, 2, 2, 1]);
+    }
+}
@@ -35,12 +35,14 @@
 //! assert_eq!(7.0, c.value(2));
 //! ```
 
+use std::convert::TryFrom;
 use std::str;
 use std::sync::Arc;
 
 use crate::array::*;
 use crate::buffer::Buffer;
 use crate::compute::kernels::arithmetic::{divide, multiply};
+use crate::compute::kernels::take::take;
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 
@@ -57,6 +59,13 @@ use crate::error::{ArrowError, Result};
 /// * Time32 and Time64: precision lost when going to higher interval
 /// * Timestamp and Date{32|64}: precision lost when going to higher interval
 /// * Temporal to/from backing primitive: zero-copy with data type change
+/// * Utf8 to Binary: zero-copy with data type change
+/// * Decimal to and from numeric, Utf8 and other decimals: values that do not fit the
+///   precision return null, casts to integers truncate and other casts round half away
+///   from zero
+/// * Binary to Utf8: values that are not valid UTF-8 return null
+/// * Dictionary to any type its values can be cast to: the value of each key is looked
+///   up, for all integer key types
 ///
 /// Unsupported Casts
 /// * To or from `StructArray`
@@ -78,6 +87,20 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
         (_, Struct(_)) => Err(ArrowError::ComputeError(
             "Cannot cast to struct from other types".to_string(),
         )),
+        (Dictionary(ref key_type, _), _) => match **key_type {
+            Int8 => cast_dictionary::<Int8Type>(array, to_type),
+            Int16 => cast_dictionary::<Int16Type>(array, to_type),
+            Int32 => cast_dictionary::<Int32Type>(array, to_type),
+            Int64 => cast_dictionary::<Int64Type>(array, to_type),
+            UInt8 => cast_dictionary::<UInt8Type>(array, to_type),
+            UInt16 => cast_dictionary::<UInt16Type>(array, to_type),
+            UInt32 => cast_dictionary::<UInt32Type>(array, to_type),
+            UInt64 => cast_dictionary::<UInt64Type>(array, to_type),
+            _ => Err(ArrowError::ComputeError(format!(
+                "Casting from dictionary with key type {:?} not supported",
+                key_type
+            ))),
+        },
         (List(_), List(ref to)) => {
             let data = array.data_ref();
             let underlying_array = make_array(data.child_data()[0].clone());
@@ -188,6 +211,22 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
             Int64 => cast_string_to_numeric::<Int64Type>(array),
             Float32 => cast_string_to_numeric::<Float32Type>(array),
             Float64 => cast_string_to_numeric::<Float64Type>(array),
+            Decimal(precision, scale) => {
+                cast_string_to_decimal(array, *precision, *scale)
+            }
+            // strings have the same layout as binaries
+            Binary => {
+                let data = array.data();
+                Ok(make_array(Arc::new(ArrayData::new(
+                    Binary,
+                    data.len(),
+                    Some(data.null_count()),
+                    data.null_buffer().cloned(),
+                    data.offset(),
+                    data.buffers().to_vec(),
+                    vec![],
+                ))))
+            }
             _ => Err(ArrowError::ComputeError(format!(
                 "Casting from {:?} to {:?} not supported",
                 from_type, to_type,
@@ -204,6 +243,19 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
             Int64 => cast_numeric_to_string::<Int64Type>(array),
             Float32 => cast_numeric_to_string::<Float32Type>(array),
             Float64 => cast_numeric_to_string::<Float64Type>(array),
+            Decimal(_, _) => {
+                let from = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+                let mut b = StringBuilder::new(array.len());
+                for i in 0..array.len() {
+                    if array.is_null(i) {
+                        b.append_null()?;
+                    } else {
+                        b.append_value(&from.value_as_string(i))?;
+                    }
+                }
+
+                Ok(Arc::new(b.finish()) as ArrayRef)
+            }
             Binary => {
                 let from = array.as_any().downcast_ref::<BinaryArray>().unwrap();
                 let mut b = StringBuilder::new(array.len());
@@ -226,6 +278,62 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
             ))),
         },
 
+        // decimal casts
+        (Decimal(_, from_scale), Decimal(precision, scale)) => {
+            let from = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+            let mut b = Decimal128Builder::new(array.len(), *precision, *scale);
+            for i in 0..array.len() {
+                let value = if array.is_null(i) {
+                    None
+                } else {
+                    Decimal128Array::rescale_value(
+                        from.value(i),
+                        *from_scale,
+                        *scale,
+                        *precision,
+                    )
+                };
+                match value {
+                    Some(value) => b.append_value(value)?,
+                    None => b.append_null()?,
+                }
+            }
+
+            Ok(Arc::new(b.finish()) as ArrayRef)
+        }
+        (Decimal(_, scale), _) => match to_type {
+            UInt8 => cast_decimal_to_numeric::<UInt8Type>(array, *scale),
+            UInt16 => cast_decimal_to_numeric::<UInt16Type>(array, *scale),
+            UInt32 => cast_decimal_to_numeric::<UInt32Type>(array, *scale),
+            UInt64 => cast_decimal_to_numeric::<UInt64Type>(array, *scale),
+            Int8 => cast_decimal_to_numeric::<Int8Type>(array, *scale),
+            Int16 => cast_decimal_to_numeric::<Int16Type>(array, *scale),
+            Int32 => cast_decimal_to_numeric::<Int32Type>(array, *scale),
+            Int64 => cast_decimal_to_numeric::<Int64Type>(array, *scale),
+            Float32 => cast_decimal_to_numeric::<Float32Type>(array, *scale),
+            Float64 => cast_decimal_to_numeric::<Float64Type>(array, *scale),
+            _ => Err(ArrowError::ComputeError(format!(
+                "Casting from {:?} to {:?} not supported",
+                from_type, to_type,
+            ))),
+        },
+        (_, Decimal(precision, scale)) => match from_type {
+            UInt8 => cast_numeric_to_decimal::<UInt8Type>(array, *precision, *scale),
+            UInt16 => cast_numeric_to_decimal::<UInt16Type>(array, *precision, *scale),
+            UInt32 => cast_numeric_to_decimal::<UInt32Type>(array, *precision, *scale),
+            UInt64 => cast_numeric_to_decimal::<UInt64Type>(array, *precision, *scale),
+            Int8 => cast_numeric_to_decimal::<Int8Type>(array, *precision, *scale),
+            Int16 => cast_numeric_to_decimal::<Int16Type>(array, *precision, *scale),
+            Int32 => cast_numeric_to_decimal::<Int32Type>(array, *precision, *scale),
+            Int64 => cast_numeric_to_decimal::<Int64Type>(array, *precision, *scale),
+            Float32 => cast_numeric_to_decimal::<Float32Type>(array, *precision, *scale),
+            Float64 => cast_numeric_to_decimal::<Float64Type>(array, *precision, *scale),
+            _ => Err(ArrowError::ComputeError(format!(
+                "Casting from {:?} to {:?} not supported",
+                from_type, to_type,
+            ))),
+        },
+
         // start numeric casts
         (UInt8, UInt16) => cast_numeric_arrays::<UInt8Type, UInt16Type>(array),
         (UInt8, UInt32) => cast_numeric_arrays::<UInt8Type, UInt32Type>(array),
@@ -465,6 +573,22 @@ pub fn cast(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
                 }
             }
         }
+        (Timestamp(from_unit, _), Timestamp(to_unit, _)) if from_unit == to_unit => {
+            // only the time zone differs, and the values are instants in UTC
+            use TimeUnit::*;
+            match to_unit {
+                Second => cast_array_data::<TimestampSecondType>(array, to_type.clone()),
+                Millisecond => {
+                    cast_array_data::<TimestampMillisecondType>(array, to_type.clone())
+                }
+                Microsecond => {
+                    cast_array_data::<TimestampMicrosecondType>(array, to_type.clone())
+                }
+                Nanosecond => {
+                    cast_array_data::<TimestampNanosecondType>(array, to_type.clone())
+                }
+            }
+        }
         (Timestamp(from_unit, _), Timestamp(to_unit, _)) => {
             let time_array = Int64Array::from(array.data());
             let from_size = time_unit_multiple(&from_unit);
@@ -582,6 +706,28 @@ where
 }
 
 /// Convert Array into a PrimitiveArray of type, and apply numeric cast
+/// Cast a dictionary array to an array of its values, cast to the given type. The
+/// values are cast before they are looked up so that each is only cast once.
+fn cast_dictionary<K: ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+    to_type: &DataType,
+) -> Result<ArrayRef> {
+    let array = array
+        .as_any()
+        .downcast_ref::<DictionaryArray<K>>()
+        .ok_or_else(|| {
+            ArrowError::ComputeError(
+                "Dictionary array does not match its key type".to_string(),
+            )
+        })?;
+    let values = cast(&array.values(), to_type)?;
+    let indices: Vec<Option<u32>> = array
+        .keys()
+        .map(|key| key.and_then(|key| key.to_usize()).map(|key| key as u32))
+        .collect();
+    take(&values, &UInt32Array::from(indices), None)
+}
+
 fn cast_numeric_arrays<FROM, TO>(from: &ArrayRef) -> Result<ArrayRef>
 where
     FROM: ArrowNumericType,
@@ -605,21 +751,16 @@ where
     T::Native: num::NumCast,
     R::Native: num::NumCast,
 {
-    let mut b = PrimitiveBuilder::<R>::new(from.len());
-
-    for i in 0..from.len() {
+    let iter = (0..from.len()).map(|i| {
         if from.is_null(i) {
-            b.append_null()?;
+            None
         } else {
             // some casts return None, such as a negative value to u{8|16|32|64}
-            match num::cast::cast(from.value(i)) {
-                Some(v) => b.append_value(v)?,
-                None => b.append_null()?,
-            };
+            num::cast::cast(from.value(i))
         }
-    }
-
-    Ok(b.finish())
+    });
+    // a range reports its exact length
+    Ok(unsafe { PrimitiveArray::<R>::from_trusted_len_iter(iter) })
 }
 
 /// Cast numeric types to Utf8
@@ -684,6 +825,109 @@ where
     Ok(b.finish())
 }
 
+/// Cast numeric types to Decimal, rounding floats and returning null for values that
+/// do not fit the precision
+fn cast_numeric_to_decimal<FROM>(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+) -> Result<ArrayRef>
+where
+    FROM: ArrowNumericType,
+    FROM::Native: num::NumCast,
+{
+    let from = array
+        .as_any()
+        .downcast_ref::<PrimitiveArray<FROM>>()
+        .unwrap();
+    let is_float = match FROM::get_data_type() {
+        DataType::Float32 | DataType::Float64 => true,
+        _ => false,
+    };
+    let mut b = Decimal128Builder::new(array.len(), precision, scale);
+    for i in 0..array.len() {
+        let value = if from.is_null(i) {
+            None
+        } else if is_float {
+            num::ToPrimitive::to_f64(&from.value(i))
+                .map(|v| (v * 10f64.powi(scale as i32)).round())
+                .filter(|v| v.is_finite() && v.abs() < 1e38)
+                .map(|v| v as i128)
+                .filter(|v| Decimal128Array::fits_precision(*v, precision))
+        } else {
+            let v = from.value(i);
+            num::ToPrimitive::to_i64(&v)
+                .map(i128::from)
+                .or_else(|| num::ToPrimitive::to_u64(&v).map(i128::from))
+                .and_then(|v| Decimal128Array::rescale_value(v, 0, scale, precision))
+        };
+        match value {
+            Some(value) => b.append_value(value)?,
+            None => b.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(b.finish()) as ArrayRef)
+}
+
+/// Cast Decimal to numeric types, truncating for integers and returning null for
+/// values that are out of range of the type
+fn cast_decimal_to_numeric<TO>(array: &ArrayRef, scale: usize) -> Result<ArrayRef>
+where
+    TO: ArrowNumericType,
+    TO::Native: num::NumCast,
+{
+    let from = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+    let is_float = match TO::get_data_type() {
+        DataType::Float32 | DataType::Float64 => true,
+        _ => false,
+    };
+    let factor = 10_i128.checked_pow(scale as u32);
+    let iter = (0..from.len()).map(|i| {
+        if from.is_null(i) {
+            None
+        } else if is_float {
+            num::cast::cast(from.value(i) as f64 / 10f64.powi(scale as i32))
+        } else {
+            let integer = factor.map_or(0, |factor| from.value(i) / factor);
+            if integer < 0 {
+                i64::try_from(integer).ok().and_then(num::cast::cast)
+            } else {
+                u64::try_from(integer).ok().and_then(num::cast::cast)
+            }
+        }
+    });
+    // a range reports its exact length
+    Ok(
+        Arc::new(unsafe { PrimitiveArray::<TO>::from_trusted_len_iter(iter) })
+            as ArrayRef,
+    )
+}
+
+/// Cast Utf8 to Decimal, returning null for strings that are not decimal numbers or do
+/// not fit the precision
+fn cast_string_to_decimal(
+    array: &ArrayRef,
+    precision: usize,
+    scale: usize,
+) -> Result<ArrayRef> {
+    let from = array.as_any().downcast_ref::<StringArray>().unwrap();
+    let mut b = Decimal128Builder::new(array.len(), precision, scale);
+    for i in 0..array.len() {
+        let value = if from.is_null(i) {
+            None
+        } else {
+            Decimal128Array::parse_value(from.value(i), precision, scale)
+        };
+        match value {
+            Some(value) => b.append_value(value)?,
+            None => b.append_null()?,
+        }
+    }
+
+    Ok(Arc::new(b.finish()) as ArrayRef)
+}
+
 /// Cast numeric types to Boolean
 ///
 /// Any zero value returns `false` while non-zero returns `true`
@@ -913,6 +1157,71 @@ mod tests {
         assert_eq!(false, c.is_valid(2));
     }
 
+    #[test]
+    fn test_cast_dictionary_to_values() {
+        let values = vec![Some("1"), None, Some("x"), Some("1")];
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(
+                values
+                    .clone()
+                    .into_iter()
+                    .collect::<DictionaryArray<Int8Type>>(),
+            ),
+            Arc::new(
+                values
+                    .clone()
+                    .into_iter()
+                    .collect::<DictionaryArray<UInt16Type>>(),
+            ),
+            Arc::new(values.into_iter().collect::<DictionaryArray<Int64Type>>()),
+        ];
+        for array in arrays {
+            let b = cast(&array, &DataType::Utf8).unwrap();
+            let c = b.as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(4, c.len());
+            assert_eq!("1", c.value(0));
+            assert_eq!(false, c.is_valid(1));
+            assert_eq!("x", c.value(2));
+            assert_eq!("1", c.value(3));
+
+            // the values are cast to the target type
+            let b = cast(&array, &DataType::Int32).unwrap();
+            let c = b.as_any().downcast_ref::<Int32Array>().unwrap();
+            assert_eq!(1, c.value(0));
+            assert_eq!(false, c.is_valid(1));
+            assert_eq!(false, c.is_valid(2));
+            assert_eq!(1, c.value(3));
+        }
+    }
+
+    #[test]
+    fn test_cast_utf8_to_binary_and_back() {
+        let a = StringArray::from(vec![Some("hello"), None, Some("arrow")]);
+        let array = Arc::new(a) as ArrayRef;
+        let array = array.slice(1, 2);
+        let b = cast(&array, &DataType::Binary).unwrap();
+        let c = b.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(2, c.len());
+        assert!(c.is_null(0));
+        assert_eq!(b"arrow", c.value(1));
+
+        let b = cast(&b, &DataType::Utf8).unwrap();
+        let c = b.as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(c.is_null(0));
+        assert_eq!("arrow", c.value(1));
+    }
+
+    #[test]
+    fn test_cast_binary_to_utf8() {
+        let values: Vec<&[u8]> = vec![b"valid", &[0xff, 0xfe]];
+        let array = Arc::new(BinaryArray::from(values)) as ArrayRef;
+        let b = cast(&array, &DataType::Utf8).unwrap();
+        let c = b.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("valid", c.value(0));
+        // not valid UTF-8
+        assert!(c.is_null(1));
+    }
+
     #[test]
     fn test_cast_bool_to_i32() {
         let a = BooleanArray::from(vec![Some(true), Some(false), None]);
@@ -1092,6 +1401,91 @@ mod tests {
         assert!(c.is_null(2));
     }
 
+    #[test]
+    fn test_cast_timestamp_to_timezone() {
+        let a = TimestampMillisecondArray::from_opt_vec(
+            vec![Some(864000000005), None],
+            Some(Arc::new("UTC".to_string())),
+        );
+        let array = Arc::new(a) as ArrayRef;
+        let to_type = DataType::Timestamp(
+            TimeUnit::Millisecond,
+            Some(Arc::new("+02:00".to_string())),
+        );
+        let b = cast(&array, &to_type).unwrap();
+        assert_eq!(&to_type, b.data_type());
+        let c = b
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(864000000005, c.value(0));
+        assert!(c.is_null(1));
+    }
+
+    #[test]
+    fn test_cast_decimal() {
+        let mut builder = Decimal128Builder::new(4, 5, 2);
+        builder.append_value(12345).unwrap();
+        builder.append_value(-1250).unwrap();
+        builder.append_null().unwrap();
+        builder.append_value(99999).unwrap();
+        let array = Arc::new(builder.finish()) as ArrayRef;
+
+        let b = cast(&array, &DataType::Int32).unwrap();
+        let c = b.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(123, c.value(0));
+        assert_eq!(-12, c.value(1));
+        assert!(c.is_null(2));
+
+        let b = cast(&array, &DataType::Float64).unwrap();
+        let c = b.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert!((c.value(0) - 123.45).abs() < 1e-9);
+
+        let b = cast(&array, &DataType::Utf8).unwrap();
+        let c = b.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("123.45", c.value(0));
+        assert_eq!("-12.50", c.value(1));
+        assert!(c.is_null(2));
+
+        let b = cast(&array, &DataType::Decimal(5, 1)).unwrap();
+        let c = b.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(1235, c.value(0));
+        assert_eq!(-125, c.value(1));
+        assert!(c.is_null(2));
+        assert_eq!(10000, c.value(3));
+
+        // 999.99 does not fit into 4 digits with 2 after the decimal point
+        let b = cast(&array, &DataType::Decimal(4, 2)).unwrap();
+        assert!(b.is_null(3));
+    }
+
+    #[test]
+    fn test_cast_to_decimal() {
+        let a = Float64Array::from(vec![Some(0.1), Some(-2.346), None, Some(1e10)]);
+        let array = Arc::new(a) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(10, 2)).unwrap();
+        let c = b.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(&DataType::Decimal(10, 2), c.data_type());
+        assert_eq!(10, c.value(0));
+        assert_eq!(-235, c.value(1));
+        assert!(c.is_null(2));
+        assert!(c.is_null(3));
+
+        let a = Int64Array::from(vec![7, -3]);
+        let array = Arc::new(a) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(10, 2)).unwrap();
+        let c = b.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(700, c.value(0));
+        assert_eq!(-300, c.value(1));
+
+        let a = StringArray::from(vec!["1.005", "x"]);
+        let array = Arc::new(a) as ArrayRef;
+        let b = cast(&array, &DataType::Decimal(10, 2)).unwrap();
+        let c = b.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(101, c.value(0));
+        assert!(c.is_null(1));
+    }
+
     #[test]
     fn test_cast_timestamp_to_i64() {
         let a = TimestampMillisecondArray::from_opt_vec(
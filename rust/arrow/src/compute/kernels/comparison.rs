@@ -23,6 +23,8 @@
 //! [here](https://doc.rust-lang.org/stable/core/arch/) for more information.
 
 use regex::Regex;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -78,55 +80,65 @@ where
     compare_op!(left, right, op)
 }
 
-pub fn like_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArray> {
-    let mut map = HashMap::new();
-    if left.len() != right.len() {
-        return Err(ArrowError::ComputeError(
-            "Cannot perform comparison operation on arrays of different length"
-                .to_string(),
-        ));
-    }
-
-    let null_bit_buffer = apply_bin_op_to_option_bitmap(
-        left.data().null_bitmap(),
-        right.data().null_bitmap(),
-        |a, b| a & b,
-    )?;
+/// Options of a `LIKE` pattern match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LikeOptions {
+    /// Whether letters match regardless of their case, as for `ILIKE`
+    pub case_insensitive: bool,
+    /// The character that makes the following `%`, `_` or escape character of the
+    /// pattern match itself, as for `LIKE 'a!%' ESCAPE '!'`
+    pub escape: Option<char>,
+}
 
-    let mut result = BooleanBufferBuilder::new(left.len());
-    for i in 0..left.len() {
-        let haystack = left.value(i);
-        let pat = right.value(i);
-        let re = if let Some(ref regex) = map.get(pat) {
-            regex
+/// Translate a `LIKE` pattern into a regular expression that matches whole values
+fn like_pattern_to_regex(pattern: &str, options: &LikeOptions) -> Result<Regex> {
+    let mut re_pattern = String::with_capacity(pattern.len() + 8);
+    re_pattern.push_str(if options.case_insensitive {
+        "(?si)^"
+    } else {
+        "(?s)^"
+    });
+    let mut buffer = [0; 4];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if Some(c) == options.escape {
+            match chars.next() {
+                Some(c) => {
+                    re_pattern.push_str(&regex::escape(c.encode_utf8(&mut buffer)))
+                }
+                None => {
+                    return Err(ArrowError::ComputeError(format!(
+                        "LIKE pattern must not end with escape character: {}",
+                        pattern
+                    )))
+                }
+            }
+        } else if c == '%' {
+            re_pattern.push_str(".*");
+        } else if c == '_' {
+            re_pattern.push('.');
         } else {
-            let re_pattern = pat.replace("%", ".*").replace("_", ".");
-            let re = Regex::new(&re_pattern).map_err(|e| {
-                ArrowError::ComputeError(format!(
-                    "Unable to build regex from LIKE pattern: {}",
-                    e
-                ))
-            })?;
-            map.insert(pat, re);
-            map.get(pat).unwrap()
-        };
-
-        result.append(re.is_match(haystack))?;
+            re_pattern.push_str(&regex::escape(c.encode_utf8(&mut buffer)));
+        }
     }
-
-    let data = ArrayData::new(
-        DataType::Boolean,
-        left.len(),
-        None,
-        null_bit_buffer,
-        left.offset(),
-        vec![result.finish()],
-        vec![],
-    );
-    Ok(PrimitiveArray::<BooleanType>::from(Arc::new(data)))
+    re_pattern.push('$');
+    Regex::new(&re_pattern).map_err(|e| {
+        ArrowError::ComputeError(format!(
+            "Unable to build regex from LIKE pattern: {}",
+            e
+        ))
+    })
 }
 
-pub fn nlike_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArray> {
+/// Match each value of the left array with the `LIKE` pattern at the same position of
+/// the right array, negating the result if `negated` is set. The regular expression of
+/// each distinct pattern is only built once.
+fn like_op(
+    left: &StringArray,
+    right: &StringArray,
+    options: &LikeOptions,
+    negated: bool,
+) -> Result<BooleanArray> {
     let mut map = HashMap::new();
     if left.len() != right.len() {
         return Err(ArrowError::ComputeError(
@@ -143,23 +155,21 @@ pub fn nlike_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArra
 
     let mut result = BooleanBufferBuilder::new(left.len());
     for i in 0..left.len() {
+        if left.is_null(i) || right.is_null(i) {
+            result.append(false)?;
+            continue;
+        }
         let haystack = left.value(i);
         let pat = right.value(i);
         let re = if let Some(ref regex) = map.get(pat) {
             regex
         } else {
-            let re_pattern = pat.replace("%", ".*").replace("_", ".");
-            let re = Regex::new(&re_pattern).map_err(|e| {
-                ArrowError::ComputeError(format!(
-                    "Unable to build regex from LIKE pattern: {}",
-                    e
-                ))
-            })?;
+            let re = like_pattern_to_regex(pat, options)?;
             map.insert(pat, re);
             map.get(pat).unwrap()
         };
 
-        result.append(!re.is_match(haystack))?;
+        result.append(re.is_match(haystack) != negated)?;
     }
 
     let data = ArrayData::new(
@@ -174,6 +184,38 @@ pub fn nlike_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArra
     Ok(PrimitiveArray::<BooleanType>::from(Arc::new(data)))
 }
 
+/// Whether each value of the left array matches the `LIKE` pattern at the same position
+/// of the right array, where `%` matches any number of characters and `_` a single one
+pub fn like_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArray> {
+    like_op(left, right, &LikeOptions::default(), false)
+}
+
+/// Whether each value of the left array does not match the `LIKE` pattern at the same
+/// position of the right array
+pub fn nlike_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArray> {
+    like_op(left, right, &LikeOptions::default(), true)
+}
+
+/// Whether each value of the left array matches the `LIKE` pattern at the same position
+/// of the right array, with a custom escape character or regardless of case
+pub fn like_utf8_with_options(
+    left: &StringArray,
+    right: &StringArray,
+    options: &LikeOptions,
+) -> Result<BooleanArray> {
+    like_op(left, right, options, false)
+}
+
+/// Whether each value of the left array does not match the `LIKE` pattern at the same
+/// position of the right array, with a custom escape character or regardless of case
+pub fn nlike_utf8_with_options(
+    left: &StringArray,
+    right: &StringArray,
+    options: &LikeOptions,
+) -> Result<BooleanArray> {
+    like_op(left, right, options, true)
+}
+
 pub fn eq_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArray> {
     compare_op!(left, right, |a, b| a == b)
 }
@@ -198,6 +240,173 @@ pub fn gt_eq_utf8(left: &StringArray, right: &StringArray) -> Result<BooleanArra
     compare_op!(left, right, |a, b| a >= b)
 }
 
+/// Decimals can only be compared by their unscaled integers if they have the same scale
+fn check_decimal_scales(left: &Decimal128Array, right: &Decimal128Array) -> Result<()> {
+    if left.scale() != right.scale() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot compare decimals of scales {} and {}",
+            left.scale(),
+            right.scale()
+        )));
+    }
+    Ok(())
+}
+
+/// Perform `left == right` operation on two decimal arrays of the same scale
+pub fn eq_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a == b)
+}
+
+/// Perform `left != right` operation on two decimal arrays of the same scale
+pub fn neq_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a != b)
+}
+
+/// Perform `left < right` operation on two decimal arrays of the same scale
+pub fn lt_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a < b)
+}
+
+/// Perform `left <= right` operation on two decimal arrays of the same scale
+pub fn lt_eq_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a <= b)
+}
+
+/// Perform `left > right` operation on two decimal arrays of the same scale
+pub fn gt_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a > b)
+}
+
+/// Perform `left >= right` operation on two decimal arrays of the same scale
+pub fn gt_eq_decimal(
+    left: &Decimal128Array,
+    right: &Decimal128Array,
+) -> Result<BooleanArray> {
+    check_decimal_scales(left, right)?;
+    compare_op!(left, right, |a, b| a >= b)
+}
+
+/// Defines how strings are compared by the collated comparison and sort kernels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Collation {
+    /// Compare strings by their UTF-8 bytes, which orders them by code point
+    Binary,
+    /// Compare strings by code point after converting them to lowercase
+    CaseInsensitive,
+}
+
+impl Default for Collation {
+    fn default() -> Self {
+        Collation::Binary
+    }
+}
+
+impl Collation {
+    /// Returns a key for `value` such that two strings are equal under this collation
+    /// if and only if their keys are equal, and are ordered like their keys
+    pub fn sort_key<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(value),
+            Collation::CaseInsensitive => Cow::Owned(value.to_lowercase()),
+        }
+    }
+
+    /// Compare two strings under this collation
+    pub fn compare(&self, left: &str, right: &str) -> Ordering {
+        match self {
+            Collation::Binary => left.cmp(right),
+            _ => self.sort_key(left).cmp(&self.sort_key(right)),
+        }
+    }
+}
+
+/// Helper function to compare the values of two string arrays under a collation
+fn compare_utf8_collated<F>(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+    op: F,
+) -> Result<BooleanArray>
+where
+    F: Fn(Ordering) -> bool,
+{
+    compare_op!(left, right, |a, b| op(collation.compare(a, b)))
+}
+
+/// Perform `left == right` operation on two string arrays under a collation
+pub fn eq_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o == Ordering::Equal)
+}
+
+/// Perform `left != right` operation on two string arrays under a collation
+pub fn neq_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o != Ordering::Equal)
+}
+
+/// Perform `left < right` operation on two string arrays under a collation
+pub fn lt_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o == Ordering::Less)
+}
+
+/// Perform `left <= right` operation on two string arrays under a collation
+pub fn lt_eq_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o != Ordering::Greater)
+}
+
+/// Perform `left > right` operation on two string arrays under a collation
+pub fn gt_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o == Ordering::Greater)
+}
+
+/// Perform `left >= right` operation on two string arrays under a collation
+pub fn gt_eq_utf8_collated(
+    left: &StringArray,
+    right: &StringArray,
+    collation: Collation,
+) -> Result<BooleanArray> {
+    compare_utf8_collated(left, right, collation, |o| o != Ordering::Less)
+}
+
 /// Helper function to perform boolean lambda function on values from two arrays using
 /// SIMD.
 #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "simd"))]
@@ -495,6 +704,52 @@ mod tests {
         nlike_utf8,
         vec![false, false, false, true]
     );
+    test_utf8!(
+        test_utf8_array_like_whole_value,
+        vec!["arrow", "arrow", "a.rrow", "arr\nw", "arrow"],
+        vec!["row%", "ar_o", "a.r%", "arr_w", "a.row"],
+        like_utf8,
+        vec![false, false, true, true, false]
+    );
+
+    #[test]
+    fn test_utf8_array_like_with_options() {
+        let left = StringArray::from(vec!["Arrow", "ARROW", "50%", "50 percent", "a_b"]);
+        let right = StringArray::from(vec!["arr%", "%RO%", "50!%", "50!%", "a!_%"]);
+        let ilike = LikeOptions {
+            case_insensitive: true,
+            escape: None,
+        };
+        let escape = LikeOptions {
+            case_insensitive: false,
+            escape: Some('!'),
+        };
+
+        let res = like_utf8_with_options(&left, &right, &ilike).unwrap();
+        let expected = vec![true, true, false, false, false];
+        assert_eq!(expected, (0..5).map(|i| res.value(i)).collect::<Vec<_>>());
+        let res = like_utf8_with_options(&left, &right, &escape).unwrap();
+        let expected = vec![false, true, true, false, true];
+        assert_eq!(expected, (0..5).map(|i| res.value(i)).collect::<Vec<_>>());
+        let res = nlike_utf8_with_options(&left, &right, &escape).unwrap();
+        let expected = vec![true, false, false, true, false];
+        assert_eq!(expected, (0..5).map(|i| res.value(i)).collect::<Vec<_>>());
+
+        // an escape character must be followed by the character it escapes
+        let left = StringArray::from(vec!["a"]);
+        let right = StringArray::from(vec!["a!"]);
+        assert!(like_utf8_with_options(&left, &right, &escape).is_err());
+    }
+
+    #[test]
+    fn test_utf8_array_like_nulls() {
+        let left = StringArray::from(vec![Some("arrow"), None, Some("arrow")]);
+        let right = StringArray::from(vec![Some("ar%"), Some("ar%"), None]);
+        let res = like_utf8(&left, &right).unwrap();
+        assert_eq!(true, res.value(0));
+        assert!(res.is_null(1));
+        assert!(res.is_null(2));
+    }
     test_utf8!(
         test_utf8_array_eq,
         vec!["arrow", "arrow", "arrow", "arrow"],
@@ -537,4 +792,61 @@ mod tests {
         gt_eq_utf8,
         vec![false, false, true, true]
     );
+
+    #[test]
+    fn test_decimal_array_comparison() {
+        let mut builder = Decimal128Builder::new(3, 10, 2);
+        builder.append_value(150).unwrap();
+        builder.append_value(-20).unwrap();
+        builder.append_null().unwrap();
+        let a = builder.finish();
+        let mut builder = Decimal128Builder::new(3, 5, 2);
+        builder.append_value(150).unwrap();
+        builder.append_value(10).unwrap();
+        builder.append_value(0).unwrap();
+        let b = builder.finish();
+
+        let c = eq_decimal(&a, &b).unwrap();
+        assert!(c.value(0));
+        assert!(!c.value(1));
+        assert!(c.is_null(2));
+        let c = lt_decimal(&a, &b).unwrap();
+        assert!(!c.value(0));
+        assert!(c.value(1));
+        let c = gt_eq_decimal(&a, &b).unwrap();
+        assert!(c.value(0));
+        assert!(!c.value(1));
+
+        let mut builder = Decimal128Builder::new(3, 10, 3);
+        builder.append_value(1500).unwrap();
+        builder.append_value(1500).unwrap();
+        builder.append_value(1500).unwrap();
+        assert!(eq_decimal(&a, &builder.finish()).is_err());
+    }
+
+    #[test]
+    fn test_utf8_array_collated() {
+        let left = StringArray::from(vec![Some("Arrow"), Some("arrow"), Some("b"), None]);
+        let right =
+            StringArray::from(vec![Some("arrow"), Some("B"), Some("A"), Some("a")]);
+
+        let res = eq_utf8_collated(&left, &right, Collation::CaseInsensitive).unwrap();
+        assert!(res.value(0));
+        assert!(!res.value(1));
+        assert!(!res.value(2));
+        assert!(res.is_null(3));
+
+        let res = lt_utf8_collated(&left, &right, Collation::CaseInsensitive).unwrap();
+        assert_eq!(
+            vec![false, true, false],
+            (0..3).map(|i| res.value(i)).collect::<Vec<_>>()
+        );
+
+        // binary collation orders uppercase before lowercase letters
+        let res = lt_utf8_collated(&left, &right, Collation::Binary).unwrap();
+        assert_eq!(
+            vec![true, false, false],
+            (0..3).map(|i| res.value(i)).collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,160 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines concat kernel for `ArrayRef`
+
+use std::sync::Arc;
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+use TimeUnit::*;
+
+/// Concatenate multiple `ArrayRef`s of the same type into a single `ArrayRef`,
+/// preserving the order of the arrays and their nulls.
+pub fn concat(arrays: &[ArrayRef]) -> Result<ArrayRef> {
+    if arrays.is_empty() {
+        return Err(ArrowError::InvalidArgumentError(
+            "concat requires at least one array".to_string(),
+        ));
+    }
+    let data_type = arrays[0].data_type();
+    if arrays.iter().any(|a| a.data_type() != data_type) {
+        return Err(ArrowError::InvalidArgumentError(
+            "It is not possible to concatenate arrays of different data types"
+                .to_string(),
+        ));
+    }
+    match data_type {
+        DataType::Boolean => concat_primitive::<BooleanType>(arrays),
+        DataType::Int8 => concat_primitive::<Int8Type>(arrays),
+        DataType::Int16 => concat_primitive::<Int16Type>(arrays),
+        DataType::Int32 => concat_primitive::<Int32Type>(arrays),
+        DataType::Int64 => concat_primitive::<Int64Type>(arrays),
+        DataType::UInt8 => concat_primitive::<UInt8Type>(arrays),
+        DataType::UInt16 => concat_primitive::<UInt16Type>(arrays),
+        DataType::UInt32 => concat_primitive::<UInt32Type>(arrays),
+        DataType::UInt64 => concat_primitive::<UInt64Type>(arrays),
+        DataType::Float32 => concat_primitive::<Float32Type>(arrays),
+        DataType::Float64 => concat_primitive::<Float64Type>(arrays),
+        DataType::Date32(_) => concat_primitive::<Date32Type>(arrays),
+        DataType::Date64(_) => concat_primitive::<Date64Type>(arrays),
+        DataType::Time32(Second) => concat_primitive::<Time32SecondType>(arrays),
+        DataType::Time32(Millisecond) => {
+            concat_primitive::<Time32MillisecondType>(arrays)
+        }
+        DataType::Time64(Microsecond) => {
+            concat_primitive::<Time64MicrosecondType>(arrays)
+        }
+        DataType::Time64(Nanosecond) => concat_primitive::<Time64NanosecondType>(arrays),
+        DataType::Timestamp(Second, _) => concat_primitive::<TimestampSecondType>(arrays),
+        DataType::Timestamp(Millisecond, _) => {
+            concat_primitive::<TimestampMillisecondType>(arrays)
+        }
+        DataType::Timestamp(Microsecond, _) => {
+            concat_primitive::<TimestampMicrosecondType>(arrays)
+        }
+        DataType::Timestamp(Nanosecond, _) => {
+            concat_primitive::<TimestampNanosecondType>(arrays)
+        }
+        DataType::Utf8 => concat_string(arrays),
+        t => Err(ArrowError::ComputeError(format!(
+            "Concat not supported for data type {:?}",
+            t
+        ))),
+    }
+}
+
+/// `concat` implementation for primitive arrays
+fn concat_primitive<T>(arrays: &[ArrayRef]) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+{
+    let len = arrays.iter().map(|a| a.len()).sum();
+    let mut builder = PrimitiveBuilder::<T>::new(len);
+    for array in arrays {
+        let a = array.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+        for i in 0..a.len() {
+            if a.is_valid(i) {
+                builder.append_value(a.value(i))?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+/// `concat` implementation for string arrays
+fn concat_string(arrays: &[ArrayRef]) -> Result<ArrayRef> {
+    let len = arrays.iter().map(|a| a.len()).sum();
+    let mut builder = StringBuilder::new(len);
+    for array in arrays {
+        let a = array.as_any().downcast_ref::<StringArray>().unwrap();
+        for i in 0..a.len() {
+            if a.is_valid(i) {
+                builder.append_value(a.value(i))?;
+            } else {
+                builder.append_null()?;
+            }
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat_primitive_arrays() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![Some(-1), None, Some(2)])),
+            Arc::new(Int64Array::from(vec![Some(3)])),
+            Arc::new(Int64Array::from(vec![None, Some(5)])),
+        ];
+        let output = concat(&arrays).unwrap();
+        let expected =
+            Int64Array::from(vec![Some(-1), None, Some(2), Some(3), None, Some(5)]);
+        let output = output.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert!(output.equals(&expected));
+    }
+
+    #[test]
+    fn test_concat_string_arrays() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![Some("hello"), None])),
+            Arc::new(StringArray::from(vec![Some("arrow")])),
+        ];
+        let output = concat(&arrays).unwrap();
+        let output = output.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(3, output.len());
+        assert_eq!("hello", output.value(0));
+        assert!(output.is_null(1));
+        assert_eq!("arrow", output.value(2));
+    }
+
+    #[test]
+    fn test_concat_incompatible_types() {
+        let arrays: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["a"])),
+        ];
+        assert!(concat(&arrays).is_err());
+    }
+}
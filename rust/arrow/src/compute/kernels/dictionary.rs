@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels for `DictionaryArray`
+
+use crate::array::*;
+use crate::compute::kernels::sort::{sort_to_indices, SortOptions};
+use crate::compute::kernels::take::take;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+/// Rebuild the dictionary of an array with only the values that its keys reference.
+///
+/// Arrays that were filtered or sliced keep the full dictionary of the original array,
+/// which this kernel drops before e.g. the array is sent over IPC. The remaining values
+/// keep their order unless `sort` is given, in which case they are sorted using the
+/// given options and the keys are remapped accordingly.
+pub fn compact_dictionary<K: ArrowDictionaryKeyType>(
+    array: &DictionaryArray<K>,
+    sort: Option<SortOptions>,
+) -> Result<DictionaryArray<K>> {
+    let values = array.values();
+
+    let mut referenced = vec![false; values.len()];
+    for key in array.keys() {
+        if let Some(key) = key {
+            match key.to_usize() {
+                Some(key) if key < referenced.len() => referenced[key] = true,
+                _ => {
+                    return Err(ArrowError::ComputeError(format!(
+                        "Dictionary key {:?} is out of bounds for {} values",
+                        key,
+                        referenced.len()
+                    )))
+                }
+            }
+        }
+    }
+
+    let indices = UInt32Array::from(
+        (0..values.len() as u32)
+            .filter(|i| referenced[*i as usize])
+            .collect::<Vec<_>>(),
+    );
+    let mut compacted = take(&values, &indices, None)?;
+    let mut order: Vec<usize> = (0..indices.len()).collect();
+    if let Some(options) = sort {
+        let sorted = sort_to_indices(&compacted, Some(options))?;
+        for i in 0..sorted.len() {
+            order[sorted.value(i) as usize] = i;
+        }
+        compacted = take(&compacted, &sorted, None)?;
+    }
+
+    // the new key of each value that is referenced
+    let mut new_keys = vec![None; values.len()];
+    for i in 0..indices.len() {
+        new_keys[indices.value(i) as usize] = K::Native::from_usize(order[i]);
+    }
+
+    let mut keys = PrimitiveBuilder::<K>::new(array.len());
+    for key in array.keys() {
+        match key.and_then(|key| new_keys[key.to_usize().unwrap()]) {
+            Some(key) => keys.append_value(key)?,
+            None => keys.append_null()?,
+        }
+    }
+    Ok(keys.finish_dict(compacted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_dictionary() {
+        let array: DictionaryArray<Int8Type> =
+            vec![Some("d"), Some("c"), None, Some("a"), Some("c"), Some("b")]
+                .into_iter()
+                .collect();
+        // a slice keeps the dictionary of the whole array
+        let array = array.slice(1, 4);
+        let array = array
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int8Type>>()
+            .unwrap();
+        assert_eq!(array.values().len(), 4);
+
+        let compacted = compact_dictionary(array, None).unwrap();
+        assert_eq!(
+            compacted.keys().collect::<Vec<_>>(),
+            vec![Some(0), None, Some(1), Some(0)]
+        );
+        let values = compacted.values();
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values.value(0), "c");
+        assert_eq!(values.value(1), "a");
+    }
+
+    #[test]
+    fn test_compact_dictionary_sorted() {
+        let array: DictionaryArray<Int16Type> =
+            vec![Some("z"), Some("x"), None, Some("y"), Some("x")]
+                .into_iter()
+                .collect();
+
+        let options = SortOptions {
+            descending: true,
+            ..Default::default()
+        };
+        let compacted = compact_dictionary(&array, Some(options)).unwrap();
+        assert_eq!(
+            compacted.keys().collect::<Vec<_>>(),
+            vec![Some(0), Some(2), None, Some(1), Some(2)]
+        );
+        let values = compacted.values();
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(values.value(0), "z");
+        assert_eq!(values.value(1), "y");
+        assert_eq!(values.value(2), "x");
+    }
+}
@@ -20,9 +20,17 @@
 pub mod aggregate;
 pub mod arithmetic;
 pub mod boolean;
+pub mod bucket;
 pub mod cast;
 pub mod comparison;
+pub mod concat;
+pub mod dictionary;
 pub mod filter;
 pub mod limit;
+pub mod regexp;
+pub mod sort;
+pub mod string;
 pub mod take;
 pub mod temporal;
+pub mod unique;
+pub mod zip;
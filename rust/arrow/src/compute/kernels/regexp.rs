@@ -0,0 +1,300 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels that match each value of a `StringArray` with the regular expression
+//! at the same position of another array, using the syntax of the `regex` crate. The
+//! regular expression of each distinct pattern is only compiled once per call.
+
+use std::collections::HashMap;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::array::*;
+use crate::error::{ArrowError, Result};
+
+/// Options of the regular expression kernels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegexOptions {
+    /// Whether letters match regardless of their case
+    pub case_insensitive: bool,
+    /// Whether `regexp_replace` replaces every match instead of only the first one
+    pub global: bool,
+}
+
+/// The compiled regular expressions of the patterns of a kernel call
+struct RegexCache<'a> {
+    options: RegexOptions,
+    regexes: HashMap<&'a str, Regex>,
+}
+
+impl<'a> RegexCache<'a> {
+    fn new(options: &RegexOptions) -> Self {
+        Self {
+            options: *options,
+            regexes: HashMap::new(),
+        }
+    }
+
+    /// Get the regular expression of a pattern, compiling it on its first use
+    fn get(&mut self, pattern: &'a str) -> Result<&Regex> {
+        if !self.regexes.contains_key(pattern) {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(self.options.case_insensitive)
+                .build()
+                .map_err(|e| {
+                    ArrowError::ComputeError(format!(
+                        "Invalid regular expression '{}': {}",
+                        pattern, e
+                    ))
+                })?;
+            self.regexes.insert(pattern, regex);
+        }
+        Ok(&self.regexes[pattern])
+    }
+}
+
+fn check_lengths(lengths: &[usize]) -> Result<()> {
+    if lengths.iter().any(|len| *len != lengths[0]) {
+        return Err(ArrowError::ComputeError(
+            "Cannot apply a regular expression kernel to arrays of different length"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether each value of a string array matches the regular expression at the same
+/// position, negating the result if `negated` is set
+fn regexp_is_match_op(
+    array: &StringArray,
+    regex_array: &StringArray,
+    options: &RegexOptions,
+    negated: bool,
+) -> Result<BooleanArray> {
+    check_lengths(&[array.len(), regex_array.len()])?;
+    let mut cache = RegexCache::new(options);
+    let mut builder = BooleanBuilder::new(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) || regex_array.is_null(i) {
+            builder.append_null()?;
+        } else {
+            let regex = cache.get(regex_array.value(i))?;
+            builder.append_value(regex.is_match(array.value(i)) != negated)?;
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Whether each value of a string array contains a match of the regular expression at
+/// the same position of the regex array. The result is null if either value is null.
+pub fn regexp_is_match_utf8(
+    array: &StringArray,
+    regex_array: &StringArray,
+    options: &RegexOptions,
+) -> Result<BooleanArray> {
+    regexp_is_match_op(array, regex_array, options, false)
+}
+
+/// Whether each value of a string array does not contain a match of the regular
+/// expression at the same position of the regex array. The result is null if either
+/// value is null.
+pub fn regexp_is_not_match_utf8(
+    array: &StringArray,
+    regex_array: &StringArray,
+    options: &RegexOptions,
+) -> Result<BooleanArray> {
+    regexp_is_match_op(array, regex_array, options, true)
+}
+
+/// Extract the first match of the regular expression at the same position of the regex
+/// array from each value of a string array, as a list of the substrings matched by its
+/// capture groups, or of the whole match if it has none. Capture groups that do not
+/// take part in the match are null, and the list is null if the value does not match or
+/// either value is null.
+pub fn regexp_match(
+    array: &StringArray,
+    regex_array: &StringArray,
+    options: &RegexOptions,
+) -> Result<ListArray> {
+    check_lengths(&[array.len(), regex_array.len()])?;
+    let mut cache = RegexCache::new(options);
+    let mut builder = ListBuilder::new(StringBuilder::new(array.len()));
+    for i in 0..array.len() {
+        if array.is_null(i) || regex_array.is_null(i) {
+            builder.append(false)?;
+            continue;
+        }
+        let regex = cache.get(regex_array.value(i))?;
+        match regex.captures(array.value(i)) {
+            Some(captures) if captures.len() == 1 => {
+                builder.values().append_value(&captures[0])?;
+                builder.append(true)?;
+            }
+            Some(captures) => {
+                for group in captures.iter().skip(1) {
+                    match group {
+                        Some(group) => builder.values().append_value(group.as_str())?,
+                        None => builder.values().append_null()?,
+                    }
+                }
+                builder.append(true)?;
+            }
+            None => builder.append(false)?,
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Replace the first match of the regular expression at the same position of the regex
+/// array in each value of a string array, or every match if `options.global` is set,
+/// with the replacement at the same position, in which `$1` or `${name}` refer to the
+/// substrings matched by capture groups. The result is null if any value is null.
+pub fn regexp_replace(
+    array: &StringArray,
+    regex_array: &StringArray,
+    replacement_array: &StringArray,
+    options: &RegexOptions,
+) -> Result<StringArray> {
+    check_lengths(&[array.len(), regex_array.len(), replacement_array.len()])?;
+    let mut cache = RegexCache::new(options);
+    let mut values = Vec::with_capacity(array.len());
+    for i in 0..array.len() {
+        if array.is_null(i) || regex_array.is_null(i) || replacement_array.is_null(i) {
+            values.push(None);
+            continue;
+        }
+        let regex = cache.get(regex_array.value(i))?;
+        let value = if options.global {
+            regex.replace_all(array.value(i), replacement_array.value(i))
+        } else {
+            regex.replace(array.value(i), replacement_array.value(i))
+        };
+        values.push(Some(value.into_owned()));
+    }
+    // a vector reports its exact length
+    Ok(unsafe { StringArray::from_trusted_len_iter(values.into_iter()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regexp_is_match() {
+        let array =
+            StringArray::from(vec![Some("arrow"), Some("Arrow"), None, Some("a")]);
+        let regex = StringArray::from(vec![Some("^ar"), Some("^ar"), Some("a"), None]);
+
+        let result =
+            regexp_is_match_utf8(&array, &regex, &RegexOptions::default()).unwrap();
+        assert_eq!(result.len(), 4);
+        assert!(result.value(0));
+        assert!(!result.value(1));
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+
+        let options = RegexOptions {
+            case_insensitive: true,
+            ..RegexOptions::default()
+        };
+        let result = regexp_is_match_utf8(&array, &regex, &options).unwrap();
+        assert!(result.value(0));
+        assert!(result.value(1));
+
+        let result = regexp_is_not_match_utf8(&array, &regex, &options).unwrap();
+        assert!(!result.value(0));
+        assert!(!result.value(1));
+        assert!(result.is_null(2));
+
+        // invalid patterns are errors
+        let regex = StringArray::from(vec!["(", "(", "(", "("]);
+        assert!(regexp_is_match_utf8(&array, &regex, &options).is_err());
+        // and so are arrays of different length
+        let regex = StringArray::from(vec!["a"]);
+        assert!(regexp_is_match_utf8(&array, &regex, &options).is_err());
+    }
+
+    #[test]
+    fn test_regexp_match() {
+        let array = StringArray::from(vec![
+            Some("key=value"),
+            Some("key"),
+            Some("no match"),
+            None,
+        ]);
+        let regex =
+            StringArray::from(vec!["(\\w+)(=(\\w+))?", "(\\w+)(=(\\w+))?", "=", "a"]);
+
+        let result = regexp_match(&array, &regex, &RegexOptions::default()).unwrap();
+        assert_eq!(result.len(), 4);
+        let values = |i: usize| -> Vec<Option<String>> {
+            let list = result.value(i);
+            let list = list.as_any().downcast_ref::<StringArray>().unwrap();
+            (0..list.len())
+                .map(|j| {
+                    if list.is_null(j) {
+                        None
+                    } else {
+                        Some(list.value(j).to_string())
+                    }
+                })
+                .collect()
+        };
+        assert_eq!(
+            values(0),
+            vec![
+                Some("key".to_string()),
+                Some("=value".to_string()),
+                Some("value".to_string())
+            ]
+        );
+        // groups that do not take part in the match are null
+        assert_eq!(values(1), vec![Some("key".to_string()), None, None]);
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+
+        // without capture groups the list contains the whole match
+        let regex = StringArray::from(vec!["y=v", "e", "m.+", "a"]);
+        let result = regexp_match(&array, &regex, &RegexOptions::default()).unwrap();
+        let list = result.value(2);
+        let list = list.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.value(0), "match");
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        let array = StringArray::from(vec![Some("a-b-c"), Some("A-B"), None]);
+        let regex = StringArray::from(vec!["(\\w)-", "(\\w)-", "-"]);
+        let replacement = StringArray::from(vec!["$1+", "$1+", "+"]);
+
+        let result =
+            regexp_replace(&array, &regex, &replacement, &RegexOptions::default())
+                .unwrap();
+        assert_eq!(result.value(0), "a+b-c");
+        assert_eq!(result.value(1), "A+B");
+        assert!(result.is_null(2));
+
+        let options = RegexOptions {
+            global: true,
+            ..RegexOptions::default()
+        };
+        let result = regexp_replace(&array, &regex, &replacement, &options).unwrap();
+        assert_eq!(result.value(0), "a+b+c");
+        assert_eq!(result.value(1), "A+B");
+    }
+}
@@ -0,0 +1,391 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines sort kernels for `ArrayRef`
+
+use std::cmp::Ordering;
+
+use crate::array::*;
+use crate::compute::{take, Collation};
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+use TimeUnit::*;
+
+/// Compares the value at an index of one array with the value at an index of another
+pub type DynComparator<'a> = Box<dyn Fn(usize, usize) -> Ordering + 'a>;
+
+/// Sort the `ArrayRef` using `SortOptions`.
+///
+/// Performs a stable sort on values and indices. Nulls are ordered according to the
+/// `nulls_first` flag in `options` and floating point NaN values are considered to be
+/// greater than any other value.
+pub fn sort(values: &ArrayRef, options: Option<SortOptions>) -> Result<ArrayRef> {
+    let indices = sort_to_indices(values, options)?;
+    take(values, &indices, None)
+}
+
+/// Sort elements from `ArrayRef` into an unsigned integer (`UInt32Array`) of indices
+pub fn sort_to_indices(
+    values: &ArrayRef,
+    options: Option<SortOptions>,
+) -> Result<UInt32Array> {
+    lexsort_to_indices(&[SortColumn {
+        values: values.clone(),
+        options,
+    }])
+}
+
+/// Options that define how sort kernels should behave
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SortOptions {
+    /// Whether to sort in descending order
+    pub descending: bool,
+    /// Whether to sort nulls first
+    pub nulls_first: bool,
+    /// How to compare strings
+    pub collation: Collation,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            descending: false,
+            nulls_first: true,
+            collation: Collation::Binary,
+        }
+    }
+}
+
+/// One column to be used in lexicographical sort
+#[derive(Clone, Debug)]
+pub struct SortColumn {
+    /// The values to sort by
+    pub values: ArrayRef,
+    /// How to order the values of this column
+    pub options: Option<SortOptions>,
+}
+
+/// Sort a list of `ArrayRef` using `SortOptions` provided for each array.
+///
+/// Performs a stable lexicographical sort on values and returns the indices of the
+/// rows in sorted order. Rows are ordered by the first column, with ties broken by
+/// the following columns in turn.
+///
+/// All columns must have the same length.
+pub fn lexsort_to_indices(columns: &[SortColumn]) -> Result<UInt32Array> {
+    if columns.is_empty() {
+        return Err(ArrowError::InvalidArgumentError(
+            "Sort requires at least one column".to_string(),
+        ));
+    }
+    let row_count = columns[0].values.len();
+    if columns.iter().any(|c| c.values.len() != row_count) {
+        return Err(ArrowError::ComputeError(
+            "lexical sort columns have different row counts".to_string(),
+        ));
+    }
+
+    let comparators = columns
+        .iter()
+        .map(build_comparator)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut indices: Vec<u32> = (0..row_count as u32).collect();
+    indices.sort_by(|a, b| {
+        for comparator in &comparators {
+            match comparator(*a as usize, *b as usize) {
+                Ordering::Equal => continue,
+                other => return other,
+            }
+        }
+        Ordering::Equal
+    });
+
+    Ok(UInt32Array::from(indices))
+}
+
+/// Build a comparator for the rows of a sort column that takes its nulls and sort
+/// direction into account
+fn build_comparator<'a>(column: &'a SortColumn) -> Result<DynComparator<'a>> {
+    let values = column.values.as_ref();
+    build_compare(values, values, column.options.unwrap_or_default())
+}
+
+/// Build a comparator that orders the rows of `left` relative to the rows of `right`,
+/// which must have the same data type.
+///
+/// Rows are ordered in the same way as by `lexsort_to_indices`, so the comparator can
+/// be used to merge arrays that were each sorted with the same options.
+pub fn build_compare<'a>(
+    left: &'a dyn Array,
+    right: &'a dyn Array,
+    options: SortOptions,
+) -> Result<DynComparator<'a>> {
+    if left.data_type() != right.data_type() {
+        return Err(ArrowError::ComputeError(format!(
+            "Cannot compare arrays of types {:?} and {:?}",
+            left.data_type(),
+            right.data_type()
+        )));
+    }
+    let compare_values = match left.data_type() {
+        DataType::Boolean => compare_primitive::<BooleanType>(left, right),
+        DataType::Int8 => compare_primitive::<Int8Type>(left, right),
+        DataType::Int16 => compare_primitive::<Int16Type>(left, right),
+        DataType::Int32 => compare_primitive::<Int32Type>(left, right),
+        DataType::Int64 => compare_primitive::<Int64Type>(left, right),
+        DataType::UInt8 => compare_primitive::<UInt8Type>(left, right),
+        DataType::UInt16 => compare_primitive::<UInt16Type>(left, right),
+        DataType::UInt32 => compare_primitive::<UInt32Type>(left, right),
+        DataType::UInt64 => compare_primitive::<UInt64Type>(left, right),
+        DataType::Float32 => compare_primitive::<Float32Type>(left, right),
+        DataType::Float64 => compare_primitive::<Float64Type>(left, right),
+        DataType::Date32(_) => compare_primitive::<Date32Type>(left, right),
+        DataType::Date64(_) => compare_primitive::<Date64Type>(left, right),
+        DataType::Time32(Second) => compare_primitive::<Time32SecondType>(left, right),
+        DataType::Time32(Millisecond) => {
+            compare_primitive::<Time32MillisecondType>(left, right)
+        }
+        DataType::Time64(Microsecond) => {
+            compare_primitive::<Time64MicrosecondType>(left, right)
+        }
+        DataType::Time64(Nanosecond) => {
+            compare_primitive::<Time64NanosecondType>(left, right)
+        }
+        DataType::Timestamp(Second, _) => {
+            compare_primitive::<TimestampSecondType>(left, right)
+        }
+        DataType::Timestamp(Millisecond, _) => {
+            compare_primitive::<TimestampMillisecondType>(left, right)
+        }
+        DataType::Timestamp(Microsecond, _) => {
+            compare_primitive::<TimestampMicrosecondType>(left, right)
+        }
+        DataType::Timestamp(Nanosecond, _) => {
+            compare_primitive::<TimestampNanosecondType>(left, right)
+        }
+        DataType::Utf8 => compare_string(left, right, options.collation),
+        t => {
+            return Err(ArrowError::ComputeError(format!(
+                "Sort not supported for data type {:?}",
+                t
+            )))
+        }
+    };
+
+    Ok(Box::new(move |a, b| {
+        match (left.is_valid(a), right.is_valid(b)) {
+            (true, true) => {
+                let ordering = compare_values(a, b);
+                if options.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            (false, false) => Ordering::Equal,
+            (false, true) if options.nulls_first => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, false) if options.nulls_first => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }))
+}
+
+/// Compare the values of primitive arrays, ignoring nulls
+fn compare_primitive<'a, T>(
+    left: &'a dyn Array,
+    right: &'a dyn Array,
+) -> DynComparator<'a>
+where
+    T: ArrowPrimitiveType,
+{
+    let left = left.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    let right = right.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    Box::new(move |a, b| cmp_nans_last(left.value(a), right.value(b)))
+}
+
+/// Compare the values of string arrays under a collation, ignoring nulls
+fn compare_string<'a>(
+    left: &'a dyn Array,
+    right: &'a dyn Array,
+    collation: Collation,
+) -> DynComparator<'a> {
+    let left = left.as_any().downcast_ref::<StringArray>().unwrap();
+    let right = right.as_any().downcast_ref::<StringArray>().unwrap();
+    Box::new(move |a, b| collation.compare(left.value(a), right.value(b)))
+}
+
+/// Compare two values that are only partially ordered, treating values that are not
+/// equal to themselves (i.e. NaN) as greater than all other values
+fn cmp_nans_last<T: PartialOrd>(a: T, b: T) -> Ordering {
+    match a.partial_cmp(&b) {
+        Some(ordering) => ordering,
+        None => match (a != a, b != b) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            _ => Ordering::Less,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::NAN;
+    use std::sync::Arc;
+
+    fn test_sort_to_indices_primitive_arrays<T>(
+        data: Vec<Option<T::Native>>,
+        options: Option<SortOptions>,
+        expected_data: Vec<u32>,
+    ) where
+        T: ArrowPrimitiveType,
+        PrimitiveArray<T>: From<Vec<Option<T::Native>>>,
+    {
+        let output = PrimitiveArray::<T>::from(data);
+        let expected = UInt32Array::from(expected_data);
+        let output = sort_to_indices(&(Arc::new(output) as ArrayRef), options).unwrap();
+        assert!(output.equals(&expected))
+    }
+
+    #[test]
+    fn test_sort_to_indices_primitives() {
+        test_sort_to_indices_primitive_arrays::<Int32Type>(
+            vec![None, Some(0), Some(2), Some(-1), Some(0), None],
+            None,
+            vec![0, 5, 3, 1, 4, 2],
+        );
+        test_sort_to_indices_primitive_arrays::<Int32Type>(
+            vec![None, Some(0), Some(2), Some(-1), Some(0), None],
+            Some(SortOptions {
+                descending: true,
+                nulls_first: false,
+                collation: Collation::Binary,
+            }),
+            vec![2, 1, 4, 3, 0, 5],
+        );
+        test_sort_to_indices_primitive_arrays::<Float64Type>(
+            vec![Some(1.5), Some(NAN), None, Some(-2.0), Some(NAN)],
+            None,
+            vec![2, 3, 0, 1, 4],
+        );
+        test_sort_to_indices_primitive_arrays::<BooleanType>(
+            vec![Some(true), None, Some(false), Some(true)],
+            Some(SortOptions {
+                descending: false,
+                nulls_first: false,
+                collation: Collation::Binary,
+            }),
+            vec![2, 0, 3, 1],
+        );
+    }
+
+    #[test]
+    fn test_sort_strings() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("hello"),
+            None,
+            Some("arrow"),
+            Some("sort"),
+        ]));
+        let sorted = sort(
+            &values,
+            Some(SortOptions {
+                descending: true,
+                nulls_first: true,
+                collation: Collation::Binary,
+            }),
+        )
+        .unwrap();
+        let sorted = sorted.as_any().downcast_ref::<StringArray>().unwrap();
+        assert!(sorted.is_null(0));
+        assert_eq!("sort", sorted.value(1));
+        assert_eq!("hello", sorted.value(2));
+        assert_eq!("arrow", sorted.value(3));
+    }
+
+    #[test]
+    fn test_lexsort_to_indices() {
+        let columns = vec![
+            SortColumn {
+                values: Arc::new(Int64Array::from(vec![2, 1, 2, 1, 2])),
+                options: None,
+            },
+            SortColumn {
+                values: Arc::new(StringArray::from(vec!["a", "b", "c", "d", "a"])),
+                options: Some(SortOptions {
+                    descending: true,
+                    nulls_first: true,
+                    collation: Collation::Binary,
+                }),
+            },
+        ];
+        let indices = lexsort_to_indices(&columns).unwrap();
+        let expected = UInt32Array::from(vec![3, 1, 2, 0, 4]);
+        assert!(indices.equals(&expected));
+    }
+
+    #[test]
+    fn test_lexsort_mismatched_lengths() {
+        let columns = vec![
+            SortColumn {
+                values: Arc::new(Int64Array::from(vec![2, 1])),
+                options: None,
+            },
+            SortColumn {
+                values: Arc::new(Int64Array::from(vec![1])),
+                options: None,
+            },
+        ];
+        assert!(lexsort_to_indices(&columns).is_err());
+        assert!(lexsort_to_indices(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sort_strings_case_insensitive() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["b", "A", "a", "B", "c"]));
+        let options = SortOptions {
+            collation: Collation::CaseInsensitive,
+            ..SortOptions::default()
+        };
+        let indices = sort_to_indices(&values, Some(options)).unwrap();
+        // the sort is stable, so values that only differ in case keep their order
+        let expected = UInt32Array::from(vec![1, 2, 0, 3, 4]);
+        assert!(indices.equals(&expected));
+    }
+
+    #[test]
+    fn test_build_compare_across_arrays() {
+        let left = Int32Array::from(vec![Some(1), None, Some(5)]);
+        let right = Int32Array::from(vec![Some(3), None]);
+        let options = SortOptions {
+            descending: true,
+            nulls_first: false,
+            collation: Collation::Binary,
+        };
+        let compare = build_compare(&left, &right, options).unwrap();
+        assert_eq!(compare(0, 0), Ordering::Greater);
+        assert_eq!(compare(2, 0), Ordering::Less);
+        assert_eq!(compare(0, 1), Ordering::Less);
+        assert_eq!(compare(1, 1), Ordering::Equal);
+
+        let strings = StringArray::from(vec!["a"]);
+        assert!(build_compare(&left, &strings, options).is_err());
+    }
+}
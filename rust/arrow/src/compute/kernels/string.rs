@@ -0,0 +1,149 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels that map each value of a `StringArray` to a new string, such as
+//! Unicode case mapping and normalization, and that concatenate the values of string
+//! arrays.
+//!
+//! The normalization kernels require the `unicode` feature.
+
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::array::*;
+use crate::error::{ArrowError, Result};
+
+/// Apply a function to each non-null value of a string array, keeping nulls
+fn map_string<F>(array: &StringArray, op: F) -> Result<StringArray>
+where
+    F: Fn(&str) -> String,
+{
+    let iter = (0..array.len()).map(|i| {
+        if array.is_null(i) {
+            None
+        } else {
+            Some(op(array.value(i)))
+        }
+    });
+    // a range reports its exact length
+    Ok(unsafe { StringArray::from_trusted_len_iter(iter) })
+}
+
+/// Converts each value of a string array to lowercase using the full Unicode case
+/// mapping, which can change the length of a value (e.g. `"İ"` becomes `"i̇"`) and
+/// takes the position of a character into account (e.g. for the Greek final sigma).
+pub fn lower(array: &StringArray) -> Result<StringArray> {
+    map_string(array, str::to_lowercase)
+}
+
+/// Converts each value of a string array to uppercase using the full Unicode case
+/// mapping, which can change the length of a value (e.g. `"ß"` becomes `"SS"`).
+pub fn upper(array: &StringArray) -> Result<StringArray> {
+    map_string(array, str::to_uppercase)
+}
+
+/// Converts each value of a string array to Unicode Normalization Form C, so that
+/// canonically equivalent values (e.g. a precomposed `"é"` and `"e"` followed by a
+/// combining acute accent) have the same representation.
+#[cfg(feature = "unicode")]
+pub fn nfc(array: &StringArray) -> Result<StringArray> {
+    map_string(array, |s| s.nfc().collect())
+}
+
+/// Converts each value of a string array to Unicode Normalization Form KC, which in
+/// addition to the canonical composition of NFC replaces compatibility characters
+/// such as ligatures and full-width forms with their plain equivalents.
+#[cfg(feature = "unicode")]
+pub fn nfkc(array: &StringArray) -> Result<StringArray> {
+    map_string(array, |s| s.nfkc().collect())
+}
+
+/// Concatenates the values at each index of two string arrays. If either value is null
+/// then the result is also null.
+pub fn concat_strings(left: &StringArray, right: &StringArray) -> Result<StringArray> {
+    if left.len() != right.len() {
+        return Err(ArrowError::ComputeError(
+            "Cannot concatenate string arrays of different length".to_string(),
+        ));
+    }
+    let iter = (0..left.len()).map(|i| {
+        if left.is_null(i) || right.is_null(i) {
+            None
+        } else {
+            let mut value =
+                String::with_capacity(left.value(i).len() + right.value(i).len());
+            value.push_str(left.value(i));
+            value.push_str(right.value(i));
+            Some(value)
+        }
+    });
+    // a range reports its exact length
+    Ok(unsafe { StringArray::from_trusted_len_iter(iter) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lower_upper() {
+        let a = StringArray::from(vec![Some("Straße"), None, Some("ΟΔΥΣΣΕΥΣ"), Some("")]);
+
+        let b = lower(&a).unwrap();
+        assert_eq!("straße", b.value(0));
+        assert!(b.is_null(1));
+        // a sigma at the end of a word is lowercased to the final form
+        assert_eq!("οδυσσευς", b.value(2));
+        assert_eq!("", b.value(3));
+
+        let b = upper(&a).unwrap();
+        assert_eq!("STRASSE", b.value(0));
+        assert!(b.is_null(1));
+        assert_eq!("ΟΔΥΣΣΕΥΣ", b.value(2));
+    }
+
+    #[test]
+    fn test_concat_strings() {
+        let a = StringArray::from(vec![Some("foo"), None, Some(""), Some("a")]);
+        let b = StringArray::from(vec![Some("bar"), Some("x"), Some("baz"), None]);
+        let c = concat_strings(&a, &b).unwrap();
+        assert_eq!("foobar", c.value(0));
+        assert!(c.is_null(1));
+        assert_eq!("baz", c.value(2));
+        assert!(c.is_null(3));
+
+        let b = StringArray::from(vec!["x"]);
+        assert!(concat_strings(&a, &b).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "unicode")]
+    fn test_normalization() {
+        let a = StringArray::from(vec![Some("e\u{301}"), None, Some("ﬁ１")]);
+
+        let b = nfc(&a).unwrap();
+        assert_eq!("\u{e9}", b.value(0));
+        assert!(b.is_null(1));
+        // compatibility characters are kept by NFC
+        assert_eq!("ﬁ１", b.value(2));
+
+        let b = nfkc(&a).unwrap();
+        assert_eq!("\u{e9}", b.value(0));
+        assert!(b.is_null(1));
+        assert_eq!("fi1", b.value(2));
+    }
+}
@@ -0,0 +1,179 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels that find the distinct values of an `ArrayRef`
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::array::*;
+use crate::compute::kernels::take::take;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+/// Find the distinct values of an array, in the order of their first occurrence.
+///
+/// Nulls count as a single distinct value. Floating point values are compared by their
+/// bit pattern, so that e.g. `0.0` and `-0.0` are distinct.
+pub fn unique(array: &ArrayRef) -> Result<ArrayRef> {
+    let (indices, _) = distinct_indices(array)?;
+    take(array, &UInt32Array::from(indices), None)
+}
+
+/// Count the occurrences of each distinct value of an array.
+///
+/// Returns a `StructArray` with a `values` field that holds the distinct values in the
+/// order returned by `unique`, and an `Int64` `counts` field.
+pub fn value_counts(array: &ArrayRef) -> Result<StructArray> {
+    let (indices, counts) = distinct_indices(array)?;
+    let values = take(array, &UInt32Array::from(indices), None)?;
+    let counts: ArrayRef = Arc::new(Int64Array::from(counts));
+    Ok(StructArray::from(vec![
+        (
+            Field::new("values", array.data_type().clone(), true),
+            values,
+        ),
+        (Field::new("counts", DataType::Int64, false), counts),
+    ]))
+}
+
+/// Find the index of the first occurrence of each distinct value of an array, and the
+/// number of its occurrences
+fn distinct_indices(array: &ArrayRef) -> Result<(Vec<u32>, Vec<i64>)> {
+    match array.data_type() {
+        DataType::Boolean => {
+            let values = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            Ok(count_distinct(array.as_ref(), |i| {
+                if values.value(i) {
+                    &[1][..]
+                } else {
+                    &[0][..]
+                }
+            }))
+        }
+        DataType::Utf8 => {
+            let values = array.as_any().downcast_ref::<StringArray>().unwrap();
+            Ok(count_distinct(array.as_ref(), |i| {
+                values.value(i).as_bytes()
+            }))
+        }
+        data_type => match data_type.primitive_byte_width() {
+            // values of fixed width types are compared by their bytes
+            Some(width) => {
+                let data = array.data_ref();
+                let values = &data.buffers()[0].data()[data.offset() * width..];
+                Ok(count_distinct(array.as_ref(), |i| {
+                    &values[i * width..(i + 1) * width]
+                }))
+            }
+            None => Err(ArrowError::ComputeError(format!(
+                "Finding distinct values is not supported for {:?}",
+                data_type
+            ))),
+        },
+    }
+}
+
+/// Count the distinct values of an array, given a function that gets the bytes of the
+/// value at an index
+fn count_distinct<'a, F>(array: &Array, value: F) -> (Vec<u32>, Vec<i64>)
+where
+    F: Fn(usize) -> &'a [u8],
+{
+    let mut indices = vec![];
+    let mut counts = vec![];
+    // the position of each distinct value in `indices`, where null is the null value
+    let mut positions: HashMap<Option<&[u8]>, usize> = HashMap::new();
+    for i in 0..array.len() {
+        let key = if array.is_null(i) {
+            None
+        } else {
+            Some(value(i))
+        };
+        match positions.entry(key) {
+            Entry::Occupied(entry) => counts[*entry.get()] += 1,
+            Entry::Vacant(entry) => {
+                entry.insert(indices.len());
+                indices.push(i as u32);
+                counts.push(1);
+            }
+        }
+    }
+    (indices, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_primitive() {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(3),
+            None,
+            Some(1),
+            Some(3),
+            None,
+        ]));
+        let result = unique(&array).unwrap();
+        let result = result.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.value(0), 3);
+        assert!(result.is_null(1));
+        assert_eq!(result.value(2), 1);
+    }
+
+    #[test]
+    fn test_unique_sliced() {
+        let array = Float64Array::from(vec![1.0, 2.0, 2.0, 3.0]).slice(1, 3);
+        let result = unique(&array).unwrap();
+        let result = result.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(result.value_slice(0, 2), &[2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let array: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("b"),
+            Some("a"),
+            None,
+            Some("b"),
+            Some("b"),
+        ]));
+        let result = value_counts(&array).unwrap();
+        assert_eq!(result.len(), 3);
+
+        let values = result.column(0);
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(values.value(0), "b");
+        assert_eq!(values.value(1), "a");
+        assert!(values.is_null(2));
+        let counts = result.column(1);
+        let counts = counts.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(counts.value_slice(0, 3), &[3, 1, 1]);
+    }
+
+    #[test]
+    fn test_value_counts_boolean() {
+        let array: ArrayRef = Arc::new(BooleanArray::from(vec![false, true, false]));
+        let result = value_counts(&array).unwrap();
+        let counts = result.column(1);
+        let counts = counts.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(counts.value_slice(0, 2), &[2, 1]);
+    }
+}
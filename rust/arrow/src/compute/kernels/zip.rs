@@ -0,0 +1,192 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines zip kernel for `ArrayRef`, which selects each element from one of two arrays
+//! based on a boolean mask
+
+use std::sync::Arc;
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+
+use TimeUnit::*;
+
+/// Zip two arrays by some boolean mask, an element-wise `if mask then truthy else falsy`.
+///
+/// Where the mask is `true` the element of `truthy` is taken, and where it is `false`
+/// or null the element of `falsy` is taken, so that a null condition falls through to
+/// the `else` branch as in SQL's `CASE WHEN`. Nulls in the selected array are
+/// preserved.
+pub fn zip(mask: &BooleanArray, truthy: &ArrayRef, falsy: &ArrayRef) -> Result<ArrayRef> {
+    if truthy.data_type() != falsy.data_type() {
+        return Err(ArrowError::InvalidArgumentError(
+            "arguments need to have the same data type".to_string(),
+        ));
+    }
+    if truthy.len() != falsy.len() || truthy.len() != mask.len() {
+        return Err(ArrowError::InvalidArgumentError(
+            "all arrays should have the same length".to_string(),
+        ));
+    }
+    match truthy.data_type() {
+        DataType::Boolean => zip_primitive::<BooleanType>(mask, truthy, falsy),
+        DataType::Int8 => zip_primitive::<Int8Type>(mask, truthy, falsy),
+        DataType::Int16 => zip_primitive::<Int16Type>(mask, truthy, falsy),
+        DataType::Int32 => zip_primitive::<Int32Type>(mask, truthy, falsy),
+        DataType::Int64 => zip_primitive::<Int64Type>(mask, truthy, falsy),
+        DataType::UInt8 => zip_primitive::<UInt8Type>(mask, truthy, falsy),
+        DataType::UInt16 => zip_primitive::<UInt16Type>(mask, truthy, falsy),
+        DataType::UInt32 => zip_primitive::<UInt32Type>(mask, truthy, falsy),
+        DataType::UInt64 => zip_primitive::<UInt64Type>(mask, truthy, falsy),
+        DataType::Float32 => zip_primitive::<Float32Type>(mask, truthy, falsy),
+        DataType::Float64 => zip_primitive::<Float64Type>(mask, truthy, falsy),
+        DataType::Date32(_) => zip_primitive::<Date32Type>(mask, truthy, falsy),
+        DataType::Date64(_) => zip_primitive::<Date64Type>(mask, truthy, falsy),
+        DataType::Time32(Second) => {
+            zip_primitive::<Time32SecondType>(mask, truthy, falsy)
+        }
+        DataType::Time32(Millisecond) => {
+            zip_primitive::<Time32MillisecondType>(mask, truthy, falsy)
+        }
+        DataType::Time64(Microsecond) => {
+            zip_primitive::<Time64MicrosecondType>(mask, truthy, falsy)
+        }
+        DataType::Time64(Nanosecond) => {
+            zip_primitive::<Time64NanosecondType>(mask, truthy, falsy)
+        }
+        DataType::Timestamp(Second, _) => {
+            zip_primitive::<TimestampSecondType>(mask, truthy, falsy)
+        }
+        DataType::Timestamp(Millisecond, _) => {
+            zip_primitive::<TimestampMillisecondType>(mask, truthy, falsy)
+        }
+        DataType::Timestamp(Microsecond, _) => {
+            zip_primitive::<TimestampMicrosecondType>(mask, truthy, falsy)
+        }
+        DataType::Timestamp(Nanosecond, _) => {
+            zip_primitive::<TimestampNanosecondType>(mask, truthy, falsy)
+        }
+        DataType::Utf8 => zip_string(mask, truthy, falsy),
+        t => Err(ArrowError::ComputeError(format!(
+            "Zip not supported for data type {:?}",
+            t
+        ))),
+    }
+}
+
+/// Whether the element at `i` is taken from the `truthy` array
+fn selects_truthy(mask: &BooleanArray, i: usize) -> bool {
+    mask.is_valid(i) && mask.value(i)
+}
+
+/// `zip` implementation for primitive arrays
+fn zip_primitive<T>(
+    mask: &BooleanArray,
+    truthy: &ArrayRef,
+    falsy: &ArrayRef,
+) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+{
+    let truthy = truthy.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    let falsy = falsy.as_any().downcast_ref::<PrimitiveArray<T>>().unwrap();
+    let mut builder = PrimitiveBuilder::<T>::new(mask.len());
+    for i in 0..mask.len() {
+        let a = if selects_truthy(mask, i) {
+            truthy
+        } else {
+            falsy
+        };
+        if a.is_valid(i) {
+            builder.append_value(a.value(i))?;
+        } else {
+            builder.append_null()?;
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+/// `zip` implementation for string arrays
+fn zip_string(
+    mask: &BooleanArray,
+    truthy: &ArrayRef,
+    falsy: &ArrayRef,
+) -> Result<ArrayRef> {
+    let truthy = truthy.as_any().downcast_ref::<StringArray>().unwrap();
+    let falsy = falsy.as_any().downcast_ref::<StringArray>().unwrap();
+    let mut builder = StringBuilder::new(mask.len());
+    for i in 0..mask.len() {
+        let a = if selects_truthy(mask, i) {
+            truthy
+        } else {
+            falsy
+        };
+        if a.is_valid(i) {
+            builder.append_value(a.value(i))?;
+        } else {
+            builder.append_null()?;
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_primitive_arrays() {
+        let mask = BooleanArray::from(vec![Some(true), Some(false), None, Some(true)]);
+        let truthy: ArrayRef =
+            Arc::new(Int32Array::from(vec![Some(1), Some(2), Some(3), None]));
+        let falsy: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(10),
+            Some(20),
+            Some(30),
+            Some(40),
+        ]));
+        let output = zip(&mask, &truthy, &falsy).unwrap();
+        let output = output.as_any().downcast_ref::<Int32Array>().unwrap();
+        let expected = Int32Array::from(vec![Some(1), Some(20), Some(30), None]);
+        assert!(output.equals(&expected));
+    }
+
+    #[test]
+    fn test_zip_string_arrays() {
+        let mask = BooleanArray::from(vec![false, true, false]);
+        let truthy: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "c"]));
+        let falsy: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("x"), Some("y"), None]));
+        let output = zip(&mask, &truthy, &falsy).unwrap();
+        let output = output.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!("x", output.value(0));
+        assert_eq!("b", output.value(1));
+        assert!(output.is_null(2));
+    }
+
+    #[test]
+    fn test_zip_invalid_arguments() {
+        let mask = BooleanArray::from(vec![true, false]);
+        let ints: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        let strings: ArrayRef = Arc::new(StringArray::from(vec!["a", "b"]));
+        assert!(zip(&mask, &ints, &strings).is_err());
+
+        let short: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        assert!(zip(&mask, &ints, &short).is_err());
+    }
+}
@@ -24,9 +24,17 @@ mod util;
 pub use self::kernels::aggregate::*;
 pub use self::kernels::arithmetic::*;
 pub use self::kernels::boolean::*;
+pub use self::kernels::bucket::*;
 pub use self::kernels::cast::*;
 pub use self::kernels::comparison::*;
+pub use self::kernels::concat::*;
+pub use self::kernels::dictionary::*;
 pub use self::kernels::filter::*;
 pub use self::kernels::limit::*;
+pub use self::kernels::regexp::*;
+pub use self::kernels::sort::*;
+pub use self::kernels::string::*;
 pub use self::kernels::take::*;
 pub use self::kernels::temporal::*;
+pub use self::kernels::unique::*;
+pub use self::kernels::zip::*;
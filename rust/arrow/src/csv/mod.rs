@@ -20,6 +20,7 @@
 pub mod reader;
 pub mod writer;
 
+pub use self::reader::BadRecordPolicy;
 pub use self::reader::Reader;
 pub use self::reader::ReaderBuilder;
 pub use self::writer::Writer;
@@ -41,19 +41,22 @@
 //! ```
 
 use lazy_static::lazy_static;
+use rayon::prelude::*;
 use regex::{Regex, RegexBuilder};
 use std::collections::HashSet;
 use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::str;
 use std::sync::Arc;
 
-use csv as csv_crate;
-
-use crate::array::{ArrayRef, PrimitiveBuilder, StringBuilder};
+use crate::array::{
+    ArrayRef, Decimal128Array, Decimal128Builder, PrimitiveBuilder, StringBuilder,
+};
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 use crate::record_batch::RecordBatch;
+use crate::util::interval;
 
-use self::csv_crate::{StringRecord, StringRecordsIntoIter};
+use csv_crate::ByteRecord;
 
 lazy_static! {
     static ref DECIMAL_RE: Regex = Regex::new(r"^-?(\d+\.\d+)$").unwrap();
@@ -93,7 +96,7 @@ fn infer_file_schema<R: Read + Seek>(
     max_read_records: Option<usize>,
     has_headers: bool,
 ) -> Result<Schema> {
-    let mut csv_reader = csv::ReaderBuilder::new()
+    let mut csv_reader = csv_crate::ReaderBuilder::new()
         .delimiter(delimiter)
         .from_reader(reader);
 
@@ -180,6 +183,25 @@ fn infer_file_schema<R: Read + Seek>(
     Ok(Schema::new(fields))
 }
 
+/// How a CSV reader handles records that cannot be read or whose values cannot be
+/// parsed as the data type of their column
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BadRecordPolicy {
+    /// Return an error with the line number of the record
+    Fail,
+    /// Skip the record
+    Skip,
+    /// Replace the values that cannot be parsed with nulls, or all values of a record
+    /// that cannot be read
+    NullFill,
+}
+
+impl Default for BadRecordPolicy {
+    fn default() -> Self {
+        BadRecordPolicy::Fail
+    }
+}
+
 /// CSV file reader
 pub struct Reader<R: Read> {
     /// Explicit schema for the CSV file
@@ -187,11 +209,20 @@ pub struct Reader<R: Read> {
     /// Optional projection for which columns to load (zero-based column indices)
     projection: Option<Vec<usize>>,
     /// File reader
-    record_iter: StringRecordsIntoIter<BufReader<R>>,
+    reader: csv_crate::Reader<BufReader<R>>,
+    /// Records of the current batch, whose allocations are reused for the records of
+    /// the following batches
+    records: Vec<ByteRecord>,
     /// Batch size (number of records to load each time)
     batch_size: usize,
     /// Current line number, used in error reporting
     line_number: usize,
+    /// How records that cannot be read or parsed are handled
+    bad_record_policy: BadRecordPolicy,
+    /// Number of records that were skipped or filled with nulls
+    bad_records: usize,
+    /// Whether the columns of a batch are converted into arrays in parallel
+    parallel: bool,
 }
 
 impl<R: Read> Reader<R> {
@@ -242,44 +273,77 @@ impl<R: Read> Reader<R> {
         batch_size: usize,
         projection: Option<Vec<usize>>,
     ) -> Self {
-        let csv_reader = csv::ReaderBuilder::new()
+        let csv_reader = csv_crate::ReaderBuilder::new()
             .has_headers(has_headers)
             .from_reader(buf_reader);
-        let record_iter = csv_reader.into_records();
         Self {
             schema,
             projection,
-            record_iter,
+            reader: csv_reader,
+            records: vec![],
             batch_size,
             line_number: if has_headers { 1 } else { 0 },
+            bad_record_policy: BadRecordPolicy::Fail,
+            bad_records: 0,
+            parallel: false,
         }
     }
 
-    /// Read the next batch of rows
-    pub fn next(&mut self) -> Result<Option<RecordBatch>> {
-        // read a batch of rows into memory
-        let mut rows: Vec<StringRecord> = Vec::with_capacity(self.batch_size);
-        for i in 0..self.batch_size {
-            match self.record_iter.next() {
-                Some(Ok(r)) => {
-                    rows.push(r);
-                }
-                Some(Err(e)) => {
-                    return Err(ArrowError::ParseError(format!(
-                        "Error parsing line {}: {:?}",
-                        self.line_number + i,
-                        e
-                    )));
+    /// Set how records that cannot be read or parsed are handled
+    pub fn with_bad_record_policy(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_record_policy = policy;
+        self
+    }
+
+    /// Set whether the columns of each batch are converted into arrays in parallel on
+    /// the threads of the rayon pool. The records are still read and split into fields
+    /// on the calling thread, so this mostly helps wide files with many columns to parse.
+    pub fn with_parallel_conversion(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Returns the number of records that were skipped or filled with nulls so far
+    pub fn bad_records(&self) -> usize {
+        self.bad_records
+    }
+
+    /// Read a batch of records into `records` and return their line numbers. The fields
+    /// of the records are only split here, and are decoded and parsed later if they
+    /// belong to a projected column.
+    fn read_records(&mut self) -> Result<Vec<usize>> {
+        let mut lines: Vec<usize> = Vec::with_capacity(self.batch_size);
+        while lines.len() < self.batch_size {
+            let line_number = self.line_number;
+            let n = lines.len();
+            if self.records.len() == n {
+                self.records.push(ByteRecord::new());
+            }
+            match self.reader.read_byte_record(&mut self.records[n]) {
+                Ok(true) => lines.push(line_number),
+                Ok(false) => break,
+                Err(e) => {
+                    if self.bad_record_policy == BadRecordPolicy::Fail || e.is_io_error()
+                    {
+                        return Err(ArrowError::ParseError(format!(
+                            "Error parsing line {}: {:?}",
+                            line_number, e
+                        )));
+                    }
+                    self.bad_records += 1;
+                    if self.bad_record_policy == BadRecordPolicy::NullFill {
+                        self.records[n].clear();
+                        lines.push(line_number);
+                    }
                 }
-                None => break,
             }
+            self.line_number += 1;
         }
+        Ok(lines)
+    }
 
-        // return early if no data was loaded
-        if rows.is_empty() {
-            return Ok(None);
-        }
-
+    /// Read the next batch of rows
+    pub fn next(&mut self) -> Result<Option<RecordBatch>> {
         let projection: Vec<usize> = match self.projection {
             Some(ref v) => v.clone(),
             None => self
@@ -291,54 +355,58 @@ impl<R: Read> Reader<R> {
                 .collect(),
         };
 
-        let rows = &rows[..];
-        let arrays: Result<Vec<ArrayRef>> = projection
-            .iter()
-            .map(|i| {
-                let field = self.schema.field(*i);
-                match field.data_type() {
-                    &DataType::Boolean => {
-                        self.build_primitive_array::<BooleanType>(rows, i)
-                    }
-                    &DataType::Int8 => self.build_primitive_array::<Int8Type>(rows, i),
-                    &DataType::Int16 => self.build_primitive_array::<Int16Type>(rows, i),
-                    &DataType::Int32 => self.build_primitive_array::<Int32Type>(rows, i),
-                    &DataType::Int64 => self.build_primitive_array::<Int64Type>(rows, i),
-                    &DataType::UInt8 => self.build_primitive_array::<UInt8Type>(rows, i),
-                    &DataType::UInt16 => {
-                        self.build_primitive_array::<UInt16Type>(rows, i)
-                    }
-                    &DataType::UInt32 => {
-                        self.build_primitive_array::<UInt32Type>(rows, i)
-                    }
-                    &DataType::UInt64 => {
-                        self.build_primitive_array::<UInt64Type>(rows, i)
-                    }
-                    &DataType::Float32 => {
-                        self.build_primitive_array::<Float32Type>(rows, i)
-                    }
-                    &DataType::Float64 => {
-                        self.build_primitive_array::<Float64Type>(rows, i)
-                    }
-                    &DataType::Utf8 => {
-                        let mut builder = StringBuilder::new(rows.len());
-                        for row_index in 0..rows.len() {
-                            match rows[row_index].get(*i) {
-                                Some(s) => builder.append_value(s).unwrap(),
-                                _ => builder.append(false).unwrap(),
-                            }
-                        }
-                        Ok(Arc::new(builder.finish()) as ArrayRef)
-                    }
-                    other => Err(ArrowError::ParseError(format!(
-                        "Unsupported data type {:?}",
-                        other
-                    ))),
-                }
-            })
-            .collect();
+        // the positions in `records` of the rows of the batch
+        let schema = self.schema.clone();
+        let (selected, lines) = loop {
+            let lines = self.read_records()?;
+            let complete = lines.len() == self.batch_size;
+            if self.bad_record_policy == BadRecordPolicy::Fail {
+                break ((0..lines.len()).collect::<Vec<_>>(), lines);
+            }
 
-        self.line_number += rows.len();
+            // find the records with projected values that cannot be parsed
+            let records = &self.records[..lines.len()];
+            let is_valid = |row: &ByteRecord| {
+                projection
+                    .iter()
+                    .all(|i| match row.get(*i).map(str::from_utf8) {
+                        Some(Ok(s)) => is_valid_value(schema.field(*i).data_type(), s),
+                        Some(Err(_)) => false,
+                        None => true,
+                    })
+            };
+            if self.bad_record_policy == BadRecordPolicy::NullFill {
+                self.bad_records += records.iter().filter(|row| !is_valid(row)).count();
+                break ((0..lines.len()).collect(), lines);
+            }
+            let selected: Vec<usize> = (0..lines.len())
+                .filter(|n| is_valid(&records[*n]))
+                .collect();
+            self.bad_records += lines.len() - selected.len();
+            // keep reading if all records of a batch were skipped
+            if !selected.is_empty() || !complete {
+                break (selected, lines);
+            }
+        };
+
+        // return early if no data was loaded
+        if selected.is_empty() {
+            return Ok(None);
+        }
+
+        let rows: Vec<&ByteRecord> = selected.iter().map(|n| &self.records[*n]).collect();
+        let lines: Vec<usize> = selected.iter().map(|n| lines[*n]).collect();
+        let rows = &rows[..];
+        let lines = &lines[..];
+        let policy = self.bad_record_policy;
+        let build = |i: &usize| build_array(&schema, policy, rows, *i, lines);
+        // the records are split on this thread, and only their conversion into arrays
+        // is spread over the threads of the rayon pool, one column per task
+        let arrays: Result<Vec<ArrayRef>> = if self.parallel && projection.len() > 1 {
+            projection.par_iter().map(build).collect()
+        } else {
+            projection.iter().map(build).collect()
+        };
 
         let schema_fields = self.schema.fields();
 
@@ -353,39 +421,227 @@ impl<R: Read> Reader<R> {
             RecordBatch::try_new(projected_schema, arr).map(|batch| Some(batch))
         })
     }
+}
 
-    fn build_primitive_array<T: ArrowPrimitiveType>(
-        &self,
-        rows: &[StringRecord],
-        col_idx: &usize,
-    ) -> Result<ArrayRef> {
-        let mut builder = PrimitiveBuilder::<T>::new(rows.len());
-        let is_boolean_type =
-            *self.schema.field(*col_idx).data_type() == DataType::Boolean;
-        for row_index in 0..rows.len() {
-            match rows[row_index].get(*col_idx) {
-                Some(s) if s.len() > 0 => {
-                    let t = if is_boolean_type {
-                        s.to_lowercase().parse::<T::Native>()
-                    } else {
-                        s.parse::<T::Native>()
-                    };
-                    match t {
-                        Ok(v) => builder.append_value(v)?,
-                        Err(_) => {
-                            // TODO: we should surface the underlying error here.
-                            return Err(ArrowError::ParseError(format!(
-                                "Error while parsing value {} at line {}",
-                                s,
-                                self.line_number + row_index
-                            )));
-                        }
+/// Convert the values of a column of the records into an array of the data type of the
+/// column
+fn build_array(
+    schema: &Schema,
+    policy: BadRecordPolicy,
+    rows: &[&ByteRecord],
+    i: usize,
+    lines: &[usize],
+) -> Result<ArrayRef> {
+    match schema.field(i).data_type() {
+        &DataType::Boolean => {
+            build_primitive_array::<BooleanType>(policy, rows, i, lines)
+        }
+        &DataType::Int8 => build_primitive_array::<Int8Type>(policy, rows, i, lines),
+        &DataType::Int16 => build_primitive_array::<Int16Type>(policy, rows, i, lines),
+        &DataType::Int32 => build_primitive_array::<Int32Type>(policy, rows, i, lines),
+        &DataType::Int64 => build_primitive_array::<Int64Type>(policy, rows, i, lines),
+        &DataType::UInt8 => build_primitive_array::<UInt8Type>(policy, rows, i, lines),
+        &DataType::UInt16 => build_primitive_array::<UInt16Type>(policy, rows, i, lines),
+        &DataType::UInt32 => build_primitive_array::<UInt32Type>(policy, rows, i, lines),
+        &DataType::UInt64 => build_primitive_array::<UInt64Type>(policy, rows, i, lines),
+        &DataType::Float32 => {
+            build_primitive_array::<Float32Type>(policy, rows, i, lines)
+        }
+        &DataType::Float64 => {
+            build_primitive_array::<Float64Type>(policy, rows, i, lines)
+        }
+        &DataType::Utf8 => build_string_array(policy, rows, i, lines),
+        &DataType::Interval(IntervalUnit::YearMonth) => {
+            build_parsed_array::<IntervalYearMonthType, _>(
+                policy,
+                rows,
+                i,
+                lines,
+                interval::parse_year_month,
+            )
+        }
+        &DataType::Interval(IntervalUnit::DayTime) => {
+            build_parsed_array::<IntervalDayTimeType, _>(
+                policy,
+                rows,
+                i,
+                lines,
+                interval::parse_day_time,
+            )
+        }
+        &DataType::Decimal(precision, scale) => {
+            build_decimal_array(policy, rows, i, lines, precision, scale)
+        }
+        other => Err(ArrowError::ParseError(format!(
+            "Unsupported data type {:?}",
+            other
+        ))),
+    }
+}
+
+/// Build a decimal array, where values with more digits after the decimal point than
+/// the scale are rounded and values that do not fit the precision are invalid
+fn build_decimal_array(
+    policy: BadRecordPolicy,
+    rows: &[&ByteRecord],
+    col_idx: usize,
+    lines: &[usize],
+    precision: usize,
+    scale: usize,
+) -> Result<ArrayRef> {
+    let mut builder = Decimal128Builder::new(rows.len(), precision, scale);
+    for row_index in 0..rows.len() {
+        match decode(policy, rows[row_index], col_idx, lines[row_index])? {
+            Some(s) if s.len() > 0 => {
+                match Decimal128Array::parse_value(s, precision, scale) {
+                    Some(v) => builder.append_value(v)?,
+                    None if policy == BadRecordPolicy::NullFill => {
+                        builder.append_null()?
+                    }
+                    None => {
+                        return Err(ArrowError::ParseError(format!(
+                            "Error while parsing value {} at line {}",
+                            s, lines[row_index]
+                        )));
+                    }
+                }
+            }
+            _ => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+fn build_string_array(
+    policy: BadRecordPolicy,
+    rows: &[&ByteRecord],
+    col_idx: usize,
+    lines: &[usize],
+) -> Result<ArrayRef> {
+    let mut builder = StringBuilder::new(rows.len());
+    for row_index in 0..rows.len() {
+        match decode(policy, rows[row_index], col_idx, lines[row_index])? {
+            Some(s) => builder.append_value(s)?,
+            None => builder.append(false)?,
+        }
+    }
+    Ok(Arc::new(builder.finish()) as ArrayRef)
+}
+
+fn build_primitive_array<T: ArrowPrimitiveType>(
+    policy: BadRecordPolicy,
+    rows: &[&ByteRecord],
+    col_idx: usize,
+    lines: &[usize],
+) -> Result<ArrayRef> {
+    let mut builder = PrimitiveBuilder::<T>::new(rows.len());
+    let is_boolean_type = T::get_data_type() == DataType::Boolean;
+    for row_index in 0..rows.len() {
+        match decode(policy, rows[row_index], col_idx, lines[row_index])? {
+            Some(s) if s.len() > 0 => {
+                let t = if is_boolean_type {
+                    s.to_lowercase().parse::<T::Native>()
+                } else {
+                    s.parse::<T::Native>()
+                };
+                match t {
+                    Ok(v) => builder.append_value(v)?,
+                    Err(_) if policy == BadRecordPolicy::NullFill => {
+                        builder.append_null()?
+                    }
+                    Err(_) => {
+                        // TODO: we should surface the underlying error here.
+                        return Err(ArrowError::ParseError(format!(
+                            "Error while parsing value {} at line {}",
+                            s, lines[row_index]
+                        )));
                     }
                 }
-                _ => builder.append_null()?,
             }
+            _ => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Build a primitive array from values that are parsed by a function, such as ISO 8601
+/// durations, which returns `None` for invalid values
+fn build_parsed_array<T, F>(
+    policy: BadRecordPolicy,
+    rows: &[&ByteRecord],
+    col_idx: usize,
+    lines: &[usize],
+    parse: F,
+) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+    F: Fn(&str) -> Option<T::Native>,
+{
+    let mut builder = PrimitiveBuilder::<T>::new(rows.len());
+    for row_index in 0..rows.len() {
+        match decode(policy, rows[row_index], col_idx, lines[row_index])? {
+            Some(s) if s.len() > 0 => match parse(s) {
+                Some(v) => builder.append_value(v)?,
+                None if policy == BadRecordPolicy::NullFill => builder.append_null()?,
+                None => {
+                    return Err(ArrowError::ParseError(format!(
+                        "Error while parsing value {} at line {}",
+                        s, lines[row_index]
+                    )));
+                }
+            },
+            _ => builder.append_null()?,
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Decode the value of a field as UTF-8, where missing values, and invalid values if
+/// they are filled with nulls, are `None`
+fn decode(
+    policy: BadRecordPolicy,
+    row: &ByteRecord,
+    col_idx: usize,
+    line_number: usize,
+) -> Result<Option<&str>> {
+    match row.get(col_idx).map(str::from_utf8) {
+        Some(Ok(s)) => Ok(Some(s)),
+        Some(Err(_)) if policy == BadRecordPolicy::NullFill => Ok(None),
+        Some(Err(e)) => Err(ArrowError::ParseError(format!(
+            "Invalid UTF-8 value at line {}: {}",
+            line_number, e
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Whether a value can be parsed as the given data type, where empty values are nulls
+fn is_valid_value(data_type: &DataType, s: &str) -> bool {
+    if s.is_empty() {
+        return true;
+    }
+    match data_type {
+        DataType::Boolean => s.to_lowercase().parse::<bool>().is_ok(),
+        DataType::Int8 => s.parse::<i8>().is_ok(),
+        DataType::Int16 => s.parse::<i16>().is_ok(),
+        DataType::Int32 => s.parse::<i32>().is_ok(),
+        DataType::Int64 => s.parse::<i64>().is_ok(),
+        DataType::UInt8 => s.parse::<u8>().is_ok(),
+        DataType::UInt16 => s.parse::<u16>().is_ok(),
+        DataType::UInt32 => s.parse::<u32>().is_ok(),
+        DataType::UInt64 => s.parse::<u64>().is_ok(),
+        DataType::Float32 => s.parse::<f32>().is_ok(),
+        DataType::Float64 => s.parse::<f64>().is_ok(),
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            interval::parse_year_month(s).is_some()
         }
-        Ok(Arc::new(builder.finish()))
+        DataType::Interval(IntervalUnit::DayTime) => {
+            interval::parse_day_time(s).is_some()
+        }
+        DataType::Decimal(precision, scale) => {
+            Decimal128Array::parse_value(s, *precision, *scale).is_some()
+        }
+        _ => true,
     }
 }
 
@@ -413,6 +669,10 @@ pub struct ReaderBuilder {
     batch_size: usize,
     /// Optional projection for which columns to load (zero-based column indices)
     projection: Option<Vec<usize>>,
+    /// How records that cannot be read or parsed are handled
+    bad_record_policy: BadRecordPolicy,
+    /// Whether the columns of a batch are converted into arrays in parallel
+    parallel: bool,
 }
 
 impl Default for ReaderBuilder {
@@ -424,6 +684,8 @@ impl Default for ReaderBuilder {
             max_records: None,
             batch_size: 1024,
             projection: None,
+            bad_record_policy: BadRecordPolicy::Fail,
+            parallel: false,
         }
     }
 }
@@ -494,6 +756,18 @@ impl ReaderBuilder {
         self
     }
 
+    /// Set how records that cannot be read or parsed are handled
+    pub fn with_bad_record_policy(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_record_policy = policy;
+        self
+    }
+
+    /// Set whether the columns of each batch are converted into arrays in parallel
+    pub fn with_parallel_conversion(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
     /// Create a new `Reader` from the `ReaderBuilder`
     pub fn build<R: Read + Seek>(self, reader: R) -> Result<Reader<R>> {
         // check if schema should be inferred
@@ -511,17 +785,20 @@ impl ReaderBuilder {
                 Arc::new(inferred_schema)
             }
         };
-        let csv_reader = csv::ReaderBuilder::new()
+        let csv_reader = csv_crate::ReaderBuilder::new()
             .delimiter(self.delimiter.unwrap_or(b','))
             .has_headers(self.has_headers)
             .from_reader(buf_reader);
-        let record_iter = csv_reader.into_records();
         Ok(Reader {
             schema,
             projection: self.projection.clone(),
-            record_iter,
+            reader: csv_reader,
+            records: vec![],
             batch_size: self.batch_size,
             line_number: if self.has_headers { 1 } else { 0 },
+            bad_record_policy: self.bad_record_policy,
+            bad_records: 0,
+            parallel: self.parallel,
         })
     }
 }
@@ -774,4 +1051,228 @@ mod tests {
             Ok(_) => panic!("should have failed"),
         }
     }
+
+    #[test]
+    fn test_bad_record_policy() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c_int", DataType::UInt64, false),
+            Field::new("c_float", DataType::Float32, true),
+            Field::new("c_string", DataType::Utf8, true),
+            Field::new("c_bool", DataType::Boolean, true),
+        ]));
+        let build = |policy| {
+            let file = File::open("test/data/various_types_invalid.csv").unwrap();
+            ReaderBuilder::new()
+                .with_schema(schema.clone())
+                .has_headers(true)
+                .with_delimiter(b'|')
+                .with_batch_size(2)
+                .with_bad_record_policy(policy)
+                .build(file)
+                .unwrap()
+        };
+
+        let mut csv = build(BadRecordPolicy::Skip);
+        let mut values = vec![];
+        while let Some(batch) = csv.next().unwrap() {
+            let c_int = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap();
+            values.extend((0..c_int.len()).map(|i| c_int.value(i)));
+        }
+        assert_eq!(values, vec![1, 2, 3, 5]);
+        assert_eq!(csv.bad_records(), 1);
+
+        let mut csv = build(BadRecordPolicy::NullFill);
+        csv.next().unwrap().unwrap();
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(csv.bad_records(), 1);
+        let c_float = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert!(c_float.is_null(0));
+        assert!(c_float.is_null(1));
+        let c_int = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(c_int.value(1), 4);
+    }
+
+    #[test]
+    fn test_parallel_conversion() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("city", DataType::Utf8, false),
+            Field::new("lat", DataType::Float64, false),
+            Field::new("lng", DataType::Float64, false),
+        ]));
+        let build = |parallel| {
+            let file = File::open("test/data/uk_cities.csv").unwrap();
+            ReaderBuilder::new()
+                .with_schema(schema.clone())
+                .with_batch_size(10)
+                .with_projection(vec![2, 0])
+                .with_parallel_conversion(parallel)
+                .build(file)
+                .unwrap()
+        };
+
+        let mut sequential = build(false);
+        let mut parallel = build(true);
+        let mut rows = 0;
+        while let Some(expected) = sequential.next().unwrap() {
+            let batch = parallel.next().unwrap().unwrap();
+            assert_eq!(batch.schema(), expected.schema());
+            for i in 0..batch.num_columns() {
+                assert!(batch.column(i).equals(expected.column(i).as_ref()));
+            }
+            rows += batch.num_rows();
+        }
+        assert!(parallel.next().unwrap().is_none());
+        assert_eq!(rows, 37);
+
+        // errors of the columns converted in parallel are returned
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c_int", DataType::UInt64, false),
+            Field::new("c_float", DataType::Float32, true),
+            Field::new("c_string", DataType::Utf8, true),
+            Field::new("c_bool", DataType::Boolean, true),
+        ]));
+        let file = File::open("test/data/various_types_invalid.csv").unwrap();
+        let mut csv = ReaderBuilder::new()
+            .with_schema(schema)
+            .has_headers(true)
+            .with_delimiter(b'|')
+            .with_parallel_conversion(true)
+            .build(file)
+            .unwrap();
+        assert!(csv.next().is_err());
+    }
+
+    #[test]
+    fn test_interval_values() {
+        let data = "P1Y2M,P3DT4H5M6.5S\n,PT1S\nP3M,x\n";
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ym", DataType::Interval(IntervalUnit::YearMonth), true),
+            Field::new("dt", DataType::Interval(IntervalUnit::DayTime), true),
+        ]));
+
+        let mut csv = Reader::new(Cursor::new(data), schema.clone(), false, 2, None);
+        let batch = csv.next().unwrap().unwrap();
+        let ym = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<IntervalYearMonthArray>()
+            .unwrap();
+        assert_eq!(ym.value(0), 14);
+        assert!(ym.is_null(1));
+        let dt = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<IntervalDayTimeArray>()
+            .unwrap();
+        assert_eq!(dt.value_as_days_millis(0), (3, 14_706_500));
+        assert_eq!(dt.value_as_days_millis(1), (0, 1_000));
+        // the duration of the last record is invalid
+        assert!(csv.next().is_err());
+
+        let mut csv = Reader::new(Cursor::new(data), schema, false, 1024, None)
+            .with_bad_record_policy(BadRecordPolicy::Skip);
+        assert_eq!(csv.next().unwrap().unwrap().num_rows(), 2);
+        assert_eq!(csv.bad_records(), 1);
+    }
+
+    #[test]
+    fn test_decimal_values() {
+        let data = "12.345,a\n,b\n-0.5,c\n1234,d\n";
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("price", DataType::Decimal(5, 2), true),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+
+        let mut csv = Reader::new(Cursor::new(data), schema.clone(), false, 3, None);
+        let batch = csv.next().unwrap().unwrap();
+        let price = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .unwrap();
+        assert_eq!(price.value(0), 1235);
+        assert!(price.is_null(1));
+        assert_eq!(price.value_as_string(2), "-0.50");
+        // the last value does not fit into 5 digits with 2 after the decimal point
+        assert!(csv.next().is_err());
+
+        let mut csv = Reader::new(Cursor::new(data), schema, false, 1024, None)
+            .with_bad_record_policy(BadRecordPolicy::NullFill);
+        let batch = csv.next().unwrap().unwrap();
+        assert!(batch.column(0).is_null(3));
+    }
+
+    #[test]
+    fn test_projection_skips_unprojected_values() {
+        // the second column is not valid UTF-8 and the third is not an integer
+        let data: &[u8] = b"1,\xff,x\n2,\xfe,y\n3,\xfd,z\n";
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+            Field::new("c", DataType::Int32, false),
+        ]));
+
+        let mut csv =
+            Reader::new(Cursor::new(data), schema.clone(), false, 2, Some(vec![0]));
+        let mut values = vec![];
+        while let Some(batch) = csv.next().unwrap() {
+            assert_eq!(batch.num_columns(), 1);
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            values.extend((0..a.len()).map(|i| a.value(i)));
+        }
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let mut csv = Reader::new(Cursor::new(data), schema, false, 2, Some(vec![1]));
+        match csv.next() {
+            Err(e) => {
+                assert!(format!("{:?}", e).contains("Invalid UTF-8 value at line 0"))
+            }
+            Ok(_) => panic!("should have failed"),
+        }
+    }
+
+    #[test]
+    fn test_bad_record_policy_unequal_lengths() {
+        let data = "1,a\n2\n3,c\n";
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let reader = |policy| {
+            Reader::new(Cursor::new(data), schema.clone(), false, 1024, None)
+                .with_bad_record_policy(policy)
+        };
+
+        match reader(BadRecordPolicy::Fail).next() {
+            Err(e) => assert!(format!("{:?}", e).contains("Error parsing line 1")),
+            Ok(_) => panic!("should have failed"),
+        }
+
+        let mut csv = reader(BadRecordPolicy::Skip);
+        assert_eq!(csv.next().unwrap().unwrap().num_rows(), 2);
+        assert_eq!(csv.bad_records(), 1);
+
+        let mut csv = reader(BadRecordPolicy::NullFill);
+        let batch = csv.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert!(batch.column(0).is_null(1));
+        assert!(batch.column(1).is_null(1));
+        assert_eq!(csv.bad_records(), 1);
+    }
 }
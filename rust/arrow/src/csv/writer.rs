@@ -65,8 +65,6 @@
 //! }
 //! ```
 
-use csv as csv_crate;
-
 use std::io::Write;
 
 use crate::array::*;
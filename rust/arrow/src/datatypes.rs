@@ -22,22 +22,28 @@
 //!  * [`Field`](crate::datatypes::Field) to describe one field withing a schema.
 //!  * [`DataType`](crate::datatypes::DataType) to describe the type of a field.
 
-use std::collections::HashMap;
-use std::fmt;
-use std::mem::size_of;
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::String, string::ToString, vec::Vec};
+use core::fmt;
+use core::mem::size_of;
 #[cfg(feature = "simd")]
-use std::ops::{Add, Div, Mul, Sub};
-use std::slice::from_raw_parts;
-use std::str::FromStr;
-use std::sync::Arc;
+use core::ops::{Add, Div, Mul, Rem, Sub};
+use core::slice::from_raw_parts;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 #[cfg(feature = "simd")]
 use packed_simd::*;
+#[cfg(feature = "std")]
 use serde_derive::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use serde_json::{
     json, Number, Value, Value::Number as VNumber, Value::String as VString,
 };
 
+#[cfg(feature = "std")]
 use crate::error::{ArrowError, Result};
 
 /// The set of datatypes that are supported by this implementation of Apache Arrow.
@@ -55,7 +61,8 @@ use crate::error::{ArrowError, Result};
 /// Nested types can themselves be nested within other arrays.
 /// For more information on these types please see
 /// [the physical memory layout of Apache Arrow](https://arrow.apache.org/docs/format/Columnar.html#physical-memory-layout).
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum DataType {
     /// A boolean datatype representing the values `true` and `false`.
     Boolean,
@@ -114,6 +121,9 @@ pub enum DataType {
     /// Opaque binary data of fixed size.
     /// Enum parameter specifies the number of bytes per value.
     FixedSizeBinary(i32),
+    /// A decimal number with the given precision (total number of digits) and scale
+    /// (number of digits after the decimal point), stored as a 128-bit integer.
+    Decimal(usize, usize),
     /// A variable-length string in Unicode with UTF-8 encoding.
     Utf8,
     /// A list of some logical data type with variable length.
@@ -127,7 +137,8 @@ pub enum DataType {
 
 /// Date is either a 32-bit or 64-bit type representing elapsed time since UNIX
 /// epoch (1970-01-01) in days or milliseconds.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum DateUnit {
     /// Days since the UNIX epoch.
     Day,
@@ -137,7 +148,8 @@ pub enum DateUnit {
 }
 
 /// An absolute length of time in seconds, milliseconds, microseconds or nanoseconds.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum TimeUnit {
     /// Time in seconds.
     Second,
@@ -150,7 +162,8 @@ pub enum TimeUnit {
 }
 
 /// YEAR_MONTH or DAY_TIME interval in SQL style.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum IntervalUnit {
     /// Indicates the number of elapsed whole months, stored as 4-byte integers.
     YearMonth,
@@ -162,7 +175,8 @@ pub enum IntervalUnit {
 /// Contains the meta-data for a single relative type.
 ///
 /// The `Schema` object is an ordered collection of `Field` objects.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Field {
     name: String,
     data_type: DataType,
@@ -174,6 +188,7 @@ pub struct Field {
 pub trait ArrowNativeType:
     fmt::Debug + Send + Sync + Copy + PartialOrd + FromStr + 'static
 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value>;
 
     /// Convert native type from usize.
@@ -205,12 +220,14 @@ pub trait ArrowPrimitiveType: 'static {
 }
 
 impl ArrowNativeType for bool {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(self.into())
     }
 }
 
 impl ArrowNativeType for i8 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -225,6 +242,7 @@ impl ArrowNativeType for i8 {
 }
 
 impl ArrowNativeType for i16 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -239,6 +257,7 @@ impl ArrowNativeType for i16 {
 }
 
 impl ArrowNativeType for i32 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -253,6 +272,7 @@ impl ArrowNativeType for i32 {
 }
 
 impl ArrowNativeType for i64 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -267,6 +287,7 @@ impl ArrowNativeType for i64 {
 }
 
 impl ArrowNativeType for u8 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -281,6 +302,7 @@ impl ArrowNativeType for u8 {
 }
 
 impl ArrowNativeType for u16 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -295,6 +317,7 @@ impl ArrowNativeType for u16 {
 }
 
 impl ArrowNativeType for u32 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -309,6 +332,7 @@ impl ArrowNativeType for u32 {
 }
 
 impl ArrowNativeType for u64 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Some(VNumber(Number::from(self)))
     }
@@ -323,6 +347,7 @@ impl ArrowNativeType for u64 {
 }
 
 impl ArrowNativeType for f32 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Number::from_f64(f64::round(self as f64 * 1000.0) / 1000.0)
             .map(|num| VNumber(num))
@@ -330,6 +355,7 @@ impl ArrowNativeType for f32 {
 }
 
 impl ArrowNativeType for f64 {
+    #[cfg(feature = "std")]
     fn into_json_value(self) -> Option<Value> {
         Number::from_f64(self).map(|num| VNumber(num))
     }
@@ -496,7 +522,8 @@ where
     Self::Simd: Add<Output = Self::Simd>
         + Sub<Output = Self::Simd>
         + Mul<Output = Self::Simd>
-        + Div<Output = Self::Simd>,
+        + Div<Output = Self::Simd>
+        + Rem<Output = Self::Simd>,
 {
     /// Defines the SIMD type that should be used for this numeric type
     type Simd;
@@ -745,6 +772,7 @@ impl<T: ArrowNativeType> ToByteSlice for T {
 
 impl DataType {
     /// Parse a data type from a JSON representation
+    #[cfg(feature = "std")]
     fn from(json: &Value) -> Result<DataType> {
         match *json {
             Value::Object(ref map) => match map.get("name") {
@@ -761,6 +789,19 @@ impl DataType {
                         )))
                     }
                 }
+                Some(s) if s == "decimal" => {
+                    match (map.get("precision"), map.get("scale")) {
+                        (Some(Value::Number(precision)), Some(Value::Number(scale))) => {
+                            Ok(DataType::Decimal(
+                                precision.as_u64().unwrap() as usize,
+                                scale.as_u64().unwrap() as usize,
+                            ))
+                        }
+                        _ => Err(ArrowError::ParseError(
+                            "Expecting a precision and scale for decimal".to_string(),
+                        )),
+                    }
+                }
                 Some(s) if s == "floatingpoint" => match map.get("precision") {
                     Some(p) if p == "HALF" => Ok(DataType::Float16),
                     Some(p) if p == "SINGLE" => Ok(DataType::Float32),
@@ -908,6 +949,7 @@ impl DataType {
     }
 
     /// Generate a JSON representation of the data type
+    #[cfg(feature = "std")]
     pub fn to_json(&self) -> Value {
         match self {
             DataType::Boolean => json!({"name": "bool"}),
@@ -927,6 +969,9 @@ impl DataType {
             DataType::FixedSizeBinary(byte_width) => {
                 json!({"name": "fixedsizebinary", "byteWidth": byte_width})
             }
+            DataType::Decimal(precision, scale) => {
+                json!({"name": "decimal", "precision": precision, "scale": scale})
+            }
             DataType::Struct(_) => json!({"name": "struct"}),
             DataType::List(_) => json!({ "name": "list"}),
             DataType::FixedSizeList(_, length) => {
@@ -983,6 +1028,29 @@ impl DataType {
             DataType::Dictionary(_, _) => json!({ "name": "dictionary"}),
         }
     }
+
+    /// The width in bytes of the values of a primitive type other than `Boolean`
+    pub(crate) fn primitive_byte_width(&self) -> Option<usize> {
+        match self {
+            DataType::Int8 | DataType::UInt8 => Some(1),
+            DataType::Int16 | DataType::UInt16 => Some(2),
+            DataType::Int32
+            | DataType::UInt32
+            | DataType::Float32
+            | DataType::Date32(_)
+            | DataType::Time32(_)
+            | DataType::Interval(IntervalUnit::YearMonth) => Some(4),
+            DataType::Int64
+            | DataType::UInt64
+            | DataType::Float64
+            | DataType::Date64(_)
+            | DataType::Time64(_)
+            | DataType::Timestamp(_, _)
+            | DataType::Duration(_)
+            | DataType::Interval(IntervalUnit::DayTime) => Some(8),
+            _ => None,
+        }
+    }
 }
 
 impl Field {
@@ -1030,6 +1098,7 @@ impl Field {
     }
 
     /// Parse a `Field` definition from a JSON representation
+    #[cfg(feature = "std")]
     pub fn from(json: &Value) -> Result<Self> {
         match *json {
             Value::Object(ref map) => {
@@ -1163,6 +1232,7 @@ impl Field {
     }
 
     /// Generate a JSON representation of the `Field`
+    #[cfg(feature = "std")]
     pub fn to_json(&self) -> Value {
         let children: Vec<Value> = match self.data_type() {
             DataType::Struct(fields) => fields.iter().map(|f| f.to_json()).collect(),
@@ -1213,6 +1283,7 @@ impl fmt::Display for Field {
 ///
 /// Note that this information is only part of the meta-data and not part of the physical
 /// memory layout.
+#[cfg(feature = "std")]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Schema {
     pub(crate) fields: Vec<Field>,
@@ -1221,6 +1292,7 @@ pub struct Schema {
     pub(crate) metadata: HashMap<String, String>,
 }
 
+#[cfg(feature = "std")]
 impl Schema {
     /// Creates an empty `Schema`
     pub fn empty() -> Self {
@@ -1368,6 +1440,7 @@ impl Schema {
     }
 }
 
+#[cfg(feature = "std")]
 impl fmt::Display for Schema {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(
@@ -1382,6 +1455,7 @@ impl fmt::Display for Schema {
 }
 
 /// A reference-counted reference to a [`Schema`](crate::datatypes::Schema).
+#[cfg(feature = "std")]
 pub type SchemaRef = Arc<Schema>;
 
 #[cfg(test)]
@@ -1565,6 +1639,15 @@ mod tests {
         assert_eq!(DataType::Int32, dt);
     }
 
+    #[test]
+    fn decimal_json_round_trip() {
+        let json = "{\"name\": \"decimal\", \"precision\": 9, \"scale\": 2}";
+        let value: Value = serde_json::from_str(json).unwrap();
+        let dt = DataType::from(&value).unwrap();
+        assert_eq!(DataType::Decimal(9, 2), dt);
+        assert_eq!(value, dt.to_json());
+    }
+
     #[test]
     fn schema_json() {
         // Add some custom metadata
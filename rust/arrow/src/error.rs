@@ -16,10 +16,11 @@
 // under the License.
 
 //! Defines `ArrowError` for representing failures in various Arrow operations
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt::{Display, Formatter};
-
-use csv as csv_crate;
 
 /// Many different operations in the `arrow` crate return this error type
 #[derive(Debug, Clone, PartialEq)]
@@ -36,12 +37,14 @@ pub enum ArrowError {
     DictionaryKeyOverflowError,
 }
 
-impl From<::std::io::Error> for ArrowError {
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ArrowError {
     fn from(error: std::io::Error) -> Self {
         ArrowError::IoError(error.description().to_string())
     }
 }
 
+#[cfg(feature = "csv")]
 impl From<csv_crate::Error> for ArrowError {
     fn from(error: csv_crate::Error) -> Self {
         match error.kind() {
@@ -66,14 +69,15 @@ impl From<csv_crate::Error> for ArrowError {
     }
 }
 
-impl From<::std::string::FromUtf8Error> for ArrowError {
+#[cfg(feature = "std")]
+impl From<std::string::FromUtf8Error> for ArrowError {
     fn from(error: std::string::FromUtf8Error) -> Self {
         ArrowError::ParseError(error.description().to_string())
     }
 }
 
 impl Display for ArrowError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             &ArrowError::MemoryError(ref desc) => write!(f, "Memory error: {}", desc),
             &ArrowError::ParseError(ref desc) => write!(f, "Parser error: {}", desc),
@@ -95,6 +99,7 @@ impl Display for ArrowError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for ArrowError {}
 
-pub type Result<T> = std::result::Result<T, ArrowError>;
+pub type Result<T> = core::result::Result<T, ArrowError>;
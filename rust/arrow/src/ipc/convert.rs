@@ -213,6 +213,10 @@ pub(crate) fn get_data_type(field: ipc::Field, may_be_dictionary: bool) -> DataT
             let fsb = field.type_as_fixed_size_binary().unwrap();
             DataType::FixedSizeBinary(fsb.byteWidth())
         }
+        ipc::Type::Decimal => {
+            let decimal = field.type_as_decimal().unwrap();
+            DataType::Decimal(decimal.precision() as usize, decimal.scale() as usize)
+        }
         ipc::Type::FloatingPoint => {
             let float = field.type_as_floating_point().unwrap();
             match float.precision() {
@@ -411,6 +415,17 @@ pub(crate) fn get_fb_field_type<'a: 'b, 'b>(
                 Some(children),
             )
         }
+        Decimal(precision, scale) => {
+            let children = fbb.create_vector(&empty_fields[..]);
+            let mut builder = ipc::DecimalBuilder::new(&mut fbb);
+            builder.add_precision(*precision as i32);
+            builder.add_scale(*scale as i32);
+            (
+                ipc::Type::Decimal,
+                builder.finish().as_union_value(),
+                Some(children),
+            )
+        }
         Date32(_) => {
             let children = fbb.create_vector(&empty_fields[..]);
             let mut builder = ipc::DateBuilder::new(&mut fbb);
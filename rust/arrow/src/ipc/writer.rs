@@ -21,15 +21,17 @@
 //! however the `FileWriter` expects a reader that supports `Seek`ing
 
 use std::io::{BufWriter, Write};
+use std::sync::Arc;
 
 use flatbuffers::FlatBufferBuilder;
 
-use crate::array::ArrayDataRef;
+use crate::array::{ArrayData, ArrayDataRef};
 use crate::buffer::{Buffer, MutableBuffer};
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 use crate::ipc;
 use crate::record_batch::RecordBatch;
+use crate::util::bit_util;
 
 pub struct FileWriter<W: Write> {
     /// The object to write to
@@ -278,7 +280,7 @@ pub(crate) fn record_batch_to_bytes(batch: &RecordBatch) -> (Vec<u8>, Vec<u8>) {
     let mut arrow_data: Vec<u8> = vec![];
     let mut offset = 0;
     for array in batch.columns() {
-        let array_data = array.data();
+        let array_data = unslice_array_data(&array.data());
         offset = write_array_data(
             &array_data,
             &mut buffers,
@@ -334,6 +336,117 @@ fn write_record_batch<R: Write>(
     Ok((meta_written, arrow_data_written))
 }
 
+/// Re-encode array data that is a slice of other array data, so that its buffers and
+/// those of its children start at its first value. IPC messages do not have offsets,
+/// so the buffers of array data are written from their first byte.
+fn unslice_array_data(array_data: &ArrayDataRef) -> ArrayDataRef {
+    if is_sliced(array_data) {
+        copy_array_data(array_data, array_data.offset(), array_data.len())
+    } else {
+        array_data.clone()
+    }
+}
+
+/// Whether array data or any of its children has a non-zero offset
+fn is_sliced(array_data: &ArrayData) -> bool {
+    array_data.offset() != 0 || array_data.child_data().iter().any(|c| is_sliced(c))
+}
+
+/// Copy `len` values of array data, starting at the value at `offset` of its buffers,
+/// into new array data with an offset of zero
+fn copy_array_data(array_data: &ArrayData, offset: usize, len: usize) -> ArrayDataRef {
+    let data_type = array_data.data_type();
+    let buffers = array_data.buffers();
+    let child_data = array_data.child_data();
+    let mut builder = ArrayData::builder(data_type.clone()).len(len);
+    if let Some(buffer) = array_data.null_buffer() {
+        builder = builder.null_bit_buffer(copy_bits(buffer, offset, len));
+    }
+    let builder = match data_type {
+        DataType::Boolean => builder.add_buffer(copy_bits(&buffers[0], offset, len)),
+        DataType::Utf8 | DataType::Binary => {
+            let (offsets, start, end) = copy_offsets(&buffers[0], offset, len);
+            builder
+                .add_buffer(offsets)
+                .add_buffer(Buffer::from(&buffers[1].data()[start..end]))
+        }
+        DataType::List(_) => {
+            let (offsets, start, end) = copy_offsets(&buffers[0], offset, len);
+            let child = &child_data[0];
+            builder.add_buffer(offsets).add_child_data(copy_array_data(
+                child,
+                child.offset() + start,
+                end - start,
+            ))
+        }
+        DataType::FixedSizeList(_, size) => {
+            let size = *size as usize;
+            let child = &child_data[0];
+            builder.add_child_data(copy_array_data(
+                child,
+                child.offset() + offset * size,
+                len * size,
+            ))
+        }
+        DataType::FixedSizeBinary(width) => {
+            builder.add_buffer(copy_values(&buffers[0], *width as usize, offset, len))
+        }
+        DataType::Struct(_) => builder.child_data(
+            child_data
+                .iter()
+                .map(|child| copy_array_data(child, child.offset() + offset, len))
+                .collect(),
+        ),
+        // the keys are copied, while the values are shared by all slices
+        DataType::Dictionary(key_type, _) => match key_type.primitive_byte_width() {
+            Some(width) => builder
+                .add_buffer(copy_values(&buffers[0], width, offset, len))
+                .child_data(child_data.to_vec()),
+            None => return Arc::new(array_data.clone()),
+        },
+        _ => match data_type.primitive_byte_width() {
+            Some(width) => {
+                builder.add_buffer(copy_values(&buffers[0], width, offset, len))
+            }
+            // the buffers of other types are written as they are
+            None => return Arc::new(array_data.clone()),
+        },
+    };
+    builder.build()
+}
+
+/// Copy `len` bits of a bitmap, starting at bit `offset`
+fn copy_bits(buffer: &Buffer, offset: usize, len: usize) -> Buffer {
+    let data = buffer.data();
+    let num_bytes = bit_util::ceil(len, 8);
+    let mut bits = MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+    let bits_data = bits.data_mut();
+    (0..len)
+        .filter(|i| bit_util::get_bit(data, offset + i))
+        .for_each(|i| bit_util::set_bit(bits_data, i));
+    bits.freeze()
+}
+
+/// Copy `len` values of the given width in bytes, starting at value `offset`
+fn copy_values(buffer: &Buffer, width: usize, offset: usize, len: usize) -> Buffer {
+    Buffer::from(&buffer.data()[offset * width..(offset + len) * width])
+}
+
+/// Copy the `len + 1` offsets of `len` variable-length values, starting at value
+/// `offset`, so that the first offset is zero. Returns the offsets along with the range
+/// of the values they pointed to.
+fn copy_offsets(buffer: &Buffer, offset: usize, len: usize) -> (Buffer, usize, usize) {
+    let offsets = unsafe { buffer.typed_data::<i32>() };
+    let offsets = &offsets[offset..=offset + len];
+    let start = offsets[0];
+    let rebased: Vec<i32> = offsets.iter().map(|o| o - start).collect();
+    (
+        Buffer::from(rebased.to_byte_slice()),
+        start as usize,
+        offsets[len] as usize,
+    )
+}
+
 /// Write array data to a vector of bytes
 fn write_array_data(
     array_data: &ArrayDataRef,
@@ -414,6 +527,7 @@ mod tests {
     use crate::datatypes::Field;
     use crate::ipc::reader::*;
     use crate::util::integration_util::*;
+    use std::convert::TryFrom;
     use std::env;
     use std::fs::File;
     use std::io::Read;
@@ -467,6 +581,105 @@ mod tests {
         // panic!("intentional failure");
     }
 
+    #[test]
+    fn test_write_sliced_batch() {
+        let schema = Schema::new(vec![
+            Field::new("ints", DataType::Int32, true),
+            Field::new("bools", DataType::Boolean, true),
+            Field::new("strings", DataType::Utf8, true),
+            Field::new("lists", DataType::List(Box::new(DataType::Int32)), true),
+        ]);
+        let ints = Int32Array::from(vec![Some(1), None, Some(3), Some(4), None, Some(6)]);
+        let bools = BooleanArray::from(vec![
+            Some(true),
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+            None,
+        ]);
+        let strings = StringArray::try_from(vec![
+            Some("a"),
+            Some("bb"),
+            None,
+            Some("ccc"),
+            Some(""),
+            Some("dddd"),
+        ])
+        .unwrap();
+        // the list at index i holds the values 0..i, except for a null at index 2
+        let mut lists = ListBuilder::new(Int32Builder::new(16));
+        for i in 0..6 {
+            for j in 0..i {
+                lists.values().append_value(j).unwrap();
+            }
+            lists.append(i != 2).unwrap();
+        }
+        let lists = lists.finish();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(ints),
+            Arc::new(bools),
+            Arc::new(strings),
+            Arc::new(lists),
+        ];
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            columns.iter().map(|column| column.slice(2, 3)).collect(),
+        )
+        .unwrap();
+
+        let mut data = vec![];
+        {
+            let mut writer = StreamWriter::try_new(&mut data, &schema).unwrap();
+            writer.write(&batch).unwrap();
+            writer.finish().unwrap();
+        }
+        let mut reader = StreamReader::try_new(std::io::Cursor::new(data)).unwrap();
+        let read_batch = reader.next().unwrap().unwrap();
+        assert_eq!(3, read_batch.num_rows());
+
+        let ints = read_batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(1, ints.null_count());
+        assert_eq!((3, 4), (ints.value(0), ints.value(1)));
+        assert!(ints.is_null(2));
+
+        let bools = read_batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert_eq!(1, bools.null_count());
+        assert!(bools.is_null(0));
+        assert_eq!((true, false), (bools.value(1), bools.value(2)));
+
+        let strings = read_batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(1, strings.null_count());
+        assert!(strings.is_null(0));
+        assert_eq!(("ccc", ""), (strings.value(1), strings.value(2)));
+
+        let lists = read_batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap();
+        assert_eq!(1, lists.null_count());
+        assert!(lists.is_null(0));
+        for (i, expected) in vec![(1, vec![0, 1, 2]), (2, vec![0, 1, 2, 3])] {
+            let values = lists.value(i);
+            let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+            let values: Vec<i32> = (0..values.len()).map(|j| values.value(j)).collect();
+            assert_eq!(expected, values);
+        }
+    }
+
     #[test]
     fn read_and_rewrite_generated_files() {
         let testdata = env::var("ARROW_TEST_DATA").expect("ARROW_TEST_DATA not defined");
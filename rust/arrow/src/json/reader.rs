@@ -54,6 +54,7 @@ use crate::array::*;
 use crate::datatypes::*;
 use crate::error::{ArrowError, Result};
 use crate::record_batch::RecordBatch;
+use crate::util::interval;
 
 /// Coerce data type during inference
 ///
@@ -414,6 +415,18 @@ impl<R: Read> Reader<R> {
                         self.build_primitive_array::<UInt16Type>(rows, field.name())
                     }
                     DataType::UInt8 => self.build_primitive_array::<UInt8Type>(rows, field.name()),
+                    DataType::Interval(IntervalUnit::YearMonth) => self
+                        .build_interval_array::<IntervalYearMonthType, _>(
+                            rows,
+                            field.name(),
+                            interval::parse_year_month,
+                        ),
+                    DataType::Interval(IntervalUnit::DayTime) => self
+                        .build_interval_array::<IntervalDayTimeType, _>(
+                            rows,
+                            field.name(),
+                            interval::parse_day_time,
+                        ),
                     DataType::Utf8 => {
                         let mut builder = StringBuilder::new(rows.len());
                         for row_index in 0..rows.len() {
@@ -593,6 +606,34 @@ impl<R: Read> Reader<R> {
         Ok(Arc::new(builder.finish()))
     }
 
+    /// Build an interval array from ISO 8601 durations, or from numbers that are the
+    /// native values of the intervals. Other values are nulls.
+    fn build_interval_array<T, F>(
+        &self,
+        rows: &[Value],
+        col_name: &str,
+        parse: F,
+    ) -> Result<ArrayRef>
+    where
+        T: ArrowNumericType,
+        T::Native: num::NumCast,
+        F: Fn(&str) -> Option<T::Native>,
+    {
+        let mut builder = PrimitiveBuilder::<T>::new(rows.len());
+        for row in rows {
+            let value = match row.get(col_name) {
+                Some(Value::String(s)) => parse(s),
+                Some(value) => value.as_i64().and_then(num::cast::cast),
+                None => None,
+            };
+            match value {
+                Some(v) => builder.append_value(v)?,
+                None => builder.append_null()?,
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+
     fn build_list_array<T: ArrowPrimitiveType>(
         &self,
         rows: &[Value],
@@ -1024,6 +1065,44 @@ mod tests {
         let _batch = reader.next().unwrap().unwrap();
     }
 
+    #[test]
+    fn test_interval_values() {
+        let data = "{\"ym\": \"P1Y2M\", \"dt\": \"P1DT0.5S\"}\n\
+                    {\"ym\": 3, \"dt\": \"P1Y\"}\n\
+                    {\"dt\": null}\n";
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("ym", DataType::Interval(IntervalUnit::YearMonth), true),
+            Field::new("dt", DataType::Interval(IntervalUnit::DayTime), true),
+        ]));
+        let mut reader = Reader::new(
+            BufReader::new(std::io::Cursor::new(data.as_bytes())),
+            schema,
+            1024,
+            None,
+        );
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+
+        let ym = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<IntervalYearMonthArray>()
+            .unwrap();
+        assert_eq!(ym.value(0), 14);
+        assert_eq!(ym.value(1), 3);
+        assert!(ym.is_null(2));
+
+        let dt = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<IntervalDayTimeArray>()
+            .unwrap();
+        assert_eq!(dt.value_as_days_millis(0), (1, 500));
+        // a year has no fixed number of days
+        assert!(dt.is_null(1));
+        assert!(dt.is_null(2));
+    }
+
     #[test]
     fn test_coersion_scalar_and_list() {
         use crate::datatypes::DataType::*;
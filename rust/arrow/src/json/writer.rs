@@ -0,0 +1,381 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Convert record batches into JSON objects with a key for each column, such as to
+//! return the rows of a query from a web service.
+//!
+//! Null values are converted to `null`, as are floating point values that JSON
+//! cannot represent, such as NaN. Dates, timestamps and intervals are converted to
+//! ISO 8601 strings, lists to arrays and structs to nested objects.
+//!
+//! Example:
+//!
+//! ```
+//! use std::sync::Arc;
+//! use arrow::array::Int32Array;
+//! use arrow::datatypes::{DataType, Field, Schema};
+//! use arrow::json::writer::record_batches_to_json_rows;
+//! use arrow::record_batch::RecordBatch;
+//!
+//! let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+//! let a = Int32Array::from(vec![Some(1), None]);
+//! let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a)]).unwrap();
+//!
+//! let rows = record_batches_to_json_rows(&[batch]).unwrap();
+//! assert_eq!(serde_json::Value::Object(rows[0].clone()), serde_json::json!({"a": 1}));
+//! assert_eq!(serde_json::Value::Object(rows[1].clone()), serde_json::json!({"a": null}));
+//! ```
+
+use serde_json::map::Map as JsonMap;
+use serde_json::{Number, Value};
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::record_batch::RecordBatch;
+
+/// Format of the timestamps, which keeps the fraction of seconds if there is one
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Convert the rows of record batches into JSON objects, in order, with a key for each
+/// column in the order of the columns of the schema
+pub fn record_batches_to_json_rows(
+    batches: &[RecordBatch],
+) -> Result<Vec<JsonMap<String, Value>>> {
+    let mut rows = vec![];
+    for batch in batches {
+        let mut batch_rows = vec![JsonMap::new(); batch.num_rows()];
+        for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+            let values = array_to_json_values(column)?;
+            for (row, value) in batch_rows.iter_mut().zip(values) {
+                row.insert(field.name().clone(), value);
+            }
+        }
+        rows.extend(batch_rows);
+    }
+    Ok(rows)
+}
+
+/// Convert each value of an array into a JSON value, mapping the values that are not
+/// null with a conversion function
+macro_rules! convert_values {
+    ($array:expr, $array_ty:ty, $convert:expr) => {{
+        let array = $array.as_any().downcast_ref::<$array_ty>().unwrap();
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    Value::Null
+                } else {
+                    $convert(array, i)
+                }
+            })
+            .collect()
+    }};
+}
+
+/// Convert each value of a primitive array into a JSON number
+macro_rules! number_values {
+    ($array:expr, $array_ty:ty) => {
+        convert_values!($array, $array_ty, |array: &$array_ty, i| Value::Number(
+            array.value(i).into()
+        ))
+    };
+}
+
+/// Convert each value of a floating point array into a JSON number, or null if JSON
+/// cannot represent it
+macro_rules! float_values {
+    ($array:expr, $array_ty:ty) => {
+        convert_values!($array, $array_ty, |array: &$array_ty, i| {
+            Number::from_f64(array.value(i) as f64).map_or(Value::Null, Value::Number)
+        })
+    };
+}
+
+/// Convert each value of a date array into a JSON string
+macro_rules! date_values {
+    ($array:expr, $array_ty:ty) => {
+        convert_values!($array, $array_ty, |array: &$array_ty, i| {
+            array
+                .value_as_date(i)
+                .map_or(Value::Null, |date| Value::String(date.to_string()))
+        })
+    };
+}
+
+/// Convert each value of a timestamp array into a JSON string
+macro_rules! timestamp_values {
+    ($array:expr, $array_ty:ty) => {
+        convert_values!($array, $array_ty, |array: &$array_ty, i| {
+            array.value_as_datetime(i).map_or(Value::Null, |datetime| {
+                Value::String(datetime.format(TIMESTAMP_FORMAT).to_string())
+            })
+        })
+    };
+}
+
+/// Convert each value of an array into a JSON value
+fn array_to_json_values(array: &ArrayRef) -> Result<Vec<Value>> {
+    let values = match array.data_type() {
+        DataType::Boolean => {
+            convert_values!(array, BooleanArray, |array: &BooleanArray, i| {
+                Value::Bool(array.value(i))
+            })
+        }
+        DataType::Int8 => number_values!(array, Int8Array),
+        DataType::Int16 => number_values!(array, Int16Array),
+        DataType::Int32 => number_values!(array, Int32Array),
+        DataType::Int64 => number_values!(array, Int64Array),
+        DataType::UInt8 => number_values!(array, UInt8Array),
+        DataType::UInt16 => number_values!(array, UInt16Array),
+        DataType::UInt32 => number_values!(array, UInt32Array),
+        DataType::UInt64 => number_values!(array, UInt64Array),
+        DataType::Float32 => float_values!(array, Float32Array),
+        DataType::Float64 => float_values!(array, Float64Array),
+        DataType::Utf8 => {
+            convert_values!(array, StringArray, |array: &StringArray, i| {
+                Value::String(array.value(i).to_string())
+            })
+        }
+        DataType::Date32(_) => date_values!(array, Date32Array),
+        DataType::Date64(_) => date_values!(array, Date64Array),
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            timestamp_values!(array, TimestampSecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            timestamp_values!(array, TimestampMillisecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            timestamp_values!(array, TimestampMicrosecondArray)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            timestamp_values!(array, TimestampNanosecondArray)
+        }
+        DataType::Interval(IntervalUnit::YearMonth) => convert_values!(
+            array,
+            IntervalYearMonthArray,
+            |array: &IntervalYearMonthArray, i| {
+                Value::String(array.value_as_iso8601(i))
+            }
+        ),
+        DataType::Interval(IntervalUnit::DayTime) => convert_values!(
+            array,
+            IntervalDayTimeArray,
+            |array: &IntervalDayTimeArray, i| Value::String(array.value_as_iso8601(i))
+        ),
+        DataType::List(_) => {
+            let array = array.as_any().downcast_ref::<ListArray>().unwrap();
+            let mut values = Vec::with_capacity(array.len());
+            for i in 0..array.len() {
+                values.push(if array.is_null(i) {
+                    Value::Null
+                } else {
+                    Value::Array(array_to_json_values(&array.value(i))?)
+                });
+            }
+            values
+        }
+        DataType::Struct(fields) => {
+            let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+            let mut objects = vec![JsonMap::new(); array.len()];
+            for (field, column) in fields.iter().zip(array.columns()) {
+                let values = array_to_json_values(column)?;
+                for (object, value) in objects.iter_mut().zip(values) {
+                    object.insert(field.name().clone(), value);
+                }
+            }
+            objects
+                .into_iter()
+                .enumerate()
+                .map(|(i, object)| {
+                    if array.is_null(i) {
+                        Value::Null
+                    } else {
+                        Value::Object(object)
+                    }
+                })
+                .collect()
+        }
+        other => {
+            return Err(ArrowError::JsonError(format!(
+                "Cannot convert values of type {:?} to JSON",
+                other
+            )))
+        }
+    };
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    #[test]
+    fn write_rows() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, true),
+            Field::new("active", DataType::Boolean, false),
+            Field::new("day", DataType::Date32(DateUnit::Day), false),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+                Arc::new(Float64Array::from(vec![Some(1.5), Some(std::f64::NAN)])),
+                Arc::new(BooleanArray::from(vec![true, false])),
+                Arc::new(Date32Array::from(vec![0, 18262])),
+                Arc::new(TimestampMillisecondArray::from_vec(
+                    vec![0, 1_577_836_800_500],
+                    None,
+                )),
+            ],
+        )?;
+        let other = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![3])),
+                Arc::new(StringArray::from(vec![Some("c")])),
+                Arc::new(Float64Array::from(vec![None])),
+                Arc::new(BooleanArray::from(vec![true])),
+                Arc::new(Date32Array::from(vec![1])),
+                Arc::new(TimestampMillisecondArray::from_vec(vec![1000], None)),
+            ],
+        )?;
+
+        let rows = record_batches_to_json_rows(&[batch, other])?;
+        let rows: Vec<Value> = rows.into_iter().map(Value::Object).collect();
+        assert_eq!(
+            rows,
+            vec![
+                json!({
+                    "id": 1,
+                    "name": "a",
+                    "score": 1.5,
+                    "active": true,
+                    "day": "1970-01-01",
+                    "ts": "1970-01-01T00:00:00",
+                }),
+                json!({
+                    "id": 2,
+                    "name": null,
+                    "score": null,
+                    "active": false,
+                    "day": "2020-01-01",
+                    "ts": "2020-01-01T00:00:00.500",
+                }),
+                json!({
+                    "id": 3,
+                    "name": "c",
+                    "score": null,
+                    "active": true,
+                    "day": "1970-01-02",
+                    "ts": "1970-01-01T00:00:01",
+                }),
+            ]
+        );
+
+        // the keys are in the order of the columns
+        let keys: Vec<&String> = rows[0].as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["id", "name", "score", "active", "day", "ts"]);
+        Ok(())
+    }
+
+    #[test]
+    fn write_nested_rows() -> Result<()> {
+        let mut builder = ListBuilder::new(Int32Builder::new(4));
+        builder.values().append_value(1)?;
+        builder.values().append_value(2)?;
+        builder.append(true)?;
+        builder.append(false)?;
+        let list = builder.finish();
+
+        let point = StructArray::from(vec![
+            (
+                Field::new("x", DataType::Int32, false),
+                Arc::new(Int32Array::from(vec![1, 3])) as ArrayRef,
+            ),
+            (
+                Field::new("y", DataType::Utf8, true),
+                Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef,
+            ),
+        ]);
+
+        let schema = Schema::new(vec![
+            Field::new("list", list.data_type().clone(), true),
+            Field::new("point", point.data_type().clone(), false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(list), Arc::new(point)],
+        )?;
+
+        let rows = record_batches_to_json_rows(&[batch])?;
+        assert_eq!(
+            Value::Object(rows[0].clone()),
+            json!({"list": [1, 2], "point": {"x": 1, "y": "a"}})
+        );
+        assert_eq!(
+            Value::Object(rows[1].clone()),
+            json!({"list": null, "point": {"x": 3, "y": null}})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write_intervals() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("ym", DataType::Interval(IntervalUnit::YearMonth), false),
+            Field::new("dt", DataType::Interval(IntervalUnit::DayTime), true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(IntervalYearMonthArray::from(vec![14])),
+                Arc::new(IntervalDayTimeArray::from(vec![Some(2 << 32)])),
+            ],
+        )?;
+        let rows = record_batches_to_json_rows(&[batch])?;
+        assert_eq!(
+            Value::Object(rows[0].clone()),
+            json!({"ym": "P1Y2M", "dt": "P2D"})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn write_unsupported_type() {
+        let schema = Schema::new(vec![Field::new("b", DataType::Binary, false)]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(BinaryArray::from(vec![&b"a"[..]]))],
+        )
+        .unwrap();
+        assert!(record_batches_to_json_rows(&[batch]).is_err());
+    }
+}
@@ -20,24 +20,38 @@
 //!
 //! Currently the project is developed and tested against nightly Rust. To learn more
 //! about the status of Arrow in Rust, see `README.md`.
+//!
+//! Without the default `std` feature only the buffers, bitmaps, data types and
+//! [`ArrayData`](crate::array::ArrayData) are built, which only need `alloc`.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![feature(specialization)]
 #![allow(dead_code)]
 #![allow(non_camel_case_types)]
 #![allow(bare_trait_objects)]
 
+extern crate alloc;
+
 pub mod array;
 pub mod bitmap;
 pub mod buffer;
+#[cfg(feature = "std")]
 pub mod compute;
+#[cfg(feature = "csv")]
 pub mod csv;
 pub mod datatypes;
 pub mod error;
 #[cfg(feature = "flight")]
 pub mod flight;
+#[cfg(feature = "std")]
 pub mod ipc;
+#[cfg(feature = "std")]
 pub mod json;
 pub mod memory;
+#[cfg(feature = "std")]
 pub mod record_batch;
+#[cfg(feature = "std")]
+pub mod row;
+#[cfg(feature = "std")]
 pub mod tensor;
 pub mod util;
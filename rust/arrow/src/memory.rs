@@ -18,27 +18,27 @@
 //! Defines memory-related functions, such as allocate/deallocate/reallocate memory
 //! regions.
 
-use std::alloc::Layout;
-use std::mem::align_of;
+use alloc::alloc::Layout;
+use core::mem::align_of;
 
 pub const ALIGNMENT: usize = 64;
 
 pub fn allocate_aligned(size: usize) -> *mut u8 {
     unsafe {
         let layout = Layout::from_size_align_unchecked(size, ALIGNMENT);
-        std::alloc::alloc_zeroed(layout)
+        alloc::alloc::alloc_zeroed(layout)
     }
 }
 
 pub fn free_aligned(p: *mut u8, size: usize) {
     unsafe {
-        std::alloc::dealloc(p, Layout::from_size_align_unchecked(size, ALIGNMENT));
+        alloc::alloc::dealloc(p, Layout::from_size_align_unchecked(size, ALIGNMENT));
     }
 }
 
 pub fn reallocate(ptr: *mut u8, old_size: usize, new_size: usize) -> *mut u8 {
     unsafe {
-        let new_ptr = std::alloc::realloc(
+        let new_ptr = alloc::alloc::realloc(
             ptr,
             Layout::from_size_align_unchecked(old_size, ALIGNMENT),
             new_size,
@@ -51,7 +51,7 @@ pub fn reallocate(ptr: *mut u8, old_size: usize, new_size: usize) -> *mut u8 {
 }
 
 pub unsafe fn memcpy(dst: *mut u8, src: *const u8, len: usize) {
-    std::ptr::copy_nonoverlapping(src, dst, len)
+    core::ptr::copy_nonoverlapping(src, dst, len)
 }
 
 extern "C" {
@@ -18,6 +18,8 @@
 //! A two-dimensional batch of column-oriented data with a defined
 //! [schema](crate::datatypes::Schema).
 
+use std::collections::HashSet;
+use std::mem;
 use std::sync::Arc;
 
 use crate::array::*;
@@ -181,6 +183,49 @@ impl RecordBatch {
     pub fn columns(&self) -> &[ArrayRef] {
         &self.columns[..]
     }
+
+    /// Returns the total number of bytes of memory occupied by the columns of the
+    /// record batch.
+    ///
+    /// Buffers that are shared between columns, e.g. when the same array is used for
+    /// several columns or columns are slices of the same array, are counted only once.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use arrow::array::{Array, ArrayRef, Int32Array};
+    /// use arrow::datatypes::{Schema, Field, DataType};
+    /// use arrow::record_batch::RecordBatch;
+    ///
+    /// # fn main() -> arrow::error::Result<()> {
+    /// let id_array: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5]));
+    /// let schema = Schema::new(vec![
+    ///     Field::new("id", DataType::Int32, false),
+    ///     Field::new("id_copy", DataType::Int32, false),
+    /// ]);
+    ///
+    /// let batch =
+    ///     RecordBatch::try_new(Arc::new(schema), vec![id_array.clone(), id_array.clone()])?;
+    ///
+    /// assert!(batch.get_array_memory_size() < 2 * id_array.get_array_memory_size());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_array_memory_size(&self) -> usize {
+        let mut allocations = HashSet::new();
+        let mut size = 0;
+        for column in &self.columns {
+            let data = column.data_ref();
+            data.for_each_buffer(&mut |buffer| {
+                if allocations.insert(buffer.allocation_ptr()) {
+                    size += buffer.capacity();
+                }
+            });
+            size += data.get_struct_memory_size() + mem::size_of_val(column.as_ref());
+        }
+        size
+    }
 }
 
 impl From<&StructArray> for RecordBatch {
@@ -265,6 +310,46 @@ mod tests {
         assert_eq!(5, record_batch.column(1).data().len());
     }
 
+    #[test]
+    fn record_batch_memory_size() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(3)]));
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![Some(4), Some(5), None]));
+        // value buffer and null bitmap of each column
+        let buffers = 2 * a.data().buffers()[0].capacity()
+            + 2 * a.data().null_buffer().unwrap().capacity();
+        assert_eq!(
+            buffers,
+            a.get_buffer_memory_size() + b.get_buffer_memory_size()
+        );
+
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![a.clone(), b.clone()]).unwrap();
+        assert_eq!(
+            a.get_array_memory_size() + b.get_array_memory_size(),
+            batch.get_array_memory_size()
+        );
+
+        // the buffers of a column that is used twice are counted once
+        let shared =
+            RecordBatch::try_new(schema.clone(), vec![a.clone(), a.clone()]).unwrap();
+        assert_eq!(
+            2 * a.get_array_memory_size() - a.get_buffer_memory_size(),
+            shared.get_array_memory_size()
+        );
+
+        // as are the buffers of slices of the same array
+        let sliced =
+            RecordBatch::try_new(schema, vec![a.slice(0, 2), a.slice(1, 2)]).unwrap();
+        assert_eq!(
+            shared.get_array_memory_size(),
+            sliced.get_array_memory_size()
+        );
+    }
+
     #[test]
     fn create_record_batch_schema_mismatch() {
         let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -0,0 +1,336 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversion between record batches and rows of Rust structs.
+//!
+//! A struct whose fields implement [`ArrowField`](crate::row::ArrowField) can
+//! implement [`ArrowSerialize`](crate::row::ArrowSerialize) and
+//! [`ArrowDeserialize`](crate::row::ArrowDeserialize) to convert a slice of structs
+//! into a record batch with one column per field, and the rows of record batches back
+//! into structs. With the `derive` feature, both traits can be derived, and the derive
+//! macros are exported along with the traits:
+//!
+//! ```ignore
+//! use arrow::row::{ArrowDeserialize, ArrowSerialize};
+//!
+//! #[derive(ArrowSerialize, ArrowDeserialize)]
+//! struct Person {
+//!     name: String,
+//!     age: Option<u32>,
+//! }
+//!
+//! let batch = Person::to_record_batch(&people)?;
+//! let people = Person::from_record_batch(&batch)?;
+//! ```
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use crate::array::*;
+use crate::datatypes::*;
+use crate::error::{ArrowError, Result};
+use crate::record_batch::RecordBatch;
+
+#[cfg(feature = "derive")]
+pub use arrow_derive::{ArrowDeserialize, ArrowSerialize};
+
+/// A Rust type that is stored in a column of a record batch
+pub trait ArrowField: Sized {
+    /// The data type of the column
+    fn data_type() -> DataType;
+
+    /// Whether the column can contain null values
+    fn is_nullable() -> bool {
+        false
+    }
+
+    /// Create a column from values, where `None` is a null value
+    fn build_array(values: Vec<Option<&Self>>) -> Result<ArrayRef>;
+
+    /// Read the value at position `i` of a column, or `None` if it is null
+    fn read_value(array: &ArrayRef, i: usize) -> Result<Option<Self>>;
+}
+
+/// A Rust struct whose rows can be converted into a record batch
+pub trait ArrowSerialize: Sized {
+    /// The schema of the record batches, with a column for each field of the struct
+    fn schema() -> Schema;
+
+    /// Create a record batch with a row for each struct
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch>;
+}
+
+/// A Rust struct that can be created from the rows of a record batch
+pub trait ArrowDeserialize: Sized {
+    /// Create a struct for each row of a record batch. The columns are found by the
+    /// names of the fields of the struct, and other columns are ignored.
+    fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Self>>;
+
+    /// Create a struct for each row of several record batches, in order
+    fn from_record_batches(batches: &[RecordBatch]) -> Result<Vec<Self>> {
+        let mut rows = vec![];
+        for batch in batches {
+            rows.extend(Self::from_record_batch(batch)?);
+        }
+        Ok(rows)
+    }
+}
+
+/// Get the column of a record batch that a field of a struct is read from
+pub fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef> {
+    match batch.schema().index_of(name) {
+        Ok(i) => Ok(batch.column(i)),
+        Err(_) => Err(ArrowError::InvalidArgumentError(format!(
+            "Record batch has no column named '{}'",
+            name
+        ))),
+    }
+}
+
+/// Read the value of a field of a struct from position `i` of a column, which must not
+/// be null unless the field is an `Option`
+pub fn read_field<T: ArrowField>(array: &ArrayRef, i: usize, name: &str) -> Result<T> {
+    match T::read_value(array, i)? {
+        Some(value) => Ok(value),
+        None => Err(ArrowError::InvalidArgumentError(format!(
+            "Column '{}' has a null value in row {}, which requires an Option field",
+            name, i
+        ))),
+    }
+}
+
+/// Downcast a column to the array type of a field
+fn downcast<T: 'static>(array: &ArrayRef, expected: DataType) -> Result<&T> {
+    array.as_any().downcast_ref::<T>().ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!(
+            "Expected a column of type {:?} but found {:?}",
+            expected,
+            array.data_type()
+        ))
+    })
+}
+
+macro_rules! primitive_field {
+    ($native_ty:ty, $arrow_ty:ty, $data_ty:expr) => {
+        impl ArrowField for $native_ty {
+            fn data_type() -> DataType {
+                $data_ty
+            }
+
+            fn build_array(values: Vec<Option<&Self>>) -> Result<ArrayRef> {
+                let values: Vec<Option<$native_ty>> =
+                    values.into_iter().map(|v| v.cloned()).collect();
+                Ok(Arc::new(PrimitiveArray::<$arrow_ty>::from(values)))
+            }
+
+            fn read_value(array: &ArrayRef, i: usize) -> Result<Option<Self>> {
+                let array = downcast::<PrimitiveArray<$arrow_ty>>(array, $data_ty)?;
+                Ok(if array.is_null(i) {
+                    None
+                } else {
+                    Some(array.value(i))
+                })
+            }
+        }
+    };
+}
+
+primitive_field!(i8, Int8Type, DataType::Int8);
+primitive_field!(i16, Int16Type, DataType::Int16);
+primitive_field!(i32, Int32Type, DataType::Int32);
+primitive_field!(i64, Int64Type, DataType::Int64);
+primitive_field!(u8, UInt8Type, DataType::UInt8);
+primitive_field!(u16, UInt16Type, DataType::UInt16);
+primitive_field!(u32, UInt32Type, DataType::UInt32);
+primitive_field!(u64, UInt64Type, DataType::UInt64);
+primitive_field!(f32, Float32Type, DataType::Float32);
+primitive_field!(f64, Float64Type, DataType::Float64);
+
+impl ArrowField for bool {
+    fn data_type() -> DataType {
+        DataType::Boolean
+    }
+
+    fn build_array(values: Vec<Option<&Self>>) -> Result<ArrayRef> {
+        let values: Vec<Option<bool>> = values.into_iter().map(|v| v.cloned()).collect();
+        Ok(Arc::new(BooleanArray::from(values)))
+    }
+
+    fn read_value(array: &ArrayRef, i: usize) -> Result<Option<Self>> {
+        let array = downcast::<BooleanArray>(array, DataType::Boolean)?;
+        Ok(if array.is_null(i) {
+            None
+        } else {
+            Some(array.value(i))
+        })
+    }
+}
+
+impl ArrowField for String {
+    fn data_type() -> DataType {
+        DataType::Utf8
+    }
+
+    fn build_array(values: Vec<Option<&Self>>) -> Result<ArrayRef> {
+        let values: Vec<Option<&str>> =
+            values.into_iter().map(|v| v.map(|s| s.as_str())).collect();
+        Ok(Arc::new(StringArray::try_from(values)?))
+    }
+
+    fn read_value(array: &ArrayRef, i: usize) -> Result<Option<Self>> {
+        let array = downcast::<StringArray>(array, DataType::Utf8)?;
+        Ok(if array.is_null(i) {
+            None
+        } else {
+            Some(array.value(i).to_string())
+        })
+    }
+}
+
+/// An optional field is stored in a nullable column, where `None` is a null value
+impl<T: ArrowField> ArrowField for Option<T> {
+    fn data_type() -> DataType {
+        T::data_type()
+    }
+
+    fn is_nullable() -> bool {
+        true
+    }
+
+    fn build_array(values: Vec<Option<&Self>>) -> Result<ArrayRef> {
+        T::build_array(
+            values
+                .into_iter()
+                .map(|v| v.and_then(|v| v.as_ref()))
+                .collect(),
+        )
+    }
+
+    fn read_value(array: &ArrayRef, i: usize) -> Result<Option<Self>> {
+        Ok(Some(T::read_value(array, i)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A struct implementing the traits the way the derive macros do
+    #[derive(Debug, PartialEq)]
+    struct Person {
+        name: String,
+        age: Option<u32>,
+        score: f64,
+    }
+
+    impl ArrowSerialize for Person {
+        fn schema() -> Schema {
+            Schema::new(vec![
+                Field::new("name", String::data_type(), String::is_nullable()),
+                Field::new(
+                    "age",
+                    Option::<u32>::data_type(),
+                    Option::<u32>::is_nullable(),
+                ),
+                Field::new("score", f64::data_type(), f64::is_nullable()),
+            ])
+        }
+
+        fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+            RecordBatch::try_new(
+                Arc::new(Self::schema()),
+                vec![
+                    String::build_array(rows.iter().map(|r| Some(&r.name)).collect())?,
+                    Option::<u32>::build_array(
+                        rows.iter().map(|r| Some(&r.age)).collect(),
+                    )?,
+                    f64::build_array(rows.iter().map(|r| Some(&r.score)).collect())?,
+                ],
+            )
+        }
+    }
+
+    impl ArrowDeserialize for Person {
+        fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
+            let name = column(batch, "name")?;
+            let age = column(batch, "age")?;
+            let score = column(batch, "score")?;
+            (0..batch.num_rows())
+                .map(|i| {
+                    Ok(Person {
+                        name: read_field(name, i, "name")?,
+                        age: read_field(age, i, "age")?,
+                        score: read_field(score, i, "score")?,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let people = vec![
+            Person {
+                name: "a".to_string(),
+                age: Some(30),
+                score: 1.5,
+            },
+            Person {
+                name: "b".to_string(),
+                age: None,
+                score: 2.0,
+            },
+        ];
+        let batch = Person::to_record_batch(&people)?;
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.schema().field(1).is_nullable());
+        assert!(!batch.schema().field(0).is_nullable());
+        assert_eq!(batch.column(1).null_count(), 1);
+
+        assert_eq!(Person::from_record_batch(&batch)?, people);
+        let rows = Person::from_record_batches(&[batch.clone(), batch])?;
+        assert_eq!(rows.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn read_invalid_columns() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("age", DataType::Int64, true),
+            Field::new("score", DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::try_from(vec![Some("a"), None])?),
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(Float64Array::from(vec![1.0, 2.0])),
+            ],
+        )?;
+        // the age column has the wrong type
+        assert!(Person::from_record_batch(&batch).is_err());
+
+        let name = column(&batch, "name")?;
+        assert_eq!(read_field::<String>(name, 0, "name")?, "a");
+        assert_eq!(read_field::<Option<String>>(name, 1, "name")?, None);
+        // a null value requires an optional field
+        assert!(read_field::<String>(name, 1, "name").is_err());
+        assert!(column(&batch, "missing").is_err());
+        Ok(())
+    }
+}
@@ -223,6 +223,10 @@ impl ArrowJsonBatch {
                             arr.as_any().downcast_ref::<FixedSizeBinaryArray>().unwrap();
                         arr.equals_json(&json_array.iter().collect::<Vec<&Value>>()[..])
                     }
+                    DataType::Decimal(_, _) => {
+                        let arr = arr.as_any().downcast_ref::<Decimal128Array>().unwrap();
+                        arr.equals_json(&json_array.iter().collect::<Vec<&Value>>()[..])
+                    }
                     DataType::List(_) => {
                         let arr = arr.as_any().downcast_ref::<ListArray>().unwrap();
                         arr.equals_json(&json_array.iter().collect::<Vec<&Value>>()[..])
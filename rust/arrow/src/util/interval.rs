@@ -0,0 +1,294 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversion of interval values from and to ISO 8601 durations, such as `P1Y2M` or
+//! `P3DT4H5M6.5S`.
+//!
+//! A `YearMonth` interval is a number of months, and a `DayTime` interval is a number
+//! of days and a number of milliseconds, which are stored in the upper and lower 32 bits
+//! of an `i64` respectively. A duration can only be converted to one of them if it has
+//! no components of the other, as a month does not have a fixed number of days.
+//!
+//! Example:
+//!
+//! ```
+//! use arrow::util::interval::*;
+//!
+//! assert_eq!(parse_year_month("P1Y2M"), Some(14));
+//! let value = parse_day_time("P3DT1.5S").unwrap();
+//! assert_eq!(day_time_parts(value), (3, 1500));
+//! assert_eq!(format_day_time(value), "P3DT1.5S");
+//! ```
+
+use std::convert::TryFrom;
+
+const MILLISECONDS_IN_SECOND: i64 = 1_000;
+const MILLISECONDS_IN_MINUTE: i64 = 60 * MILLISECONDS_IN_SECOND;
+const MILLISECONDS_IN_HOUR: i64 = 60 * MILLISECONDS_IN_MINUTE;
+
+/// The components of a parsed duration
+#[derive(Debug, Default)]
+struct Duration {
+    months: i64,
+    days: i64,
+    milliseconds: i64,
+}
+
+/// Parse an ISO 8601 duration with an optional leading `-`, where the seconds may have
+/// a fraction
+fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (negative, s) = if s.starts_with('-') {
+        (true, &s[1..])
+    } else {
+        (false, s)
+    };
+    if !s.starts_with('P') && !s.starts_with('p') {
+        return None;
+    }
+
+    let mut duration = Duration::default();
+    let mut time = false;
+    let mut components = 0;
+    let mut number = String::new();
+    for c in s[1..].chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'T' | 't' if !time && number.is_empty() => time = true,
+            _ => {
+                if number.is_empty() {
+                    return None;
+                }
+                match (time, c.to_ascii_uppercase()) {
+                    (true, 'S') => {
+                        let seconds = number.parse::<f64>().ok()?;
+                        let milliseconds = (seconds * 1000.0).round();
+                        if !milliseconds.is_finite() || milliseconds >= 2f64.powi(62) {
+                            return None;
+                        }
+                        duration.milliseconds =
+                            duration.milliseconds.checked_add(milliseconds as i64)?;
+                    }
+                    (time, designator) => {
+                        let n = number.parse::<i64>().ok()?;
+                        let (value, factor) = match (time, designator) {
+                            (false, 'Y') => (&mut duration.months, 12),
+                            (false, 'M') => (&mut duration.months, 1),
+                            (false, 'W') => (&mut duration.days, 7),
+                            (false, 'D') => (&mut duration.days, 1),
+                            (true, 'H') => {
+                                (&mut duration.milliseconds, MILLISECONDS_IN_HOUR)
+                            }
+                            (true, 'M') => {
+                                (&mut duration.milliseconds, MILLISECONDS_IN_MINUTE)
+                            }
+                            _ => return None,
+                        };
+                        *value = value.checked_add(n.checked_mul(factor)?)?;
+                    }
+                }
+                number.clear();
+                components += 1;
+            }
+        }
+    }
+    if !number.is_empty() || components == 0 {
+        return None;
+    }
+
+    if negative {
+        duration.months = -duration.months;
+        duration.days = -duration.days;
+        duration.milliseconds = -duration.milliseconds;
+    }
+    Some(duration)
+}
+
+/// Parse an ISO 8601 duration of years and months, such as `P1Y2M`, into a number of
+/// months. Returns `None` if the duration is invalid, has days or a time, or overflows.
+pub fn parse_year_month(s: &str) -> Option<i32> {
+    let duration = parse_duration(s)?;
+    if duration.days != 0 || duration.milliseconds != 0 {
+        return None;
+    }
+    i32::try_from(duration.months).ok()
+}
+
+/// Parse an ISO 8601 duration of weeks, days and time, such as `P3DT4H5M6.5S`, into a
+/// `DayTime` interval value. Returns `None` if the duration is invalid, has years or
+/// months, or overflows.
+pub fn parse_day_time(s: &str) -> Option<i64> {
+    let duration = parse_duration(s)?;
+    if duration.months != 0 {
+        return None;
+    }
+    Some(day_time_value(
+        i32::try_from(duration.days).ok()?,
+        i32::try_from(duration.milliseconds).ok()?,
+    ))
+}
+
+/// Create a `DayTime` interval value from a number of days and milliseconds
+pub fn day_time_value(days: i32, milliseconds: i32) -> i64 {
+    ((days as i64) << 32) | (milliseconds as u32 as i64)
+}
+
+/// Split a `DayTime` interval value into its number of days and milliseconds
+pub fn day_time_parts(value: i64) -> (i32, i32) {
+    ((value >> 32) as i32, value as i32)
+}
+
+/// Format a number of months as an ISO 8601 duration, such as `P1Y2M`
+pub fn format_year_month(months: i32) -> String {
+    let sign = if months < 0 { "-" } else { "" };
+    let months = (months as i64).abs();
+    let mut s = format!("{}P", sign);
+    if months >= 12 {
+        s.push_str(&format!("{}Y", months / 12));
+    }
+    if months % 12 != 0 || months == 0 {
+        s.push_str(&format!("{}M", months % 12));
+    }
+    s
+}
+
+/// Format a `DayTime` interval value as an ISO 8601 duration, such as `P3DT4H5M6.5S`
+pub fn format_day_time(value: i64) -> String {
+    let (days, milliseconds) = day_time_parts(value);
+    // the sign is only moved in front of the duration if both parts agree on it
+    let negative = days <= 0 && milliseconds <= 0 && (days < 0 || milliseconds < 0);
+    let (days, milliseconds) = if negative {
+        (-(days as i64), -(milliseconds as i64))
+    } else {
+        (days as i64, milliseconds as i64)
+    };
+
+    let mut s = if negative { "-P" } else { "P" }.to_string();
+    if days != 0 {
+        s.push_str(&format!("{}D", days));
+    }
+    if milliseconds != 0 || days == 0 {
+        s.push('T');
+        let hours = milliseconds / MILLISECONDS_IN_HOUR;
+        let minutes = milliseconds % MILLISECONDS_IN_HOUR / MILLISECONDS_IN_MINUTE;
+        let seconds = milliseconds % MILLISECONDS_IN_MINUTE;
+        if hours != 0 {
+            s.push_str(&format!("{}H", hours));
+        }
+        if minutes != 0 {
+            s.push_str(&format!("{}M", minutes));
+        }
+        if seconds != 0 || milliseconds == 0 {
+            let fraction = seconds % MILLISECONDS_IN_SECOND;
+            if fraction == 0 {
+                s.push_str(&format!("{}S", seconds / MILLISECONDS_IN_SECOND));
+            } else {
+                let fraction = format!("{:03}", fraction.abs());
+                s.push_str(&format!(
+                    "{}{}.{}S",
+                    if seconds < 0 { "-" } else { "" },
+                    (seconds / MILLISECONDS_IN_SECOND).abs(),
+                    fraction.trim_end_matches('0')
+                ));
+            }
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_year_month() {
+        assert_eq!(parse_year_month("P1Y2M"), Some(14));
+        assert_eq!(parse_year_month("p3m"), Some(3));
+        assert_eq!(parse_year_month("P2Y"), Some(24));
+        assert_eq!(parse_year_month("-P1Y"), Some(-12));
+        assert_eq!(parse_year_month(" P0M "), Some(0));
+        // days and times have no fixed number of months
+        assert_eq!(parse_year_month("P1Y2D"), None);
+        assert_eq!(parse_year_month("PT1H"), None);
+        assert_eq!(parse_year_month("P"), None);
+        assert_eq!(parse_year_month("1Y"), None);
+        assert_eq!(parse_year_month("P1.5Y"), None);
+        assert_eq!(parse_year_month("P1X"), None);
+        assert_eq!(parse_year_month("P1"), None);
+        assert_eq!(parse_year_month("P999999999999Y"), None);
+    }
+
+    #[test]
+    fn test_parse_day_time() {
+        assert_eq!(
+            parse_day_time("P3DT4H5M6.5S").map(day_time_parts),
+            Some((3, 4 * 3_600_000 + 5 * 60_000 + 6_500))
+        );
+        assert_eq!(parse_day_time("P2W").map(day_time_parts), Some((14, 0)));
+        assert_eq!(
+            parse_day_time("PT36H").map(day_time_parts),
+            Some((0, 129_600_000))
+        );
+        assert_eq!(
+            parse_day_time("-P1DT1S").map(day_time_parts),
+            Some((-1, -1000))
+        );
+        assert_eq!(parse_day_time("PT0.001S").map(day_time_parts), Some((0, 1)));
+        assert_eq!(parse_day_time("P1M"), None);
+        assert_eq!(parse_day_time("P1H"), None);
+        assert_eq!(parse_day_time("PT"), None);
+        assert_eq!(parse_day_time("P1DT"), None);
+        assert_eq!(parse_day_time("P1TD"), None);
+    }
+
+    #[test]
+    fn test_day_time_value() {
+        for (days, milliseconds) in vec![(0, 0), (1, 2), (-1, 2), (3, -4), (-5, -6)] {
+            let value = day_time_value(days, milliseconds);
+            assert_eq!(day_time_parts(value), (days, milliseconds));
+        }
+        assert_eq!(day_time_value(1, 0), 1 << 32);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format_year_month(14), "P1Y2M");
+        assert_eq!(format_year_month(24), "P2Y");
+        assert_eq!(format_year_month(0), "P0M");
+        assert_eq!(format_year_month(-3), "-P3M");
+
+        let format =
+            |days, milliseconds| format_day_time(day_time_value(days, milliseconds));
+        assert_eq!(
+            format(3, 4 * 3_600_000 + 5 * 60_000 + 6_500),
+            "P3DT4H5M6.5S"
+        );
+        assert_eq!(format(2, 0), "P2D");
+        assert_eq!(format(0, 0), "PT0S");
+        assert_eq!(format(0, 60_000), "PT1M");
+        assert_eq!(format(-1, -1_250), "-P1DT1.25S");
+        assert_eq!(format(1, -1_000), "P1DT-1S");
+
+        // formatted values are parsed back to the same values
+        for s in vec!["P1Y2M", "-P3M", "P0M"] {
+            assert_eq!(format_year_month(parse_year_month(s).unwrap()), s);
+        }
+        for s in vec!["P3DT4H5M6.5S", "-P1DT1.25S", "PT0S", "P2D"] {
+            assert_eq!(format_day_time(parse_day_time(s).unwrap()), s);
+        }
+    }
+}
@@ -16,6 +16,11 @@
 // under the License.
 
 pub mod bit_util;
+#[cfg(feature = "std")]
 pub(crate) mod integration_util;
+#[cfg(feature = "std")]
+pub mod interval;
+#[cfg(feature = "std")]
 pub mod string_writer;
+#[cfg(feature = "std")]
 pub mod test_util;
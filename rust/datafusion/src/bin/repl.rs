@@ -19,6 +19,7 @@
 
 use clap::{crate_version, App, Arg};
 use datafusion::error::Result;
+use datafusion::execution::config::ExecutionConfig;
 use datafusion::execution::context::ExecutionContext;
 use datafusion::utils;
 use rustyline::Editor;
@@ -55,12 +56,13 @@ pub fn main() {
         env::set_current_dir(&p).unwrap();
     };
 
-    let batch_size = matches
-        .value_of("batch-size")
-        .map(|size| size.parse::<usize>().unwrap())
-        .unwrap_or(1_048_576);
+    let mut config = ExecutionConfig::new();
+    if let Some(size) = matches.value_of("batch-size") {
+        config.batch_size = size.parse::<usize>().unwrap();
+    }
 
     let mut ctx = ExecutionContext::new();
+    ctx.set_config(config);
 
     let mut rl = Editor::<()>::new();
     rl.load_history(".history").ok();
@@ -75,7 +77,7 @@ pub fn main() {
             Ok(ref line) if line.trim_end().ends_with(';') => {
                 query.push_str(line.trim_end());
                 rl.add_history_entry(query.clone());
-                match exec_and_print(&mut ctx, query, batch_size) {
+                match exec_and_print(&mut ctx, query) {
                     Ok(_) => {}
                     Err(err) => println!("{:?}", err),
                 }
@@ -99,13 +101,11 @@ fn is_exit_command(line: &str) -> bool {
     line == "quit" || line == "exit"
 }
 
-fn exec_and_print(
-    ctx: &mut ExecutionContext,
-    sql: String,
-    batch_size: usize,
-) -> Result<()> {
+fn exec_and_print(ctx: &mut ExecutionContext, sql: String) -> Result<()> {
     let now = Instant::now();
 
+    // the batch size can be changed during the session with `SET batch_size = n`
+    let batch_size = ctx.config().batch_size;
     let results = ctx.sql(&sql, batch_size)?;
 
     if results.is_empty() {
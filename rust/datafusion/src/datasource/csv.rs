@@ -17,7 +17,7 @@
 
 //! CSV Data source
 
-use std::fs::File;
+use std::fs::{self, File};
 
 use arrow::csv;
 use arrow::datatypes::{Field, Schema};
@@ -25,9 +25,10 @@ use arrow::record_batch::RecordBatch;
 use std::string::String;
 use std::sync::Arc;
 
-use crate::datasource::{ScanResult, TableProvider};
+use crate::datasource::{ScanResult, Statistics, TableProvider};
 use crate::error::Result;
-use crate::execution::physical_plan::csv::CsvExec;
+use crate::execution::physical_plan::common;
+use crate::execution::physical_plan::csv::{CsvExec, CsvReadOptions};
 use crate::execution::physical_plan::{BatchIterator, ExecutionPlan};
 
 /// Represents a CSV file with a provided schema
@@ -35,16 +36,29 @@ use crate::execution::physical_plan::{BatchIterator, ExecutionPlan};
 pub struct CsvFile {
     filename: String,
     schema: Arc<Schema>,
-    has_header: bool,
+    options: CsvReadOptions,
+    /// Statistics collected by scanning the file, if any
+    statistics: Option<Statistics>,
 }
 
 impl CsvFile {
     #[allow(missing_docs)]
     pub fn new(filename: &str, schema: &Schema, has_header: bool) -> Self {
+        let options = CsvReadOptions::new().has_header(has_header);
+        Self::new_with_options(filename, schema, options)
+    }
+
+    /// Create a CSV table that reads its file(s) with the given options
+    pub fn new_with_options(
+        filename: &str,
+        schema: &Schema,
+        options: CsvReadOptions,
+    ) -> Self {
         Self {
             filename: String::from(filename),
             schema: Arc::new(schema.clone()),
-            has_header,
+            options,
+            statistics: None,
         }
     }
 }
@@ -59,10 +73,10 @@ impl TableProvider for CsvFile {
         projection: &Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Vec<ScanResult>> {
-        let exec = CsvExec::try_new(
+        let exec = CsvExec::try_new_with_options(
             &self.filename,
             self.schema.clone(),
-            self.has_header,
+            self.options.clone(),
             projection.clone(),
             batch_size,
         )?;
@@ -73,6 +87,35 @@ impl TableProvider for CsvFile {
             .collect::<Result<Vec<_>>>()?;
         Ok(iterators)
     }
+
+    fn statistics(&self) -> Statistics {
+        if let Some(ref statistics) = self.statistics {
+            return statistics.clone();
+        }
+
+        // the size of the files on disk is a reasonable estimate of the size of the table
+        let mut filenames: Vec<String> = vec![];
+        let total_byte_size =
+            match common::build_file_list(&self.filename, &mut filenames, ".csv") {
+                Ok(_) => filenames
+                    .iter()
+                    .map(|f| fs::metadata(f).map(|m| m.len() as usize))
+                    .collect::<std::io::Result<Vec<_>>>()
+                    .ok()
+                    .map(|sizes| sizes.iter().sum()),
+                Err(_) => None,
+            };
+        Statistics {
+            num_rows: None,
+            total_byte_size,
+            column_statistics: None,
+        }
+    }
+
+    fn set_statistics(&mut self, statistics: Statistics) -> Result<()> {
+        self.statistics = Some(statistics);
+        Ok(())
+    }
 }
 
 /// Iterator over CSV batches
@@ -20,14 +20,54 @@
 use std::sync::{Arc, Mutex};
 
 use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
 
-use crate::error::Result;
-use crate::execution::physical_plan::BatchIterator;
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::datasource::DatasourceExec;
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, PhysicalExpr};
+use crate::logicalplan::{Expr, ScalarValue};
 
 /// Returned by implementors of `Table#scan`, this `BatchIterator` is wrapped with
 /// an `Arc` and `Mutex` so that it can be shared across threads as it is used.
 pub type ScanResult = Arc<Mutex<dyn BatchIterator>>;
 
+/// Statistics about a table that the query planner can use when choosing between
+/// physical operators, such as whether a join input is small enough to broadcast
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    /// The number of rows in the table, if known
+    pub num_rows: Option<usize>,
+    /// The total size of the table in bytes, if known
+    pub total_byte_size: Option<usize>,
+    /// Statistics about each column of the table, if known
+    pub column_statistics: Option<Vec<ColumnStatistics>>,
+}
+
+/// Statistics about a single column of a table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStatistics {
+    /// The number of null values in the column, if known
+    pub null_count: Option<usize>,
+    /// An estimate of the number of distinct values in the column, if known
+    pub distinct_count: Option<usize>,
+    /// The minimum value of the column, if known
+    pub min_value: Option<ScalarValue>,
+    /// The maximum value of the column, if known
+    pub max_value: Option<ScalarValue>,
+}
+
+/// A predicate on the projected columns of a table scan that is pushed into the table
+/// provider
+#[derive(Clone)]
+pub struct ScanFilter {
+    /// The logical expression, which a table provider can analyze, such as to skip
+    /// partitions based on their statistics
+    pub expr: Expr,
+    /// The physical expression, which a table provider can evaluate against the rows
+    /// it reads
+    pub physical_expr: Arc<dyn PhysicalExpr>,
+}
+
 /// Source table
 pub trait TableProvider {
     /// Get a reference to the schema for this table
@@ -40,4 +80,57 @@ pub trait TableProvider {
         projection: &Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Vec<ScanResult>>;
+
+    /// Create an execution plan that scans the table with the provided projection.
+    ///
+    /// The filter is a predicate on the projected columns and the limit is a maximum
+    /// number of rows of each partition that pass the filter. Both are also applied by
+    /// the plans that read the scan, so a table may use them to skip data while reading
+    /// but does not have to. By default, they are ignored and the iterators returned by
+    /// `scan` are wrapped in a `DatasourceExec`.
+    fn create_physical_plan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filter: Option<&ScanFilter>,
+        _limit: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let partitions = self.scan(projection, batch_size)?;
+        if partitions.is_empty() {
+            return Err(ExecutionError::General(
+                "Table provider returned no partitions".to_string(),
+            ));
+        }
+        let schema = partitions[0].lock().unwrap().schema();
+        Ok(Arc::new(DatasourceExec::new(schema, partitions)))
+    }
+
+    /// Get statistics for this table, if available
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    /// Replace the statistics of this table, such as with the statistics collected by
+    /// `ANALYZE TABLE`
+    fn set_statistics(&mut self, _statistics: Statistics) -> Result<()> {
+        Err(ExecutionError::NotImplemented(
+            "Table provider does not support storing statistics".to_string(),
+        ))
+    }
+
+    /// Append record batches to this table, such as the rows of an `INSERT` statement.
+    /// The batches have the schema of the table.
+    fn insert(&mut self, _batches: Vec<RecordBatch>) -> Result<()> {
+        Err(ExecutionError::NotImplemented(
+            "Table provider does not support inserting rows".to_string(),
+        ))
+    }
+
+    /// Replace the rows of this table with record batches, such as the rows resulting
+    /// from a `MERGE`, `UPDATE` or `DELETE` statement. The batches have the schema of the table.
+    fn overwrite(&mut self, _batches: Vec<RecordBatch>) -> Result<()> {
+        Err(ExecutionError::NotImplemented(
+            "Table provider does not support replacing rows".to_string(),
+        ))
+    }
 }
@@ -0,0 +1,257 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The views of the `information_schema` schema, which describe the tables registered
+//! on an execution context and their columns, as in the SQL standard:
+//!
+//! * `information_schema.tables` has a row for each table
+//! * `information_schema.columns` has a row for each column of each table
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringBuilder, UInt64Builder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::datasource::{MemTable, ScanFilter, ScanResult, TableProvider};
+use crate::error::Result;
+use crate::execution::physical_plan::ExecutionPlan;
+
+/// The name of the schema of the views
+pub const INFORMATION_SCHEMA: &str = "information_schema";
+/// The name of the catalog that all tables belong to
+pub const CATALOG_NAME: &str = "datafusion";
+/// The name of the schema of the registered tables
+pub const DEFAULT_SCHEMA_NAME: &str = "default";
+
+/// The qualified name of the view of the tables
+pub const TABLES: &str = "information_schema.tables";
+/// The qualified name of the view of the columns
+pub const COLUMNS: &str = "information_schema.columns";
+
+/// A view of the `information_schema` schema, whose rows are created from the tables of
+/// an execution context when it is scanned
+pub struct InformationSchemaTable {
+    name: &'static str,
+    /// The names and schemas of the registered tables, sorted by name
+    tables: Vec<(String, Arc<Schema>)>,
+}
+
+impl InformationSchemaTable {
+    /// Create the view with the given qualified name, such as
+    /// `information_schema.tables`, over the given tables. Returns `None` if there is
+    /// no such view.
+    pub fn new(name: &str, mut tables: Vec<(String, Arc<Schema>)>) -> Option<Self> {
+        let name = match name {
+            TABLES => TABLES,
+            COLUMNS => COLUMNS,
+            _ => return None,
+        };
+        tables.sort_by(|a, b| a.0.cmp(&b.0));
+        Some(Self { name, tables })
+    }
+
+    /// Get the schema of the view with the given qualified name, if there is one
+    pub fn schema_of(name: &str) -> Option<Arc<Schema>> {
+        match name {
+            TABLES => Some(Arc::new(Schema::new(vec![
+                Field::new("table_catalog", DataType::Utf8, false),
+                Field::new("table_schema", DataType::Utf8, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("table_type", DataType::Utf8, false),
+            ]))),
+            COLUMNS => Some(Arc::new(Schema::new(vec![
+                Field::new("table_catalog", DataType::Utf8, false),
+                Field::new("table_schema", DataType::Utf8, false),
+                Field::new("table_name", DataType::Utf8, false),
+                Field::new("column_name", DataType::Utf8, false),
+                Field::new("ordinal_position", DataType::UInt64, false),
+                Field::new("is_nullable", DataType::Utf8, false),
+                Field::new("data_type", DataType::Utf8, false),
+            ]))),
+            _ => None,
+        }
+    }
+
+    /// The registered tables followed by the views, with their schema names
+    fn all_tables(&self) -> Vec<(&str, &str, Arc<Schema>)> {
+        let mut tables: Vec<(&str, &str, Arc<Schema>)> = self
+            .tables
+            .iter()
+            .map(|(name, schema)| (DEFAULT_SCHEMA_NAME, name.as_str(), schema.clone()))
+            .collect();
+        for view in &[COLUMNS, TABLES] {
+            let name = &view[INFORMATION_SCHEMA.len() + 1..];
+            tables.push((INFORMATION_SCHEMA, name, Self::schema_of(view).unwrap()));
+        }
+        tables
+    }
+
+    /// Create the rows of `information_schema.tables`
+    fn tables_batch(&self) -> Result<RecordBatch> {
+        let tables = self.all_tables();
+        let mut catalogs = StringBuilder::new(tables.len());
+        let mut schemas = StringBuilder::new(tables.len());
+        let mut names = StringBuilder::new(tables.len());
+        let mut types = StringBuilder::new(tables.len());
+        for (schema_name, table_name, _) in &tables {
+            catalogs.append_value(CATALOG_NAME)?;
+            schemas.append_value(schema_name)?;
+            names.append_value(table_name)?;
+            types.append_value(if *schema_name == INFORMATION_SCHEMA {
+                "VIEW"
+            } else {
+                "BASE TABLE"
+            })?;
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(catalogs.finish()),
+            Arc::new(schemas.finish()),
+            Arc::new(names.finish()),
+            Arc::new(types.finish()),
+        ];
+        Ok(RecordBatch::try_new(self.schema(), columns)?)
+    }
+
+    /// Create the rows of `information_schema.columns`, with the columns of each table
+    /// in order
+    fn columns_batch(&self) -> Result<RecordBatch> {
+        let tables = self.all_tables();
+        let len = tables.iter().map(|(_, _, s)| s.fields().len()).sum();
+        let mut catalogs = StringBuilder::new(len);
+        let mut schemas = StringBuilder::new(len);
+        let mut table_names = StringBuilder::new(len);
+        let mut names = StringBuilder::new(len);
+        let mut positions = UInt64Builder::new(len);
+        let mut nullables = StringBuilder::new(len);
+        let mut types = StringBuilder::new(len);
+        for (schema_name, table_name, schema) in &tables {
+            for (i, field) in schema.fields().iter().enumerate() {
+                catalogs.append_value(CATALOG_NAME)?;
+                schemas.append_value(schema_name)?;
+                table_names.append_value(table_name)?;
+                names.append_value(field.name())?;
+                // the ordinal positions of the columns start at 1
+                positions.append_value(i as u64 + 1)?;
+                nullables.append_value(if field.is_nullable() { "YES" } else { "NO" })?;
+                types.append_value(&format!("{:?}", field.data_type()))?;
+            }
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(catalogs.finish()),
+            Arc::new(schemas.finish()),
+            Arc::new(table_names.finish()),
+            Arc::new(names.finish()),
+            Arc::new(positions.finish()),
+            Arc::new(nullables.finish()),
+            Arc::new(types.finish()),
+        ];
+        Ok(RecordBatch::try_new(self.schema(), columns)?)
+    }
+
+    /// Create the rows of the view as an in-memory table
+    fn load(&self) -> Result<MemTable> {
+        let batch = match self.name {
+            TABLES => self.tables_batch()?,
+            _ => self.columns_batch()?,
+        };
+        MemTable::new(self.schema(), vec![batch])
+    }
+}
+
+impl TableProvider for InformationSchemaTable {
+    fn schema(&self) -> Arc<Schema> {
+        Self::schema_of(self.name).unwrap()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Vec<ScanResult>> {
+        self.load()?.scan(projection, batch_size)
+    }
+
+    fn create_physical_plan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filter: Option<&ScanFilter>,
+        limit: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.load()?
+            .create_physical_plan(projection, filter, limit, batch_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::BatchIterator;
+    use crate::test;
+
+    #[test]
+    fn tables_and_columns() -> Result<()> {
+        let tables = vec![
+            (
+                "t2".to_string(),
+                Arc::new(Schema::new(vec![Field::new("b", DataType::Utf8, true)])),
+            ),
+            (
+                "t1".to_string(),
+                Arc::new(Schema::new(vec![
+                    Field::new("a", DataType::Int32, false),
+                    Field::new("c", DataType::Float64, true),
+                ])),
+            ),
+        ];
+        assert!(
+            InformationSchemaTable::new("information_schema.views", vec![]).is_none()
+        );
+
+        let view = InformationSchemaTable::new(TABLES, tables.clone()).unwrap();
+        let batch = view.load()?.scan(&Some(vec![1, 2, 3]), 1024)?[0]
+            .lock()
+            .unwrap()
+            .next()?
+            .unwrap();
+        assert_eq!(
+            test::format_batch(&batch),
+            vec![
+                "default,t1,BASE TABLE",
+                "default,t2,BASE TABLE",
+                "information_schema,columns,VIEW",
+                "information_schema,tables,VIEW",
+            ]
+        );
+
+        let view = InformationSchemaTable::new(COLUMNS, tables).unwrap();
+        let batch = view.load()?.scan(&Some(vec![2, 3, 4, 5, 6]), 1024)?[0]
+            .lock()
+            .unwrap()
+            .next()?
+            .unwrap();
+        let rows = test::format_batch(&batch);
+        assert_eq!(
+            rows[..3].to_vec(),
+            vec!["t1,a,1,NO,Int32", "t1,c,2,YES,Float64", "t2,b,1,YES,Utf8"]
+        );
+        // the columns of the views are listed too
+        assert_eq!(rows.len(), 3 + 7 + 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Newline-delimited JSON data source
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::string::String;
+use std::sync::{Arc, Mutex};
+
+use arrow::datatypes::{Field, Schema};
+use arrow::json;
+use arrow::record_batch::RecordBatch;
+
+use crate::datasource::{ScanResult, Statistics, TableProvider};
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::{common, BatchIterator};
+
+/// Represents a newline-delimited JSON file, or a directory of such files, with one
+/// JSON object per line
+pub struct NdJsonFile {
+    path: String,
+    schema: Arc<Schema>,
+    /// Statistics collected by scanning the files, if any
+    statistics: Option<Statistics>,
+}
+
+impl NdJsonFile {
+    /// Create a table that reads the file(s) with the provided schema
+    pub fn new(path: &str, schema: &Schema) -> Self {
+        Self {
+            path: String::from(path),
+            schema: Arc::new(schema.clone()),
+            statistics: None,
+        }
+    }
+
+    /// Create a table whose schema is inferred from the first file, reading at most
+    /// `max_records` records of it or all records if `None`
+    pub fn try_new_infer_schema(path: &str, max_records: Option<usize>) -> Result<Self> {
+        let filenames = Self::filenames(path)?;
+        if filenames.is_empty() {
+            return Err(ExecutionError::General(format!(
+                "No JSON files found at '{}' to infer the schema from",
+                path
+            )));
+        }
+        let reader = json::ReaderBuilder::new()
+            .infer_schema(max_records)
+            .build::<File>(File::open(&filenames[0])?)?;
+        Ok(Self::new(path, &reader.schema()))
+    }
+
+    fn filenames(path: &str) -> Result<Vec<String>> {
+        let mut filenames: Vec<String> = vec![];
+        common::build_file_list(path, &mut filenames, ".json")?;
+        Ok(filenames)
+    }
+}
+
+impl TableProvider for NdJsonFile {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    /// Scan the file(s), returning one BatchIterator per file
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Vec<ScanResult>> {
+        Self::filenames(&self.path)?
+            .iter()
+            .map(|filename| {
+                let iterator = NdJsonBatchIterator::try_new(
+                    filename,
+                    self.schema.clone(),
+                    projection,
+                    batch_size,
+                )?;
+                Ok(Arc::new(Mutex::new(iterator)) as ScanResult)
+            })
+            .collect()
+    }
+
+    fn statistics(&self) -> Statistics {
+        if let Some(ref statistics) = self.statistics {
+            return statistics.clone();
+        }
+
+        // the size of the files on disk is a reasonable estimate of the size of the table
+        let total_byte_size = match Self::filenames(&self.path) {
+            Ok(filenames) => filenames
+                .iter()
+                .map(|f| fs::metadata(f).map(|m| m.len() as usize))
+                .collect::<std::io::Result<Vec<_>>>()
+                .ok()
+                .map(|sizes| sizes.iter().sum()),
+            Err(_) => None,
+        };
+        Statistics {
+            num_rows: None,
+            total_byte_size,
+            column_statistics: None,
+        }
+    }
+
+    fn set_statistics(&mut self, statistics: Statistics) -> Result<()> {
+        self.statistics = Some(statistics);
+        Ok(())
+    }
+}
+
+/// Iterator over the batches of a newline-delimited JSON file
+pub struct NdJsonBatchIterator {
+    schema: Arc<Schema>,
+    projection: Option<Vec<usize>>,
+    reader: json::Reader<File>,
+}
+
+impl NdJsonBatchIterator {
+    /// Create an iterator that reads a file with the provided schema, returning the
+    /// projected columns in the order of the projection
+    pub fn try_new(
+        filename: &str,
+        schema: Arc<Schema>,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let file = File::open(filename)?;
+        let reader =
+            json::Reader::new(BufReader::new(file), schema.clone(), batch_size, None);
+
+        let projected_schema = match projection {
+            Some(p) => {
+                let projected_fields: Vec<Field> =
+                    p.iter().map(|i| schema.fields()[*i].clone()).collect();
+                Arc::new(Schema::new(projected_fields))
+            }
+            None => schema,
+        };
+
+        Ok(Self {
+            schema: projected_schema,
+            projection: projection.clone(),
+            reader,
+        })
+    }
+}
+
+impl BatchIterator for NdJsonBatchIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.reader.next()? {
+            Some(batch) => match self.projection {
+                Some(ref projection) => Ok(Some(RecordBatch::try_new(
+                    self.schema.clone(),
+                    projection
+                        .iter()
+                        .map(|i| batch.column(*i).clone())
+                        .collect(),
+                )?)),
+                None => Ok(Some(batch)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array};
+    use arrow::datatypes::DataType;
+
+    #[test]
+    fn infer_schema_and_scan() -> Result<()> {
+        let table =
+            NdJsonFile::try_new_infer_schema("../arrow/test/data/basic.json", None)?;
+        let schema = table.schema();
+        let fields: Vec<(&str, &DataType)> = schema
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), f.data_type()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("a", &DataType::Int64),
+                ("b", &DataType::Float64),
+                ("c", &DataType::Boolean),
+                ("d", &DataType::Utf8),
+            ]
+        );
+
+        // the projected columns are returned in the order of the projection
+        let partitions = table.scan(&Some(vec![1, 0]), 5)?;
+        assert_eq!(partitions.len(), 1);
+        let mut it = partitions[0].lock().unwrap();
+        let batch = it.next()?.unwrap();
+        assert_eq!(batch.num_rows(), 5);
+        assert_eq!(batch.schema().field(0).name(), "b");
+        let b = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        let a = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!((a.value(1), b.value(1)), (-10, -3.5));
+
+        let mut rows = batch.num_rows();
+        while let Some(batch) = it.next()? {
+            rows += batch.num_rows();
+        }
+        assert_eq!(rows, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn infer_schema_without_files() {
+        assert!(NdJsonFile::try_new_infer_schema(
+            "../arrow/test/data/uk_cities.csv",
+            None
+        )
+        .is_err());
+    }
+}
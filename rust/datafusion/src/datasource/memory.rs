@@ -24,24 +24,57 @@ use std::sync::{Arc, Mutex};
 use arrow::datatypes::{Field, Schema};
 use arrow::record_batch::RecordBatch;
 
-use crate::datasource::{ScanResult, TableProvider};
+use crate::datasource::pruning::may_match;
+use crate::datasource::statistics::min_max_statistics;
+use crate::datasource::{
+    ColumnStatistics, ScanFilter, ScanResult, Statistics, TableProvider,
+};
 use crate::error::{ExecutionError, Result};
-use crate::execution::physical_plan::BatchIterator;
+use crate::execution::physical_plan::common;
+use crate::execution::physical_plan::datasource::DatasourceExec;
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan};
 
 /// In-memory table
 pub struct MemTable {
     schema: Arc<Schema>,
-    batches: Vec<RecordBatch>,
+    /// The batches of each partition
+    partitions: Vec<Vec<RecordBatch>>,
+    /// The null counts and minimum and maximum values of the columns of each
+    /// partition, which are used to skip partitions when the table is scanned with a
+    /// filter
+    partition_statistics: Vec<Vec<ColumnStatistics>>,
+    /// Statistics collected by scanning the table, if any
+    statistics: Option<Statistics>,
 }
 
 impl MemTable {
-    /// Create a new in-memory table from the provided schema and record batches
+    /// Create a new in-memory table with a single partition from the provided schema
+    /// and record batches
     pub fn new(schema: Arc<Schema>, batches: Vec<RecordBatch>) -> Result<Self> {
-        if batches
+        Self::new_partitioned(schema, vec![batches])
+    }
+
+    /// Create a new in-memory table from the provided schema and the record batches of
+    /// each partition
+    pub fn new_partitioned(
+        schema: Arc<Schema>,
+        partitions: Vec<Vec<RecordBatch>>,
+    ) -> Result<Self> {
+        if partitions
             .iter()
+            .flatten()
             .all(|batch| batch.schema().as_ref() == schema.as_ref())
         {
-            Ok(Self { schema, batches })
+            let partition_statistics = partitions
+                .iter()
+                .map(|batches| min_max_statistics(&schema, batches))
+                .collect();
+            Ok(Self {
+                schema,
+                partitions,
+                partition_statistics,
+                statistics: None,
+            })
         } else {
             Err(ExecutionError::General(
                 "Mismatch between schema and batches".to_string(),
@@ -49,42 +82,40 @@ impl MemTable {
         }
     }
 
-    /// Create a mem table by reading from another data source
+    /// Create a mem table by reading from another data source, keeping its partitions
     pub fn load(t: &dyn TableProvider) -> Result<Self> {
         let schema = t.schema();
         let partitions = t.scan(&None, 1024 * 1024)?;
 
-        let mut data: Vec<RecordBatch> = vec![];
+        let mut data: Vec<Vec<RecordBatch>> = vec![];
         for it in &partitions {
+            let mut batches = vec![];
             while let Ok(Some(batch)) = it.lock().unwrap().next() {
-                data.push(batch);
+                batches.push(batch);
             }
+            data.push(batches);
         }
 
-        MemTable::new(schema.clone(), data)
+        MemTable::new_partitioned(schema.clone(), data)
     }
-}
 
-impl TableProvider for MemTable {
-    fn schema(&self) -> Arc<Schema> {
-        self.schema.clone()
+    /// The number of bytes of memory occupied by the record batches of this table
+    pub fn memory_size(&self) -> usize {
+        self.partitions
+            .iter()
+            .flatten()
+            .map(|batch| batch.get_array_memory_size())
+            .sum()
     }
 
-    fn scan(
+    /// Get the indices of the columns of a projection and the projected schema
+    fn project(
         &self,
         projection: &Option<Vec<usize>>,
-        _batch_size: usize,
-    ) -> Result<Vec<ScanResult>> {
+    ) -> Result<(Vec<usize>, Arc<Schema>)> {
         let columns: Vec<usize> = match projection {
             Some(p) => p.clone(),
-            None => {
-                let l = self.schema.fields().len();
-                let mut v = Vec::with_capacity(l);
-                for i in 0..l {
-                    v.push(i);
-                }
-                v
-            }
+            None => (0..self.schema.fields().len()).collect(),
         };
 
         let projected_columns: Result<Vec<Field>> = columns
@@ -100,10 +131,16 @@ impl TableProvider for MemTable {
             })
             .collect();
 
-        let projected_schema = Arc::new(Schema::new(projected_columns?));
+        Ok((columns, Arc::new(Schema::new(projected_columns?))))
+    }
 
-        let batches = self
-            .batches
+    /// Create an iterator over the projected columns of the batches of a partition
+    fn scan_partition(
+        batches: &[RecordBatch],
+        columns: &[usize],
+        projected_schema: &Arc<Schema>,
+    ) -> Result<ScanResult> {
+        let batches = batches
             .iter()
             .map(|batch| {
                 RecordBatch::try_new(
@@ -111,17 +148,118 @@ impl TableProvider for MemTable {
                     columns.iter().map(|i| batch.column(*i).clone()).collect(),
                 )
             })
-            .collect();
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+        Ok(Arc::new(Mutex::new(MemBatchIterator {
+            schema: projected_schema.clone(),
+            index: 0,
+            batches,
+        })))
+    }
+}
+
+impl TableProvider for MemTable {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _batch_size: usize,
+    ) -> Result<Vec<ScanResult>> {
+        let (columns, projected_schema) = self.project(projection)?;
+        self.partitions
+            .iter()
+            .map(|batches| Self::scan_partition(batches, &columns, &projected_schema))
+            .collect()
+    }
+
+    fn create_physical_plan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filter: Option<&ScanFilter>,
+        _limit: Option<usize>,
+        _batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let (columns, projected_schema) = self.project(projection)?;
+        let mut partitions = vec![];
+        for (batches, statistics) in
+            self.partitions.iter().zip(self.partition_statistics.iter())
+        {
+            // skip the partitions whose statistics rule out rows that pass the filter,
+            // which references the projected columns
+            if let Some(filter) = filter {
+                let statistics: Vec<ColumnStatistics> =
+                    columns.iter().map(|i| statistics[*i].clone()).collect();
+                if !may_match(&filter.expr, &statistics) {
+                    continue;
+                }
+            }
+            partitions.push(Self::scan_partition(batches, &columns, &projected_schema)?);
+        }
+        // the plan has an empty partition when all partitions were skipped
+        if partitions.is_empty() {
+            partitions.push(Self::scan_partition(&[], &columns, &projected_schema)?);
+        }
+        Ok(Arc::new(DatasourceExec::new(projected_schema, partitions)))
+    }
 
-        match batches {
-            Ok(batches) => Ok(vec![Arc::new(Mutex::new(MemBatchIterator {
-                schema: projected_schema.clone(),
-                index: 0,
-                batches,
-            }))]),
-            Err(e) => Err(ExecutionError::ArrowError(e)),
+    fn statistics(&self) -> Statistics {
+        if let Some(ref statistics) = self.statistics {
+            return statistics.clone();
+        }
+        let batches = self.partitions.iter().flatten();
+        let num_rows = batches.clone().map(|batch| batch.num_rows()).sum();
+        let total_byte_size = batches.map(common::batch_byte_size).sum();
+        Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: Some(total_byte_size),
+            column_statistics: None,
         }
     }
+
+    fn set_statistics(&mut self, statistics: Statistics) -> Result<()> {
+        self.statistics = Some(statistics);
+        Ok(())
+    }
+
+    /// Append the batches to the last partition. The statistics collected by scanning
+    /// the table are discarded, since they no longer describe its data.
+    fn insert(&mut self, batches: Vec<RecordBatch>) -> Result<()> {
+        if batches
+            .iter()
+            .any(|batch| batch.schema().as_ref() != self.schema.as_ref())
+        {
+            return Err(ExecutionError::General(
+                "Mismatch between schema and batches".to_string(),
+            ));
+        }
+        if self.partitions.is_empty() {
+            self.partitions.push(vec![]);
+            self.partition_statistics.push(vec![]);
+        }
+        let last = self.partitions.len() - 1;
+        self.partitions[last].extend(batches);
+        self.partition_statistics[last] =
+            min_max_statistics(&self.schema, &self.partitions[last]);
+        self.statistics = None;
+        Ok(())
+    }
+
+    fn overwrite(&mut self, batches: Vec<RecordBatch>) -> Result<()> {
+        if batches
+            .iter()
+            .any(|batch| batch.schema().as_ref() != self.schema.as_ref())
+        {
+            return Err(ExecutionError::General(
+                "Mismatch between schema and batches".to_string(),
+            ));
+        }
+        self.partition_statistics = vec![min_max_statistics(&self.schema, &batches)];
+        self.partitions = vec![batches];
+        self.statistics = None;
+        Ok(())
+    }
 }
 
 /// Iterator over an in-memory table
@@ -149,6 +287,10 @@ impl BatchIterator for MemBatchIterator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::physical_plan::common::collect;
+    use crate::execution::physical_plan::expressions::{binary, col, lit};
+    use crate::logicalplan::{Expr, Operator, ScalarValue};
+    use crate::test;
     use arrow::array::Int32Array;
     use arrow::datatypes::{DataType, Field, Schema};
 
@@ -207,6 +349,100 @@ mod tests {
         assert_eq!(3, batch1.num_columns());
     }
 
+    #[test]
+    fn test_statistics() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![4, 5, 6])),
+            ],
+        )
+        .unwrap();
+
+        let provider = MemTable::new(schema, vec![batch.clone(), batch]).unwrap();
+
+        let stats = provider.statistics();
+        assert_eq!(Some(6), stats.num_rows);
+        assert!(stats.total_byte_size.unwrap() >= 6 * 2 * 4);
+    }
+
+    #[test]
+    fn test_insert() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |a: Vec<i32>| -> Result<RecordBatch> {
+            Ok(RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a))],
+            )?)
+        };
+        let mut provider = MemTable::new(schema.clone(), vec![batch(vec![1, 2])?])?;
+        provider.set_statistics(Statistics::default())?;
+
+        provider.insert(vec![batch(vec![3])?, batch(vec![4, 5])?])?;
+        assert_eq!(provider.statistics().num_rows, Some(5));
+        let partitions = provider.scan(&None, 1024)?;
+        assert_eq!(partitions.len(), 1);
+        let batches = collect(partitions[0].clone())?;
+        assert_eq!(batches.len(), 3);
+
+        // the partition statistics include the inserted rows
+        let filter = ScanFilter {
+            expr: Expr::BinaryExpr {
+                left: Arc::new(Expr::Column(0)),
+                op: Operator::Gt,
+                right: Arc::new(Expr::Literal(ScalarValue::Int32(4))),
+            },
+            physical_expr: binary(
+                col(0, &schema),
+                Operator::Gt,
+                lit(ScalarValue::Int32(4)),
+            ),
+        };
+        let plan = provider.create_physical_plan(&None, Some(&filter), None, 1024)?;
+        assert_eq!(collect(plan.partitions()?[0].execute()?)?.len(), 3);
+
+        let other_schema =
+            Arc::new(Schema::new(vec![Field::new("b", DataType::Int32, false)]));
+        let other_batch = RecordBatch::try_new(
+            other_schema,
+            vec![Arc::new(Int32Array::from(vec![6]))],
+        )?;
+        assert!(provider.insert(vec![other_batch]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overwrite() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |a: Vec<i32>| -> Result<RecordBatch> {
+            Ok(RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a))],
+            )?)
+        };
+        let mut provider = MemTable::new_partitioned(
+            schema.clone(),
+            vec![vec![batch(vec![1, 2])?], vec![batch(vec![3])?]],
+        )?;
+
+        provider.overwrite(vec![batch(vec![4, 5, 6])?])?;
+        assert_eq!(provider.statistics().num_rows, Some(3));
+        let partitions = provider.scan(&None, 1024)?;
+        assert_eq!(partitions.len(), 1);
+        let batches = collect(partitions[0].clone())?;
+        assert_eq!(test::format_batch(&batches[0]), vec!["4", "5", "6"]);
+
+        provider.overwrite(vec![])?;
+        assert_eq!(provider.statistics().num_rows, Some(0));
+        Ok(())
+    }
+
     #[test]
     fn test_invalid_projection() {
         let schema = Arc::new(Schema::new(vec![
@@ -272,4 +508,67 @@ mod tests {
             ),
         }
     }
+
+    #[test]
+    fn test_partition_pruning() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let partition = |a: Vec<i32>| -> Result<Vec<RecordBatch>> {
+            let b = a.iter().map(|v| v * 10).collect::<Vec<_>>();
+            Ok(vec![RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+            )?])
+        };
+        let provider = MemTable::new_partitioned(
+            schema.clone(),
+            vec![
+                partition(vec![1, 2, 3])?,
+                partition(vec![4, 5, 6])?,
+                partition(vec![7, 8, 9])?,
+            ],
+        )?;
+
+        // b > value on a projection of b only
+        let projection = Some(vec![1]);
+        let projected_schema = Schema::new(vec![schema.field(1).clone()]);
+        let filter = |value| ScanFilter {
+            expr: Expr::BinaryExpr {
+                left: Arc::new(Expr::Column(0)),
+                op: Operator::Gt,
+                right: Arc::new(Expr::Literal(ScalarValue::Int32(value))),
+            },
+            physical_expr: binary(
+                col(0, &projected_schema),
+                Operator::Gt,
+                lit(ScalarValue::Int32(value)),
+            ),
+        };
+
+        let plan =
+            provider.create_physical_plan(&projection, Some(&filter(55)), None, 1024)?;
+        let partitions = plan.partitions()?;
+        assert_eq!(partitions.len(), 2);
+        let batches = collect(partitions[0].execute()?)?;
+        let values = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(values.value_slice(0, 3), &[40, 50, 60]);
+
+        // all partitions are skipped
+        let plan =
+            provider.create_physical_plan(&projection, Some(&filter(90)), None, 1024)?;
+        let partitions = plan.partitions()?;
+        assert_eq!(partitions.len(), 1);
+        assert!(collect(partitions[0].execute()?)?.is_empty());
+
+        let plan = provider.create_physical_plan(&projection, None, None, 1024)?;
+        assert_eq!(plan.partitions()?.len(), 3);
+
+        Ok(())
+    }
 }
@@ -17,11 +17,26 @@
 
 //! DataFusion data sources
 
+#[cfg(feature = "csv")]
 pub mod csv;
 pub mod datasource;
+pub mod information_schema;
+pub mod json;
 pub mod memory;
+#[cfg(feature = "parquet")]
 pub mod parquet;
+pub mod pruning;
+pub mod statistics;
+pub mod streaming;
 
+#[cfg(feature = "csv")]
 pub use self::csv::{CsvBatchIterator, CsvFile};
-pub use self::datasource::{ScanResult, TableProvider};
+pub use self::datasource::{
+    ColumnStatistics, ScanFilter, ScanResult, Statistics, TableProvider,
+};
+pub use self::json::{NdJsonBatchIterator, NdJsonFile};
 pub use self::memory::{MemBatchIterator, MemTable};
+pub use self::streaming::{
+    FileIngester, IngestFormat, PollResult, SchemaDrift, SchemaDriftAction,
+    StreamingTable,
+};
@@ -22,27 +22,65 @@ use std::sync::Arc;
 
 use arrow::datatypes::*;
 
-use crate::datasource::{ScanResult, TableProvider};
+use crate::datasource::{ScanFilter, ScanResult, Statistics, TableProvider};
 use crate::error::Result;
-use crate::execution::physical_plan::parquet::ParquetExec;
+use crate::execution::physical_plan::parquet::{ParquetExec, ParquetFooterCache};
 use crate::execution::physical_plan::{common, ExecutionPlan};
 
 /// Table-based representation of a `ParquetFile`
 pub struct ParquetTable {
     path: String,
     schema: Arc<Schema>,
+    /// Statistics collected by scanning the files, if any
+    statistics: Option<Statistics>,
+    /// Statistics read from the metadata in the footers of the files
+    footer_statistics: Statistics,
+    /// Footers of the files, which are read once instead of for every query
+    footer_cache: Arc<ParquetFooterCache>,
 }
 
 impl ParquetTable {
     /// Attempt to initialize a new `ParquetTable` from a file path
     pub fn try_new(path: &str) -> Result<Self> {
-        let parquet_exec = ParquetExec::try_new(path, None, 0)?;
+        Self::try_new_with_footer_cache(path, Arc::new(ParquetFooterCache::new()))
+    }
+
+    /// Attempt to initialize a new `ParquetTable` from a file path, with a cache of
+    /// footers that can be shared with other tables over the same files
+    pub fn try_new_with_footer_cache(
+        path: &str,
+        footer_cache: Arc<ParquetFooterCache>,
+    ) -> Result<Self> {
+        let parquet_exec =
+            ParquetExec::try_new_with_footer_cache(path, None, 0, footer_cache.clone())?;
         let schema = parquet_exec.schema();
         Ok(Self {
             path: path.to_string(),
             schema,
+            statistics: None,
+            footer_statistics: parquet_exec.statistics()?,
+            footer_cache,
         })
     }
+
+    /// Get the cache of the footers of the files
+    pub fn footer_cache(&self) -> &Arc<ParquetFooterCache> {
+        &self.footer_cache
+    }
+
+    /// Create a `ParquetExec` that reads the footers of the files through the cache
+    fn parquet_exec(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<ParquetExec> {
+        ParquetExec::try_new_with_footer_cache(
+            &self.path,
+            projection.clone(),
+            batch_size,
+            self.footer_cache.clone(),
+        )
+    }
 }
 
 impl TableProvider for ParquetTable {
@@ -61,8 +99,7 @@ impl TableProvider for ParquetTable {
         let mut filenames: Vec<String> = vec![];
         common::build_file_list(&self.path, &mut filenames, ".parquet")?;
 
-        let parquet_exec =
-            ParquetExec::try_new(&self.path, projection.clone(), batch_size)?;
+        let parquet_exec = self.parquet_exec(projection, batch_size)?;
 
         let partitions = parquet_exec.partitions()?;
 
@@ -72,6 +109,39 @@ impl TableProvider for ParquetTable {
             .collect::<Result<Vec<_>>>()?;
         Ok(iterators)
     }
+
+    /// Create a `ParquetExec` that reads the file(s), applying the filter and the
+    /// limit to each file while it is read. Files whose footer statistics or partition
+    /// directories rule out rows that pass the filter are not part of the plan.
+    fn create_physical_plan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filter: Option<&ScanFilter>,
+        limit: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let mut parquet_exec = self.parquet_exec(projection, batch_size)?;
+        if let Some(filter) = filter {
+            parquet_exec = parquet_exec
+                .prune_files(&filter.expr)?
+                .with_predicate(filter.physical_expr.clone());
+        }
+        if let Some(limit) = limit {
+            parquet_exec = parquet_exec.with_limit(limit);
+        }
+        Ok(Arc::new(parquet_exec))
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.statistics
+            .clone()
+            .unwrap_or_else(|| self.footer_statistics.clone())
+    }
+
+    fn set_statistics(&mut self, statistics: Statistics) -> Result<()> {
+        self.statistics = Some(statistics);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -299,6 +369,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn footers_are_read_once() -> Result<()> {
+        let testdata =
+            env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        let table = ParquetTable::try_new(&filename)?;
+        assert_eq!(table.footer_cache().len(), 1);
+
+        let footer = table.footer_cache().footer(&filename)?;
+        table.create_physical_plan(&None, None, None, 1024)?;
+        table.scan(&Some(vec![0]), 1024)?;
+        assert_eq!(table.footer_cache().len(), 1);
+        assert!(Arc::ptr_eq(
+            &footer,
+            &table.footer_cache().footer(&filename)?
+        ));
+        Ok(())
+    }
+
     fn load_table(name: &str) -> Box<dyn TableProvider> {
         let testdata =
             env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
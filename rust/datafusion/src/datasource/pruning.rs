@@ -0,0 +1,302 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Decides whether a partition of a table can contain rows that pass a filter, based on
+//! the statistics of its columns, so that table providers can skip partitions
+
+use std::cmp::Ordering;
+
+use crate::datasource::ColumnStatistics;
+use crate::logicalplan::{Expr, Operator, ScalarValue};
+use arrow::datatypes::DataType;
+
+/// Whether a predicate can be true for any row of a partition whose columns have the
+/// given statistics. The predicate references the columns by their index in
+/// `statistics`. Returns true whenever the statistics are not sufficient to rule out
+/// a match.
+pub fn may_match(predicate: &Expr, statistics: &[ColumnStatistics]) -> bool {
+    match predicate {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => may_match(left, statistics) && may_match(right, statistics),
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Or,
+            right,
+        } => may_match(left, statistics) || may_match(right, statistics),
+        Expr::BinaryExpr { left, op, right } => {
+            match (left.evaluate_literal(), right.evaluate_literal()) {
+                (None, Some(value)) => may_compare(left, op, &value, statistics),
+                (Some(value), None) => match reverse(op) {
+                    Some(op) => may_compare(right, &op, &value, statistics),
+                    None => true,
+                },
+                _ => true,
+            }
+        }
+        Expr::Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        } => match (low.evaluate_literal(), high.evaluate_literal()) {
+            (Some(low), Some(high)) => {
+                may_compare(expr, &Operator::GtEq, &low, statistics)
+                    && may_compare(expr, &Operator::LtEq, &high, statistics)
+            }
+            _ => true,
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated: false,
+        } => list.iter().any(|value| match value.evaluate_literal() {
+            Some(value) => may_compare(expr, &Operator::Eq, &value, statistics),
+            None => true,
+        }),
+        Expr::IsNull(expr) => match expr.as_ref() {
+            Expr::Column(i) => statistics
+                .get(*i)
+                .map_or(true, |column| column.null_count != Some(0)),
+            _ => true,
+        },
+        _ => true,
+    }
+}
+
+/// Whether an expression can compare to a value with the given operator for any row
+fn may_compare(
+    expr: &Expr,
+    op: &Operator,
+    value: &ScalarValue,
+    statistics: &[ColumnStatistics],
+) -> bool {
+    let (min, max) = match bounds(expr, statistics) {
+        Some(bounds) => bounds,
+        None => return true,
+    };
+    let (min_cmp, max_cmp) = match (min.compare(value), max.compare(value)) {
+        (Some(min_cmp), Some(max_cmp)) => (min_cmp, max_cmp),
+        _ => return true,
+    };
+    match op {
+        Operator::Eq => min_cmp != Ordering::Greater && max_cmp != Ordering::Less,
+        Operator::NotEq => min_cmp != Ordering::Equal || max_cmp != Ordering::Equal,
+        Operator::Lt => min_cmp == Ordering::Less,
+        Operator::LtEq => min_cmp != Ordering::Greater,
+        Operator::Gt => max_cmp == Ordering::Greater,
+        Operator::GtEq => max_cmp != Ordering::Less,
+        _ => true,
+    }
+}
+
+/// The minimum and maximum value of an expression that is a column, or an integer
+/// column that is cast to another numeric type, which preserves the order of values
+fn bounds<'a>(
+    expr: &Expr,
+    statistics: &'a [ColumnStatistics],
+) -> Option<(&'a ScalarValue, &'a ScalarValue)> {
+    match expr {
+        Expr::Column(i) => {
+            let column = statistics.get(*i)?;
+            Some((column.min_value.as_ref()?, column.max_value.as_ref()?))
+        }
+        Expr::Cast { expr, data_type } if is_numeric(data_type) => {
+            let (min, max) = bounds(expr, statistics)?;
+            if is_integer(min) && is_integer(max) {
+                Some((min, max))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The operator that gives the same result when its operands are swapped
+fn reverse(op: &Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq => Some(Operator::Eq),
+        Operator::NotEq => Some(Operator::NotEq),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        _ => None,
+    }
+}
+
+fn is_integer(value: &ScalarValue) -> bool {
+    match value {
+        ScalarValue::Int8(_)
+        | ScalarValue::Int16(_)
+        | ScalarValue::Int32(_)
+        | ScalarValue::Int64(_)
+        | ScalarValue::UInt8(_)
+        | ScalarValue::UInt16(_)
+        | ScalarValue::UInt32(_)
+        | ScalarValue::UInt64(_) => true,
+        _ => false,
+    }
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64
+        | DataType::Float32
+        | DataType::Float64 => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn column(min: ScalarValue, max: ScalarValue, null_count: usize) -> ColumnStatistics {
+        ColumnStatistics {
+            null_count: Some(null_count),
+            distinct_count: None,
+            min_value: Some(min),
+            max_value: Some(max),
+        }
+    }
+
+    fn binary(left: Expr, op: Operator, right: Expr) -> Expr {
+        Expr::BinaryExpr {
+            left: Arc::new(left),
+            op,
+            right: Arc::new(right),
+        }
+    }
+
+    #[test]
+    fn prune_comparisons() {
+        // a between 10 and 20, b between "m" and "p" without nulls
+        let statistics = vec![
+            column(ScalarValue::Int32(10), ScalarValue::Int32(20), 1),
+            column(
+                ScalarValue::Utf8("m".to_string()),
+                ScalarValue::Utf8("p".to_string()),
+                0,
+            ),
+        ];
+        let a = || Expr::Column(0);
+        let b = || Expr::Column(1);
+        let int = |v| Expr::Literal(ScalarValue::Int64(v));
+        let string = |v: &str| Expr::Literal(ScalarValue::Utf8(v.to_string()));
+
+        assert!(may_match(&binary(a(), Operator::Eq, int(15)), &statistics));
+        assert!(!may_match(&binary(a(), Operator::Eq, int(25)), &statistics));
+        assert!(!may_match(&binary(a(), Operator::Gt, int(20)), &statistics));
+        assert!(may_match(
+            &binary(a(), Operator::GtEq, int(20)),
+            &statistics
+        ));
+        assert!(!may_match(&binary(a(), Operator::Lt, int(10)), &statistics));
+        // the literal is on the left
+        assert!(!may_match(&binary(int(5), Operator::Gt, a()), &statistics));
+        assert!(may_match(&binary(int(11), Operator::Gt, a()), &statistics));
+        // the literal is an expression of literals
+        let sum = binary(int(15), Operator::Plus, int(6));
+        assert!(!may_match(&binary(a(), Operator::GtEq, sum), &statistics));
+
+        assert!(!may_match(
+            &binary(b(), Operator::Eq, string("z")),
+            &statistics
+        ));
+        assert!(may_match(
+            &binary(b(), Operator::LtEq, string("m")),
+            &statistics
+        ));
+
+        let cast_a = Expr::Cast {
+            expr: Arc::new(a()),
+            data_type: DataType::Float64,
+        };
+        let float = Expr::Literal(ScalarValue::Float64(20.5));
+        assert!(!may_match(
+            &binary(cast_a, Operator::GtEq, float),
+            &statistics
+        ));
+
+        let and = binary(
+            binary(a(), Operator::Gt, int(12)),
+            Operator::And,
+            binary(b(), Operator::Eq, string("a")),
+        );
+        assert!(!may_match(&and, &statistics));
+        let or = binary(
+            binary(a(), Operator::Gt, int(12)),
+            Operator::Or,
+            binary(b(), Operator::Eq, string("a")),
+        );
+        assert!(may_match(&or, &statistics));
+    }
+
+    #[test]
+    fn prune_lists_and_nulls() {
+        let statistics = vec![
+            column(ScalarValue::UInt32(1), ScalarValue::UInt32(5), 0),
+            column(ScalarValue::UInt32(1), ScalarValue::UInt32(5), 2),
+        ];
+        let int = |v| Arc::new(Expr::Literal(ScalarValue::Int64(v)));
+
+        let between = |low, high| Expr::Between {
+            expr: Arc::new(Expr::Column(0)),
+            negated: false,
+            low: int(low),
+            high: int(high),
+        };
+        assert!(may_match(&between(5, 9), &statistics));
+        assert!(!may_match(&between(6, 9), &statistics));
+
+        let in_list = |values: Vec<i64>| Expr::InList {
+            expr: Arc::new(Expr::Column(0)),
+            list: values
+                .into_iter()
+                .map(|v| Expr::Literal(ScalarValue::Int64(v)))
+                .collect(),
+            negated: false,
+        };
+        assert!(!may_match(&in_list(vec![0, 7]), &statistics));
+        assert!(may_match(&in_list(vec![0, 3]), &statistics));
+
+        assert!(!may_match(
+            &Expr::IsNull(Arc::new(Expr::Column(0))),
+            &statistics
+        ));
+        assert!(may_match(
+            &Expr::IsNull(Arc::new(Expr::Column(1))),
+            &statistics
+        ));
+
+        // other expressions are not used for pruning
+        let not = Expr::Not(Arc::new(between(5, 9)));
+        assert!(may_match(&not, &statistics));
+    }
+}
@@ -0,0 +1,347 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Collects statistics about a table by scanning all of its data, as done by
+//! `ANALYZE TABLE`
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::datasource::{ColumnStatistics, Statistics, TableProvider};
+use crate::error::Result;
+use crate::execution::physical_plan::common;
+use crate::logicalplan::ScalarValue;
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, Int8Array, StringArray, UInt16Array, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use arrow::compute;
+use arrow::datatypes::{DataType, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// The number of bits of the hash of a value used to select a register of the distinct
+/// counter. The standard error of the estimate is `1.04 / sqrt(2^precision)`.
+const DISTINCT_COUNTER_PRECISION: u32 = 12;
+
+/// Scan all partitions of a table and compute its row count, size and per-column
+/// statistics
+pub fn collect_statistics(
+    provider: &dyn TableProvider,
+    batch_size: usize,
+) -> Result<Statistics> {
+    let mut columns: Vec<ColumnStatisticsBuilder> = provider
+        .schema()
+        .fields()
+        .iter()
+        .map(|_| ColumnStatisticsBuilder::new())
+        .collect();
+    let mut num_rows = 0;
+    let mut total_byte_size = 0;
+
+    for partition in provider.scan(&None, batch_size)? {
+        let mut it = partition.lock().unwrap();
+        while let Some(batch) = it.next()? {
+            num_rows += batch.num_rows();
+            total_byte_size += common::batch_byte_size(&batch);
+            for (column, builder) in batch.columns().iter().zip(columns.iter_mut()) {
+                builder.update(column);
+            }
+        }
+    }
+
+    Ok(Statistics {
+        num_rows: Some(num_rows),
+        total_byte_size: Some(total_byte_size),
+        column_statistics: Some(columns.into_iter().map(|c| c.build()).collect()),
+    })
+}
+
+/// Compute the null count and the minimum and maximum values of each column of a
+/// sequence of batches, without estimating the number of distinct values
+pub fn min_max_statistics(
+    schema: &Schema,
+    batches: &[RecordBatch],
+) -> Vec<ColumnStatistics> {
+    let mut columns: Vec<ColumnStatisticsBuilder> = schema
+        .fields()
+        .iter()
+        .map(|_| ColumnStatisticsBuilder {
+            null_count: 0,
+            min_value: None,
+            max_value: None,
+            distinct: None,
+        })
+        .collect();
+    for batch in batches {
+        for (column, builder) in batch.columns().iter().zip(columns.iter_mut()) {
+            builder.update(column);
+        }
+    }
+    columns.into_iter().map(|c| c.build()).collect()
+}
+
+/// Update the minimum, maximum and distinct values of a column from a primitive array
+macro_rules! primitive_statistics {
+    ($SELF:ident, $ARRAY:ident, $ARRAY_TYPE:ident, $SCALAR:ident, $KEY:expr) => {{
+        let array = $ARRAY.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+        let min = match ($SELF.min_value.take(), compute::min(array)) {
+            (Some(ScalarValue::$SCALAR(a)), Some(b)) => Some(if a < b { a } else { b }),
+            (Some(ScalarValue::$SCALAR(a)), None) => Some(a),
+            (_, b) => b,
+        };
+        $SELF.min_value = min.map(ScalarValue::$SCALAR);
+        let max = match ($SELF.max_value.take(), compute::max(array)) {
+            (Some(ScalarValue::$SCALAR(a)), Some(b)) => Some(if a > b { a } else { b }),
+            (Some(ScalarValue::$SCALAR(a)), None) => Some(a),
+            (_, b) => b,
+        };
+        $SELF.max_value = max.map(ScalarValue::$SCALAR);
+        if let Some(ref mut distinct) = $SELF.distinct {
+            for i in 0..array.len() {
+                if !array.is_null(i) {
+                    distinct.insert(($KEY)(array.value(i)));
+                }
+            }
+        }
+    }};
+}
+
+/// Accumulates the statistics of a single column over a sequence of arrays
+struct ColumnStatisticsBuilder {
+    null_count: usize,
+    min_value: Option<ScalarValue>,
+    max_value: Option<ScalarValue>,
+    /// The distinct values seen so far, or None if the type of the column is not
+    /// supported
+    distinct: Option<DistinctCounter>,
+}
+
+impl ColumnStatisticsBuilder {
+    fn new() -> Self {
+        Self {
+            null_count: 0,
+            min_value: None,
+            max_value: None,
+            distinct: Some(DistinctCounter::new(DISTINCT_COUNTER_PRECISION)),
+        }
+    }
+
+    fn update(&mut self, array: &ArrayRef) {
+        self.null_count += array.null_count();
+        match array.data_type() {
+            DataType::Int8 => primitive_statistics!(self, array, Int8Array, Int8, |v| v),
+            DataType::Int16 => {
+                primitive_statistics!(self, array, Int16Array, Int16, |v| v)
+            }
+            DataType::Int32 => {
+                primitive_statistics!(self, array, Int32Array, Int32, |v| v)
+            }
+            DataType::Int64 => {
+                primitive_statistics!(self, array, Int64Array, Int64, |v| v)
+            }
+            DataType::UInt8 => {
+                primitive_statistics!(self, array, UInt8Array, UInt8, |v| v)
+            }
+            DataType::UInt16 => {
+                primitive_statistics!(self, array, UInt16Array, UInt16, |v| v)
+            }
+            DataType::UInt32 => {
+                primitive_statistics!(self, array, UInt32Array, UInt32, |v| v)
+            }
+            DataType::UInt64 => {
+                primitive_statistics!(self, array, UInt64Array, UInt64, |v| v)
+            }
+            // floats are not hashable, so their bit patterns are counted instead
+            DataType::Float32 => {
+                let key = |v: f32| v.to_bits();
+                primitive_statistics!(self, array, Float32Array, Float32, key)
+            }
+            DataType::Float64 => {
+                let key = |v: f64| v.to_bits();
+                primitive_statistics!(self, array, Float64Array, Float64, key)
+            }
+            DataType::Boolean => {
+                let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+                for i in 0..array.len() {
+                    if array.is_null(i) {
+                        continue;
+                    }
+                    let value = array.value(i);
+                    self.min_value = Some(ScalarValue::Boolean(match self.min_value {
+                        Some(ScalarValue::Boolean(min)) => min && value,
+                        _ => value,
+                    }));
+                    self.max_value = Some(ScalarValue::Boolean(match self.max_value {
+                        Some(ScalarValue::Boolean(max)) => max || value,
+                        _ => value,
+                    }));
+                    if let Some(ref mut distinct) = self.distinct {
+                        distinct.insert(value);
+                    }
+                }
+            }
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                for i in 0..array.len() {
+                    if array.is_null(i) {
+                        continue;
+                    }
+                    let value = array.value(i);
+                    match self.min_value {
+                        Some(ScalarValue::Utf8(ref min)) if min.as_str() <= value => {}
+                        _ => self.min_value = Some(ScalarValue::Utf8(value.to_string())),
+                    }
+                    match self.max_value {
+                        Some(ScalarValue::Utf8(ref max)) if max.as_str() >= value => {}
+                        _ => self.max_value = Some(ScalarValue::Utf8(value.to_string())),
+                    }
+                    if let Some(ref mut distinct) = self.distinct {
+                        distinct.insert(value);
+                    }
+                }
+            }
+            // only the null count is known for other types
+            _ => self.distinct = None,
+        }
+    }
+
+    fn build(self) -> ColumnStatistics {
+        ColumnStatistics {
+            null_count: Some(self.null_count),
+            distinct_count: self.distinct.map(|d| d.estimate()),
+            min_value: self.min_value,
+            max_value: self.max_value,
+        }
+    }
+}
+
+/// Estimates the number of distinct values in a column using a HyperLogLog sketch, so
+/// that memory usage does not depend on the number of values
+struct DistinctCounter {
+    precision: u32,
+    /// For each register, the maximum number of leading zeros seen in the hashes that
+    /// map to it, plus one
+    registers: Vec<u8>,
+}
+
+impl DistinctCounter {
+    fn new(precision: u32) -> Self {
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    fn insert<T: Hash>(&mut self, value: T) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // the first bits select the register and the position of the first set bit in
+        // the remaining bits is recorded, bounded by a sentinel bit
+        let index = (hash >> (64 - self.precision)) as usize;
+        let remaining = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        // small cardinalities are estimated more accurately from the number of empty
+        // registers
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as usize
+        } else {
+            estimate.round() as usize
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use arrow::datatypes::Field;
+    use std::sync::Arc;
+
+    #[test]
+    fn collect_table_statistics() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let batch1 = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(3), None, Some(-1)])),
+                Arc::new(StringArray::from(vec!["x", "y", "x"])),
+            ],
+        )?;
+        let batch2 = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(7), Some(3)])),
+                Arc::new(StringArray::from(vec!["a", "z"])),
+            ],
+        )?;
+        let table = MemTable::new(schema, vec![batch1, batch2])?;
+
+        let stats = collect_statistics(&table, 1024)?;
+        assert_eq!(stats.num_rows, Some(5));
+
+        let columns = stats.column_statistics.unwrap();
+        assert_eq!(
+            columns[0],
+            ColumnStatistics {
+                null_count: Some(1),
+                distinct_count: Some(3),
+                min_value: Some(ScalarValue::Int32(-1)),
+                max_value: Some(ScalarValue::Int32(7)),
+            }
+        );
+        assert_eq!(
+            columns[1],
+            ColumnStatistics {
+                null_count: Some(0),
+                distinct_count: Some(4),
+                min_value: Some(ScalarValue::Utf8("a".to_string())),
+                max_value: Some(ScalarValue::Utf8("z".to_string())),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn estimate_distinct_count() {
+        let mut counter = DistinctCounter::new(DISTINCT_COUNTER_PRECISION);
+        for i in 0..100_000 {
+            // every value is inserted twice
+            counter.insert(i % 50_000);
+        }
+        let estimate = counter.estimate() as f64;
+        assert!((estimate - 50_000.0).abs() / 50_000.0 < 0.05);
+    }
+}
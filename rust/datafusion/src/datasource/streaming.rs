@@ -0,0 +1,476 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Streaming data source, whose data is ingested from files while the table is
+//! registered with an execution context, so that queries see the files that arrived
+//! before they were executed.
+
+use std::collections::HashSet;
+#[cfg(feature = "csv")]
+use std::fs::File;
+use std::sync::{Arc, Mutex};
+
+use arrow::array::ArrayRef;
+#[cfg(feature = "csv")]
+use arrow::csv;
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+#[cfg(feature = "parquet")]
+use crate::datasource::parquet::ParquetTable;
+use crate::datasource::{MemTable, ScanResult, Statistics, TableProvider};
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::common;
+
+/// The data of a streaming table, which is shared with its ingesters
+struct StreamingTableState {
+    schema: Arc<Schema>,
+    /// The batches of each partition, one partition per ingested file
+    partitions: Vec<Vec<RecordBatch>>,
+}
+
+/// Table whose partitions are appended by `FileIngester`s while it is registered with
+/// an execution context
+pub struct StreamingTable {
+    state: Arc<Mutex<StreamingTableState>>,
+}
+
+impl StreamingTable {
+    /// Create an empty streaming table with the given schema
+    pub fn new(schema: Arc<Schema>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(StreamingTableState {
+                schema,
+                partitions: vec![],
+            })),
+        }
+    }
+
+    /// Create an ingester that appends the files of the given format to this table
+    pub fn ingester(&self, format: IngestFormat) -> FileIngester {
+        FileIngester {
+            state: self.state.clone(),
+            format,
+            batch_size: 1024 * 1024,
+            ingested: HashSet::new(),
+            on_schema_drift: None,
+        }
+    }
+
+    /// The number of partitions of the table, which is the number of ingested files
+    pub fn partition_count(&self) -> usize {
+        self.state.lock().unwrap().partitions.len()
+    }
+}
+
+impl TableProvider for StreamingTable {
+    fn schema(&self) -> Arc<Schema> {
+        self.state.lock().unwrap().schema.clone()
+    }
+
+    fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        batch_size: usize,
+    ) -> Result<Vec<ScanResult>> {
+        let state = self.state.lock().unwrap();
+        if state.partitions.is_empty() {
+            return MemTable::new(state.schema.clone(), vec![])?
+                .scan(projection, batch_size);
+        }
+        let mut iterators = vec![];
+        for partition in &state.partitions {
+            let table = MemTable::new(state.schema.clone(), partition.clone())?;
+            iterators.extend(table.scan(projection, batch_size)?);
+        }
+        Ok(iterators)
+    }
+
+    fn statistics(&self) -> Statistics {
+        let state = self.state.lock().unwrap();
+        let batches = state.partitions.iter().flatten();
+        Statistics {
+            num_rows: Some(batches.clone().map(|batch| batch.num_rows()).sum()),
+            total_byte_size: Some(batches.map(common::batch_byte_size).sum()),
+            column_statistics: None,
+        }
+    }
+}
+
+/// The format of the files that are ingested into a streaming table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IngestFormat {
+    /// CSV files, whose schema is inferred from each file
+    Csv {
+        /// Whether the first line of each file holds the names of the columns
+        has_header: bool,
+    },
+    /// Parquet files
+    Parquet,
+}
+
+impl IngestFormat {
+    /// The extension of the files of this format
+    fn extension(&self) -> &'static str {
+        match self {
+            IngestFormat::Csv { .. } => ".csv",
+            IngestFormat::Parquet => ".parquet",
+        }
+    }
+}
+
+/// A file whose schema differs from the schema of the table it is ingested into
+#[derive(Debug)]
+pub struct SchemaDrift<'a> {
+    /// The path of the file
+    pub path: &'a str,
+    /// The schema of the table
+    pub table_schema: &'a Schema,
+    /// The schema of the file
+    pub file_schema: &'a Schema,
+}
+
+/// How to handle a file whose schema differs from the schema of the table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SchemaDriftAction {
+    /// Fail to ingest the file, returning an error
+    Fail,
+    /// Do not ingest the file
+    Skip,
+    /// Evolve the schema of the table to include the columns of the file. Columns that
+    /// are missing from the file or from the data ingested so far are null, and
+    /// columns that have a different type in the file cause an error.
+    Evolve,
+}
+
+/// Callback that decides how to handle a file whose schema differs from the schema of
+/// the table
+pub type SchemaDriftCallback = Box<dyn Fn(&SchemaDrift) -> SchemaDriftAction + Send>;
+
+/// The outcome of polling a directory for files that were not ingested yet
+#[derive(Debug)]
+pub struct PollResult {
+    /// The number of files that were ingested
+    pub ingested: usize,
+    /// The paths of the files that failed to be ingested, along with their errors. These
+    /// files are tried again by the next poll, since they may not have been completely
+    /// written yet.
+    pub failed: Vec<(String, ExecutionError)>,
+}
+
+/// Ingests files into the partitions of a `StreamingTable`, either from given paths or
+/// by polling a directory for files that were not ingested yet
+pub struct FileIngester {
+    state: Arc<Mutex<StreamingTableState>>,
+    format: IngestFormat,
+    batch_size: usize,
+    /// The paths of the files that were ingested
+    ingested: HashSet<String>,
+    on_schema_drift: Option<SchemaDriftCallback>,
+}
+
+impl FileIngester {
+    /// Set the number of rows in the batches that files are read into
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the callback that decides how to handle files whose schema differs from the
+    /// schema of the table. Without a callback, such files fail to be ingested.
+    pub fn with_schema_drift_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&SchemaDrift) -> SchemaDriftAction + Send + 'static,
+    {
+        self.on_schema_drift = Some(Box::new(callback));
+        self
+    }
+
+    /// Ingest the files of the directory, or its subdirectories, that were not ingested
+    /// yet in the order of their paths. A file that fails to be ingested does not stop
+    /// the remaining files from being ingested.
+    pub fn poll_directory(&mut self, dir: &str) -> Result<PollResult> {
+        let mut filenames = vec![];
+        common::build_file_list(dir, &mut filenames, self.format.extension())?;
+        filenames.sort();
+        let mut result = PollResult {
+            ingested: 0,
+            failed: vec![],
+        };
+        for filename in filenames {
+            if self.ingested.contains(&filename) {
+                continue;
+            }
+            match self.ingest_file(&filename) {
+                Ok(true) => result.ingested += 1,
+                Ok(false) => {}
+                Err(e) => result.failed.push((filename, e)),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Ingest a file as a new partition of the table. Returns whether the file was
+    /// ingested, which it is not if it was skipped because of its schema.
+    pub fn ingest_file(&mut self, path: &str) -> Result<bool> {
+        let (file_schema, batches) = self.read_file(path)?;
+
+        let mut state = self.state.lock().unwrap();
+        let batches = if file_schema.as_ref() == state.schema.as_ref() {
+            batches
+        } else {
+            let drift = SchemaDrift {
+                path,
+                table_schema: &state.schema,
+                file_schema: &file_schema,
+            };
+            let action = match &self.on_schema_drift {
+                Some(callback) => callback(&drift),
+                None => SchemaDriftAction::Fail,
+            };
+            match action {
+                SchemaDriftAction::Fail => {
+                    return Err(ExecutionError::General(format!(
+                        "Schema of file {} does not match the schema of the table",
+                        path
+                    )))
+                }
+                SchemaDriftAction::Skip => {
+                    self.ingested.insert(path.to_string());
+                    return Ok(false);
+                }
+                SchemaDriftAction::Evolve => {
+                    let schema = Arc::new(evolve_schema(&state.schema, &file_schema)?);
+                    let partitions = state
+                        .partitions
+                        .iter()
+                        .map(|partition| {
+                            partition
+                                .iter()
+                                .map(|batch| conform_batch(batch, &schema))
+                                .collect::<Result<Vec<_>>>()
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    state.schema = schema;
+                    state.partitions = partitions;
+                    batches
+                        .iter()
+                        .map(|batch| conform_batch(batch, &state.schema))
+                        .collect::<Result<Vec<_>>>()?
+                }
+            }
+        };
+
+        state.partitions.push(batches);
+        self.ingested.insert(path.to_string());
+        Ok(true)
+    }
+
+    /// Read the schema and the batches of a file
+    fn read_file(&self, path: &str) -> Result<(Arc<Schema>, Vec<RecordBatch>)> {
+        let mut batches = vec![];
+        match self.format {
+            #[cfg(feature = "csv")]
+            IngestFormat::Csv { has_header } => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .has_headers(has_header)
+                    .infer_schema(None)
+                    .with_batch_size(self.batch_size)
+                    .build(File::open(path)?)?;
+                while let Some(batch) = reader.next()? {
+                    batches.push(batch);
+                }
+                Ok((reader.schema(), batches))
+            }
+            #[cfg(feature = "parquet")]
+            IngestFormat::Parquet => {
+                let table = ParquetTable::try_new(path)?;
+                for partition in table.scan(&None, self.batch_size)? {
+                    let mut partition = partition.lock().unwrap();
+                    while let Some(batch) = partition.next()? {
+                        batches.push(batch);
+                    }
+                }
+                Ok((table.schema(), batches))
+            }
+            #[allow(unreachable_patterns)]
+            format => Err(ExecutionError::NotImplemented(format!(
+                "{:?} files cannot be ingested without the corresponding feature",
+                format
+            ))),
+        }
+    }
+}
+
+/// Merge the schema of a table with the schema of a file, appending the columns that
+/// are new in the file. Columns that are missing from either schema become nullable.
+fn evolve_schema(table_schema: &Schema, file_schema: &Schema) -> Result<Schema> {
+    let mut fields = vec![];
+    for field in table_schema.fields() {
+        match file_schema.field_with_name(field.name()) {
+            Ok(file_field) if file_field.data_type() != field.data_type() => {
+                return Err(ExecutionError::General(format!(
+                    "Cannot evolve column {} of type {:?} to type {:?}",
+                    field.name(),
+                    field.data_type(),
+                    file_field.data_type()
+                )))
+            }
+            Ok(file_field) => fields.push(Field::new(
+                field.name(),
+                field.data_type().clone(),
+                field.is_nullable() || file_field.is_nullable(),
+            )),
+            Err(_) => {
+                fields.push(Field::new(field.name(), field.data_type().clone(), true))
+            }
+        }
+    }
+    for field in file_schema.fields() {
+        if table_schema.field_with_name(field.name()).is_err() {
+            fields.push(Field::new(field.name(), field.data_type().clone(), true));
+        }
+    }
+    Ok(Schema::new(fields))
+}
+
+/// Convert a batch to a schema whose columns are matched by name, filling the columns
+/// that are missing from the batch with nulls
+fn conform_batch(batch: &RecordBatch, schema: &Arc<Schema>) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| match batch.schema().index_of(field.name()) {
+            Ok(i) => Ok(batch.column(i).clone()),
+            Err(_) => common::new_null_array(field.data_type(), batch.num_rows()),
+        })
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::context::ExecutionContext;
+    use crate::test;
+    use arrow::datatypes::DataType;
+    use std::fs;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    fn write_file(dir: &TempDir, name: &str, content: &str) -> Result<String> {
+        let path = dir.path().join(name);
+        File::create(&path)?.write_all(content.as_bytes())?;
+        Ok(path.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn poll_directory() -> Result<()> {
+        let dir = TempDir::new("poll_directory")?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let table = StreamingTable::new(schema);
+        let mut ingester = table.ingester(IngestFormat::Csv { has_header: true });
+        let path = dir.path().to_str().unwrap();
+
+        write_file(&dir, "1.csv", "a,b\n1,x\n2,y\n")?;
+        assert_eq!(ingester.poll_directory(path)?.ingested, 1);
+        // files are only ingested once
+        write_file(&dir, "2.csv", "a,b\n3,z\n")?;
+        fs::write(dir.path().join("ignored.txt"), "a,b\n4,w\n")?;
+        assert_eq!(ingester.poll_directory(path)?.ingested, 1);
+        assert_eq!(table.partition_count(), 2);
+
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("t", Box::new(table));
+        let results = ctx.sql("SELECT a, b FROM t ORDER BY a", 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1,x", "2,y", "3,z"]);
+
+        // files that arrive after the table was registered are visible to later queries
+        write_file(&dir, "3.csv", "a,b\n5,v\n")?;
+        assert_eq!(ingester.poll_directory(path)?.ingested, 1);
+        let results = ctx.sql("SELECT COUNT(a) FROM t", 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["4"]);
+        Ok(())
+    }
+
+    #[test]
+    fn poll_directory_after_rejected_file() -> Result<()> {
+        let dir = TempDir::new("poll_directory_after_rejected_file")?;
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let table = StreamingTable::new(schema);
+        let mut ingester = table.ingester(IngestFormat::Csv { has_header: true });
+        let path = dir.path().to_str().unwrap();
+
+        // the file with a different schema is rejected, but the files after it are
+        // still ingested
+        let rejected = write_file(&dir, "1.csv", "a,c\n1,2.5\n")?;
+        write_file(&dir, "2.csv", "a\n2\n")?;
+        let result = ingester.poll_directory(path)?;
+        assert_eq!(result.ingested, 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, rejected);
+        assert_eq!(table.partition_count(), 1);
+
+        // the rejected file is tried again, without blocking later files
+        write_file(&dir, "3.csv", "a\n3\n")?;
+        let result = ingester.poll_directory(path)?;
+        assert_eq!(result.ingested, 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(table.partition_count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn schema_drift() -> Result<()> {
+        let dir = TempDir::new("schema_drift")?;
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let table = StreamingTable::new(schema);
+        let first = write_file(&dir, "1.csv", "a\n1\n")?;
+        let second = write_file(&dir, "2.csv", "a,c\n2,3.5\n")?;
+
+        // without a callback, files with a different schema are not ingested
+        let mut ingester = table.ingester(IngestFormat::Csv { has_header: true });
+        ingester.ingest_file(&first)?;
+        assert!(ingester.ingest_file(&second).is_err());
+
+        let mut ingester = table
+            .ingester(IngestFormat::Csv { has_header: true })
+            .with_schema_drift_callback(|_| SchemaDriftAction::Skip);
+        assert!(!ingester.ingest_file(&second)?);
+        assert_eq!(table.partition_count(), 1);
+
+        let mut ingester = table
+            .ingester(IngestFormat::Csv { has_header: true })
+            .with_schema_drift_callback(|drift| {
+                assert_eq!(drift.file_schema.fields().len(), 2);
+                SchemaDriftAction::Evolve
+            });
+        assert!(ingester.ingest_file(&second)?);
+        let schema = table.schema();
+        assert_eq!(schema.fields().len(), 2);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("t", Box::new(table));
+        let results = ctx.sql("SELECT a, c FROM t ORDER BY a", 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1,NULL", "2,3.5"]);
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ use std::io::Error;
 use std::result;
 
 use arrow::error::ArrowError;
+#[cfg(feature = "parquet")]
 use parquet::errors::ParquetError;
 
 use sqlparser::sqlparser::ParserError;
@@ -35,6 +36,7 @@ pub enum ExecutionError {
     /// Wraps an error from the Arrow crate
     ArrowError(ArrowError),
     /// Wraps an error from the Parquet crate
+    #[cfg(feature = "parquet")]
     ParquetError(ParquetError),
     /// I/O error
     IoError(Error),
@@ -50,6 +52,11 @@ pub enum ExecutionError {
     InternalError(String),
     /// Query engine execution error
     ExecutionError(String),
+    /// The query was cancelled, for example because it exceeded its maximum execution
+    /// time
+    Cancelled(String),
+    /// An error of the execution of the query with the given id
+    Query(u64, Box<ExecutionError>),
 }
 
 impl From<Error> for ExecutionError {
@@ -76,6 +83,7 @@ impl From<ArrowError> for ExecutionError {
     }
 }
 
+#[cfg(feature = "parquet")]
 impl From<ParquetError> for ExecutionError {
     fn from(e: ParquetError) -> Self {
         ExecutionError::ParquetError(e)
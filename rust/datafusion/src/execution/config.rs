@@ -0,0 +1,139 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Configuration of an execution context that can be changed at runtime with
+//! `SET name = value` statements and inspected with `SHOW name` or `SHOW ALL`
+
+use crate::error::{ExecutionError, Result};
+
+/// Default number of rows in the batches that frontends execute queries with
+pub const DEFAULT_BATCH_SIZE: usize = 1_048_576;
+
+/// Default maximum estimated size in bytes of the left input of a join for its hash
+/// table to be broadcast to every partition of the right input
+pub const DEFAULT_BROADCAST_JOIN_THRESHOLD: usize = 10 * 1024 * 1024;
+
+/// Configuration variables of an execution context
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionConfig {
+    /// Number of rows in the batches that frontends such as the REPL pass to
+    /// `ExecutionContext::sql`
+    pub batch_size: usize,
+    /// Number of partitions that the inputs of a partitioned join are repartitioned
+    /// into, or 0 to use the larger number of partitions of the two inputs
+    pub target_partitions: usize,
+    /// Maximum estimated size in bytes of the left input of a join for the join to be
+    /// executed by broadcasting its hash table instead of repartitioning both inputs
+    pub broadcast_join_threshold: usize,
+    /// Maximum time in milliseconds that a query can run before it is cancelled with
+    /// an error, or 0 for no limit. The time is measured from the creation of the
+    /// physical plan and checked each time a batch is read from a table.
+    pub max_execution_time_ms: usize,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            target_partitions: 0,
+            broadcast_join_threshold: DEFAULT_BROADCAST_JOIN_THRESHOLD,
+            max_execution_time_ms: 0,
+        }
+    }
+}
+
+impl ExecutionConfig {
+    /// Create a configuration with the default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The names and values of all variables, in alphabetical order
+    pub fn variables(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("batch_size", self.batch_size.to_string()),
+            (
+                "broadcast_join_threshold",
+                self.broadcast_join_threshold.to_string(),
+            ),
+            (
+                "max_execution_time_ms",
+                self.max_execution_time_ms.to_string(),
+            ),
+            ("target_partitions", self.target_partitions.to_string()),
+        ]
+    }
+
+    /// The value of a variable, or `None` if there is no variable with this name
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.variables()
+            .into_iter()
+            .find(|(variable, _)| variable.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Set a variable by name from the text of its value
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        let variable = match name.to_lowercase().as_str() {
+            "batch_size" => &mut self.batch_size,
+            "target_partitions" => &mut self.target_partitions,
+            "broadcast_join_threshold" => &mut self.broadcast_join_threshold,
+            "max_execution_time_ms" => &mut self.max_execution_time_ms,
+            _ => {
+                return Err(ExecutionError::General(format!(
+                    "Unknown configuration variable '{}'",
+                    name
+                )))
+            }
+        };
+        let value = value.parse::<usize>().map_err(|_| {
+            ExecutionError::General(format!(
+                "Invalid value '{}' for configuration variable '{}'",
+                value, name
+            ))
+        })?;
+        if value == 0 && name.eq_ignore_ascii_case("batch_size") {
+            return Err(ExecutionError::General(
+                "The batch size must be positive".to_string(),
+            ));
+        }
+        *variable = value;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get_variables() -> Result<()> {
+        let mut config = ExecutionConfig::new();
+        config.set("batch_size", "8192")?;
+        config.set("TARGET_PARTITIONS", "4")?;
+        assert_eq!(config.batch_size, 8192);
+        assert_eq!(config.target_partitions, 4);
+        assert_eq!(config.get("Batch_Size"), Some("8192".to_string()));
+        assert_eq!(config.get("missing"), None);
+
+        assert!(config.set("missing", "1").is_err());
+        assert!(config.set("batch_size", "many").is_err());
+        assert!(config.set("batch_size", "0").is_err());
+        assert_eq!(config.batch_size, 8192);
+        Ok(())
+    }
+}
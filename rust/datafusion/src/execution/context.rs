@@ -18,48 +18,148 @@
 //! ExecutionContext contains methods for registering data sources and executing queries
 
 use std::collections::HashMap;
+#[cfg(feature = "csv")]
 use std::fs;
+#[cfg(feature = "csv")]
 use std::path::Path;
 use std::string::String;
 use std::sync::Arc;
-use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use arrow::array::{ArrayRef, StringArray};
+use arrow::compute::{cast, concat, Collation, SortOptions};
+#[cfg(feature = "csv")]
 use arrow::csv;
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 
+#[cfg(feature = "csv")]
 use crate::datasource::csv::CsvFile;
+use crate::datasource::information_schema::{self, InformationSchemaTable};
+use crate::datasource::json::NdJsonFile;
+#[cfg(feature = "parquet")]
 use crate::datasource::parquet::ParquetTable;
-use crate::datasource::TableProvider;
+use crate::datasource::statistics::collect_statistics;
+use crate::datasource::{ScanFilter, TableProvider};
 use crate::error::{ExecutionError, Result};
+use crate::execution::config::ExecutionConfig;
+use crate::execution::export::write_plan;
+use crate::execution::memory_manager::MemoryManager;
 use crate::execution::physical_plan::common;
-use crate::execution::physical_plan::datasource::DatasourceExec;
+use crate::execution::physical_plan::cross_join::CrossJoinExec;
+#[cfg(feature = "csv")]
+use crate::execution::physical_plan::csv::CsvReadOptions;
+use crate::execution::physical_plan::datetime_expressions::register_datetime_functions;
+use crate::execution::physical_plan::deduplicate::DeduplicateExec;
+use crate::execution::physical_plan::empty::EmptyExec;
 use crate::execution::physical_plan::expressions::{
-    Alias, Avg, BinaryExpr, CastExpr, Column, Count, Literal, Max, Min, Sum,
+    col, histogram_buckets, reservoir_sample_size, Alias, Avg, BinaryExpr, BoolAnd,
+    BoolOr, CaseExpr, CastExpr, Column, Count, ElementAtExpr, Histogram, InListExpr,
+    IsNotNullExpr, IsNullExpr, LikeExpr, Literal, Max, Min, MinMaxBy, ReservoirSample,
+    Sum,
 };
 use crate::execution::physical_plan::hash_aggregate::HashAggregateExec;
+use crate::execution::physical_plan::hash_join::{HashJoinExec, PartitionMode};
 use crate::execution::physical_plan::limit::LimitExec;
 use crate::execution::physical_plan::math_expressions::register_math_functions;
 use crate::execution::physical_plan::merge::MergeExec;
 use crate::execution::physical_plan::projection::ProjectionExec;
+use crate::execution::physical_plan::repartition::RepartitionExec;
+use crate::execution::physical_plan::scheduled::ScheduledExec;
 use crate::execution::physical_plan::selection::SelectionExec;
+use crate::execution::physical_plan::sort::SortExec;
+use crate::execution::physical_plan::string_expressions::register_string_functions;
+use crate::execution::physical_plan::timeout::TimeoutExec;
 use crate::execution::physical_plan::udf::{ScalarFunction, ScalarFunctionExpr};
-use crate::execution::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr};
+use crate::execution::physical_plan::union::UnionExec;
+use crate::execution::physical_plan::window::{
+    AggregateWindowExpr, RankExpr, RankType, ValueExpr, ValueType, WindowAggExec,
+};
+use crate::execution::physical_plan::{
+    AggregateExpr, ExecutionPlan, PhysicalExpr, PhysicalSortExpr, WindowExpr,
+};
+use crate::execution::scheduler::Scheduler;
 use crate::execution::table_impl::TableImpl;
 use crate::logicalplan::*;
+use crate::optimizer::decorrelate_subqueries::DecorrelateSubqueries;
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::projection_push_down::ProjectionPushDown;
 use crate::optimizer::resolve_columns::ResolveColumnsRule;
 use crate::optimizer::type_coercion::TypeCoercionRule;
-use crate::sql::parser::{DFASTNode, DFParser, FileType};
-use crate::sql::planner::{SchemaProvider, SqlToRel};
+use crate::optimizer::utils::cast_untyped_null_columns;
+use crate::sql::parser::{DFASTNode, DFParser, ExplainFormat, FileType, IdentifierCase};
+use crate::sql::planner::{convert_decimal_type, NullOrdering, SchemaProvider, SqlToRel};
 use crate::table::Table;
 use sqlparser::sqlast::{SQLColumnDef, SQLType};
 
+pub use crate::execution::config::DEFAULT_BROADCAST_JOIN_THRESHOLD;
+
+/// A hook that is called with each SQL statement that is executed by
+/// `ExecutionContext::sql` before it is planned, so that embedders can extend the SQL
+/// dialect with statements of their own, such as `CACHE TABLE t`
+pub trait StatementHook: Send + Sync {
+    /// Handle a SQL statement, where `statement` is the parsed statement or `None` if
+    /// the statement is not valid DataFusion SQL. Returns the results of the statement
+    /// if this hook handled it, or `None` to continue with the next hook and finally
+    /// with planning the statement.
+    fn handle(
+        &self,
+        ctx: &mut ExecutionContext,
+        sql: &str,
+        statement: Option<&DFASTNode>,
+    ) -> Result<Option<Vec<RecordBatch>>>;
+}
+
+/// A query that is executed by `ExecutionContext::collect_plan`, as reported to query
+/// listeners
+#[derive(Debug, Clone)]
+pub struct QueryInfo {
+    /// The id of the query, which is unique within its execution context
+    pub id: u64,
+    /// The fingerprint of the optimized logical plan
+    pub fingerprint: u64,
+    /// The optimized logical plan
+    pub plan: LogicalPlan,
+}
+
+/// A listener that is notified when queries start and end, for example to write audit
+/// logs or to track slow queries
+pub trait QueryListener: Send + Sync {
+    /// Called before the execution of a query starts
+    fn on_start(&self, _query: &QueryInfo) {}
+    /// Called after the execution of a query ended, with the time it took and either
+    /// the number of rows of its result or the error that it failed with
+    fn on_end(
+        &self,
+        _query: &QueryInfo,
+        _elapsed: Duration,
+        _result: std::result::Result<usize, &ExecutionError>,
+    ) {
+    }
+}
+
 /// Execution context for registering data sources and executing queries
 pub struct ExecutionContext {
     datasources: HashMap<String, Box<dyn TableProvider>>,
     scalar_functions: HashMap<String, Box<ScalarFunction>>,
+    statement_hooks: Vec<Arc<dyn StatementHook>>,
+    query_listeners: Vec<Arc<dyn QueryListener>>,
+    config: ExecutionConfig,
+    memory_manager: Arc<MemoryManager>,
+    scheduler: Arc<Scheduler>,
+    /// The id of the query whose physical plan was created last, which identifies the
+    /// query to the scheduler and to query listeners
+    query_id: u64,
+    /// The values of the uncorrelated scalar subqueries and of the deterministic scalar
+    /// functions of constants of the current query, which are evaluated once when its
+    /// physical plan is created and planned as literals
+    constant_values: HashMap<Expr, ScalarValue>,
+    /// The time after which the current query is cancelled, if its execution time is
+    /// limited, which is computed once when its physical plan is created
+    deadline: Option<Arc<Instant>>,
+    null_ordering: NullOrdering,
+    collation: Collation,
+    identifier_case: IdentifierCase,
 }
 
 impl ExecutionContext {
@@ -68,19 +168,72 @@ impl ExecutionContext {
         let mut ctx = Self {
             datasources: HashMap::new(),
             scalar_functions: HashMap::new(),
+            statement_hooks: vec![],
+            query_listeners: vec![],
+            config: ExecutionConfig::new(),
+            memory_manager: Arc::new(MemoryManager::new(usize::max_value())),
+            scheduler: Arc::new(Scheduler::default()),
+            query_id: 0,
+            constant_values: HashMap::new(),
+            deadline: None,
+            null_ordering: NullOrdering::NullsLargest,
+            collation: Collation::Binary,
+            identifier_case: IdentifierCase::Preserve,
         };
         register_math_functions(&mut ctx);
+        register_string_functions(&mut ctx);
+        register_datetime_functions(&mut ctx);
         ctx
     }
 
     /// Execute a SQL query and produce a Relation (a schema-aware iterator over a series
-    /// of RecordBatch instances)
+    /// of RecordBatch instances).
+    ///
+    /// The SQL may also be a script of statements separated by semicolons, which are
+    /// executed in order until one fails. The results of the last statement are returned.
     pub fn sql(&mut self, sql: &str, batch_size: usize) -> Result<Vec<RecordBatch>> {
-        let plan = self.create_logical_plan(sql)?;
+        let statements = DFParser::split_statements(sql);
+        if statements.len() > 1 {
+            let mut results = vec![];
+            for statement in statements {
+                results = self.sql(statement, batch_size)?;
+            }
+            return Ok(results);
+        }
+        // an empty statement is left to the parser to reject
+        let sql = statements.first().cloned().unwrap_or(sql);
+        let statement = DFParser::parse_sql_with_identifier_case(
+            String::from(sql),
+            self.identifier_case,
+        );
+        for hook in self.statement_hooks.clone() {
+            if let Some(results) = hook.handle(self, sql, statement.as_ref().ok())? {
+                return Ok(results);
+            }
+        }
+        let plan = self.create_logical_plan_from_ast(statement?)?;
 
         return self.collect_plan(&plan, batch_size);
     }
 
+    /// Register a hook that can intercept SQL statements before they are planned. Hooks
+    /// are called in the order in which they were registered.
+    pub fn register_statement_hook(&mut self, hook: Arc<dyn StatementHook>) {
+        self.statement_hooks.push(hook);
+    }
+
+    /// Register a listener that is notified when queries executed by `collect_plan`
+    /// start and end
+    pub fn register_query_listener(&mut self, listener: Arc<dyn QueryListener>) {
+        self.query_listeners.push(listener);
+    }
+
+    /// Get the id of the query whose physical plan was created last. Errors of queries
+    /// executed by `collect_plan` are wrapped in `ExecutionError::Query` with this id.
+    pub fn last_query_id(&self) -> u64 {
+        self.query_id
+    }
+
     /// Executes a logical plan and produce a Relation (a schema-aware iterator over a series
     /// of RecordBatch instances)
     pub fn collect_plan(
@@ -96,47 +249,192 @@ impl ExecutionContext {
                 ref file_type,
                 ref header_row,
             } => match file_type {
+                #[cfg(feature = "csv")]
                 FileType::CSV => {
                     self.register_csv(name, location, schema, *header_row);
                     Ok(vec![])
                 }
+                #[cfg(feature = "parquet")]
                 FileType::Parquet => {
                     self.register_parquet(name, location)?;
                     Ok(vec![])
                 }
-                _ => Err(ExecutionError::ExecutionError(format!(
-                    "Unsupported file type {:?}.",
-                    file_type
+                FileType::NdJson => {
+                    // without a column list, the schema is inferred from the files
+                    if schema.fields().is_empty() {
+                        self.register_ndjson(name, location)?;
+                    } else {
+                        self.register_table(
+                            name,
+                            Box::new(NdJsonFile::new(location, schema)),
+                        );
+                    }
+                    Ok(vec![])
+                }
+                #[allow(unreachable_patterns)]
+                file_type => Err(ExecutionError::NotImplemented(format!(
+                    "{:?} tables require DataFusion to be built with the `{}` feature",
+                    file_type,
+                    format!("{:?}", file_type).to_lowercase()
                 ))),
             },
 
+            LogicalPlan::AnalyzeTable { ref name, .. } => {
+                self.analyze_table(name, batch_size)?;
+                Ok(vec![])
+            }
+
+            LogicalPlan::DropTable {
+                ref name,
+                if_exists,
+                ..
+            } => {
+                if self.deregister_table(name).is_none() && !*if_exists {
+                    return Err(ExecutionError::General(format!(
+                        "Table '{}' does not exist",
+                        name
+                    )));
+                }
+                Ok(vec![])
+            }
+
+            LogicalPlan::SetVariable {
+                ref variable,
+                ref value,
+                ..
+            } => {
+                self.config.set(variable, value)?;
+                Ok(vec![])
+            }
+
+            LogicalPlan::ShowVariable {
+                ref variable,
+                ref schema,
+            } => {
+                let variables: Vec<(&str, String)> = self
+                    .config
+                    .variables()
+                    .into_iter()
+                    .filter(|(name, _)| match variable {
+                        Some(variable) => name == variable,
+                        None => true,
+                    })
+                    .collect();
+                let names: Vec<&str> = variables.iter().map(|(name, _)| *name).collect();
+                let values: Vec<&str> =
+                    variables.iter().map(|(_, value)| value.as_str()).collect();
+                Ok(vec![RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(StringArray::from(names)),
+                        Arc::new(StringArray::from(values)),
+                    ],
+                )?])
+            }
+
+            LogicalPlan::Insert {
+                ref table_name,
+                ref input,
+                ..
+            } => {
+                let batches = self.collect_plan(input, batch_size)?;
+                self.insert_into(table_name, batches, false)?;
+                Ok(vec![])
+            }
+
+            LogicalPlan::Overwrite {
+                ref table_name,
+                ref input,
+                ..
+            } => {
+                let batches = self.collect_plan(input, batch_size)?;
+                self.insert_into(table_name, batches, true)?;
+                Ok(vec![])
+            }
+
+            LogicalPlan::Copy {
+                ref input,
+                ref location,
+                ref file_type,
+                ..
+            } => {
+                let plan = self.optimize(input)?;
+                let physical_plan = self.create_physical_plan(&plan, batch_size)?;
+                write_plan(physical_plan.as_ref(), location, file_type)?;
+                Ok(vec![])
+            }
+
+            LogicalPlan::Explain {
+                analyze,
+                verbose,
+                format,
+                ref plan,
+                ref schema,
+            } => Ok(vec![self.explain(
+                plan,
+                *analyze,
+                *verbose,
+                *format,
+                schema.clone(),
+                batch_size,
+            )?]),
+
             plan => {
                 let plan = self.optimize(&plan)?;
-                let plan = self.create_physical_plan(&plan, batch_size)?;
-                Ok(self.collect(plan.as_ref())?)
+                let physical_plan = self.create_physical_plan(&plan, batch_size)?;
+                let query = QueryInfo {
+                    id: self.query_id,
+                    fingerprint: plan.fingerprint(),
+                    plan,
+                };
+                for listener in &self.query_listeners {
+                    listener.on_start(&query);
+                }
+
+                // the clock is only read for the listeners, as there is none on some
+                // targets, such as WebAssembly in a browser
+                let start = if self.query_listeners.is_empty() {
+                    None
+                } else {
+                    Some(Instant::now())
+                };
+                let results = self.collect(physical_plan.as_ref());
+                let elapsed = start.map_or(Duration::from_secs(0), |s| s.elapsed());
+                for listener in &self.query_listeners {
+                    let result = match results {
+                        Ok(ref batches) => Ok(batches.iter().map(|b| b.num_rows()).sum()),
+                        Err(ref e) => Err(e),
+                    };
+                    listener.on_end(&query, elapsed, result);
+                }
+                results.map_err(|e| ExecutionError::Query(query.id, Box::new(e)))
             }
         }
     }
 
     /// Creates a logical plan
     pub fn create_logical_plan(&mut self, sql: &str) -> Result<LogicalPlan> {
-        let ast = DFParser::parse_sql(String::from(sql))?;
+        let ast = DFParser::parse_sql_with_identifier_case(
+            String::from(sql),
+            self.identifier_case,
+        )?;
+        self.create_logical_plan_from_ast(ast)
+    }
 
+    /// Creates a logical plan from a statement that was parsed with `DFParser`
+    pub fn create_logical_plan_from_ast(
+        &mut self,
+        ast: DFASTNode,
+    ) -> Result<LogicalPlan> {
         match ast {
-            DFASTNode::ANSI(ansi) => {
-                let schema_provider = ExecutionContextSchemaProvider {
-                    datasources: &self.datasources,
-                    scalar_functions: &self.scalar_functions,
-                };
-
-                // create a query planner
-                let query_planner = SqlToRel::new(schema_provider);
-
-                // plan the query (create a logical relational plan)
-                let plan = query_planner.sql_to_rel(&ansi)?;
-
-                Ok(plan)
-            }
+            DFASTNode::ANSI(ansi) => self.query_planner().sql_to_rel(&ansi),
+            DFASTNode::SetOperation {
+                body,
+                order_by,
+                limit,
+            } => self
+                .query_planner()
+                .set_operation_to_rel(&body, &order_by, &limit),
             DFASTNode::CreateExternalTable {
                 name,
                 columns,
@@ -154,7 +452,304 @@ impl ExecutionContext {
                     header_row,
                 })
             }
+            DFASTNode::AnalyzeTable { name } => Ok(LogicalPlan::AnalyzeTable {
+                name,
+                schema: Arc::new(Schema::empty()),
+            }),
+            DFASTNode::DropTable { name, if_exists } => Ok(LogicalPlan::DropTable {
+                name,
+                if_exists,
+                schema: Arc::new(Schema::empty()),
+            }),
+            DFASTNode::SetVariable { variable, value } => Ok(LogicalPlan::SetVariable {
+                variable,
+                value,
+                schema: Arc::new(Schema::empty()),
+            }),
+            DFASTNode::ShowVariable { variable } => {
+                if let Some(ref variable) = variable {
+                    if self.config.get(variable).is_none() {
+                        return Err(ExecutionError::General(format!(
+                            "Unknown configuration variable '{}'",
+                            variable
+                        )));
+                    }
+                }
+                Ok(LogicalPlan::ShowVariable {
+                    variable,
+                    schema: Arc::new(Schema::new(vec![
+                        Field::new("name", DataType::Utf8, false),
+                        Field::new("value", DataType::Utf8, false),
+                    ])),
+                })
+            }
+            DFASTNode::ShowTables => {
+                let schema =
+                    InformationSchemaTable::schema_of(information_schema::TABLES)
+                        .unwrap();
+                LogicalPlanBuilder::scan(
+                    "default",
+                    information_schema::TABLES,
+                    &schema,
+                    None,
+                )?
+                .build()
+            }
+            DFASTNode::ShowColumns { table_name } => {
+                let table_schema = if self.datasources.contains_key(&table_name) {
+                    information_schema::DEFAULT_SCHEMA_NAME
+                } else if InformationSchemaTable::schema_of(&table_name).is_some() {
+                    information_schema::INFORMATION_SCHEMA
+                } else {
+                    return Err(ExecutionError::General(format!(
+                        "No table named '{}'",
+                        table_name
+                    )));
+                };
+                // the views are listed by their unqualified names
+                let name = table_name.rsplit('.').next().unwrap();
+                let schema =
+                    InformationSchemaTable::schema_of(information_schema::COLUMNS)
+                        .unwrap();
+                let predicate = Expr::BinaryExpr {
+                    left: Arc::new(col_index(1).eq(&lit_str(table_schema))),
+                    op: Operator::And,
+                    right: Arc::new(col_index(2).eq(&lit_str(name))),
+                };
+                // column_name, data_type and is_nullable
+                LogicalPlanBuilder::scan(
+                    "default",
+                    information_schema::COLUMNS,
+                    &schema,
+                    None,
+                )?
+                .filter(predicate)?
+                .project(vec![col_index(3), col_index(6), col_index(5)])?
+                .build()
+            }
+            DFASTNode::Insert {
+                table_name,
+                columns,
+                source,
+            } => {
+                let input = self.create_logical_plan_from_ast(*source)?;
+                let input = self.insert_input(&table_name, &columns, &input)?;
+                Ok(LogicalPlan::Insert {
+                    table_name,
+                    input: Arc::new(input),
+                    schema: Arc::new(Schema::empty()),
+                })
+            }
+            DFASTNode::Merge {
+                table_name,
+                alias,
+                source,
+                on,
+                clauses,
+            } => {
+                let input = self.query_planner().merge_to_rel(
+                    &table_name,
+                    &alias,
+                    &source,
+                    &on,
+                    &clauses,
+                )?;
+                Ok(LogicalPlan::Overwrite {
+                    table_name,
+                    input: Arc::new(input),
+                    schema: Arc::new(Schema::empty()),
+                })
+            }
+            DFASTNode::Update {
+                table_name,
+                assignments,
+                selection,
+            } => {
+                let input = self.query_planner().update_to_rel(
+                    &table_name,
+                    &assignments,
+                    &selection,
+                )?;
+                Ok(LogicalPlan::Overwrite {
+                    table_name,
+                    input: Arc::new(input),
+                    schema: Arc::new(Schema::empty()),
+                })
+            }
+            DFASTNode::Delete {
+                table_name,
+                selection,
+            } => {
+                let input = self
+                    .query_planner()
+                    .delete_to_rel(&table_name, &selection)?;
+                Ok(LogicalPlan::Overwrite {
+                    table_name,
+                    input: Arc::new(input),
+                    schema: Arc::new(Schema::empty()),
+                })
+            }
+            DFASTNode::Copy {
+                source,
+                location,
+                file_type,
+            } => Ok(LogicalPlan::Copy {
+                input: Arc::new(self.create_logical_plan_from_ast(*source)?),
+                location,
+                file_type,
+                schema: Arc::new(Schema::empty()),
+            }),
+            DFASTNode::Explain {
+                analyze,
+                verbose,
+                format,
+                statement,
+            } => Ok(LogicalPlan::Explain {
+                analyze,
+                verbose,
+                format,
+                plan: Arc::new(self.create_logical_plan_from_ast(*statement)?),
+                schema: Arc::new(Schema::new(vec![
+                    Field::new("plan_type", DataType::Utf8, false),
+                    Field::new("plan", DataType::Utf8, false),
+                ])),
+            }),
+        }
+    }
+
+    /// Project the columns of the query of an INSERT statement onto the columns of the
+    /// table in order, casting them to the types of the table. The columns of the query
+    /// are matched by position with the given column names, or with all columns of the
+    /// table if there are none.
+    fn insert_input(
+        &self,
+        table_name: &str,
+        columns: &[String],
+        input: &LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let table_schema = match self.datasources.get(table_name) {
+            Some(provider) => provider.schema(),
+            None => {
+                return Err(ExecutionError::General(format!(
+                    "No table named '{}'",
+                    table_name
+                )))
+            }
+        };
+        let columns: Vec<&str> = if columns.is_empty() {
+            table_schema
+                .fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect()
+        } else {
+            columns.iter().map(|c| c.as_str()).collect()
+        };
+        let input_schema = input.schema();
+        if columns.len() != input_schema.fields().len() {
+            return Err(ExecutionError::General(format!(
+                "INSERT into {} columns but the query has {} columns",
+                columns.len(),
+                input_schema.fields().len()
+            )));
+        }
+        for (i, column) in columns.iter().enumerate() {
+            if table_schema.index_of(column).is_err() {
+                return Err(ExecutionError::General(format!(
+                    "Table '{}' has no column named '{}'",
+                    table_name, column
+                )));
+            }
+            if columns[..i].contains(column) {
+                return Err(ExecutionError::General(format!(
+                    "Column '{}' is specified more than once in INSERT",
+                    column
+                )));
+            }
+        }
+
+        // untyped nulls take the types of the columns they are inserted into
+        let types = columns
+            .iter()
+            .map(|c| Ok(table_schema.field_with_name(c)?.data_type().clone()))
+            .collect::<Result<Vec<_>>>()?;
+        let input = &cast_untyped_null_columns(input, &types)?;
+        let input_schema = input.schema();
+
+        let expr = table_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let i = match columns.iter().position(|c| *c == field.name().as_str()) {
+                    Some(i) => i,
+                    None => {
+                        return Err(ExecutionError::General(format!(
+                            "INSERT must provide a value for column '{}'",
+                            field.name()
+                        )))
+                    }
+                };
+                let expr = if input_schema.field(i).data_type() == field.data_type() {
+                    Expr::Column(i)
+                } else {
+                    Expr::Cast {
+                        expr: Arc::new(Expr::Column(i)),
+                        data_type: field.data_type().clone(),
+                    }
+                };
+                Ok(Expr::Alias(Arc::new(expr), field.name().clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        LogicalPlanBuilder::from(input).project(expr)?.build()
+    }
+
+    /// Append batches whose columns are the columns of a table to the table, or replace
+    /// the rows of the table with them
+    fn insert_into(
+        &mut self,
+        table_name: &str,
+        batches: Vec<RecordBatch>,
+        overwrite: bool,
+    ) -> Result<()> {
+        let provider = match self.datasources.get_mut(table_name) {
+            Some(provider) => provider,
+            None => {
+                return Err(ExecutionError::General(format!(
+                    "No table named '{}'",
+                    table_name
+                )))
+            }
+        };
+        let schema = provider.schema();
+        let mut table_batches = vec![];
+        for batch in batches.iter().filter(|batch| batch.num_rows() > 0) {
+            for (field, column) in schema.fields().iter().zip(batch.columns()) {
+                if !field.is_nullable() && column.null_count() > 0 {
+                    return Err(ExecutionError::General(format!(
+                        "Cannot insert null values into column '{}'",
+                        field.name()
+                    )));
+                }
+            }
+            table_batches.push(RecordBatch::try_new(
+                schema.clone(),
+                batch.columns().to_vec(),
+            )?);
         }
+        if overwrite {
+            provider.overwrite(table_batches)
+        } else {
+            provider.insert(table_batches)
+        }
+    }
+
+    /// Create a query planner for the tables and functions registered on this context
+    fn query_planner(&self) -> SqlToRel<ExecutionContextSchemaProvider<'_>> {
+        let schema_provider = ExecutionContextSchemaProvider {
+            datasources: &self.datasources,
+            scalar_functions: &self.scalar_functions,
+        };
+        SqlToRel::new(schema_provider).with_null_ordering(self.null_ordering)
     }
 
     /// Register a scalar UDF
@@ -184,20 +779,26 @@ impl ExecutionContext {
             SQLType::Int => Ok(DataType::Int32),
             SQLType::SmallInt => Ok(DataType::Int16),
             SQLType::Char(_) | SQLType::Varchar(_) | SQLType::Text => Ok(DataType::Utf8),
-            SQLType::Decimal(_, _) => Ok(DataType::Float64),
+            SQLType::Decimal(precision, scale) => convert_decimal_type(precision, scale),
             SQLType::Float(_) => Ok(DataType::Float32),
             SQLType::Real | SQLType::Double => Ok(DataType::Float64),
             SQLType::Boolean => Ok(DataType::Boolean),
             SQLType::Date => Ok(DataType::Date64(DateUnit::Day)),
             SQLType::Time => Ok(DataType::Time64(TimeUnit::Millisecond)),
             SQLType::Timestamp => Ok(DataType::Date64(DateUnit::Millisecond)),
+            SQLType::Binary(_) | SQLType::Varbinary(_) | SQLType::Bytea => {
+                Ok(DataType::Binary)
+            }
+            SQLType::Custom(ref name) if name == "INTERVAL YEAR TO MONTH" => {
+                Ok(DataType::Interval(IntervalUnit::YearMonth))
+            }
+            SQLType::Custom(ref name) if name == "INTERVAL DAY TO SECOND" => {
+                Ok(DataType::Interval(IntervalUnit::DayTime))
+            }
             SQLType::Uuid
             | SQLType::Clob(_)
-            | SQLType::Binary(_)
-            | SQLType::Varbinary(_)
             | SQLType::Blob(_)
             | SQLType::Regclass
-            | SQLType::Bytea
             | SQLType::Custom(_)
             | SQLType::Array(_) => Err(ExecutionError::General(format!(
                 "Unsupported data type: {:?}.",
@@ -207,6 +808,7 @@ impl ExecutionContext {
     }
 
     /// Register a CSV file as a table so that it can be queried from SQL
+    #[cfg(feature = "csv")]
     pub fn register_csv(
         &mut self,
         name: &str,
@@ -217,18 +819,66 @@ impl ExecutionContext {
         self.register_table(name, Box::new(CsvFile::new(filename, schema, has_header)));
     }
 
+    /// Register a CSV file as a table that is read with the given options, such as a
+    /// number of lines to skip at the start of each file
+    #[cfg(feature = "csv")]
+    pub fn register_csv_with_options(
+        &mut self,
+        name: &str,
+        filename: &str,
+        schema: &Schema,
+        options: CsvReadOptions,
+    ) {
+        self.register_table(
+            name,
+            Box::new(CsvFile::new_with_options(filename, schema, options)),
+        );
+    }
+
     /// Register a Parquet file as a table so that it can be queried from SQL
+    #[cfg(feature = "parquet")]
     pub fn register_parquet(&mut self, name: &str, filename: &str) -> Result<()> {
         let table = ParquetTable::try_new(&filename)?;
         self.register_table(name, Box::new(table));
         Ok(())
     }
 
+    /// Register a newline-delimited JSON file, or a directory of such files, as a table
+    /// whose schema is inferred from the records of the first file
+    pub fn register_ndjson(&mut self, name: &str, filename: &str) -> Result<()> {
+        let table = NdJsonFile::try_new_infer_schema(filename, None)?;
+        self.register_table(name, Box::new(table));
+        Ok(())
+    }
+
     /// Register a table so that it can be queried from SQL
     pub fn register_table(&mut self, name: &str, provider: Box<dyn TableProvider>) {
         self.datasources.insert(name.to_string(), provider);
     }
 
+    /// Remove a table from the context, returning its provider if it was registered.
+    /// Any data held by the provider, such as the batches of a `MemTable`, is released
+    /// when the returned provider is dropped.
+    pub fn deregister_table(&mut self, name: &str) -> Option<Box<dyn TableProvider>> {
+        self.datasources.remove(name)
+    }
+
+    /// Create the view of the `information_schema` schema with the given qualified
+    /// name, such as `information_schema.tables`, over the currently registered tables
+    fn information_schema_table(&self, name: &str) -> Option<InformationSchemaTable> {
+        let tables = self
+            .datasources
+            .iter()
+            .map(|(name, provider)| (name.clone(), provider.schema()))
+            .collect();
+        InformationSchemaTable::new(name, tables)
+    }
+
+    /// Get the provider of a registered table by name
+    pub fn table_provider(&self, name: &str) -> Option<&dyn TableProvider> {
+        self.datasources.get(name).map(|provider| provider.as_ref())
+    }
+
     /// Get a table by name
     pub fn table(&mut self, table_name: &str) -> Result<Arc<dyn Table>> {
         match self.datasources.get(table_name) {
@@ -251,12 +901,135 @@ impl ExecutionContext {
         }
     }
 
+    /// Scan a table to collect statistics about its data, such as its row count and the
+    /// minimum and maximum values of each column. The statistics are stored on the
+    /// table provider, where they are used when planning queries.
+    pub fn analyze_table(&mut self, table_name: &str, batch_size: usize) -> Result<()> {
+        match self.datasources.get_mut(table_name) {
+            Some(provider) => {
+                let statistics = collect_statistics(provider.as_ref(), batch_size)?;
+                provider.set_statistics(statistics)
+            }
+            _ => Err(ExecutionError::General(format!(
+                "No table named '{}'",
+                table_name
+            ))),
+        }
+    }
+
+    /// Set the maximum estimated size in bytes of the left input of a join for the join
+    /// to be executed by broadcasting its hash table instead of repartitioning both inputs
+    pub fn set_broadcast_join_threshold(&mut self, bytes: usize) {
+        self.config.broadcast_join_threshold = bytes;
+    }
+
+    /// Get the maximum estimated size in bytes of the left input of a broadcast join
+    pub fn broadcast_join_threshold(&self) -> usize {
+        self.config.broadcast_join_threshold
+    }
+
+    /// Replace the configuration variables of this context, which can also be changed
+    /// with `SET name = value` statements
+    pub fn set_config(&mut self, config: ExecutionConfig) {
+        self.config = config;
+    }
+
+    /// Get the configuration variables of this context
+    pub fn config(&self) -> &ExecutionConfig {
+        &self.config
+    }
+
+    /// Set the placement of nulls by ORDER BY when neither `NULLS FIRST` nor
+    /// `NULLS LAST` is specified. Nulls sort as if they were larger than any other value
+    /// by default, as in PostgreSQL.
+    pub fn set_null_ordering(&mut self, null_ordering: NullOrdering) {
+        self.null_ordering = null_ordering;
+    }
+
+    /// Get the placement of nulls by ORDER BY when it is not specified
+    pub fn null_ordering(&self) -> NullOrdering {
+        self.null_ordering
+    }
+
+    /// Set the collation used to compare strings in comparison operators, ORDER BY,
+    /// GROUP BY, MIN and MAX. Strings are compared by their bytes by default. With a
+    /// case-insensitive collation, GROUP BY outputs a lowercase key for each group.
+    pub fn set_collation(&mut self, collation: Collation) {
+        self.collation = collation;
+    }
+
+    /// Set the collation used to compare strings by its name, `binary` or
+    /// `case_insensitive`. Locale-aware collations, such as `de_DE`, are not supported
+    /// yet, since they need ICU.
+    pub fn set_collation_by_name(&mut self, name: &str) -> Result<()> {
+        self.collation = match name.to_lowercase().as_str() {
+            "binary" => Collation::Binary,
+            "case_insensitive" => Collation::CaseInsensitive,
+            _ => {
+                return Err(ExecutionError::NotImplemented(format!(
+                    "Unsupported collation '{}'. Locale-aware collations are not \
+                     implemented, only 'binary' and 'case_insensitive' are supported",
+                    name
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Get the collation used to compare strings
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
+
+    /// Set how the case of identifiers that are not quoted is normalized before they
+    /// are matched with the names of tables and columns. Identifiers keep their case by
+    /// default. Quoted identifiers, such as `"MixedCase Col"`, always keep their case.
+    pub fn set_identifier_case(&mut self, identifier_case: IdentifierCase) {
+        self.identifier_case = identifier_case;
+    }
+
+    /// Get how the case of identifiers that are not quoted is normalized
+    pub fn identifier_case(&self) -> IdentifierCase {
+        self.identifier_case
+    }
+
+    /// Limit the memory used by execution plans that buffer their input. Plans that
+    /// exceed the limit spill to disk.
+    pub fn set_memory_limit(&mut self, bytes: usize) {
+        self.memory_manager = Arc::new(MemoryManager::new(bytes));
+    }
+
+    /// Get the memory manager used by execution plans created by this context
+    pub fn memory_manager(&self) -> Arc<MemoryManager> {
+        self.memory_manager.clone()
+    }
+
+    /// Limit the number of partitions that execute at the same time across all queries
+    /// of this context, whichever operators they belong to. The limit must be positive.
+    /// Partitions that exceed the limit wait for a slot, which is granted fairly between
+    /// queries.
+    pub fn set_max_concurrent_partitions(&mut self, limit: usize) -> Result<()> {
+        self.scheduler = Arc::new(Scheduler::try_new(limit)?);
+        Ok(())
+    }
+
+    /// Get the scheduler used by execution plans created by this context
+    pub fn scheduler(&self) -> Arc<Scheduler> {
+        self.scheduler.clone()
+    }
+
     /// Optimize the logical plan by applying optimizer rules
     pub fn optimize(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let plan = self.optimize_before_type_coercion(plan)?;
+        TypeCoercionRule::new(&self.scalar_functions).optimize(&plan)
+    }
+
+    /// Apply the optimizer rules that run before type coercion, which is applied last
+    fn optimize_before_type_coercion(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
         let rules: Vec<Box<dyn OptimizerRule>> = vec![
             Box::new(ResolveColumnsRule::new()),
+            Box::new(DecorrelateSubqueries::new()),
             Box::new(ProjectionPushDown::new()),
-            Box::new(TypeCoercionRule::new(&self.scalar_functions)),
         ];
         let mut plan = plan.clone();
         for mut rule in rules {
@@ -265,129 +1038,459 @@ impl ExecutionContext {
         Ok(plan)
     }
 
-    /// Create a physical plan from a logical plan
-    pub fn create_physical_plan(
+    /// Describe a logical plan as rows containing the type of plan and one line of the
+    /// plan each. The verbose form shows the plan before and after optimization with the
+    /// output schema of each node and the casts added by type coercion, followed by the
+    /// output schema of the physical plan. The analyze form executes the physical plan
+    /// and adds the metrics collected by each of its operators.
+    fn explain(
         &mut self,
-        logical_plan: &LogicalPlan,
+        plan: &LogicalPlan,
+        analyze: bool,
+        verbose: bool,
+        format: ExplainFormat,
+        schema: Arc<Schema>,
         batch_size: usize,
-    ) -> Result<Arc<dyn ExecutionPlan>> {
-        match logical_plan {
-            LogicalPlan::TableScan {
-                table_name,
-                projection,
-                ..
-            } => match self.datasources.get(table_name) {
-                Some(provider) => {
-                    let partitions = provider.scan(projection, batch_size)?;
-                    if partitions.is_empty() {
-                        Err(ExecutionError::General(
-                            "Table provider returned no partitions".to_string(),
-                        ))
-                    } else {
-                        let partition = partitions[0].lock().unwrap();
-                        let schema = partition.schema();
-                        let exec =
-                            DatasourceExec::new(schema.clone(), partitions.clone());
-                        Ok(Arc::new(exec))
+    ) -> Result<RecordBatch> {
+        let uncoerced = self.optimize_before_type_coercion(plan)?;
+        let optimized =
+            TypeCoercionRule::new(&self.scalar_functions).optimize(&uncoerced)?;
+
+        let mut plan_types = vec![];
+        let mut lines = vec![];
+        // a plan is described by a row for each node as text, and by a single row in
+        // the other formats
+        let mut describe_plan =
+            |plan_type, plan: &LogicalPlan, uncoerced: Option<&LogicalPlan>| {
+                let plan_lines = match format {
+                    ExplainFormat::Text if verbose => plan.explain_lines(uncoerced),
+                    ExplainFormat::Text => {
+                        format!("{:?}", plan).lines().map(str::to_string).collect()
                     }
+                    ExplainFormat::Graphviz => vec![plan.to_graphviz()],
+                    ExplainFormat::Json => vec![plan.to_json().to_string()],
+                };
+                for line in plan_lines {
+                    plan_types.push(plan_type);
+                    lines.push(line);
+                }
+            };
+        if verbose {
+            describe_plan("initial_logical_plan", plan, None);
+            describe_plan("optimized_logical_plan", &optimized, Some(&uncoerced));
+            match optimized {
+                LogicalPlan::CreateExternalTable { .. }
+                | LogicalPlan::AnalyzeTable { .. }
+                | LogicalPlan::DropTable { .. }
+                | LogicalPlan::SetVariable { .. }
+                | LogicalPlan::ShowVariable { .. }
+                | LogicalPlan::Insert { .. }
+                | LogicalPlan::Overwrite { .. }
+                | LogicalPlan::Copy { .. }
+                | LogicalPlan::Explain { .. } => {}
+                _ => {
+                    let physical_plan =
+                        self.create_physical_plan(&optimized, batch_size)?;
+                    let physical_schema = physical_plan.schema();
+                    let mut line = format_schema(&physical_schema);
+                    if physical_schema.as_ref() != optimized.schema().as_ref() {
+                        line.push_str(
+                            " (differs from the optimized logical plan schema)",
+                        );
+                    }
+                    plan_types.push("physical_plan_schema");
+                    lines.push(line);
                 }
-                _ => Err(ExecutionError::General(format!(
-                    "No table named {}",
-                    table_name
-                ))),
-            },
-            LogicalPlan::Projection { input, expr, .. } => {
-                let input = self.create_physical_plan(input, batch_size)?;
-                let input_schema = input.as_ref().schema().clone();
-                let runtime_expr = expr
-                    .iter()
-                    .map(|e| self.create_physical_expr(e, &input_schema))
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(Arc::new(ProjectionExec::try_new(runtime_expr, input)?))
             }
-            LogicalPlan::Aggregate {
-                input,
-                group_expr,
-                aggr_expr,
-                ..
-            } => {
-                // Initially need to perform the aggregate and then merge the partitions
-                let input = self.create_physical_plan(input, batch_size)?;
-                let input_schema = input.as_ref().schema().clone();
+        } else {
+            describe_plan("logical_plan", &optimized, None);
+        }
+        if analyze {
+            match optimized {
+                LogicalPlan::CreateExternalTable { .. }
+                | LogicalPlan::AnalyzeTable { .. }
+                | LogicalPlan::DropTable { .. }
+                | LogicalPlan::SetVariable { .. }
+                | LogicalPlan::ShowVariable { .. }
+                | LogicalPlan::Insert { .. }
+                | LogicalPlan::Overwrite { .. }
+                | LogicalPlan::Copy { .. }
+                | LogicalPlan::Explain { .. } => {}
+                _ => {
+                    let physical_plan =
+                        self.create_physical_plan(&optimized, batch_size)?;
+                    self.collect(physical_plan.as_ref())?;
+                    for line in collect_metrics(physical_plan) {
+                        plan_types.push("physical_plan_metrics");
+                        lines.push(line);
+                    }
+                }
+            }
+        }
 
-                let group_expr = group_expr
-                    .iter()
-                    .map(|e| self.create_physical_expr(e, &input_schema))
-                    .collect::<Result<Vec<_>>>()?;
+        let lines: Vec<&str> = lines.iter().map(|l| l.as_str()).collect();
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(plan_types)),
+                Arc::new(StringArray::from(lines)),
+            ],
+        )?)
+    }
+
+    /// Create a physical plan from a logical plan
+    pub fn create_physical_plan(
+        &mut self,
+        logical_plan: &LogicalPlan,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.query_id += 1;
+        self.deadline = match self.config.max_execution_time_ms {
+            0 => None,
+            ms => Some(Arc::new(Instant::now() + Duration::from_millis(ms as u64))),
+        };
+        self.constant_values.clear();
+        self.evaluate_plan_constants(logical_plan, batch_size)?;
+        let plan = self.create_plan_node(logical_plan, batch_size)?;
+        Ok(self.create_query_plan(plan))
+    }
+
+    /// Wrap the physical plan of the current query, or of one of its subqueries, in
+    /// the plans that cancel it once its deadline has passed and that execute its
+    /// partitions with slots of the scheduler, if either is limited
+    fn create_query_plan(&self, plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let plan = if self.scheduler.limit() < usize::max_value() {
+            Arc::new(ScheduledExec::new(
+                plan,
+                self.scheduler.clone(),
+                self.query_id,
+            ))
+        } else {
+            plan
+        };
+        self.with_deadline(plan)
+    }
+
+    /// Wrap a physical plan in a plan that cancels it once the deadline of the current
+    /// query has passed, if there is one
+    fn with_deadline(&self, plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        match self.deadline {
+            Some(ref deadline) => Arc::new(TimeoutExec::new(
+                plan,
+                Duration::from_millis(self.config.max_execution_time_ms as u64),
+                deadline.clone(),
+            )),
+            None => plan,
+        }
+    }
+
+    /// Evaluate the uncorrelated scalar subqueries and the deterministic scalar
+    /// functions of constants in the expressions of a logical plan and its inputs, which
+    /// are then planned as literals of their values
+    fn evaluate_plan_constants(
+        &mut self,
+        plan: &LogicalPlan,
+        batch_size: usize,
+    ) -> Result<()> {
+        for expr in plan.expressions() {
+            self.evaluate_constants(expr, batch_size)?;
+        }
+        for input in plan.inputs() {
+            self.evaluate_plan_constants(input, batch_size)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate the uncorrelated scalar subqueries and the deterministic scalar
+    /// functions of constants in an expression, evaluating each distinct one only once
+    fn evaluate_constants(&mut self, expr: &Expr, batch_size: usize) -> Result<()> {
+        // the arguments of a function are evaluated first, so that they are constants
+        // when the function itself is
+        for child in expr.children() {
+            self.evaluate_constants(child, batch_size)?;
+        }
+        if self.constant_values.contains_key(expr) {
+            return Ok(());
+        }
+        let value = match expr {
+            Expr::ScalarSubquery(subquery) => {
+                Some(self.execute_scalar_subquery(subquery, batch_size)?)
+            }
+            Expr::ScalarFunction { name, args, .. } => match self
+                .scalar_functions
+                .get(name)
+            {
+                Some(f) if f.deterministic => self.evaluate_scalar_function(f, args)?,
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(value) = value {
+            self.constant_values.insert(expr.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// Execute an uncorrelated scalar subquery, whose value is null if it returns no
+    /// rows
+    fn execute_scalar_subquery(
+        &mut self,
+        subquery: &Subquery,
+        batch_size: usize,
+    ) -> Result<ScalarValue> {
+        let plan = self.optimize(subquery.plan())?;
+        self.evaluate_plan_constants(&plan, batch_size)?;
+        let plan = self.create_query_plan(self.create_plan_node(&plan, batch_size)?);
+        let batches = self.collect(plan.as_ref())?;
+        let mut rows = batches.iter().filter(|batch| batch.num_rows() > 0);
+        match (rows.next(), rows.next()) {
+            (None, _) => Ok(ScalarValue::Null),
+            (Some(batch), None) if batch.num_rows() == 1 => {
+                Ok(common::get_scalar_value(batch.column(0), 0)?
+                    .unwrap_or(ScalarValue::Null))
+            }
+            _ => Err(ExecutionError::ExecutionError(
+                "Scalar subquery returned more than one row".to_string(),
+            )),
+        }
+    }
+
+    /// Evaluate a scalar function if all of its arguments are constants
+    fn evaluate_scalar_function(
+        &self,
+        f: &ScalarFunction,
+        args: &[Expr],
+    ) -> Result<Option<ScalarValue>> {
+        let mut arrays = Vec::with_capacity(args.len());
+        for arg in args {
+            let array = match self.constant_values.get(arg) {
+                Some(ScalarValue::Null) => {
+                    common::new_null_array(&arg.get_type(&Schema::empty())?, 1)?
+                }
+                Some(value) => common::scalars_to_array(
+                    &value.get_datatype(),
+                    &[Some(value.clone())],
+                )?,
+                None => match evaluate_constant(arg)? {
+                    Some(array) => array,
+                    None => return Ok(None),
+                },
+            };
+            arrays.push(array);
+        }
+        let result = (f.fun)(&arrays)?;
+        if result.len() != 1 {
+            // a function without arguments does not know how many values to return
+            return Ok(None);
+        }
+        Ok(Some(
+            common::get_scalar_value(&result, 0)?.unwrap_or(ScalarValue::Null),
+        ))
+    }
+
+    /// Create the physical plan of a node of the logical plan of the current query
+    fn create_plan_node(
+        &mut self,
+        logical_plan: &LogicalPlan,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match logical_plan {
+            LogicalPlan::TableScan { .. } => {
+                self.create_scan_plan(logical_plan, None, None, batch_size)
+            }
+            LogicalPlan::EmptyRelation { .. } => Ok(Arc::new(EmptyExec::new())),
+            LogicalPlan::Projection { input, expr, .. } => {
+                let input = self.create_plan_node(input, batch_size)?;
+                self.create_projection_plan(input, expr)
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                ..
+            } => {
+                // Initially need to perform the aggregate and then merge the partitions
+                let input = self.create_plan_node(input, batch_size)?;
+                let input_schema = input.as_ref().schema().clone();
+
+                let group_expr = group_expr
+                    .iter()
+                    .map(|e| self.create_physical_expr(e, &input_schema))
+                    .collect::<Result<Vec<_>>>()?;
                 let aggr_expr = aggr_expr
                     .iter()
                     .map(|e| self.create_aggregate_expr(e, &input_schema))
                     .collect::<Result<Vec<_>>>()?;
 
+                let requires_reducer = aggr_expr.iter().any(|e| e.requires_reducer());
                 let initial_aggr =
-                    HashAggregateExec::try_new(group_expr, aggr_expr, input)?;
+                    HashAggregateExec::try_new(group_expr, aggr_expr, input)?
+                        .with_collation(self.collation);
 
                 let schema = initial_aggr.schema();
                 let partitions = initial_aggr.partitions()?;
 
-                if partitions.len() == 1 {
+                if partitions.len() == 1 && !requires_reducer {
                     return Ok(Arc::new(initial_aggr));
                 }
 
                 let (final_group, final_aggr) = initial_aggr.make_final_expr();
 
-                let merge = Arc::new(MergeExec::new(schema.clone(), partitions));
+                let merge = Arc::new(
+                    MergeExec::new(schema.clone(), partitions)
+                        .with_input(Arc::new(initial_aggr)),
+                );
 
-                Ok(Arc::new(HashAggregateExec::try_new(
-                    final_group,
-                    final_aggr,
-                    merge,
-                )?))
+                Ok(Arc::new(
+                    HashAggregateExec::try_new(final_group, final_aggr, merge)?
+                        .with_collation(self.collation),
+                ))
             }
             LogicalPlan::Selection { input, expr, .. } => {
-                let input = self.create_physical_plan(input, batch_size)?;
+                self.create_selection_plan(input, expr, None, batch_size)
+            }
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => {
+                // a left input that is small enough is collected into a single hash
+                // table that is shared by every partition of the right input
+                let mode = match self.estimate_byte_size(left) {
+                    Some(size) if size <= self.config.broadcast_join_threshold => {
+                        PartitionMode::CollectLeft
+                    }
+                    _ => PartitionMode::Partitioned,
+                };
+
+                let left = self.create_plan_node(left, batch_size)?;
+                let right = self.create_plan_node(right, batch_size)?;
+
+                let left_partitions = left.partitions()?.len();
+                let right_partitions = right.partitions()?.len();
+                let (left, right) = if mode == PartitionMode::CollectLeft
+                    || (left_partitions == 1 && right_partitions == 1)
+                {
+                    (left, right)
+                } else {
+                    let num_partitions = match self.config.target_partitions {
+                        0 => left_partitions.max(right_partitions),
+                        n => n,
+                    };
+                    let left_schema = left.schema();
+                    let right_schema = right.schema();
+                    let left_keys = on
+                        .iter()
+                        .map(|(l, _)| col(*l, left_schema.as_ref()))
+                        .collect();
+                    let right_keys = on
+                        .iter()
+                        .map(|(_, r)| col(*r, right_schema.as_ref()))
+                        .collect();
+                    let left: Arc<dyn ExecutionPlan> = Arc::new(
+                        RepartitionExec::try_new(left, left_keys, num_partitions)?,
+                    );
+                    let right: Arc<dyn ExecutionPlan> = Arc::new(
+                        RepartitionExec::try_new(right, right_keys, num_partitions)?,
+                    );
+                    (left, right)
+                };
+
+                Ok(Arc::new(
+                    HashJoinExec::try_new(left, right, on, join_type, mode)?
+                        .with_memory_manager(self.memory_manager.clone())
+                        .with_null_equals_null(*null_equals_null),
+                ))
+            }
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                let left = self.create_plan_node(left, batch_size)?;
+                let right = self.create_plan_node(right, batch_size)?;
+                Ok(Arc::new(CrossJoinExec::try_new(left, right)?))
+            }
+            LogicalPlan::Union { inputs, schema } => {
+                let inputs = inputs
+                    .iter()
+                    .map(|input| self.create_plan_node(input, batch_size))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Arc::new(UnionExec::try_new(inputs, schema.clone())?))
+            }
+            LogicalPlan::Limit { input, expr, .. } => match expr {
+                &Expr::Literal(ref scalar_value) => {
+                    let limit: usize = match scalar_value {
+                        ScalarValue::Int8(limit) if *limit >= 0 => Ok(*limit as usize),
+                        ScalarValue::Int16(limit) if *limit >= 0 => Ok(*limit as usize),
+                        ScalarValue::Int32(limit) if *limit >= 0 => Ok(*limit as usize),
+                        ScalarValue::Int64(limit) if *limit >= 0 => Ok(*limit as usize),
+                        ScalarValue::UInt8(limit) => Ok(*limit as usize),
+                        ScalarValue::UInt16(limit) => Ok(*limit as usize),
+                        ScalarValue::UInt32(limit) => Ok(*limit as usize),
+                        ScalarValue::UInt64(limit) => Ok(*limit as usize),
+                        _ => Err(ExecutionError::ExecutionError(
+                            "Limit only supports non-negative integer literals"
+                                .to_string(),
+                        )),
+                    }?;
+                    let input = self.create_limited_plan(input, limit, batch_size)?;
+                    let input_schema = input.schema();
+                    Ok(Arc::new(
+                        LimitExec::new(input_schema, input.partitions()?, limit)
+                            .with_input(input),
+                    ))
+                }
+                _ => Err(ExecutionError::ExecutionError(
+                    "Limit only supports non-negative integer literals".to_string(),
+                )),
+            },
+            LogicalPlan::Sort { input, expr, .. } => {
+                let input = self.create_plan_node(input, batch_size)?;
+                let input_schema = input.as_ref().schema().clone();
+
+                let sort_expr = expr
+                    .iter()
+                    .map(|e| self.create_physical_sort_expr(e, &input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Arc::new(
+                    SortExec::try_new(sort_expr, input)?
+                        .with_memory_manager(self.memory_manager.clone())
+                        .with_batch_size(batch_size),
+                ))
+            }
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => {
+                let input = self.create_plan_node(input, batch_size)?;
                 let input_schema = input.as_ref().schema().clone();
-                let runtime_expr = self.create_physical_expr(expr, &input_schema)?;
-                Ok(Arc::new(SelectionExec::try_new(runtime_expr, input)?))
+
+                let window_expr = window_expr
+                    .iter()
+                    .map(|e| self.create_window_expr(e, &input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Arc::new(WindowAggExec::try_new(window_expr, input)?))
             }
-            LogicalPlan::Limit { input, expr, .. } => {
-                let input = self.create_physical_plan(input, batch_size)?;
+            LogicalPlan::Deduplicate {
+                input,
+                keys,
+                keep_first,
+                ..
+            } => {
+                let input = self.create_plan_node(input, batch_size)?;
                 let input_schema = input.as_ref().schema().clone();
 
-                match expr {
-                    &Expr::Literal(ref scalar_value) => {
-                        let limit: usize = match scalar_value {
-                            ScalarValue::Int8(limit) if *limit >= 0 => {
-                                Ok(*limit as usize)
-                            }
-                            ScalarValue::Int16(limit) if *limit >= 0 => {
-                                Ok(*limit as usize)
-                            }
-                            ScalarValue::Int32(limit) if *limit >= 0 => {
-                                Ok(*limit as usize)
-                            }
-                            ScalarValue::Int64(limit) if *limit >= 0 => {
-                                Ok(*limit as usize)
-                            }
-                            ScalarValue::UInt8(limit) => Ok(*limit as usize),
-                            ScalarValue::UInt16(limit) => Ok(*limit as usize),
-                            ScalarValue::UInt32(limit) => Ok(*limit as usize),
-                            ScalarValue::UInt64(limit) => Ok(*limit as usize),
-                            _ => Err(ExecutionError::ExecutionError(
-                                "Limit only supports non-negative integer literals"
-                                    .to_string(),
-                            )),
-                        }?;
-                        Ok(Arc::new(LimitExec::new(
-                            input_schema.clone(),
-                            input.partitions()?,
-                            limit,
-                        )))
-                    }
-                    _ => Err(ExecutionError::ExecutionError(
-                        "Limit only supports non-negative integer literals".to_string(),
-                    )),
-                }
+                let keys = keys
+                    .iter()
+                    .map(|e| self.create_physical_expr(e, &input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Arc::new(DeduplicateExec::try_new(
+                    keys,
+                    *keep_first,
+                    input,
+                )?))
             }
             _ => Err(ExecutionError::General(
                 "Unsupported logical plan variant".to_string(),
@@ -395,6 +1498,141 @@ impl ExecutionContext {
         }
     }
 
+    /// Create the physical plan of a projection of an input
+    fn create_projection_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        expr: &[Expr],
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input_schema = input.as_ref().schema().clone();
+        let runtime_expr = expr
+            .iter()
+            .map(|e| self.create_physical_expr(e, &input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(ProjectionExec::try_new(runtime_expr, input)?))
+    }
+
+    /// Create the physical plan of the input of a limit. The limit is pushed through
+    /// projections and selections into the scan of a table, which can then stop reading
+    /// once it has produced enough rows.
+    fn create_limited_plan(
+        &mut self,
+        plan: &LogicalPlan,
+        limit: usize,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match plan {
+            LogicalPlan::TableScan { .. } => {
+                self.create_scan_plan(plan, None, Some(limit), batch_size)
+            }
+            LogicalPlan::Selection { input, expr, .. } => {
+                self.create_selection_plan(input, expr, Some(limit), batch_size)
+            }
+            LogicalPlan::Projection { input, expr, .. } => {
+                let input = self.create_limited_plan(input, limit, batch_size)?;
+                self.create_projection_plan(input, expr)
+            }
+            _ => self.create_plan_node(plan, batch_size),
+        }
+    }
+
+    /// Create the physical plan of a table scan, pushing a filter on its projected
+    /// columns and a limit on the rows that pass the filter into the table provider
+    fn create_scan_plan(
+        &self,
+        scan: &LogicalPlan,
+        filter: Option<ScanFilter>,
+        limit: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match scan {
+            LogicalPlan::TableScan {
+                table_name,
+                projection,
+                ..
+            } => {
+                // the views of the information schema are created when they are scanned,
+                // so that they list the tables registered at that time
+                let view;
+                let provider: &dyn TableProvider = match self.datasources.get(table_name)
+                {
+                    Some(provider) => provider.as_ref(),
+                    None => match self.information_schema_table(table_name) {
+                        Some(table) => {
+                            view = table;
+                            &view
+                        }
+                        None => {
+                            return Err(ExecutionError::General(format!(
+                                "No table named {}",
+                                table_name
+                            )))
+                        }
+                    },
+                };
+                let plan = provider.create_physical_plan(
+                    projection,
+                    filter.as_ref(),
+                    limit,
+                    batch_size,
+                )?;
+                // the scans are cancelled too, so that operators that read all of their
+                // input before they return a batch, such as sorts, stop reading it
+                Ok(self.with_deadline(plan))
+            }
+            _ => Err(ExecutionError::InternalError(
+                "Expected a table scan".to_string(),
+            )),
+        }
+    }
+
+    /// Create the physical plan of a selection. When the selection reads a table scan,
+    /// its predicate and a limit on its output are also pushed into the scan.
+    fn create_selection_plan(
+        &mut self,
+        input: &LogicalPlan,
+        expr: &Expr,
+        limit: Option<usize>,
+        batch_size: usize,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let (input, runtime_expr) = match input {
+            LogicalPlan::TableScan { .. } => {
+                let runtime_expr = self.create_physical_expr(expr, input.schema())?;
+                let filter = ScanFilter {
+                    expr: expr.clone(),
+                    physical_expr: runtime_expr.clone(),
+                };
+                let scan =
+                    self.create_scan_plan(input, Some(filter), limit, batch_size)?;
+                (scan, runtime_expr)
+            }
+            _ => {
+                let input = self.create_plan_node(input, batch_size)?;
+                let runtime_expr = self.create_physical_expr(expr, &input.schema())?;
+                (input, runtime_expr)
+            }
+        };
+        Ok(Arc::new(SelectionExec::try_new(runtime_expr, input)?))
+    }
+
+    /// Estimate the size in bytes of the output of a logical plan, based on the
+    /// statistics of the table providers it reads from
+    fn estimate_byte_size(&self, plan: &LogicalPlan) -> Option<usize> {
+        match plan {
+            LogicalPlan::TableScan { table_name, .. } => self
+                .datasources
+                .get(table_name)
+                .and_then(|provider| provider.statistics().total_byte_size),
+            // these can only make the output smaller than the input
+            LogicalPlan::Projection { input, .. }
+            | LogicalPlan::Selection { input, .. }
+            | LogicalPlan::Limit { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Deduplicate { input, .. } => self.estimate_byte_size(input),
+            _ => None,
+        }
+    }
+
     /// Create a physical expression from a logical expression
     pub fn create_physical_expr(
         &self,
@@ -409,17 +1647,151 @@ impl ExecutionContext {
             Expr::Column(i) => {
                 Ok(Arc::new(Column::new(*i, &input_schema.field(*i).name())))
             }
+            Expr::Literal(ScalarValue::Null) => {
+                Ok(Arc::new(Literal::new_null(e.get_type(input_schema)?)))
+            }
             Expr::Literal(value) => Ok(Arc::new(Literal::new(value.clone()))),
-            Expr::BinaryExpr { left, op, right } => Ok(Arc::new(BinaryExpr::new(
-                self.create_physical_expr(left, input_schema)?,
-                op.clone(),
-                self.create_physical_expr(right, input_schema)?,
+            Expr::BinaryExpr { left, op, right } => {
+                let expr = Arc::new(
+                    BinaryExpr::new(
+                        self.create_physical_expr(left, input_schema)?,
+                        op.clone(),
+                        self.create_physical_expr(right, input_schema)?,
+                    )
+                    .with_collation(self.collation),
+                );
+                // expressions of literals are folded into a literal of the same type,
+                // unless strings are compared with another collation than the one of
+                // scalar values
+                match e.evaluate_literal() {
+                    Some(value)
+                        if !value.is_null()
+                            && self.collation == Collation::Binary
+                            && expr.data_type(input_schema).ok()
+                                == Some(value.get_datatype()) =>
+                    {
+                        Ok(Arc::new(Literal::new(value)))
+                    }
+                    _ => Ok(expr),
+                }
+            }
+            Expr::ElementAt { list, index } => Ok(Arc::new(ElementAtExpr::new(
+                self.create_physical_expr(list, input_schema)?,
+                self.create_physical_expr(index, input_schema)?,
+            ))),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                // the condition is evaluated as comparisons with both bounds
+                let value = self.create_physical_expr(expr, input_schema)?;
+                let low = self.create_physical_expr(low, input_schema)?;
+                let high = self.create_physical_expr(high, input_schema)?;
+                let (low_op, op, high_op) = if *negated {
+                    (Operator::Lt, Operator::Or, Operator::Gt)
+                } else {
+                    (Operator::GtEq, Operator::And, Operator::LtEq)
+                };
+                Ok(Arc::new(BinaryExpr::new(
+                    Arc::new(
+                        BinaryExpr::new(value.clone(), low_op, low)
+                            .with_collation(self.collation),
+                    ),
+                    op,
+                    Arc::new(
+                        BinaryExpr::new(value, high_op, high)
+                            .with_collation(self.collation),
+                    ),
+                )))
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = self.create_physical_expr(expr, input_schema)?;
+                // lists of constants are evaluated once, so that they can be hashed
+                let constants = list
+                    .iter()
+                    .map(evaluate_constant)
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>();
+                let in_list = match constants {
+                    Some(constants) => {
+                        InListExpr::new_constant(value, concat(&constants)?, *negated)
+                    }
+                    None => InListExpr::new(
+                        value,
+                        list.iter()
+                            .map(|e| self.create_physical_expr(e, input_schema))
+                            .collect::<Result<Vec<_>>>()?,
+                        *negated,
+                    ),
+                };
+                Ok(Arc::new(in_list.with_collation(self.collation)))
+            }
+            Expr::Like {
+                expr,
+                pattern,
+                negated,
+                case_insensitive,
+                escape,
+            } => Ok(Arc::new(
+                LikeExpr::new(
+                    self.create_physical_expr(expr, input_schema)?,
+                    self.create_physical_expr(pattern, input_schema)?,
+                    *negated,
+                )
+                .with_case_insensitive(*case_insensitive)
+                .with_escape(*escape),
+            )),
+            Expr::IsNull(expr) => Ok(Arc::new(IsNullExpr::new(
+                self.create_physical_expr(expr, input_schema)?,
+            ))),
+            Expr::IsNotNull(expr) => Ok(Arc::new(IsNotNullExpr::new(
+                self.create_physical_expr(expr, input_schema)?,
             ))),
+            Expr::Cast { expr, data_type } if expr.is_untyped_null() => {
+                Ok(Arc::new(Literal::new_null(data_type.clone())))
+            }
             Expr::Cast { expr, data_type } => Ok(Arc::new(CastExpr::try_new(
                 self.create_physical_expr(expr, input_schema)?,
                 input_schema,
                 data_type.clone(),
             )?)),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => Ok(Arc::new(CaseExpr::try_new(
+                when_then_expr
+                    .iter()
+                    .map(|(when, then)| {
+                        Ok((
+                            self.create_physical_expr(when, input_schema)?,
+                            self.create_physical_expr(then, input_schema)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                match else_expr {
+                    Some(else_expr) => {
+                        Some(self.create_physical_expr(else_expr, input_schema)?)
+                    }
+                    None => None,
+                },
+            )?)),
+            Expr::ScalarSubquery(_) | Expr::ScalarFunction { .. }
+                if self.constant_values.contains_key(e) =>
+            {
+                match &self.constant_values[e] {
+                    ScalarValue::Null => {
+                        Ok(Arc::new(Literal::new_null(e.get_type(input_schema)?)))
+                    }
+                    value => Ok(Arc::new(Literal::new(value.clone()))),
+                }
+            }
             Expr::ScalarFunction {
                 name,
                 args,
@@ -464,14 +1836,35 @@ impl ExecutionContext {
                     "avg" => Ok(Arc::new(Avg::new(
                         self.create_physical_expr(&args[0], input_schema)?,
                     ))),
-                    "max" => Ok(Arc::new(Max::new(
+                    "max" => Ok(Arc::new(
+                        Max::new(self.create_physical_expr(&args[0], input_schema)?)
+                            .with_collation(self.collation),
+                    )),
+                    "min" => Ok(Arc::new(
+                        Min::new(self.create_physical_expr(&args[0], input_schema)?)
+                            .with_collation(self.collation),
+                    )),
+                    "count" => Ok(Arc::new(Count::new(
+                        self.create_physical_expr(&args[0], input_schema)?,
+                    ))),
+                    "bool_and" => Ok(Arc::new(BoolAnd::new(
                         self.create_physical_expr(&args[0], input_schema)?,
                     ))),
-                    "min" => Ok(Arc::new(Min::new(
+                    "bool_or" => Ok(Arc::new(BoolOr::new(
                         self.create_physical_expr(&args[0], input_schema)?,
                     ))),
-                    "count" => Ok(Arc::new(Count::new(
+                    "max_by" | "min_by" => Ok(Arc::new(MinMaxBy::new(
+                        self.create_physical_expr(&args[0], input_schema)?,
+                        self.create_physical_expr(&args[1], input_schema)?,
+                        name.eq_ignore_ascii_case("max_by"),
+                    ))),
+                    "histogram" => Ok(Arc::new(Histogram::new(
+                        self.create_physical_expr(&args[0], input_schema)?,
+                        histogram_buckets(&args[1..])?,
+                    ))),
+                    "reservoir_sample" => Ok(Arc::new(ReservoirSample::new(
                         self.create_physical_expr(&args[0], input_schema)?,
+                        reservoir_sample_size(&args[1])?,
                     ))),
                     other => Err(ExecutionError::NotImplemented(format!(
                         "Unsupported aggregate function '{}'",
@@ -486,6 +1879,145 @@ impl ExecutionContext {
         }
     }
 
+    /// Create a physical sort expression from a logical sort expression
+    pub fn create_physical_sort_expr(
+        &self,
+        e: &Expr,
+        input_schema: &Schema,
+    ) -> Result<PhysicalSortExpr> {
+        match e {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => Ok(PhysicalSortExpr {
+                expr: self.create_physical_expr(expr, input_schema)?,
+                options: SortOptions {
+                    descending: !*asc,
+                    nulls_first: *nulls_first,
+                    collation: self.collation,
+                },
+            }),
+            _ => Err(ExecutionError::ExecutionError(
+                "Sort only accepts sort expressions".to_string(),
+            )),
+        }
+    }
+
+    /// Create a window function expression from a logical expression
+    pub fn create_window_expr(
+        &self,
+        e: &Expr,
+        input_schema: &Schema,
+    ) -> Result<Arc<dyn WindowExpr>> {
+        match e {
+            Expr::WindowFunction {
+                name,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+                return_type,
+            } => {
+                let partition_by = partition_by
+                    .iter()
+                    .map(|e| self.create_physical_expr(e, input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+                let order_by = order_by
+                    .iter()
+                    .map(|e| self.create_physical_sort_expr(e, input_schema))
+                    .collect::<Result<Vec<_>>>()?;
+                let rank_type = match name.to_lowercase().as_ref() {
+                    "row_number" => RankType::RowNumber,
+                    "rank" => RankType::Rank,
+                    "dense_rank" => RankType::DenseRank,
+                    "ntile" => match args.get(0) {
+                        Some(Expr::Literal(ScalarValue::Int64(n))) if *n > 0 => {
+                            RankType::Ntile(*n as u64)
+                        }
+                        _ => {
+                            return Err(ExecutionError::General(format!(
+                                "Invalid arguments for NTILE: {:?}",
+                                args
+                            )))
+                        }
+                    },
+                    lower_name @ "lag"
+                    | lower_name @ "lead"
+                    | lower_name @ "first_value"
+                    | lower_name @ "last_value" => {
+                        let value_type = match (lower_name, args.get(1)) {
+                            ("lag", Some(Expr::Literal(ScalarValue::Int64(n)))) => {
+                                ValueType::Lag(*n as usize)
+                            }
+                            ("lead", Some(Expr::Literal(ScalarValue::Int64(n)))) => {
+                                ValueType::Lead(*n as usize)
+                            }
+                            ("first_value", None) => ValueType::FirstValue,
+                            ("last_value", None) => ValueType::LastValue,
+                            _ => {
+                                return Err(ExecutionError::General(format!(
+                                    "Invalid arguments for {}: {:?}",
+                                    name, args
+                                )))
+                            }
+                        };
+                        let mut expr = ValueExpr::new(
+                            name,
+                            value_type,
+                            self.create_physical_expr(&args[0], input_schema)?,
+                            partition_by,
+                            order_by,
+                            *window_frame,
+                        );
+                        if let Some(default) = args.get(2) {
+                            expr = expr.with_default(
+                                self.create_physical_expr(default, input_schema)?,
+                            );
+                        }
+                        return Ok(Arc::new(expr));
+                    }
+                    _ => {
+                        // other window functions are aggregate functions evaluated
+                        // over the frame of each row
+                        let aggregate = self.create_aggregate_expr(
+                            &Expr::AggregateFunction {
+                                name: name.clone(),
+                                args: args.clone(),
+                                return_type: return_type.clone(),
+                            },
+                            input_schema,
+                        )?;
+                        if aggregate.requires_reducer() {
+                            return Err(ExecutionError::NotImplemented(format!(
+                                "Aggregate function '{}' is not supported as a window \
+                                 function",
+                                name
+                            )));
+                        }
+                        return Ok(Arc::new(AggregateWindowExpr::new(
+                            name,
+                            aggregate,
+                            partition_by,
+                            order_by,
+                            *window_frame,
+                        )));
+                    }
+                };
+                Ok(Arc::new(RankExpr::new(
+                    name,
+                    rank_type,
+                    partition_by,
+                    order_by,
+                )))
+            }
+            other => Err(ExecutionError::General(format!(
+                "Invalid window expression '{:?}'",
+                other
+            ))),
+        }
+    }
+
     /// Execute a physical plan and collect the results in memory
     pub fn collect(&self, plan: &dyn ExecutionPlan) -> Result<Vec<RecordBatch>> {
         let partitions = plan.partitions()?;
@@ -513,19 +2045,20 @@ impl ExecutionContext {
     }
 
     /// Execute a query and write the results to a partitioned CSV file
+    #[cfg(feature = "csv")]
     pub fn write_csv(&self, plan: &dyn ExecutionPlan, path: &str) -> Result<()> {
         // create directory to contain the CSV files (one per partition)
         let path = path.to_string();
         fs::create_dir(&path)?;
 
-        let threads: Vec<JoinHandle<Result<()>>> = plan
+        let threads: Vec<common::JoinHandle<Result<()>>> = plan
             .partitions()?
             .iter()
             .enumerate()
             .map(|(i, p)| {
                 let p = p.clone();
                 let path = path.clone();
-                thread::spawn(move || {
+                common::spawn(move || {
                     let filename = format!("part-{}.csv", i);
                     let path = Path::new(&path).join(&filename);
                     let file = fs::File::create(path)?;
@@ -563,7 +2096,10 @@ struct ExecutionContextSchemaProvider<'a> {
 
 impl SchemaProvider for ExecutionContextSchemaProvider<'_> {
     fn get_table_meta(&self, name: &str) -> Option<Arc<Schema>> {
-        self.datasources.get(name).map(|ds| ds.schema().clone())
+        match self.datasources.get(name) {
+            Some(ds) => Some(ds.schema().clone()),
+            None => InformationSchemaTable::schema_of(name),
+        }
     }
 
     fn get_function_meta(&self, name: &str) -> Option<Arc<FunctionMeta>> {
@@ -578,17 +2114,60 @@ impl SchemaProvider for ExecutionContextSchemaProvider<'_> {
     }
 }
 
+/// Collect the metrics of the operators of a physical plan, visiting each operator
+/// before its inputs
+fn collect_metrics(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+    let mut lines = plan.metrics();
+    for child in plan.children() {
+        lines.extend(collect_metrics(child));
+    }
+    lines
+}
+
+/// Evaluate an expression of literals into an array with its value. Returns `None` for
+/// other expressions and null values.
+fn evaluate_constant(expr: &Expr) -> Result<Option<ArrayRef>> {
+    if let Some(value) = expr.evaluate_literal() {
+        return match value {
+            ScalarValue::Null => Ok(None),
+            value => Ok(Some(common::scalars_to_array(
+                &value.get_datatype(),
+                &[Some(value)],
+            )?)),
+        };
+    }
+    // casts that cannot be evaluated on scalar values are evaluated on arrays
+    match expr {
+        Expr::Cast { expr, data_type } => match evaluate_constant(expr)? {
+            Some(array) => Ok(Some(cast(&array, data_type)?)),
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use crate::datasource::MemTable;
+    use crate::execution::physical_plan::expressions::histogram_data_type;
     use crate::execution::physical_plan::udf::ScalarUdf;
     use crate::test;
-    use arrow::array::{ArrayRef, Int32Array};
+    use crate::utils::array_value_to_string;
+    use arrow::array::{
+        ArrayRef, BooleanArray, Date32Array, Decimal128Builder, DictionaryArray,
+        FixedSizeListBuilder, Float32Array, Float64Array, Int32Array, Int32Builder,
+        Int64Array, ListArray, ListBuilder, StringArray, StringBuilder, StructArray,
+        TimestampNanosecondArray, TimestampSecondArray, UInt32Array, UInt64Array,
+    };
     use arrow::compute::add;
+    use std::convert::TryFrom;
     use std::fs::File;
     use std::io::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
     use tempdir::TempDir;
 
     #[test]
@@ -702,15 +2281,268 @@ mod tests {
     }
 
     #[test]
-    fn aggregate_grouped_avg() -> Result<()> {
-        let results = execute("SELECT c1, AVG(c2) FROM test GROUP BY c1", 4)?;
+    fn aggregate_grouped_by_alias_and_position() -> Result<()> {
+        let results =
+            execute("SELECT c1 + 1 AS next, SUM(c2) FROM test GROUP BY next", 4)?;
         assert_eq!(results.len(), 1);
 
         let batch = &results[0];
-        let expected: Vec<&str> = vec!["0,5.5", "1,5.5", "2,5.5", "3,5.5"];
+        assert_eq!(batch.schema().field(0).name(), "next");
         let mut rows = test::format_batch(&batch);
         rows.sort();
-        assert_eq!(rows, expected);
+        assert_eq!(rows, vec!["1,55", "2,55", "3,55", "4,55"]);
+
+        let results = execute("SELECT SUM(c2), c1 FROM test GROUP BY 2", 4)?;
+        assert_eq!(results.len(), 1);
+
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["55,0", "55,1", "55,2", "55,3"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_having() -> Result<()> {
+        let results = execute(
+            "SELECT c1, SUM(c2) AS total FROM test GROUP BY c1 \
+             HAVING MAX(c2) = 10 AND c1 > 1 AND total > 0",
+            4,
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        assert_eq!(batch.schema().field(1).name(), "total");
+        let mut rows = test::format_batch(&batch);
+        rows.sort();
+        assert_eq!(rows, vec!["2,55", "3,55"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_max_by_min_by() -> Result<()> {
+        // a single partition is aggregated in two phases as well
+        for partitions in &[1, 4] {
+            let results = execute(
+                "SELECT MAX_BY(c1, c2 * 10 + c1), MIN_BY(c1, c2 * 10 + c1) FROM test",
+                *partitions,
+            )?;
+            assert_eq!(results.len(), 1);
+
+            let batch = &results[0];
+            let expected = format!("{},0", partitions - 1);
+            assert_eq!(test::format_batch(&batch), vec![expected]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_max_by() -> Result<()> {
+        let results = execute(
+            "SELECT c1, MAX_BY(c2, c2), MIN_BY(c2 * 2, c2) FROM test GROUP BY c1",
+            4,
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        let expected: Vec<&str> = vec!["0,10,2", "1,10,2", "2,10,2", "3,10,2"];
+        let mut rows = test::format_batch(&batch);
+        rows.sort();
+        assert_eq!(rows, expected);
+
+        Ok(())
+    }
+
+    /// The bucket counts of each row of a HISTOGRAM column
+    fn histogram_counts(array: &ArrayRef) -> Vec<Vec<u64>> {
+        let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+        (0..array.len())
+            .map(|row| {
+                (0..array.num_columns())
+                    .map(|i| {
+                        array
+                            .column(i)
+                            .as_any()
+                            .downcast_ref::<UInt64Array>()
+                            .unwrap()
+                            .value(row)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_histogram() -> Result<()> {
+        for partitions in &[1, 4] {
+            let results =
+                execute("SELECT HISTOGRAM(c2, 1, 11, 5) FROM test", *partitions)?;
+            assert_eq!(results.len(), 1);
+
+            let batch = &results[0];
+            assert_eq!(batch.schema().field(0).data_type(), &histogram_data_type(5));
+            let n = 2 * *partitions as u64;
+            assert_eq!(
+                histogram_counts(batch.column(0)),
+                vec![vec![0, n, n, n, n, n, 0]]
+            );
+        }
+
+        // the buckets of descending bounds are numbered from the first bound
+        let results = execute(
+            "SELECT c1, HISTOGRAM(c2, 10, 3, 1) FROM test WHERE c1 < 2 GROUP BY c1",
+            4,
+        )?;
+        let batch = &results[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(
+            histogram_counts(batch.column(1)),
+            vec![vec![0, 7, 3], vec![0, 7, 3]]
+        );
+
+        let tmp_dir = TempDir::new("aggregate_histogram")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+        for sql in &[
+            "SELECT HISTOGRAM(c2, 1, 11) FROM test",
+            "SELECT HISTOGRAM(c2, 1, c1, 5) FROM test",
+            "SELECT HISTOGRAM(c2, 1, 1, 5) FROM test",
+            "SELECT HISTOGRAM(c2, 1, 11, 0) FROM test",
+        ] {
+            assert!(ctx.create_logical_plan(sql).is_err(), "{}", sql);
+        }
+
+        Ok(())
+    }
+
+    /// The sorted values of each row of a list column of UInt64 values
+    fn sorted_lists(array: &ArrayRef) -> Vec<Vec<u64>> {
+        let array = array.as_any().downcast_ref::<ListArray>().unwrap();
+        (0..array.len())
+            .map(|row| {
+                let list = array.value(row);
+                let list = list.as_any().downcast_ref::<UInt64Array>().unwrap();
+                let mut values: Vec<u64> =
+                    (0..list.len()).map(|i| list.value(i)).collect();
+                values.sort();
+                values
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_reservoir_sample() -> Result<()> {
+        // all values are sampled if there are no more than the size of the sample
+        for partitions in &[1, 4] {
+            let results =
+                execute("SELECT RESERVOIR_SAMPLE(c2, 40) FROM test", *partitions)?;
+            let batch = &results[0];
+            assert_eq!(
+                batch.schema().field(0).data_type(),
+                &DataType::List(Box::new(DataType::UInt64))
+            );
+            let mut expected: Vec<u64> =
+                (1..=10).flat_map(|v| vec![v; *partitions]).collect();
+            expected.sort();
+            assert_eq!(sorted_lists(batch.column(0)), vec![expected]);
+        }
+
+        // otherwise the sample has the requested size and each row is sampled once
+        let results = execute("SELECT RESERVOIR_SAMPLE(c2, 25) FROM test", 4)?;
+        let sample = &sorted_lists(results[0].column(0))[0];
+        assert_eq!(sample.len(), 25);
+        for v in 1..=10 {
+            let n = sample.iter().filter(|s| **s == v).count();
+            assert!(n <= 4, "{} sampled {} times", v, n);
+        }
+
+        let results = execute(
+            "SELECT c1, RESERVOIR_SAMPLE(c2, 3) FROM test WHERE c2 > 2 GROUP BY c1",
+            4,
+        )?;
+        let batch = &results[0];
+        assert_eq!(batch.num_rows(), 4);
+        for sample in sorted_lists(batch.column(1)) {
+            assert_eq!(sample.len(), 3);
+            assert!(sample.windows(2).all(|w| w[0] < w[1]));
+            assert!(sample.iter().all(|v| *v > 2 && *v <= 10));
+        }
+
+        let tmp_dir = TempDir::new("aggregate_reservoir_sample")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+        for sql in &[
+            "SELECT RESERVOIR_SAMPLE(c2) FROM test",
+            "SELECT RESERVOIR_SAMPLE(c2, 0) FROM test",
+            "SELECT RESERVOIR_SAMPLE(c2, c1) FROM test",
+        ] {
+            assert!(ctx.create_logical_plan(sql).is_err(), "{}", sql);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn width_bucket_function() -> Result<()> {
+        let results = execute(
+            "SELECT c2, width_bucket(c2, 0, 10, 5), width_bucket(c2, 10, 0, 2) FROM test",
+            1,
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        let expected: Vec<&str> = vec![
+            "1,1,2", "2,2,2", "3,2,2", "4,3,2", "5,3,2", "6,4,1", "7,4,1", "8,5,1",
+            "9,5,1", "10,6,1",
+        ];
+        assert_eq!(test::format_batch(&batch), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_bool() -> Result<()> {
+        let results = execute("SELECT BOOL_AND(c2 > 0), BOOL_OR(c2 > 10) FROM test", 4)?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        assert_eq!(test::format_batch(&batch), vec!["true,false"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_bool() -> Result<()> {
+        let results = execute(
+            "SELECT c1, BOOL_AND(c2 > 1), EVERY(c2 > 0), ANY(c1 > 2) FROM test GROUP BY c1",
+            4,
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        let expected: Vec<&str> = vec![
+            "0,false,true,false",
+            "1,false,true,false",
+            "2,false,true,false",
+            "3,false,true,true",
+        ];
+        let mut rows = test::format_batch(&batch);
+        rows.sort();
+        assert_eq!(rows, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_avg() -> Result<()> {
+        let results = execute("SELECT c1, AVG(c2) FROM test GROUP BY c1", 4)?;
+        assert_eq!(results.len(), 1);
+
+        let batch = &results[0];
+        let expected: Vec<&str> = vec!["0,5.5", "1,5.5", "2,5.5", "3,5.5"];
+        let mut rows = test::format_batch(&batch);
+        rows.sort();
+        assert_eq!(rows, expected);
 
         Ok(())
     }
@@ -857,6 +2689,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn copy_to_files() -> Result<()> {
+        let tmp_dir = TempDir::new("copy_to_files")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        // the files are not written to the directory of the table
+        let out_dir = TempDir::new("copy_to_files_out")?;
+        let out = |name: &str| out_dir.path().join(name).to_str().unwrap().to_string();
+        let aggregates = "SELECT COUNT(*), SUM(c1), MIN(c2), MAX(c2) FROM";
+        let results = collect(&mut ctx, &format!("{} test", aggregates))?;
+        let expected = test::format_batch(&results[0]);
+
+        // the partitions are written to a single file, as CSV if no format is given
+        let query = "COPY (SELECT c1, c2 FROM test) TO";
+        ctx.sql(&format!("{} '{}'", query, out("test.csv")), 1024)?;
+        let sql = format!("{} '{}' (FORMAT PARQUET)", query, out("test.parquet"));
+        ctx.sql(&sql, 1024)?;
+        let sql = format!("{} '{}' (FORMAT JSON)", query, out("test.json"));
+        ctx.sql(&sql, 1024)?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::UInt32, false),
+            Field::new("c2", DataType::UInt64, false),
+        ]));
+        ctx.register_csv("csv", &out("test.csv"), &schema, true);
+        ctx.register_parquet("parquet", &out("test.parquet"))?;
+        ctx.register_ndjson("json", &out("test.json"))?;
+        for table in &["csv", "parquet", "json"] {
+            let results = collect(&mut ctx, &format!("{} {}", aggregates, table))?;
+            assert_eq!(test::format_batch(&results[0]), expected);
+        }
+
+        // the rows are written in the order of the query
+        let sql = "SELECT c2, c1 FROM test WHERE c1 < 3 ORDER BY c2 DESC, c1";
+        ctx.sql(&format!("COPY ({}) TO '{}'", sql, out("sorted.csv")), 1024)?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c2", DataType::UInt64, false),
+            Field::new("c1", DataType::UInt32, false),
+        ]));
+        ctx.register_csv("sorted", &out("sorted.csv"), &schema, true);
+        assert_eq!(
+            test::format_batch(&collect(&mut ctx, "SELECT c2, c1 FROM sorted")?[0]),
+            test::format_batch(&collect(&mut ctx, sql)?[0])
+        );
+
+        // only queries can be copied, to files of a known format
+        let sql = format!("COPY test TO '{}'", out("t.csv"));
+        assert!(ctx.sql(&sql, 1024).is_err());
+        let sql = format!("{} '{}' (FORMAT XML)", query, out("t.xml"));
+        assert!(ctx.sql(&sql, 1024).is_err());
+        Ok(())
+    }
+
     #[test]
     fn scalar_udf() -> Result<()> {
         let schema = Arc::new(Schema::new(vec![
@@ -950,6 +2833,2142 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn join_broadcast() -> Result<()> {
+        let tmp_dir = TempDir::new("join_broadcast")?;
+        let mut ctx = create_join_ctx(&tmp_dir, 4)?;
+
+        // the small table on the left is broadcast to every partition of the right
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim JOIN test ON id = c1")?;
+        assert_join_results(&results, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_partitioned() -> Result<()> {
+        let tmp_dir = TempDir::new("join_partitioned")?;
+        let mut ctx = create_join_ctx(&tmp_dir, 4)?;
+
+        // a zero threshold forces both inputs to be repartitioned on the join keys
+        ctx.set_broadcast_join_threshold(0);
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim JOIN test ON id = c1")?;
+        assert_join_results(&results, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_with_max_concurrent_partitions() -> Result<()> {
+        let tmp_dir = TempDir::new("join_with_max_concurrent_partitions")?;
+        let mut ctx = create_join_ctx(&tmp_dir, 4)?;
+        ctx.set_broadcast_join_threshold(0);
+        assert!(ctx.set_max_concurrent_partitions(0).is_err());
+        ctx.set_max_concurrent_partitions(1)?;
+
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim JOIN test ON id = c1")?;
+        assert_join_results(&results, 4);
+        assert_eq!(ctx.scheduler().running(), 0);
+
+        // the partitions of aggregates and sorts above the join also need slots
+        let results = collect(
+            &mut ctx,
+            "SELECT name, COUNT(*) FROM dim JOIN test ON id = c1 \
+             GROUP BY name ORDER BY name",
+        )?;
+        assert_eq!(results.iter().map(|b| b.num_rows()).sum::<usize>(), 4);
+        assert_eq!(ctx.scheduler().running(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_spilled() -> Result<()> {
+        let tmp_dir = TempDir::new("join_spilled")?;
+        let mut ctx = create_join_ctx(&tmp_dir, 4)?;
+
+        // a tiny memory limit forces the hash table to be spilled to disk
+        ctx.set_memory_limit(1);
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim JOIN test ON id = c1")?;
+        assert_join_results(&results, 4);
+        assert_eq!(ctx.memory_manager().used(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cross_join() -> Result<()> {
+        let tmp_dir = TempDir::new("cross_join")?;
+        let mut ctx = create_join_ctx(&tmp_dir, 4)?;
+
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim CROSS JOIN test")?;
+        let row_count: usize = results.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(row_count, 4 * 40);
+
+        // an implicit cross join filtered on an equality behaves like an inner join
+        let results = collect(&mut ctx, "SELECT name, c2 FROM dim, test WHERE id = c1")?;
+        assert_join_results(&results, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort() -> Result<()> {
+        let tmp_dir = TempDir::new("sort")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let results = collect(&mut ctx, "SELECT c1, c2 FROM test ORDER BY c1 DESC, c2")?;
+        assert_eq!(results.len(), 1);
+
+        let rows = test::format_batch(&results[0]);
+        assert_eq!(rows.len(), 40);
+        assert_eq!(rows[..3].to_vec(), vec!["3,1", "3,2", "3,3"]);
+        assert_eq!(rows[37..].to_vec(), vec!["0,8", "0,9", "0,10"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_spilled() -> Result<()> {
+        let tmp_dir = TempDir::new("sort_spilled")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        // a tiny memory limit forces every input batch to be spilled as a sorted run
+        ctx.set_memory_limit(1);
+        let results = collect(&mut ctx, "SELECT c1, c2 FROM test ORDER BY c1 DESC, c2")?;
+
+        let rows: Vec<String> = results.iter().flat_map(test::format_batch).collect();
+        assert_eq!(rows.len(), 40);
+        assert_eq!(rows[..3].to_vec(), vec!["3,1", "3,2", "3,3"]);
+        assert_eq!(rows[37..].to_vec(), vec!["0,8", "0,9", "0,10"]);
+        assert_eq!(ctx.memory_manager().used(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn window_functions() -> Result<()> {
+        let tmp_dir = TempDir::new("window_functions")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT c1, c2, ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c2 DESC), \
+             RANK() OVER (ORDER BY c1), DENSE_RANK() OVER (ORDER BY c1) \
+             FROM test ORDER BY c1, c2 DESC",
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let rows = test::format_batch(&results[0]);
+        assert_eq!(rows.len(), 40);
+        assert_eq!(
+            rows[..3].to_vec(),
+            vec!["0,10,1,1,1", "0,9,2,1,1", "0,8,3,1,1"]
+        );
+        assert_eq!(
+            rows[37..].to_vec(),
+            vec!["3,3,8,31,4", "3,2,9,31,4", "3,1,10,31,4"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_on() -> Result<()> {
+        let tmp_dir = TempDir::new("distinct_on")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        // the first row of each partition in the order of the ORDER BY clause is kept
+        let results = collect(
+            &mut ctx,
+            "SELECT DISTINCT ON (c1) c1, c2 FROM test ORDER BY c1, c2 DESC",
+        )?;
+        let rows: Vec<String> = results.iter().flat_map(test::format_batch).collect();
+        assert_eq!(rows, vec!["0,10", "1,10", "2,10", "3,10"]);
+
+        let results = collect(
+            &mut ctx,
+            "SELECT DISTINCT ON (c2 / 5) c2 / 5, c1 FROM test \
+             ORDER BY c2 / 5, c1 DESC LIMIT 2",
+        )?;
+        let rows: Vec<String> = results.iter().flat_map(test::format_batch).collect();
+        assert_eq!(rows, vec!["0,3", "1,3"]);
+
+        let plan = ctx.create_logical_plan(
+            "SELECT DISTINCT ON (c1) c1, c2 FROM test ORDER BY c2, c1",
+        );
+        assert!(plan.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_element() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        // [a, b], NULL, [c], []
+        let mut tags = ListBuilder::new(StringBuilder::new(4));
+        tags.values().append_value("a")?;
+        tags.values().append_value("b")?;
+        tags.append(true)?;
+        tags.append(false)?;
+        tags.values().append_value("c")?;
+        tags.append(true)?;
+        tags.append(true)?;
+        let mut points = FixedSizeListBuilder::new(Int32Builder::new(8), 2);
+        for (x, y) in &[(1, 2), (3, 4), (5, 6), (7, 8)] {
+            points.values().append_value(*x)?;
+            points.values().append_value(*y)?;
+            points.append(true)?;
+        }
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("tags", DataType::List(Box::new(DataType::Utf8)), true),
+            Field::new(
+                "point",
+                DataType::FixedSizeList(Box::new(DataType::Int32), 2),
+                false,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(tags.finish()),
+                Arc::new(points.finish()),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT id, tags[1], tags[2], point[2] FROM t")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["1,a,b,2", "2,NULL,NULL,4", "3,c,NULL,6", "4,NULL,NULL,8"]
+        );
+
+        // the index may be an expression, and the out of bounds indices are null
+        let results = collect(
+            &mut ctx,
+            "SELECT t.point[id - 1], element_at(tags, 1) FROM t",
+        )?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["NULL,a", "3,NULL", "6,c", "NULL,NULL"]
+        );
+
+        assert!(ctx.create_logical_plan("SELECT id[1] FROM t").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_window_functions() -> Result<()> {
+        let tmp_dir = TempDir::new("aggregate_window_functions")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT c1, c2, \
+             SUM(c2) OVER (PARTITION BY c1 ORDER BY c2 \
+             ROWS BETWEEN 2 PRECEDING AND CURRENT ROW), \
+             COUNT(*) OVER (PARTITION BY c1) \
+             FROM test ORDER BY c1, c2",
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let rows = test::format_batch(&results[0]);
+        assert_eq!(rows.len(), 40);
+        assert_eq!(
+            rows[..4].to_vec(),
+            vec!["0,1,1,10", "0,2,3,10", "0,3,6,10", "0,4,9,10"]
+        );
+        assert_eq!(rows[39], "3,10,27,10");
+
+        Ok(())
+    }
+
+    #[test]
+    fn value_window_functions() -> Result<()> {
+        let tmp_dir = TempDir::new("value_window_functions")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT c1, c2, \
+             LAG(c2) OVER (PARTITION BY c1 ORDER BY c2), \
+             LEAD(c2, 2, 0) OVER (PARTITION BY c1 ORDER BY c2), \
+             FIRST_VALUE(c2) OVER (PARTITION BY c1 ORDER BY c2 DESC), \
+             LAST_VALUE(c2) OVER (PARTITION BY c1 ORDER BY c2 \
+             ROWS BETWEEN CURRENT ROW AND 1 FOLLOWING), \
+             NTILE(3) OVER (PARTITION BY c1 ORDER BY c2) \
+             FROM test ORDER BY c1, c2",
+        )?;
+        assert_eq!(results.len(), 1);
+
+        let rows = test::format_batch(&results[0]);
+        assert_eq!(rows.len(), 40);
+        assert_eq!(
+            rows[..5].to_vec(),
+            vec![
+                "0,1,NULL,3,10,2,1",
+                "0,2,1,4,10,3,1",
+                "0,3,2,5,10,4,1",
+                "0,4,3,6,10,5,1",
+                "0,5,4,7,10,6,2",
+            ]
+        );
+        assert_eq!(rows[38], "3,9,8,0,10,10,3");
+        assert_eq!(rows[39], "3,10,9,0,10,10,3");
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_by_expr_not_projected() -> Result<()> {
+        let tmp_dir = TempDir::new("sort_by_expr_not_projected")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT c1 FROM test ORDER BY c2 * 10 + c1 DESC LIMIT 4",
+        )?;
+        assert_eq!(results[0].num_columns(), 1);
+        assert_eq!(test::format_batch(&results[0]), vec!["3", "2", "1", "0"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn string_concat_and_modulus() -> Result<()> {
+        let tmp_dir = TempDir::new("string_concat_and_modulus")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let results = collect(
+            &mut ctx,
+            "SELECT c1 || '-' || c2, c2 % 3 FROM test WHERE c2 % 4 = 0",
+        )?;
+        assert_eq!(test::format_batch(&results[0]), vec!["0-4,1", "0-8,2"]);
+
+        // the modulus of a division by zero is an error
+        assert!(collect(&mut ctx, "SELECT c2 % 0 FROM test").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn like_ilike_and_escape() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec![
+                Some("Apache Arrow"),
+                Some("100%"),
+                Some("a.b"),
+                None,
+            ]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s LIKE 'a%'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a.b"]);
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s ILIKE 'a%'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["Apache Arrow", "a.b"]);
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s NOT ILIKE '%ARROW'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["100%", "a.b"]);
+
+        // the pattern is matched with the whole value, and only `%` and `_` are
+        // wildcards
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s LIKE 'a_b'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a.b"]);
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s NOT LIKE 'a.'")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["Apache Arrow", "100%", "a.b"]
+        );
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s LIKE '%!%' ESCAPE '!'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["100%"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn regular_expressions() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("s", DataType::Utf8, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec![
+                Some("Apache Arrow"),
+                Some("key=value"),
+                Some("a.b"),
+                None,
+            ]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s ~ '^a'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a.b"]);
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s ~* '^a'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["Apache Arrow", "a.b"]);
+
+        let results = collect(&mut ctx, "SELECT s FROM t WHERE s !~ '\\w=\\w'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["Apache Arrow", "a.b"]);
+
+        let results = collect(&mut ctx, "SELECT s ~ 'A', s !~* 'A' FROM t")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["true,false", "false,false", "false,false", "NULL,NULL"]
+        );
+
+        let results = collect(
+            &mut ctx,
+            "SELECT regexp_match(s, '(\\w+)=(\\w+)')[2], \
+             regexp_replace(s, '(\\w)\\.', '$1-') FROM t",
+        )?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec![
+                "NULL,Apache Arrow",
+                "value,key=value",
+                "NULL,a-b",
+                "NULL,NULL"
+            ]
+        );
+
+        // invalid patterns are errors
+        assert!(collect(&mut ctx, "SELECT s FROM t WHERE s ~ '('").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_is_null() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![
+                Some(2),
+                None,
+                Some(1),
+                None,
+            ]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT a FROM t WHERE a IS NOT NULL")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2", "1"]);
+
+        let results = collect(&mut ctx, "SELECT a FROM t WHERE a IS NULL")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["NULL", "NULL"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_partitioned_mem_table() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let partitions = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]
+            .into_iter()
+            .map(|values| {
+                Ok(vec![RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(values))],
+                )?])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ctx.register_table(
+            "t",
+            Box::new(MemTable::new_partitioned(schema, partitions)?),
+        );
+
+        // the first partition is skipped based on its statistics
+        let logical_plan =
+            ctx.create_logical_plan("SELECT a FROM t WHERE a > 5 AND a < 8")?;
+        let logical_plan = ctx.optimize(&logical_plan)?;
+        let physical_plan = ctx.create_physical_plan(&logical_plan, 1024)?;
+        assert_eq!(physical_plan.partitions()?.len(), 2);
+
+        let mut values = vec![];
+        for batch in ctx.collect(physical_plan.as_ref())? {
+            values.extend(test::format_batch(&batch));
+        }
+        values.sort();
+        assert_eq!(values, vec!["6", "7"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_nulls() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![
+                Some(2),
+                None,
+                Some(1),
+                None,
+                Some(3),
+            ]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let queries = vec![
+            (
+                "SELECT a FROM t ORDER BY a",
+                vec!["1", "2", "3", "NULL", "NULL"],
+            ),
+            (
+                "SELECT a FROM t ORDER BY a DESC",
+                vec!["NULL", "NULL", "3", "2", "1"],
+            ),
+            (
+                "SELECT a FROM t ORDER BY a NULLS FIRST",
+                vec!["NULL", "NULL", "1", "2", "3"],
+            ),
+            (
+                "SELECT a FROM t ORDER BY a DESC NULLS LAST",
+                vec!["3", "2", "1", "NULL", "NULL"],
+            ),
+        ];
+        for (sql, expected) in queries {
+            let results = collect(&mut ctx, sql)?;
+            assert_eq!(test::format_batch(&results[0]), expected);
+        }
+
+        // the default placement of nulls is configurable
+        ctx.set_null_ordering(NullOrdering::NullsSmallest);
+        let results = collect(&mut ctx, "SELECT a FROM t ORDER BY a")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["NULL", "NULL", "1", "2", "3"]
+        );
+
+        Ok(())
+    }
+
+    /// Caches a table in memory for `CACHE TABLE name`, and records the other
+    /// statements that are parsed
+    struct CacheTableHook {
+        statements: Mutex<Vec<String>>,
+    }
+
+    impl StatementHook for CacheTableHook {
+        fn handle(
+            &self,
+            ctx: &mut ExecutionContext,
+            sql: &str,
+            statement: Option<&DFASTNode>,
+        ) -> Result<Option<Vec<RecordBatch>>> {
+            let words: Vec<&str> = sql.split_whitespace().collect();
+            match (words.as_slice(), statement) {
+                (["CACHE", "TABLE", name], None) => {
+                    let table = match ctx.table_provider(name) {
+                        Some(provider) => MemTable::load(provider)?,
+                        None => {
+                            return Err(ExecutionError::General(format!(
+                                "No table named '{}'",
+                                name
+                            )))
+                        }
+                    };
+                    ctx.register_table(name, Box::new(table));
+                    Ok(Some(vec![]))
+                }
+                (_, Some(_)) => {
+                    self.statements.lock().unwrap().push(sql.to_string());
+                    Ok(None)
+                }
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn statement_hook() -> Result<()> {
+        let tmp_dir = TempDir::new("statement_hook")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        let hook = Arc::new(CacheTableHook {
+            statements: Mutex::new(vec![]),
+        });
+        ctx.register_statement_hook(hook.clone());
+
+        let sql = "SELECT COUNT(*) FROM test";
+        let results = ctx.sql(sql, 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["40"]);
+
+        assert!(ctx.sql("CACHE TABLE test", 1024)?.is_empty());
+        let results = ctx.sql(sql, 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["40"]);
+        assert_eq!(*hook.statements.lock().unwrap(), vec![sql, sql]);
+
+        // statements that no hook handles are still planned
+        assert!(ctx.sql("CACHE TABLE missing", 1024).is_err());
+        match ctx.sql("UNCACHE TABLE test", 1024) {
+            Err(ExecutionError::ParserError(_)) => {}
+            _ => panic!("expected a parser error"),
+        }
+
+        Ok(())
+    }
+
+    /// Records the events of query listeners
+    struct RecordingListener {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl QueryListener for RecordingListener {
+        fn on_start(&self, query: &QueryInfo) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("start {} {}", query.id, query.fingerprint));
+        }
+
+        fn on_end(
+            &self,
+            query: &QueryInfo,
+            _elapsed: Duration,
+            result: std::result::Result<usize, &ExecutionError>,
+        ) {
+            let result = match result {
+                Ok(row_count) => format!("{} rows", row_count),
+                Err(_) => "error".to_string(),
+            };
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("end {} {} {}", query.id, query.fingerprint, result));
+        }
+    }
+
+    #[test]
+    fn query_listener() -> Result<()> {
+        let tmp_dir = TempDir::new("query_listener")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        let listener = Arc::new(RecordingListener {
+            events: Mutex::new(vec![]),
+        });
+        ctx.register_query_listener(listener.clone());
+        let fail: ScalarUdf =
+            |_: &Vec<ArrayRef>| Err(ExecutionError::General("failed".to_string()));
+        ctx.register_udf(ScalarFunction::new(
+            "fail",
+            vec![Field::new("a", DataType::UInt32, true)],
+            DataType::UInt32,
+            fail,
+        ));
+
+        let sql = "SELECT c1 FROM test WHERE c2 > 8";
+        ctx.sql(sql, 1024)?;
+        let first_id = ctx.last_query_id();
+        ctx.sql(sql, 1024)?;
+        let second_id = ctx.last_query_id();
+        assert_ne!(first_id, second_id);
+
+        let plan = ctx.create_logical_plan(sql)?;
+        let fingerprint = ctx.optimize(&plan)?.fingerprint();
+        let failing_sql = "SELECT fail(c1) FROM test";
+        match ctx.sql(failing_sql, 1024) {
+            Err(ExecutionError::Query(id, e)) => {
+                assert_eq!(id, ctx.last_query_id());
+                match *e {
+                    ExecutionError::General(ref message) => assert_eq!(message, "failed"),
+                    _ => panic!("unexpected error {:?}", e),
+                }
+            }
+            _ => panic!("expected the query to fail"),
+        }
+        let failing_id = ctx.last_query_id();
+        let plan = ctx.create_logical_plan(failing_sql)?;
+        let failing_fingerprint = ctx.optimize(&plan)?.fingerprint();
+        assert_ne!(fingerprint, failing_fingerprint);
+
+        assert_eq!(
+            *listener.events.lock().unwrap(),
+            vec![
+                format!("start {} {}", first_id, fingerprint),
+                format!("end {} {} 8 rows", first_id, fingerprint),
+                format!("start {} {}", second_id, fingerprint),
+                format!("end {} {} 8 rows", second_id, fingerprint),
+                format!("start {} {}", failing_id, failing_fingerprint),
+                format!("end {} {} error", failing_id, failing_fingerprint),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_by_boolean_column() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Boolean, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(BooleanArray::from(vec![true, false, true, true])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT a, SUM(b) FROM t GROUP BY a")?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["false,2", "true,8"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_decimal_column() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, false),
+            Field::new("price", DataType::Decimal(10, 2), true),
+        ]));
+        let mut prices = Decimal128Builder::new(4, 10, 2);
+        prices.append_value(1025)?;
+        prices.append_value(1050)?;
+        prices.append_null()?;
+        prices.append_value(-199)?;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["x", "x", "y", "y"])),
+                Arc::new(prices.finish()),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT price FROM t WHERE price > 10.25")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["10.50"]);
+
+        let results = collect(
+            &mut ctx,
+            "SELECT a, SUM(price), AVG(price) FROM t GROUP BY a",
+        )?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["x,20.75,10.375000", "y,-1.99,-1.990000"]);
+
+        let results = collect(
+            &mut ctx,
+            "SELECT CAST(price AS DECIMAL(10, 1)), CAST('1.005' AS DECIMAL(5, 2)) \
+             FROM t WHERE price = CAST(10.5 AS DECIMAL(4, 2))",
+        )?;
+        assert_eq!(test::format_batch(&results[0]), vec!["10.5,1.01"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_by_nullable_columns() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(1), None, None])),
+                Arc::new(StringArray::try_from(vec![
+                    Some("x"),
+                    Some("x"),
+                    None,
+                    Some("x"),
+                    None,
+                ])?),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT a, SUM(c) FROM t GROUP BY a")?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["1,4", "NULL,11"]);
+
+        let results = collect(&mut ctx, "SELECT a, b, SUM(c) FROM t GROUP BY a, b")?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["1,NULL,3", "1,x,1", "NULL,NULL,5", "NULL,x,6"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_by_dictionary_column() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "a",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let dictionary: DictionaryArray<Int32Type> =
+            vec![Some("x"), Some("y"), Some("x"), None]
+                .into_iter()
+                .collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(dictionary),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT a, SUM(b) FROM t GROUP BY a")?;
+        let groups = results[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        let values = groups.values();
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        let sums = results[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        let mut rows: Vec<(Option<String>, i64)> = groups
+            .keys()
+            .enumerate()
+            .map(|(i, key)| {
+                (
+                    key.map(|key| values.value(key as usize).to_string()),
+                    sums.value(i),
+                )
+            })
+            .collect();
+        rows.sort();
+        assert_eq!(
+            rows,
+            vec![
+                (None, 4),
+                (Some("x".to_string()), 4),
+                (Some("y".to_string()), 2)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn filter_dictionary_columns_with_any_key_type() -> Result<()> {
+        let values = vec![Some("x"), Some("y"), Some("x"), None];
+        let dictionaries: Vec<(DataType, ArrayRef)> = vec![
+            (
+                DataType::Int8,
+                Arc::new(
+                    values
+                        .clone()
+                        .into_iter()
+                        .collect::<DictionaryArray<Int8Type>>(),
+                ),
+            ),
+            (
+                DataType::UInt16,
+                Arc::new(
+                    values
+                        .clone()
+                        .into_iter()
+                        .collect::<DictionaryArray<UInt16Type>>(),
+                ),
+            ),
+            (
+                DataType::Int64,
+                Arc::new(values.into_iter().collect::<DictionaryArray<Int64Type>>()),
+            ),
+        ];
+        for (key_type, dictionary) in dictionaries {
+            let mut ctx = ExecutionContext::new();
+            // the dictionary is cast to its values for the comparison
+            let schema = Arc::new(Schema::new(vec![
+                Field::new(
+                    "a",
+                    DataType::Dictionary(Box::new(key_type), Box::new(DataType::Utf8)),
+                    true,
+                ),
+                Field::new("b", DataType::Int32, false),
+            ]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![dictionary, Arc::new(Int32Array::from(vec![1, 2, 3, 4]))],
+            )?;
+            ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+            let results = collect(&mut ctx, "SELECT b FROM t WHERE a = 'x' ORDER BY b")?;
+            assert_eq!(test::format_batch(&results[0]), vec!["1", "3"]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_by_float_columns() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Float32, false),
+            Field::new("b", DataType::Float64, false),
+            Field::new("c", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float32Array::from(vec![1.5, 0.0, 1.5, -0.0])),
+                Arc::new(Float64Array::from(vec![2.25, -0.0, 2.25, 0.0])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT a, b, SUM(c) FROM t GROUP BY a, b")?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["0.0,0.0,6", "1.5,2.25,4"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cancel_query_after_max_execution_time() -> Result<()> {
+        let tmp_dir = TempDir::new("cancel_query_after_max_execution_time")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        let sql = "SELECT c1, SUM(c2) FROM test GROUP BY c1";
+        assert_eq!(ctx.sql(sql, 1024)?[0].num_rows(), 4);
+
+        ctx.sql("SET max_execution_time_ms = 1", 1024)?;
+        let plan = ctx.create_logical_plan(sql)?;
+        let plan = ctx.optimize(&plan)?;
+        let plan = ctx.create_physical_plan(&plan, 1024)?;
+        thread::sleep(Duration::from_millis(10));
+        match ctx.collect(plan.as_ref()) {
+            Err(ExecutionError::Cancelled(_)) => {}
+            _ => panic!("expected the query to be cancelled"),
+        }
+
+        // the plan at the root of a query checks its deadline, even without table scans
+        let plan = ctx.create_logical_plan("SELECT 1 + 2")?;
+        let plan = ctx.optimize(&plan)?;
+        let plan = ctx.create_physical_plan(&plan, 1024)?;
+        thread::sleep(Duration::from_millis(10));
+        match ctx.collect(plan.as_ref()) {
+            Err(ExecutionError::Cancelled(_)) => {}
+            _ => panic!("expected the query without tables to be cancelled"),
+        }
+
+        ctx.sql("SET max_execution_time_ms = 0", 1024)?;
+        assert_eq!(ctx.sql(sql, 1024)?[0].num_rows(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn set_and_show_variables() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        assert!(ctx.sql("SET batch_size = 8192", 1024)?.is_empty());
+        assert!(ctx.sql("SET target_partitions TO 4", 1024)?.is_empty());
+        assert_eq!(ctx.config().batch_size, 8192);
+        assert_eq!(ctx.config().target_partitions, 4);
+
+        let results = ctx.sql("SHOW ALL", 1024)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec![
+                "batch_size,8192",
+                format!(
+                    "broadcast_join_threshold,{}",
+                    DEFAULT_BROADCAST_JOIN_THRESHOLD
+                )
+                .as_str(),
+                "max_execution_time_ms,0",
+                "target_partitions,4",
+            ]
+        );
+        let results = ctx.sql("SHOW BATCH_SIZE", 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["batch_size,8192"]);
+
+        assert!(ctx.sql("SET missing = 1", 1024).is_err());
+        assert!(ctx.sql("SET batch_size = 0", 1024).is_err());
+        assert!(ctx.sql("SHOW missing", 1024).is_err());
+        assert_eq!(ctx.config().batch_size, 8192);
+
+        Ok(())
+    }
+
+    #[test]
+    fn timestamps_with_timezone() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let tz = Some(Arc::new("+02:00".to_string()));
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Second, tz.clone()),
+            false,
+        )]));
+        let noon_utc = 1_590_969_600 + 12 * 3600;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(TimestampSecondArray::from_vec(
+                vec![noon_utc - 3600, noon_utc, noon_utc + 3600],
+                tz.clone(),
+            ))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        // the string without an offset is in the time zone of the column, while the
+        // string with an offset is an instant
+        let results = collect(
+            &mut ctx,
+            "SELECT ts FROM t \
+             WHERE ts >= to_timestamp('2020-06-01 14:00:00', '+02:00') \
+             AND ts < to_timestamp('2020-06-01T12:30:00Z')",
+        )?;
+        assert_eq!(
+            results[0].schema().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, tz)
+        );
+        assert_eq!(results[0].num_rows(), 1);
+        assert_eq!(
+            array_value_to_string(results[0].column(0).clone(), 0)?,
+            "2020-06-01T14:00:00+02:00"
+        );
+
+        // casting to a timestamp without a time zone keeps the instant
+        let results = collect(&mut ctx, "SELECT CAST(ts AS TIMESTAMP) FROM t")?;
+        let ts = results[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(ts.value(1), noon_utc * 1_000_000_000);
+
+        assert!(ctx
+            .sql(
+                "SELECT to_timestamp('2020-06-01', 'Europe/Berlin') FROM t",
+                1024
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_grouped_by_temporal_columns() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let utc = Some(Arc::new("UTC".to_string()));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("d", DataType::Date32(DateUnit::Day), false),
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Nanosecond, utc.clone()),
+                false,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Date32Array::from(vec![1, 2, 1, 1])),
+                Arc::new(TimestampNanosecondArray::from_vec(
+                    vec![10, 20, 10, 30],
+                    utc.clone(),
+                )),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT d, ts, COUNT(*) FROM t GROUP BY d, ts")?;
+        let batch = &results[0];
+        assert_eq!(
+            &DataType::Timestamp(TimeUnit::Nanosecond, utc),
+            batch.schema().field(1).data_type()
+        );
+        let d = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        let ts = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        let count = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        let mut rows: Vec<(i32, i64, u64)> = (0..batch.num_rows())
+            .map(|i| (d.value(i), ts.value(i), count.value(i)))
+            .collect();
+        rows.sort();
+        assert_eq!(rows, vec![(1, 10, 2), (1, 30, 1), (2, 20, 1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn case_insensitive_collation() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        ctx.set_collation(Collation::CaseInsensitive);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["b", "A", "a", "B", "c"])),
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+            ],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT b FROM t WHERE a = 'a'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2", "3"]);
+
+        let results = collect(&mut ctx, "SELECT a FROM t WHERE a > 'A'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["b", "B", "c"]);
+
+        let results = collect(&mut ctx, "SELECT a, b FROM t ORDER BY a, b DESC")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["a,3", "A,2", "B,4", "b,1", "c,5"]
+        );
+
+        let results = collect(&mut ctx, "SELECT a, SUM(b) FROM t GROUP BY a")?;
+        let mut rows = test::format_batch(&results[0]);
+        rows.sort();
+        assert_eq!(rows, vec!["a,5", "b,5", "c,5"]);
+
+        let results = collect(&mut ctx, "SELECT MIN(a), MAX(a) FROM t")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["A,c"]);
+
+        // locale-aware collations are not implemented
+        match ctx.set_collation_by_name("de_DE") {
+            Err(ExecutionError::NotImplemented(_)) => {}
+            other => panic!("Expected a NotImplemented error but got {:?}", other),
+        }
+        assert_eq!(ctx.collation(), Collation::CaseInsensitive);
+
+        // strings are compared by their bytes by default
+        ctx.set_collation_by_name("BINARY")?;
+        assert_eq!(ctx.collation(), Collation::Binary);
+        let results = collect(&mut ctx, "SELECT MIN(a), MAX(a) FROM t")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["A,c"]);
+        let results = collect(&mut ctx, "SELECT COUNT(a) FROM t WHERE a = 'a'")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_operations() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 3]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("b", DataType::Int64, false),
+            Field::new("c", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![2, 4, 3])),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])),
+            ],
+        )?;
+        ctx.register_table("u", Box::new(MemTable::new(schema, vec![batch])?));
+
+        // the columns of the union are named after the first query and have a type
+        // that the columns of all queries can be coerced to
+        let sql = "SELECT a FROM t UNION ALL SELECT b FROM u WHERE c <> 'x' \
+                   UNION ALL SELECT a FROM t ORDER BY a DESC LIMIT 4";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(results.len(), 1);
+        let schema = results[0].schema();
+        assert_eq!(schema.field(0).name(), "a");
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(test::format_batch(&results[0]), vec!["4", "3", "3", "3"]);
+
+        // set operations without ALL remove duplicate rows
+        let sql =
+            "SELECT a FROM t UNION SELECT b FROM u UNION SELECT a FROM t ORDER BY a";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1", "2", "3", "4"]);
+
+        let sql = "SELECT b FROM u INTERSECT SELECT a FROM t ORDER BY b";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["3"]);
+
+        let sql = "SELECT b FROM u EXCEPT SELECT a FROM t ORDER BY b";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2", "4"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn derived_table() -> Result<()> {
+        let tmp_dir = TempDir::new("derived_table")?;
+        let mut ctx = create_ctx(&tmp_dir, 2)?;
+
+        let sql = "SELECT * FROM (SELECT c2 AS x, c1 FROM test WHERE c1 = 1) AS sub \
+                   WHERE sub.x > 8 ORDER BY x";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].schema().field(0).name(), "x");
+        assert_eq!(test::format_batch(&results[0]), vec!["9,1", "10,1"]);
+
+        // only the columns used by the outer query are loaded
+        let plan =
+            ctx.create_logical_plan("SELECT c1 FROM (SELECT c1, c2 FROM test) AS sub")?;
+        let plan = ctx.optimize(&plan)?;
+        let expected = "Projection: #0\
+                        \n  Projection: #0\
+                        \n    TableScan: test projection=Some([0])";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        Ok(())
+    }
+
+    #[test]
+    fn subqueries() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )?;
+        ctx.register_table("customers", Box::new(MemTable::new(schema, vec![batch])?));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("customer_id", DataType::UInt32, false),
+            Field::new("amount", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 1, 3])),
+                Arc::new(Int64Array::from(vec![10, 30, 5])),
+            ],
+        )?;
+        ctx.register_table("orders", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let sql = "SELECT name FROM customers \
+                   WHERE id IN (SELECT customer_id FROM orders) ORDER BY name";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a", "c"]);
+
+        let sql = "SELECT name FROM customers \
+                   WHERE id NOT IN (SELECT customer_id FROM orders) ORDER BY name";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["b"]);
+
+        let sql = "SELECT name FROM customers WHERE EXISTS \
+                   (SELECT * FROM orders WHERE customer_id = id AND amount > 20)";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a"]);
+
+        let sql = "SELECT name FROM customers WHERE NOT EXISTS \
+                   (SELECT * FROM orders WHERE customer_id = customers.id) ORDER BY name";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["b"]);
+
+        // customers without orders get a null maximum
+        let sql = "SELECT name, (SELECT MAX(amount) FROM orders WHERE customer_id = id) \
+                   FROM customers ORDER BY name";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["a,30", "b,NULL", "c,5"]
+        );
+
+        let sql = "SELECT name FROM customers \
+                   WHERE (SELECT MAX(amount) FROM orders WHERE customer_id = id) > 20 \
+                   ORDER BY name";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a"]);
+
+        // a correlated COUNT would be null instead of zero for customers without orders
+        let sql = "SELECT name, (SELECT COUNT(*) FROM orders WHERE customer_id = id) \
+                   FROM customers";
+        assert!(collect(&mut ctx, sql).is_err());
+
+        // uncorrelated scalar subqueries are executed once and planned as literals
+        let sql = "SELECT name FROM customers \
+                   WHERE id = (SELECT MAX(customer_id) FROM orders)";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["c"]);
+
+        let sql = "SELECT name FROM customers \
+                   WHERE id = (SELECT customer_id FROM orders WHERE amount > 100)";
+        let results = collect(&mut ctx, sql)?;
+        assert!(results.iter().all(|batch| batch.num_rows() == 0));
+
+        let sql = "SELECT name FROM customers \
+                   WHERE id = (SELECT customer_id FROM orders WHERE amount > 20)";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["a"]);
+
+        let sql =
+            "SELECT name FROM customers WHERE id = (SELECT customer_id FROM orders)";
+        match collect(&mut ctx, sql) {
+            Err(ExecutionError::ExecutionError(message)) => {
+                assert_eq!(message, "Scalar subquery returned more than one row")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn constant_scalar_functions() -> Result<()> {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let tmp_dir = TempDir::new("constant_scalar_functions")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+        let double: ScalarUdf = |args: &Vec<ArrayRef>| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            let values = args[0].as_any().downcast_ref::<Int64Array>().unwrap();
+            Ok(Arc::new(add(values, values)?))
+        };
+        ctx.register_udf(ScalarFunction::new(
+            "double",
+            vec![Field::new("a", DataType::Int64, true)],
+            DataType::Int64,
+            double,
+        ));
+
+        // a deterministic function of constants is evaluated once per query
+        let sql = "SELECT COUNT(*) FROM test WHERE c2 > double(4)";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["8"]);
+        assert_eq!(CALLS.swap(0, Ordering::SeqCst), 1);
+
+        // other functions are evaluated for each batch
+        let double = ctx.scalar_functions()["double"].fun;
+        ctx.register_udf(
+            ScalarFunction::new(
+                "double",
+                vec![Field::new("a", DataType::Int64, true)],
+                DataType::Int64,
+                double,
+            )
+            .with_deterministic(false),
+        );
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["8"]);
+        // once for each of the partitions
+        assert_eq!(CALLS.swap(0, Ordering::SeqCst), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn between() -> Result<()> {
+        let tmp_dir = TempDir::new("between")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let sql = "SELECT c2 FROM test WHERE c2 BETWEEN 3 AND 5 ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["3", "4", "5"]);
+
+        let sql = "SELECT c2 FROM test WHERE c2 NOT BETWEEN 2 AND 9 ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1", "10"]);
+        Ok(())
+    }
+
+    #[test]
+    fn in_list() -> Result<()> {
+        let tmp_dir = TempDir::new("in_list")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let sql = "SELECT c2 FROM test WHERE c2 IN (3, 5, 11) ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["3", "5"]);
+
+        // a large list is probed through a hash set
+        let sql = "SELECT c2 FROM test WHERE c2 NOT IN (1, 2, 3, 4, 5, 6, 7, 8, 9)";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["10"]);
+
+        let sql = "SELECT c2 FROM test WHERE c2 IN (c1 + 1, 4) ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1", "4"]);
+        Ok(())
+    }
+
+    #[test]
+    fn quoted_identifiers() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("MixedCase Col", DataType::UInt32, false),
+            Field::new("amount", DataType::UInt32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(vec![1, 2, 3])),
+                Arc::new(UInt32Array::from(vec![10, 20, 30])),
+            ],
+        )?;
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("Sales", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let sql = "SELECT \"MixedCase Col\", amount FROM \"Sales\" \
+                   WHERE \"MixedCase Col\" > 1 ORDER BY amount";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2,20", "3,30"]);
+
+        // unquoted identifiers are matched after converting them to lowercase
+        ctx.set_identifier_case(IdentifierCase::Lowercase);
+        let sql = "SELECT AMOUNT AS \"Total\" FROM \"Sales\" WHERE \"MixedCase Col\" = 1";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(results[0].schema().field(0).name(), "Total");
+        assert_eq!(test::format_batch(&results[0]), vec!["10"]);
+        assert!(ctx.create_logical_plan("SELECT amount FROM Sales").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn self_join() -> Result<()> {
+        let tmp_dir = TempDir::new("self_join")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let sql = "SELECT a.c2, b.c2 FROM test a JOIN test b ON a.c1 = b.c1 \
+                   WHERE a.c2 = b.c2 + 1 AND b.c2 < 4 ORDER BY b.c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2,1", "3,2", "4,3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn fold_expressions_of_literals() -> Result<()> {
+        let tmp_dir = TempDir::new("fold_expressions_of_literals")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let sql = "SELECT c2 FROM test WHERE c2 > 4 + 4 ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["9", "10"]);
+
+        let sql = "SELECT c2 FROM test WHERE c2 IN (2 * 3, 10 - 1) ORDER BY c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["6", "9"]);
+
+        let sql = "SELECT c2 FROM test WHERE 1 = 1 AND c2 < 2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1"]);
+
+        let schema = Schema::new(vec![Field::new("c1", DataType::UInt32, false)]);
+        let sum = Expr::BinaryExpr {
+            left: Arc::new(Expr::Literal(ScalarValue::Int64(4))),
+            op: Operator::Plus,
+            right: Arc::new(Expr::Literal(ScalarValue::Int64(4))),
+        };
+        assert_eq!(ctx.create_physical_expr(&sum, &schema)?.name(), "lit");
+        Ok(())
+    }
+
+    #[test]
+    fn select_without_from() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let results = collect(&mut ctx, "SELECT 1 + 2, 'a', sqrt(16) * 2")?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(test::format_batch(&results[0]), vec!["3,a,8.0"]);
+
+        let results = collect(&mut ctx, "SELECT now()")?;
+        let schema = results[0].schema();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+        assert_eq!(results[0].num_rows(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn select_from_values() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let sql = "SELECT name, id * 2 FROM (VALUES (1, 'a'), (2.5, 'b'), (3, 'c')) \
+                   AS t(id, name) WHERE id > 2 OR name = 'a' ORDER BY id DESC";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["c,6.0", "b,5.0", "a,2.0"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn insert_into() -> Result<()> {
+        let tmp_dir = TempDir::new("insert_into")?;
+        let mut ctx = create_ctx(&tmp_dir, 2)?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![])?));
+
+        let results = ctx.sql("INSERT INTO t VALUES (1, 'a'), (2, 'b')", 1024)?;
+        assert!(results.is_empty());
+        // the columns of the query are cast to the types of the table
+        ctx.sql(
+            "INSERT INTO t (name, id) SELECT 'x', c2 FROM test WHERE c1 = 0 AND c2 > 8",
+            1024,
+        )?;
+
+        let results = collect(&mut ctx, "SELECT id, name FROM t ORDER BY id")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["1,a", "2,b", "9,x", "10,x"]
+        );
+
+        assert!(ctx
+            .sql("INSERT INTO missing VALUES (1, 'a')", 1024)
+            .is_err());
+        assert!(ctx.sql("INSERT INTO t VALUES (1)", 1024).is_err());
+        assert!(ctx.sql("INSERT INTO t (id) VALUES (1)", 1024).is_err());
+        assert!(ctx
+            .sql("INSERT INTO t (id, id) VALUES (1, 2)", 1024)
+            .is_err());
+        // CSV tables do not support inserting rows
+        assert!(ctx.sql("INSERT INTO test VALUES (1, 2)", 1024).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn untyped_nulls() -> Result<()> {
+        let tmp_dir = TempDir::new("untyped_nulls")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        // NULL takes the type of its context
+        let sql = "SELECT NULL + c2, COALESCE(NULL, c1) FROM test WHERE c2 < 2";
+        let results = collect(&mut ctx, sql)?;
+        let schema = results[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::UInt64);
+        assert_eq!(schema.field(1).data_type(), &DataType::UInt32);
+        assert_eq!(test::format_batch(&results[0]), vec!["NULL,0", "NULL,0"]);
+
+        // and is a string without one
+        let results = collect(&mut ctx, "SELECT NULL FROM test WHERE c2 = 0")?;
+        assert_eq!(results[0].schema().field(0).data_type(), &DataType::Utf8);
+        assert_eq!(test::format_batch(&results[0]), vec!["NULL"]);
+
+        // NULL is inserted as a value of the type of its column
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("active", DataType::Boolean, true),
+        ]));
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![])?));
+        ctx.sql("INSERT INTO t VALUES (1, NULL), (2, NULL)", 1024)?;
+        ctx.sql(
+            "INSERT INTO t (active, id) SELECT NULL, c2 FROM test WHERE c2 = 3",
+            1024,
+        )?;
+        let results = collect(&mut ctx, "SELECT id, active FROM t ORDER BY id")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["1,NULL", "2,NULL", "3,NULL"]
+        );
+        // but not into columns that are not nullable
+        assert!(ctx.sql("INSERT INTO t VALUES (NULL, NULL)", 1024).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_into() -> Result<()> {
+        let tmp_dir = TempDir::new("merge_into")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![])?));
+        ctx.sql("INSERT INTO t VALUES (1, 'a'), (2, 'b'), (3, 'c')", 1024)?;
+
+        let results = ctx.sql(
+            "MERGE INTO t USING (VALUES (2, 'x'), (3, 'y'), (4, 'z')) AS s(id, name) \
+             ON t.id = s.id \
+             WHEN MATCHED AND s.name = 'y' THEN DELETE \
+             WHEN MATCHED THEN UPDATE SET name = s.name \
+             WHEN NOT MATCHED THEN INSERT (id, name) VALUES (s.id, s.name)",
+            1024,
+        )?;
+        assert!(results.is_empty());
+        let results = collect(&mut ctx, "SELECT id, name FROM t ORDER BY id")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["1,a", "2,x", "4,z"]);
+
+        // source rows without a match are only inserted if a clause applies, and the
+        // columns that are not inserted into are null
+        ctx.sql(
+            "MERGE INTO t AS target \
+             USING (SELECT CAST(c2 AS BIGINT) + 3 AS id FROM test WHERE c2 <= 3) AS source \
+             ON target.id = source.id \
+             WHEN NOT MATCHED AND source.id > 5 THEN INSERT (id) VALUES (source.id)",
+            1024,
+        )?;
+        let results = collect(&mut ctx, "SELECT id, name FROM t ORDER BY id")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["1,a", "2,x", "4,z", "6,NULL"]
+        );
+
+        assert!(ctx
+            .sql(
+                "MERGE INTO t USING (VALUES (1)) AS s(id) ON t.id = s.id \
+                 WHEN MATCHED THEN UPDATE SET missing = 1",
+                1024
+            )
+            .is_err());
+        // the id of the table is not nullable
+        assert!(ctx
+            .sql(
+                "MERGE INTO t USING (VALUES (7)) AS s(id) ON t.id = s.id \
+                 WHEN NOT MATCHED THEN INSERT (name) VALUES ('n')",
+                1024
+            )
+            .is_err());
+        // CSV tables do not support replacing rows
+        assert!(ctx
+            .sql(
+                "MERGE INTO test USING test AS s ON test.c2 = s.c2 \
+                 WHEN MATCHED THEN DELETE",
+                1024
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn update_and_delete() -> Result<()> {
+        let tmp_dir = TempDir::new("update_and_delete")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("score", DataType::Int32, true),
+        ]));
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![])?));
+        ctx.sql(
+            "INSERT INTO t VALUES (1, 'a', 10), (2, 'b', 20), (3, NULL, 30)",
+            1024,
+        )?;
+        let rows = |ctx: &mut ExecutionContext| -> Result<Vec<String>> {
+            let results = collect(ctx, "SELECT id, name, score FROM t ORDER BY id")?;
+            Ok(results.iter().flat_map(test::format_batch).collect())
+        };
+
+        // the rows for which the condition is null are kept
+        let results = ctx.sql("DELETE FROM t WHERE name = 'b'", 1024)?;
+        assert!(results.is_empty());
+        assert_eq!(rows(&mut ctx)?, vec!["1,a,10", "3,NULL,30"]);
+
+        // the values are cast to the types of the columns
+        ctx.sql(
+            "UPDATE t SET score = score * 2, name = 'x' WHERE t.id > 1",
+            1024,
+        )?;
+        assert_eq!(rows(&mut ctx)?, vec!["1,a,10", "3,x,60"]);
+        ctx.sql("UPDATE t SET score = id", 1024)?;
+        assert_eq!(rows(&mut ctx)?, vec!["1,a,1", "3,x,3"]);
+
+        ctx.sql("DELETE FROM t", 1024)?;
+        assert!(rows(&mut ctx)?.is_empty());
+
+        assert!(ctx.sql("UPDATE t SET missing = 1", 1024).is_err());
+        assert!(ctx.sql("UPDATE missing SET id = 1", 1024).is_err());
+        // a misspelled WHERE clause is not ignored
+        assert!(ctx.sql("DELETE FROM t WERE id = 1", 1024).is_err());
+        // CSV tables do not support replacing rows
+        assert!(ctx.sql("DELETE FROM test WHERE c2 > 5", 1024).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sql_script() -> Result<()> {
+        let tmp_dir = TempDir::new("sql_script")?;
+        let mut ctx = create_ctx(&tmp_dir, 2)?;
+
+        // the results of the last statement are returned
+        let script = format!(
+            "CREATE EXTERNAL TABLE t (c1 INT, c2 BIGINT) STORED AS CSV \
+             WITH HEADER ROW LOCATION '{}';\n\
+             SELECT COUNT(*), SUM(c2) FROM t WHERE c2 > 5;\n",
+            tmp_dir.path().to_str().unwrap()
+        );
+        let results = ctx.sql(&script, 1024)?;
+        assert_eq!(test::format_batch(&results[0]), vec!["10,80"]);
+
+        // semicolons in strings do not separate statements
+        let results = ctx.sql("SELECT 'a;b' AS \"c;d\";;", 1024)?;
+        assert_eq!(results[0].schema().field(0).name(), "c;d");
+        assert_eq!(test::format_batch(&results[0]), vec!["a;b"]);
+
+        // the statements after a failing statement are not executed
+        assert!(ctx
+            .sql("DROP TABLE t; SELECT * FROM missing; DROP TABLE test", 1024)
+            .is_err());
+        assert!(ctx.table("t").is_err());
+        assert!(ctx.table("test").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn create_external_table_json() -> Result<()> {
+        let tmp_dir = TempDir::new("create_external_table_json")?;
+        for partition in 0..2 {
+            let file_path = tmp_dir.path().join(format!("partition-{}.json", partition));
+            let mut file = File::create(file_path)?;
+            for i in 0..3 {
+                let data = format!(
+                    "{{\"a\": {}, \"b\": {}.5, \"c\": \"x{}\"}}\n",
+                    partition, i, i
+                );
+                file.write_all(data.as_bytes())?;
+            }
+        }
+        let location = tmp_dir.path().to_str().unwrap();
+
+        // the schema is inferred from the files
+        let mut ctx = ExecutionContext::new();
+        ctx.sql(
+            &format!(
+                "CREATE EXTERNAL TABLE t STORED AS JSON LOCATION '{}'",
+                location
+            ),
+            1024,
+        )?;
+        let schema = ctx.table_provider("t").unwrap().schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+        let results = collect(&mut ctx, "SELECT a, SUM(b) FROM t GROUP BY a ORDER BY a")?;
+        assert_eq!(test::format_batch(&results[0]), vec!["0,4.5", "1,4.5"]);
+
+        // a column list reads only the listed columns
+        ctx.sql(
+            &format!(
+                "CREATE EXTERNAL TABLE t2 (c VARCHAR, a INT) STORED AS NDJSON \
+                 LOCATION '{}'",
+                location
+            ),
+            1024,
+        )?;
+        let results = collect(&mut ctx, "SELECT c, a FROM t2 WHERE a = 1 ORDER BY c")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["x0,1", "x1,1", "x2,1"]
+        );
+
+        assert!(ctx
+            .sql(
+                "CREATE EXTERNAL TABLE t3 STORED AS AVRO LOCATION 'missing'",
+                1024
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn create_external_table_with_intervals() -> Result<()> {
+        let tmp_dir = TempDir::new("create_external_table_with_intervals")?;
+        let file_path = tmp_dir.path().join("schedule.csv");
+        let mut file = File::create(&file_path)?;
+        file.write_all(b"task,frequency,runtime,retention\n")?;
+        file.write_all(b"backup,P1D,PT2H30M,P1Y\n")?;
+        file.write_all(b"report,P1W,PT0.5S,P1Y6M\n")?;
+        file.write_all(b"audit,,P1DT1H,P3M\n")?;
+
+        let mut ctx = ExecutionContext::new();
+        ctx.sql(
+            &format!(
+                "CREATE EXTERNAL TABLE schedule (\
+                    task VARCHAR NOT NULL, \
+                    frequency INTERVAL, \
+                    runtime INTERVAL DAY TO SECOND, \
+                    retention INTERVAL YEAR TO MONTH) \
+                 STORED AS CSV WITH HEADER ROW LOCATION '{}'",
+                file_path.to_str().unwrap()
+            ),
+            1024,
+        )?;
+        let schema = ctx.table_provider("schedule").unwrap().schema();
+        assert_eq!(
+            schema.field(1).data_type(),
+            &DataType::Interval(IntervalUnit::DayTime)
+        );
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Interval(IntervalUnit::DayTime)
+        );
+        assert_eq!(
+            schema.field(3).data_type(),
+            &DataType::Interval(IntervalUnit::YearMonth)
+        );
+
+        let results = collect(&mut ctx, "SELECT * FROM schedule")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec![
+                "backup,P1D,PT2H30M,P1Y",
+                "report,P7D,PT0.5S,P1Y6M",
+                "audit,NULL,P1DT1H,P3M",
+            ]
+        );
+
+        // an interval is either of years and months or of days and time
+        assert!(ctx
+            .sql(
+                "CREATE EXTERNAL TABLE t (i INTERVAL YEAR TO SECOND) \
+                 STORED AS CSV LOCATION 'missing'",
+                1024
+            )
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn case_when() -> Result<()> {
+        let tmp_dir = TempDir::new("case_when")?;
+        let mut ctx = create_ctx(&tmp_dir, 2)?;
+
+        let sql = "SELECT c1, \
+                   CASE WHEN c2 > 3 THEN 'big' ELSE 'small' END, \
+                   CASE c1 WHEN 0 THEN 'zero' WHEN 1 THEN 'one' END \
+                   FROM test WHERE c2 = 3 OR c2 = 4 ORDER BY c1, c2";
+        let results = collect(&mut ctx, sql)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["0,small,zero", "0,big,zero", "1,small,one", "1,big,one"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unicode_case_mapping() -> Result<()> {
+        let mut ctx = ExecutionContext::new();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec![
+                Some("Straße"),
+                None,
+                Some("STRASSE"),
+            ]))],
+        )?;
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let results = collect(&mut ctx, "SELECT lower(a), upper(a) FROM t")?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["straße,STRASSE", "NULL,NULL", "strasse,STRASSE"]
+        );
+
+        let results = collect(
+            &mut ctx,
+            "SELECT COUNT(a) FROM t WHERE upper(a) = 'STRASSE'",
+        )?;
+        assert_eq!(test::format_batch(&results[0]), vec!["2"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn explain() -> Result<()> {
+        let tmp_dir = TempDir::new("explain")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let results = collect(&mut ctx, "EXPLAIN SELECT c1 FROM test")?;
+        let expected = vec![
+            "logical_plan,Projection: #0",
+            "logical_plan,  TableScan: test projection=Some([0])",
+        ];
+        assert_eq!(test::format_batch(&results[0]), expected);
+
+        let results = collect(&mut ctx, "EXPLAIN VERBOSE SELECT c1 + c2 AS s FROM test")?;
+        let rows = test::format_batch(&results[0]);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(
+            rows[..4].to_vec(),
+            vec![
+                "initial_logical_plan,Projection: #0 Plus #1 AS s schema=[s:UInt64]",
+                "initial_logical_plan,  TableScan: test projection=None \
+                 schema=[c1:UInt32, c2:UInt64]",
+                "optimized_logical_plan,Projection: CAST(#0 AS UInt64) Plus #1 AS s \
+                 schema=[s:UInt64] implicit_casts=[CAST(#0 AS UInt64)]",
+                "optimized_logical_plan,  TableScan: test projection=Some([0, 1]) \
+                 schema=[c1:UInt32, c2:UInt64]",
+            ]
+        );
+        assert!(rows[4].starts_with("physical_plan_schema,[s:UInt64]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn explain_formats() -> Result<()> {
+        let tmp_dir = TempDir::new("explain_formats")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let results = ctx.sql(
+            "EXPLAIN (FORMAT GRAPHVIZ) SELECT c1, 'a' AS s FROM test",
+            1024,
+        )?;
+        let batch = &results[0];
+        assert_eq!(batch.num_rows(), 1);
+        let plan = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .value(0);
+        let expected = vec![
+            "digraph LogicalPlan {",
+            // quotes within the descriptions are escaped
+            "  node0 [shape=box label=\"Projection: #0, Utf8(\\\"a\\\") AS s\\n\
+             schema=[c1:UInt32, s:Utf8]\"]",
+            "  node1 [shape=box label=\"TableScan: test projection=Some([0])\\n\
+             schema=[c1:UInt32]\"]",
+            "  node1 -> node0",
+            "}",
+        ];
+        assert_eq!(plan.lines().collect::<Vec<_>>(), expected);
+
+        let results =
+            ctx.sql("EXPLAIN (VERBOSE, FORMAT JSON) SELECT c1 FROM test", 1024)?;
+        let batch = &results[0];
+        let plan_types = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(plan_types.value(0), "initial_logical_plan");
+        assert_eq!(plan_types.value(1), "optimized_logical_plan");
+        assert_eq!(plan_types.value(2), "physical_plan_schema");
+        let plan = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .value(1);
+        let plan: serde_json::Value = serde_json::from_str(plan).unwrap();
+        assert_eq!(plan["node"], "Projection: #0");
+        assert_eq!(plan["schema"][0]["name"], "c1");
+        assert_eq!(plan["schema"][0]["data_type"], "UInt32");
+        assert_eq!(plan["schema"][0]["nullable"], false);
+        assert_eq!(
+            plan["inputs"][0]["node"],
+            "TableScan: test projection=Some([0])"
+        );
+        assert_eq!(plan["inputs"][0]["inputs"].as_array().unwrap().len(), 0);
+
+        // the options can also follow EXPLAIN as keywords
+        let results = ctx.sql("EXPLAIN VERBOSE SELECT c1 FROM test", 1024)?;
+        assert_eq!(results[0].num_rows(), 5);
+        assert!(ctx
+            .sql("EXPLAIN (FORMAT XML) SELECT c1 FROM test", 1024)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn drop_table() -> Result<()> {
+        let tmp_dir = TempDir::new("drop_table")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+
+        let results = ctx.sql("DROP TABLE test", 1024)?;
+        assert!(results.is_empty());
+        assert!(ctx.table_provider("test").is_none());
+        assert!(collect(&mut ctx, "SELECT c1 FROM test").is_err());
+
+        assert!(ctx.sql("DROP TABLE test", 1024).is_err());
+        ctx.sql("DROP TABLE IF EXISTS test", 1024)?;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![])?));
+        let provider = ctx.deregister_table("t").unwrap();
+        assert_eq!(provider.schema().field(0).name(), "a");
+        assert!(ctx.deregister_table("t").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn information_schema() -> Result<()> {
+        let tmp_dir = TempDir::new("information_schema")?;
+        let mut ctx = create_ctx(&tmp_dir, 1)?;
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        ctx.register_table("other", Box::new(MemTable::new(schema, vec![])?));
+
+        let results = ctx.sql("SHOW TABLES", 1024)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec![
+                "datafusion,default,other,BASE TABLE",
+                "datafusion,default,test,BASE TABLE",
+                "datafusion,information_schema,columns,VIEW",
+                "datafusion,information_schema,tables,VIEW",
+            ]
+        );
+
+        let results = ctx.sql("SHOW COLUMNS FROM test", 1024)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["c1,UInt32,NO", "c2,UInt64,NO"]
+        );
+        assert!(ctx.sql("SHOW COLUMNS FROM missing", 1024).is_err());
+
+        // DESCRIBE is the same as SHOW COLUMNS
+        for sql in &["DESCRIBE test", "describe table test", "DESC test"] {
+            let results = ctx.sql(sql, 1024)?;
+            assert_eq!(results[0].schema().field(0).name(), "column_name");
+            assert_eq!(
+                test::format_batch(&results[0]),
+                vec!["c1,UInt32,NO", "c2,UInt64,NO"]
+            );
+        }
+        assert!(ctx.sql("DESCRIBE missing", 1024).is_err());
+
+        // the table name is parsed like a relation of the FROM clause, so that it may
+        // be quoted or qualified by its schema
+        let results = ctx.sql("DESCRIBE information_schema.tables", 1024)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec![
+                "table_catalog,Utf8,NO",
+                "table_schema,Utf8,NO",
+                "table_name,Utf8,NO",
+                "table_type,Utf8,NO",
+            ]
+        );
+        ctx.set_identifier_case(IdentifierCase::Uppercase);
+        assert!(ctx.sql("DESCRIBE test", 1024).is_err());
+        let results = ctx.sql("DESCRIBE \"test\"", 1024)?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["c1,UInt32,NO", "c2,UInt64,NO"]
+        );
+        ctx.set_identifier_case(IdentifierCase::Preserve);
+
+        // the views can be queried like tables, and list the tables registered when
+        // they are scanned
+        ctx.sql("DROP TABLE other", 1024)?;
+        let results = ctx.sql(
+            "SELECT table_name, column_name, ordinal_position \
+             FROM information_schema.columns \
+             WHERE table_schema = 'default' \
+             ORDER BY table_name, ordinal_position",
+            1024,
+        )?;
+        assert_eq!(
+            test::format_batch(&results[0]),
+            vec!["test,c1,1", "test,c2,2"]
+        );
+        let results = ctx.sql(
+            "SELECT t.table_name FROM information_schema.tables AS t \
+             WHERE t.table_type = 'BASE TABLE'",
+            1024,
+        )?;
+        assert_eq!(test::format_batch(&results[0]), vec!["test"]);
+        Ok(())
+    }
+
+    #[test]
+    fn analyze_table() -> Result<()> {
+        let tmp_dir = TempDir::new("analyze_table")?;
+        let mut ctx = create_ctx(&tmp_dir, 4)?;
+
+        let stats = ctx.datasources.get("test").unwrap().statistics();
+        assert_eq!(stats.num_rows, None);
+        assert_eq!(stats.column_statistics, None);
+
+        let results = ctx.sql("ANALYZE TABLE test", 1024)?;
+        assert!(results.is_empty());
+
+        let stats = ctx.datasources.get("test").unwrap().statistics();
+        assert_eq!(stats.num_rows, Some(40));
+        let columns = stats.column_statistics.unwrap();
+        assert_eq!(columns[0].min_value, Some(ScalarValue::UInt32(0)));
+        assert_eq!(columns[0].max_value, Some(ScalarValue::UInt32(3)));
+        assert_eq!(columns[0].distinct_count, Some(4));
+        assert_eq!(columns[1].min_value, Some(ScalarValue::UInt64(1)));
+        assert_eq!(columns[1].max_value, Some(ScalarValue::UInt64(10)));
+        assert_eq!(columns[1].null_count, Some(0));
+
+        Ok(())
+    }
+
+    /// Register a small dimension table alongside the partitioned test table
+    fn create_join_ctx(
+        tmp_dir: &TempDir,
+        partition_count: usize,
+    ) -> Result<ExecutionContext> {
+        let mut ctx = create_ctx(tmp_dir, partition_count)?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let names: Vec<String> = (0..partition_count)
+            .map(|i| format!("partition-{}", i))
+            .collect();
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt32Array::from(
+                    (0..partition_count as u32).collect::<Vec<_>>(),
+                )),
+                Arc::new(StringArray::from(
+                    names.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+                )),
+            ],
+        )?;
+        ctx.register_table("dim", Box::new(MemTable::new(schema, vec![batch])?));
+
+        Ok(ctx)
+    }
+
+    /// Every row of the test table matches exactly one row of the dimension table
+    fn assert_join_results(results: &Vec<RecordBatch>, partition_count: usize) {
+        let mut row_count = 0;
+        for batch in results {
+            assert_eq!(batch.num_columns(), 2);
+            let names = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("Actual column should be StringArray");
+            for i in 0..batch.num_rows() {
+                assert!(names.value(i).starts_with("partition-"));
+            }
+            row_count += batch.num_rows();
+        }
+        // the first line of each file is treated as a header
+        assert_eq!(row_count, partition_count * 10);
+    }
+
     /// Execute SQL and return results
     fn collect(ctx: &mut ExecutionContext, sql: &str) -> Result<Vec<RecordBatch>> {
         let logical_plan = ctx.create_logical_plan(sql)?;
@@ -0,0 +1,93 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Writes the results of a physical plan to a single file, as for
+//! `COPY (query) TO 'path'`. The partitions of the plan are executed one after another
+//! and each batch is written as soon as it is produced, so that the results are never
+//! held in memory as a whole.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+#[cfg(feature = "csv")]
+use arrow::csv;
+use arrow::json::writer::record_batches_to_json_rows;
+use arrow::record_batch::RecordBatch;
+#[cfg(feature = "parquet")]
+use parquet::arrow::ArrowWriter;
+use serde_json::Value;
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::ExecutionPlan;
+use crate::sql::parser::FileType;
+
+/// Execute a physical plan and write its results to a new file at the given path,
+/// replacing any existing file. CSV files have a header row, and JSON files contain an
+/// object per line for each row.
+pub fn write_plan(
+    plan: &dyn ExecutionPlan,
+    path: &str,
+    file_type: &FileType,
+) -> Result<()> {
+    match file_type {
+        #[cfg(feature = "csv")]
+        FileType::CSV => {
+            let mut writer = csv::Writer::new(File::create(path)?);
+            for_each_batch(plan, |batch| Ok(writer.write(batch)?))
+        }
+        #[cfg(feature = "parquet")]
+        FileType::Parquet => {
+            let mut writer =
+                ArrowWriter::try_new(File::create(path)?, plan.schema(), None)?;
+            for_each_batch(plan, |batch| Ok(writer.write(batch)?))?;
+            Ok(writer.close()?)
+        }
+        FileType::NdJson => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            for_each_batch(plan, |batch| {
+                for row in record_batches_to_json_rows(&[batch.clone()])? {
+                    serde_json::to_writer(&mut writer, &Value::Object(row))
+                        .map_err(|e| ExecutionError::General(e.to_string()))?;
+                    writer.write_all(b"\n")?;
+                }
+                Ok(())
+            })?;
+            Ok(writer.flush()?)
+        }
+        #[allow(unreachable_patterns)]
+        file_type => Err(ExecutionError::NotImplemented(format!(
+            "{:?} files require DataFusion to be built with the `{}` feature",
+            file_type,
+            format!("{:?}", file_type).to_lowercase()
+        ))),
+    }
+}
+
+/// Call a function with each batch of each partition of a physical plan, in order
+fn for_each_batch<F>(plan: &dyn ExecutionPlan, mut f: F) -> Result<()>
+where
+    F: FnMut(&RecordBatch) -> Result<()>,
+{
+    for partition in plan.partitions()? {
+        let it = partition.execute()?;
+        let mut it = it.lock().unwrap();
+        while let Some(batch) = it.next()? {
+            f(&batch)?;
+        }
+    }
+    Ok(())
+}
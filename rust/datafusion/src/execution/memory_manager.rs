@@ -0,0 +1,125 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tracks the memory used by operators that buffer their input, so that they can spill
+//! to disk instead of exceeding a configured limit
+
+use std::sync::{Arc, Mutex};
+
+/// Keeps track of the memory reserved by execution plans against a limit
+#[derive(Debug)]
+pub struct MemoryManager {
+    /// The maximum number of bytes that can be reserved
+    limit: usize,
+    /// The number of bytes currently reserved
+    used: Mutex<usize>,
+}
+
+impl MemoryManager {
+    /// Create a new memory manager with a limit in bytes
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            used: Mutex::new(0),
+        }
+    }
+
+    /// Get the maximum number of bytes that can be reserved
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get the number of bytes currently reserved
+    pub fn used(&self) -> usize {
+        *self.used.lock().unwrap()
+    }
+
+    fn try_reserve(&self, bytes: usize) -> bool {
+        let mut used = self.used.lock().unwrap();
+        if *used + bytes > self.limit {
+            false
+        } else {
+            *used += bytes;
+            true
+        }
+    }
+
+    fn release(&self, bytes: usize) {
+        let mut used = self.used.lock().unwrap();
+        *used -= bytes;
+    }
+}
+
+/// Memory reserved from a memory manager, which is released when the reservation is
+/// dropped
+#[derive(Debug)]
+pub struct MemoryReservation {
+    manager: Arc<MemoryManager>,
+    size: usize,
+}
+
+impl MemoryReservation {
+    /// Create an empty reservation
+    pub fn new(manager: Arc<MemoryManager>) -> Self {
+        Self { manager, size: 0 }
+    }
+
+    /// Get the number of bytes reserved
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Try to reserve additional memory, returning false if this would exceed the limit
+    /// of the memory manager
+    pub fn try_grow(&mut self, bytes: usize) -> bool {
+        if self.manager.try_reserve(bytes) {
+            self.size += bytes;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.manager.release(self.size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_and_release() {
+        let manager = Arc::new(MemoryManager::new(100));
+
+        let mut first = MemoryReservation::new(manager.clone());
+        assert!(first.try_grow(60));
+
+        let mut second = MemoryReservation::new(manager.clone());
+        assert!(!second.try_grow(50));
+        assert!(second.try_grow(40));
+        assert_eq!(manager.used(), 100);
+
+        drop(first);
+        assert_eq!(manager.used(), 40);
+        assert!(second.try_grow(50));
+        assert_eq!(second.size(), 90);
+    }
+}
@@ -17,6 +17,10 @@
 
 //! DataFusion query execution
 
+pub mod config;
 pub mod context;
+pub mod export;
+pub mod memory_manager;
 pub mod physical_plan;
+pub mod scheduler;
 pub mod table_impl;
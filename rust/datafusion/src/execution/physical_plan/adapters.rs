@@ -0,0 +1,270 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines adapters that transform the batches of a batch iterator, so that execution
+//! plans which process their input one batch at a time, such as projections and
+//! selections, do not need to implement an iterator of their own
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::BatchIterator;
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::compute::filter;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Create an iterator that applies a function to each batch of the input. The batches
+/// returned by the function must have the given schema.
+pub fn map_batches<F>(
+    input: Arc<Mutex<dyn BatchIterator>>,
+    schema: Arc<Schema>,
+    f: F,
+) -> Arc<Mutex<dyn BatchIterator>>
+where
+    F: FnMut(RecordBatch) -> Result<RecordBatch> + Send + Sync + 'static,
+{
+    Arc::new(Mutex::new(MapIterator { schema, input, f }))
+}
+
+/// Create an iterator that only keeps the rows of each input batch for which the
+/// predicate returned by a function is true. The predicate must be a boolean array with
+/// a value for each row of the batch.
+pub fn filter_batches<F>(
+    input: Arc<Mutex<dyn BatchIterator>>,
+    mut f: F,
+) -> Arc<Mutex<dyn BatchIterator>>
+where
+    F: FnMut(&RecordBatch) -> Result<ArrayRef> + Send + Sync + 'static,
+{
+    let schema = input.lock().unwrap().schema();
+    map_batches(input, schema, move |batch| {
+        let predicate = f(&batch)?;
+        filter_record_batch(&batch, predicate.as_ref())
+    })
+}
+
+/// Create an iterator that applies a function to each batch of the input, which may
+/// split it into any number of batches, and returns the resulting batches one at a
+/// time. The batches returned by the function must have the given schema.
+pub fn try_flatten_batches<F>(
+    input: Arc<Mutex<dyn BatchIterator>>,
+    schema: Arc<Schema>,
+    f: F,
+) -> Arc<Mutex<dyn BatchIterator>>
+where
+    F: FnMut(RecordBatch) -> Result<Vec<RecordBatch>> + Send + Sync + 'static,
+{
+    Arc::new(Mutex::new(FlattenIterator {
+        schema,
+        input,
+        f,
+        pending: VecDeque::new(),
+    }))
+}
+
+/// Keep the rows of a batch for which a boolean predicate is true
+pub fn filter_record_batch(
+    batch: &RecordBatch,
+    predicate: &dyn Array,
+) -> Result<RecordBatch> {
+    match predicate.as_any().downcast_ref::<BooleanArray>() {
+        Some(predicate) => {
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|array| Ok(filter(array.as_ref(), predicate)?))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(RecordBatch::try_new(batch.schema().clone(), columns)?)
+        }
+        None => Err(ExecutionError::InternalError(
+            "Predicate evaluated to non-boolean value".to_string(),
+        )),
+    }
+}
+
+/// Iterator that applies a function to each batch of its input
+struct MapIterator<F> {
+    schema: Arc<Schema>,
+    input: Arc<Mutex<dyn BatchIterator>>,
+    f: F,
+}
+
+impl<F> BatchIterator for MapIterator<F>
+where
+    F: FnMut(RecordBatch) -> Result<RecordBatch> + Send + Sync,
+{
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let batch = self.input.lock().unwrap().next()?;
+        match batch {
+            Some(batch) => Ok(Some((self.f)(batch)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Iterator that splits each batch of its input into any number of batches
+struct FlattenIterator<F> {
+    schema: Arc<Schema>,
+    input: Arc<Mutex<dyn BatchIterator>>,
+    f: F,
+    /// The batches produced from the last input batch that have not been returned
+    pending: VecDeque<RecordBatch>,
+}
+
+impl<F> BatchIterator for FlattenIterator<F>
+where
+    F: FnMut(RecordBatch) -> Result<Vec<RecordBatch>> + Send + Sync,
+{
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            if let Some(batch) = self.pending.pop_front() {
+                return Ok(Some(batch));
+            }
+            let batch = self.input.lock().unwrap().next()?;
+            match batch {
+                Some(batch) => self.pending.extend((self.f)(batch)?),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::common::{self, RecordBatchIterator};
+    use arrow::array::Int32Array;
+    use arrow::compute::add;
+    use arrow::datatypes::{DataType, Field};
+
+    fn input() -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = vec![
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+            )?,
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(Int32Array::from(vec![4, 5]))],
+            )?,
+        ];
+        Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+            schema,
+            batches.into_iter().map(Arc::new).collect(),
+        ))))
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<Vec<i32>> {
+        batches
+            .iter()
+            .map(|batch| {
+                let a = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                (0..a.len()).map(|i| a.value(i)).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn map_each_batch() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("b", DataType::Int32, false)]));
+        let output_schema = schema.clone();
+        let it = map_batches(input()?, schema.clone(), move |batch| {
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let doubled = add(a, a)?;
+            Ok(RecordBatch::try_new(
+                output_schema.clone(),
+                vec![Arc::new(doubled)],
+            )?)
+        });
+        assert_eq!(it.lock().unwrap().schema(), schema);
+        let batches = common::collect(it)?;
+        assert_eq!(values(&batches), vec![vec![2, 4, 6], vec![8, 10]]);
+        Ok(())
+    }
+
+    #[test]
+    fn filter_rows() -> Result<()> {
+        let it = filter_batches(input()?, |batch| {
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let even: Vec<bool> = (0..a.len()).map(|i| a.value(i) % 2 == 0).collect();
+            Ok(Arc::new(BooleanArray::from(even)))
+        });
+        let batches = common::collect(it)?;
+        assert_eq!(values(&batches), vec![vec![2], vec![4]]);
+
+        // predicates must be boolean
+        let it = filter_batches(input()?, |batch| Ok(batch.column(0).clone()));
+        assert!(common::collect(it).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn split_batches() -> Result<()> {
+        let schema = input()?.lock().unwrap().schema();
+        // split each batch into batches of a single row
+        let it = try_flatten_batches(input()?, schema.clone(), |batch| {
+            Ok((0..batch.num_rows())
+                .map(|i| {
+                    let a = batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<Int32Array>()
+                        .unwrap();
+                    RecordBatch::try_new(
+                        batch.schema().clone(),
+                        vec![Arc::new(Int32Array::from(vec![a.value(i)]))],
+                    )
+                })
+                .collect::<arrow::error::Result<Vec<_>>>()?)
+        });
+        let batches = common::collect(it)?;
+        assert_eq!(
+            values(&batches),
+            vec![vec![1], vec![2], vec![3], vec![4], vec![5]]
+        );
+
+        // errors of the function are returned by the iterator
+        let it = try_flatten_batches(input()?, schema, |_| {
+            Err(ExecutionError::General("failed".to_string()))
+        });
+        assert!(common::collect(it).is_err());
+        Ok(())
+    }
+}
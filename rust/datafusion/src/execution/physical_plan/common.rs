@@ -20,14 +20,21 @@
 use std::fs;
 use std::fs::metadata;
 use std::sync::{Arc, Mutex};
+use std::thread;
 
 use crate::error::{ExecutionError, Result};
 use crate::execution::physical_plan::BatchIterator;
+use crate::execution::scheduler;
 
 use crate::logicalplan::ScalarValue;
-use arrow::array::{self, ArrayRef};
-use arrow::datatypes::{DataType, Schema};
+use arrow::array::{self, ArrayData, ArrayDataRef, ArrayRef, UInt32Array};
+use arrow::buffer::{Buffer, MutableBuffer};
+use arrow::compute::take;
+use arrow::datatypes::{
+    DataType, DateUnit, Field, IntervalUnit, Schema, TimeUnit, ToByteSlice,
+};
 use arrow::record_batch::RecordBatch;
+use arrow::util::bit_util;
 
 /// Iterator over a vector of record batches
 pub struct RecordBatchIterator {
@@ -80,6 +87,291 @@ pub fn collect(it: Arc<Mutex<dyn BatchIterator>>) -> Result<Vec<RecordBatch>> {
     }
 }
 
+/// A handle to a task started by `spawn`
+pub struct JoinHandle<T> {
+    #[cfg(feature = "threads")]
+    handle: thread::JoinHandle<T>,
+    #[cfg(not(feature = "threads"))]
+    result: T,
+}
+
+impl<T> JoinHandle<T> {
+    /// Wait for the task to finish and get its result, or the payload of its panic. The
+    /// slot of the scheduler held by the current thread is released while it waits.
+    pub fn join(self) -> thread::Result<T> {
+        #[cfg(feature = "threads")]
+        {
+            let handle = self.handle;
+            scheduler::wait(|| handle.join())
+        }
+        #[cfg(not(feature = "threads"))]
+        {
+            Ok(self.result)
+        }
+    }
+}
+
+/// Run a task on a new thread, such as to execute a partition of a plan. Without the
+/// `threads` feature, as on WebAssembly where threads cannot be spawned, the task is
+/// run to completion on the current thread instead, so that partitions are executed
+/// one after the other. A task started by a thread that executes a partition with a
+/// slot of a scheduler waits for a slot of the same scheduler.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let partition = scheduler::current_partition();
+    let f = move || match partition {
+        Some((slots, query)) => scheduler::run_partition(&slots, query, f),
+        None => f(),
+    };
+    #[cfg(feature = "threads")]
+    {
+        JoinHandle {
+            handle: thread::spawn(f),
+        }
+    }
+    #[cfg(not(feature = "threads"))]
+    {
+        JoinHandle { result: f() }
+    }
+}
+
+/// Compute the number of bytes held by the buffers of the columns of a batch
+pub fn batch_byte_size(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|array| array_data_size(&array.data()))
+        .sum()
+}
+
+/// Compute the number of bytes held by the buffers of an array, including child arrays
+fn array_data_size(data: &ArrayDataRef) -> usize {
+    let buffers: usize = data.buffers().iter().map(|b| b.len()).sum();
+    let null_buffer = data.null_buffer().map(|b| b.len()).unwrap_or(0);
+    let children: usize = data.child_data().iter().map(array_data_size).sum();
+    buffers + null_buffer + children
+}
+
+/// Take the rows at the given indices from each column in a batch
+pub fn take_columns(batch: &RecordBatch, indices: &UInt32Array) -> Result<Vec<ArrayRef>> {
+    batch
+        .columns()
+        .iter()
+        .map(|array| Ok(take(array, indices, None)?))
+        .collect()
+}
+
+macro_rules! null_array {
+    ($BUILDER:ident, $LEN:expr) => {{
+        let mut builder = array::$BUILDER::new($LEN);
+        for _ in 0..$LEN {
+            builder.append_null()?;
+        }
+        Ok(Arc::new(builder.finish()) as ArrayRef)
+    }};
+}
+
+/// Create an array of the given type and length in which every value is null
+pub fn new_null_array(data_type: &DataType, len: usize) -> Result<ArrayRef> {
+    match data_type {
+        DataType::Boolean => null_array!(BooleanBuilder, len),
+        DataType::Int8 => null_array!(Int8Builder, len),
+        DataType::Int16 => null_array!(Int16Builder, len),
+        DataType::Int32 => null_array!(Int32Builder, len),
+        DataType::Int64 => null_array!(Int64Builder, len),
+        DataType::UInt8 => null_array!(UInt8Builder, len),
+        DataType::UInt16 => null_array!(UInt16Builder, len),
+        DataType::UInt32 => null_array!(UInt32Builder, len),
+        DataType::UInt64 => null_array!(UInt64Builder, len),
+        DataType::Float32 => null_array!(Float32Builder, len),
+        DataType::Float64 => null_array!(Float64Builder, len),
+        DataType::Date32(_) => null_array!(Date32Builder, len),
+        DataType::Date64(_) => null_array!(Date64Builder, len),
+        DataType::Timestamp(TimeUnit::Second, None) => {
+            null_array!(TimestampSecondBuilder, len)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            null_array!(TimestampMillisecondBuilder, len)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            null_array!(TimestampMicrosecondBuilder, len)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            null_array!(TimestampNanosecondBuilder, len)
+        }
+        DataType::Utf8 => null_array!(StringBuilder, len),
+        DataType::Decimal(precision, scale) => {
+            let mut builder = array::Decimal128Builder::new(len, *precision, *scale);
+            for _ in 0..len {
+                builder.append_null()?;
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }
+        other => Err(ExecutionError::NotImplemented(format!(
+            "Null arrays of type {:?} are not supported",
+            other
+        ))),
+    }
+}
+
+macro_rules! scalars_to_primitive_array {
+    ($BUILDER:ident, $SCALAR:ident, $VALUES:expr) => {{
+        let mut builder = array::$BUILDER::new($VALUES.len());
+        for value in $VALUES {
+            match value {
+                Some(ScalarValue::$SCALAR(v)) => builder.append_value(v.clone())?,
+                None | Some(ScalarValue::Null) => builder.append_null()?,
+                Some(other) => {
+                    return Err(ExecutionError::ExecutionError(format!(
+                        "Unexpected value {:?} for array of {}",
+                        other,
+                        stringify!($SCALAR)
+                    )))
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()) as ArrayRef)
+    }};
+}
+
+/// Create an array of the given type from a list of values, in which `None` and
+/// `ScalarValue::Null` are null
+pub fn scalars_to_array(
+    data_type: &DataType,
+    values: &[Option<ScalarValue>],
+) -> Result<ArrayRef> {
+    match data_type {
+        DataType::Boolean => scalars_to_primitive_array!(BooleanBuilder, Boolean, values),
+        DataType::Int8 => scalars_to_primitive_array!(Int8Builder, Int8, values),
+        DataType::Int16 => scalars_to_primitive_array!(Int16Builder, Int16, values),
+        DataType::Int32 => scalars_to_primitive_array!(Int32Builder, Int32, values),
+        DataType::Int64 => scalars_to_primitive_array!(Int64Builder, Int64, values),
+        DataType::UInt8 => scalars_to_primitive_array!(UInt8Builder, UInt8, values),
+        DataType::UInt16 => scalars_to_primitive_array!(UInt16Builder, UInt16, values),
+        DataType::UInt32 => scalars_to_primitive_array!(UInt32Builder, UInt32, values),
+        DataType::UInt64 => scalars_to_primitive_array!(UInt64Builder, UInt64, values),
+        DataType::Float32 => scalars_to_primitive_array!(Float32Builder, Float32, values),
+        DataType::Float64 => scalars_to_primitive_array!(Float64Builder, Float64, values),
+        DataType::Date32(DateUnit::Day) => {
+            scalars_to_primitive_array!(Date32Builder, Date32, values)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => scalars_to_primitive_array!(
+            TimestampNanosecondBuilder,
+            TimestampNanosecond,
+            values
+        ),
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            scalars_to_primitive_array!(
+                IntervalYearMonthBuilder,
+                IntervalYearMonth,
+                values
+            )
+        }
+        DataType::Interval(IntervalUnit::DayTime) => {
+            scalars_to_primitive_array!(IntervalDayTimeBuilder, IntervalDayTime, values)
+        }
+        DataType::Decimal(precision, scale) => {
+            let mut builder =
+                array::Decimal128Builder::new(values.len(), *precision, *scale);
+            for value in values {
+                match value {
+                    Some(ScalarValue::Decimal128(v, _, _)) => builder.append_value(*v)?,
+                    None | Some(ScalarValue::Null) => builder.append_null()?,
+                    Some(other) => {
+                        return Err(ExecutionError::ExecutionError(format!(
+                            "Unexpected value {:?} for array of Decimal128",
+                            other
+                        )))
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }
+        DataType::Utf8 => {
+            let mut builder = array::StringBuilder::new(values.len());
+            for value in values {
+                match value {
+                    Some(ScalarValue::Utf8(v)) => builder.append_value(v)?,
+                    None | Some(ScalarValue::Null) => builder.append_null()?,
+                    Some(other) => {
+                        return Err(ExecutionError::ExecutionError(format!(
+                            "Unexpected value {:?} for array of Utf8",
+                            other
+                        )))
+                    }
+                }
+            }
+            Ok(Arc::new(builder.finish()) as ArrayRef)
+        }
+        DataType::Struct(fields) => {
+            // a null struct is represented by null values for each of its fields
+            let columns = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let field_values = values
+                        .iter()
+                        .map(|value| match value {
+                            Some(ScalarValue::Struct(v)) => Some(v[i].clone()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    Ok((
+                        field.clone(),
+                        scalars_to_array(field.data_type(), &field_values)?,
+                    ))
+                })
+                .collect::<Result<Vec<(Field, ArrayRef)>>>()?;
+            Ok(Arc::new(array::StructArray::from(columns)) as ArrayRef)
+        }
+        DataType::List(value_type) => {
+            // the values of all lists are concatenated into the child array
+            let mut offsets = Vec::with_capacity(values.len() + 1);
+            offsets.push(0i32);
+            let mut child_values = vec![];
+            let num_bytes = bit_util::ceil(values.len(), 8);
+            let mut null_buf =
+                MutableBuffer::new(num_bytes).with_bitset(num_bytes, false);
+            let mut null_count = 0;
+            {
+                let null_slice = null_buf.data_mut();
+                for (i, value) in values.iter().enumerate() {
+                    match value {
+                        Some(ScalarValue::List(v)) => {
+                            bit_util::set_bit(null_slice, i);
+                            child_values.extend(v.iter().cloned().map(Some));
+                        }
+                        None | Some(ScalarValue::Null) => null_count += 1,
+                        Some(other) => {
+                            return Err(ExecutionError::ExecutionError(format!(
+                                "Unexpected value {:?} for array of List",
+                                other
+                            )))
+                        }
+                    }
+                    offsets.push(child_values.len() as i32);
+                }
+            }
+            let child = scalars_to_array(value_type, &child_values)?;
+            let data = ArrayData::builder(data_type.clone())
+                .len(values.len())
+                .null_count(null_count)
+                .null_bit_buffer(null_buf.freeze())
+                .add_buffer(Buffer::from(offsets.to_byte_slice()))
+                .add_child_data(child.data())
+                .build();
+            Ok(Arc::new(array::ListArray::from(data)) as ArrayRef)
+        }
+        other => Err(ExecutionError::NotImplemented(format!(
+            "Arrays of type {:?} cannot be built from scalar values",
+            other
+        ))),
+    }
+}
+
 /// Recursively build a list of files in a directory with a given extension
 pub fn build_file_list(dir: &str, filenames: &mut Vec<String>, ext: &str) -> Result<()> {
     let metadata = metadata(dir)?;
@@ -183,6 +475,47 @@ pub fn get_scalar_value(array: &ArrayRef, row: usize) -> Result<Option<ScalarVal
                 .unwrap();
             Some(ScalarValue::Float64(array.value(row)))
         }
+        DataType::Boolean => {
+            let array = array
+                .as_any()
+                .downcast_ref::<array::BooleanArray>()
+                .unwrap();
+            Some(ScalarValue::Boolean(array.value(row)))
+        }
+        DataType::Utf8 => {
+            let array = array.as_any().downcast_ref::<array::StringArray>().unwrap();
+            Some(ScalarValue::Utf8(array.value(row).to_string()))
+        }
+        DataType::Decimal(precision, scale) => {
+            let array = array
+                .as_any()
+                .downcast_ref::<array::Decimal128Array>()
+                .unwrap();
+            Some(ScalarValue::Decimal128(
+                array.value(row),
+                *precision,
+                *scale,
+            ))
+        }
+        DataType::Struct(_) => {
+            let array = array.as_any().downcast_ref::<array::StructArray>().unwrap();
+            let values = array
+                .columns()
+                .iter()
+                .map(|column| {
+                    Ok(get_scalar_value(column, row)?.unwrap_or(ScalarValue::Null))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Some(ScalarValue::Struct(values))
+        }
+        DataType::List(_) => {
+            let array = array.as_any().downcast_ref::<array::ListArray>().unwrap();
+            let list = array.value(row);
+            let values = (0..list.len())
+                .map(|i| Ok(get_scalar_value(&list, i)?.unwrap_or(ScalarValue::Null)))
+                .collect::<Result<Vec<_>>>()?;
+            Some(ScalarValue::List(values))
+        }
         other => {
             return Err(ExecutionError::ExecutionError(format!(
                 "Unsupported data type {:?} for result of aggregate expression",
@@ -0,0 +1,275 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the cross join execution plan, which produces the cartesian product of its
+//! inputs. The left input is collected into memory once and every batch of the right
+//! input is then combined with each left row in turn, so that no output batch is larger
+//! than the right batch it was produced from.
+
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::physical_plan::common::{self, JoinHandle};
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use crate::execution::scheduler;
+use crate::logicalplan::{build_join_schema, JoinType};
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// The collected batches of the left input, shared between all output partitions
+type LeftState = Arc<Mutex<Option<Arc<Vec<RecordBatch>>>>>;
+
+/// Cross join execution plan
+pub struct CrossJoinExec {
+    /// The left input, which is collected into memory
+    left: Arc<dyn ExecutionPlan>,
+    /// The right input, which is streamed
+    right: Arc<dyn ExecutionPlan>,
+    /// The output schema, containing the left fields followed by the right fields
+    schema: Arc<Schema>,
+}
+
+impl CrossJoinExec {
+    /// Create a new cross join execution plan
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        let schema = build_join_schema(&left.schema(), &right.schema(), &JoinType::Inner);
+        Ok(Self {
+            left,
+            right,
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+impl ExecutionPlan for CrossJoinExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        let left = self.left.partitions()?;
+        let state: LeftState = Arc::new(Mutex::new(None));
+        Ok(self
+            .right
+            .partitions()?
+            .iter()
+            .map(|p| {
+                Arc::new(CrossJoinPartition {
+                    schema: self.schema.clone(),
+                    left: left.clone(),
+                    state: state.clone(),
+                    right: p.clone(),
+                }) as Arc<dyn Partition>
+            })
+            .collect())
+    }
+}
+
+/// Represents a single partition of a cross join execution plan, which joins one
+/// partition of the right input with the whole left input
+struct CrossJoinPartition {
+    schema: Arc<Schema>,
+    left: Vec<Arc<dyn Partition>>,
+    state: LeftState,
+    right: Arc<dyn Partition>,
+}
+
+impl Partition for CrossJoinPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let left = {
+            // the partition that collects the left input waits for other threads
+            let mut state = scheduler::wait(|| self.state.lock().unwrap());
+            match state.as_ref() {
+                Some(batches) => batches.clone(),
+                None => {
+                    let batches = Arc::new(collect_left(&self.left)?);
+                    *state = Some(batches.clone());
+                    batches
+                }
+            }
+        };
+        Ok(Arc::new(Mutex::new(CrossJoinIterator {
+            schema: self.schema.clone(),
+            left,
+            right: self.right.execute()?,
+            current: None,
+            left_batch: 0,
+            left_row: 0,
+        })))
+    }
+}
+
+/// Execute the left partitions in parallel and collect their batches
+fn collect_left(partitions: &Vec<Arc<dyn Partition>>) -> Result<Vec<RecordBatch>> {
+    let threads: Vec<JoinHandle<Result<Vec<RecordBatch>>>> = partitions
+        .iter()
+        .map(|p| {
+            let p = p.clone();
+            common::spawn(move || common::collect(p.execute()?))
+        })
+        .collect();
+
+    let mut batches = vec![];
+    for thread in threads {
+        let join = thread.join().expect("Failed to join thread");
+        batches.extend(join?.into_iter().filter(|b| b.num_rows() > 0));
+    }
+    Ok(batches)
+}
+
+/// Iterator over the cartesian product of the left batches and one right partition
+struct CrossJoinIterator {
+    schema: Arc<Schema>,
+    left: Arc<Vec<RecordBatch>>,
+    right: Arc<Mutex<dyn BatchIterator>>,
+    /// The right batch currently being joined
+    current: Option<RecordBatch>,
+    /// The position of the next left row to join with the current right batch
+    left_batch: usize,
+    left_row: usize,
+}
+
+impl CrossJoinIterator {
+    /// Combine a single left row with every row of a right batch
+    fn join_row(&self, right: &RecordBatch) -> Result<RecordBatch> {
+        let batch = &self.left[self.left_batch];
+        let indices = UInt32Array::from(vec![self.left_row as u32; right.num_rows()]);
+        let mut columns: Vec<ArrayRef> = common::take_columns(batch, &indices)?;
+        columns.extend(right.columns().iter().cloned());
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+impl BatchIterator for CrossJoinIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        if self.left.is_empty() {
+            return Ok(None);
+        }
+        loop {
+            if self.current.is_none() {
+                match self.right.lock().unwrap().next()? {
+                    Some(batch) if batch.num_rows() == 0 => continue,
+                    Some(batch) => {
+                        self.current = Some(batch);
+                        self.left_batch = 0;
+                        self.left_row = 0;
+                    }
+                    None => return Ok(None),
+                }
+            }
+
+            if let Some(ref right) = self.current {
+                let output = self.join_row(right)?;
+
+                // move on to the next left row, or the next right batch once all left
+                // rows have been joined with this one
+                self.left_row += 1;
+                if self.left_row == self.left[self.left_batch].num_rows() {
+                    self.left_row = 0;
+                    self.left_batch += 1;
+                    if self.left_batch == self.left.len() {
+                        self.current = None;
+                    }
+                }
+                return Ok(Some(output));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::datasource::DatasourceExec;
+    use crate::test;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field};
+
+    /// Create an execution plan with one partition per vector of values
+    fn build_table(
+        name: &str,
+        partitions: Vec<Vec<i32>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new(name, DataType::Int32, false)]));
+        let iterators = partitions
+            .into_iter()
+            .map(|values| {
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(values))],
+                )?;
+                Ok(Arc::new(Mutex::new(common::RecordBatchIterator::new(
+                    schema.clone(),
+                    vec![Arc::new(batch)],
+                ))) as Arc<Mutex<dyn BatchIterator>>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(DatasourceExec::new(schema, iterators)))
+    }
+
+    #[test]
+    fn cross_join() -> Result<()> {
+        let left = build_table("a", vec![vec![1, 2], vec![3]])?;
+        let right = build_table("b", vec![vec![10, 20], vec![30]])?;
+
+        let join = CrossJoinExec::try_new(left, right)?;
+        assert_eq!(join.partitions()?.len(), 2);
+        assert_eq!(join.schema().fields().len(), 2);
+
+        let batches = test::execute(&join)?;
+
+        // no output batch is larger than the right batch it was produced from
+        assert!(batches.iter().all(|b| b.num_rows() <= 2));
+
+        let mut rows = vec![];
+        for batch in &batches {
+            rows.extend(test::format_batch(batch));
+        }
+        rows.sort();
+        let expected = vec![
+            "1,10", "1,20", "1,30", "2,10", "2,20", "2,30", "3,10", "3,20", "3,30",
+        ];
+        assert_eq!(rows, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cross_join_empty_left() -> Result<()> {
+        let left = build_table("a", vec![vec![]])?;
+        let right = build_table("b", vec![vec![10, 20]])?;
+
+        let join = CrossJoinExec::try_new(left, right)?;
+        assert!(test::execute(&join)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -17,28 +17,130 @@
 
 //! Execution plan for reading CSV files
 
+use std::collections::HashMap;
 use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use crate::error::Result;
+use crate::error::{ExecutionError, Result};
 use crate::execution::physical_plan::common;
 use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
 use arrow::csv;
+pub use arrow::csv::BadRecordPolicy;
 use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 
+/// Options for reading CSV files
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    /// Does the CSV file have a header? Can be overridden for individual files.
+    pub has_header: bool,
+    /// Number of lines to skip at the start of each file, before the header
+    pub skip_rows: usize,
+    /// Whether individual files have a header, keyed by their path or file name
+    pub file_headers: HashMap<String, bool>,
+    /// How rows that cannot be read or parsed are handled
+    pub bad_records: BadRecordPolicy,
+    /// Whether the columns of each batch are converted into arrays in parallel
+    pub parallel_conversion: bool,
+}
+
+impl CsvReadOptions {
+    /// Create options for reading CSV files that have a header
+    pub fn new() -> Self {
+        Self {
+            has_header: true,
+            skip_rows: 0,
+            file_headers: HashMap::new(),
+            bad_records: BadRecordPolicy::Fail,
+            parallel_conversion: false,
+        }
+    }
+
+    /// Specify whether the files have a header
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Skip a number of lines, such as a preamble written by an export, at the start
+    /// of each file
+    pub fn skip_rows(mut self, skip_rows: usize) -> Self {
+        self.skip_rows = skip_rows;
+        self
+    }
+
+    /// Specify whether malformed rows fail the query, are skipped or have their
+    /// values replaced with nulls. Skipped and null-filled rows are counted in the
+    /// metrics of the scan.
+    pub fn bad_records(mut self, policy: BadRecordPolicy) -> Self {
+        self.bad_records = policy;
+        self
+    }
+
+    /// Specify whether the columns of each batch are converted into arrays in parallel.
+    /// The lines of a file are still read and split into fields by a single thread,
+    /// so this speeds up scans of a single file with many columns on many-core
+    /// machines.
+    pub fn parallel_conversion(mut self, parallel: bool) -> Self {
+        self.parallel_conversion = parallel;
+        self
+    }
+
+    /// Specify whether a single file has a header, given its path or file name
+    pub fn file_header(mut self, file: &str, has_header: bool) -> Self {
+        self.file_headers.insert(file.to_string(), has_header);
+        self
+    }
+
+    /// Whether the file with the given path has a header
+    pub fn file_has_header(&self, path: &str) -> bool {
+        let file_name = Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path);
+        self.file_headers
+            .get(path)
+            .or_else(|| self.file_headers.get(file_name))
+            .cloned()
+            .unwrap_or(self.has_header)
+    }
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Execution plan for scanning a CSV file
 pub struct CsvExec {
     /// Path to directory containing partitioned CSV files with the same schema
     path: String,
     /// Schema representing the CSV files after the optional projection is applied
     schema: Arc<Schema>,
-    /// Does the CSV file have a header?
-    has_header: bool,
+    /// Options for reading the files
+    options: CsvReadOptions,
     /// Optional projection for which columns to load
     projection: Option<Vec<usize>>,
     /// Batch size
     batch_size: usize,
+    /// Metrics of each file, collected by the partitions that read them
+    metrics: Mutex<Vec<Arc<Mutex<CsvFileMetrics>>>>,
+}
+
+/// Metrics collected while reading a CSV file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvFileMetrics {
+    /// Path of the file
+    pub filename: String,
+    /// Number of batches read from the file
+    pub batches: usize,
+    /// Number of rows read from the file
+    pub rows_read: usize,
+    /// Number of malformed rows that were skipped or filled with nulls
+    pub bad_records: usize,
 }
 
 impl ExecutionPlan for CsvExec {
@@ -51,20 +153,44 @@ impl ExecutionPlan for CsvExec {
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         let mut filenames: Vec<String> = vec![];
         common::build_file_list(&self.path, &mut filenames, ".csv")?;
-        let partitions = filenames
+        let metrics: Vec<_> = filenames
             .iter()
             .map(|filename| {
+                Arc::new(Mutex::new(CsvFileMetrics {
+                    filename: filename.clone(),
+                    ..Default::default()
+                }))
+            })
+            .collect();
+        let partitions = filenames
+            .iter()
+            .zip(metrics.iter())
+            .map(|(filename, metrics)| {
                 Arc::new(CsvPartition::new(
                     &filename,
                     self.schema.clone(),
-                    self.has_header,
+                    &self.options,
                     self.projection.clone(),
                     self.batch_size,
+                    metrics.clone(),
                 )) as Arc<dyn Partition>
             })
             .collect();
+        *self.metrics.lock().unwrap() = metrics;
         Ok(partitions)
     }
+
+    fn metrics(&self) -> Vec<String> {
+        self.file_metrics()
+            .iter()
+            .map(|m| {
+                format!(
+                    "CsvExec: file={} batches={} rows_read={} bad_records={}",
+                    m.filename, m.batches, m.rows_read, m.bad_records
+                )
+            })
+            .collect()
+    }
 }
 
 impl CsvExec {
@@ -75,15 +201,39 @@ impl CsvExec {
         has_header: bool,
         projection: Option<Vec<usize>>,
         batch_size: usize,
+    ) -> Result<Self> {
+        let options = CsvReadOptions::new().has_header(has_header);
+        Self::try_new_with_options(path, schema, options, projection, batch_size)
+    }
+
+    /// Create a new execution plan for reading a set of CSV files with the given
+    /// options
+    pub fn try_new_with_options(
+        path: &str,
+        schema: Arc<Schema>,
+        options: CsvReadOptions,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
     ) -> Result<Self> {
         Ok(Self {
             path: path.to_string(),
             schema,
-            has_header,
+            options,
             projection,
             batch_size,
+            metrics: Mutex::new(vec![]),
         })
     }
+
+    /// Get the metrics of each file collected by the last execution of the partitions
+    pub fn file_metrics(&self) -> Vec<CsvFileMetrics> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|metrics| metrics.lock().unwrap().clone())
+            .collect()
+    }
 }
 
 /// CSV Partition
@@ -94,26 +244,39 @@ struct CsvPartition {
     schema: Arc<Schema>,
     /// Does the CSV file have a header?
     has_header: bool,
+    /// Number of lines to skip before the header
+    skip_rows: usize,
+    /// How rows that cannot be read or parsed are handled
+    bad_records: BadRecordPolicy,
+    /// Whether the columns of each batch are converted in parallel
+    parallel_conversion: bool,
     /// Optional projection for which columns to load
     projection: Option<Vec<usize>>,
     /// Batch size
     batch_size: usize,
+    /// Metrics of the file
+    metrics: Arc<Mutex<CsvFileMetrics>>,
 }
 
 impl CsvPartition {
     fn new(
         path: &str,
         schema: Arc<Schema>,
-        has_header: bool,
+        options: &CsvReadOptions,
         projection: Option<Vec<usize>>,
         batch_size: usize,
+        metrics: Arc<Mutex<CsvFileMetrics>>,
     ) -> Self {
         Self {
             path: path.to_string(),
             schema,
-            has_header,
+            has_header: options.file_has_header(path),
+            skip_rows: options.skip_rows,
+            bad_records: options.bad_records,
+            parallel_conversion: options.parallel_conversion,
             projection,
             batch_size,
+            metrics,
         }
     }
 }
@@ -121,41 +284,46 @@ impl CsvPartition {
 impl Partition for CsvPartition {
     /// Execute this partition and return an iterator over RecordBatch
     fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
-        Ok(Arc::new(Mutex::new(CsvIterator::try_new(
-            &self.path,
-            self.schema.clone(),
-            self.has_header,
-            &self.projection,
-            self.batch_size,
-        )?)))
+        Ok(Arc::new(Mutex::new(CsvIterator::try_new(self)?)))
     }
 }
 
 /// Iterator over batches
 struct CsvIterator {
+    /// Path to the CSV file
+    path: String,
     /// Arrow CSV reader
     reader: csv::Reader<File>,
+    /// Metrics of the file
+    metrics: Arc<Mutex<CsvFileMetrics>>,
 }
 
 impl CsvIterator {
-    /// Create an iterator for a CSV file
-    pub fn try_new(
-        filename: &str,
-        schema: Arc<Schema>,
-        has_header: bool,
-        projection: &Option<Vec<usize>>,
-        batch_size: usize,
-    ) -> Result<Self> {
-        let file = File::open(filename)?;
-        let reader = csv::Reader::new(
-            file,
-            schema.clone(),
-            has_header,
-            batch_size,
-            projection.clone(),
-        );
+    /// Create an iterator for the CSV file of a partition
+    fn try_new(partition: &CsvPartition) -> Result<Self> {
+        let mut buf_reader = BufReader::new(File::open(&partition.path)?);
+        let mut line = String::new();
+        for _ in 0..partition.skip_rows {
+            line.clear();
+            if buf_reader.read_line(&mut line)? == 0 {
+                break;
+            }
+        }
+        let reader = csv::Reader::from_buf_reader(
+            buf_reader,
+            partition.schema.clone(),
+            partition.has_header,
+            partition.batch_size,
+            partition.projection.clone(),
+        )
+        .with_bad_record_policy(partition.bad_records)
+        .with_parallel_conversion(partition.parallel_conversion);
 
-        Ok(Self { reader })
+        Ok(Self {
+            path: partition.path.clone(),
+            reader,
+            metrics: partition.metrics.clone(),
+        })
     }
 }
 
@@ -167,6 +335,182 @@ impl BatchIterator for CsvIterator {
 
     /// Get the next RecordBatch
     fn next(&mut self) -> Result<Option<RecordBatch>> {
-        Ok(self.reader.next()?)
+        let batch = self.reader.next().map_err(|e| {
+            ExecutionError::ExecutionError(format!(
+                "Error reading CSV file {}: {}",
+                self.path, e
+            ))
+        })?;
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.bad_records = self.reader.bad_records();
+        if let Some(batch) = &batch {
+            metrics.batches += 1;
+            metrics.rows_read += batch.num_rows();
+        }
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::common;
+    use crate::test;
+    use arrow::array::UInt32Array;
+    use arrow::datatypes::{DataType, Field};
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn skip_rows_and_file_headers() -> Result<()> {
+        let tmp_dir = TempDir::new("skip_rows_and_file_headers")?;
+        fs::write(
+            tmp_dir.path().join("with_header.csv"),
+            "exported 2020-01-01\nc1,c2\n1,2\n3,4\n",
+        )?;
+        fs::write(
+            tmp_dir.path().join("without_header.csv"),
+            "exported 2020-01-02\n5,6\n",
+        )?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::UInt32, false),
+            Field::new("c2", DataType::UInt32, false),
+        ]));
+        let options = CsvReadOptions::new()
+            .skip_rows(1)
+            .file_header("without_header.csv", false);
+        assert!(options.file_has_header("with_header.csv"));
+        assert!(!options.file_has_header("/data/without_header.csv"));
+
+        let exec = CsvExec::try_new_with_options(
+            tmp_dir.path().to_str().unwrap(),
+            schema,
+            options,
+            Some(vec![0]),
+            1024,
+        )?;
+        let mut values = vec![];
+        for partition in exec.partitions()? {
+            for batch in common::collect(partition.execute()?)? {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<UInt32Array>()
+                    .unwrap();
+                values.extend((0..array.len()).map(|i| array.value(i)));
+            }
+        }
+        values.sort();
+        assert_eq!(values, vec![1, 3, 5]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bad_records() -> Result<()> {
+        let tmp_dir = TempDir::new("bad_records")?;
+        let path = tmp_dir.path().join("data.csv");
+        fs::write(&path, "c1,c2\n1,2\nx,4\n5\n7,8\n")?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::UInt32, true),
+            Field::new("c2", DataType::UInt32, true),
+        ]));
+        let scan = |policy| -> Result<(Vec<Option<u32>>, CsvExec)> {
+            let options = CsvReadOptions::new().bad_records(policy);
+            let exec = CsvExec::try_new_with_options(
+                tmp_dir.path().to_str().unwrap(),
+                schema.clone(),
+                options,
+                None,
+                1024,
+            )?;
+            let mut values = vec![];
+            for partition in exec.partitions()? {
+                for batch in common::collect(partition.execute()?)? {
+                    let array = batch
+                        .column(0)
+                        .as_any()
+                        .downcast_ref::<UInt32Array>()
+                        .unwrap();
+                    values.extend((0..array.len()).map(|i| {
+                        if array.is_null(i) {
+                            None
+                        } else {
+                            Some(array.value(i))
+                        }
+                    }));
+                }
+            }
+            Ok((values, exec))
+        };
+
+        match scan(BadRecordPolicy::Fail) {
+            Err(e) => assert_eq!(
+                format!("{:?}", e),
+                format!(
+                    "ExecutionError(\"Error reading CSV file {}: Parser error: \
+                     Error while parsing value x at line 2\")",
+                    path.to_str().unwrap()
+                )
+            ),
+            Ok(_) => panic!("should have failed"),
+        }
+
+        let (values, exec) = scan(BadRecordPolicy::Skip)?;
+        assert_eq!(values, vec![Some(1), Some(7)]);
+        let metrics = exec.file_metrics();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].rows_read, 2);
+        assert_eq!(metrics[0].bad_records, 2);
+
+        let (values, exec) = scan(BadRecordPolicy::NullFill)?;
+        assert_eq!(values, vec![Some(1), None, None, Some(7)]);
+        assert_eq!(exec.file_metrics()[0].bad_records, 2);
+        assert_eq!(
+            exec.metrics(),
+            vec![format!(
+                "CsvExec: file={} batches=1 rows_read=4 bad_records=2",
+                path.to_str().unwrap()
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_conversion() -> Result<()> {
+        let tmp_dir = TempDir::new("parallel_conversion")?;
+        let path = tmp_dir.path().join("data.csv");
+        fs::write(
+            &path,
+            "c1,c2,c3
+1,a,2
+3,,4
+5,c,6
+",
+        )?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::UInt32, false),
+            Field::new("c2", DataType::Utf8, true),
+            Field::new("c3", DataType::UInt32, false),
+        ]));
+        let options = CsvReadOptions::new().parallel_conversion(true);
+        let exec = CsvExec::try_new_with_options(
+            tmp_dir.path().to_str().unwrap(),
+            schema,
+            options,
+            Some(vec![2, 1]),
+            2,
+        )?;
+        let mut rows = vec![];
+        for partition in exec.partitions()? {
+            for batch in common::collect(partition.execute()?)? {
+                assert_eq!(batch.schema().field(0).name(), "c3");
+                rows.extend(test::format_batch(&batch));
+            }
+        }
+        assert_eq!(rows, vec!["2,a", "4,", "6,c"]);
+
+        Ok(())
     }
 }
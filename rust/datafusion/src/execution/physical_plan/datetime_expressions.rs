@@ -0,0 +1,217 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Date and time expressions
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::context::ExecutionContext;
+use crate::execution::physical_plan::udf::ScalarFunction;
+
+use arrow::array::{Array, ArrayRef, StringArray, TimestampNanosecondArray};
+use arrow::datatypes::{DataType, Field, TimeUnit};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+
+use std::sync::Arc;
+
+/// Parse the time zone of a timestamp type, which is either `UTC` or a fixed offset from
+/// UTC such as `+02:00`, `+0200` or `+02`. Named time zones such as `Europe/Berlin`
+/// are not supported, because they require a time zone database.
+pub fn parse_timezone(tz: &str) -> Result<FixedOffset> {
+    if tz.eq_ignore_ascii_case("UTC") || tz == "Z" {
+        return Ok(FixedOffset::east(0));
+    }
+    let unsupported = || {
+        ExecutionError::NotImplemented(format!(
+            "Unsupported time zone '{}', only UTC and fixed offsets such as +02:00 are \
+             supported",
+            tz
+        ))
+    };
+    let sign = match tz.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(unsupported()),
+    };
+    let offset = &tz[1..];
+    if !offset.chars().all(|c| c.is_ascii_digit() || c == ':') {
+        return Err(unsupported());
+    }
+    let (hours, minutes) = match offset.len() {
+        2 => (offset, "00"),
+        4 => (&offset[..2], &offset[2..]),
+        5 if &offset[2..3] == ":" => (&offset[..2], &offset[3..]),
+        _ => return Err(unsupported()),
+    };
+    match (hours.parse::<i32>(), minutes.parse::<i32>()) {
+        (Ok(hours), Ok(minutes)) if minutes < 60 => {
+            FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+                .ok_or_else(unsupported)
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Parse a string as a timestamp in nanoseconds since the epoch. Strings without an
+/// offset, such as `2020-06-01 12:00:00` or `2020-06-01`, are in the given time zone.
+fn string_to_timestamp_nanos(s: &str, tz: &FixedOffset) -> Result<i64> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(s) {
+        return Ok(ts.timestamp_nanos());
+    }
+    if let Ok(ts) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z") {
+        return Ok(ts.timestamp_nanos());
+    }
+    let local = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .filter_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .next()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(|date| date.and_hms(0, 0, 0))
+        });
+    match local.and_then(|ts| tz.from_local_datetime(&ts).single()) {
+        Some(ts) => Ok(ts.timestamp_nanos()),
+        None => Err(ExecutionError::General(format!(
+            "Cannot parse '{}' as a timestamp",
+            s
+        ))),
+    }
+}
+
+/// Parse strings as timestamps in nanoseconds. The second argument is the time zone of
+/// the result, or an empty string for timestamps without a time zone, which are in UTC.
+fn to_timestamp(args: &Vec<ArrayRef>) -> Result<ArrayRef> {
+    let strings = as_string_array(&args[0])?;
+    let timezones = as_string_array(&args[1])?;
+    let tz =
+        if timezones.len() > 0 && !timezones.is_null(0) && !timezones.value(0).is_empty()
+        {
+            Some(timezones.value(0).to_string())
+        } else {
+            None
+        };
+    let offset = match &tz {
+        Some(tz) => parse_timezone(tz)?,
+        None => FixedOffset::east(0),
+    };
+    let values = (0..strings.len())
+        .map(|i| {
+            if strings.is_null(i) {
+                Ok(None)
+            } else {
+                string_to_timestamp_nanos(strings.value(i), &offset).map(Some)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(TimestampNanosecondArray::from_opt_vec(
+        values,
+        tz.map(Arc::new),
+    )))
+}
+
+fn as_string_array(array: &ArrayRef) -> Result<&StringArray> {
+    array.as_any().downcast_ref::<StringArray>().ok_or_else(|| {
+        ExecutionError::General("Invalid data type for to_timestamp".to_string())
+    })
+}
+
+/// Register date and time scalar functions with the context. The SQL planner passes the
+/// time zone of `to_timestamp(s [, timezone])` as the second argument, and sets the
+/// return type to a timestamp in that time zone.
+pub fn register_datetime_functions(ctx: &mut ExecutionContext) {
+    ctx.register_udf(ScalarFunction::new(
+        "to_timestamp",
+        vec![
+            Field::new("s", DataType::Utf8, true),
+            Field::new("timezone", DataType::Utf8, true),
+        ],
+        DataType::Timestamp(TimeUnit::Nanosecond, None),
+        to_timestamp,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_timezones() -> Result<()> {
+        assert_eq!(parse_timezone("UTC")?, FixedOffset::east(0));
+        assert_eq!(parse_timezone("+02:00")?, FixedOffset::east(7200));
+        assert_eq!(parse_timezone("-0530")?, FixedOffset::west(19800));
+        assert_eq!(parse_timezone("+01")?, FixedOffset::east(3600));
+        for tz in &["Europe/Berlin", "+2:00", "+02:60", "02:00", "+02:00:00", ""] {
+            assert!(parse_timezone(tz).is_err(), "{}", tz);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn to_timestamp_in_timezone() -> Result<()> {
+        let strings: ArrayRef = Arc::new(StringArray::try_from(vec![
+            Some("2020-06-01T12:00:00Z"),
+            Some("2020-06-01 12:00:00+02:00"),
+            Some("2020-06-01 12:00:00.5"),
+            Some("2020-06-01"),
+            None,
+        ])?);
+        let hour = 3_600_000_000_000;
+        let noon_utc = 1_590_969_600_000_000_000 + 12 * hour;
+
+        let timezones = StringArray::try_from(vec![Some("+02:00"); 5])?;
+        let result = to_timestamp(&vec![strings.clone(), Arc::new(timezones)])?;
+        assert_eq!(
+            result.data_type(),
+            &DataType::Timestamp(
+                TimeUnit::Nanosecond,
+                Some(Arc::new("+02:00".to_string()))
+            )
+        );
+        let result = result
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        // strings without an offset are in the time zone of the result
+        assert_eq!(result.value(0), noon_utc);
+        assert_eq!(result.value(1), noon_utc - 2 * hour);
+        assert_eq!(result.value(2), noon_utc - 2 * hour + hour / 7200);
+        assert_eq!(result.value(3), noon_utc - 14 * hour);
+        assert!(result.is_null(4));
+
+        let timezones = StringArray::try_from(vec![Some(""); 5])?;
+        let result = to_timestamp(&vec![strings, Arc::new(timezones)])?;
+        assert_eq!(
+            result.data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+        let result = result
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(result.value(2), noon_utc + hour / 7200);
+        assert_eq!(result.value(3), noon_utc - 12 * hour);
+
+        let invalid = StringArray::try_from(vec![Some("June 1st")])?;
+        let timezones = StringArray::try_from(vec![Some("")])?;
+        assert!(
+            to_timestamp(&vec![Arc::new(invalid) as ArrayRef, Arc::new(timezones)])
+                .is_err()
+        );
+        Ok(())
+    }
+}
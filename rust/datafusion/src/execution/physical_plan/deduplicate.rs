@@ -0,0 +1,286 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the deduplication plan, which removes the rows whose keys are equal to
+//! those of an adjacent row. Rows are compared as they are read, so that duplicates are
+//! only removed from sorted input, but without collecting the input first.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::physical_plan::common::{get_scalar_value, take_columns};
+use crate::execution::physical_plan::{
+    BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
+};
+use crate::logicalplan::ScalarValue;
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::concat;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Execution plan that keeps the first or last row of each run of rows with equal keys
+pub struct DeduplicateExec {
+    /// The key expressions
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    /// Whether the first row of each run is kept rather than the last
+    keep_first: bool,
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+}
+
+impl DeduplicateExec {
+    /// Create a deduplication of an input, which must be sorted by the keys to remove
+    /// all duplicates
+    pub fn try_new(
+        keys: Vec<Arc<dyn PhysicalExpr>>,
+        keep_first: bool,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        Ok(Self {
+            keys,
+            keep_first,
+            input,
+        })
+    }
+}
+
+impl ExecutionPlan for DeduplicateExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// The input partitions are read one after the other in a single partition, so
+    /// that runs spanning several partitions are deduplicated too
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(vec![Arc::new(DeduplicatePartition {
+            schema: self.input.schema(),
+            keys: self.keys.clone(),
+            keep_first: self.keep_first,
+            partitions: self.input.partitions()?,
+        })])
+    }
+}
+
+/// Represents the single partition of a deduplication plan
+struct DeduplicatePartition {
+    schema: Arc<Schema>,
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    keep_first: bool,
+    partitions: Vec<Arc<dyn Partition>>,
+}
+
+impl Partition for DeduplicatePartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        Ok(Arc::new(Mutex::new(DeduplicateIterator {
+            schema: self.schema.clone(),
+            keys: self.keys.clone(),
+            keep_first: self.keep_first,
+            partitions: self.partitions.iter().cloned().collect(),
+            input: None,
+            previous_keys: None,
+            pending: None,
+        })))
+    }
+}
+
+/// The values of the keys of a row, where nulls are equal to each other
+type RowKeys = Vec<Option<ScalarValue>>;
+
+/// Deduplication iterator
+struct DeduplicateIterator {
+    schema: Arc<Schema>,
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    keep_first: bool,
+    /// The input partitions that have not been read yet
+    partitions: VecDeque<Arc<dyn Partition>>,
+    /// The input partition being read
+    input: Option<Arc<Mutex<dyn BatchIterator>>>,
+    /// The keys of the last row that was read
+    previous_keys: Option<RowKeys>,
+    /// When keeping the last row of each run, the last row that was read, which is
+    /// returned unless the next row continues its run
+    pending: Option<RecordBatch>,
+}
+
+impl DeduplicateIterator {
+    /// Get the next non-empty batch of the input partitions
+    fn next_input_batch(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            let input = match &self.input {
+                Some(input) => input.clone(),
+                None => match self.partitions.pop_front() {
+                    Some(partition) => {
+                        let input = partition.execute()?;
+                        self.input = Some(input.clone());
+                        input
+                    }
+                    None => return Ok(None),
+                },
+            };
+            let mut input = input.lock().unwrap();
+            match input.next()? {
+                Some(batch) if batch.num_rows() > 0 => return Ok(Some(batch)),
+                Some(_) => {}
+                None => self.input = None,
+            }
+        }
+    }
+
+    /// Deduplicate a batch, returning the rows that are kept
+    fn deduplicate(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let keys = self
+            .keys
+            .iter()
+            .map(|key| key.evaluate(batch))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        let row_keys = (0..batch.num_rows())
+            .map(|row| {
+                keys.iter()
+                    .map(|key| get_scalar_value(key, row))
+                    .collect::<Result<RowKeys>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut indices: Vec<u32> = vec![];
+        let mut previous_row = None;
+        if self.keep_first {
+            // a row is kept if its keys differ from those of the previous row
+            for (row, keys) in row_keys.iter().enumerate() {
+                if self.previous_keys.as_ref() != Some(keys) {
+                    indices.push(row as u32);
+                }
+                self.previous_keys = Some(keys.clone());
+            }
+        } else {
+            // a row is kept if its keys differ from those of the next row, so that the
+            // last row of the previous batch is only kept now
+            if let Some(pending) = self.pending.take() {
+                if self.previous_keys.as_ref() != Some(&row_keys[0]) {
+                    previous_row = Some(pending);
+                }
+            }
+            for row in 0..row_keys.len() - 1 {
+                if row_keys[row] != row_keys[row + 1] {
+                    indices.push(row as u32);
+                }
+            }
+            let last = row_keys.len() - 1;
+            self.pending = Some(self.take_rows(batch, &[last as u32])?);
+            self.previous_keys = Some(row_keys[last].clone());
+        }
+
+        let batch = self.take_rows(batch, &indices)?;
+        let previous_row = match previous_row {
+            Some(previous_row) => previous_row,
+            None => return Ok(batch),
+        };
+        // the pending row of the previous batch precedes the rows of this batch
+        let columns = previous_row
+            .columns()
+            .iter()
+            .zip(batch.columns())
+            .map(|(pending, column)| Ok(concat(&[pending.clone(), column.clone()])?))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+
+    fn take_rows(&self, batch: &RecordBatch, indices: &[u32]) -> Result<RecordBatch> {
+        let indices = UInt32Array::from(indices.to_vec());
+        Ok(RecordBatch::try_new(
+            self.schema.clone(),
+            take_columns(batch, &indices)?,
+        )?)
+    }
+}
+
+impl BatchIterator for DeduplicateIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        match self.next_input_batch()? {
+            Some(batch) => Ok(Some(self.deduplicate(&batch)?)),
+            // the last row of the input ends its run
+            None => Ok(self.pending.take()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::{MemTable, TableProvider};
+    use crate::execution::physical_plan::common;
+    use crate::execution::physical_plan::expressions::col;
+    use crate::test;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+
+    fn deduplicate(keep_first: bool) -> Result<Vec<String>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int32, true),
+            Field::new("v", DataType::Utf8, false),
+        ]));
+        let batch = |keys: Vec<Option<i32>>, values: Vec<&str>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(keys)),
+                    Arc::new(StringArray::from(values)),
+                ],
+            )
+        };
+        // runs continue across batches and partitions
+        let partitions = vec![
+            vec![
+                batch(vec![Some(1), Some(1), Some(2)], vec!["a", "b", "c"])?,
+                batch(vec![Some(2), None], vec!["d", "e"])?,
+            ],
+            vec![
+                batch(vec![None, None, Some(3)], vec!["f", "g", "h"])?,
+                batch(vec![], vec![])?,
+                batch(vec![Some(3)], vec!["i"])?,
+            ],
+        ];
+        let input = MemTable::new_partitioned(schema.clone(), partitions)?
+            .create_physical_plan(&None, None, None, 1024)?;
+        let plan = DeduplicateExec::try_new(vec![col(0, &schema)], keep_first, input)?;
+
+        let partitions = plan.partitions()?;
+        assert_eq!(partitions.len(), 1);
+        let results = common::collect(partitions[0].execute()?)?;
+        Ok(results.iter().flat_map(test::format_batch).collect())
+    }
+
+    #[test]
+    fn keep_first() -> Result<()> {
+        assert_eq!(deduplicate(true)?, vec!["1,a", "2,c", "NULL,e", "3,h"]);
+        Ok(())
+    }
+
+    #[test]
+    fn keep_last() -> Result<()> {
+        assert_eq!(deduplicate(false)?, vec!["1,b", "2,d", "NULL,g", "3,i"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the plan of a query without a FROM clause, which has a single row
+
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::physical_plan::common::RecordBatchIterator;
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use arrow::array::BooleanArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Execution plan that produces a single row, which the expressions of a query without
+/// a FROM clause are evaluated against. Record batches must have at least one column,
+/// so the row has a placeholder column with a null value that no expression refers to.
+pub struct EmptyExec {
+    schema: Arc<Schema>,
+}
+
+impl Default for EmptyExec {
+    fn default() -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![Field::new(
+                "placeholder",
+                DataType::Boolean,
+                true,
+            )])),
+        }
+    }
+}
+
+impl EmptyExec {
+    /// Create a plan that produces a single row
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ExecutionPlan for EmptyExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(vec![Arc::new(EmptyPartition {
+            schema: self.schema.clone(),
+        })])
+    }
+}
+
+/// The single partition of an empty plan
+struct EmptyPartition {
+    schema: Arc<Schema>,
+}
+
+impl Partition for EmptyPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let batch = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![Arc::new(BooleanArray::from(vec![None]))],
+        )?;
+        Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+            self.schema.clone(),
+            vec![Arc::new(batch)],
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn single_row() -> Result<()> {
+        let empty = EmptyExec::new();
+        assert_eq!(empty.partitions()?.len(), 1);
+
+        let batches = test::execute(&empty)?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 1);
+        assert_eq!(batches[0].num_columns(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the evaluation context, which lets physical expressions reuse the memory of
+//! the arrays they produced for earlier batches
+
+use std::collections::HashMap;
+
+use arrow::array::ArrayRef;
+use arrow::buffer::{Buffer, MutableBuffer};
+use arrow::util::bit_util;
+
+/// Maximum number of output buffers that are tracked until they can be reused. Buffers
+/// of arrays that are kept for longer, e.g. by a sort, are no longer tracked once more
+/// recent buffers replace them.
+const MAX_TRACKED_BUFFERS: usize = 16;
+
+/// State for evaluating physical expressions against the batches of a partition.
+///
+/// The context keeps the buffers of the arrays that expressions produce. Once such an
+/// array is no longer referenced, its memory is used for the output of a later batch
+/// of the same size instead of allocating new memory, so that evaluating expressions
+/// over batches of a steady size does not allocate output buffers.
+///
+/// A context is owned by the iterator of a single partition and is not shared between
+/// threads.
+#[derive(Debug, Default)]
+pub struct EvaluationContext {
+    /// Buffers of arrays produced by expressions that may still be referenced
+    in_use: Vec<Buffer>,
+    /// Buffers that are no longer referenced, keyed by their capacity in bytes, which is
+    /// determined by the data type and length of the arrays they were created for
+    free: HashMap<usize, Vec<MutableBuffer>>,
+}
+
+impl EvaluationContext {
+    /// Create a new evaluation context without any buffers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get an empty buffer that can hold `len` bytes, reusing the memory of an array
+    /// that is no longer referenced when possible
+    pub fn buffer(&mut self, len: usize) -> MutableBuffer {
+        self.reclaim();
+        let capacity = bit_util::round_upto_multiple_of_64(len);
+        match self
+            .free
+            .get_mut(&capacity)
+            .and_then(|buffers| buffers.pop())
+        {
+            Some(buffer) => buffer,
+            None => MutableBuffer::new(len),
+        }
+    }
+
+    /// Track the buffers of an array produced by an expression, so that their memory
+    /// can be reused once the array is no longer referenced
+    pub fn track(&mut self, array: &ArrayRef) {
+        for buffer in array.data().buffers() {
+            if self.in_use.len() == MAX_TRACKED_BUFFERS {
+                self.in_use.remove(0);
+            }
+            self.in_use.push(buffer.clone());
+        }
+    }
+
+    /// The number of buffers that are ready to be reused
+    pub fn free_buffer_count(&self) -> usize {
+        self.free.values().map(|buffers| buffers.len()).sum()
+    }
+
+    /// Move the tracked buffers that are no longer referenced elsewhere to the free
+    /// buffers
+    fn reclaim(&mut self) {
+        let in_use = std::mem::replace(&mut self.in_use, vec![]);
+        for buffer in in_use {
+            match buffer.into_mutable() {
+                Ok(mut buffer) => {
+                    buffer.clear();
+                    let buffers =
+                        self.free.entry(buffer.capacity()).or_insert_with(Vec::new);
+                    if buffers.len() < MAX_TRACKED_BUFFERS {
+                        buffers.push(buffer);
+                    }
+                }
+                Err(buffer) => self.in_use.push(buffer),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, Int32Array};
+    use std::sync::Arc;
+
+    #[test]
+    fn reuse_buffers() {
+        let mut ctx = EvaluationContext::new();
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        let ptr = array.data().buffers()[0].raw_data();
+        ctx.track(&array);
+
+        // the memory cannot be reused while the array is referenced
+        let buffer = ctx.buffer(12);
+        assert_ne!(ptr, buffer.raw_data());
+        assert_eq!(ctx.free_buffer_count(), 0);
+
+        drop(array);
+        let buffer = ctx.buffer(12);
+        assert_eq!(ptr, buffer.raw_data());
+        assert!(buffer.is_empty());
+        assert_eq!(ctx.free_buffer_count(), 0);
+    }
+
+    #[test]
+    fn buffers_are_keyed_by_size() {
+        let mut ctx = EvaluationContext::new();
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3]));
+        ctx.track(&array);
+        drop(array);
+
+        // a buffer that is too large for the memory of the array is allocated
+        let buffer = ctx.buffer(1000);
+        assert!(buffer.capacity() >= 1000);
+        assert_eq!(ctx.free_buffer_count(), 1);
+    }
+}
@@ -17,34 +17,66 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
+use std::borrow::Cow;
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::mem;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::error::{ExecutionError, Result};
-use crate::execution::physical_plan::common::get_scalar_value;
+use crate::execution::physical_plan::common::{get_scalar_value, new_null_array};
+use crate::execution::physical_plan::evaluation_context::EvaluationContext;
 use crate::execution::physical_plan::{Accumulator, AggregateExpr, PhysicalExpr};
-use crate::logicalplan::{Operator, ScalarValue};
+use crate::logicalplan::{Expr, Operator, ScalarValue};
+use crate::optimizer::utils;
 use arrow::array::{
-    ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
-    Int64Array, Int8Array, StringArray, TimestampNanosecondArray, UInt16Array,
-    UInt32Array, UInt64Array, UInt8Array,
+    Array, ArrayRef, BooleanArray, Decimal128Array, FixedSizeListArray, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, ListArray,
+    PrimitiveArray, StringArray, StructArray, TimestampMicrosecondArray,
+    TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
+    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 use arrow::array::{
-    Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder,
-    Int8Builder, StringBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
-    UInt8Builder,
+    BooleanBuilder, Date32Builder, Decimal128Builder, Float32Builder, Float64Builder,
+    Int16Builder, Int32Builder, Int64Builder, Int8Builder, IntervalDayTimeBuilder,
+    IntervalYearMonthBuilder, StringBuilder, TimestampNanosecondBuilder, UInt16Builder,
+    UInt32Builder, UInt64Builder, UInt8Builder,
 };
 use arrow::compute;
-use arrow::compute::kernels::arithmetic::{add, divide, multiply, subtract};
-use arrow::compute::kernels::boolean::{and, or};
+use arrow::compute::kernels::arithmetic::{add, divide, modulus, multiply, subtract};
+use arrow::compute::kernels::arithmetic::{
+    add_with_buffer, divide_with_buffer, modulus_with_buffer, multiply_with_buffer,
+    subtract_with_buffer,
+};
+use arrow::compute::kernels::boolean::{and, is_not_null, is_null, or};
 use arrow::compute::kernels::cast::cast;
 use arrow::compute::kernels::comparison::{eq, gt, gt_eq, lt, lt_eq, neq};
+use arrow::compute::kernels::comparison::{
+    eq_decimal, gt_decimal, gt_eq_decimal, lt_decimal, lt_eq_decimal, neq_decimal,
+};
 use arrow::compute::kernels::comparison::{
     eq_utf8, gt_eq_utf8, gt_utf8, like_utf8, lt_eq_utf8, lt_utf8, neq_utf8, nlike_utf8,
 };
-use arrow::datatypes::{DataType, Schema, TimeUnit};
+use arrow::compute::kernels::comparison::{
+    eq_utf8_collated, gt_eq_utf8_collated, gt_utf8_collated, lt_eq_utf8_collated,
+    lt_utf8_collated, neq_utf8_collated, Collation,
+};
+use arrow::compute::kernels::comparison::{
+    like_utf8_with_options, nlike_utf8_with_options, LikeOptions,
+};
+use arrow::compute::kernels::regexp::{
+    regexp_is_match_utf8, regexp_is_not_match_utf8, RegexOptions,
+};
+use arrow::compute::kernels::string::concat_strings;
+use arrow::compute::WidthBuckets;
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, Int8Type, Schema, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
 use arrow::record_batch::RecordBatch;
+use rand::Rng;
 
 /// Represents an aliased expression
 pub struct Alias {
@@ -74,6 +106,14 @@ impl PhysicalExpr for Alias {
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
         self.expr.evaluate(batch)
     }
+
+    fn evaluate_with_context(
+        &self,
+        batch: &RecordBatch,
+        ctx: &mut EvaluationContext,
+    ) -> Result<ArrayRef> {
+        self.expr.evaluate_with_context(batch, ctx)
+    }
 }
 
 /// Represents the column at a given index in a RecordBatch
@@ -141,6 +181,8 @@ impl AggregateExpr for Sum {
             }
             DataType::Float32 => Ok(DataType::Float32),
             DataType::Float64 => Ok(DataType::Float64),
+            // sums of decimals keep their scale and have the maximum precision
+            DataType::Decimal(_, scale) => Ok(DataType::Decimal(38, scale)),
             other => Err(ExecutionError::General(format!(
                 "SUM does not support {:?}",
                 other
@@ -153,7 +195,10 @@ impl AggregateExpr for Sum {
     }
 
     fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
-        Rc::new(RefCell::new(SumAccumulator { sum: None }))
+        Rc::new(RefCell::new(SumAccumulator {
+            sum: None,
+            count: 0,
+        }))
     }
 
     fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
@@ -177,13 +222,31 @@ macro_rules! sum_accumulate {
     }};
 }
 
+macro_rules! sum_retract {
+    ($SELF:ident, $VALUE:expr, $SCALAR_VARIANT:ident, $TY:ty) => {{
+        $SELF.sum = match $SELF.sum {
+            Some(ScalarValue::$SCALAR_VARIANT(n)) => {
+                Some(ScalarValue::$SCALAR_VARIANT(n - $VALUE as $TY))
+            }
+            _ => {
+                return Err(ExecutionError::InternalError(
+                    "Unexpected ScalarValue variant".to_string(),
+                ))
+            }
+        };
+    }};
+}
+
 struct SumAccumulator {
     sum: Option<ScalarValue>,
+    /// The number of values in the sum, which is null again once all are retracted
+    count: u64,
 }
 
 impl Accumulator for SumAccumulator {
     fn accumulate_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
         if let Some(value) = value {
+            self.count += 1;
             match value {
                 ScalarValue::Int8(value) => {
                     sum_accumulate!(self, value, Int8Array, Int64, i64);
@@ -215,6 +278,62 @@ impl Accumulator for SumAccumulator {
                 ScalarValue::Float64(value) => {
                     sum_accumulate!(self, value, Float64Array, Float64, f64);
                 }
+                ScalarValue::Decimal128(value, _, scale) => {
+                    let sum = match &self.sum {
+                        Some(ScalarValue::Decimal128(n, _, _)) => {
+                            n.checked_add(value).ok_or_else(|| {
+                                ExecutionError::ExecutionError(
+                                    "Decimal overflow in SUM".to_string(),
+                                )
+                            })?
+                        }
+                        Some(_) => {
+                            return Err(ExecutionError::InternalError(
+                                "Unexpected ScalarValue variant".to_string(),
+                            ))
+                        }
+                        None => value,
+                    };
+                    self.sum = Some(ScalarValue::Decimal128(sum, 38, scale));
+                }
+                other => {
+                    return Err(ExecutionError::General(format!(
+                        "SUM does not support {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+
+    fn retract_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        if let Some(value) = value {
+            match value {
+                ScalarValue::Int8(value) => sum_retract!(self, value, Int64, i64),
+                ScalarValue::Int16(value) => sum_retract!(self, value, Int64, i64),
+                ScalarValue::Int32(value) => sum_retract!(self, value, Int64, i64),
+                ScalarValue::Int64(value) => sum_retract!(self, value, Int64, i64),
+                ScalarValue::UInt8(value) => sum_retract!(self, value, UInt64, u64),
+                ScalarValue::UInt16(value) => sum_retract!(self, value, UInt64, u64),
+                ScalarValue::UInt32(value) => sum_retract!(self, value, UInt64, u64),
+                ScalarValue::UInt64(value) => sum_retract!(self, value, UInt64, u64),
+                ScalarValue::Float32(value) => sum_retract!(self, value, Float32, f32),
+                ScalarValue::Float64(value) => sum_retract!(self, value, Float64, f64),
+                ScalarValue::Decimal128(value, _, scale) => match self.sum {
+                    Some(ScalarValue::Decimal128(n, _, _)) => {
+                        self.sum = Some(ScalarValue::Decimal128(n - value, 38, scale));
+                    }
+                    _ => {
+                        return Err(ExecutionError::InternalError(
+                            "Unexpected ScalarValue variant".to_string(),
+                        ))
+                    }
+                },
                 other => {
                     return Err(ExecutionError::General(format!(
                         "SUM does not support {:?}",
@@ -222,11 +341,17 @@ impl Accumulator for SumAccumulator {
                     )))
                 }
             }
+            self.count -= 1;
+            if self.count == 0 {
+                self.sum = None;
+            }
         }
         Ok(())
     }
 
     fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
+        // the values of the batch are counted, rather than their sum
+        let count = self.count + (array.len() - array.null_count()) as u64;
         let sum = match array.data_type() {
             DataType::UInt8 => {
                 match compute::sum(array.as_any().downcast_ref::<UInt8Array>().unwrap()) {
@@ -293,11 +418,31 @@ impl Accumulator for SumAccumulator {
                     None => Ok(None),
                 }
             }
+            DataType::Decimal(_, scale) => {
+                let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+                let mut sum: Option<i128> = None;
+                for i in 0..array.len() {
+                    if array.is_valid(i) {
+                        let value = array.value(i);
+                        sum = Some(match sum {
+                            Some(n) => n.checked_add(value).ok_or_else(|| {
+                                ExecutionError::ExecutionError(
+                                    "Decimal overflow in SUM".to_string(),
+                                )
+                            })?,
+                            None => value,
+                        });
+                    }
+                }
+                Ok(sum.map(|n| ScalarValue::Decimal128(n, 38, *scale)))
+            }
             _ => Err(ExecutionError::ExecutionError(
                 "Unsupported data type for SUM".to_string(),
             )),
         }?;
-        self.accumulate_scalar(sum)
+        self.accumulate_scalar(sum)?;
+        self.count = count;
+        Ok(())
     }
 
     fn get_value(&self) -> Result<Option<ScalarValue>> {
@@ -339,6 +484,7 @@ impl AggregateExpr for Avg {
             | DataType::UInt64
             | DataType::Float32
             | DataType::Float64 => Ok(DataType::Float64),
+            DataType::Decimal(_, scale) => Ok(DataType::Decimal(38, avg_scale(scale))),
             other => Err(ExecutionError::General(format!(
                 "AVG does not support {:?}",
                 other
@@ -354,6 +500,7 @@ impl AggregateExpr for Avg {
         Rc::new(RefCell::new(AvgAccumulator {
             sum: None,
             count: None,
+            decimal_sum: None,
         }))
     }
 
@@ -376,9 +523,27 @@ macro_rules! avg_accumulate {
         };
     }};
 }
+
+macro_rules! avg_retract {
+    ($SELF:ident, $VALUE:expr) => {{
+        if let (Some(sum), Some(count)) = ($SELF.sum, $SELF.count) {
+            $SELF.sum = Some(sum - $VALUE as f64);
+            $SELF.count = Some(count - 1);
+        }
+    }};
+}
+
+/// The scale of the average of decimals of a scale, which has at least 6 digits after
+/// the decimal point so that averaging the averages of partitions keeps the scale
+fn avg_scale(scale: usize) -> usize {
+    scale.max(6)
+}
+
 struct AvgAccumulator {
     sum: Option<f64>,
     count: Option<i64>,
+    /// the exact sum and the scale of decimal values
+    decimal_sum: Option<(i128, usize)>,
 }
 
 impl Accumulator for AvgAccumulator {
@@ -395,6 +560,53 @@ impl Accumulator for AvgAccumulator {
                 ScalarValue::UInt64(value) => avg_accumulate!(self, value, UInt64Array),
                 ScalarValue::Float32(value) => avg_accumulate!(self, value, Float32Array),
                 ScalarValue::Float64(value) => avg_accumulate!(self, value, Float64Array),
+                ScalarValue::Decimal128(value, _, scale) => {
+                    let sum = match self.decimal_sum {
+                        Some((sum, _)) => sum.checked_add(value).ok_or_else(|| {
+                            ExecutionError::ExecutionError(
+                                "Decimal overflow in AVG".to_string(),
+                            )
+                        })?,
+                        None => value,
+                    };
+                    self.decimal_sum = Some((sum, scale));
+                    self.count = Some(self.count.unwrap_or(0) + 1);
+                }
+                other => {
+                    return Err(ExecutionError::General(format!(
+                        "AVG does not support {:?}",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn supports_retract(&self) -> bool {
+        true
+    }
+
+    fn retract_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        if let Some(value) = value {
+            match value {
+                ScalarValue::Int8(value) => avg_retract!(self, value),
+                ScalarValue::Int16(value) => avg_retract!(self, value),
+                ScalarValue::Int32(value) => avg_retract!(self, value),
+                ScalarValue::Int64(value) => avg_retract!(self, value),
+                ScalarValue::UInt8(value) => avg_retract!(self, value),
+                ScalarValue::UInt16(value) => avg_retract!(self, value),
+                ScalarValue::UInt32(value) => avg_retract!(self, value),
+                ScalarValue::UInt64(value) => avg_retract!(self, value),
+                ScalarValue::Float32(value) => avg_retract!(self, value),
+                ScalarValue::Float64(value) => avg_retract!(self, value),
+                ScalarValue::Decimal128(value, _, scale) => {
+                    if let (Some((sum, _)), Some(count)) = (self.decimal_sum, self.count)
+                    {
+                        self.decimal_sum = Some((sum - value, scale));
+                        self.count = Some(count - 1);
+                    }
+                }
                 other => {
                     return Err(ExecutionError::General(format!(
                         "AVG does not support {:?}",
@@ -402,6 +614,12 @@ impl Accumulator for AvgAccumulator {
                     )))
                 }
             }
+            // the average of no values is null
+            if self.count == Some(0) {
+                self.sum = None;
+                self.count = None;
+                self.decimal_sum = None;
+            }
         }
         Ok(())
     }
@@ -414,6 +632,21 @@ impl Accumulator for AvgAccumulator {
     }
 
     fn get_value(&self) -> Result<Option<ScalarValue>> {
+        if let (Some((sum, scale)), Some(count)) = (self.decimal_sum, self.count) {
+            let scale_to = avg_scale(scale);
+            let sum = Decimal128Array::rescale_value(sum, scale, scale_to, 38)
+                .ok_or_else(|| {
+                    ExecutionError::ExecutionError("Decimal overflow in AVG".to_string())
+                })?;
+            // divide rounding half away from zero
+            let count = count as i128;
+            let avg = if (sum % count).abs() * 2 >= count {
+                sum / count + sum.signum()
+            } else {
+                sum / count
+            };
+            return Ok(Some(ScalarValue::Decimal128(avg, 38, scale_to)));
+        }
         match (self.sum, self.count) {
             (Some(sum), Some(count)) => {
                 Ok(Some(ScalarValue::Float64(sum / count as f64)))
@@ -431,12 +664,22 @@ pub fn avg(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
 /// MAX aggregate expression
 pub struct Max {
     expr: Arc<dyn PhysicalExpr>,
+    collation: Collation,
 }
 
 impl Max {
     /// Create a new MAX aggregate function
     pub fn new(expr: Arc<dyn PhysicalExpr>) -> Self {
-        Self { expr }
+        Self {
+            expr,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Compare strings under the given collation
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
     }
 }
 
@@ -455,6 +698,7 @@ impl AggregateExpr for Max {
             }
             DataType::Float32 => Ok(DataType::Float32),
             DataType::Float64 => Ok(DataType::Float64),
+            DataType::Utf8 => Ok(DataType::Utf8),
             other => Err(ExecutionError::General(format!(
                 "MAX does not support {:?}",
                 other
@@ -467,11 +711,17 @@ impl AggregateExpr for Max {
     }
 
     fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
-        Rc::new(RefCell::new(MaxAccumulator { max: None }))
+        Rc::new(RefCell::new(MaxAccumulator {
+            max: None,
+            collation: self.collation,
+        }))
     }
 
     fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
-        Arc::new(Max::new(Arc::new(Column::new(column_index, &self.name()))))
+        Arc::new(
+            Max::new(Arc::new(Column::new(column_index, &self.name())))
+                .with_collation(self.collation),
+        )
     }
 }
 
@@ -496,6 +746,7 @@ macro_rules! max_accumulate {
 }
 struct MaxAccumulator {
     max: Option<ScalarValue>,
+    collation: Collation,
 }
 
 impl Accumulator for MaxAccumulator {
@@ -532,6 +783,14 @@ impl Accumulator for MaxAccumulator {
                 ScalarValue::Float64(value) => {
                     max_accumulate!(self, value, Float64Array, Float64, f64)
                 }
+                ScalarValue::Utf8(value) => {
+                    self.max = accumulate_string(
+                        self.max.take(),
+                        value,
+                        self.collation,
+                        Ordering::Greater,
+                    )?;
+                }
                 other => {
                     return Err(ExecutionError::General(format!(
                         "MAX does not support {:?}",
@@ -610,6 +869,11 @@ impl Accumulator for MaxAccumulator {
                     None => Ok(None),
                 }
             }
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                Ok(compute::max_string(array, self.collation)
+                    .map(|s| ScalarValue::Utf8(s.to_string())))
+            }
             _ => Err(ExecutionError::ExecutionError(
                 "Unsupported data type for MAX".to_string(),
             )),
@@ -622,6 +886,27 @@ impl Accumulator for MaxAccumulator {
     }
 }
 
+/// Accumulate a string into the current minimum or maximum, replacing it if the string
+/// compares to it as `replace_if` under the collation
+fn accumulate_string(
+    current: Option<ScalarValue>,
+    value: String,
+    collation: Collation,
+    replace_if: Ordering,
+) -> Result<Option<ScalarValue>> {
+    match current {
+        Some(ScalarValue::Utf8(current))
+            if collation.compare(&value, &current) != replace_if =>
+        {
+            Ok(Some(ScalarValue::Utf8(current)))
+        }
+        Some(ScalarValue::Utf8(_)) | None => Ok(Some(ScalarValue::Utf8(value))),
+        Some(_) => Err(ExecutionError::InternalError(
+            "Unexpected ScalarValue variant".to_string(),
+        )),
+    }
+}
+
 /// Create a max expression
 pub fn max(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
     Arc::new(Max::new(expr))
@@ -630,12 +915,22 @@ pub fn max(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
 /// MIN aggregate expression
 pub struct Min {
     expr: Arc<dyn PhysicalExpr>,
+    collation: Collation,
 }
 
 impl Min {
     /// Create a new MIN aggregate function
     pub fn new(expr: Arc<dyn PhysicalExpr>) -> Self {
-        Self { expr }
+        Self {
+            expr,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Compare strings under the given collation
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
     }
 }
 
@@ -654,6 +949,7 @@ impl AggregateExpr for Min {
             }
             DataType::Float32 => Ok(DataType::Float32),
             DataType::Float64 => Ok(DataType::Float64),
+            DataType::Utf8 => Ok(DataType::Utf8),
             other => Err(ExecutionError::General(format!(
                 "MIN does not support {:?}",
                 other
@@ -666,11 +962,17 @@ impl AggregateExpr for Min {
     }
 
     fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
-        Rc::new(RefCell::new(MinAccumulator { min: None }))
+        Rc::new(RefCell::new(MinAccumulator {
+            min: None,
+            collation: self.collation,
+        }))
     }
 
     fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
-        Arc::new(Min::new(Arc::new(Column::new(column_index, &self.name()))))
+        Arc::new(
+            Min::new(Arc::new(Column::new(column_index, &self.name())))
+                .with_collation(self.collation),
+        )
     }
 }
 
@@ -695,6 +997,7 @@ macro_rules! min_accumulate {
 }
 struct MinAccumulator {
     min: Option<ScalarValue>,
+    collation: Collation,
 }
 
 impl Accumulator for MinAccumulator {
@@ -731,6 +1034,14 @@ impl Accumulator for MinAccumulator {
                 ScalarValue::Float64(value) => {
                     min_accumulate!(self, value, Float64Array, Float64, f64)
                 }
+                ScalarValue::Utf8(value) => {
+                    self.min = accumulate_string(
+                        self.min.take(),
+                        value,
+                        self.collation,
+                        Ordering::Less,
+                    )?;
+                }
                 other => {
                     return Err(ExecutionError::General(format!(
                         "MIN does not support {:?}",
@@ -809,6 +1120,11 @@ impl Accumulator for MinAccumulator {
                     None => Ok(None),
                 }
             }
+            DataType::Utf8 => {
+                let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+                Ok(compute::min_string(array, self.collation)
+                    .map(|s| ScalarValue::Utf8(s.to_string())))
+            }
             _ => Err(ExecutionError::ExecutionError(
                 "Unsupported data type for MIN".to_string(),
             )),
@@ -873,6 +1189,17 @@ impl Accumulator for CountAccumulator {
         Ok(())
     }
 
+    fn supports_retract(&self) -> bool {
+        true
+    }
+
+    fn retract_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        if value.is_some() {
+            self.count -= 1;
+        }
+        Ok(())
+    }
+
     fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
         self.count += array.len() as u64 - array.null_count() as u64;
         Ok(())
@@ -888,168 +1215,1168 @@ pub fn count(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
     Arc::new(Count::new(expr))
 }
 
-/// Invoke a compute kernel on a pair of binary data arrays
-macro_rules! compute_utf8_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
-        let ll = $LEFT
-            .as_any()
-            .downcast_ref::<$DT>()
-            .expect("compute_op failed to downcast array");
-        let rr = $RIGHT
-            .as_any()
-            .downcast_ref::<$DT>()
-            .expect("compute_op failed to downcast array");
-        Ok(Arc::new(paste::expr! {[<$OP _utf8>]}(&ll, &rr)?))
-    }};
+/// BOOL_AND aggregate expression
+/// Returns whether all non-null values of the given boolean expression are true.
+pub struct BoolAnd {
+    expr: Arc<dyn PhysicalExpr>,
 }
 
-/// Invoke a compute kernel on a pair of arrays
-macro_rules! compute_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
-        let ll = $LEFT
-            .as_any()
-            .downcast_ref::<$DT>()
-            .expect("compute_op failed to downcast array");
-        let rr = $RIGHT
-            .as_any()
-            .downcast_ref::<$DT>()
-            .expect("compute_op failed to downcast array");
-        Ok(Arc::new($OP(&ll, &rr)?))
-    }};
+impl BoolAnd {
+    /// Create a new BOOL_AND aggregate function
+    pub fn new(expr: Arc<dyn PhysicalExpr>) -> Self {
+        Self { expr }
+    }
 }
 
-macro_rules! binary_string_array_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
-        match $LEFT.data_type() {
-            DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
-            other => Err(ExecutionError::General(format!(
-                "Unsupported data type {:?}",
-                other
-            ))),
-        }
-    }};
-}
+impl AggregateExpr for BoolAnd {
+    fn name(&self) -> String {
+        "BOOL_AND".to_string()
+    }
 
-/// Invoke a compute kernel on a pair of arrays
-/// The binary_primitive_array_op macro only evaluates for primitive types
-/// like integers and floats.
-macro_rules! binary_primitive_array_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
-        match $LEFT.data_type() {
-            DataType::Int8 => compute_op!($LEFT, $RIGHT, $OP, Int8Array),
-            DataType::Int16 => compute_op!($LEFT, $RIGHT, $OP, Int16Array),
-            DataType::Int32 => compute_op!($LEFT, $RIGHT, $OP, Int32Array),
-            DataType::Int64 => compute_op!($LEFT, $RIGHT, $OP, Int64Array),
-            DataType::UInt8 => compute_op!($LEFT, $RIGHT, $OP, UInt8Array),
-            DataType::UInt16 => compute_op!($LEFT, $RIGHT, $OP, UInt16Array),
-            DataType::UInt32 => compute_op!($LEFT, $RIGHT, $OP, UInt32Array),
-            DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
-            DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
-            DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
-            other => Err(ExecutionError::General(format!(
-                "Unsupported data type {:?}",
-                other
-            ))),
-        }
-    }};
-}
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        bool_aggregate_data_type(&self.expr, input_schema, "BOOL_AND")
+    }
 
-/// The binary_array_op macro includes types that extend beyond the primitive,
-/// such as Utf8 strings.
-macro_rules! binary_array_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
-        match $LEFT.data_type() {
-            DataType::Int8 => compute_op!($LEFT, $RIGHT, $OP, Int8Array),
-            DataType::Int16 => compute_op!($LEFT, $RIGHT, $OP, Int16Array),
-            DataType::Int32 => compute_op!($LEFT, $RIGHT, $OP, Int32Array),
-            DataType::Int64 => compute_op!($LEFT, $RIGHT, $OP, Int64Array),
-            DataType::UInt8 => compute_op!($LEFT, $RIGHT, $OP, UInt8Array),
-            DataType::UInt16 => compute_op!($LEFT, $RIGHT, $OP, UInt16Array),
-            DataType::UInt32 => compute_op!($LEFT, $RIGHT, $OP, UInt32Array),
-            DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
-            DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
-            DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
-            DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
-            DataType::Timestamp(TimeUnit::Nanosecond, None) => {
-                compute_op!($LEFT, $RIGHT, $OP, TimestampNanosecondArray)
-            }
-            other => Err(ExecutionError::General(format!(
-                "Unsupported data type {:?}",
-                other
-            ))),
-        }
-    }};
+    fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        self.expr.evaluate(batch)
+    }
+
+    fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
+        Rc::new(RefCell::new(BoolAccumulator {
+            value: None,
+            is_and: true,
+        }))
+    }
+
+    fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
+        Arc::new(BoolAnd::new(Arc::new(Column::new(
+            column_index,
+            &self.name(),
+        ))))
+    }
 }
 
-/// Invoke a boolean kernel on a pair of arrays
-macro_rules! boolean_op {
-    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
-        let ll = $LEFT
-            .as_any()
-            .downcast_ref::<BooleanArray>()
-            .expect("boolean_op failed to downcast array");
-        let rr = $RIGHT
-            .as_any()
-            .downcast_ref::<BooleanArray>()
-            .expect("boolean_op failed to downcast array");
-        Ok(Arc::new($OP(&ll, &rr)?))
-    }};
+/// Create a bool_and expression
+pub fn bool_and(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
+    Arc::new(BoolAnd::new(expr))
 }
-/// Binary expression
-pub struct BinaryExpr {
-    left: Arc<dyn PhysicalExpr>,
-    op: Operator,
-    right: Arc<dyn PhysicalExpr>,
+
+/// BOOL_OR aggregate expression
+/// Returns whether any non-null value of the given boolean expression is true.
+pub struct BoolOr {
+    expr: Arc<dyn PhysicalExpr>,
 }
 
-impl BinaryExpr {
-    /// Create new binary expression
-    pub fn new(
-        left: Arc<dyn PhysicalExpr>,
-        op: Operator,
-        right: Arc<dyn PhysicalExpr>,
-    ) -> Self {
-        Self { left, op, right }
+impl BoolOr {
+    /// Create a new BOOL_OR aggregate function
+    pub fn new(expr: Arc<dyn PhysicalExpr>) -> Self {
+        Self { expr }
     }
 }
 
-impl PhysicalExpr for BinaryExpr {
+impl AggregateExpr for BoolOr {
     fn name(&self) -> String {
-        format!("{:?}", self.op)
+        "BOOL_OR".to_string()
     }
 
     fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
-        self.left.data_type(input_schema)
+        bool_aggregate_data_type(&self.expr, input_schema, "BOOL_OR")
     }
 
-    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
-        let left = self.left.evaluate(batch)?;
-        let right = self.right.evaluate(batch)?;
-        if left.data_type() != right.data_type() {
-            return Err(ExecutionError::General(format!(
-                "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
-                self.op,
-                left.data_type(),
-                right.data_type()
-            )));
-        }
-        match &self.op {
-            Operator::Like => binary_string_array_op!(left, right, like),
-            Operator::NotLike => binary_string_array_op!(left, right, nlike),
-            Operator::Lt => binary_array_op!(left, right, lt),
-            Operator::LtEq => binary_array_op!(left, right, lt_eq),
-            Operator::Gt => binary_array_op!(left, right, gt),
-            Operator::GtEq => binary_array_op!(left, right, gt_eq),
-            Operator::Eq => binary_array_op!(left, right, eq),
-            Operator::NotEq => binary_array_op!(left, right, neq),
-            Operator::Plus => binary_primitive_array_op!(left, right, add),
-            Operator::Minus => binary_primitive_array_op!(left, right, subtract),
-            Operator::Multiply => binary_primitive_array_op!(left, right, multiply),
-            Operator::Divide => binary_primitive_array_op!(left, right, divide),
-            Operator::And => {
-                if left.data_type() == &DataType::Boolean {
-                    boolean_op!(left, right, and)
-                } else {
+    fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        self.expr.evaluate(batch)
+    }
+
+    fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
+        Rc::new(RefCell::new(BoolAccumulator {
+            value: None,
+            is_and: false,
+        }))
+    }
+
+    fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
+        Arc::new(BoolOr::new(Arc::new(Column::new(
+            column_index,
+            &self.name(),
+        ))))
+    }
+}
+
+/// Create a bool_or expression
+pub fn bool_or(expr: Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr> {
+    Arc::new(BoolOr::new(expr))
+}
+
+/// The data type of BOOL_AND and BOOL_OR, which only accept boolean input
+fn bool_aggregate_data_type(
+    expr: &Arc<dyn PhysicalExpr>,
+    input_schema: &Schema,
+    name: &str,
+) -> Result<DataType> {
+    match expr.data_type(input_schema)? {
+        DataType::Boolean => Ok(DataType::Boolean),
+        other => Err(ExecutionError::General(format!(
+            "{} does not support {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// Accumulator for BOOL_AND and BOOL_OR, which ignores null values and produces null
+/// if there were no non-null values
+struct BoolAccumulator {
+    value: Option<bool>,
+    /// Whether values are combined with AND rather than OR
+    is_and: bool,
+}
+
+impl BoolAccumulator {
+    fn combine(&mut self, value: bool) {
+        self.value = Some(match self.value {
+            Some(v) if self.is_and => v && value,
+            Some(v) => v || value,
+            None => value,
+        });
+    }
+}
+
+impl Accumulator for BoolAccumulator {
+    fn accumulate_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        match value {
+            Some(ScalarValue::Boolean(value)) => self.combine(value),
+            Some(other) => {
+                return Err(ExecutionError::General(format!(
+                    "Boolean aggregate does not support {:?}",
+                    other
+                )))
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
+        let array = array
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| {
+                ExecutionError::ExecutionError(
+                    "Unsupported data type for boolean aggregate".to_string(),
+                )
+            })?;
+        let value = if self.is_and {
+            compute::all(array)
+        } else {
+            compute::any(array)
+        };
+        if let Some(value) = value {
+            self.combine(value);
+        }
+        Ok(())
+    }
+
+    fn get_value(&self) -> Result<Option<ScalarValue>> {
+        Ok(self.value.map(ScalarValue::Boolean))
+    }
+}
+
+/// MAX_BY and MIN_BY aggregate expressions
+/// Return the value of an expression at the row where another expression, the order,
+/// is largest or smallest. Rows where the order is null are ignored.
+pub struct MinMaxBy {
+    input: MinMaxByInput,
+    /// Whether the value at the largest order is returned, rather than the smallest
+    is_max: bool,
+}
+
+/// The input of a MAX_BY or MIN_BY aggregate expression
+enum MinMaxByInput {
+    /// The value and order expressions, which are evaluated against the rows of the
+    /// input and combined into the intermediate state of each partition
+    Rows {
+        value: Arc<dyn PhysicalExpr>,
+        order: Arc<dyn PhysicalExpr>,
+    },
+    /// The intermediate (value, order) state of each partition, which is combined into
+    /// the final value
+    State(Arc<dyn PhysicalExpr>),
+}
+
+impl MinMaxBy {
+    /// Create a new MAX_BY or MIN_BY aggregate function
+    pub fn new(
+        value: Arc<dyn PhysicalExpr>,
+        order: Arc<dyn PhysicalExpr>,
+        is_max: bool,
+    ) -> Self {
+        Self {
+            input: MinMaxByInput::Rows { value, order },
+            is_max,
+        }
+    }
+}
+
+impl AggregateExpr for MinMaxBy {
+    fn name(&self) -> String {
+        if self.is_max {
+            "MAX_BY".to_string()
+        } else {
+            "MIN_BY".to_string()
+        }
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        match &self.input {
+            MinMaxByInput::Rows { value, order } => Ok(DataType::Struct(vec![
+                Field::new("value", value.data_type(input_schema)?, true),
+                Field::new("order", order.data_type(input_schema)?, true),
+            ])),
+            // integer values are widened to 64 bits, as the results of MAX and MIN
+            MinMaxByInput::State(state) => match state.data_type(input_schema)? {
+                DataType::Struct(fields) => match fields[0].data_type() {
+                    DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64 => Ok(DataType::Int64),
+                    DataType::UInt8
+                    | DataType::UInt16
+                    | DataType::UInt32
+                    | DataType::UInt64 => Ok(DataType::UInt64),
+                    other => Ok(other.clone()),
+                },
+                other => Err(ExecutionError::InternalError(format!(
+                    "Unexpected state of type {:?} for {}",
+                    other,
+                    self.name()
+                ))),
+            },
+        }
+    }
+
+    fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        match &self.input {
+            MinMaxByInput::Rows { value, order } => {
+                let value = value.evaluate(batch)?;
+                let order = order.evaluate(batch)?;
+                Ok(Arc::new(StructArray::from(vec![
+                    (Field::new("value", value.data_type().clone(), true), value),
+                    (Field::new("order", order.data_type().clone(), true), order),
+                ])))
+            }
+            MinMaxByInput::State(state) => state.evaluate(batch),
+        }
+    }
+
+    fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
+        Rc::new(RefCell::new(MinMaxByAccumulator {
+            value: ScalarValue::Null,
+            order: None,
+            is_max: self.is_max,
+            is_final: match self.input {
+                MinMaxByInput::Rows { .. } => false,
+                MinMaxByInput::State(_) => true,
+            },
+        }))
+    }
+
+    fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
+        Arc::new(MinMaxBy {
+            input: MinMaxByInput::State(Arc::new(Column::new(
+                column_index,
+                &self.name(),
+            ))),
+            is_max: self.is_max,
+        })
+    }
+
+    fn requires_reducer(&self) -> bool {
+        match self.input {
+            MinMaxByInput::Rows { .. } => true,
+            MinMaxByInput::State(_) => false,
+        }
+    }
+}
+
+/// Accumulates the value at the extreme order seen so far. The (value, order) pairs of
+/// the input and the intermediate state have the same representation, so that states
+/// can be combined like rows.
+struct MinMaxByAccumulator {
+    value: ScalarValue,
+    order: Option<ScalarValue>,
+    is_max: bool,
+    /// Whether the accumulator produces the final value rather than the intermediate
+    /// state
+    is_final: bool,
+}
+
+impl Accumulator for MinMaxByAccumulator {
+    fn accumulate_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        let (value, order) = match value {
+            Some(ScalarValue::Struct(pair)) if pair.len() == 2 => {
+                (pair[0].clone(), pair[1].clone())
+            }
+            None => return Ok(()),
+            Some(other) => {
+                return Err(ExecutionError::InternalError(format!(
+                    "Unexpected input {:?} for MAX_BY or MIN_BY",
+                    other
+                )))
+            }
+        };
+        if order == ScalarValue::Null {
+            return Ok(());
+        }
+        // the first row wins when the order is tied
+        let replace = match &self.order {
+            None => true,
+            Some(current) => {
+                let ordering = compare_scalar_values(&order, current)?;
+                if self.is_max {
+                    ordering == Ordering::Greater
+                } else {
+                    ordering == Ordering::Less
+                }
+            }
+        };
+        if replace {
+            self.value = value;
+            self.order = Some(order);
+        }
+        Ok(())
+    }
+
+    fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
+        for row in 0..array.len() {
+            self.accumulate_scalar(get_scalar_value(array, row)?)?;
+        }
+        Ok(())
+    }
+
+    fn get_value(&self) -> Result<Option<ScalarValue>> {
+        match (&self.order, self.is_final) {
+            (None, _) => Ok(None),
+            (Some(_), true) if self.value == ScalarValue::Null => Ok(None),
+            (Some(_), true) => Ok(Some(self.value.clone())),
+            (Some(order), false) => Ok(Some(ScalarValue::Struct(vec![
+                self.value.clone(),
+                order.clone(),
+            ]))),
+        }
+    }
+}
+
+/// Compare two non-null values of the same type
+fn compare_scalar_values(left: &ScalarValue, right: &ScalarValue) -> Result<Ordering> {
+    let ordering = match (left, right) {
+        (ScalarValue::Boolean(l), ScalarValue::Boolean(r)) => l.partial_cmp(r),
+        (ScalarValue::Int8(l), ScalarValue::Int8(r)) => l.partial_cmp(r),
+        (ScalarValue::Int16(l), ScalarValue::Int16(r)) => l.partial_cmp(r),
+        (ScalarValue::Int32(l), ScalarValue::Int32(r)) => l.partial_cmp(r),
+        (ScalarValue::Int64(l), ScalarValue::Int64(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt8(l), ScalarValue::UInt8(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt16(l), ScalarValue::UInt16(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt32(l), ScalarValue::UInt32(r)) => l.partial_cmp(r),
+        (ScalarValue::UInt64(l), ScalarValue::UInt64(r)) => l.partial_cmp(r),
+        (ScalarValue::Float32(l), ScalarValue::Float32(r)) => l.partial_cmp(r),
+        (ScalarValue::Float64(l), ScalarValue::Float64(r)) => l.partial_cmp(r),
+        (ScalarValue::Utf8(l), ScalarValue::Utf8(r)) => l.partial_cmp(r),
+        _ => None,
+    };
+    ordering.ok_or_else(|| {
+        ExecutionError::ExecutionError(format!(
+            "Cannot compare {:?} with {:?}",
+            left, right
+        ))
+    })
+}
+
+/// Create a max_by expression
+pub fn max_by(
+    value: Arc<dyn PhysicalExpr>,
+    order: Arc<dyn PhysicalExpr>,
+) -> Arc<dyn AggregateExpr> {
+    Arc::new(MinMaxBy::new(value, order, true))
+}
+
+/// Create a min_by expression
+pub fn min_by(
+    value: Arc<dyn PhysicalExpr>,
+    order: Arc<dyn PhysicalExpr>,
+) -> Arc<dyn AggregateExpr> {
+    Arc::new(MinMaxBy::new(value, order, false))
+}
+
+/// HISTOGRAM aggregate expression
+/// Count the values of an expression in each of a number of buckets of equal width
+/// between two bounds, as numbered by `width_bucket`. Returns a struct with a `UInt64`
+/// count per bucket, including the buckets before and after the bounds.
+pub struct Histogram {
+    input: HistogramInput,
+    buckets: WidthBuckets,
+}
+
+/// The input of a HISTOGRAM aggregate expression
+enum HistogramInput {
+    /// The expression whose values are counted in the rows of the input
+    Rows(Arc<dyn PhysicalExpr>),
+    /// The counts of each partition, which are added up into the final counts
+    State(Arc<dyn PhysicalExpr>),
+}
+
+impl Histogram {
+    /// Create a new HISTOGRAM aggregate function
+    pub fn new(expr: Arc<dyn PhysicalExpr>, buckets: WidthBuckets) -> Self {
+        Self {
+            input: HistogramInput::Rows(expr),
+            buckets,
+        }
+    }
+}
+
+/// Create the buckets of HISTOGRAM from its arguments after the first, which must be
+/// constant: the two bounds and the number of buckets
+pub fn histogram_buckets(args: &[Expr]) -> Result<WidthBuckets> {
+    let literal = |i: usize, data_type: &DataType| {
+        args.get(i)
+            .and_then(|arg| arg.evaluate_literal())
+            .and_then(|value| value.cast_to(data_type))
+    };
+    match (
+        literal(0, &DataType::Float64),
+        literal(1, &DataType::Float64),
+        literal(2, &DataType::UInt32),
+    ) {
+        (
+            Some(ScalarValue::Float64(low)),
+            Some(ScalarValue::Float64(high)),
+            Some(ScalarValue::UInt32(n)),
+        ) if args.len() == 3 => Ok(WidthBuckets::try_new(low, high, n)?),
+        _ => Err(ExecutionError::General(
+            "HISTOGRAM expects constant numeric bounds and number of buckets".to_string(),
+        )),
+    }
+}
+
+/// The data type of HISTOGRAM, a struct with the fields `bucket_0` to
+/// `bucket_{num_buckets + 1}`
+pub fn histogram_data_type(num_buckets: u32) -> DataType {
+    DataType::Struct(
+        (0..num_buckets as usize + 2)
+            .map(|i| Field::new(&format!("bucket_{}", i), DataType::UInt64, false))
+            .collect(),
+    )
+}
+
+impl AggregateExpr for Histogram {
+    fn name(&self) -> String {
+        "HISTOGRAM".to_string()
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        match &self.input {
+            HistogramInput::Rows(expr) => match expr.data_type(input_schema)? {
+                ref dt if is_numeric(dt) => {
+                    Ok(histogram_data_type(self.buckets.num_buckets()))
+                }
+                other => Err(ExecutionError::General(format!(
+                    "HISTOGRAM does not support {:?}",
+                    other
+                ))),
+            },
+            HistogramInput::State(_) => {
+                Ok(histogram_data_type(self.buckets.num_buckets()))
+            }
+        }
+    }
+
+    fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        match &self.input {
+            HistogramInput::Rows(expr) | HistogramInput::State(expr) => {
+                expr.evaluate(batch)
+            }
+        }
+    }
+
+    fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
+        Rc::new(RefCell::new(HistogramAccumulator {
+            buckets: self.buckets,
+            counts: vec![0; self.buckets.num_buckets() as usize + 2],
+            is_final: match self.input {
+                HistogramInput::Rows(_) => false,
+                HistogramInput::State(_) => true,
+            },
+        }))
+    }
+
+    fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
+        Arc::new(Histogram {
+            input: HistogramInput::State(Arc::new(Column::new(
+                column_index,
+                &self.name(),
+            ))),
+            buckets: self.buckets,
+        })
+    }
+}
+
+/// Counts the values in each bucket. The intermediate state has the same type as the
+/// final value, so that counts are combined by adding them up.
+struct HistogramAccumulator {
+    buckets: WidthBuckets,
+    counts: Vec<u64>,
+    /// Whether the input is the counts of other accumulators rather than values
+    is_final: bool,
+}
+
+impl HistogramAccumulator {
+    fn add_counts(&mut self, counts: &[ScalarValue]) -> Result<()> {
+        if counts.len() != self.counts.len() {
+            return Err(ExecutionError::InternalError(format!(
+                "Expected {} counts for HISTOGRAM, found {}",
+                self.counts.len(),
+                counts.len()
+            )));
+        }
+        for (total, count) in self.counts.iter_mut().zip(counts) {
+            match count {
+                ScalarValue::UInt64(n) => *total += n,
+                other => {
+                    return Err(ExecutionError::InternalError(format!(
+                        "Unexpected count {:?} for HISTOGRAM",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for HistogramAccumulator {
+    fn accumulate_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        match value {
+            None | Some(ScalarValue::Null) => Ok(()),
+            Some(ScalarValue::Struct(counts)) if self.is_final => {
+                self.add_counts(&counts)
+            }
+            Some(value) => match value.cast_to(&DataType::Float64) {
+                Some(ScalarValue::Float64(v)) => {
+                    if let Some(bucket) = self.buckets.bucket(v) {
+                        self.counts[bucket as usize] += 1;
+                    }
+                    Ok(())
+                }
+                _ => Err(ExecutionError::InternalError(format!(
+                    "Unexpected input {:?} for HISTOGRAM",
+                    value
+                ))),
+            },
+        }
+    }
+
+    fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
+        if self.is_final {
+            for row in 0..array.len() {
+                self.accumulate_scalar(get_scalar_value(array, row)?)?;
+            }
+            return Ok(());
+        }
+        let array = cast(array, &DataType::Float64)?;
+        let array = array
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .expect("Failed to cast array");
+        let counts = compute::histogram(array, &self.buckets);
+        for (total, count) in self.counts.iter_mut().zip(counts) {
+            *total += count;
+        }
+        Ok(())
+    }
+
+    fn get_value(&self) -> Result<Option<ScalarValue>> {
+        Ok(Some(ScalarValue::Struct(
+            self.counts
+                .iter()
+                .map(|n| ScalarValue::UInt64(*n))
+                .collect(),
+        )))
+    }
+}
+
+/// Create a histogram expression
+pub fn histogram(
+    expr: Arc<dyn PhysicalExpr>,
+    buckets: WidthBuckets,
+) -> Arc<dyn AggregateExpr> {
+    Arc::new(Histogram::new(expr, buckets))
+}
+
+/// RESERVOIR_SAMPLE aggregate expression
+/// Sample up to a number of the non-null values of an expression uniformly at random.
+/// Returns a list of the sampled values, which has all of the values if there are no
+/// more of them than the size of the sample.
+pub struct ReservoirSample {
+    input: ReservoirSampleInput,
+    size: usize,
+}
+
+/// The input of a RESERVOIR_SAMPLE aggregate expression
+enum ReservoirSampleInput {
+    /// The expression whose values are sampled from the rows of the input
+    Rows(Arc<dyn PhysicalExpr>),
+    /// The (count, sample) state of each partition, which are merged into the final
+    /// sample
+    State(Arc<dyn PhysicalExpr>),
+}
+
+impl ReservoirSample {
+    /// Create a new RESERVOIR_SAMPLE aggregate function
+    pub fn new(expr: Arc<dyn PhysicalExpr>, size: usize) -> Self {
+        Self {
+            input: ReservoirSampleInput::Rows(expr),
+            size,
+        }
+    }
+}
+
+/// Get the size of the sample of RESERVOIR_SAMPLE from its second argument, which must
+/// be a positive constant
+pub fn reservoir_sample_size(arg: &Expr) -> Result<usize> {
+    match arg
+        .evaluate_literal()
+        .and_then(|value| value.cast_to(&DataType::UInt32))
+    {
+        Some(ScalarValue::UInt32(size)) if size > 0 => Ok(size as usize),
+        _ => Err(ExecutionError::General(
+            "RESERVOIR_SAMPLE expects a positive constant sample size".to_string(),
+        )),
+    }
+}
+
+impl AggregateExpr for ReservoirSample {
+    fn name(&self) -> String {
+        "RESERVOIR_SAMPLE".to_string()
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        match &self.input {
+            ReservoirSampleInput::Rows(expr) => {
+                let value_type = expr.data_type(input_schema)?;
+                Ok(DataType::Struct(vec![
+                    Field::new("count", DataType::UInt64, false),
+                    Field::new("sample", DataType::List(Box::new(value_type)), false),
+                ]))
+            }
+            ReservoirSampleInput::State(state) => match state.data_type(input_schema)? {
+                DataType::Struct(fields) => Ok(fields[1].data_type().clone()),
+                other => Err(ExecutionError::InternalError(format!(
+                    "Unexpected state of type {:?} for {}",
+                    other,
+                    self.name()
+                ))),
+            },
+        }
+    }
+
+    fn evaluate_input(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        match &self.input {
+            ReservoirSampleInput::Rows(expr) | ReservoirSampleInput::State(expr) => {
+                expr.evaluate(batch)
+            }
+        }
+    }
+
+    fn create_accumulator(&self) -> Rc<RefCell<dyn Accumulator>> {
+        Rc::new(RefCell::new(ReservoirSampleAccumulator {
+            size: self.size,
+            count: 0,
+            sample: vec![],
+            is_final: match self.input {
+                ReservoirSampleInput::Rows(_) => false,
+                ReservoirSampleInput::State(_) => true,
+            },
+        }))
+    }
+
+    fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr> {
+        Arc::new(ReservoirSample {
+            input: ReservoirSampleInput::State(Arc::new(Column::new(
+                column_index,
+                &self.name(),
+            ))),
+            size: self.size,
+        })
+    }
+
+    fn requires_reducer(&self) -> bool {
+        match self.input {
+            ReservoirSampleInput::Rows(_) => true,
+            ReservoirSampleInput::State(_) => false,
+        }
+    }
+}
+
+/// Keeps a uniform sample of the values seen so far with reservoir sampling. The
+/// samples of several accumulators are merged by drawing from them in proportion to
+/// the number of values that they were taken from.
+struct ReservoirSampleAccumulator {
+    size: usize,
+    /// The number of values that the sample was taken from
+    count: u64,
+    sample: Vec<ScalarValue>,
+    /// Whether the input is the state of other accumulators rather than values
+    is_final: bool,
+}
+
+impl ReservoirSampleAccumulator {
+    fn add_value(&mut self, value: ScalarValue) {
+        self.count += 1;
+        if self.sample.len() < self.size {
+            self.sample.push(value);
+        } else {
+            // the new value replaces a sampled one with probability size / count
+            let index = rand::thread_rng().gen_range(0, self.count);
+            if index < self.size as u64 {
+                self.sample[index as usize] = value;
+            }
+        }
+    }
+
+    fn merge(&mut self, count: u64, mut sample: Vec<ScalarValue>) {
+        let mut own = mem::replace(&mut self.sample, vec![]);
+        let (mut own_count, mut other_count) = (self.count, count);
+        self.count += count;
+        let mut rng = rand::thread_rng();
+        // each sample holds all of its values or at least `size` of them, so neither
+        // runs out of values while its count is positive
+        while self.sample.len() < self.size && own_count + other_count > 0 {
+            let (values, count) = if rng.gen_range(0, own_count + other_count) < own_count
+            {
+                (&mut own, &mut own_count)
+            } else {
+                (&mut sample, &mut other_count)
+            };
+            let index = rng.gen_range(0, values.len());
+            self.sample.push(values.swap_remove(index));
+            *count -= 1;
+        }
+    }
+}
+
+impl Accumulator for ReservoirSampleAccumulator {
+    fn accumulate_scalar(&mut self, value: Option<ScalarValue>) -> Result<()> {
+        match value {
+            None | Some(ScalarValue::Null) => {}
+            Some(ScalarValue::Struct(state)) if self.is_final => {
+                match (state.get(0), state.get(1)) {
+                    (
+                        Some(ScalarValue::UInt64(count)),
+                        Some(ScalarValue::List(sample)),
+                    ) => self.merge(*count, sample.clone()),
+                    _ => {
+                        return Err(ExecutionError::InternalError(format!(
+                            "Unexpected state {:?} for RESERVOIR_SAMPLE",
+                            state
+                        )))
+                    }
+                }
+            }
+            Some(value) => self.add_value(value),
+        }
+        Ok(())
+    }
+
+    fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()> {
+        for row in 0..array.len() {
+            self.accumulate_scalar(get_scalar_value(array, row)?)?;
+        }
+        Ok(())
+    }
+
+    fn get_value(&self) -> Result<Option<ScalarValue>> {
+        let sample = ScalarValue::List(self.sample.clone());
+        if self.is_final {
+            Ok(Some(sample))
+        } else {
+            Ok(Some(ScalarValue::Struct(vec![
+                ScalarValue::UInt64(self.count),
+                sample,
+            ])))
+        }
+    }
+}
+
+/// Create a reservoir_sample expression
+pub fn reservoir_sample(
+    expr: Arc<dyn PhysicalExpr>,
+    size: usize,
+) -> Arc<dyn AggregateExpr> {
+    Arc::new(ReservoirSample::new(expr, size))
+}
+
+/// Invoke a compute kernel on a pair of binary data arrays
+macro_rules! compute_utf8_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<$DT>()
+            .expect("compute_op failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<$DT>()
+            .expect("compute_op failed to downcast array");
+        Ok(Arc::new(paste::expr! {[<$OP _utf8>]}(&ll, &rr)?))
+    }};
+}
+
+/// Invoke a compute kernel on a pair of decimal arrays
+macro_rules! compute_decimal_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .expect("compute_op failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<Decimal128Array>()
+            .expect("compute_op failed to downcast array");
+        Ok(Arc::new(paste::expr! {[<$OP _decimal>]}(&ll, &rr)?))
+    }};
+}
+
+/// Invoke a compute kernel on a pair of arrays
+macro_rules! compute_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $DT:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<$DT>()
+            .expect("compute_op failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<$DT>()
+            .expect("compute_op failed to downcast array");
+        Ok(Arc::new($OP(&ll, &rr)?))
+    }};
+}
+
+macro_rules! binary_string_array_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        match $LEFT.data_type() {
+            DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
+            other => Err(ExecutionError::General(format!(
+                "Unsupported data type {:?}",
+                other
+            ))),
+        }
+    }};
+}
+
+/// Invoke a compute kernel on a pair of arrays
+/// The binary_primitive_array_op macro only evaluates for primitive types
+/// like integers and floats.
+macro_rules! binary_primitive_array_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        match $LEFT.data_type() {
+            DataType::Int8 => compute_op!($LEFT, $RIGHT, $OP, Int8Array),
+            DataType::Int16 => compute_op!($LEFT, $RIGHT, $OP, Int16Array),
+            DataType::Int32 => compute_op!($LEFT, $RIGHT, $OP, Int32Array),
+            DataType::Int64 => compute_op!($LEFT, $RIGHT, $OP, Int64Array),
+            DataType::UInt8 => compute_op!($LEFT, $RIGHT, $OP, UInt8Array),
+            DataType::UInt16 => compute_op!($LEFT, $RIGHT, $OP, UInt16Array),
+            DataType::UInt32 => compute_op!($LEFT, $RIGHT, $OP, UInt32Array),
+            DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
+            DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
+            DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
+            other => Err(ExecutionError::General(format!(
+                "Unsupported data type {:?}",
+                other
+            ))),
+        }
+    }};
+}
+
+/// The binary_array_op macro includes types that extend beyond the primitive,
+/// such as Utf8 strings.
+macro_rules! binary_array_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        match $LEFT.data_type() {
+            DataType::Int8 => compute_op!($LEFT, $RIGHT, $OP, Int8Array),
+            DataType::Int16 => compute_op!($LEFT, $RIGHT, $OP, Int16Array),
+            DataType::Int32 => compute_op!($LEFT, $RIGHT, $OP, Int32Array),
+            DataType::Int64 => compute_op!($LEFT, $RIGHT, $OP, Int64Array),
+            DataType::UInt8 => compute_op!($LEFT, $RIGHT, $OP, UInt8Array),
+            DataType::UInt16 => compute_op!($LEFT, $RIGHT, $OP, UInt16Array),
+            DataType::UInt32 => compute_op!($LEFT, $RIGHT, $OP, UInt32Array),
+            DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
+            DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
+            DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Utf8 => compute_utf8_op!($LEFT, $RIGHT, $OP, StringArray),
+            // timestamps are instants in UTC, so their time zone does not matter
+            DataType::Timestamp(TimeUnit::Second, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, TimestampSecondArray)
+            }
+            DataType::Timestamp(TimeUnit::Millisecond, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, TimestampMillisecondArray)
+            }
+            DataType::Timestamp(TimeUnit::Microsecond, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, TimestampMicrosecondArray)
+            }
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+                compute_op!($LEFT, $RIGHT, $OP, TimestampNanosecondArray)
+            }
+            DataType::Decimal(_, _) => compute_decimal_op!($LEFT, $RIGHT, $OP),
+            other => Err(ExecutionError::General(format!(
+                "Unsupported data type {:?}",
+                other
+            ))),
+        }
+    }};
+}
+
+/// Invoke a comparison kernel on a pair of arrays, comparing strings under a collation
+macro_rules! comparison_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $COLLATION:expr) => {{
+        if $LEFT.data_type() == &DataType::Utf8 && $COLLATION != Collation::Binary {
+            let ll = $LEFT
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("comparison_op failed to downcast array");
+            let rr = $RIGHT
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("comparison_op failed to downcast array");
+            Ok(Arc::new(paste::expr! {[<$OP _utf8_collated>]}(
+                &ll, &rr, $COLLATION,
+            )?))
+        } else {
+            binary_array_op!($LEFT, $RIGHT, $OP)
+        }
+    }};
+}
+
+/// Invoke an arithmetic kernel on a pair of primitive arrays, writing the result into a
+/// buffer of the evaluation context
+macro_rules! compute_op_with_context {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $T:ident, $CTX:expr) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<PrimitiveArray<$T>>()
+            .expect("compute_op_with_context failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<PrimitiveArray<$T>>()
+            .expect("compute_op_with_context failed to downcast array");
+        let len = ll.len() * mem::size_of::<<$T as ArrowPrimitiveType>::Native>();
+        let buffer = $CTX.buffer(len);
+        Ok(Arc::new(paste::expr! {[<$OP _with_buffer>]}(
+            &ll, &rr, buffer,
+        )?))
+    }};
+}
+
+/// Invoke an arithmetic kernel on a pair of arrays, writing the result into a buffer of
+/// the evaluation context. Only numeric types are supported.
+macro_rules! binary_primitive_array_op_with_context {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident, $CTX:expr) => {{
+        match $LEFT.data_type() {
+            DataType::Int8 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Int8Type, $CTX)
+            }
+            DataType::Int16 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Int16Type, $CTX)
+            }
+            DataType::Int32 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Int32Type, $CTX)
+            }
+            DataType::Int64 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Int64Type, $CTX)
+            }
+            DataType::UInt8 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, UInt8Type, $CTX)
+            }
+            DataType::UInt16 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, UInt16Type, $CTX)
+            }
+            DataType::UInt32 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, UInt32Type, $CTX)
+            }
+            DataType::UInt64 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, UInt64Type, $CTX)
+            }
+            DataType::Float32 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Float32Type, $CTX)
+            }
+            DataType::Float64 => {
+                compute_op_with_context!($LEFT, $RIGHT, $OP, Float64Type, $CTX)
+            }
+            other => Err(ExecutionError::General(format!(
+                "Unsupported data type {:?}",
+                other
+            ))),
+        }
+    }};
+}
+
+/// Invoke a boolean kernel on a pair of arrays
+macro_rules! boolean_op {
+    ($LEFT:expr, $RIGHT:expr, $OP:ident) => {{
+        let ll = $LEFT
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .expect("boolean_op failed to downcast array");
+        let rr = $RIGHT
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .expect("boolean_op failed to downcast array");
+        Ok(Arc::new($OP(&ll, &rr)?))
+    }};
+}
+/// Binary expression
+pub struct BinaryExpr {
+    left: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    right: Arc<dyn PhysicalExpr>,
+    collation: Collation,
+}
+
+impl BinaryExpr {
+    /// Create new binary expression
+    pub fn new(
+        left: Arc<dyn PhysicalExpr>,
+        op: Operator,
+        right: Arc<dyn PhysicalExpr>,
+    ) -> Self {
+        Self {
+            left,
+            op,
+            right,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Compare strings under the given collation
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+}
+
+impl PhysicalExpr for BinaryExpr {
+    fn name(&self) -> String {
+        format!("{:?}", self.op)
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        match &self.op {
+            Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq
+            | Operator::Like
+            | Operator::NotLike
+            | Operator::RegexMatch
+            | Operator::RegexIMatch
+            | Operator::RegexNotMatch
+            | Operator::RegexNotIMatch => Ok(DataType::Boolean),
+            _ => self.left.data_type(input_schema),
+        }
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let left = self.left.evaluate(batch)?;
+        let right = self.right.evaluate(batch)?;
+        self.evaluate_arrays(&left, &right)
+    }
+
+    fn evaluate_with_context(
+        &self,
+        batch: &RecordBatch,
+        ctx: &mut EvaluationContext,
+    ) -> Result<ArrayRef> {
+        let left = self.left.evaluate_with_context(batch, ctx)?;
+        let right = self.right.evaluate_with_context(batch, ctx)?;
+        if left.data_type() != right.data_type() {
+            return self.evaluate_arrays(&left, &right);
+        }
+        // arithmetic kernels write their output into memory of the context
+        let result: Result<ArrayRef> = match &self.op {
+            Operator::Plus => {
+                binary_primitive_array_op_with_context!(left, right, add, ctx)
+            }
+            Operator::Minus => {
+                binary_primitive_array_op_with_context!(left, right, subtract, ctx)
+            }
+            Operator::Multiply => {
+                binary_primitive_array_op_with_context!(left, right, multiply, ctx)
+            }
+            Operator::Divide => {
+                binary_primitive_array_op_with_context!(left, right, divide, ctx)
+            }
+            Operator::Modulus => {
+                binary_primitive_array_op_with_context!(left, right, modulus, ctx)
+            }
+            _ => return self.evaluate_arrays(&left, &right),
+        };
+        let result = result?;
+        ctx.track(&result);
+        Ok(result)
+    }
+}
+
+impl BinaryExpr {
+    /// Apply the operator to the evaluated operands
+    fn evaluate_arrays(&self, left: &ArrayRef, right: &ArrayRef) -> Result<ArrayRef> {
+        if left.data_type() != right.data_type() {
+            return Err(ExecutionError::General(format!(
+                "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
+                self.op,
+                left.data_type(),
+                right.data_type()
+            )));
+        }
+        match &self.op {
+            Operator::Like => binary_string_array_op!(left, right, like),
+            Operator::NotLike => binary_string_array_op!(left, right, nlike),
+            Operator::RegexMatch => self.regexp_op(left, right, false, false),
+            Operator::RegexIMatch => self.regexp_op(left, right, true, false),
+            Operator::RegexNotMatch => self.regexp_op(left, right, false, true),
+            Operator::RegexNotIMatch => self.regexp_op(left, right, true, true),
+            Operator::Lt => comparison_op!(left, right, lt, self.collation),
+            Operator::LtEq => comparison_op!(left, right, lt_eq, self.collation),
+            Operator::Gt => comparison_op!(left, right, gt, self.collation),
+            Operator::GtEq => comparison_op!(left, right, gt_eq, self.collation),
+            Operator::Eq => comparison_op!(left, right, eq, self.collation),
+            Operator::NotEq => comparison_op!(left, right, neq, self.collation),
+            Operator::Plus => binary_primitive_array_op!(left, right, add),
+            Operator::Minus => binary_primitive_array_op!(left, right, subtract),
+            Operator::Multiply => binary_primitive_array_op!(left, right, multiply),
+            Operator::Divide => binary_primitive_array_op!(left, right, divide),
+            Operator::Modulus => binary_primitive_array_op!(left, right, modulus),
+            Operator::StringConcat => match left.data_type() {
+                DataType::Utf8 => compute_op!(left, right, concat_strings, StringArray),
+                other => Err(ExecutionError::General(format!(
+                    "Unsupported data type {:?}",
+                    other
+                ))),
+            },
+            Operator::And => {
+                if left.data_type() == &DataType::Boolean {
+                    boolean_op!(left, right, and)
+                } else {
+                    return Err(ExecutionError::General(format!(
+                        "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
+                        self.op,
+                        left.data_type(),
+                        right.data_type()
+                    )));
+                }
+            }
+            Operator::Or => {
+                if left.data_type() == &DataType::Boolean {
+                    boolean_op!(left, right, or)
+                } else {
                     return Err(ExecutionError::General(format!(
                         "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
                         self.op,
@@ -1058,74 +2385,631 @@ impl PhysicalExpr for BinaryExpr {
                     )));
                 }
             }
-            Operator::Or => {
-                if left.data_type() == &DataType::Boolean {
-                    boolean_op!(left, right, or)
+            _ => Err(ExecutionError::General("Unsupported operator".to_string())),
+        }
+    }
+
+    /// Match the strings of the left operand with the regular expressions of the right
+    fn regexp_op(
+        &self,
+        left: &ArrayRef,
+        right: &ArrayRef,
+        case_insensitive: bool,
+        negated: bool,
+    ) -> Result<ArrayRef> {
+        match (
+            left.as_any().downcast_ref::<StringArray>(),
+            right.as_any().downcast_ref::<StringArray>(),
+        ) {
+            (Some(left), Some(right)) => {
+                let options = RegexOptions {
+                    case_insensitive,
+                    ..RegexOptions::default()
+                };
+                Ok(Arc::new(if negated {
+                    regexp_is_not_match_utf8(left, right, &options)?
+                } else {
+                    regexp_is_match_utf8(left, right, &options)?
+                }))
+            }
+            _ => Err(ExecutionError::General(format!(
+                "Unsupported data type {:?}",
+                left.data_type()
+            ))),
+        }
+    }
+}
+
+/// Create a binary expression
+pub fn binary(
+    l: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    r: Arc<dyn PhysicalExpr>,
+) -> Arc<dyn PhysicalExpr> {
+    Arc::new(BinaryExpr::new(l, op, r))
+}
+
+/// Not expression
+pub struct NotExpr {
+    arg: Arc<dyn PhysicalExpr>,
+}
+
+impl NotExpr {
+    /// Create new not expression
+    pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
+        Self { arg }
+    }
+}
+
+impl PhysicalExpr for NotExpr {
+    fn name(&self) -> String {
+        "NOT".to_string()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        return Ok(DataType::Boolean);
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let arg = self.arg.evaluate(batch)?;
+        if arg.data_type() != &DataType::Boolean {
+            return Err(ExecutionError::General(format!(
+                "Cannot evaluate \"not\" expression with type {:?}",
+                arg.data_type(),
+            )));
+        }
+        let arg = arg
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .expect("boolean_op failed to downcast array");
+        return Ok(Arc::new(arrow::compute::kernels::boolean::not(arg)?));
+    }
+}
+
+/// Create a unary expression
+pub fn not(arg: Arc<dyn PhysicalExpr>) -> Arc<dyn PhysicalExpr> {
+    Arc::new(NotExpr::new(arg))
+}
+
+/// IS NULL expression
+pub struct IsNullExpr {
+    arg: Arc<dyn PhysicalExpr>,
+}
+
+impl IsNullExpr {
+    /// Create new is null expression
+    pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
+        Self { arg }
+    }
+}
+
+impl PhysicalExpr for IsNullExpr {
+    fn name(&self) -> String {
+        "IS NULL".to_string()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let arg = self.arg.evaluate(batch)?;
+        Ok(Arc::new(is_null(arg.as_ref())?))
+    }
+}
+
+/// Create an IS NULL expression
+pub fn is_null_expr(arg: Arc<dyn PhysicalExpr>) -> Arc<dyn PhysicalExpr> {
+    Arc::new(IsNullExpr::new(arg))
+}
+
+/// IS NOT NULL expression
+pub struct IsNotNullExpr {
+    arg: Arc<dyn PhysicalExpr>,
+}
+
+impl IsNotNullExpr {
+    /// Create new is not null expression
+    pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
+        Self { arg }
+    }
+}
+
+impl PhysicalExpr for IsNotNullExpr {
+    fn name(&self) -> String {
+        "IS NOT NULL".to_string()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let arg = self.arg.evaluate(batch)?;
+        Ok(Arc::new(is_not_null(arg.as_ref())?))
+    }
+}
+
+/// Create an IS NOT NULL expression
+pub fn is_not_null_expr(arg: Arc<dyn PhysicalExpr>) -> Arc<dyn PhysicalExpr> {
+    Arc::new(IsNotNullExpr::new(arg))
+}
+
+/// Element of a list at an index starting at 1, which is null if the list or the index
+/// is null or the index is out of bounds
+pub struct ElementAtExpr {
+    list: Arc<dyn PhysicalExpr>,
+    index: Arc<dyn PhysicalExpr>,
+}
+
+impl ElementAtExpr {
+    /// Create an element expression over a List or FixedSizeList expression and an
+    /// Int64 index
+    pub fn new(list: Arc<dyn PhysicalExpr>, index: Arc<dyn PhysicalExpr>) -> Self {
+        Self { list, index }
+    }
+}
+
+impl PhysicalExpr for ElementAtExpr {
+    fn name(&self) -> String {
+        "element_at".to_string()
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        utils::element_type(&self.list.data_type(input_schema)?)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let list = self.list.evaluate(batch)?;
+        let index = self.index.evaluate(batch)?;
+        let index = match index.as_any().downcast_ref::<Int64Array>() {
+            Some(index) => index,
+            None => {
+                return Err(ExecutionError::General(format!(
+                    "Invalid list index type {:?}",
+                    index.data_type()
+                )))
+            }
+        };
+        element_at(&list, index)
+    }
+}
+
+/// Get the element of each list of a List or FixedSizeList array at an index starting
+/// at 1
+fn element_at(list: &ArrayRef, index: &Int64Array) -> Result<ArrayRef> {
+    // the offset of each list within the values and its length
+    let (values, bounds): (ArrayRef, Vec<(i64, i64)>) = match list.data_type() {
+        DataType::List(_) => {
+            let list = list.as_any().downcast_ref::<ListArray>().unwrap();
+            let bounds = (0..list.len())
+                .map(|i| (list.value_offset(i) as i64, list.value_length(i) as i64))
+                .collect();
+            (list.values(), bounds)
+        }
+        DataType::FixedSizeList(_, _) => {
+            let list = list.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+            let bounds = (0..list.len())
+                .map(|i| (list.value_offset(i) as i64, list.value_length() as i64))
+                .collect();
+            (list.values(), bounds)
+        }
+        other => {
+            return Err(ExecutionError::General(format!(
+                "Cannot get an element of a value of type {:?}",
+                other
+            )))
+        }
+    };
+
+    let mut indices = UInt32Builder::new(list.len());
+    for (i, (offset, length)) in bounds.into_iter().enumerate() {
+        if list.is_valid(i)
+            && index.is_valid(i)
+            && index.value(i) >= 1
+            && index.value(i) <= length
+        {
+            indices.append_value((offset + index.value(i) - 1) as u32)?;
+        } else {
+            indices.append_null()?;
+        }
+    }
+    Ok(compute::take(&values, &indices.finish(), None)?)
+}
+
+/// CASE expression, which evaluates to the result of the first branch whose condition
+/// is true for a row, or to the ELSE result if there is none.
+///
+/// Branches are evaluated lazily for each batch: the conditions are only evaluated
+/// while there are rows whose branch has not been determined, and a result is only
+/// evaluated if its condition is true for any of those rows.
+pub struct CaseExpr {
+    /// The conditions and results of the branches, which all have the same type
+    when_then_expr: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+    /// The result when no condition is true, which is null if missing
+    else_expr: Option<Arc<dyn PhysicalExpr>>,
+}
+
+impl CaseExpr {
+    /// Create a CASE expression
+    pub fn try_new(
+        when_then_expr: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+        else_expr: Option<Arc<dyn PhysicalExpr>>,
+    ) -> Result<Self> {
+        if when_then_expr.is_empty() {
+            return Err(ExecutionError::General(
+                "CASE expression requires at least one WHEN clause".to_string(),
+            ));
+        }
+        Ok(Self {
+            when_then_expr,
+            else_expr,
+        })
+    }
+}
+
+impl PhysicalExpr for CaseExpr {
+    fn name(&self) -> String {
+        "CASE".to_string()
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.when_then_expr[0].1.data_type(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        // whether the branch of each row is yet to be determined
+        let mut remaining = vec![true; num_rows];
+        let mut remaining_count = num_rows;
+
+        let mut branches = vec![];
+        for (when, then) in &self.when_then_expr {
+            if remaining_count == 0 {
+                break;
+            }
+            let condition = when.evaluate(batch)?;
+            let condition = condition
+                .as_any()
+                .downcast_ref::<BooleanArray>()
+                .ok_or_else(|| {
+                    ExecutionError::General(format!(
+                        "CASE condition must be boolean but is {:?}",
+                        condition.data_type()
+                    ))
+                })?;
+            // a null condition does not select the branch
+            let mut mask = Vec::with_capacity(num_rows);
+            for i in 0..num_rows {
+                let selected =
+                    remaining[i] && condition.is_valid(i) && condition.value(i);
+                if selected {
+                    remaining[i] = false;
+                    remaining_count -= 1;
+                }
+                mask.push(selected);
+            }
+            if mask.iter().any(|selected| *selected) {
+                branches.push((BooleanArray::from(mask), then.evaluate(batch)?));
+            }
+        }
+
+        let mut result = match &self.else_expr {
+            Some(else_expr) if remaining_count > 0 => else_expr.evaluate(batch)?,
+            _ => new_null_array(&self.data_type(&batch.schema())?, num_rows)?,
+        };
+        // the masks of the branches do not overlap, so they can be applied in any order
+        for (mask, then) in branches.iter().rev() {
+            result = compute::zip(mask, then, &result)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Number of constant values from which an IN list is probed through a hash set,
+/// rather than by comparing each value of the expression with each value of the list
+const IN_LIST_HASH_THRESHOLD: usize = 8;
+
+/// The values of an IN list
+enum InListValues {
+    /// Expressions that are evaluated for each batch
+    Exprs(Vec<Arc<dyn PhysicalExpr>>),
+    /// Values that are known when the expression is created
+    Constants(ArrayRef),
+}
+
+/// IN expression, which is true for rows whose value equals any value of a list.
+///
+/// Like a disjunction of equality comparisons, the result is null if the value is null,
+/// or if it is not found and the list contains null.
+pub struct InListExpr {
+    expr: Arc<dyn PhysicalExpr>,
+    values: InListValues,
+    negated: bool,
+    collation: Collation,
+}
+
+impl InListExpr {
+    /// Create an IN expression with a list of expressions, which are evaluated for
+    /// each batch
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        list: Vec<Arc<dyn PhysicalExpr>>,
+        negated: bool,
+    ) -> Self {
+        Self {
+            expr,
+            values: InListValues::Exprs(list),
+            negated,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Create an IN expression with a list of constant values. Large lists are probed
+    /// through a hash set of their values.
+    pub fn new_constant(
+        expr: Arc<dyn PhysicalExpr>,
+        values: ArrayRef,
+        negated: bool,
+    ) -> Self {
+        Self {
+            expr,
+            values: InListValues::Constants(values),
+            negated,
+            collation: Collation::Binary,
+        }
+    }
+
+    /// Compare strings under the given collation
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// The result of the condition for a row, given whether its value was found in
+    /// the list, which is unknown for a null value, and whether the list has nulls
+    fn result(&self, found: Option<bool>, list_has_null: bool) -> Option<bool> {
+        match found {
+            Some(true) => Some(!self.negated),
+            // a value that is not found may still equal the null values of the list
+            Some(false) if list_has_null => None,
+            Some(false) => Some(self.negated),
+            None => None,
+        }
+    }
+}
+
+/// Key of a value of an IN list or of the expression that is compared with it, such
+/// that two values of the same type are equal if and only if their keys are equal
+#[derive(PartialEq, Eq, Hash)]
+enum InListKey<'a> {
+    Integer(i128),
+    /// The bits of a float, where negative zero is replaced by zero
+    Float(u64),
+    /// The sort key of a string under the collation
+    Utf8(Cow<'a, str>),
+}
+
+/// Get the key of each value of an array, which is `None` for null values
+macro_rules! in_list_keys {
+    ($ARRAY:expr, $ARRAY_TYPE:ident, $KEY:expr) => {{
+        let array = $ARRAY
+            .as_any()
+            .downcast_ref::<$ARRAY_TYPE>()
+            .expect("in_list_keys failed to downcast array");
+        (0..array.len())
+            .map(|i| {
+                if array.is_null(i) {
+                    None
+                } else {
+                    Some($KEY(array.value(i)))
+                }
+            })
+            .collect()
+    }};
+}
+
+/// Get the key of each value of an array, comparing strings under the collation
+fn in_list_keys(
+    array: &ArrayRef,
+    collation: Collation,
+) -> Result<Vec<Option<InListKey>>> {
+    let float_key = |value: f64| {
+        InListKey::Float(if value == 0.0 { 0.0f64 } else { value }.to_bits())
+    };
+    Ok(match array.data_type() {
+        DataType::Boolean => {
+            in_list_keys!(array, BooleanArray, |v: bool| InListKey::Integer(v as i128))
+        }
+        DataType::Int8 => {
+            in_list_keys!(array, Int8Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::Int16 => {
+            in_list_keys!(array, Int16Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::Int32 => {
+            in_list_keys!(array, Int32Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::Int64 => {
+            in_list_keys!(array, Int64Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::UInt8 => {
+            in_list_keys!(array, UInt8Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::UInt16 => {
+            in_list_keys!(array, UInt16Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::UInt32 => {
+            in_list_keys!(array, UInt32Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::UInt64 => {
+            in_list_keys!(array, UInt64Array, |v| InListKey::Integer(v as i128))
+        }
+        DataType::Float32 => in_list_keys!(array, Float32Array, |v| float_key(v as f64)),
+        DataType::Float64 => in_list_keys!(array, Float64Array, float_key),
+        DataType::Utf8 => in_list_keys!(array, StringArray, |v| InListKey::Utf8(
+            collation.sort_key(v)
+        )),
+        other => {
+            return Err(ExecutionError::NotImplemented(format!(
+                "IN lists are not supported for type {:?}",
+                other
+            )))
+        }
+    })
+}
+
+impl PhysicalExpr for InListExpr {
+    fn name(&self) -> String {
+        "IN".to_string()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let value = self.expr.evaluate(batch)?;
+        let check_type = |array: &ArrayRef| {
+            if array.data_type() == value.data_type() {
+                Ok(())
+            } else {
+                Err(ExecutionError::General(format!(
+                    "IN list values of type {:?} cannot be compared with {:?}",
+                    array.data_type(),
+                    value.data_type()
+                )))
+            }
+        };
+        let keys = in_list_keys(&value, self.collation)?;
+
+        let result: Vec<Option<bool>> = match &self.values {
+            InListValues::Constants(values) => {
+                check_type(values)?;
+                let list = in_list_keys(values, self.collation)?;
+                let has_null = list.iter().any(Option::is_none);
+                let list = list.into_iter().flatten().collect::<Vec<_>>();
+                let set = if list.len() >= IN_LIST_HASH_THRESHOLD {
+                    Some(list.iter().collect::<HashSet<_>>())
                 } else {
-                    return Err(ExecutionError::General(format!(
-                        "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
-                        self.op,
-                        left.data_type(),
-                        right.data_type()
-                    )));
+                    None
+                };
+                keys.iter()
+                    .map(|key| {
+                        let found = key.as_ref().map(|key| match &set {
+                            Some(set) => set.contains(key),
+                            None => list.contains(key),
+                        });
+                        self.result(found, has_null)
+                    })
+                    .collect()
+            }
+            InListValues::Exprs(exprs) => {
+                let mut list = Vec::with_capacity(exprs.len());
+                for expr in exprs {
+                    let array = expr.evaluate(batch)?;
+                    check_type(&array)?;
+                    list.push(array);
                 }
+                let list = list
+                    .iter()
+                    .map(|array| in_list_keys(array, self.collation))
+                    .collect::<Result<Vec<_>>>()?;
+                keys.iter()
+                    .enumerate()
+                    .map(|(i, key)| {
+                        let found = key.as_ref().map(|key| {
+                            list.iter().any(|values| values[i].as_ref() == Some(key))
+                        });
+                        let has_null = list.iter().any(|values| values[i].is_none());
+                        self.result(found, has_null)
+                    })
+                    .collect()
             }
-            _ => Err(ExecutionError::General("Unsupported operator".to_string())),
-        }
+        };
+        Ok(Arc::new(BooleanArray::from(result)))
     }
 }
 
-/// Create a binary expression
-pub fn binary(
-    l: Arc<dyn PhysicalExpr>,
-    op: Operator,
-    r: Arc<dyn PhysicalExpr>,
-) -> Arc<dyn PhysicalExpr> {
-    Arc::new(BinaryExpr::new(l, op, r))
+/// LIKE expression, which is true for rows whose value matches a pattern, where `%`
+/// matches any number of characters and `_` a single one. The result is null if the
+/// value or the pattern is null.
+pub struct LikeExpr {
+    expr: Arc<dyn PhysicalExpr>,
+    pattern: Arc<dyn PhysicalExpr>,
+    negated: bool,
+    options: LikeOptions,
 }
 
-/// Not expression
-pub struct NotExpr {
-    arg: Arc<dyn PhysicalExpr>,
-}
+impl LikeExpr {
+    /// Create a LIKE expression that matches case-sensitively and without an escape
+    /// character
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        pattern: Arc<dyn PhysicalExpr>,
+        negated: bool,
+    ) -> Self {
+        Self {
+            expr,
+            pattern,
+            negated,
+            options: LikeOptions::default(),
+        }
+    }
 
-impl NotExpr {
-    /// Create new not expression
-    pub fn new(arg: Arc<dyn PhysicalExpr>) -> Self {
-        Self { arg }
+    /// Match letters regardless of their case, as for ILIKE
+    pub fn with_case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.options.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Make the character that follows the escape character in the pattern match
+    /// itself
+    pub fn with_escape(mut self, escape: Option<char>) -> Self {
+        self.options.escape = escape;
+        self
     }
 }
 
-impl PhysicalExpr for NotExpr {
+impl PhysicalExpr for LikeExpr {
     fn name(&self) -> String {
-        "NOT".to_string()
+        if self.options.case_insensitive {
+            "ILIKE".to_string()
+        } else {
+            "LIKE".to_string()
+        }
     }
 
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
-        return Ok(DataType::Boolean);
+        Ok(DataType::Boolean)
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
-        let arg = self.arg.evaluate(batch)?;
-        if arg.data_type() != &DataType::Boolean {
-            return Err(ExecutionError::General(format!(
-                "Cannot evaluate \"not\" expression with type {:?}",
-                arg.data_type(),
-            )));
+        let value = self.expr.evaluate(batch)?;
+        let pattern = self.pattern.evaluate(batch)?;
+        let strings = (
+            value.as_any().downcast_ref::<StringArray>(),
+            pattern.as_any().downcast_ref::<StringArray>(),
+        );
+        match strings {
+            (Some(value), Some(pattern)) if self.negated => Ok(Arc::new(
+                nlike_utf8_with_options(value, pattern, &self.options)?,
+            )),
+            (Some(value), Some(pattern)) => Ok(Arc::new(like_utf8_with_options(
+                value,
+                pattern,
+                &self.options,
+            )?)),
+            _ => Err(ExecutionError::General(format!(
+                "{} cannot match values of type {:?} with a pattern of type {:?}",
+                self.name(),
+                value.data_type(),
+                pattern.data_type()
+            ))),
         }
-        let arg = arg
-            .as_any()
-            .downcast_ref::<BooleanArray>()
-            .expect("boolean_op failed to downcast array");
-        return Ok(Arc::new(arrow::compute::kernels::boolean::not(arg)?));
     }
 }
 
-/// Create a unary expression
-pub fn not(arg: Arc<dyn PhysicalExpr>) -> Arc<dyn PhysicalExpr> {
-    Arc::new(NotExpr::new(arg))
-}
-
 /// CAST expression casts an expression to a specific data type
 pub struct CastExpr {
     /// The expression to cast
@@ -1152,16 +3036,7 @@ impl CastExpr {
         cast_type: DataType,
     ) -> Result<Self> {
         let expr_type = expr.data_type(input_schema)?;
-        // numbers can be cast to numbers and strings
-        if is_numeric(&expr_type)
-            && (is_numeric(&cast_type) || cast_type == DataType::Utf8)
-        {
-            Ok(Self { expr, cast_type })
-        } else if expr_type == DataType::Binary && cast_type == DataType::Utf8 {
-            Ok(Self { expr, cast_type })
-        } else if is_numeric(&expr_type)
-            && cast_type == DataType::Timestamp(TimeUnit::Nanosecond, None)
-        {
+        if is_supported_cast(&expr_type, &cast_type) {
             Ok(Self { expr, cast_type })
         } else {
             Err(ExecutionError::General(format!(
@@ -1172,6 +3047,37 @@ impl CastExpr {
     }
 }
 
+/// Whether a CAST expression can cast values of one type to another
+fn is_supported_cast(from_type: &DataType, to_type: &DataType) -> bool {
+    match from_type {
+        // dictionaries are cast by casting their values, whatever their key type
+        DataType::Dictionary(_, ref value_type) => {
+            **value_type == *to_type || is_supported_cast(value_type, to_type)
+        }
+        // numbers can be cast to numbers, strings and timestamps
+        _ if is_numeric(from_type) => match to_type {
+            DataType::Utf8 | DataType::Timestamp(_, _) | DataType::Decimal(_, _) => true,
+            _ => is_numeric(to_type),
+        },
+        // decimals can be cast to numbers, strings and other decimals
+        DataType::Decimal(_, _) => match to_type {
+            DataType::Utf8 | DataType::Decimal(_, _) => true,
+            _ => is_numeric(to_type),
+        },
+        // timestamps can be cast to other units and time zones, keeping the instant
+        DataType::Timestamp(_, _) => match to_type {
+            DataType::Timestamp(_, _) | DataType::Int64 => true,
+            _ => false,
+        },
+        DataType::Binary => *to_type == DataType::Utf8,
+        DataType::Utf8 => match to_type {
+            DataType::Binary | DataType::Decimal(_, _) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl PhysicalExpr for CastExpr {
     fn name(&self) -> String {
         "CAST".to_string()
@@ -1187,15 +3093,28 @@ impl PhysicalExpr for CastExpr {
     }
 }
 
-/// Represents a non-null literal value
+/// Represents a literal value, which is only null if its data type is known
 pub struct Literal {
     value: ScalarValue,
+    /// The data type of a null value
+    null_type: Option<DataType>,
 }
 
 impl Literal {
     /// Create a literal value expression
     pub fn new(value: ScalarValue) -> Self {
-        Self { value }
+        Self {
+            value,
+            null_type: None,
+        }
+    }
+
+    /// Create a null literal of the given data type
+    pub fn new_null(data_type: DataType) -> Self {
+        Self {
+            value: ScalarValue::Null,
+            null_type: Some(data_type),
+        }
     }
 }
 
@@ -1217,11 +3136,20 @@ impl PhysicalExpr for Literal {
     }
 
     fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
-        Ok(self.value.get_datatype())
+        match &self.null_type {
+            Some(data_type) => Ok(data_type.clone()),
+            None => Ok(self.value.get_datatype()),
+        }
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        if let Some(data_type) = &self.null_type {
+            return new_null_array(data_type, batch.num_rows());
+        }
         match &self.value {
+            ScalarValue::Boolean(value) => {
+                build_literal_array!(batch, BooleanBuilder, *value)
+            }
             ScalarValue::Int8(value) => build_literal_array!(batch, Int8Builder, *value),
             ScalarValue::Int16(value) => {
                 build_literal_array!(batch, Int16Builder, *value)
@@ -1251,6 +3179,26 @@ impl PhysicalExpr for Literal {
                 build_literal_array!(batch, Float64Builder, *value)
             }
             ScalarValue::Utf8(value) => build_literal_array!(batch, StringBuilder, value),
+            ScalarValue::Date32(value) => {
+                build_literal_array!(batch, Date32Builder, *value)
+            }
+            ScalarValue::TimestampNanosecond(value) => {
+                build_literal_array!(batch, TimestampNanosecondBuilder, *value)
+            }
+            ScalarValue::IntervalYearMonth(value) => {
+                build_literal_array!(batch, IntervalYearMonthBuilder, *value)
+            }
+            ScalarValue::IntervalDayTime(value) => {
+                build_literal_array!(batch, IntervalDayTimeBuilder, *value)
+            }
+            ScalarValue::Decimal128(value, precision, scale) => {
+                let mut builder =
+                    Decimal128Builder::new(batch.num_rows(), *precision, *scale);
+                for _ in 0..batch.num_rows() {
+                    builder.append_value(*value)?;
+                }
+                Ok(Arc::new(builder.finish()))
+            }
             other => Err(ExecutionError::General(format!(
                 "Unsupported literal type {:?}",
                 other
@@ -1269,7 +3217,10 @@ mod tests {
     use super::*;
     use crate::error::Result;
     use crate::execution::physical_plan::common::get_scalar_value;
-    use arrow::array::{PrimitiveArray, StringArray, Time64NanosecondArray};
+    use arrow::array::{
+        BinaryArray, FixedSizeListBuilder, ListBuilder, PrimitiveArray, StringArray,
+        Time64NanosecondArray,
+    };
     use arrow::datatypes::*;
 
     #[test]
@@ -1358,6 +3309,205 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn is_null_op() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let a = StringArray::from(vec![Some("foo"), None]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+
+        let result = is_null_expr(col(0, &schema)).evaluate(&batch)?;
+        let result = result
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .expect("failed to downcast to BooleanArray");
+        assert_eq!(result.null_count(), 0);
+        assert_eq!(result.value(0), false);
+        assert_eq!(result.value(1), true);
+
+        let result = is_not_null_expr(col(0, &schema)).evaluate(&batch)?;
+        let result = result
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .expect("failed to downcast to BooleanArray");
+        assert_eq!(result.value(0), true);
+        assert_eq!(result.value(1), false);
+
+        Ok(())
+    }
+
+    #[test]
+    fn case_when() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let a = Int32Array::from(vec![Some(1), Some(5), None, Some(10)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+        let utf8 = |s: &str| lit(ScalarValue::Utf8(s.to_string()));
+
+        // CASE WHEN a < 3 THEN 'small' WHEN a < 8 THEN 'medium' END
+        let when_then_expr = vec![
+            (
+                binary(col(0, &schema), Operator::Lt, lit(ScalarValue::Int32(3))),
+                utf8("small"),
+            ),
+            (
+                binary(col(0, &schema), Operator::Lt, lit(ScalarValue::Int32(8))),
+                utf8("medium"),
+            ),
+        ];
+        let case = CaseExpr::try_new(when_then_expr.clone(), None)?;
+        let result = case.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(result.value(0), "small");
+        assert_eq!(result.value(1), "medium");
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+
+        let case = CaseExpr::try_new(when_then_expr, Some(utf8("large")))?;
+        let result = case.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(result.value(1), "medium");
+        // a null condition falls through to the ELSE result
+        assert_eq!(result.value(2), "large");
+        assert_eq!(result.value(3), "large");
+
+        Ok(())
+    }
+
+    #[test]
+    fn in_list() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let a = StringArray::from(vec![Some("a"), Some("d"), None, Some("B")]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+        let utf8 = |s: &str| lit(ScalarValue::Utf8(s.to_string()));
+
+        let expr = InListExpr::new(col(0, &schema), vec![utf8("a"), utf8("b")], false);
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert!(result.value(0));
+        assert!(!result.value(1));
+        assert!(result.is_null(2));
+        assert!(!result.value(3));
+
+        // a value that is not found in a list with nulls may equal a null value
+        let values: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("a"), None, Some("b")]));
+        let expr = InListExpr::new_constant(col(0, &schema), values, true)
+            .with_collation(Collation::CaseInsensitive);
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert!(!result.value(0));
+        assert!(result.is_null(1));
+        assert!(result.is_null(2));
+        assert!(!result.value(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn in_list_hashed() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Float64, false)]);
+        let a = Float64Array::from(vec![-0.0, 2.5, 100.0]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+
+        let values: Vec<f64> =
+            (0..IN_LIST_HASH_THRESHOLD * 2).map(|i| i as f64).collect();
+        let values: ArrayRef = Arc::new(Float64Array::from(values));
+        let expr = InListExpr::new_constant(col(0, &schema), values, false);
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+        // negative zero equals zero
+        assert!(result.value(0));
+        assert!(!result.value(1));
+        assert!(!result.value(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn like() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        let a = StringArray::from(vec![Some("Apache"), Some("10%"), None, Some("a_c")]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+        let utf8 = |s: &str| lit(ScalarValue::Utf8(s.to_string()));
+        let evaluate = |expr: LikeExpr| -> Result<Vec<Option<bool>>> {
+            let result = expr.evaluate(&batch)?;
+            let result = result.as_any().downcast_ref::<BooleanArray>().unwrap();
+            Ok((0..result.len())
+                .map(|i| {
+                    if result.is_null(i) {
+                        None
+                    } else {
+                        Some(result.value(i))
+                    }
+                })
+                .collect())
+        };
+
+        let expr = LikeExpr::new(col(0, &schema), utf8("a%"), false);
+        assert_eq!(
+            evaluate(expr)?,
+            vec![Some(false), Some(false), None, Some(true)]
+        );
+
+        let expr =
+            LikeExpr::new(col(0, &schema), utf8("a%"), true).with_case_insensitive(true);
+        assert_eq!(
+            evaluate(expr)?,
+            vec![Some(false), Some(true), None, Some(false)]
+        );
+
+        let expr =
+            LikeExpr::new(col(0, &schema), utf8("%#%"), false).with_escape(Some('#'));
+        assert_eq!(
+            evaluate(expr)?,
+            vec![Some(false), Some(true), None, Some(false)]
+        );
+
+        // only strings can be matched
+        let expr = LikeExpr::new(lit(ScalarValue::Int32(1)), utf8("1"), false);
+        assert!(expr.evaluate(&batch).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn case_when_is_lazy() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let a = Int32Array::from(vec![1, 2]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+        // comparing a number with a string fails when evaluated
+        let invalid = binary(
+            col(0, &schema),
+            Operator::Eq,
+            lit(ScalarValue::Utf8("x".to_string())),
+        );
+        assert!(invalid.evaluate(&batch).is_err());
+
+        // the second branch is not evaluated since the first one matches every row,
+        // and its result is not evaluated since it never matches
+        let case = CaseExpr::try_new(
+            vec![
+                (
+                    binary(col(0, &schema), Operator::Gt, lit(ScalarValue::Int32(0))),
+                    col(0, &schema),
+                ),
+                (invalid.clone(), col(0, &schema)),
+            ],
+            Some(col(0, &schema)),
+        )?;
+        assert_eq!(case.evaluate(&batch)?.len(), 2);
+
+        let case = CaseExpr::try_new(
+            vec![(
+                binary(col(0, &schema), Operator::Lt, lit(ScalarValue::Int32(0))),
+                invalid,
+            )],
+            None,
+        )?;
+        assert_eq!(case.evaluate(&batch)?.null_count(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn cast_i32_to_u32() -> Result<()> {
         let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -1396,6 +3546,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn cast_binary_to_utf8() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Binary, false)]);
+        let values: Vec<&[u8]> = vec![b"arrow", &[0xc3, 0x28]];
+        let a = BinaryArray::from(values);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(a)])?;
+
+        let cast = CastExpr::try_new(col(0, &schema), &schema, DataType::Utf8)?;
+        let result = cast.evaluate(&batch)?;
+        let result = result
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .expect("failed to downcast to StringArray");
+        assert_eq!(result.value(0), "arrow");
+        // invalid UTF-8 becomes null instead of failing the query
+        assert!(result.is_null(1));
+
+        Ok(())
+    }
+
     #[test]
     fn cast_i64_to_timestamp_nanoseconds() -> Result<()> {
         let schema = Schema::new(vec![Field::new("a", DataType::Int64, false)]);
@@ -1869,6 +4039,117 @@ mod tests {
         accum.get_value()
     }
 
+    #[test]
+    fn max_by_min_by() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("value", DataType::Utf8, true),
+            Field::new("order", DataType::Int32, true),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"])),
+                Arc::new(Int32Array::from(vec![
+                    Some(3),
+                    None,
+                    Some(5),
+                    Some(1),
+                    Some(5),
+                ])),
+            ],
+        )?;
+
+        let max_by = max_by(col(0, &schema), col(1, &schema));
+        assert_eq!(
+            max_by.data_type(&schema)?,
+            DataType::Struct(vec![
+                Field::new("value", DataType::Utf8, true),
+                Field::new("order", DataType::Int32, true),
+            ])
+        );
+        let state = do_min_max_by(&max_by, &batch)?;
+        assert_eq!(
+            state,
+            Some(ScalarValue::Struct(vec![
+                ScalarValue::Utf8("c".to_string()),
+                ScalarValue::Int32(5),
+            ]))
+        );
+
+        // the reducer combines the states of partitions into the final value
+        let reducer = max_by.create_reducer(0);
+        let accum = reducer.create_accumulator();
+        let mut accum = accum.borrow_mut();
+        accum.accumulate_scalar(Some(ScalarValue::Struct(vec![
+            ScalarValue::Utf8("x".to_string()),
+            ScalarValue::Int32(4),
+        ])))?;
+        accum.accumulate_scalar(state)?;
+        accum.accumulate_scalar(None)?;
+        assert_eq!(accum.get_value()?, Some(ScalarValue::Utf8("c".to_string())));
+
+        let min_by = min_by(col(0, &schema), col(1, &schema));
+        assert_eq!(
+            do_min_max_by(&min_by, &batch)?,
+            Some(ScalarValue::Struct(vec![
+                ScalarValue::Utf8("d".to_string()),
+                ScalarValue::Int32(1),
+            ]))
+        );
+
+        Ok(())
+    }
+
+    fn do_min_max_by(
+        aggregate: &Arc<dyn AggregateExpr>,
+        batch: &RecordBatch,
+    ) -> Result<Option<ScalarValue>> {
+        let accum = aggregate.create_accumulator();
+        let input = aggregate.evaluate_input(batch)?;
+        let mut accum = accum.borrow_mut();
+        accum.accumulate_batch(&input)?;
+        accum.get_value()
+    }
+
+    #[test]
+    fn bool_and_or() -> Result<()> {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(true),
+            None,
+            Some(false),
+            Some(true),
+        ]));
+        assert_eq!(
+            do_bool_aggregate(&a, bool_and)?,
+            Some(ScalarValue::Boolean(false))
+        );
+        assert_eq!(
+            do_bool_aggregate(&a, bool_or)?,
+            Some(ScalarValue::Boolean(true))
+        );
+
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![None, None]));
+        assert_eq!(do_bool_aggregate(&a, bool_and)?, None);
+        assert_eq!(do_bool_aggregate(&a, bool_or)?, None);
+
+        Ok(())
+    }
+
+    fn do_bool_aggregate(
+        array: &ArrayRef,
+        aggregate: fn(Arc<dyn PhysicalExpr>) -> Arc<dyn AggregateExpr>,
+    ) -> Result<Option<ScalarValue>> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Boolean, true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![array.clone()])?;
+        let agg = aggregate(col(0, &schema));
+        assert_eq!(agg.data_type(&schema)?, DataType::Boolean);
+        let accum = agg.create_accumulator();
+        let input = agg.evaluate_input(&batch)?;
+        let mut accum = accum.borrow_mut();
+        accum.accumulate_batch(&input)?;
+        accum.get_value()
+    }
+
     #[test]
     fn plus_op() -> Result<()> {
         let schema = Schema::new(vec![
@@ -1962,8 +4243,61 @@ mod tests {
         let arithmetic_op = binary(col(0, schema.as_ref()), op, col(1, schema.as_ref()));
         let batch = RecordBatch::try_new(schema, data)?;
         let result = arithmetic_op.evaluate(&batch)?;
+        let mut ctx = EvaluationContext::new();
+        let result_with_context =
+            arithmetic_op.evaluate_with_context(&batch, &mut ctx)?;
+
+        assert_array_eq::<T>(PrimitiveArray::<T>::from(expected.data()), result);
+        assert_array_eq::<T>(expected, result_with_context);
+
+        Ok(())
+    }
+
+    #[test]
+    fn arithmetic_with_context() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+        // expression: "(a + b) * b"
+        let expr = binary(
+            binary(col(0, &schema), Operator::Plus, col(1, &schema)),
+            Operator::Multiply,
+            col(1, &schema),
+        );
+        let mut ctx = EvaluationContext::new();
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(Int64Array::from(vec![4, 5, 6])),
+            ],
+        )?;
+        let result = expr.evaluate_with_context(&batch, &mut ctx)?;
+        assert_array_eq::<Int64Type>(Int64Array::from(vec![20, 35, 54]), result.clone());
+        let ptr = result.data().buffers()[0].raw_data();
 
-        assert_array_eq::<T>(expected, result);
+        // the output of the first batch is still referenced, but the memory of the
+        // intermediate result can be reused
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![7, 8, 9])),
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+            ],
+        )?;
+        let second = expr.evaluate_with_context(&batch, &mut ctx)?;
+        assert_array_eq::<Int64Type>(Int64Array::from(vec![8, 20, 36]), second.clone());
+        assert_ne!(ptr, second.data().buffers()[0].raw_data());
+        assert_array_eq::<Int64Type>(Int64Array::from(vec![20, 35, 54]), result.clone());
+
+        // once the outputs are dropped, their memory is written to again
+        drop(result);
+        drop(second);
+        let third = expr.evaluate_with_context(&batch, &mut ctx)?;
+        assert_array_eq::<Int64Type>(Int64Array::from(vec![8, 20, 36]), third.clone());
+        assert!(ctx.free_buffer_count() > 0);
 
         Ok(())
     }
@@ -2004,4 +4338,78 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn element_at_list() -> Result<()> {
+        // [[1, 2, 3], NULL, [4], [5, NULL]]
+        let mut builder = ListBuilder::new(Int32Builder::new(8));
+        for value in &[1, 2, 3] {
+            builder.values().append_value(*value)?;
+        }
+        builder.append(true)?;
+        builder.append(false)?;
+        builder.values().append_value(4)?;
+        builder.append(true)?;
+        builder.values().append_value(5)?;
+        builder.values().append_null()?;
+        builder.append(true)?;
+        let list = builder.finish();
+
+        let schema = Schema::new(vec![
+            Field::new("a", list.data_type().clone(), true),
+            Field::new("i", DataType::Int64, true),
+        ]);
+        let index = Int64Array::from(vec![Some(3), Some(1), Some(2), None]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.clone()),
+            vec![Arc::new(list), Arc::new(index)],
+        )?;
+
+        let expr = ElementAtExpr::new(col(0, &schema), col(1, &schema));
+        assert_eq!(expr.data_type(&schema)?, DataType::Int32);
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<Int32Array>().unwrap();
+        // the null list and index and the out of bounds index are null
+        assert_eq!(result.value(0), 3);
+        assert!(result.is_null(1));
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+
+        // the index starts at 1, and the null element is null
+        let expr = ElementAtExpr::new(col(0, &schema), lit(ScalarValue::Int64(2)));
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(result.value(0), 2);
+        assert!(result.is_null(1));
+        assert!(result.is_null(2));
+        assert!(result.is_null(3));
+
+        let expr = ElementAtExpr::new(col(0, &schema), lit(ScalarValue::Int64(0)));
+        assert_eq!(expr.evaluate(&batch)?.null_count(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn element_at_fixed_size_list() -> Result<()> {
+        let mut builder = FixedSizeListBuilder::new(StringBuilder::new(4), 2);
+        for (x, y) in &[("a", "b"), ("c", "d")] {
+            builder.values().append_value(x)?;
+            builder.values().append_value(y)?;
+            builder.append(true)?;
+        }
+        let list = builder.finish();
+        let schema = Schema::new(vec![Field::new("a", list.data_type().clone(), false)]);
+        let batch = RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(list)])?;
+
+        let expr = ElementAtExpr::new(col(0, &schema), lit(ScalarValue::Int64(2)));
+        assert_eq!(expr.data_type(&schema)?, DataType::Utf8);
+        let result = expr.evaluate(&batch)?;
+        let result = result.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(result.value(0), "b");
+        assert_eq!(result.value(1), "d");
+
+        let expr = ElementAtExpr::new(col(0, &schema), lit(ScalarValue::Int64(3)));
+        assert_eq!(expr.evaluate(&batch)?.null_count(), 2);
+        Ok(())
+    }
 }
@@ -0,0 +1,654 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines an external sort, which sorts more rows than fit in memory by writing sorted
+//! runs to spill files and merging them back together. The sort is independent of any
+//! particular execution plan, so that all operators that need their input in order
+//! (sorts, sort-merge joins and aggregations that spill) can share it.
+
+use std::cmp::Ordering;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::memory_manager::{MemoryManager, MemoryReservation};
+use crate::execution::physical_plan::common::{self, JoinHandle, RecordBatchIterator};
+use crate::execution::physical_plan::spill::SpillFile;
+use crate::execution::physical_plan::{BatchIterator, Partition, PhysicalSortExpr};
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::compute::{
+    build_compare, concat, lexsort_to_indices, DynComparator, SortColumn,
+};
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Sorts batches that are inserted one at a time. Batches are buffered in memory until
+/// the memory manager refuses to reserve more, at which point the buffered batches are
+/// sorted and written to a spill file as a sorted run on a separate thread. Once all
+/// batches are inserted, the runs are merged into a single sorted stream.
+pub struct ExternalSorter {
+    schema: Arc<Schema>,
+    /// Sort expressions, in order of precedence
+    expr: Vec<PhysicalSortExpr>,
+    /// The maximum number of rows in the batches written to spill files and produced
+    /// by the merge
+    batch_size: usize,
+    memory_manager: Option<Arc<MemoryManager>>,
+    /// The memory reserved for the buffered batches
+    reservation: Option<MemoryReservation>,
+    /// The batches that have not been spilled
+    batches: Vec<RecordBatch>,
+    /// The threads writing sorted runs to spill files
+    spills: Vec<JoinHandle<Result<SpillFile>>>,
+    /// The sorted runs that have been written
+    runs: Vec<SpillFile>,
+}
+
+impl ExternalSorter {
+    /// Create a new sorter that keeps all batches in memory
+    pub fn new(
+        schema: Arc<Schema>,
+        expr: Vec<PhysicalSortExpr>,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            schema,
+            expr,
+            batch_size,
+            memory_manager: None,
+            reservation: None,
+            batches: vec![],
+            spills: vec![],
+            runs: vec![],
+        }
+    }
+
+    /// Reserve memory for the buffered batches from a memory manager. Once the limit of
+    /// the memory manager is reached, sorted runs are spilled to disk.
+    pub fn with_memory_manager(mut self, memory_manager: Arc<MemoryManager>) -> Self {
+        self.reservation = Some(MemoryReservation::new(memory_manager.clone()));
+        self.memory_manager = Some(memory_manager);
+        self
+    }
+
+    /// Add a batch to be sorted
+    pub fn insert(&mut self, batch: RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let size = batch.get_array_memory_size();
+        if !self.try_grow(size) {
+            self.spill();
+            if !self.try_grow(size) {
+                // other runs may still hold memory while they are written
+                self.wait_for_spills()?;
+                if !self.try_grow(size) {
+                    // the batch does not fit on its own, so it becomes a run by itself
+                    self.batches.push(batch);
+                    self.spill();
+                    return Ok(());
+                }
+            }
+        }
+        self.batches.push(batch);
+        Ok(())
+    }
+
+    /// Get the number of sorted runs that were spilled to disk so far
+    pub fn spill_count(&self) -> usize {
+        self.runs.len() + self.spills.len()
+    }
+
+    /// Sort the inserted batches and return them as a single stream of batches
+    pub fn finish(mut self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        self.wait_for_spills()?;
+
+        let in_memory = if self.batches.is_empty() {
+            None
+        } else {
+            let batch = combine_batches(&self.batches, self.schema.clone())?;
+            self.batches.clear();
+            Some(sort_batch(&batch, &self.expr)?)
+        };
+
+        if self.runs.is_empty() {
+            return Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+                self.schema.clone(),
+                in_memory.into_iter().map(Arc::new).collect(),
+            ))));
+        }
+
+        let mut streams = self
+            .runs
+            .iter()
+            .map(|run| run.read())
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(batch) = in_memory {
+            streams.push(Arc::new(Mutex::new(RecordBatchIterator::new(
+                self.schema.clone(),
+                vec![Arc::new(batch)],
+            ))));
+        }
+
+        let mut merge = SortedMergeIterator::try_new(
+            self.schema.clone(),
+            streams,
+            self.expr.clone(),
+            self.batch_size,
+        )?;
+        // the spill files and the memory of the in-memory run are held until the
+        // merge is dropped
+        merge.runs = mem::replace(&mut self.runs, vec![]);
+        merge.reservation = self.reservation.take();
+        Ok(Arc::new(Mutex::new(merge)))
+    }
+
+    fn try_grow(&mut self, size: usize) -> bool {
+        match self.reservation {
+            Some(ref mut reservation) => reservation.try_grow(size),
+            None => true,
+        }
+    }
+
+    /// Sort the buffered batches and write them to a spill file on a separate thread,
+    /// which releases their memory once the run is written
+    fn spill(&mut self) {
+        if self.batches.is_empty() {
+            return;
+        }
+        let batches = mem::replace(&mut self.batches, vec![]);
+        let reservation = match self.memory_manager {
+            Some(ref memory_manager) => mem::replace(
+                &mut self.reservation,
+                Some(MemoryReservation::new(memory_manager.clone())),
+            ),
+            None => None,
+        };
+        let schema = self.schema.clone();
+        let expr = self.expr.clone();
+        let batch_size = self.batch_size;
+        self.spills.push(common::spawn(move || {
+            let run = write_sorted_run(schema, &batches, &expr, batch_size);
+            drop(reservation);
+            run
+        }));
+    }
+
+    /// Wait for the threads writing sorted runs to finish
+    fn wait_for_spills(&mut self) -> Result<()> {
+        for spill in self.spills.drain(..) {
+            let run = spill.join().expect("Failed to join thread")?;
+            self.runs.push(run);
+        }
+        Ok(())
+    }
+}
+
+/// Sort the batches of a run and write them to a spill file
+fn write_sorted_run(
+    schema: Arc<Schema>,
+    batches: &[RecordBatch],
+    expr: &[PhysicalSortExpr],
+    batch_size: usize,
+) -> Result<SpillFile> {
+    let batch = sort_batch(&combine_batches(batches, schema.clone())?, expr)?;
+    let mut file = SpillFile::new(schema);
+    let mut start = 0;
+    while start < batch.num_rows() {
+        let end = (start + batch_size.max(1)).min(batch.num_rows());
+        file.write(&take_range(&batch, start, end)?)?;
+        start = end;
+    }
+    file.finish()?;
+    Ok(file)
+}
+
+/// Sort the rows of several partitions into a single stream. Each partition is sorted on
+/// its own thread by an external sorter, after which the sorted partitions are merged.
+pub fn sort_partitions(
+    schema: Arc<Schema>,
+    partitions: &[Arc<dyn Partition>],
+    expr: &[PhysicalSortExpr],
+    memory_manager: Option<Arc<MemoryManager>>,
+    batch_size: usize,
+) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+    let threads: Vec<JoinHandle<Result<Arc<Mutex<dyn BatchIterator>>>>> = partitions
+        .iter()
+        .map(|p| {
+            let p = p.clone();
+            let mut sorter =
+                ExternalSorter::new(schema.clone(), expr.to_vec(), batch_size);
+            if let Some(ref memory_manager) = memory_manager {
+                sorter = sorter.with_memory_manager(memory_manager.clone());
+            }
+            common::spawn(move || {
+                let it = p.execute()?;
+                let mut it = it.lock().unwrap();
+                while let Some(batch) = it.next()? {
+                    sorter.insert(batch)?;
+                }
+                sorter.finish()
+            })
+        })
+        .collect();
+
+    let mut streams = vec![];
+    for thread in threads {
+        streams.push(thread.join().expect("Failed to join thread")?);
+    }
+    if streams.len() == 1 {
+        return Ok(streams.remove(0));
+    }
+    merge_sorted_streams(schema, streams, expr, batch_size)
+}
+
+/// Merge streams whose batches are each sorted by the sort expressions into a single
+/// sorted stream. Rows that compare equal are returned in the order of their streams.
+pub fn merge_sorted_streams(
+    schema: Arc<Schema>,
+    streams: Vec<Arc<Mutex<dyn BatchIterator>>>,
+    expr: &[PhysicalSortExpr],
+    batch_size: usize,
+) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+    Ok(Arc::new(Mutex::new(SortedMergeIterator::try_new(
+        schema,
+        streams,
+        expr.to_vec(),
+        batch_size,
+    )?)))
+}
+
+/// Concatenate the columns of several batches into a single batch
+pub fn combine_batches(
+    batches: &[RecordBatch],
+    schema: Arc<Schema>,
+) -> Result<RecordBatch> {
+    let columns = (0..schema.fields().len())
+        .map(|i| {
+            let arrays: Vec<ArrayRef> =
+                batches.iter().map(|b| b.column(i).clone()).collect();
+            Ok(concat(&arrays)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Sort the rows of a batch by evaluating the sort expressions against it
+pub fn sort_batch(batch: &RecordBatch, expr: &[PhysicalSortExpr]) -> Result<RecordBatch> {
+    let sort_columns = expr
+        .iter()
+        .map(|e| {
+            Ok(SortColumn {
+                values: e.expr.evaluate(batch)?,
+                options: Some(e.options),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let indices = lexsort_to_indices(&sort_columns)?;
+    Ok(RecordBatch::try_new(
+        batch.schema().clone(),
+        common::take_columns(batch, &indices)?,
+    )?)
+}
+
+/// Take the rows in the range `start..end` from a batch
+fn take_range(batch: &RecordBatch, start: usize, end: usize) -> Result<RecordBatch> {
+    let indices = UInt32Array::from((start as u32..end as u32).collect::<Vec<_>>());
+    Ok(RecordBatch::try_new(
+        batch.schema().clone(),
+        common::take_columns(batch, &indices)?,
+    )?)
+}
+
+/// The position of a merge within one of its input streams
+struct Cursor {
+    stream: Arc<Mutex<dyn BatchIterator>>,
+    /// The current batch, or `None` once the stream is exhausted
+    batch: Option<RecordBatch>,
+    /// The sort keys evaluated against the current batch
+    keys: Vec<ArrayRef>,
+    /// The next row of the current batch to be merged
+    row: usize,
+}
+
+impl Cursor {
+    /// Move to the next non-empty batch of the stream
+    fn next_batch(&mut self, expr: &[PhysicalSortExpr]) -> Result<()> {
+        self.row = 0;
+        self.keys.clear();
+        loop {
+            self.batch = self.stream.lock().unwrap().next()?;
+            match self.batch {
+                Some(ref batch) if batch.num_rows() == 0 => continue,
+                Some(ref batch) => {
+                    self.keys = expr
+                        .iter()
+                        .map(|e| e.expr.evaluate(batch))
+                        .collect::<Result<_>>()?;
+                    return Ok(());
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Iterator that performs a k-way merge of sorted streams
+struct SortedMergeIterator {
+    schema: Arc<Schema>,
+    expr: Vec<PhysicalSortExpr>,
+    batch_size: usize,
+    cursors: Vec<Cursor>,
+    /// Spill files read by the cursors, which are deleted when the merge is dropped
+    runs: Vec<SpillFile>,
+    /// Memory reserved for batches that are merged from memory
+    reservation: Option<MemoryReservation>,
+}
+
+impl SortedMergeIterator {
+    fn try_new(
+        schema: Arc<Schema>,
+        streams: Vec<Arc<Mutex<dyn BatchIterator>>>,
+        expr: Vec<PhysicalSortExpr>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let mut cursors = Vec::with_capacity(streams.len());
+        for stream in streams {
+            let mut cursor = Cursor {
+                stream,
+                batch: None,
+                keys: vec![],
+                row: 0,
+            };
+            cursor.next_batch(&expr)?;
+            cursors.push(cursor);
+        }
+        Ok(Self {
+            schema,
+            expr,
+            batch_size: batch_size.max(1),
+            cursors,
+            runs: vec![],
+            reservation: None,
+        })
+    }
+
+    /// Determine the cursors that the next rows are taken from, up to `limit` rows or
+    /// until the current batch of a cursor is exhausted
+    fn merge_current_batches(&self, limit: usize) -> Result<Vec<usize>> {
+        let active: Vec<usize> = (0..self.cursors.len())
+            .filter(|i| self.cursors[*i].batch.is_some())
+            .collect();
+
+        // comparators between the current batches of each pair of cursors i < j
+        let mut comparators: Vec<Vec<Vec<DynComparator>>> =
+            (0..self.cursors.len()).map(|_| vec![]).collect();
+        for (n, i) in active.iter().enumerate() {
+            let mut row = (0..self.cursors.len()).map(|_| vec![]).collect::<Vec<_>>();
+            for j in &active[n + 1..] {
+                row[*j] = self
+                    .expr
+                    .iter()
+                    .enumerate()
+                    .map(|(k, e)| {
+                        Ok(build_compare(
+                            self.cursors[*i].keys[k].as_ref(),
+                            self.cursors[*j].keys[k].as_ref(),
+                            e.options,
+                        )?)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+            }
+            comparators[*i] = row;
+        }
+        let compare = |i: usize, a: usize, j: usize, b: usize| -> Ordering {
+            let (ordering, comparators) = if i < j {
+                (Ordering::Less, &comparators[i][j])
+            } else {
+                (Ordering::Greater, &comparators[j][i])
+            };
+            for comparator in comparators {
+                let result = if i < j {
+                    comparator(a, b)
+                } else {
+                    comparator(b, a).reverse()
+                };
+                if result != Ordering::Equal {
+                    return result;
+                }
+            }
+            // rows that compare equal are taken from the earlier stream first
+            ordering
+        };
+
+        let mut rows: Vec<usize> = self.cursors.iter().map(|c| c.row).collect();
+        let mut order = vec![];
+        while !active.is_empty() && order.len() < limit {
+            let mut best = active[0];
+            for i in &active[1..] {
+                if compare(*i, rows[*i], best, rows[best]) == Ordering::Less {
+                    best = *i;
+                }
+            }
+            order.push(best);
+            rows[best] += 1;
+            if let Some(ref batch) = self.cursors[best].batch {
+                if rows[best] == batch.num_rows() {
+                    break;
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+impl BatchIterator for SortedMergeIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let mut pieces = vec![];
+        let mut num_rows = 0;
+        while num_rows < self.batch_size {
+            let order = self.merge_current_batches(self.batch_size - num_rows)?;
+            if order.is_empty() {
+                break;
+            }
+            num_rows += order.len();
+
+            // consecutive rows from the same cursor are taken as a single piece
+            let mut start = 0;
+            while start < order.len() {
+                let cursor = &mut self.cursors[order[start]];
+                let mut end = start + 1;
+                while end < order.len() && order[end] == order[start] {
+                    end += 1;
+                }
+                if let Some(ref batch) = cursor.batch {
+                    pieces.push(take_range(batch, cursor.row, cursor.row + end - start)?);
+                }
+                cursor.row += end - start;
+                start = end;
+            }
+
+            for cursor in &mut self.cursors {
+                let exhausted = match cursor.batch {
+                    Some(ref batch) => cursor.row == batch.num_rows(),
+                    None => false,
+                };
+                if exhausted {
+                    cursor.next_batch(&self.expr)?;
+                }
+            }
+        }
+
+        match pieces.len() {
+            0 => Ok(None),
+            1 => Ok(pieces.pop()),
+            _ => Ok(Some(combine_batches(&pieces, self.schema.clone())?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::expressions::col;
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field};
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]))
+    }
+
+    fn batch(a: Vec<Option<i32>>, b: Vec<&str>) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            schema(),
+            vec![
+                Arc::new(Int32Array::from(a)),
+                Arc::new(StringArray::from(b)),
+            ],
+        )?)
+    }
+
+    fn sort_expr(descending: bool) -> Vec<PhysicalSortExpr> {
+        vec![PhysicalSortExpr {
+            expr: col(0, schema().as_ref()),
+            options: SortOptions {
+                descending,
+                nulls_first: true,
+                ..SortOptions::default()
+            },
+        }]
+    }
+
+    fn values(batches: &[RecordBatch]) -> Vec<(Option<i32>, String)> {
+        let mut values = vec![];
+        for batch in batches {
+            let a = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let b = batch
+                .column(1)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            for i in 0..batch.num_rows() {
+                let value = if a.is_null(i) { None } else { Some(a.value(i)) };
+                values.push((value, b.value(i).to_string()));
+            }
+        }
+        values
+    }
+
+    fn stream(batches: Vec<RecordBatch>) -> Arc<Mutex<dyn BatchIterator>> {
+        Arc::new(Mutex::new(RecordBatchIterator::new(
+            schema(),
+            batches.into_iter().map(Arc::new).collect(),
+        )))
+    }
+
+    #[test]
+    fn merge_streams() -> Result<()> {
+        let first = stream(vec![
+            batch(vec![None, Some(3)], vec!["w", "x"])?,
+            batch(vec![Some(3), Some(7)], vec!["y", "z"])?,
+        ]);
+        let second = stream(vec![
+            batch(vec![Some(1), Some(3)], vec!["p", "q"])?,
+            batch(vec![], vec![])?,
+            batch(vec![Some(8)], vec!["r"])?,
+        ]);
+
+        let merged =
+            merge_sorted_streams(schema(), vec![first, second], &sort_expr(false), 4)?;
+        let batches = common::collect(merged)?;
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+
+        let expected = vec![
+            (None, "w"),
+            (Some(1), "p"),
+            (Some(3), "x"),
+            (Some(3), "y"),
+            (Some(3), "q"),
+            (Some(7), "z"),
+            (Some(8), "r"),
+        ];
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(a, b)| (a, b.to_string()))
+            .collect();
+        assert_eq!(values(&batches), expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_with_spills() -> Result<()> {
+        // a tiny memory limit forces every batch to be spilled as its own run
+        let memory_manager = Arc::new(MemoryManager::new(1));
+        let mut sorter = ExternalSorter::new(schema(), sort_expr(true), 2)
+            .with_memory_manager(memory_manager.clone());
+        sorter.insert(batch(vec![Some(2), None, Some(5)], vec!["a", "b", "c"])?)?;
+        sorter.insert(batch(vec![Some(4), Some(2)], vec!["d", "e"])?)?;
+        sorter.insert(batch(vec![Some(9)], vec!["f"])?)?;
+        assert_eq!(sorter.spill_count(), 3);
+
+        let batches = common::collect(sorter.finish()?)?;
+        assert!(batches.iter().all(|b| b.num_rows() <= 2));
+        let expected = vec![
+            (None, "b"),
+            (Some(9), "f"),
+            (Some(5), "c"),
+            (Some(4), "d"),
+            (Some(2), "a"),
+            (Some(2), "e"),
+        ];
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(a, b)| (a, b.to_string()))
+            .collect();
+        assert_eq!(values(&batches), expected);
+        assert_eq!(memory_manager.used(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_in_memory() -> Result<()> {
+        let mut sorter = ExternalSorter::new(schema(), sort_expr(false), 2);
+        sorter.insert(batch(vec![Some(2), Some(1)], vec!["a", "b"])?)?;
+        sorter.insert(batch(vec![Some(0)], vec!["c"])?)?;
+        assert_eq!(sorter.spill_count(), 0);
+
+        // without spills the sorted rows are returned as a single batch
+        let batches = common::collect(sorter.finish()?)?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 3);
+        Ok(())
+    }
+}
@@ -27,17 +27,28 @@ use crate::execution::physical_plan::{
 };
 
 use arrow::array::{
-    ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
-    StringArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    ArrayRef, BooleanArray, Date32Array, Date64Array, DictionaryArray, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, StringArray,
+    Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray,
+    Time64NanosecondArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, TimestampSecondArray, UInt16Array, UInt32Array,
+    UInt64Array, UInt8Array,
 };
 use arrow::array::{
-    Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder,
-    Int8Builder, StringBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
-    UInt8Builder,
+    BooleanBuilder, Date32Builder, Date64Builder, Float32Builder, Float64Builder,
+    Int16Builder, Int32Builder, Int64Builder, Int8Builder, StringBuilder,
+    StringDictionaryBuilder, Time32MillisecondBuilder, Time32SecondBuilder,
+    Time64MicrosecondBuilder, Time64NanosecondBuilder, UInt16Builder, UInt32Builder,
+    UInt64Builder, UInt8Builder,
+};
+use arrow::compute::Collation;
+use arrow::datatypes::{
+    DataType, DateUnit, Field, Int16Type, Int32Type, Int64Type, Int8Type, Schema,
+    TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
 };
-use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 
+use crate::execution::physical_plan::common::{get_scalar_value, scalars_to_array};
 use crate::execution::physical_plan::expressions::Column;
 use crate::logicalplan::ScalarValue;
 use fnv::FnvHashMap;
@@ -48,6 +59,7 @@ pub struct HashAggregateExec {
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     input: Arc<dyn ExecutionPlan>,
     schema: Arc<Schema>,
+    collation: Collation,
 }
 
 impl HashAggregateExec {
@@ -75,9 +87,17 @@ impl HashAggregateExec {
             aggr_expr,
             input,
             schema,
+            collation: Collation::Binary,
         })
     }
 
+    /// Group strings that are equal under the given collation. Each group is output
+    /// with the collation's sort key of its strings.
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
     /// Create the final group and aggregate expressions from the initial group and aggregate
     /// expressions
     pub fn make_final_expr(
@@ -106,6 +126,10 @@ impl ExecutionPlan for HashAggregateExec {
         self.schema.clone()
     }
 
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         Ok(self
             .input
@@ -118,6 +142,7 @@ impl ExecutionPlan for HashAggregateExec {
                         self.aggr_expr.clone(),
                         p.clone() as Arc<dyn Partition>,
                         self.schema.clone(),
+                        self.collation,
                     ));
 
                 aggregate
@@ -131,6 +156,7 @@ struct HashAggregatePartition {
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     input: Arc<dyn Partition>,
     schema: Arc<Schema>,
+    collation: Collation,
 }
 
 impl HashAggregatePartition {
@@ -140,12 +166,14 @@ impl HashAggregatePartition {
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: Arc<dyn Partition>,
         schema: Arc<Schema>,
+        collation: Collation,
     ) -> Self {
         HashAggregatePartition {
             group_expr,
             aggr_expr,
             input,
             schema,
+            collation,
         }
     }
 }
@@ -164,6 +192,7 @@ impl Partition for HashAggregatePartition {
                 self.group_expr.clone(),
                 self.aggr_expr.clone(),
                 self.input.execute()?,
+                self.collation,
             ))))
         }
     }
@@ -177,6 +206,7 @@ macro_rules! group_array_from_map_entries {
         for k in $MAP.keys() {
             match k[$COL_INDEX] {
                 GroupByScalar::$TY(n) => builder.append_value(n).unwrap(),
+                GroupByScalar::Null => builder.append_null().unwrap(),
                 _ => err = true,
             }
         }
@@ -191,6 +221,92 @@ macro_rules! group_array_from_map_entries {
     }};
 }
 
+/// Create a floating point array from `key` attribute in map entry, where the values are
+/// stored by their bits
+macro_rules! float_group_array_from_map_entries {
+    ($BUILDER:ident, $TY:ident, $NATIVE:ident, $MAP:expr, $COL_INDEX:expr) => {{
+        let mut builder = $BUILDER::new($MAP.len());
+        for k in $MAP.keys() {
+            match k[$COL_INDEX] {
+                GroupByScalar::$TY(bits) => {
+                    builder.append_value($NATIVE::from_bits(bits))?
+                }
+                GroupByScalar::Null => builder.append_null()?,
+                _ => {
+                    return Err(ExecutionError::ExecutionError(
+                        "unexpected type when creating grouping array from aggregate map"
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()) as ArrayRef)
+    }};
+}
+
+/// Create a dictionary array of strings from `key` attribute in map entry, for a grouping
+/// expression whose dictionary values are strings
+macro_rules! string_dictionary_group_array_from_map_entries {
+    ($KEY_TYPE:ident, $MAP:expr, $COL_INDEX:expr) => {{
+        let mut builder = StringDictionaryBuilder::<$KEY_TYPE>::with_capacity(
+            $MAP.len(),
+            $MAP.len(),
+            1024,
+        );
+        for k in $MAP.keys() {
+            match &k[$COL_INDEX] {
+                GroupByScalar::Utf8(s) => {
+                    builder.append(s)?;
+                }
+                GroupByScalar::Null => builder.append_null()?,
+                _ => {
+                    return Err(ExecutionError::ExecutionError(
+                        "Unexpected value for dictionary group column".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(Arc::new(builder.finish()) as ArrayRef)
+    }};
+}
+
+/// The values of a dictionary array and the index of the value of a non-null row
+macro_rules! dictionary_value_index {
+    ($ARRAY:expr, $KEY_TYPE:ident, $ROW:expr) => {{
+        let array = $ARRAY
+            .as_any()
+            .downcast_ref::<DictionaryArray<$KEY_TYPE>>()
+            .unwrap();
+        match array.keys().nth($ROW) {
+            Some(Some(key)) => (array.values(), key as usize),
+            _ => {
+                return Err(ExecutionError::InternalError(
+                    "Expected a valid dictionary key".to_string(),
+                ))
+            }
+        }
+    }};
+}
+
+/// Create a timestamp array with the time zone of the grouping expression from `key`
+/// attribute in map entry
+macro_rules! timestamp_group_array_from_map_entries {
+    ($ARRAY:ident, $TY:ident, $MAP:expr, $COL_INDEX:expr, $TZ:expr) => {{
+        let values = $MAP
+            .keys()
+            .map(|k| match k[$COL_INDEX] {
+                GroupByScalar::$TY(n) => Ok(Some(n)),
+                GroupByScalar::Null => Ok(None),
+                _ => Err(ExecutionError::ExecutionError(
+                    "unexpected type when creating grouping array from aggregate map"
+                        .to_string(),
+                )),
+            })
+            .collect::<Result<Vec<Option<i64>>>>()?;
+        Ok(Arc::new($ARRAY::from_opt_vec(values, $TZ.clone())) as ArrayRef)
+    }};
+}
+
 /// Create array from `value` attribute in map entry (representing an aggregate scalar
 /// value)
 macro_rules! aggr_array_from_map_entries {
@@ -247,6 +363,7 @@ struct GroupedHashAggregateIterator {
     group_expr: Vec<Arc<dyn PhysicalExpr>>,
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     input: Arc<Mutex<dyn BatchIterator>>,
+    collation: Collation,
     finished: bool,
 }
 
@@ -257,12 +374,14 @@ impl GroupedHashAggregateIterator {
         group_expr: Vec<Arc<dyn PhysicalExpr>>,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: Arc<Mutex<dyn BatchIterator>>,
+        collation: Collation,
     ) -> Self {
         GroupedHashAggregateIterator {
             schema,
             group_expr,
             aggr_expr,
             input,
+            collation,
             finished: false,
         }
     }
@@ -332,6 +451,13 @@ impl BatchIterator for GroupedHashAggregateIterator {
             for row in 0..batch.num_rows() {
                 // create grouping key for this row
                 create_key(&group_values, row, &mut key)?;
+                if self.collation != Collation::Binary {
+                    for k in key.iter_mut() {
+                        if let GroupByScalar::Utf8(s) = k {
+                            *s = self.collation.sort_key(s).into_owned();
+                        }
+                    }
+                }
 
                 if let Some(accumulator_set) = map.get(&key) {
                     accumulators.push(accumulator_set.clone());
@@ -425,6 +551,22 @@ impl BatchIterator for GroupedHashAggregateIterator {
                         col,
                         accumulators
                     ),
+                    DataType::Boolean => update_accumulators!(
+                        array,
+                        BooleanArray,
+                        ScalarValue::Boolean,
+                        col,
+                        accumulators
+                    ),
+                    DataType::Utf8 | DataType::Struct(_) | DataType::Decimal(_, _) => {
+                        for row in 0..array.len() {
+                            if array.is_valid(row) {
+                                let value = get_scalar_value(array, row)?;
+                                let mut accum = accumulators[row][col].borrow_mut();
+                                accum.accumulate_scalar(value)?;
+                            }
+                        }
+                    }
                     other => {
                         return Err(ExecutionError::ExecutionError(format!(
                             "Unsupported data type {:?} for result of aggregate expression",
@@ -470,11 +612,153 @@ impl BatchIterator for GroupedHashAggregateIterator {
                 DataType::Int64 => {
                     group_array_from_map_entries!(Int64Builder, Int64, map, i)
                 }
+                DataType::Boolean => {
+                    group_array_from_map_entries!(BooleanBuilder, Boolean, map, i)
+                }
+                DataType::Date32(DateUnit::Day) => {
+                    group_array_from_map_entries!(Date32Builder, Date32, map, i)
+                }
+                DataType::Date64(DateUnit::Millisecond) => {
+                    group_array_from_map_entries!(Date64Builder, Date64, map, i)
+                }
+                DataType::Time32(TimeUnit::Second) => {
+                    group_array_from_map_entries!(
+                        Time32SecondBuilder,
+                        Time32Second,
+                        map,
+                        i
+                    )
+                }
+                DataType::Time32(TimeUnit::Millisecond) => {
+                    group_array_from_map_entries!(
+                        Time32MillisecondBuilder,
+                        Time32Millisecond,
+                        map,
+                        i
+                    )
+                }
+                DataType::Time64(TimeUnit::Microsecond) => {
+                    group_array_from_map_entries!(
+                        Time64MicrosecondBuilder,
+                        Time64Microsecond,
+                        map,
+                        i
+                    )
+                }
+                DataType::Time64(TimeUnit::Nanosecond) => {
+                    group_array_from_map_entries!(
+                        Time64NanosecondBuilder,
+                        Time64Nanosecond,
+                        map,
+                        i
+                    )
+                }
+                DataType::Float32 => float_group_array_from_map_entries!(
+                    Float32Builder,
+                    Float32,
+                    f32,
+                    map,
+                    i
+                ),
+                DataType::Float64 => float_group_array_from_map_entries!(
+                    Float64Builder,
+                    Float64,
+                    f64,
+                    map,
+                    i
+                ),
+                DataType::Timestamp(TimeUnit::Second, ref tz) => {
+                    timestamp_group_array_from_map_entries!(
+                        TimestampSecondArray,
+                        TimestampSecond,
+                        map,
+                        i,
+                        tz
+                    )
+                }
+                DataType::Timestamp(TimeUnit::Millisecond, ref tz) => {
+                    timestamp_group_array_from_map_entries!(
+                        TimestampMillisecondArray,
+                        TimestampMillisecond,
+                        map,
+                        i,
+                        tz
+                    )
+                }
+                DataType::Timestamp(TimeUnit::Microsecond, ref tz) => {
+                    timestamp_group_array_from_map_entries!(
+                        TimestampMicrosecondArray,
+                        TimestampMicrosecond,
+                        map,
+                        i,
+                        tz
+                    )
+                }
+                DataType::Timestamp(TimeUnit::Nanosecond, ref tz) => {
+                    timestamp_group_array_from_map_entries!(
+                        TimestampNanosecondArray,
+                        TimestampNanosecond,
+                        map,
+                        i,
+                        tz
+                    )
+                }
+                DataType::Dictionary(ref key_type, ref value_type)
+                    if **value_type == DataType::Utf8 =>
+                {
+                    match **key_type {
+                        DataType::Int8 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                Int8Type, map, i
+                            )
+                        }
+                        DataType::Int16 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                Int16Type, map, i
+                            )
+                        }
+                        DataType::Int32 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                Int32Type, map, i
+                            )
+                        }
+                        DataType::Int64 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                Int64Type, map, i
+                            )
+                        }
+                        DataType::UInt8 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                UInt8Type, map, i
+                            )
+                        }
+                        DataType::UInt16 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                UInt16Type, map, i
+                            )
+                        }
+                        DataType::UInt32 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                UInt32Type, map, i
+                            )
+                        }
+                        DataType::UInt64 => {
+                            string_dictionary_group_array_from_map_entries!(
+                                UInt64Type, map, i
+                            )
+                        }
+                        _ => Err(ExecutionError::ExecutionError(
+                            "Unsupported dictionary key type of group by expr"
+                                .to_string(),
+                        )),
+                    }
+                }
                 DataType::Utf8 => {
                     let mut builder = StringBuilder::new(1);
                     for k in map.keys() {
                         match &k[i] {
                             GroupByScalar::Utf8(s) => builder.append_value(&s).unwrap(),
+                            GroupByScalar::Null => builder.append_null().unwrap(),
                             _ => {
                                 return Err(ExecutionError::ExecutionError(
                                     "Unexpected value for Utf8 group column".to_string(),
@@ -524,6 +808,22 @@ impl BatchIterator for GroupedHashAggregateIterator {
                     DataType::Float64 => {
                         aggr_array_from_map_entries!(Float64Builder, Float64, f64, map, i)
                     }
+                    DataType::Boolean => {
+                        aggr_array_from_map_entries!(
+                            BooleanBuilder,
+                            Boolean,
+                            bool,
+                            map,
+                            i
+                        )
+                    }
+                    DataType::Utf8 | DataType::Struct(_) | DataType::Decimal(_, _) => {
+                        let values = map
+                            .values()
+                            .map(|v| v[i].as_ref().borrow().get_value())
+                            .collect::<Result<Vec<_>>>()?;
+                        scalars_to_array(&aggr_data_type, &values)
+                    }
                     _ => Err(ExecutionError::ExecutionError(
                         "Unsupported aggregate expr".to_string(),
                     )),
@@ -638,6 +938,12 @@ impl BatchIterator for HashAggregateIterator {
                 DataType::Float64 => {
                     aggr_array_from_accumulator!(Float64Builder, Float64, f64, value)
                 }
+                DataType::Boolean => {
+                    aggr_array_from_accumulator!(BooleanBuilder, Boolean, bool, value)
+                }
+                DataType::Utf8 | DataType::Struct(_) | DataType::Decimal(_, _) => {
+                    scalars_to_array(&aggr_data_type, &[value])
+                }
                 _ => Err(ExecutionError::ExecutionError(
                     "Unsupported aggregate expr".to_string(),
                 )),
@@ -650,76 +956,236 @@ impl BatchIterator for HashAggregateIterator {
     }
 }
 
-/// Enumeration of types that can be used in a GROUP BY expression (all primitives except
-/// for floating point numerics)
+/// Enumeration of types that can be used in a GROUP BY expression or as a join key.
+/// Floating point numbers are stored by their bits, with all zeros and all NaNs
+/// normalized to a single representation so that they form one group each.
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-enum GroupByScalar {
+pub enum GroupByScalar {
+    /// unsigned 8bit int
     UInt8(u8),
+    /// unsigned 16bit int
     UInt16(u16),
+    /// unsigned 32bit int
     UInt32(u32),
+    /// unsigned 64bit int
     UInt64(u64),
+    /// signed 8bit int
     Int8(i8),
+    /// signed 16bit int
     Int16(i16),
+    /// signed 32bit int
     Int32(i32),
+    /// signed 64bit int
     Int64(i64),
+    /// bits of a 32bit floating point number
+    Float32(u32),
+    /// bits of a 64bit floating point number
+    Float64(u64),
+    /// utf-8 encoded string
     Utf8(String),
+    /// true or false
+    Boolean(bool),
+    /// days since the UNIX epoch
+    Date32(i32),
+    /// milliseconds since the UNIX epoch
+    Date64(i64),
+    /// seconds since midnight
+    Time32Second(i32),
+    /// milliseconds since midnight
+    Time32Millisecond(i32),
+    /// microseconds since midnight
+    Time64Microsecond(i64),
+    /// nanoseconds since midnight
+    Time64Nanosecond(i64),
+    /// seconds since the UNIX epoch
+    TimestampSecond(i64),
+    /// milliseconds since the UNIX epoch
+    TimestampMillisecond(i64),
+    /// microseconds since the UNIX epoch
+    TimestampMicrosecond(i64),
+    /// nanoseconds since the UNIX epoch
+    TimestampNanosecond(i64),
+    /// null value of any type, which forms a group of its own
+    Null,
+}
+
+/// The bits of a 32bit floating point number, with -0.0 mapped to 0.0 and all NaNs to a
+/// single NaN
+fn f32_key(value: f32) -> u32 {
+    if value.is_nan() {
+        std::f32::NAN.to_bits()
+    } else if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
 }
 
-/// Create a Vec<GroupByScalar> that can be used as a map key
-fn create_key(
+/// The bits of a 64bit floating point number, with -0.0 mapped to 0.0 and all NaNs to a
+/// single NaN
+fn f64_key(value: f64) -> u64 {
+    if value.is_nan() {
+        std::f64::NAN.to_bits()
+    } else if value == 0.0 {
+        0
+    } else {
+        value.to_bits()
+    }
+}
+
+/// Create a Vec<GroupByScalar> that can be used as a map key, where null values of any
+/// type are represented by `GroupByScalar::Null`
+pub fn create_key(
     group_by_keys: &Vec<ArrayRef>,
     row: usize,
     vec: &mut Vec<GroupByScalar>,
 ) -> Result<()> {
     for i in 0..group_by_keys.len() {
-        let col = &group_by_keys[i];
-        match col.data_type() {
-            DataType::UInt8 => {
-                let array = col.as_any().downcast_ref::<UInt8Array>().unwrap();
-                vec[i] = GroupByScalar::UInt8(array.value(row))
-            }
-            DataType::UInt16 => {
-                let array = col.as_any().downcast_ref::<UInt16Array>().unwrap();
-                vec[i] = GroupByScalar::UInt16(array.value(row))
-            }
-            DataType::UInt32 => {
-                let array = col.as_any().downcast_ref::<UInt32Array>().unwrap();
-                vec[i] = GroupByScalar::UInt32(array.value(row))
-            }
-            DataType::UInt64 => {
-                let array = col.as_any().downcast_ref::<UInt64Array>().unwrap();
-                vec[i] = GroupByScalar::UInt64(array.value(row))
-            }
-            DataType::Int8 => {
-                let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
-                vec[i] = GroupByScalar::Int8(array.value(row))
-            }
-            DataType::Int16 => {
-                let array = col.as_any().downcast_ref::<Int16Array>().unwrap();
-                vec[i] = GroupByScalar::Int16(array.value(row))
-            }
-            DataType::Int32 => {
-                let array = col.as_any().downcast_ref::<Int32Array>().unwrap();
-                vec[i] = GroupByScalar::Int32(array.value(row))
-            }
-            DataType::Int64 => {
-                let array = col.as_any().downcast_ref::<Int64Array>().unwrap();
-                vec[i] = GroupByScalar::Int64(array.value(row))
-            }
-            DataType::Utf8 => {
-                let array = col.as_any().downcast_ref::<StringArray>().unwrap();
-                vec[i] = GroupByScalar::Utf8(String::from(array.value(row)))
-            }
-            _ => {
-                return Err(ExecutionError::ExecutionError(
-                    "Unsupported GROUP BY data type".to_string(),
-                ))
-            }
-        }
+        vec[i] = create_scalar(&group_by_keys[i], row)?;
     }
     Ok(())
 }
 
+/// Create the GroupByScalar of a row of an array. Rows of dictionary arrays are
+/// represented by their values, so that they group and join with arrays of the value
+/// type and with arrays that use a different dictionary.
+fn create_scalar(col: &ArrayRef, row: usize) -> Result<GroupByScalar> {
+    if col.is_null(row) {
+        return Ok(GroupByScalar::Null);
+    }
+    Ok(match col.data_type() {
+        DataType::UInt8 => {
+            let array = col.as_any().downcast_ref::<UInt8Array>().unwrap();
+            GroupByScalar::UInt8(array.value(row))
+        }
+        DataType::UInt16 => {
+            let array = col.as_any().downcast_ref::<UInt16Array>().unwrap();
+            GroupByScalar::UInt16(array.value(row))
+        }
+        DataType::UInt32 => {
+            let array = col.as_any().downcast_ref::<UInt32Array>().unwrap();
+            GroupByScalar::UInt32(array.value(row))
+        }
+        DataType::UInt64 => {
+            let array = col.as_any().downcast_ref::<UInt64Array>().unwrap();
+            GroupByScalar::UInt64(array.value(row))
+        }
+        DataType::Int8 => {
+            let array = col.as_any().downcast_ref::<Int8Array>().unwrap();
+            GroupByScalar::Int8(array.value(row))
+        }
+        DataType::Int16 => {
+            let array = col.as_any().downcast_ref::<Int16Array>().unwrap();
+            GroupByScalar::Int16(array.value(row))
+        }
+        DataType::Int32 => {
+            let array = col.as_any().downcast_ref::<Int32Array>().unwrap();
+            GroupByScalar::Int32(array.value(row))
+        }
+        DataType::Int64 => {
+            let array = col.as_any().downcast_ref::<Int64Array>().unwrap();
+            GroupByScalar::Int64(array.value(row))
+        }
+        DataType::Float32 => {
+            let array = col.as_any().downcast_ref::<Float32Array>().unwrap();
+            GroupByScalar::Float32(f32_key(array.value(row)))
+        }
+        DataType::Float64 => {
+            let array = col.as_any().downcast_ref::<Float64Array>().unwrap();
+            GroupByScalar::Float64(f64_key(array.value(row)))
+        }
+        DataType::Utf8 => {
+            let array = col.as_any().downcast_ref::<StringArray>().unwrap();
+            GroupByScalar::Utf8(String::from(array.value(row)))
+        }
+        DataType::Boolean => {
+            let array = col.as_any().downcast_ref::<BooleanArray>().unwrap();
+            GroupByScalar::Boolean(array.value(row))
+        }
+        DataType::Date32(DateUnit::Day) => {
+            let array = col.as_any().downcast_ref::<Date32Array>().unwrap();
+            GroupByScalar::Date32(array.value(row))
+        }
+        DataType::Date64(DateUnit::Millisecond) => {
+            let array = col.as_any().downcast_ref::<Date64Array>().unwrap();
+            GroupByScalar::Date64(array.value(row))
+        }
+        DataType::Time32(TimeUnit::Second) => {
+            let array = col.as_any().downcast_ref::<Time32SecondArray>().unwrap();
+            GroupByScalar::Time32Second(array.value(row))
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<Time32MillisecondArray>()
+                .unwrap();
+            GroupByScalar::Time32Millisecond(array.value(row))
+        }
+        DataType::Time64(TimeUnit::Microsecond) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<Time64MicrosecondArray>()
+                .unwrap();
+            GroupByScalar::Time64Microsecond(array.value(row))
+        }
+        DataType::Time64(TimeUnit::Nanosecond) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<Time64NanosecondArray>()
+                .unwrap();
+            GroupByScalar::Time64Nanosecond(array.value(row))
+        }
+        DataType::Timestamp(TimeUnit::Second, _) => {
+            let array = col.as_any().downcast_ref::<TimestampSecondArray>().unwrap();
+            GroupByScalar::TimestampSecond(array.value(row))
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, _) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .unwrap();
+            GroupByScalar::TimestampMillisecond(array.value(row))
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, _) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<TimestampMicrosecondArray>()
+                .unwrap();
+            GroupByScalar::TimestampMicrosecond(array.value(row))
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            let array = col
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .unwrap();
+            GroupByScalar::TimestampNanosecond(array.value(row))
+        }
+        DataType::Dictionary(ref key_type, _) => {
+            let (values, index) = match **key_type {
+                DataType::Int8 => dictionary_value_index!(col, Int8Type, row),
+                DataType::Int16 => dictionary_value_index!(col, Int16Type, row),
+                DataType::Int32 => dictionary_value_index!(col, Int32Type, row),
+                DataType::Int64 => dictionary_value_index!(col, Int64Type, row),
+                DataType::UInt8 => dictionary_value_index!(col, UInt8Type, row),
+                DataType::UInt16 => dictionary_value_index!(col, UInt16Type, row),
+                DataType::UInt32 => dictionary_value_index!(col, UInt32Type, row),
+                DataType::UInt64 => dictionary_value_index!(col, UInt64Type, row),
+                _ => {
+                    return Err(ExecutionError::ExecutionError(
+                        "Unsupported GROUP BY dictionary key type".to_string(),
+                    ))
+                }
+            };
+            create_scalar(&values, index)?
+        }
+        _ => {
+            return Err(ExecutionError::ExecutionError(
+                "Unsupported GROUP BY data type".to_string(),
+            ))
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -792,4 +1258,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn dictionary_keys() -> Result<()> {
+        let strings: ArrayRef = Arc::new(StringArray::from(vec!["b", "a"]));
+        let first: DictionaryArray<Int32Type> =
+            vec![Some("a"), Some("b"), None].into_iter().collect();
+        let second: DictionaryArray<UInt8Type> =
+            vec![Some("b"), Some("a")].into_iter().collect();
+        let dictionaries: Vec<ArrayRef> = vec![Arc::new(first), Arc::new(second)];
+
+        let mut keys = vec![GroupByScalar::Null; 2];
+        create_key(&dictionaries, 0, &mut keys)?;
+        assert_eq!(
+            keys,
+            vec![
+                GroupByScalar::Utf8("a".to_string()),
+                GroupByScalar::Utf8("b".to_string())
+            ]
+        );
+
+        let mut key = vec![GroupByScalar::Null];
+        create_key(&vec![strings.clone()], 1, &mut key)?;
+        assert_eq!(key[0], keys[0]);
+
+        create_key(&dictionaries, 1, &mut keys)?;
+        assert_eq!(keys[0], GroupByScalar::Utf8("b".to_string()));
+        create_key(&vec![dictionaries[0].clone()], 2, &mut key)?;
+        assert_eq!(key[0], GroupByScalar::Null);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,1331 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the hash join execution plan. The left input is used to build a hash table
+//! which is then probed with the batches of the right input.
+//!
+//! When a memory manager is configured and the left input does not fit within its limit,
+//! the join falls back to a partitioned (Grace) hash join: both inputs are split into
+//! partitions by the hash of their join keys and spilled to disk, after which each pair
+//! of partitions is joined separately.
+
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::memory_manager::{MemoryManager, MemoryReservation};
+use crate::execution::physical_plan::common::{self, JoinHandle};
+use crate::execution::physical_plan::hash_aggregate::{create_key, GroupByScalar};
+use crate::execution::physical_plan::spill::SpillFile;
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use crate::execution::scheduler;
+use crate::logicalplan::{build_join_schema, JoinType};
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+use fnv::{FnvHashMap, FnvHasher};
+
+/// The number of partitions each input is split into when a join spills to disk
+const SPILL_PARTITIONS: usize = 16;
+
+/// Seed for the hash used to assign rows to spill partitions. This differs from the
+/// hash used to repartition the inputs of a join so that all rows of an input partition
+/// do not end up in the same spill partition.
+const SPILL_HASH_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Determines how the build side of a hash join is distributed between partitions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PartitionMode {
+    /// Both inputs are hash partitioned on the join keys, so each partition of the right
+    /// input only needs to be joined with the matching partition of the left input
+    Partitioned,
+    /// All partitions of the left input are collected into a single hash table that is
+    /// shared by every partition of the right input. This avoids repartitioning the
+    /// right input and is used when the left input is small.
+    CollectLeft,
+}
+
+/// Hash join execution plan
+pub struct HashJoinExec {
+    /// The left input, used to build the hash table
+    left: Arc<dyn ExecutionPlan>,
+    /// The right input, used to probe the hash table
+    right: Arc<dyn ExecutionPlan>,
+    /// Pairs of (left, right) column indices to join on
+    on: Vec<(usize, usize)>,
+    /// The join type
+    join_type: JoinType,
+    /// How the build side is distributed between partitions
+    mode: PartitionMode,
+    /// The output schema, containing the left fields followed by the right fields
+    schema: Arc<Schema>,
+    /// Memory manager that the hash table is accounted against, if any
+    memory_manager: Option<Arc<MemoryManager>>,
+    /// Whether null keys match each other
+    null_equals_null: bool,
+}
+
+impl HashJoinExec {
+    /// Create a new hash join execution plan
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: &Vec<(usize, usize)>,
+        join_type: &JoinType,
+        mode: PartitionMode,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        if on.is_empty() {
+            return Err(ExecutionError::General(
+                "Hash join requires at least one pair of join columns".to_string(),
+            ));
+        }
+
+        for (l, r) in on {
+            if *l >= left_schema.fields().len() || *r >= right_schema.fields().len() {
+                return Err(ExecutionError::InvalidColumn(format!(
+                    "Invalid join column pair (#{}, #{})",
+                    l, r
+                )));
+            }
+            let left_type = left_schema.field(*l).data_type();
+            let right_type = right_schema.field(*r).data_type();
+            if left_type != right_type {
+                return Err(ExecutionError::General(format!(
+                    "Join keys must have the same type but found {:?} and {:?}",
+                    left_type, right_type
+                )));
+            }
+        }
+
+        let schema = build_join_schema(&left_schema, &right_schema, join_type);
+
+        Ok(Self {
+            left,
+            right,
+            on: on.clone(),
+            join_type: *join_type,
+            mode,
+            schema: Arc::new(schema),
+            memory_manager: None,
+            null_equals_null: false,
+        })
+    }
+
+    /// Account the memory used by the hash table against a memory manager. If the left
+    /// input does not fit within its limit, both inputs are spilled to disk.
+    pub fn with_memory_manager(mut self, memory_manager: Arc<MemoryManager>) -> Self {
+        self.memory_manager = Some(memory_manager);
+        self
+    }
+
+    /// Determine whether null keys match each other. By default rows with a null key do
+    /// not match any row, following the semantics of `=`.
+    pub fn with_null_equals_null(mut self, null_equals_null: bool) -> Self {
+        self.null_equals_null = null_equals_null;
+        self
+    }
+
+    /// Get the partition mode of this hash join
+    pub fn mode(&self) -> PartitionMode {
+        self.mode
+    }
+
+    fn create_partition(
+        &self,
+        build: Arc<BuildSide>,
+        probe: usize,
+        right: Arc<dyn Partition>,
+    ) -> Arc<dyn Partition> {
+        Arc::new(HashJoinPartition {
+            schema: self.schema.clone(),
+            on: self.on.clone(),
+            join_type: self.join_type,
+            build,
+            probe,
+            right,
+            right_schema: self.right.schema(),
+        })
+    }
+
+    fn create_build_side(
+        &self,
+        partitions: Vec<Arc<dyn Partition>>,
+        probes: usize,
+    ) -> Arc<BuildSide> {
+        Arc::new(BuildSide {
+            schema: self.left.schema(),
+            partitions,
+            memory_manager: self.memory_manager.clone(),
+            null_equals_null: self.null_equals_null,
+            data: Mutex::new(None),
+            finished_probes: Mutex::new(vec![false; probes]),
+        })
+    }
+}
+
+impl ExecutionPlan for HashJoinExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        let left = self.left.partitions()?;
+        let right = self.right.partitions()?;
+
+        match self.mode {
+            PartitionMode::CollectLeft => {
+                // the hash table is built once and shared between all partitions
+                let build = self.create_build_side(left, right.len());
+                Ok(right
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| self.create_partition(build.clone(), i, p.clone()))
+                    .collect())
+            }
+            PartitionMode::Partitioned => {
+                if left.len() != right.len() {
+                    return Err(ExecutionError::General(format!(
+                        "Partitioned hash join requires inputs with the same number of \
+                         partitions but found {} and {}",
+                        left.len(),
+                        right.len()
+                    )));
+                }
+                Ok(left
+                    .iter()
+                    .zip(right.iter())
+                    .map(|(l, r)| {
+                        let build = self.create_build_side(vec![l.clone()], 1);
+                        self.create_partition(build, 0, r.clone())
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+/// A hash table over the rows of the left input
+struct JoinHashTable {
+    /// The batches of the left input
+    batches: Vec<RecordBatch>,
+    /// Map from join key to the (batch, row) positions of the rows with that key
+    map: FnvHashMap<JoinKey, Vec<(usize, usize)>>,
+    /// Whether null keys match each other
+    null_equals_null: bool,
+    /// Whether each row of the left input has matched a row of the right input
+    visited: Mutex<Vec<Vec<bool>>>,
+    /// The memory reserved for the batches, which is released with the hash table
+    _reservation: Option<MemoryReservation>,
+}
+
+impl JoinHashTable {
+    fn try_new(
+        batches: Vec<RecordBatch>,
+        on: &Vec<(usize, usize)>,
+        null_equals_null: bool,
+        reservation: Option<MemoryReservation>,
+    ) -> Result<Self> {
+        let mut map: FnvHashMap<JoinKey, Vec<(usize, usize)>> = FnvHashMap::default();
+        let mut builder = JoinKeyBuilder::new(on.len(), null_equals_null);
+        for (batch_index, batch) in batches.iter().enumerate() {
+            let keys: Vec<ArrayRef> =
+                on.iter().map(|(l, _)| batch.column(*l).clone()).collect();
+            for row in 0..batch.num_rows() {
+                if let Some(key) = builder.build(&keys, row)? {
+                    map.entry(key.clone())
+                        .or_insert_with(Vec::new)
+                        .push((batch_index, row));
+                }
+            }
+        }
+
+        let visited = batches
+            .iter()
+            .map(|batch| vec![false; batch.num_rows()])
+            .collect();
+
+        Ok(Self {
+            batches,
+            map,
+            null_equals_null,
+            visited: Mutex::new(visited),
+            _reservation: reservation,
+        })
+    }
+}
+
+/// The values of the join columns of a row, where null values are represented by `None`
+type JoinKey = Vec<Option<GroupByScalar>>;
+
+/// Creates the join keys of rows, reusing the same buffers for every row
+struct JoinKeyBuilder {
+    values: Vec<GroupByScalar>,
+    key: JoinKey,
+    null_equals_null: bool,
+}
+
+impl JoinKeyBuilder {
+    fn new(len: usize, null_equals_null: bool) -> Self {
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(GroupByScalar::UInt32(0));
+        }
+        Self {
+            values,
+            key: vec![None; len],
+            null_equals_null,
+        }
+    }
+
+    /// Get the join key of a row, or None if the row has a null key and therefore
+    /// cannot match any other row
+    fn build(&mut self, keys: &Vec<ArrayRef>, row: usize) -> Result<Option<&JoinKey>> {
+        if !self.null_equals_null && keys.iter().any(|k| k.is_null(row)) {
+            return Ok(None);
+        }
+        create_key(keys, row, &mut self.values)?;
+        for (i, k) in keys.iter().enumerate() {
+            self.key[i] = if k.is_null(row) {
+                None
+            } else {
+                Some(mem::replace(&mut self.values[i], GroupByScalar::UInt32(0)))
+            };
+        }
+        Ok(Some(&self.key))
+    }
+}
+
+/// The left input of a join after it has been collected
+enum BuildData {
+    /// The left input fits in memory
+    InMemory(JoinHashTable),
+    /// The left input was spilled to disk
+    Spilled(SpilledBuild),
+}
+
+/// The left input of a join, split into partitions by the hash of the join keys and
+/// spilled to disk
+struct SpilledBuild {
+    files: Vec<SpillFile>,
+    /// Whether each row of each partition has matched a row of the right input, merged
+    /// from all partitions probing the spilled input
+    visited: Mutex<Vec<Vec<Vec<bool>>>>,
+}
+
+/// The left side of a join, shared between the partitions that probe the same
+/// hash table
+struct BuildSide {
+    /// The schema of the left input
+    schema: Arc<Schema>,
+    /// The left partitions to build the hash table from
+    partitions: Vec<Arc<dyn Partition>>,
+    /// Memory manager that the hash table is accounted against, if any
+    memory_manager: Option<Arc<MemoryManager>>,
+    /// Whether null keys match each other
+    null_equals_null: bool,
+    /// The collected left input, which is built when the first probing partition is
+    /// executed
+    data: Mutex<Option<Arc<BuildData>>>,
+    /// Whether each probing partition has consumed all of its input. A partition whose
+    /// iterator is dropped before that, for example below a limit, does not finish, so
+    /// that the unmatched left rows are only produced once it is executed again.
+    finished_probes: Mutex<Vec<bool>>,
+}
+
+impl BuildSide {
+    /// Get the collected left input, collecting it if this is the first partition to
+    /// request it
+    fn data(&self, on: &Vec<(usize, usize)>) -> Result<Arc<BuildData>> {
+        // the partition that collects the left input holds the lock while it waits for
+        // the threads that execute the left partitions, which need slots of the scheduler
+        let mut data = scheduler::wait(|| self.data.lock().unwrap());
+        match data.as_ref() {
+            Some(data) => Ok(data.clone()),
+            None => {
+                let collected = Arc::new(self.collect(on)?);
+                *data = Some(collected.clone());
+                Ok(collected)
+            }
+        }
+    }
+
+    /// Collect the left partitions in parallel, spilling them to disk if they do not
+    /// fit within the limit of the memory manager
+    fn collect(&self, on: &Vec<(usize, usize)>) -> Result<BuildData> {
+        let keys: Vec<usize> = on.iter().map(|(l, _)| *l).collect();
+        let state = Arc::new(Mutex::new(CollectState {
+            schema: self.schema.clone(),
+            keys,
+            batches: vec![],
+            reservation: self
+                .memory_manager
+                .as_ref()
+                .map(|m| MemoryReservation::new(m.clone())),
+            spill: None,
+        }));
+
+        let threads: Vec<JoinHandle<Result<()>>> = self
+            .partitions
+            .iter()
+            .map(|p| {
+                let p = p.clone();
+                let state = state.clone();
+                common::spawn(move || {
+                    let it = p.execute()?;
+                    let mut it = it.lock().unwrap();
+                    while let Some(batch) = it.next()? {
+                        if batch.num_rows() > 0 {
+                            state.lock().unwrap().push(batch)?;
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            let join = thread.join().expect("Failed to join thread");
+            join?;
+        }
+
+        let mut state = state.lock().unwrap();
+        match state.spill.take() {
+            Some(mut files) => {
+                for file in files.iter_mut() {
+                    file.finish()?;
+                }
+                let visited = files.iter().map(|_| vec![]).collect();
+                Ok(BuildData::Spilled(SpilledBuild {
+                    files,
+                    visited: Mutex::new(visited),
+                }))
+            }
+            None => {
+                let batches = state.batches.drain(..).collect();
+                let table = JoinHashTable::try_new(
+                    batches,
+                    on,
+                    self.null_equals_null,
+                    state.reservation.take(),
+                )?;
+                Ok(BuildData::InMemory(table))
+            }
+        }
+    }
+
+    /// Record that a probing partition has consumed all of its input, returning true
+    /// if it was the last one to do so
+    fn finish_probe(&self, probe: usize) -> Result<bool> {
+        let mut finished_probes = self.finished_probes.lock().unwrap();
+        match finished_probes.get(probe) {
+            Some(false) => {
+                finished_probes[probe] = true;
+                Ok(finished_probes.iter().all(|finished| *finished))
+            }
+            Some(true) => Err(ExecutionError::General(format!(
+                "Partition {} of the hash join probed the hash table more than once",
+                probe
+            ))),
+            None => Err(ExecutionError::InternalError(format!(
+                "Hash join has no probing partition {}",
+                probe
+            ))),
+        }
+    }
+}
+
+/// The left batches collected so far
+struct CollectState {
+    schema: Arc<Schema>,
+    /// The indices of the join key columns
+    keys: Vec<usize>,
+    /// The batches held in memory
+    batches: Vec<RecordBatch>,
+    /// The memory reserved for the batches
+    reservation: Option<MemoryReservation>,
+    /// The spill files, once the batches no longer fit in memory
+    spill: Option<Vec<SpillFile>>,
+}
+
+impl CollectState {
+    fn push(&mut self, batch: RecordBatch) -> Result<()> {
+        if self.spill.is_none() {
+            let fits = match self.reservation {
+                Some(ref mut reservation) => {
+                    reservation.try_grow(batch.get_array_memory_size())
+                }
+                None => true,
+            };
+            if fits {
+                self.batches.push(batch);
+                return Ok(());
+            }
+
+            // move the batches collected so far to disk and release their memory
+            let mut files = create_spill_files(&self.schema);
+            for batch in self.batches.drain(..) {
+                spill_batch(&batch, &self.keys, &mut files)?;
+            }
+            self.reservation.take();
+            self.spill = Some(files);
+        }
+        match self.spill {
+            Some(ref mut files) => spill_batch(&batch, &self.keys, files),
+            None => Ok(()),
+        }
+    }
+}
+
+fn create_spill_files(schema: &Arc<Schema>) -> Vec<SpillFile> {
+    (0..SPILL_PARTITIONS)
+        .map(|_| SpillFile::new(schema.clone()))
+        .collect()
+}
+
+/// Split a batch into partitions by the hash of its key columns and write each
+/// partition to the corresponding spill file
+fn spill_batch(
+    batch: &RecordBatch,
+    keys: &Vec<usize>,
+    files: &mut Vec<SpillFile>,
+) -> Result<()> {
+    let key_columns: Vec<ArrayRef> =
+        keys.iter().map(|i| batch.column(*i).clone()).collect();
+    let mut key = Vec::with_capacity(keys.len());
+    for _ in 0..keys.len() {
+        key.push(GroupByScalar::UInt32(0));
+    }
+
+    let mut indices: Vec<Vec<u32>> = vec![vec![]; files.len()];
+    for row in 0..batch.num_rows() {
+        // rows with null keys either never match or only match rows that also have
+        // null keys, which are written to the same partition on both sides
+        let partition = if key_columns.iter().any(|k| k.is_null(row)) {
+            0
+        } else {
+            create_key(&key_columns, row, &mut key)?;
+            let mut hasher = FnvHasher::with_key(SPILL_HASH_SEED);
+            key.hash(&mut hasher);
+            (hasher.finish() % files.len() as u64) as usize
+        };
+        indices[partition].push(row as u32);
+    }
+
+    for (file, indices) in files.iter_mut().zip(indices.into_iter()) {
+        if !indices.is_empty() {
+            let columns = common::take_columns(batch, &UInt32Array::from(indices))?;
+            file.write(&RecordBatch::try_new(batch.schema().clone(), columns)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Represents a single partition of a hash join execution plan
+struct HashJoinPartition {
+    schema: Arc<Schema>,
+    on: Vec<(usize, usize)>,
+    join_type: JoinType,
+    /// The left side to build the hash table from
+    build: Arc<BuildSide>,
+    /// The index of this partition among the partitions probing the same hash table
+    probe: usize,
+    /// The right partition to probe the hash table with
+    right: Arc<dyn Partition>,
+    right_schema: Arc<Schema>,
+}
+
+impl Partition for HashJoinPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let data = self.build.data(&self.on)?;
+        let state = match *data {
+            BuildData::InMemory(_) => ProbeState::InMemory,
+            BuildData::Spilled(_) => {
+                ProbeState::Spilling(create_spill_files(&self.right_schema))
+            }
+        };
+        Ok(Arc::new(Mutex::new(HashJoinIterator {
+            schema: self.schema.clone(),
+            on: self.on.clone(),
+            join_type: self.join_type,
+            build: self.build.clone(),
+            probe: self.probe,
+            data,
+            right: self.right.execute()?,
+            buffer: VecDeque::new(),
+            state,
+        })))
+    }
+}
+
+/// Whether rows of the left input are produced after all of the right input has been
+/// probed, depending on whether they had a match
+fn emits_left_after_probe(join_type: JoinType) -> bool {
+    match join_type {
+        JoinType::Left | JoinType::Full | JoinType::LeftSemi | JoinType::LeftAnti => true,
+        JoinType::Inner | JoinType::Right => false,
+    }
+}
+
+/// Whether the matching pairs of rows of both inputs are part of the output
+fn emits_matches(join_type: JoinType) -> bool {
+    match join_type {
+        JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => true,
+        JoinType::LeftSemi | JoinType::LeftAnti => false,
+    }
+}
+
+/// Whether the rows of the right input without a match are part of the output
+fn emits_unmatched_right(join_type: JoinType) -> bool {
+    match join_type {
+        JoinType::Right | JoinType::Full => true,
+        JoinType::Inner | JoinType::Left | JoinType::LeftSemi | JoinType::LeftAnti => {
+            false
+        }
+    }
+}
+
+/// Create a column of nulls for each of the given fields
+fn null_columns(fields: &[Field], len: usize) -> Result<Vec<ArrayRef>> {
+    fields
+        .iter()
+        .map(|f| common::new_null_array(f.data_type(), len))
+        .collect()
+}
+
+/// The progress of a hash join iterator
+enum ProbeState {
+    /// Probing the in-memory hash table with the right input
+    InMemory,
+    /// Spilling the right input to disk, split into the same partitions as the
+    /// spilled left input
+    Spilling(Vec<SpillFile>),
+    /// Joining the spilled partitions of both inputs, one pair at a time
+    JoiningSpilled {
+        /// The spilled partitions of the right input
+        files: Vec<SpillFile>,
+        /// The index of the partition being joined
+        partition: usize,
+        /// The hash table of the left partition and the iterator over the right
+        /// partition, once loaded
+        current: Option<(JoinHashTable, Arc<Mutex<dyn BatchIterator>>)>,
+    },
+    /// Producing the rows of the spilled partitions of the left input that depend on
+    /// whether they had a match
+    LeftSpilled {
+        /// The index of the next partition to produce rows for
+        partition: usize,
+    },
+    /// All output has been produced
+    Finished,
+}
+
+/// Iterator that probes the hash table with the batches of the right input
+struct HashJoinIterator {
+    schema: Arc<Schema>,
+    on: Vec<(usize, usize)>,
+    join_type: JoinType,
+    build: Arc<BuildSide>,
+    /// The index of the partition among the partitions probing the same hash table
+    probe: usize,
+    data: Arc<BuildData>,
+    right: Arc<Mutex<dyn BatchIterator>>,
+    /// Output batches that have been produced but not yet returned
+    buffer: VecDeque<RecordBatch>,
+    state: ProbeState,
+}
+
+impl HashJoinIterator {
+    /// Probe a hash table with a batch from the right input
+    fn probe(&mut self, table: &JoinHashTable, batch: &RecordBatch) -> Result<()> {
+        let keys: Vec<ArrayRef> = self
+            .on
+            .iter()
+            .map(|(_, r)| batch.column(*r).clone())
+            .collect();
+
+        let mut builder = JoinKeyBuilder::new(keys.len(), table.null_equals_null);
+
+        // the matching row indices are grouped by the left batch they refer to, so that
+        // each group can be gathered with a single `take` per column
+        let num_batches = table.batches.len();
+        let mut left_indices: Vec<Vec<u32>> = vec![vec![]; num_batches];
+        let mut right_indices: Vec<Vec<u32>> = vec![vec![]; num_batches];
+        let mut unmatched: Vec<u32> = vec![];
+
+        for row in 0..batch.num_rows() {
+            let matches = match builder.build(&keys, row)? {
+                Some(key) => table.map.get(key),
+                None => None,
+            };
+            match matches {
+                Some(matches) => {
+                    for (batch_index, left_row) in matches {
+                        left_indices[*batch_index].push(*left_row as u32);
+                        right_indices[*batch_index].push(row as u32);
+                    }
+                }
+                None => unmatched.push(row as u32),
+            }
+        }
+
+        if emits_left_after_probe(self.join_type) {
+            let mut visited = table.visited.lock().unwrap();
+            for (batch_index, rows) in left_indices.iter().enumerate() {
+                for row in rows {
+                    visited[batch_index][*row as usize] = true;
+                }
+            }
+        }
+
+        if !emits_matches(self.join_type) {
+            return Ok(());
+        }
+
+        for (batch_index, (left_rows, right_rows)) in left_indices
+            .into_iter()
+            .zip(right_indices.into_iter())
+            .enumerate()
+        {
+            if left_rows.is_empty() {
+                continue;
+            }
+            let mut columns = common::take_columns(
+                &table.batches[batch_index],
+                &UInt32Array::from(left_rows),
+            )?;
+            columns.extend(common::take_columns(batch, &UInt32Array::from(right_rows))?);
+            self.buffer
+                .push_back(RecordBatch::try_new(self.schema.clone(), columns)?);
+        }
+
+        // rows of the right input without a match are padded with nulls
+        if emits_unmatched_right(self.join_type) && !unmatched.is_empty() {
+            let mut columns = null_columns(self.build.schema.fields(), unmatched.len())?;
+            columns.extend(common::take_columns(batch, &UInt32Array::from(unmatched))?);
+            self.buffer
+                .push_back(RecordBatch::try_new(self.schema.clone(), columns)?);
+        }
+
+        Ok(())
+    }
+
+    /// Produce the rows of the left input that are part of the output once all of the
+    /// right input has been probed. These are the rows with a match for a semi join and
+    /// the rows without a match otherwise, padded with nulls for outer joins. Batches
+    /// without visited flags have not been probed.
+    fn left_after_probe(
+        &mut self,
+        batches: &[RecordBatch],
+        visited: &[Vec<bool>],
+    ) -> Result<()> {
+        let matched = self.join_type == JoinType::LeftSemi;
+        let left_len = self.build.schema.fields().len();
+        for (batch_index, batch) in batches.iter().enumerate() {
+            let indices: Vec<u32> = (0..batch.num_rows())
+                .filter(|row| match visited.get(batch_index) {
+                    Some(visited) => visited[*row] == matched,
+                    None => !matched,
+                })
+                .map(|row| row as u32)
+                .collect();
+            if indices.is_empty() {
+                continue;
+            }
+            let len = indices.len();
+            // semi and anti joins have no columns of the right input to pad
+            let mut columns = common::take_columns(batch, &UInt32Array::from(indices))?;
+            columns.extend(null_columns(&self.schema.fields()[left_len..], len)?);
+            self.buffer
+                .push_back(RecordBatch::try_new(self.schema.clone(), columns)?);
+        }
+        Ok(())
+    }
+
+    /// Make progress from the given state, returning the next state
+    fn advance(&mut self, state: ProbeState) -> Result<ProbeState> {
+        let data = self.data.clone();
+        match (state, data.as_ref()) {
+            (ProbeState::InMemory, BuildData::InMemory(table)) => {
+                let batch = self.right.lock().unwrap().next()?;
+                match batch {
+                    Some(batch) => {
+                        self.probe(table, &batch)?;
+                        Ok(ProbeState::InMemory)
+                    }
+                    None => {
+                        // the unmatched left rows are only known once every partition
+                        // probing the same hash table has consumed all of its input
+                        if self.build.finish_probe(self.probe)?
+                            && emits_left_after_probe(self.join_type)
+                        {
+                            let visited = table.visited.lock().unwrap();
+                            self.left_after_probe(&table.batches, &visited)?;
+                        }
+                        Ok(ProbeState::Finished)
+                    }
+                }
+            }
+            (ProbeState::Spilling(mut files), BuildData::Spilled(_)) => {
+                let batch = self.right.lock().unwrap().next()?;
+                match batch {
+                    Some(batch) => {
+                        let keys = self.on.iter().map(|(_, r)| *r).collect();
+                        spill_batch(&batch, &keys, &mut files)?;
+                        Ok(ProbeState::Spilling(files))
+                    }
+                    None => {
+                        for file in files.iter_mut() {
+                            file.finish()?;
+                        }
+                        Ok(ProbeState::JoiningSpilled {
+                            files,
+                            partition: 0,
+                            current: None,
+                        })
+                    }
+                }
+            }
+            (
+                ProbeState::JoiningSpilled {
+                    files,
+                    partition,
+                    current: Some((table, it)),
+                },
+                BuildData::Spilled(spilled),
+            ) => {
+                let batch = it.lock().unwrap().next()?;
+                match batch {
+                    Some(batch) => {
+                        self.probe(&table, &batch)?;
+                        Ok(ProbeState::JoiningSpilled {
+                            files,
+                            partition,
+                            current: Some((table, it)),
+                        })
+                    }
+                    None => {
+                        if emits_left_after_probe(self.join_type) {
+                            let local = table.visited.lock().unwrap();
+                            let mut visited = spilled.visited.lock().unwrap();
+                            if visited[partition].is_empty() {
+                                visited[partition] = local.clone();
+                            } else {
+                                for (merged, local) in
+                                    visited[partition].iter_mut().zip(local.iter())
+                                {
+                                    for (m, l) in merged.iter_mut().zip(local.iter()) {
+                                        *m = *m || *l;
+                                    }
+                                }
+                            }
+                        }
+                        Ok(ProbeState::JoiningSpilled {
+                            files,
+                            partition: partition + 1,
+                            current: None,
+                        })
+                    }
+                }
+            }
+            (
+                ProbeState::JoiningSpilled {
+                    files,
+                    partition,
+                    current: None,
+                },
+                BuildData::Spilled(spilled),
+            ) => {
+                if partition < files.len() {
+                    // load the left partition into memory and probe it with the right
+                    // partition with the same hashes
+                    let batches = common::collect(spilled.files[partition].read()?)?;
+                    let table = JoinHashTable::try_new(
+                        batches,
+                        &self.on,
+                        self.build.null_equals_null,
+                        None,
+                    )?;
+                    let it = files[partition].read()?;
+                    Ok(ProbeState::JoiningSpilled {
+                        files,
+                        partition,
+                        current: Some((table, it)),
+                    })
+                } else if self.build.finish_probe(self.probe)?
+                    && emits_left_after_probe(self.join_type)
+                {
+                    Ok(ProbeState::LeftSpilled { partition: 0 })
+                } else {
+                    Ok(ProbeState::Finished)
+                }
+            }
+            (ProbeState::LeftSpilled { partition }, BuildData::Spilled(spilled)) => {
+                if partition < spilled.files.len() {
+                    let batches = common::collect(spilled.files[partition].read()?)?;
+                    let visited = spilled.visited.lock().unwrap();
+                    self.left_after_probe(&batches, &visited[partition])?;
+                    Ok(ProbeState::LeftSpilled {
+                        partition: partition + 1,
+                    })
+                } else {
+                    Ok(ProbeState::Finished)
+                }
+            }
+            (ProbeState::Finished, _) => Ok(ProbeState::Finished),
+            _ => Err(ExecutionError::InternalError(
+                "Hash join state does not match the collected left input".to_string(),
+            )),
+        }
+    }
+}
+
+impl BatchIterator for HashJoinIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            if let Some(batch) = self.buffer.pop_front() {
+                return Ok(Some(batch));
+            }
+            if let ProbeState::Finished = self.state {
+                return Ok(None);
+            }
+            let state = std::mem::replace(&mut self.state, ProbeState::Finished);
+            self.state = self.advance(state)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::datasource::DatasourceExec;
+    use crate::execution::physical_plan::expressions::col;
+    use crate::execution::physical_plan::repartition::RepartitionExec;
+    use crate::test;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::DataType;
+
+    /// Create an execution plan with one partition per batch of values
+    fn build_table(
+        names: (&str, &str),
+        partitions: Vec<(Vec<i32>, Vec<i32>)>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(names.0, DataType::Int32, false),
+            Field::new(names.1, DataType::Int32, false),
+        ]));
+        let iterators = partitions
+            .into_iter()
+            .map(|(a, b)| {
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+                )?;
+                Ok(Arc::new(Mutex::new(common::RecordBatchIterator::new(
+                    schema.clone(),
+                    vec![Arc::new(batch)],
+                ))) as Arc<Mutex<dyn BatchIterator>>)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(DatasourceExec::new(schema, iterators)))
+    }
+
+    fn join_rows(join: &HashJoinExec) -> Result<Vec<String>> {
+        let mut rows = vec![];
+        for batch in test::execute(join)? {
+            rows.extend(test::format_batch(&batch));
+        }
+        rows.sort();
+        Ok(rows)
+    }
+
+    #[test]
+    fn join_collect_left() -> Result<()> {
+        let left = build_table(("a1", "b1"), vec![(vec![1, 2, 3], vec![10, 20, 30])])?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![
+                (vec![1, 2], vec![100, 200]),
+                (vec![3, 4, 1], vec![300, 400, 101]),
+            ],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+        )?;
+
+        // one output partition per partition of the right input
+        assert_eq!(join.partitions()?.len(), 2);
+        assert_eq!(join.schema().fields().len(), 4);
+
+        let expected = vec!["1,10,1,100", "1,10,1,101", "2,20,2,200", "3,30,3,300"];
+        assert_eq!(join_rows(&join)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_left() -> Result<()> {
+        let left =
+            build_table(("a1", "b1"), vec![(vec![1, 2, 3, 5], vec![10, 20, 30, 50])])?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1, 2], vec![100, 200]), (vec![3, 4], vec![300, 400])],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Left,
+            PartitionMode::CollectLeft,
+        )?;
+
+        // the columns of the right input become nullable
+        assert!(join.schema().field(2).is_nullable());
+
+        // the unmatched left row is only produced once, even though the hash table is
+        // shared between two partitions
+        let expected = vec!["1,10,1,100", "2,20,2,200", "3,30,3,300", "5,50,NULL,NULL"];
+        assert_eq!(join_rows(&join)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_left_with_dropped_partition() -> Result<()> {
+        let left =
+            build_table(("a1", "b1"), vec![(vec![1, 2, 3, 5], vec![10, 20, 30, 50])])?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1, 2], vec![100, 200]), (vec![3, 4], vec![300, 400])],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Left,
+            PartitionMode::CollectLeft,
+        )?;
+        let partitions = join.partitions()?;
+        let rows = |partition: &Arc<dyn Partition>| -> Result<Vec<String>> {
+            let mut rows = vec![];
+            for batch in common::collect(partition.execute()?)? {
+                rows.extend(test::format_batch(&batch));
+            }
+            rows.sort();
+            Ok(rows)
+        };
+
+        // the unmatched left row is not produced while the first partition has been
+        // dropped before it consumed all of its input
+        drop(partitions[0].execute()?);
+        assert_eq!(rows(&partitions[1])?, vec!["3,30,3,300"]);
+
+        // but once it is executed again
+        let expected = vec!["1,10,1,100", "2,20,2,200", "5,50,NULL,NULL"];
+        assert_eq!(rows(&partitions[0])?, expected);
+
+        // a partition cannot finish probing the hash table twice
+        assert!(rows(&partitions[0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_right() -> Result<()> {
+        let left = build_table(("a1", "b1"), vec![(vec![1, 2], vec![10, 20])])?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1, 3], vec![100, 300]), (vec![2, 4], vec![200, 400])],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Right,
+            PartitionMode::CollectLeft,
+        )?;
+
+        // the columns of the left input become nullable
+        assert!(join.schema().field(0).is_nullable());
+
+        let expected = vec![
+            "1,10,1,100",
+            "2,20,2,200",
+            "NULL,NULL,3,300",
+            "NULL,NULL,4,400",
+        ];
+        assert_eq!(join_rows(&join)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_full() -> Result<()> {
+        let left = build_table(
+            ("a1", "b1"),
+            vec![(vec![1, 2], vec![10, 20]), (vec![5], vec![50])],
+        )?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1, 3], vec![100, 300]), (vec![2, 4], vec![200, 400])],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Full,
+            PartitionMode::CollectLeft,
+        )?;
+
+        // the columns of both inputs become nullable
+        assert!(join.schema().field(0).is_nullable());
+        assert!(join.schema().field(2).is_nullable());
+
+        let expected = vec![
+            "1,10,1,100",
+            "2,20,2,200",
+            "5,50,NULL,NULL",
+            "NULL,NULL,3,300",
+            "NULL,NULL,4,400",
+        ];
+        assert_eq!(join_rows(&join)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_semi_anti() -> Result<()> {
+        for memory_limit in &[None, Some(1)] {
+            for join_type in &[JoinType::LeftSemi, JoinType::LeftAnti] {
+                let left = build_table(
+                    ("a1", "b1"),
+                    vec![(vec![1, 2, 3], vec![10, 20, 30]), (vec![4], vec![40])],
+                )?;
+                let right = build_table(
+                    ("a2", "b2"),
+                    vec![(vec![1, 3], vec![100, 300]), (vec![1, 5], vec![101, 500])],
+                )?;
+
+                let mut join = HashJoinExec::try_new(
+                    left,
+                    right,
+                    &vec![(0, 0)],
+                    join_type,
+                    PartitionMode::CollectLeft,
+                )?;
+                if let Some(limit) = memory_limit {
+                    join = join.with_memory_manager(Arc::new(MemoryManager::new(*limit)));
+                }
+
+                // only the columns of the left input are produced
+                assert_eq!(join.schema().fields().len(), 2);
+
+                // each left row is produced at most once, even with multiple matches
+                let expected = match join_type {
+                    JoinType::LeftSemi => vec!["1,10", "3,30"],
+                    _ => vec!["2,20", "4,40"],
+                };
+                assert_eq!(join_rows(&join)?, expected);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_spilled() -> Result<()> {
+        for join_type in &[JoinType::Inner, JoinType::Left, JoinType::Full] {
+            let left = build_table(
+                ("a1", "b1"),
+                vec![
+                    (vec![1, 2, 3], vec![10, 20, 30]),
+                    (vec![5, 1], vec![50, 11]),
+                ],
+            )?;
+            let right = build_table(
+                ("a2", "b2"),
+                vec![(vec![1, 3], vec![100, 300]), (vec![2, 4], vec![200, 400])],
+            )?;
+
+            // the memory limit is too small to hold any batch, so both inputs are
+            // spilled to disk
+            let memory_manager = Arc::new(MemoryManager::new(1));
+            let join = HashJoinExec::try_new(
+                left,
+                right,
+                &vec![(0, 0)],
+                join_type,
+                PartitionMode::CollectLeft,
+            )?
+            .with_memory_manager(memory_manager.clone());
+
+            let mut expected =
+                vec!["1,10,1,100", "1,11,1,100", "2,20,2,200", "3,30,3,300"];
+            if *join_type != JoinType::Inner {
+                expected.push("5,50,NULL,NULL");
+            }
+            if *join_type == JoinType::Full {
+                expected.push("NULL,NULL,4,400");
+            }
+            assert_eq!(join_rows(&join)?, expected);
+            assert_eq!(memory_manager.used(), 0);
+        }
+
+        Ok(())
+    }
+
+    /// Create an execution plan with a single partition and a nullable key column
+    fn build_nullable_table(
+        names: (&str, &str),
+        a: Vec<Option<i32>>,
+        b: Vec<i32>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(names.0, DataType::Int32, true),
+            Field::new(names.1, DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )?;
+        let it = common::RecordBatchIterator::new(schema.clone(), vec![Arc::new(batch)]);
+        Ok(Arc::new(DatasourceExec::new(
+            schema,
+            vec![Arc::new(Mutex::new(it))],
+        )))
+    }
+
+    #[test]
+    fn join_null_equals_null() -> Result<()> {
+        for null_equals_null in &[false, true] {
+            for memory_limit in &[None, Some(1)] {
+                let left = build_nullable_table(
+                    ("a1", "b1"),
+                    vec![Some(1), None],
+                    vec![10, 20],
+                )?;
+                let right = build_nullable_table(
+                    ("a2", "b2"),
+                    vec![None, Some(1), None],
+                    vec![100, 200, 300],
+                )?;
+
+                let mut join = HashJoinExec::try_new(
+                    left,
+                    right,
+                    &vec![(0, 0)],
+                    &JoinType::Inner,
+                    PartitionMode::CollectLeft,
+                )?
+                .with_null_equals_null(*null_equals_null);
+                if let Some(limit) = memory_limit {
+                    join = join.with_memory_manager(Arc::new(MemoryManager::new(*limit)));
+                }
+
+                let mut expected = vec!["1,10,1,200"];
+                if *null_equals_null {
+                    expected.push("NULL,20,NULL,100");
+                    expected.push("NULL,20,NULL,300");
+                }
+                assert_eq!(join_rows(&join)?, expected);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_partitioned() -> Result<()> {
+        let left = build_table(
+            ("a1", "b1"),
+            vec![(vec![1, 2], vec![10, 20]), (vec![3, 1], vec![30, 11])],
+        )?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1, 3], vec![100, 300]), (vec![5], vec![500])],
+        )?;
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let left = Arc::new(RepartitionExec::try_new(
+            left,
+            vec![col(0, left_schema.as_ref())],
+            3,
+        )?);
+        let right = Arc::new(RepartitionExec::try_new(
+            right,
+            vec![col(0, right_schema.as_ref())],
+            3,
+        )?);
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+        )?;
+        assert_eq!(join.partitions()?.len(), 3);
+
+        let expected = vec!["1,10,1,100", "1,11,1,100", "3,30,3,300"];
+        assert_eq!(join_rows(&join)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_partitioned_mismatched_partitions() -> Result<()> {
+        let left = build_table(("a1", "b1"), vec![(vec![1], vec![10])])?;
+        let right = build_table(
+            ("a2", "b2"),
+            vec![(vec![1], vec![100]), (vec![2], vec![200])],
+        )?;
+
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            &vec![(0, 0)],
+            &JoinType::Inner,
+            PartitionMode::Partitioned,
+        )?;
+        assert!(join.partitions().is_err());
+
+        Ok(())
+    }
+}
@@ -18,7 +18,7 @@
 //! Defines the LIMIT plan
 
 use crate::error::{ExecutionError, Result};
-use crate::execution::physical_plan::common::RecordBatchIterator;
+use crate::execution::physical_plan::common::{self, JoinHandle, RecordBatchIterator};
 use crate::execution::physical_plan::ExecutionPlan;
 use crate::execution::physical_plan::{BatchIterator, Partition};
 use arrow::array::ArrayRef;
@@ -26,8 +26,6 @@ use arrow::compute::limit;
 use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::thread::JoinHandle;
 
 /// Limit execution plan
 pub struct LimitExec {
@@ -37,6 +35,8 @@ pub struct LimitExec {
     partitions: Vec<Arc<dyn Partition>>,
     /// Maximum number of rows to return
     limit: usize,
+    /// The plan that the input partitions belong to, if known
+    input: Option<Arc<dyn ExecutionPlan>>,
 }
 
 impl LimitExec {
@@ -50,8 +50,16 @@ impl LimitExec {
             schema,
             partitions,
             limit,
+            input: None,
         }
     }
+
+    /// Set the plan that the input partitions belong to, which is reported as the
+    /// child of this plan
+    pub fn with_input(mut self, input: Arc<dyn ExecutionPlan>) -> Self {
+        self.input = Some(input);
+        self
+    }
 }
 
 impl ExecutionPlan for LimitExec {
@@ -59,6 +67,10 @@ impl ExecutionPlan for LimitExec {
         self.schema.clone()
     }
 
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.input.iter().cloned().collect()
+    }
+
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         Ok(vec![Arc::new(LimitPartition {
             schema: self.schema.clone(),
@@ -86,7 +98,7 @@ impl Partition for LimitPartition {
             .map(|p| {
                 let p = p.clone();
                 let limit = self.limit;
-                thread::spawn(move || {
+                common::spawn(move || {
                     let it = p.execute()?;
                     collect_with_limit(it, limit)
                 })
@@ -17,11 +17,14 @@
 
 //! Math expressions
 
-use crate::error::ExecutionError;
+use crate::error::{ExecutionError, Result};
 use crate::execution::context::ExecutionContext;
 use crate::execution::physical_plan::udf::ScalarFunction;
 
-use arrow::array::{Array, ArrayRef, Float64Array, Float64Builder};
+use arrow::array::{
+    Array, ArrayRef, Float64Array, Float64Builder, UInt32Array, UInt32Builder,
+};
+use arrow::compute::{self, WidthBuckets};
 use arrow::datatypes::{DataType, Field};
 
 use std::sync::Arc;
@@ -56,6 +59,48 @@ macro_rules! math_unary_function {
     };
 }
 
+/// WIDTH_BUCKET(value, low, high, num_buckets) returns the bucket of each value among
+/// `num_buckets` buckets of equal width between `low` and `high`. The bounds and the
+/// number of buckets must be the same for all rows.
+fn width_bucket(args: &Vec<ArrayRef>) -> Result<ArrayRef> {
+    let values = args[0].as_any().downcast_ref::<Float64Array>();
+    let low = args[1].as_any().downcast_ref::<Float64Array>();
+    let high = args[2].as_any().downcast_ref::<Float64Array>();
+    let num_buckets = args[3].as_any().downcast_ref::<UInt32Array>();
+    let (values, low, high, num_buckets) = match (values, low, high, num_buckets) {
+        (Some(values), Some(low), Some(high), Some(num_buckets)) => {
+            (values, low, high, num_buckets)
+        }
+        _ => {
+            return Err(ExecutionError::General(
+                "Invalid data type for width_bucket".to_string(),
+            ))
+        }
+    };
+    if values.len() == 0 {
+        return Ok(Arc::new(UInt32Builder::new(0).finish()));
+    }
+
+    let constant = low.null_count() == 0
+        && high.null_count() == 0
+        && num_buckets.null_count() == 0
+        && (1..values.len()).all(|i| {
+            low.value(i) == low.value(0)
+                && high.value(i) == high.value(0)
+                && num_buckets.value(i) == num_buckets.value(0)
+        });
+    if !constant {
+        return Err(ExecutionError::General(
+            "The bounds and number of buckets of width_bucket must be constant"
+                .to_string(),
+        ));
+    }
+
+    let buckets =
+        WidthBuckets::try_new(low.value(0), high.value(0), num_buckets.value(0))?;
+    Ok(Arc::new(compute::width_bucket(values, &buckets)?))
+}
+
 /// Register math scalar functions with the context
 pub fn register_math_functions(ctx: &mut ExecutionContext) {
     ctx.register_udf(math_unary_function!("sqrt", sqrt));
@@ -75,6 +120,17 @@ pub fn register_math_functions(ctx: &mut ExecutionContext) {
     ctx.register_udf(math_unary_function!("log", ln));
     ctx.register_udf(math_unary_function!("log2", log2));
     ctx.register_udf(math_unary_function!("log10", log10));
+    ctx.register_udf(ScalarFunction::new(
+        "width_bucket",
+        vec![
+            Field::new("value", DataType::Float64, true),
+            Field::new("low", DataType::Float64, false),
+            Field::new("high", DataType::Float64, false),
+            Field::new("num_buckets", DataType::UInt32, false),
+        ],
+        DataType::UInt32,
+        width_bucket,
+    ));
 }
 
 #[cfg(test)]
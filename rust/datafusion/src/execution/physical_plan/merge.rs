@@ -19,14 +19,12 @@
 //! into a single partition
 
 use crate::error::Result;
-use crate::execution::physical_plan::common::RecordBatchIterator;
+use crate::execution::physical_plan::common::{JoinHandle, RecordBatchIterator};
 use crate::execution::physical_plan::{common, ExecutionPlan};
 use crate::execution::physical_plan::{BatchIterator, Partition};
 use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::thread::JoinHandle;
 
 /// Merge execution plan executes partitions in parallel and combines them into a single
 /// partition. No guarantees are made about the order of the resulting partition.
@@ -35,12 +33,25 @@ pub struct MergeExec {
     schema: Arc<Schema>,
     /// Input partitions
     partitions: Vec<Arc<dyn Partition>>,
+    /// The plan that the input partitions belong to, if known
+    input: Option<Arc<dyn ExecutionPlan>>,
 }
 
 impl MergeExec {
     /// Create a new MergeExec
     pub fn new(schema: Arc<Schema>, partitions: Vec<Arc<dyn Partition>>) -> Self {
-        MergeExec { schema, partitions }
+        MergeExec {
+            schema,
+            partitions,
+            input: None,
+        }
+    }
+
+    /// Set the plan that the input partitions belong to, which is reported as the
+    /// child of this plan
+    pub fn with_input(mut self, input: Arc<dyn ExecutionPlan>) -> Self {
+        self.input = Some(input);
+        self
     }
 }
 
@@ -49,6 +60,10 @@ impl ExecutionPlan for MergeExec {
         self.schema.clone()
     }
 
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.input.iter().cloned().collect()
+    }
+
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         Ok(vec![Arc::new(MergePartition {
             schema: self.schema.clone(),
@@ -71,7 +86,7 @@ impl Partition for MergePartition {
             .iter()
             .map(|p| {
                 let p = p.clone();
-                thread::spawn(move || {
+                common::spawn(move || {
                     let it = p.execute()?;
                     common::collect(it)
                 })
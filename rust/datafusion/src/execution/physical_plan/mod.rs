@@ -21,9 +21,11 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
-use crate::error::Result;
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::evaluation_context::EvaluationContext;
 use crate::logicalplan::ScalarValue;
 use arrow::array::ArrayRef;
+use arrow::compute::SortOptions;
 use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 
@@ -33,6 +35,15 @@ pub trait ExecutionPlan {
     fn schema(&self) -> Arc<Schema>;
     /// Get the partitions for this execution plan. Each partition can be executed in parallel.
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>>;
+    /// Get the input plans of this execution plan
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+    /// Get the metrics collected while executing the partitions of this plan, one line
+    /// each, which are shown by `EXPLAIN ANALYZE`
+    fn metrics(&self) -> Vec<String> {
+        vec![]
+    }
 }
 
 /// Represents a partition of an execution plan that can be executed on a thread
@@ -57,6 +68,24 @@ pub trait PhysicalExpr: Send + Sync {
     fn data_type(&self, input_schema: &Schema) -> Result<DataType>;
     /// Evaluate an expression against a RecordBatch
     fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
+    /// Evaluate an expression against a RecordBatch, reusing the memory of arrays that
+    /// were produced for earlier batches of the same partition where supported
+    fn evaluate_with_context(
+        &self,
+        batch: &RecordBatch,
+        _ctx: &mut EvaluationContext,
+    ) -> Result<ArrayRef> {
+        self.evaluate(batch)
+    }
+}
+
+/// Expression used to sort the rows of a RecordBatch, together with the order to sort in
+#[derive(Clone)]
+pub struct PhysicalSortExpr {
+    /// Expression that is evaluated to produce the sort key
+    pub expr: Arc<dyn PhysicalExpr>,
+    /// Sort direction and placement of nulls
+    pub options: SortOptions,
 }
 
 /// Agggregate expression that can be evaluated against a RecordBatch
@@ -73,6 +102,24 @@ pub trait AggregateExpr: Send + Sync {
     /// For example, to combine the results of a parallel SUM we just need to do another SUM, but
     /// to combine the results of parallel COUNT we would also use SUM.
     fn create_reducer(&self, column_index: usize) -> Arc<dyn AggregateExpr>;
+    /// Whether the result of this expression is an intermediate state that has to be
+    /// combined by its reducer to produce the final value, even when the input has a
+    /// single partition
+    fn requires_reducer(&self) -> bool {
+        false
+    }
+}
+
+/// Window function expression that computes a value for each row of a RecordBatch from
+/// the rows of its window partition
+pub trait WindowExpr: Send + Sync {
+    /// Get the name to use in a schema to represent the result of this expression
+    fn name(&self) -> String;
+    /// Get the data type of this expression, given the schema of the input
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType>;
+    /// Evaluate the expression against all rows of a RecordBatch, producing a value for
+    /// each row in the order of the batch
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef>;
 }
 
 /// Aggregate accumulator
@@ -83,17 +130,46 @@ pub trait Accumulator {
     fn accumulate_batch(&mut self, array: &ArrayRef) -> Result<()>;
     /// Get the final value for the accumulator
     fn get_value(&self) -> Result<Option<ScalarValue>>;
+    /// Whether values can be removed from the accumulator with `retract_scalar`, so
+    /// that it can be evaluated over a sliding window frame
+    fn supports_retract(&self) -> bool {
+        false
+    }
+    /// Remove a value that was accumulated before with `accumulate_scalar`
+    fn retract_scalar(&mut self, _value: Option<ScalarValue>) -> Result<()> {
+        Err(ExecutionError::InternalError(
+            "The accumulator does not support retracting values".to_string(),
+        ))
+    }
 }
 
+pub mod adapters;
 pub mod common;
+pub mod cross_join;
+#[cfg(feature = "csv")]
 pub mod csv;
 pub mod datasource;
+pub mod datetime_expressions;
+pub mod deduplicate;
+pub mod empty;
+pub mod evaluation_context;
 pub mod expressions;
+pub mod external_sort;
 pub mod hash_aggregate;
+pub mod hash_join;
 pub mod limit;
 pub mod math_expressions;
 pub mod merge;
+#[cfg(feature = "parquet")]
 pub mod parquet;
 pub mod projection;
+pub mod repartition;
+pub mod scheduled;
 pub mod selection;
+pub mod sort;
+pub mod spill;
+pub mod string_expressions;
+pub mod timeout;
 pub mod udf;
+pub mod union;
+pub mod window;
@@ -17,17 +17,31 @@
 
 //! Execution plan for reading Parquet files
 
-use std::fs::File;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::datasource::pruning::may_match;
+use crate::datasource::{ColumnStatistics, Statistics};
 use crate::error::{ExecutionError, Result};
 use crate::execution::physical_plan::common;
-use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
-use arrow::datatypes::Schema;
+use crate::execution::physical_plan::evaluation_context::EvaluationContext;
+use crate::execution::physical_plan::limit::truncate_batch;
+use crate::execution::physical_plan::selection::filter_batch;
+use crate::execution::physical_plan::{
+    BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
+};
+use crate::logicalplan::{Expr, ScalarValue};
+use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::{RecordBatch, RecordBatchReader};
-use parquet::file::reader::SerializedFileReader;
+use parquet::file::metadata::RowGroupMetaData;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::statistics::Statistics as ParquetStatistics;
 
 use crossbeam::channel::{unbounded, Receiver, Sender};
 use parquet::arrow::{ArrowReader, ParquetFileArrowReader};
@@ -38,48 +52,462 @@ pub struct ParquetExec {
     filenames: Vec<String>,
     /// Schema after projection is applied
     schema: Arc<Schema>,
-    /// Projection for which columns to load
-    projection: Vec<usize>,
+    /// Names of the columns to load, which are resolved against the schema of each
+    /// file so that files whose columns are in a different order can be read
+    columns: Vec<String>,
+    /// Predicate on the projected columns that rows must pass to be returned
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    /// Maximum number of rows to return per file
+    limit: Option<usize>,
     /// Batch size
     batch_size: usize,
+    /// Metrics of each file, collected by the partitions that read them
+    metrics: Vec<Arc<Mutex<ParquetFileMetrics>>>,
+    /// Footers of the files that have already been read
+    footer_cache: Arc<ParquetFooterCache>,
+    /// Paths of the files that were skipped because their statistics or partition
+    /// values rule out rows that pass the predicate
+    pruned_filenames: Vec<String>,
+}
+
+/// Metadata read from the footer of a Parquet file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParquetFooter {
+    /// Schema of the file
+    pub schema: Arc<Schema>,
+    /// Number of rows in the file
+    pub num_rows: usize,
+    /// Uncompressed size of all columns in the file
+    pub total_byte_size: usize,
+    /// Null counts and minimum and maximum values of each field of the schema, combined
+    /// over all row groups
+    pub column_statistics: Vec<ColumnStatistics>,
+}
+
+/// Cache of the footers of Parquet files, so that planning repeated queries over the same
+/// files does not read their footers again. A footer is read again once the modification
+/// time of its file changes.
+#[derive(Debug, Default)]
+pub struct ParquetFooterCache {
+    footers: Mutex<HashMap<String, (SystemTime, Arc<ParquetFooter>)>>,
+}
+
+impl ParquetFooterCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the footer of a file, reading it unless it is cached for the current
+    /// modification time of the file
+    pub fn footer(&self, filename: &str) -> Result<Arc<ParquetFooter>> {
+        let modified = fs::metadata(filename)?.modified()?;
+        if let Some((cached_modified, footer)) =
+            self.footers.lock().unwrap().get(filename)
+        {
+            if *cached_modified == modified {
+                return Ok(footer.clone());
+            }
+        }
+        let footer = Arc::new(read_footer(filename)?);
+        self.footers
+            .lock()
+            .unwrap()
+            .insert(filename.to_string(), (modified, footer.clone()));
+        Ok(footer)
+    }
+
+    /// Number of files whose footers are cached
+    pub fn len(&self) -> usize {
+        self.footers.lock().unwrap().len()
+    }
+
+    /// Whether no footers are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all footers from the cache
+    pub fn clear(&self) {
+        self.footers.lock().unwrap().clear()
+    }
+}
+
+/// Read the schema and the statistics of a file from its footer
+fn read_footer(filename: &str) -> Result<ParquetFooter> {
+    let file_reader = Rc::new(SerializedFileReader::new(File::open(filename)?)?);
+    let mut arrow_reader = ParquetFileArrowReader::new(file_reader.clone());
+    let schema = arrow_reader.get_schema()?;
+    let metadata = file_reader.metadata();
+    let num_rows = metadata.file_metadata().num_rows() as usize;
+    let total_byte_size = metadata
+        .row_groups()
+        .iter()
+        .map(|row_group| row_group.total_byte_size() as usize)
+        .sum();
+    let column_statistics = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            column_statistics(metadata.row_groups(), field.name(), field.data_type())
+        })
+        .collect();
+    Ok(ParquetFooter {
+        schema: Arc::new(schema),
+        num_rows,
+        total_byte_size,
+        column_statistics,
+    })
+}
+
+/// Combine the statistics of a top-level column over the row groups of a file. The
+/// minimum and maximum are only known if every row group has them.
+fn column_statistics(
+    row_groups: &[RowGroupMetaData],
+    name: &str,
+    data_type: &DataType,
+) -> ColumnStatistics {
+    let mut null_count = Some(0);
+    let mut bounds: Option<Option<(ScalarValue, ScalarValue)>> = None;
+    for row_group in row_groups {
+        let statistics = (0..row_group.num_columns())
+            .map(|i| row_group.column(i))
+            .find(|column| column.column_path().string() == name)
+            .and_then(|column| column.statistics());
+        let statistics = match statistics {
+            Some(statistics) => statistics,
+            None => return ColumnStatistics::default(),
+        };
+        null_count = null_count.map(|n| n + statistics.null_count() as usize);
+        let row_group_bounds = min_max_value(statistics, data_type);
+        bounds = Some(match (bounds, row_group_bounds) {
+            (None, row_group_bounds) => row_group_bounds,
+            (Some(Some((min, max))), Some((row_group_min, row_group_max))) => {
+                match (min.compare(&row_group_min), max.compare(&row_group_max)) {
+                    (Some(min_cmp), Some(max_cmp)) => Some((
+                        if min_cmp == Ordering::Greater {
+                            row_group_min
+                        } else {
+                            min
+                        },
+                        if max_cmp == Ordering::Less {
+                            row_group_max
+                        } else {
+                            max
+                        },
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        });
+    }
+    let (min_value, max_value) = match bounds {
+        Some(Some((min, max))) => (Some(min), Some(max)),
+        _ => (None, None),
+    };
+    ColumnStatistics {
+        null_count,
+        distinct_count: None,
+        min_value,
+        max_value,
+    }
+}
+
+/// The minimum and maximum value of the statistics of a column chunk as values of the
+/// Arrow type of the column. Strings are only used if they were compared as unsigned
+/// bytes, which files written before the column order was recorded did not do.
+fn min_max_value(
+    statistics: &ParquetStatistics,
+    data_type: &DataType,
+) -> Option<(ScalarValue, ScalarValue)> {
+    if !statistics.has_min_max_set() {
+        return None;
+    }
+    match (statistics, data_type) {
+        (ParquetStatistics::Boolean(s), DataType::Boolean) => Some((
+            ScalarValue::Boolean(*s.min()),
+            ScalarValue::Boolean(*s.max()),
+        )),
+        (ParquetStatistics::Int32(s), DataType::Int8) => Some((
+            ScalarValue::Int8(*s.min() as i8),
+            ScalarValue::Int8(*s.max() as i8),
+        )),
+        (ParquetStatistics::Int32(s), DataType::Int16) => Some((
+            ScalarValue::Int16(*s.min() as i16),
+            ScalarValue::Int16(*s.max() as i16),
+        )),
+        (ParquetStatistics::Int32(s), DataType::Int32) => {
+            Some((ScalarValue::Int32(*s.min()), ScalarValue::Int32(*s.max())))
+        }
+        (ParquetStatistics::Int64(s), DataType::Int64) => {
+            Some((ScalarValue::Int64(*s.min()), ScalarValue::Int64(*s.max())))
+        }
+        (ParquetStatistics::Float(s), DataType::Float32)
+            if !s.min().is_nan() && !s.max().is_nan() =>
+        {
+            Some((
+                ScalarValue::Float32(*s.min()),
+                ScalarValue::Float32(*s.max()),
+            ))
+        }
+        (ParquetStatistics::Double(s), DataType::Float64)
+            if !s.min().is_nan() && !s.max().is_nan() =>
+        {
+            Some((
+                ScalarValue::Float64(*s.min()),
+                ScalarValue::Float64(*s.max()),
+            ))
+        }
+        (ParquetStatistics::ByteArray(s), DataType::Utf8)
+            if !statistics.is_min_max_deprecated() =>
+        {
+            Some((
+                ScalarValue::Utf8(s.min().as_utf8().ok()?.to_string()),
+                ScalarValue::Utf8(s.max().as_utf8().ok()?.to_string()),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The values of the columns that a file belongs to according to the `column=value`
+/// directories of its path, such as `year=2020/month=1/data.parquet`
+fn partition_values(filename: &str) -> Vec<(String, String)> {
+    match Path::new(filename).parent() {
+        Some(parent) => parent
+            .iter()
+            .filter_map(|segment| {
+                let segment = segment.to_str()?;
+                let pos = segment.find('=')?;
+                Some((segment[..pos].to_string(), segment[pos + 1..].to_string()))
+            })
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Parse the value of a partition directory as a value of the type of its column
+fn parse_partition_value(value: &str, data_type: &DataType) -> Option<ScalarValue> {
+    match data_type {
+        DataType::Utf8 => Some(ScalarValue::Utf8(value.to_string())),
+        DataType::Float32 | DataType::Float64 => {
+            ScalarValue::Float64(value.parse().ok()?).cast_to(data_type)
+        }
+        _ => ScalarValue::Int64(value.parse().ok()?).cast_to(data_type),
+    }
+}
+
+/// Metrics collected while reading a Parquet file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParquetFileMetrics {
+    /// Path of the file
+    pub filename: String,
+    /// Number of batches read from the file
+    pub batches: usize,
+    /// Number of rows read from the file
+    pub rows_read: usize,
+    /// Number of rows that passed the predicate and the limit
+    pub rows_produced: usize,
+    /// Time spent reading and filtering batches
+    pub elapsed: Duration,
 }
 
 impl ParquetExec {
-    /// Create a new Parquet reader execution plan
+    /// Create a new Parquet reader execution plan, with a projection given by the
+    /// indices of the columns in the first file
     pub fn try_new(
         path: &str,
         projection: Option<Vec<usize>>,
         batch_size: usize,
     ) -> Result<Self> {
+        Self::try_new_with_footer_cache(
+            path,
+            projection,
+            batch_size,
+            Arc::new(ParquetFooterCache::new()),
+        )
+    }
+
+    /// Create a new Parquet reader execution plan like `try_new`, which reads the
+    /// footers of the files through the given cache
+    pub fn try_new_with_footer_cache(
+        path: &str,
+        projection: Option<Vec<usize>>,
+        batch_size: usize,
+        footer_cache: Arc<ParquetFooterCache>,
+    ) -> Result<Self> {
+        let (filenames, schema) = Self::read_schema(path, &footer_cache)?;
+        let projection = match projection {
+            Some(p) => p,
+            None => (0..schema.fields().len()).collect(),
+        };
+        let columns = projection
+            .iter()
+            .map(|i| schema.field(*i).name().clone())
+            .collect();
+        Self::try_new_with_schema(filenames, &schema, columns, batch_size, footer_cache)
+    }
+
+    /// Create a new Parquet reader execution plan, with a projection given by the
+    /// names of the columns
+    pub fn try_new_with_columns(
+        path: &str,
+        columns: Option<Vec<String>>,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let footer_cache = Arc::new(ParquetFooterCache::new());
+        let (filenames, schema) = Self::read_schema(path, &footer_cache)?;
+        let columns = match columns {
+            Some(columns) => columns,
+            None => schema.fields().iter().map(|f| f.name().clone()).collect(),
+        };
+        Self::try_new_with_schema(filenames, &schema, columns, batch_size, footer_cache)
+    }
+
+    /// Find the files of a path and read the schema of the first one
+    fn read_schema(
+        path: &str,
+        footer_cache: &ParquetFooterCache,
+    ) -> Result<(Vec<String>, Arc<Schema>)> {
         let mut filenames: Vec<String> = vec![];
         common::build_file_list(path, &mut filenames, ".parquet")?;
         if filenames.is_empty() {
-            Err(ExecutionError::General("No files found".to_string()))
-        } else {
-            let file = File::open(&filenames[0])?;
-            let file_reader = Rc::new(SerializedFileReader::new(file)?);
-            let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
-            let schema = arrow_reader.get_schema()?;
-
-            let projection = match projection {
-                Some(p) => p,
-                None => (0..schema.fields().len()).collect(),
-            };
+            return Err(ExecutionError::General("No files found".to_string()));
+        }
+        let schema = footer_cache.footer(&filenames[0])?.schema.clone();
+        Ok((filenames, schema))
+    }
 
-            let projected_schema = Schema::new(
-                projection
-                    .iter()
-                    .map(|i| schema.field(*i).clone())
-                    .collect(),
-            );
-
-            Ok(Self {
-                filenames,
-                schema: Arc::new(projected_schema),
-                projection,
-                batch_size,
-            })
+    /// Only return the rows that pass a predicate on the projected columns
+    pub fn with_predicate(mut self, predicate: Arc<dyn PhysicalExpr>) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Stop reading a file once it has returned the given number of rows
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the files that cannot contain rows that pass a predicate on the projected
+    /// columns, judging by the minimum and maximum values in their footers and by the
+    /// values of the partition directories in their paths. The predicate is still
+    /// applied to the rows of the remaining files.
+    pub fn prune_files(mut self, predicate: &Expr) -> Result<Self> {
+        let mut filenames = vec![];
+        let mut metrics = vec![];
+        for (filename, file_metrics) in self.filenames.into_iter().zip(self.metrics) {
+            let footer = self.footer_cache.footer(&filename)?;
+            let partition_values = partition_values(&filename);
+            let statistics = self
+                .schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    let partition_value = partition_values
+                        .iter()
+                        .find(|(name, _)| name == field.name())
+                        .and_then(|(_, value)| {
+                            parse_partition_value(value, field.data_type())
+                        });
+                    match partition_value {
+                        Some(value) => ColumnStatistics {
+                            null_count: Some(0),
+                            distinct_count: Some(1),
+                            min_value: Some(value.clone()),
+                            max_value: Some(value),
+                        },
+                        None => footer
+                            .schema
+                            .index_of(field.name())
+                            .map(|i| footer.column_statistics[i].clone())
+                            .unwrap_or_default(),
+                    }
+                })
+                .collect::<Vec<_>>();
+            if may_match(predicate, &statistics) {
+                filenames.push(filename);
+                metrics.push(file_metrics);
+            } else {
+                self.pruned_filenames.push(filename);
+            }
+        }
+        self.filenames = filenames;
+        self.metrics = metrics;
+        Ok(self)
+    }
+
+    /// Get the paths of the files that are read, one per partition
+    pub fn filenames(&self) -> &[String] {
+        &self.filenames
+    }
+
+    /// Get the paths of the files that were skipped by `prune_files`
+    pub fn pruned_filenames(&self) -> &[String] {
+        &self.pruned_filenames
+    }
+
+    /// Get the metrics of each file collected by the last execution of the partitions
+    pub fn file_metrics(&self) -> Vec<ParquetFileMetrics> {
+        self.metrics
+            .iter()
+            .map(|metrics| metrics.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// Get statistics about the files from the metadata in their footers, without
+    /// applying the predicate or the limit. The byte size is the uncompressed size of
+    /// all columns.
+    pub fn statistics(&self) -> Result<Statistics> {
+        let mut num_rows = 0;
+        let mut total_byte_size = 0;
+        for filename in &self.filenames {
+            let footer = self.footer_cache.footer(filename)?;
+            num_rows += footer.num_rows;
+            total_byte_size += footer.total_byte_size;
         }
+        Ok(Statistics {
+            num_rows: Some(num_rows),
+            total_byte_size: Some(total_byte_size),
+            column_statistics: None,
+        })
+    }
+
+    fn try_new_with_schema(
+        filenames: Vec<String>,
+        schema: &Schema,
+        columns: Vec<String>,
+        batch_size: usize,
+        footer_cache: Arc<ParquetFooterCache>,
+    ) -> Result<Self> {
+        let projected_schema = Schema::new(
+            columns
+                .iter()
+                .map(|name| Ok(schema.field_with_name(name)?.clone()))
+                .collect::<Result<Vec<_>>>()?,
+        );
+        let metrics = filenames
+            .iter()
+            .map(|filename| {
+                Arc::new(Mutex::new(ParquetFileMetrics {
+                    filename: filename.clone(),
+                    ..Default::default()
+                }))
+            })
+            .collect();
+        Ok(Self {
+            filenames,
+            schema: Arc::new(projected_schema),
+            columns,
+            predicate: None,
+            limit: None,
+            batch_size,
+            metrics,
+            footer_cache,
+            pruned_filenames: vec![],
+        })
     }
 }
 
@@ -89,20 +517,78 @@ impl ExecutionPlan for ParquetExec {
     }
 
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        // the plan has an empty partition when all files were pruned
+        if self.filenames.is_empty() {
+            return Ok(vec![Arc::new(EmptyParquetPartition {
+                schema: self.schema.clone(),
+            })]);
+        }
         let partitions = self
             .filenames
             .iter()
-            .map(|filename| {
+            .zip(self.metrics.iter())
+            .map(|(filename, metrics)| {
+                *metrics.lock().unwrap() = ParquetFileMetrics {
+                    filename: filename.clone(),
+                    ..Default::default()
+                };
+                let scan = FileScan {
+                    schema: self.schema.clone(),
+                    predicate: self.predicate.clone(),
+                    limit: self.limit,
+                    ctx: EvaluationContext::new(),
+                    metrics: metrics.clone(),
+                };
                 Arc::new(ParquetPartition::new(
                     &filename,
-                    self.projection.clone(),
-                    self.schema.clone(),
+                    self.columns.clone(),
+                    scan,
                     self.batch_size,
                 )) as Arc<dyn Partition>
             })
             .collect();
         Ok(partitions)
     }
+
+    fn metrics(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .file_metrics()
+            .iter()
+            .map(|m| {
+                format!(
+                    "ParquetExec: file={} batches={} rows_read={} rows_produced={} \
+                     elapsed_ms={}",
+                    m.filename,
+                    m.batches,
+                    m.rows_read,
+                    m.rows_produced,
+                    m.elapsed.as_millis()
+                )
+            })
+            .collect();
+        if !self.pruned_filenames.is_empty() {
+            lines.push(format!(
+                "ParquetExec: pruned_files={} of {}",
+                self.pruned_filenames.len(),
+                self.pruned_filenames.len() + self.filenames.len()
+            ));
+        }
+        lines
+    }
+}
+
+/// The partition of a plan whose files were all pruned, which returns no batches
+struct EmptyParquetPartition {
+    schema: Arc<Schema>,
+}
+
+impl Partition for EmptyParquetPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        Ok(Arc::new(Mutex::new(common::RecordBatchIterator::new(
+            self.schema.clone(),
+            vec![],
+        ))))
+    }
 }
 
 struct ParquetPartition {
@@ -110,11 +596,12 @@ struct ParquetPartition {
 }
 
 impl ParquetPartition {
-    /// Create a new Parquet partition
-    pub fn new(
+    /// Create a new Parquet partition that reads the given columns of a file into
+    /// batches with the schema of the scan
+    fn new(
         filename: &str,
-        projection: Vec<usize>,
-        schema: Arc<Schema>,
+        columns: Vec<String>,
+        mut scan: FileScan,
         batch_size: usize,
     ) -> Self {
         // because the parquet implementation is not thread-safe, it is necessary to execute
@@ -126,6 +613,7 @@ impl ParquetPartition {
         ) = unbounded();
 
         let filename = filename.to_string();
+        let schema = scan.schema.clone();
 
         thread::spawn(move || {
             //TODO error handling, remove unwraps
@@ -138,36 +626,28 @@ impl ParquetPartition {
 
                     let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
 
-                    match arrow_reader
-                        .get_record_reader_by_columns(projection, batch_size)
-                    {
+                    let batch_reader = resolve_projection(&mut arrow_reader, &columns)
+                        .and_then(|projection| {
+                            Ok(arrow_reader
+                                .get_record_reader_by_columns(projection, batch_size)?)
+                        });
+                    match batch_reader {
                         Ok(mut batch_reader) => {
                             while let Ok(_) = request_rx.recv() {
-                                match batch_reader.next_batch() {
-                                    Ok(Some(batch)) => {
-                                        response_tx.send(Ok(Some(batch))).unwrap();
-                                    }
-                                    Ok(None) => {
-                                        response_tx.send(Ok(None)).unwrap();
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        response_tx
-                                            .send(Err(ExecutionError::General(format!(
-                                                "{:?}",
-                                                e
-                                            ))))
-                                            .unwrap();
-                                        break;
-                                    }
+                                let batch = scan.next_batch(&mut batch_reader);
+                                let done = match batch {
+                                    Ok(Some(_)) => false,
+                                    _ => true,
+                                };
+                                response_tx.send(batch).unwrap();
+                                if done {
+                                    break;
                                 }
                             }
                         }
 
                         Err(e) => {
-                            response_tx
-                                .send(Err(ExecutionError::General(format!("{:?}", e))))
-                                .unwrap();
+                            response_tx.send(Err(e)).unwrap();
                         }
                     }
                 }
@@ -190,6 +670,100 @@ impl ParquetPartition {
     }
 }
 
+/// Reads the batches of a file, applying the predicate and the limit of the plan
+struct FileScan {
+    schema: Arc<Schema>,
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    limit: Option<usize>,
+    ctx: EvaluationContext,
+    metrics: Arc<Mutex<ParquetFileMetrics>>,
+}
+
+impl FileScan {
+    /// Read the next batch that has rows passing the predicate, or `None` once the file
+    /// is exhausted or the limit is reached
+    fn next_batch<R: RecordBatchReader>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<Option<RecordBatch>> {
+        let start = Instant::now();
+        let batch = self.read_batch(reader);
+        self.metrics.lock().unwrap().elapsed += start.elapsed();
+        batch
+    }
+
+    fn read_batch<R: RecordBatchReader>(
+        &mut self,
+        reader: &mut R,
+    ) -> Result<Option<RecordBatch>> {
+        loop {
+            let produced = self.metrics.lock().unwrap().rows_produced;
+            let remaining = match self.limit {
+                Some(limit) if produced >= limit => return Ok(None),
+                Some(limit) => Some(limit - produced),
+                None => None,
+            };
+            let batch = match reader
+                .next_batch()
+                .map_err(|e| ExecutionError::General(format!("{:?}", e)))?
+            {
+                Some(batch) => conform_batch(&batch, &self.schema)?,
+                None => return Ok(None),
+            };
+            {
+                let mut metrics = self.metrics.lock().unwrap();
+                metrics.batches += 1;
+                metrics.rows_read += batch.num_rows();
+            }
+            let batch = match &self.predicate {
+                Some(predicate) => {
+                    filter_batch(&batch, predicate.as_ref(), &mut self.ctx)?
+                }
+                None => batch,
+            };
+            let batch = match remaining {
+                Some(n) if batch.num_rows() > n => truncate_batch(&batch, n)?,
+                _ => batch,
+            };
+            // batches without rows that pass the predicate are skipped
+            if batch.num_rows() > 0 {
+                self.metrics.lock().unwrap().rows_produced += batch.num_rows();
+                return Ok(Some(batch));
+            }
+        }
+    }
+}
+
+/// Find the index of each of the given columns in the schema of a file
+fn resolve_projection(
+    arrow_reader: &mut ParquetFileArrowReader,
+    columns: &[String],
+) -> Result<Vec<usize>> {
+    let schema = arrow_reader.get_schema()?;
+    columns
+        .iter()
+        .map(|name| {
+            schema.index_of(name).map_err(|_| {
+                ExecutionError::General(format!(
+                    "Column {} not found in Parquet file",
+                    name
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Convert a batch that was read from a file to the schema of the plan, matching its
+/// columns by name, so that the batches of all files have the same schema
+fn conform_batch(batch: &RecordBatch, schema: &Arc<Schema>) -> Result<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| Ok(batch.column(batch.schema().index_of(field.name())?).clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
 impl Partition for ParquetPartition {
     fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
         Ok(self.iterator.clone())
@@ -226,7 +800,15 @@ impl BatchIterator for ParquetIterator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::execution::physical_plan::expressions::{binary, col, lit};
+    use crate::logicalplan::Operator;
+    use arrow::array::Int32Array;
+    use parquet::column::writer::ColumnWriter;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::{FileWriter, RowGroupWriter, SerializedFileWriter};
+    use parquet::schema::parser::parse_message_type;
     use std::env;
+    use tempdir::TempDir;
 
     #[test]
     fn test() -> Result<()> {
@@ -257,4 +839,250 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn project_columns_by_name() -> Result<()> {
+        let tmp_dir = TempDir::new("project_columns_by_name")?;
+        // the columns of the second file are in a different order
+        write_int32_file(
+            &tmp_dir,
+            "1.parquet",
+            &[("a", vec![1, 2]), ("b", vec![3, 4])],
+        )?;
+        write_int32_file(&tmp_dir, "2.parquet", &[("b", vec![5]), ("a", vec![6])])?;
+        let path = tmp_dir.path().to_str().unwrap();
+
+        let parquet_exec = ParquetExec::try_new(path, Some(vec![1]), 1024)?;
+        let mut values = vec![];
+        for partition in parquet_exec.partitions()? {
+            let results = partition.execute()?;
+            let mut results = results.lock().unwrap();
+            while let Some(batch) = results.next()? {
+                assert_eq!(batch.schema(), &parquet_exec.schema());
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int32Array>()
+                    .unwrap();
+                values.extend((0..array.len()).map(|i| array.value(i)));
+            }
+        }
+        values.sort();
+        assert_eq!(values, vec![3, 4, 5]);
+
+        // a column that is missing from a file fails the partition of the file
+        write_int32_file(&tmp_dir, "3.parquet", &[("a", vec![7])])?;
+        let parquet_exec =
+            ParquetExec::try_new_with_columns(path, Some(vec!["b".to_string()]), 1024)?;
+        let errors = parquet_exec
+            .partitions()?
+            .iter()
+            .filter(|partition| {
+                let results = partition.execute().unwrap();
+                let mut results = results.lock().unwrap();
+                results.next().is_err()
+            })
+            .count();
+        assert_eq!(errors, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn predicate_and_limit() -> Result<()> {
+        let tmp_dir = TempDir::new("predicate_and_limit")?;
+        write_int32_file(&tmp_dir, "1.parquet", &[("a", (1..=10).collect())])?;
+        let path = tmp_dir.path().to_str().unwrap();
+
+        let parquet_exec = ParquetExec::try_new(path, None, 2)?;
+        let statistics = parquet_exec.statistics()?;
+        assert_eq!(statistics.num_rows, Some(10));
+        assert!(statistics.total_byte_size.unwrap() > 0);
+
+        let schema = parquet_exec.schema();
+        let predicate = binary(
+            col(0, schema.as_ref()),
+            Operator::Gt,
+            lit(ScalarValue::Int32(3)),
+        );
+        let parquet_exec = parquet_exec.with_predicate(predicate).with_limit(4);
+        let partitions = parquet_exec.partitions()?;
+        let results = partitions[0].execute()?;
+        let mut results = results.lock().unwrap();
+        let mut values = vec![];
+        while let Some(batch) = results.next()? {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            values.extend((0..array.len()).map(|i| array.value(i)));
+        }
+        assert_eq!(values, vec![4, 5, 6, 7]);
+
+        // the file is no longer read once the limit is reached
+        let metrics = parquet_exec.file_metrics();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].batches, 4);
+        assert_eq!(metrics[0].rows_read, 8);
+        assert_eq!(metrics[0].rows_produced, 4);
+        assert!(parquet_exec.metrics()[0].starts_with(&format!(
+            "ParquetExec: file={} batches=4 rows_read=8 rows_produced=4 elapsed_ms=",
+            parquet_exec.filenames()[0]
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn footer_cache() -> Result<()> {
+        let tmp_dir = TempDir::new("footer_cache")?;
+        write_int32_file(&tmp_dir, "1.parquet", &[("a", vec![1, 2])])?;
+        write_int32_file(&tmp_dir, "2.parquet", &[("a", vec![3])])?;
+        let path = tmp_dir.path().to_str().unwrap();
+
+        let footer_cache = Arc::new(ParquetFooterCache::new());
+        let parquet_exec = ParquetExec::try_new_with_footer_cache(
+            path,
+            None,
+            1024,
+            footer_cache.clone(),
+        )?;
+        assert_eq!(footer_cache.len(), 1);
+        assert_eq!(parquet_exec.statistics()?.num_rows, Some(3));
+        assert_eq!(footer_cache.len(), 2);
+
+        // the footers are not read again for another plan over the same files
+        let filename = parquet_exec.filenames()[0].clone();
+        let footer = footer_cache.footer(&filename)?;
+        let parquet_exec = ParquetExec::try_new_with_footer_cache(
+            path,
+            None,
+            1024,
+            footer_cache.clone(),
+        )?;
+        assert_eq!(parquet_exec.statistics()?.num_rows, Some(3));
+        assert!(Arc::ptr_eq(&footer, &footer_cache.footer(&filename)?));
+
+        // a file that is rewritten is read again
+        write_int32_file(&tmp_dir, "1.parquet", &[("a", vec![1, 2, 4, 5])])?;
+        assert_eq!(parquet_exec.statistics()?.num_rows, Some(5));
+        assert_eq!(footer_cache.len(), 2);
+
+        footer_cache.clear();
+        assert!(footer_cache.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn prune_files() -> Result<()> {
+        let tmp_dir = TempDir::new("prune_files")?;
+        fs::create_dir(tmp_dir.path().join("b=7"))?;
+        write_int32_file(
+            &tmp_dir,
+            "1.parquet",
+            &[("a", vec![1, 2, 3]), ("b", vec![1, 1, 1])],
+        )?;
+        write_int32_file(
+            &tmp_dir,
+            "2.parquet",
+            &[("a", vec![10, 11]), ("b", vec![2, 2])],
+        )?;
+        write_int32_file(&tmp_dir, "b=7/3.parquet", &[("a", vec![1]), ("b", vec![7])])?;
+        let path = tmp_dir.path().to_str().unwrap();
+
+        let footer_cache = Arc::new(ParquetFooterCache::new());
+        let filename = format!("{}/1.parquet", path);
+        let footer = footer_cache.footer(&filename)?;
+        assert_eq!(
+            footer.column_statistics[0],
+            ColumnStatistics {
+                null_count: Some(0),
+                distinct_count: None,
+                min_value: Some(ScalarValue::Int32(1)),
+                max_value: Some(ScalarValue::Int32(3)),
+            }
+        );
+
+        let predicate = |column: usize, op: Operator, value: i32| Expr::BinaryExpr {
+            left: Arc::new(Expr::Column(column)),
+            op,
+            right: Arc::new(Expr::Literal(ScalarValue::Int32(value))),
+        };
+        let plan = |predicate: Expr| -> Result<ParquetExec> {
+            ParquetExec::try_new_with_footer_cache(
+                path,
+                None,
+                1024,
+                footer_cache.clone(),
+            )?
+            .prune_files(&predicate)
+        };
+
+        let parquet_exec = plan(predicate(0, Operator::Gt, 5))?;
+        assert_eq!(parquet_exec.filenames(), &[format!("{}/2.parquet", path)]);
+        assert_eq!(parquet_exec.pruned_filenames().len(), 2);
+        assert_eq!(parquet_exec.partitions()?.len(), 1);
+        assert_eq!(
+            parquet_exec.metrics().last().unwrap(),
+            "ParquetExec: pruned_files=2 of 3"
+        );
+
+        let parquet_exec = plan(predicate(1, Operator::Eq, 7))?;
+        assert_eq!(
+            parquet_exec.filenames(),
+            &[format!("{}/b=7/3.parquet", path)]
+        );
+
+        // a plan whose files are all pruned has a partition without batches
+        let parquet_exec = plan(predicate(0, Operator::Gt, 100))?;
+        assert!(parquet_exec.filenames().is_empty());
+        let partitions = parquet_exec.partitions()?;
+        assert_eq!(partitions.len(), 1);
+        let results = partitions[0].execute()?;
+        assert!(results.lock().unwrap().next()?.is_none());
+
+        assert_eq!(
+            partition_values("/data/year=2020/month=1/part-0.parquet"),
+            vec![
+                ("year".to_string(), "2020".to_string()),
+                ("month".to_string(), "1".to_string())
+            ]
+        );
+
+        Ok(())
+    }
+
+    /// Write a Parquet file with a row group of required Int32 columns
+    fn write_int32_file(
+        dir: &TempDir,
+        name: &str,
+        columns: &[(&str, Vec<i32>)],
+    ) -> Result<()> {
+        let message_type = format!(
+            "message schema {{ {} }}",
+            columns
+                .iter()
+                .map(|(name, _)| format!("REQUIRED INT32 {};", name))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let schema = Rc::new(parse_message_type(&message_type)?);
+        let props = Rc::new(WriterProperties::builder().build());
+        let file = File::create(dir.path().join(name))?;
+        let mut writer = SerializedFileWriter::new(file, schema, props)?;
+        let mut row_group_writer = writer.next_row_group()?;
+        for (_, values) in columns {
+            if let Some(mut column_writer) = row_group_writer.next_column()? {
+                if let ColumnWriter::Int32ColumnWriter(ref mut typed) = column_writer {
+                    typed.write_batch(values, None, None)?;
+                }
+                row_group_writer.close_column(column_writer)?;
+            }
+        }
+        writer.close_row_group(row_group_writer)?;
+        writer.close()?;
+        Ok(())
+    }
 }
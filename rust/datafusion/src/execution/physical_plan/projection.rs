@@ -23,6 +23,8 @@
 use std::sync::{Arc, Mutex};
 
 use crate::error::Result;
+use crate::execution::physical_plan::adapters;
+use crate::execution::physical_plan::evaluation_context::EvaluationContext;
 use crate::execution::physical_plan::{
     BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
 };
@@ -68,6 +70,10 @@ impl ExecutionPlan for ProjectionExec {
         self.schema.clone()
     }
 
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
     /// Get the partitions for this execution plan
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         let partitions: Vec<Arc<dyn Partition>> = self
@@ -100,38 +106,20 @@ struct ProjectionPartition {
 impl Partition for ProjectionPartition {
     /// Execute the projection
     fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
-        Ok(Arc::new(Mutex::new(ProjectionIterator {
-            schema: self.schema.clone(),
-            expr: self.expr.clone(),
-            input: self.input.execute()?,
-        })))
-    }
-}
-
-/// Projection iterator
-struct ProjectionIterator {
-    schema: Arc<Schema>,
-    expr: Vec<Arc<dyn PhysicalExpr>>,
-    input: Arc<Mutex<dyn BatchIterator>>,
-}
-
-impl BatchIterator for ProjectionIterator {
-    /// Get the schema
-    fn schema(&self) -> Arc<Schema> {
-        self.schema.clone()
-    }
-
-    /// Get the next batch
-    fn next(&mut self) -> Result<Option<RecordBatch>> {
-        let mut input = self.input.lock().unwrap();
-        match input.next()? {
-            Some(batch) => {
-                let arrays: Result<Vec<_>> =
-                    self.expr.iter().map(|expr| expr.evaluate(&batch)).collect();
-                Ok(Some(RecordBatch::try_new(self.schema.clone(), arrays?)?))
-            }
-            None => Ok(None),
-        }
+        let schema = self.schema.clone();
+        let expr = self.expr.clone();
+        let mut ctx = EvaluationContext::new();
+        Ok(adapters::map_batches(
+            self.input.execute()?,
+            self.schema.clone(),
+            move |batch| {
+                let arrays = expr
+                    .iter()
+                    .map(|expr| expr.evaluate_with_context(&batch, &mut ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+            },
+        ))
     }
 }
 
@@ -0,0 +1,258 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the repartition plan, which redistributes the rows of the input partitions
+//! into a new set of partitions based on a hash of one or more expressions, so that rows
+//! with the same values end up in the same partition
+
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::common::{self, JoinHandle, RecordBatchIterator};
+use crate::execution::physical_plan::hash_aggregate::{create_key, GroupByScalar};
+use crate::execution::physical_plan::{
+    BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
+};
+use crate::execution::scheduler;
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use fnv::FnvHasher;
+
+/// The output batches of each partition, computed once when the first output
+/// partition is executed
+type RepartitionState = Arc<Mutex<Option<Vec<Vec<Arc<RecordBatch>>>>>>;
+
+/// Hash repartition execution plan
+pub struct RepartitionExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Expressions used to compute the hash of each row
+    hash_expr: Vec<Arc<dyn PhysicalExpr>>,
+    /// The number of output partitions
+    num_partitions: usize,
+}
+
+impl RepartitionExec {
+    /// Create a new hash repartition execution plan
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        hash_expr: Vec<Arc<dyn PhysicalExpr>>,
+        num_partitions: usize,
+    ) -> Result<Self> {
+        if num_partitions == 0 {
+            return Err(ExecutionError::General(
+                "Cannot repartition into zero partitions".to_string(),
+            ));
+        }
+        Ok(Self {
+            input,
+            hash_expr,
+            num_partitions,
+        })
+    }
+}
+
+impl ExecutionPlan for RepartitionExec {
+    fn schema(&self) -> Arc<Schema> {
+        // repartitioning does not change the schema of the input
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        let input = self.input.partitions()?;
+        let state: RepartitionState = Arc::new(Mutex::new(None));
+        Ok((0..self.num_partitions)
+            .map(|i| {
+                Arc::new(RepartitionPartition {
+                    schema: self.input.schema(),
+                    input: input.clone(),
+                    hash_expr: self.hash_expr.clone(),
+                    num_partitions: self.num_partitions,
+                    partition: i,
+                    state: state.clone(),
+                }) as Arc<dyn Partition>
+            })
+            .collect())
+    }
+}
+
+/// Represents a single output partition of a repartition execution plan
+struct RepartitionPartition {
+    schema: Arc<Schema>,
+    input: Vec<Arc<dyn Partition>>,
+    hash_expr: Vec<Arc<dyn PhysicalExpr>>,
+    num_partitions: usize,
+    partition: usize,
+    state: RepartitionState,
+}
+
+impl Partition for RepartitionPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        // the partition that repartitions the input waits for other threads
+        let mut state = scheduler::wait(|| self.state.lock().unwrap());
+        if state.is_none() {
+            *state = Some(repartition(
+                &self.input,
+                &self.hash_expr,
+                self.num_partitions,
+            )?);
+        }
+        let batches = match state.as_ref() {
+            Some(partitions) => partitions[self.partition].clone(),
+            None => vec![],
+        };
+        Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+            self.schema.clone(),
+            batches,
+        ))))
+    }
+}
+
+/// Execute the input partitions in parallel and distribute the resulting rows between
+/// `num_partitions` output partitions
+fn repartition(
+    input: &Vec<Arc<dyn Partition>>,
+    hash_expr: &Vec<Arc<dyn PhysicalExpr>>,
+    num_partitions: usize,
+) -> Result<Vec<Vec<Arc<RecordBatch>>>> {
+    let threads: Vec<JoinHandle<Result<Vec<Vec<RecordBatch>>>>> = input
+        .iter()
+        .map(|p| {
+            let p = p.clone();
+            let hash_expr = hash_expr.clone();
+            common::spawn(move || {
+                let mut output: Vec<Vec<RecordBatch>> = vec![vec![]; num_partitions];
+                let it = p.execute()?;
+                let mut it = it.lock().unwrap();
+                while let Some(batch) = it.next()? {
+                    let batches = hash_partition(&batch, &hash_expr, num_partitions)?;
+                    for (i, batch) in batches.into_iter().enumerate() {
+                        if batch.num_rows() > 0 {
+                            output[i].push(batch);
+                        }
+                    }
+                }
+                Ok(output)
+            })
+        })
+        .collect();
+
+    // combine the results from each thread
+    let mut combined_results: Vec<Vec<Arc<RecordBatch>>> = vec![vec![]; num_partitions];
+    for thread in threads {
+        let join = thread.join().expect("Failed to join thread");
+        let result = join?;
+        for (i, batches) in result.into_iter().enumerate() {
+            batches
+                .into_iter()
+                .for_each(|batch| combined_results[i].push(Arc::new(batch)));
+        }
+    }
+    Ok(combined_results)
+}
+
+/// Split a batch into `num_partitions` batches based on the hash of the given expressions
+pub fn hash_partition(
+    batch: &RecordBatch,
+    hash_expr: &Vec<Arc<dyn PhysicalExpr>>,
+    num_partitions: usize,
+) -> Result<Vec<RecordBatch>> {
+    let values = hash_expr
+        .iter()
+        .map(|expr| expr.evaluate(batch))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+
+    let mut indices: Vec<Vec<u32>> = vec![vec![]; num_partitions];
+    let mut key: Vec<GroupByScalar> = Vec::with_capacity(values.len());
+    for _ in 0..values.len() {
+        key.push(GroupByScalar::UInt32(0));
+    }
+    for row in 0..batch.num_rows() {
+        create_key(&values, row, &mut key)?;
+        let mut hasher = FnvHasher::default();
+        key.hash(&mut hasher);
+        let partition = (hasher.finish() % num_partitions as u64) as usize;
+        indices[partition].push(row as u32);
+    }
+
+    indices
+        .into_iter()
+        .map(|indices| {
+            let columns = common::take_columns(batch, &UInt32Array::from(indices))?;
+            Ok(RecordBatch::try_new(batch.schema().clone(), columns)?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::execution::physical_plan::expressions::col;
+    use crate::test;
+    use arrow::array::StringArray;
+    use std::collections::HashSet;
+
+    #[test]
+    fn hash_repartition() -> Result<()> {
+        let schema = test::aggr_test_schema();
+
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 1024)?;
+
+        let repartition =
+            RepartitionExec::try_new(Arc::new(csv), vec![col(0, schema.as_ref())], 3)?;
+
+        let partitions = repartition.partitions()?;
+        assert_eq!(partitions.len(), 3);
+
+        let mut row_count = 0;
+        let mut seen: HashSet<String> = HashSet::new();
+        for partition in &partitions {
+            let batches = common::collect(partition.execute()?)?;
+
+            // every value of the partitioning column must end up in a single partition
+            let mut values: HashSet<String> = HashSet::new();
+            for batch in &batches {
+                row_count += batch.num_rows();
+                let c1 = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                for i in 0..c1.len() {
+                    values.insert(c1.value(i).to_string());
+                }
+            }
+            for value in values {
+                assert!(seen.insert(value));
+            }
+        }
+
+        assert_eq!(row_count, 100);
+        assert_eq!(seen.len(), 5);
+
+        Ok(())
+    }
+}
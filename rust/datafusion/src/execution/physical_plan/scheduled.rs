@@ -0,0 +1,205 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines an execution plan that executes its input partitions only while they hold a
+//! slot of a scheduler, which limits the number of partitions that execute at the same
+//! time across all queries of a context. It is placed at the root of the plan of a
+//! query, and the partitions of the operators below it that are executed on other
+//! threads wait for slots of the same scheduler.
+
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use crate::execution::scheduler::{self, Scheduler};
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Execution plan that waits for a slot of a scheduler before executing each of its
+/// input partitions and before reading each batch from them. The slot is released as
+/// soon as the batch has been read, so that partitions of other queries can run
+/// between batches.
+pub struct ScheduledExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The scheduler that grants the slots
+    scheduler: Arc<Scheduler>,
+    /// The query that the partitions belong to, which the scheduler queues fairly
+    /// against other queries
+    query: u64,
+}
+
+impl ScheduledExec {
+    /// Create a plan that executes the input of a query with slots of a scheduler
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        scheduler: Arc<Scheduler>,
+        query: u64,
+    ) -> Self {
+        Self {
+            input,
+            scheduler,
+            query,
+        }
+    }
+}
+
+impl ExecutionPlan for ScheduledExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(self
+            .input
+            .partitions()?
+            .into_iter()
+            .map(|input| {
+                Arc::new(ScheduledPartition {
+                    input,
+                    scheduler: self.scheduler.clone(),
+                    query: self.query,
+                }) as Arc<dyn Partition>
+            })
+            .collect())
+    }
+}
+
+/// Represents a single partition of a scheduled execution plan
+struct ScheduledPartition {
+    input: Arc<dyn Partition>,
+    scheduler: Arc<Scheduler>,
+    query: u64,
+}
+
+impl Partition for ScheduledPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let input = scheduler::run_partition(&self.scheduler, self.query, || {
+            self.input.execute()
+        })?;
+        Ok(Arc::new(Mutex::new(ScheduledIterator {
+            input,
+            scheduler: self.scheduler.clone(),
+            query: self.query,
+        })))
+    }
+}
+
+/// Iterator that reads each batch of an input partition while holding a slot
+struct ScheduledIterator {
+    input: Arc<Mutex<dyn BatchIterator>>,
+    scheduler: Arc<Scheduler>,
+    query: u64,
+}
+
+impl BatchIterator for ScheduledIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.lock().unwrap().schema()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let input = &self.input;
+        scheduler::run_partition(&self.scheduler, self.query, || {
+            input.lock().unwrap().next()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::adapters::map_batches;
+    use crate::execution::physical_plan::common;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::execution::physical_plan::merge::MergeExec;
+    use crate::test;
+
+    /// Partition that records the number of running partitions of a scheduler whenever
+    /// it reads a batch
+    struct RunningPartition {
+        input: Arc<dyn Partition>,
+        scheduler: Arc<Scheduler>,
+        running: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Partition for RunningPartition {
+        fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+            let input = self.input.execute()?;
+            let schema = input.lock().unwrap().schema();
+            let scheduler = self.scheduler.clone();
+            let running = self.running.clone();
+            Ok(map_batches(input, schema, move |batch| {
+                running.lock().unwrap().push(scheduler.running());
+                Ok(batch)
+            }))
+        }
+    }
+
+    #[test]
+    fn read_partitions_with_one_slot() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+        let csv = Arc::new(CsvExec::try_new(&path, schema.clone(), true, None, 10)?);
+
+        let scheduler = Arc::new(Scheduler::try_new(1)?);
+        let plan = ScheduledExec::new(csv, scheduler.clone(), 1);
+        let merge = MergeExec::new(schema, plan.partitions()?);
+        let batches = common::collect(merge.partitions()?[0].execute()?)?;
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 100);
+        assert_eq!(scheduler.running(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn execute_partitions_of_any_operator_with_slots() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 10)?;
+
+        let scheduler = Arc::new(Scheduler::try_new(1)?);
+        let running = Arc::new(Mutex::new(vec![]));
+        let partitions = csv
+            .partitions()?
+            .into_iter()
+            .map(|input| {
+                Arc::new(RunningPartition {
+                    input,
+                    scheduler: scheduler.clone(),
+                    running: running.clone(),
+                }) as Arc<dyn Partition>
+            })
+            .collect();
+        // the merge executes its input partitions on other threads, which hold the only
+        // slot while they read batches, while the thread of the merge waits for them
+        let merge = Arc::new(MergeExec::new(schema, partitions));
+        let plan = ScheduledExec::new(merge, scheduler.clone(), 1);
+        let batches = common::collect(plan.partitions()?[0].execute()?)?;
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 100);
+
+        let running = running.lock().unwrap();
+        assert!(!running.is_empty());
+        assert!(running.iter().all(|r| *r == 1));
+        assert_eq!(scheduler.running(), 0);
+        Ok(())
+    }
+}
@@ -19,12 +19,12 @@
 
 use std::sync::{Arc, Mutex};
 
-use crate::error::{ExecutionError, Result};
+use crate::error::Result;
+use crate::execution::physical_plan::adapters;
+use crate::execution::physical_plan::evaluation_context::EvaluationContext;
 use crate::execution::physical_plan::{
     BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
 };
-use arrow::array::BooleanArray;
-use arrow::compute::filter;
 use arrow::datatypes::Schema;
 use arrow::record_batch::RecordBatch;
 
@@ -56,6 +56,10 @@ impl ExecutionPlan for SelectionExec {
         self.input.schema()
     }
 
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
     /// Get the partitions for this execution plan
     fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
         let partitions: Vec<Arc<dyn Partition>> = self
@@ -65,7 +69,6 @@ impl ExecutionPlan for SelectionExec {
             .map(|p| {
                 let expr = self.expr.clone();
                 let partition: Arc<dyn Partition> = Arc::new(SelectionPartition {
-                    schema: self.input.schema(),
                     expr,
                     input: p.clone() as Arc<dyn Partition>,
                 });
@@ -80,7 +83,6 @@ impl ExecutionPlan for SelectionExec {
 
 /// Represents a single partition of a Selection execution plan
 struct SelectionPartition {
-    schema: Arc<Schema>,
     expr: Arc<dyn PhysicalExpr>,
     input: Arc<dyn Partition>,
 }
@@ -88,57 +90,24 @@ struct SelectionPartition {
 impl Partition for SelectionPartition {
     /// Execute the Selection
     fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
-        Ok(Arc::new(Mutex::new(SelectionIterator {
-            schema: self.schema.clone(),
-            expr: self.expr.clone(),
-            input: self.input.execute()?,
-        })))
+        let expr = self.expr.clone();
+        let mut ctx = EvaluationContext::new();
+        Ok(adapters::filter_batches(
+            self.input.execute()?,
+            move |batch| expr.evaluate_with_context(batch, &mut ctx),
+        ))
     }
 }
 
-/// Selection iterator
-struct SelectionIterator {
-    schema: Arc<Schema>,
-    expr: Arc<dyn PhysicalExpr>,
-    input: Arc<Mutex<dyn BatchIterator>>,
-}
-
-impl BatchIterator for SelectionIterator {
-    /// Get the schema
-    fn schema(&self) -> Arc<Schema> {
-        self.schema.clone()
-    }
-
-    /// Get the next batch
-    fn next(&mut self) -> Result<Option<RecordBatch>> {
-        let mut input = self.input.lock().unwrap();
-        match input.next()? {
-            Some(batch) => {
-                // evaluate the selection predicate to get a boolean array
-                let predicate_result = self.expr.evaluate(&batch)?;
-
-                if let Some(f) = predicate_result.as_any().downcast_ref::<BooleanArray>()
-                {
-                    // filter each array
-                    let mut filtered_arrays = vec![];
-                    for i in 0..batch.num_columns() {
-                        let array = batch.column(i);
-                        let filtered_array = filter(array.as_ref(), f)?;
-                        filtered_arrays.push(filtered_array);
-                    }
-                    Ok(Some(RecordBatch::try_new(
-                        batch.schema().clone(),
-                        filtered_arrays,
-                    )?))
-                } else {
-                    Err(ExecutionError::InternalError(
-                        "Predicate evaluated to non-boolean value".to_string(),
-                    ))
-                }
-            }
-            None => Ok(None),
-        }
-    }
+/// Filter the rows of a batch with a predicate
+pub fn filter_batch(
+    batch: &RecordBatch,
+    expr: &dyn PhysicalExpr,
+    ctx: &mut EvaluationContext,
+) -> Result<RecordBatch> {
+    // evaluate the selection predicate to get a boolean array
+    let predicate_result = expr.evaluate_with_context(batch, ctx)?;
+    adapters::filter_record_batch(batch, predicate_result.as_ref())
 }
 
 #[cfg(test)]
@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the SORT plan, which sorts the rows of all input partitions into a single
+//! output partition
+
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::config::DEFAULT_BATCH_SIZE;
+use crate::execution::memory_manager::MemoryManager;
+use crate::execution::physical_plan::external_sort;
+use crate::execution::physical_plan::{
+    BatchIterator, ExecutionPlan, Partition, PhysicalSortExpr,
+};
+use arrow::datatypes::Schema;
+
+/// Sort execution plan
+pub struct SortExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions, in order of precedence
+    expr: Vec<PhysicalSortExpr>,
+    /// Memory manager that limits the rows held in memory, if any
+    memory_manager: Option<Arc<MemoryManager>>,
+    /// The maximum number of rows in the batches of sorted runs that are merged
+    batch_size: usize,
+}
+
+impl SortExec {
+    /// Create a new sort execution plan
+    pub fn try_new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        Ok(Self {
+            expr,
+            input,
+            memory_manager: None,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Reserve memory for the rows being sorted from a memory manager. Input partitions
+    /// that do not fit within its limit are spilled to disk in sorted runs.
+    pub fn with_memory_manager(mut self, memory_manager: Arc<MemoryManager>) -> Self {
+        self.memory_manager = Some(memory_manager);
+        self
+    }
+
+    /// Set the maximum number of rows in the batches of sorted runs that are merged
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl ExecutionPlan for SortExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(vec![Arc::new(SortPartition {
+            schema: self.input.schema(),
+            partitions: self.input.partitions()?,
+            expr: self.expr.clone(),
+            memory_manager: self.memory_manager.clone(),
+            batch_size: self.batch_size,
+        })])
+    }
+}
+
+/// Represents a single partition of a sort plan, which sorts the rows of all input
+/// partitions
+struct SortPartition {
+    schema: Arc<Schema>,
+    partitions: Vec<Arc<dyn Partition>>,
+    expr: Vec<PhysicalSortExpr>,
+    memory_manager: Option<Arc<MemoryManager>>,
+    batch_size: usize,
+}
+
+impl Partition for SortPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        external_sort::sort_partitions(
+            self.schema.clone(),
+            &self.partitions,
+            &self.expr,
+            self.memory_manager.clone(),
+            self.batch_size,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::execution::physical_plan::expressions::col;
+    use crate::test;
+    use arrow::array::{StringArray, UInt32Array};
+    use arrow::compute::SortOptions;
+
+    #[test]
+    fn sort() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let partitions = 4;
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", partitions)?;
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 1024)?;
+
+        let sort = SortExec::try_new(
+            vec![
+                // c1 ascending
+                PhysicalSortExpr {
+                    expr: col(0, schema.as_ref()),
+                    options: SortOptions::default(),
+                },
+                // c2 descending
+                PhysicalSortExpr {
+                    expr: col(1, schema.as_ref()),
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: false,
+                        ..SortOptions::default()
+                    },
+                },
+            ],
+            Arc::new(csv),
+        )?;
+
+        // the output of SortExec has a single partition
+        assert_eq!(sort.partitions()?.len(), 1);
+
+        let batches = test::execute(&sort)?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 100);
+
+        let c1 = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let c2 = batches[0]
+            .column(1)
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+
+        assert_eq!(c1.value(0), "a");
+        assert_eq!(c1.value(c1.len() - 1), "e");
+        for i in 1..c1.len() {
+            let previous = (c1.value(i - 1), c2.value(i - 1));
+            assert!(
+                previous.0 < c1.value(i)
+                    || (previous.0 == c1.value(i) && previous.1 >= c2.value(i))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines temporary files that record batches can be written to when an execution plan
+//! runs out of memory, and read back from later
+
+use std::env;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::common::RecordBatchIterator;
+use crate::execution::physical_plan::BatchIterator;
+use arrow::datatypes::Schema;
+use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+
+/// Counter used to give each spill file of this process a unique name
+static NEXT_SPILL_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary file in the Arrow IPC file format, which is deleted when dropped
+pub struct SpillFile {
+    path: PathBuf,
+    schema: Arc<Schema>,
+    /// The writer, which is created when the first batch is written
+    writer: Option<FileWriter<File>>,
+    /// Whether any batches have been written
+    written: bool,
+    num_rows: usize,
+}
+
+impl SpillFile {
+    /// Create a new spill file in the temporary directory of the system
+    pub fn new(schema: Arc<Schema>) -> Self {
+        let path = env::temp_dir().join(format!(
+            "datafusion-spill-{}-{}.arrow",
+            process::id(),
+            NEXT_SPILL_FILE.fetch_add(1, Ordering::SeqCst)
+        ));
+        Self {
+            path,
+            schema,
+            writer: None,
+            written: false,
+            num_rows: 0,
+        }
+    }
+
+    /// Append a batch to the file
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        if self.writer.is_none() {
+            if self.written {
+                return Err(ExecutionError::InternalError(
+                    "Cannot write to a spill file that has been finished".to_string(),
+                ));
+            }
+            let file = File::create(&self.path)?;
+            self.writer = Some(FileWriter::try_new(file, &self.schema)?);
+            self.written = true;
+        }
+        if let Some(ref mut writer) = self.writer {
+            writer.write(batch)?;
+        }
+        self.num_rows += batch.num_rows();
+        Ok(())
+    }
+
+    /// Finish writing the file, after which it can be read
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.finish()?;
+        }
+        Ok(())
+    }
+
+    /// Get the number of rows written to the file
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Read the batches back from the file
+    pub fn read(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        if self.writer.is_some() {
+            return Err(ExecutionError::InternalError(
+                "Cannot read from a spill file that has not been finished".to_string(),
+            ));
+        }
+        if !self.written {
+            return Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+                self.schema.clone(),
+                vec![],
+            ))));
+        }
+        let reader = FileReader::try_new(File::open(&self.path)?)?;
+        Ok(Arc::new(Mutex::new(SpillFileIterator {
+            schema: self.schema.clone(),
+            reader,
+        })))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        // the writer must be closed before the file can be removed on some platforms
+        self.writer.take();
+        if self.written {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Iterator over the batches of a spill file
+struct SpillFileIterator {
+    schema: Arc<Schema>,
+    reader: FileReader<File>,
+}
+
+impl BatchIterator for SpillFileIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        Ok(self.reader.next()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::common;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn write_and_read() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])),
+            ],
+        )?;
+
+        let mut file = SpillFile::new(schema.clone());
+        file.write(&batch)?;
+        file.write(&batch)?;
+        file.finish()?;
+        assert_eq!(file.num_rows(), 6);
+        assert!(file.path.exists());
+
+        let batches = common::collect(file.read()?)?;
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[1].num_rows(), 3);
+        let b = batches[1]
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(b.value(2), "z");
+
+        // the file is removed when it is dropped
+        let path = file.path.clone();
+        drop(file);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_empty() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let mut file = SpillFile::new(schema);
+        file.finish()?;
+        assert!(common::collect(file.read()?)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! String expressions
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::context::ExecutionContext;
+use crate::execution::physical_plan::udf::ScalarFunction;
+
+use arrow::array::{ArrayRef, StringArray};
+use arrow::compute;
+use arrow::compute::RegexOptions;
+use arrow::datatypes::{DataType, Field};
+
+use std::sync::Arc;
+
+macro_rules! string_unary_function {
+    ($NAME:expr, $FUNC:ident) => {
+        ScalarFunction::new(
+            $NAME,
+            vec![Field::new("s", DataType::Utf8, true)],
+            DataType::Utf8,
+            |args: &Vec<ArrayRef>| {
+                let s = &args[0].as_any().downcast_ref::<StringArray>();
+                match s {
+                    Some(array) => Ok(Arc::new(compute::$FUNC(array)?)),
+                    _ => Err(ExecutionError::General(format!(
+                        "Invalid data type for {}",
+                        $NAME
+                    ))),
+                }
+            },
+        )
+    };
+}
+
+/// Downcast the string arguments of a scalar function
+fn string_args<'a>(name: &str, args: &'a [ArrayRef]) -> Result<Vec<&'a StringArray>> {
+    args.iter()
+        .map(|arg| {
+            arg.as_any().downcast_ref::<StringArray>().ok_or_else(|| {
+                ExecutionError::General(format!("Invalid data type for {}", name))
+            })
+        })
+        .collect()
+}
+
+/// `regexp_match(string, pattern)` returns the substrings matched by the capture groups
+/// of the first match of a regular expression, or the whole match if it has none
+fn regexp_match() -> ScalarFunction {
+    ScalarFunction::new(
+        "regexp_match",
+        vec![
+            Field::new("string", DataType::Utf8, true),
+            Field::new("pattern", DataType::Utf8, true),
+        ],
+        DataType::List(Box::new(DataType::Utf8)),
+        |args: &Vec<ArrayRef>| {
+            let args = string_args("regexp_match", args)?;
+            let options = RegexOptions::default();
+            Ok(Arc::new(compute::regexp_match(args[0], args[1], &options)?))
+        },
+    )
+}
+
+/// `regexp_replace(string, pattern, replacement)` replaces the first match of a regular
+/// expression, where `$1` in the replacement refers to the first capture group
+fn regexp_replace() -> ScalarFunction {
+    ScalarFunction::new(
+        "regexp_replace",
+        vec![
+            Field::new("string", DataType::Utf8, true),
+            Field::new("pattern", DataType::Utf8, true),
+            Field::new("replacement", DataType::Utf8, true),
+        ],
+        DataType::Utf8,
+        |args: &Vec<ArrayRef>| {
+            let args = string_args("regexp_replace", args)?;
+            let options = RegexOptions::default();
+            Ok(Arc::new(compute::regexp_replace(
+                args[0], args[1], args[2], &options,
+            )?))
+        },
+    )
+}
+
+/// Register string scalar functions with the context. The normalization functions
+/// require the `unicode` feature.
+pub fn register_string_functions(ctx: &mut ExecutionContext) {
+    ctx.register_udf(string_unary_function!("lower", lower));
+    ctx.register_udf(string_unary_function!("upper", upper));
+    ctx.register_udf(regexp_match());
+    ctx.register_udf(regexp_replace());
+    #[cfg(feature = "unicode")]
+    {
+        ctx.register_udf(string_unary_function!("nfc", nfc));
+        ctx.register_udf(string_unary_function!("nfkc", nfkc));
+    }
+}
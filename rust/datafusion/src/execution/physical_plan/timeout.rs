@@ -0,0 +1,163 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines an execution plan that cancels its input once a query has run for longer
+//! than its maximum execution time. The deadline of a query is computed once when it
+//! starts and is shared by the plan at its root, which checks it before each batch that
+//! the query returns, and by its table scans, which check it before each batch that
+//! operators read from them.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Execution plan that returns a `Cancelled` error instead of the next batch of its
+/// input once its deadline has passed
+pub struct TimeoutExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The maximum execution time, which is reported in the error
+    max_execution_time: Duration,
+    /// The time after which no more batches are returned, shared by the plans of a query
+    deadline: Arc<Instant>,
+}
+
+impl TimeoutExec {
+    /// Create a plan that cancels its input once the deadline of its query, which has a
+    /// maximum execution time of `max_execution_time`, has passed
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        max_execution_time: Duration,
+        deadline: Arc<Instant>,
+    ) -> Self {
+        Self {
+            input,
+            max_execution_time,
+            deadline,
+        }
+    }
+}
+
+impl ExecutionPlan for TimeoutExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(self
+            .input
+            .partitions()?
+            .into_iter()
+            .map(|input| {
+                Arc::new(TimeoutPartition {
+                    input,
+                    max_execution_time: self.max_execution_time,
+                    deadline: self.deadline.clone(),
+                }) as Arc<dyn Partition>
+            })
+            .collect())
+    }
+}
+
+/// Represents a single partition of a timeout execution plan
+struct TimeoutPartition {
+    input: Arc<dyn Partition>,
+    max_execution_time: Duration,
+    deadline: Arc<Instant>,
+}
+
+impl Partition for TimeoutPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        check_deadline(&self.deadline, self.max_execution_time)?;
+        Ok(Arc::new(Mutex::new(TimeoutIterator {
+            input: self.input.execute()?,
+            max_execution_time: self.max_execution_time,
+            deadline: self.deadline.clone(),
+        })))
+    }
+}
+
+/// Iterator over the batches of an input partition until the deadline has passed
+struct TimeoutIterator {
+    input: Arc<Mutex<dyn BatchIterator>>,
+    max_execution_time: Duration,
+    deadline: Arc<Instant>,
+}
+
+impl BatchIterator for TimeoutIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.input.lock().unwrap().schema()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        check_deadline(&self.deadline, self.max_execution_time)?;
+        self.input.lock().unwrap().next()
+    }
+}
+
+fn check_deadline(deadline: &Instant, max_execution_time: Duration) -> Result<()> {
+    if Instant::now() >= *deadline {
+        Err(ExecutionError::Cancelled(format!(
+            "Query exceeded its maximum execution time of {} ms",
+            max_execution_time.as_millis()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::physical_plan::common;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::test;
+
+    #[test]
+    fn cancel_after_deadline() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+        let csv = Arc::new(CsvExec::try_new(&path, schema, true, None, 1024)?);
+
+        let max_execution_time = Duration::from_secs(3600);
+        let deadline = Arc::new(Instant::now() + max_execution_time);
+        let plan = TimeoutExec::new(csv.clone(), max_execution_time, deadline);
+        let partitions = plan.partitions()?;
+        assert_eq!(partitions.len(), 4);
+        let batches = common::collect(partitions[0].execute()?)?;
+        assert_eq!(batches.len(), 1);
+
+        let max_execution_time = Duration::from_millis(0);
+        let deadline = Arc::new(Instant::now());
+        let plan = TimeoutExec::new(csv, max_execution_time, deadline);
+        match plan.partitions()?[0].execute() {
+            Err(ExecutionError::Cancelled(message)) => {
+                assert_eq!(message, "Query exceeded its maximum execution time of 0 ms")
+            }
+            _ => panic!("expected the query to be cancelled"),
+        }
+        Ok(())
+    }
+}
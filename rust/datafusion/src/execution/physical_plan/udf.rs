@@ -40,6 +40,9 @@ pub struct ScalarFunction {
     pub return_type: DataType,
     /// UDF implementation
     pub fun: ScalarUdf,
+    /// Whether the function always returns the same result for the same arguments, so
+    /// that calls with constant arguments can be evaluated once per query
+    pub deterministic: bool,
 }
 
 impl ScalarFunction {
@@ -55,8 +58,16 @@ impl ScalarFunction {
             args,
             return_type,
             fun,
+            deterministic: true,
         }
     }
+
+    /// Set whether the function always returns the same result for the same arguments,
+    /// which is assumed by default
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
 }
 
 /// Scalar UDF Physical Expression
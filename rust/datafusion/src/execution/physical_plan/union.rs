@@ -0,0 +1,191 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the UNION plan, which appends the rows of several inputs by combining their
+//! partitions
+
+use std::sync::{Arc, Mutex};
+
+use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::{BatchIterator, ExecutionPlan, Partition};
+use arrow::compute::cast;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+/// Union execution plan, whose partitions are the partitions of all of its inputs
+pub struct UnionExec {
+    /// The inputs, whose columns are matched by position
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    /// The output schema, which the columns of each input are cast to
+    schema: Arc<Schema>,
+}
+
+impl UnionExec {
+    /// Create a union of the given inputs with the given output schema. Each input must
+    /// have as many columns as the schema, and columns whose type differs from the type
+    /// in the schema are cast to it.
+    pub fn try_new(
+        inputs: Vec<Arc<dyn ExecutionPlan>>,
+        schema: Arc<Schema>,
+    ) -> Result<Self> {
+        if inputs.is_empty() {
+            return Err(ExecutionError::General(
+                "Union requires at least one input".to_string(),
+            ));
+        }
+        for input in &inputs {
+            let fields = input.schema().fields().len();
+            if fields != schema.fields().len() {
+                return Err(ExecutionError::General(format!(
+                    "Union input has {} columns but {} were expected",
+                    fields,
+                    schema.fields().len()
+                )));
+            }
+        }
+        Ok(Self { inputs, schema })
+    }
+}
+
+impl ExecutionPlan for UnionExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inputs.clone()
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        let mut partitions: Vec<Arc<dyn Partition>> = vec![];
+        for input in &self.inputs {
+            for partition in input.partitions()? {
+                partitions.push(Arc::new(UnionPartition {
+                    schema: self.schema.clone(),
+                    input: partition,
+                }));
+            }
+        }
+        Ok(partitions)
+    }
+}
+
+/// Represents a single partition of an input of a union
+struct UnionPartition {
+    schema: Arc<Schema>,
+    input: Arc<dyn Partition>,
+}
+
+impl Partition for UnionPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        Ok(Arc::new(Mutex::new(UnionIterator {
+            schema: self.schema.clone(),
+            input: self.input.execute()?,
+        })))
+    }
+}
+
+/// Iterator over the batches of an input partition of a union, which converts them to
+/// the schema of the union
+struct UnionIterator {
+    schema: Arc<Schema>,
+    input: Arc<Mutex<dyn BatchIterator>>,
+}
+
+impl BatchIterator for UnionIterator {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn next(&mut self) -> Result<Option<RecordBatch>> {
+        let mut input = self.input.lock().unwrap();
+        match input.next()? {
+            Some(batch) => {
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .zip(self.schema.fields().iter())
+                    .map(|(column, field)| {
+                        if column.data_type() == field.data_type() {
+                            Ok(column.clone())
+                        } else {
+                            Ok(cast(column, field.data_type())?)
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::execution::physical_plan::expressions::{col, CastExpr};
+    use crate::execution::physical_plan::projection::ProjectionExec;
+    use crate::test;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn union_partitions() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+
+        // c2 is a UInt32 column in the first input and an Int64 column in the second
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 1024)?;
+        let first = ProjectionExec::try_new(vec![col(1, &schema)], Arc::new(csv))?;
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 1024)?;
+        let second = ProjectionExec::try_new(
+            vec![Arc::new(CastExpr::try_new(
+                col(1, &schema),
+                &schema,
+                DataType::Int64,
+            )?)],
+            Arc::new(csv),
+        )?;
+
+        let union_schema =
+            Arc::new(Schema::new(vec![Field::new("c2", DataType::Int64, false)]));
+        let union = UnionExec::try_new(
+            vec![Arc::new(first), Arc::new(second)],
+            union_schema.clone(),
+        )?;
+
+        // the partitions of both inputs are kept
+        assert_eq!(union.partitions()?.len(), 8);
+
+        let batches = test::execute(&union)?;
+        let mut row_count = 0;
+        for batch in &batches {
+            assert_eq!(batch.schema().as_ref(), union_schema.as_ref());
+            assert!(batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .is_some());
+            row_count += batch.num_rows();
+        }
+        assert_eq!(row_count, 200);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,822 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the window plan, which collects all input partitions and evaluates window
+//! functions over them, appending a column for each function to the input columns
+
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use crate::error::Result;
+use crate::execution::physical_plan::common::{self, JoinHandle, RecordBatchIterator};
+use crate::execution::physical_plan::external_sort::combine_batches;
+use crate::execution::physical_plan::{
+    AggregateExpr, BatchIterator, ExecutionPlan, Partition, PhysicalExpr,
+    PhysicalSortExpr, WindowExpr,
+};
+use crate::logicalplan::{WindowFrame, WindowFrameBound, WindowFrameUnits};
+use arrow::array::{ArrayRef, UInt64Array};
+use arrow::compute::{lexsort_to_indices, SortColumn};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Window execution plan
+pub struct WindowAggExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Window function expressions
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    /// Schema of the input columns followed by the window function columns
+    schema: Arc<Schema>,
+}
+
+impl WindowAggExec {
+    /// Create a new window execution plan
+    pub fn try_new(
+        window_expr: Vec<Arc<dyn WindowExpr>>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        let input_schema = input.schema();
+        let mut fields = input_schema.fields().clone();
+        for expr in &window_expr {
+            fields.push(Field::new(
+                &expr.name(),
+                expr.data_type(&input_schema)?,
+                true,
+            ));
+        }
+        Ok(Self {
+            input,
+            window_expr,
+            schema: Arc::new(Schema::new(fields)),
+        })
+    }
+}
+
+impl ExecutionPlan for WindowAggExec {
+    fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn partitions(&self) -> Result<Vec<Arc<dyn Partition>>> {
+        Ok(vec![Arc::new(WindowAggPartition {
+            schema: self.schema.clone(),
+            input_schema: self.input.schema(),
+            partitions: self.input.partitions()?,
+            window_expr: self.window_expr.clone(),
+        })])
+    }
+}
+
+/// Represents a single partition of a window plan, which evaluates the window functions
+/// over the rows of all input partitions
+struct WindowAggPartition {
+    schema: Arc<Schema>,
+    input_schema: Arc<Schema>,
+    partitions: Vec<Arc<dyn Partition>>,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+}
+
+impl Partition for WindowAggPartition {
+    fn execute(&self) -> Result<Arc<Mutex<dyn BatchIterator>>> {
+        let threads: Vec<JoinHandle<Result<Vec<RecordBatch>>>> = self
+            .partitions
+            .iter()
+            .map(|p| {
+                let p = p.clone();
+                common::spawn(move || common::collect(p.execute()?))
+            })
+            .collect();
+
+        let mut batches = vec![];
+        for thread in threads {
+            let join = thread.join().expect("Failed to join thread");
+            batches.extend(join?.into_iter().filter(|b| b.num_rows() > 0));
+        }
+
+        let output = if batches.is_empty() {
+            vec![]
+        } else {
+            let batch = combine_batches(&batches, self.input_schema.clone())?;
+            let mut columns = batch.columns().to_vec();
+            for expr in &self.window_expr {
+                columns.push(expr.evaluate(&batch)?);
+            }
+            vec![Arc::new(RecordBatch::try_new(
+                self.schema.clone(),
+                columns,
+            )?)]
+        };
+
+        Ok(Arc::new(Mutex::new(RecordBatchIterator::new(
+            self.schema.clone(),
+            output,
+        ))))
+    }
+}
+
+/// The rows of a batch in the order of their window partitions and of the ORDER BY
+/// clause within each partition
+pub struct WindowOrder {
+    /// The indices of the rows of the batch, in sorted order
+    pub indices: Vec<usize>,
+    /// The ranges of positions in `indices` that hold the rows of each partition
+    pub partitions: Vec<Range<usize>>,
+    /// Whether the row at each position in `indices` is a peer of the row before it,
+    /// which is the case when both are in the same partition and have equal ORDER BY
+    /// values
+    pub peers: Vec<bool>,
+}
+
+impl WindowOrder {
+    /// Sort the rows of a batch by the partitioning expressions followed by the sort
+    /// expressions of a window
+    pub fn try_new(
+        batch: &RecordBatch,
+        partition_by: &[Arc<dyn PhysicalExpr>],
+        order_by: &[PhysicalSortExpr],
+    ) -> Result<Self> {
+        let partition_columns = partition_by
+            .iter()
+            .map(|e| e.evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let order_columns = order_by
+            .iter()
+            .map(|e| e.expr.evaluate(batch))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut sort_columns: Vec<SortColumn> = partition_columns
+            .iter()
+            .map(|values| SortColumn {
+                values: values.clone(),
+                options: None,
+            })
+            .collect();
+        sort_columns.extend(order_columns.iter().zip(order_by.iter()).map(
+            |(values, e)| SortColumn {
+                values: values.clone(),
+                options: Some(e.options),
+            },
+        ));
+        let indices: Vec<usize> = if sort_columns.is_empty() {
+            (0..batch.num_rows()).collect()
+        } else {
+            let indices = lexsort_to_indices(&sort_columns)?;
+            (0..indices.len())
+                .map(|i| indices.value(i) as usize)
+                .collect()
+        };
+
+        let mut partitions = vec![];
+        let mut peers = Vec::with_capacity(indices.len());
+        let mut start = 0;
+        for i in 0..indices.len() {
+            let new_partition =
+                i > 0 && !rows_equal(&partition_columns, indices[i - 1], indices[i])?;
+            if new_partition {
+                partitions.push(start..i);
+                start = i;
+            }
+            peers.push(
+                i > start && rows_equal(&order_columns, indices[i - 1], indices[i])?,
+            );
+        }
+        if !indices.is_empty() {
+            partitions.push(start..indices.len());
+        }
+
+        Ok(Self {
+            indices,
+            partitions,
+            peers,
+        })
+    }
+
+    /// The range of positions in `indices` of the peers of the row at each position,
+    /// including the row itself
+    pub fn peer_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges = Vec::with_capacity(self.indices.len());
+        let mut start = 0;
+        for i in 0..self.indices.len() {
+            if !self.peers[i] {
+                start = i;
+            }
+            ranges.push(start..self.indices.len());
+        }
+        let mut end = self.indices.len();
+        for i in (0..self.indices.len()).rev() {
+            ranges[i].end = end;
+            if !self.peers[i] {
+                end = i;
+            }
+        }
+        ranges
+    }
+}
+
+/// Whether two rows have the same values in each of the given columns, where nulls are
+/// equal to each other
+fn rows_equal(columns: &[ArrayRef], left: usize, right: usize) -> Result<bool> {
+    for column in columns {
+        if common::get_scalar_value(column, left)?
+            != common::get_scalar_value(column, right)?
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// The ranking window functions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankType {
+    /// The position of a row within its partition, starting at 1
+    RowNumber,
+    /// The position of the first peer of a row within its partition, so that there are
+    /// gaps after rows with peers
+    Rank,
+    /// The number of distinct ORDER BY values up to a row within its partition, so that
+    /// there are no gaps
+    DenseRank,
+    /// The number of the bucket of a row, when the rows of its partition are divided
+    /// into the given number of buckets that differ in size by at most one row
+    Ntile(u64),
+}
+
+/// ROW_NUMBER, RANK, DENSE_RANK or NTILE window function
+pub struct RankExpr {
+    name: String,
+    rank_type: RankType,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    order_by: Vec<PhysicalSortExpr>,
+}
+
+impl RankExpr {
+    /// Create a new ranking window function
+    pub fn new(
+        name: &str,
+        rank_type: RankType,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        order_by: Vec<PhysicalSortExpr>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            rank_type,
+            partition_by,
+            order_by,
+        }
+    }
+}
+
+impl WindowExpr for RankExpr {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn data_type(&self, _input_schema: &Schema) -> Result<DataType> {
+        Ok(DataType::UInt64)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let order = WindowOrder::try_new(batch, &self.partition_by, &self.order_by)?;
+        let mut values = vec![0; batch.num_rows()];
+        for partition in &order.partitions {
+            let mut rank = 0;
+            for (n, i) in partition.clone().enumerate() {
+                rank = match self.rank_type {
+                    RankType::RowNumber => n as u64 + 1,
+                    RankType::Rank if order.peers[i] => rank,
+                    RankType::Rank => n as u64 + 1,
+                    RankType::DenseRank if order.peers[i] => rank,
+                    RankType::DenseRank => rank + 1,
+                    RankType::Ntile(buckets) => {
+                        ntile(n as u64, partition.len() as u64, buckets)
+                    }
+                };
+                values[order.indices[i]] = rank;
+            }
+        }
+        Ok(Arc::new(UInt64Array::from(values)))
+    }
+}
+
+/// The bucket of the row at position `n` of a partition with `num_rows` rows, where the
+/// first `num_rows % buckets` buckets have one additional row
+fn ntile(n: u64, num_rows: u64, buckets: u64) -> u64 {
+    let size = num_rows / buckets;
+    let larger = num_rows % buckets;
+    if n < larger * (size + 1) {
+        n / (size + 1) + 1
+    } else {
+        larger + (n - larger * (size + 1)) / size + 1
+    }
+}
+
+/// The window functions that return the value of an expression for another row of the
+/// window partition
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    /// The row that is the given number of rows before the current row
+    Lag(usize),
+    /// The row that is the given number of rows after the current row
+    Lead(usize),
+    /// The first row of the window frame
+    FirstValue,
+    /// The last row of the window frame
+    LastValue,
+}
+
+/// LAG, LEAD, FIRST_VALUE or LAST_VALUE window function
+pub struct ValueExpr {
+    name: String,
+    value_type: ValueType,
+    expr: Arc<dyn PhysicalExpr>,
+    default: Option<Arc<dyn PhysicalExpr>>,
+    partition_by: Vec<Arc<dyn PhysicalExpr>>,
+    order_by: Vec<PhysicalSortExpr>,
+    window_frame: Option<WindowFrame>,
+}
+
+impl ValueExpr {
+    /// Create a new window function that returns the value of an expression for
+    /// another row. Only FIRST_VALUE and LAST_VALUE use the window frame.
+    pub fn new(
+        name: &str,
+        value_type: ValueType,
+        expr: Arc<dyn PhysicalExpr>,
+        partition_by: Vec<Arc<dyn PhysicalExpr>>,
+        order_by: Vec<PhysicalSortExpr>,
+        window_frame: Option<WindowFrame>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            value_type,
+            expr,
+            default: None,
+            partition_by,
+            order_by,
+            window_frame,
+        }
+    }
+
+    /// Use the value of an expression for the current row when there is no row at the
+    /// offset of LAG or LEAD, instead of null
+    pub fn with_default(mut self, default: Arc<dyn PhysicalExpr>) -> Self {
+        self.default = Some(default);
+        self
+    }
+}
+
+impl WindowExpr for ValueExpr {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn data_type(&self, input_schema: &Schema) -> Result<DataType> {
+        self.expr.data_type(input_schema)
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let order = WindowOrder::try_new(batch, &self.partition_by, &self.order_by)?;
+        let peer_ranges = order.peer_ranges();
+        let input = self.expr.evaluate(batch)?;
+        let default = match &self.default {
+            Some(default) => Some(default.evaluate(batch)?),
+            None => None,
+        };
+        let frame = self
+            .window_frame
+            .unwrap_or_else(|| WindowFrame::default_for(!self.order_by.is_empty()));
+
+        let mut values = vec![None; batch.num_rows()];
+        let value_at = |j: usize| common::get_scalar_value(&input, order.indices[j]);
+        for partition in &order.partitions {
+            // the start and the end of the frames never decrease from one row to the
+            // next, so that the frames can slide over the partition with a single
+            // accumulator, to which the rows that enter the frame are added and from
+            // which the rows that leave it are retracted
+            let accumulator = self.aggregate.create_accumulator();
+            let sliding = match frame.start_bound {
+                WindowFrameBound::Preceding(None) => true,
+                _ => accumulator.borrow().supports_retract(),
+            };
+            if sliding {
+                let (mut frame_start, mut frame_end) = (partition.start, partition.start);
+                for i in partition.clone() {
+                    let (start, end) = frame_range(&frame, partition, &peer_ranges[i], i);
+                    let mut accumulator = accumulator.borrow_mut();
+                    for j in frame_end..end {
+                        accumulator.accumulate_scalar(value_at(j)?)?;
+                    }
+                    for j in frame_start..start {
+                        accumulator.retract_scalar(value_at(j)?)?;
+                    }
+                    frame_start = start;
+                    frame_end = end;
+                    values[order.indices[i]] = accumulator.get_value()?;
+                }
+            } else if frame.end_bound == WindowFrameBound::Following(None) {
+                // frames that end with the partition only grow from one row to the
+                // previous one, so that they are accumulated in reverse
+                let mut frame_start = partition.end;
+                for i in partition.clone().rev() {
+                    let (start, _) = frame_range(&frame, partition, &peer_ranges[i], i);
+                    let mut accumulator = accumulator.borrow_mut();
+                    for j in (start..frame_start).rev() {
+                        accumulator.accumulate_scalar(value_at(j)?)?;
+                    }
+                    frame_start = frame_start.min(start);
+                    values[order.indices[i]] = accumulator.get_value()?;
+                }
+            } else {
+                // the rows of bounded frames of aggregates that cannot retract values,
+                // such as MIN and MAX, are accumulated for each frame
+                for i in partition.clone() {
+                    let (start, end) = frame_range(&frame, partition, &peer_ranges[i], i);
+                    let accumulator = self.aggregate.create_accumulator();
+                    let mut accumulator = accumulator.borrow_mut();
+                    for j in start..end {
+                        accumulator.accumulate_scalar(value_at(j)?)?;
+                    }
+                    values[order.indices[i]] = accumulator.get_value()?;
+                }
+            }
+        }
+        common::scalars_to_array(&self.data_type(&batch.schema())?, &values)
+    }
+}
+
+/// The range of positions in the sorted rows that make up the frame of the row at
+/// position `i`, given the positions of its partition and of its peers
+fn frame_range(
+    frame: &WindowFrame,
+    partition: &Range<usize>,
+    peers: &Range<usize>,
+    i: usize,
+) -> (usize, usize) {
+    let is_range = frame.units == WindowFrameUnits::Range;
+    let start = match frame.start_bound {
+        WindowFrameBound::Preceding(None) => partition.start,
+        WindowFrameBound::Preceding(Some(n)) => {
+            i.saturating_sub(n as usize).max(partition.start)
+        }
+        WindowFrameBound::CurrentRow if is_range => peers.start,
+        WindowFrameBound::CurrentRow => i,
+        WindowFrameBound::Following(Some(n)) => (i + n as usize).min(partition.end),
+        WindowFrameBound::Following(None) => partition.end,
+    };
+    // the end is exclusive
+    let end = match frame.end_bound {
+        WindowFrameBound::Preceding(None) => partition.start,
+        WindowFrameBound::Preceding(Some(n)) => {
+            (i + 1).saturating_sub(n as usize).max(partition.start)
+        }
+        WindowFrameBound::CurrentRow if is_range => peers.end,
+        WindowFrameBound::CurrentRow => i + 1,
+        WindowFrameBound::Following(Some(n)) => (i + 1 + n as usize).min(partition.end),
+        WindowFrameBound::Following(None) => partition.end,
+    };
+    (start, end.max(start))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::execution::physical_plan::csv::CsvExec;
+    use crate::execution::physical_plan::expressions::{col, lit, Avg, Count, Max, Sum};
+    use crate::logicalplan::ScalarValue;
+    use crate::test;
+    use arrow::array::{Float64Array, Int32Array, Int64Array};
+    use arrow::compute::SortOptions;
+
+    #[test]
+    fn rank_functions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 1, 1, 2, 1])),
+                Arc::new(Int32Array::from(vec![
+                    Some(5),
+                    Some(1),
+                    Some(3),
+                    Some(5),
+                    None,
+                    Some(7),
+                ])),
+            ],
+        )?;
+
+        // partitioned by a, ordered by b descending with nulls last
+        let rank = |rank_type| -> Result<Vec<u64>> {
+            let expr = RankExpr::new(
+                "rank",
+                rank_type,
+                vec![col(0, &schema)],
+                vec![PhysicalSortExpr {
+                    expr: col(1, &schema),
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: false,
+                        ..SortOptions::default()
+                    },
+                }],
+            );
+            let values = expr.evaluate(&batch)?;
+            let values = values.as_any().downcast_ref::<UInt64Array>().unwrap();
+            Ok(values.value_slice(0, values.len()).to_vec())
+        };
+
+        let row_number = rank(RankType::RowNumber)?;
+        // the order of peers is not defined
+        assert!(
+            row_number == vec![2, 1, 4, 3, 2, 1] || row_number == vec![3, 1, 4, 2, 2, 1]
+        );
+        assert_eq!(rank(RankType::Rank)?, vec![2, 1, 4, 2, 2, 1]);
+        assert_eq!(rank(RankType::DenseRank)?, vec![2, 1, 3, 2, 2, 1]);
+
+        // the partition with a = 1 has buckets of 2, 1 and 1 rows
+        let ntile = rank(RankType::Ntile(3))?;
+        assert_eq!((ntile[1], ntile[2], ntile[4], ntile[5]), (1, 3, 2, 1));
+        assert_eq!(ntile[0] + ntile[3], 3);
+        // with more buckets than rows, each row has its own bucket
+        assert_eq!(rank(RankType::Ntile(10))?, row_number);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_window_frames() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 2, 1, 1, 1])),
+                Arc::new(Int32Array::from(vec![3, 1, 4, 5, 2, 2])),
+            ],
+        )?;
+
+        // SUM(b) partitioned by a, ordered by b
+        let sum = |window_frame| -> Result<Vec<i64>> {
+            let expr = AggregateWindowExpr::new(
+                "SUM",
+                Arc::new(Sum::new(col(1, &schema))),
+                vec![col(0, &schema)],
+                vec![PhysicalSortExpr {
+                    expr: col(1, &schema),
+                    options: SortOptions::default(),
+                }],
+                window_frame,
+            );
+            let values = expr.evaluate(&batch)?;
+            let values = values.as_any().downcast_ref::<Int64Array>().unwrap();
+            Ok(values.value_slice(0, values.len()).to_vec())
+        };
+
+        // the default frame includes the peers of the current row
+        assert_eq!(sum(None)?, vec![8, 1, 4, 13, 5, 5]);
+
+        let frame = WindowFrame::try_new(
+            WindowFrameUnits::Rows,
+            WindowFrameBound::Preceding(Some(1)),
+            WindowFrameBound::CurrentRow,
+        )?;
+        let values = sum(Some(frame))?;
+        assert_eq!(values[..4], [5, 1, 4, 8]);
+        // the peers with b = 2 are in either order
+        assert_eq!(values[4] + values[5], 7);
+
+        let frame = WindowFrame::try_new(
+            WindowFrameUnits::Rows,
+            WindowFrameBound::CurrentRow,
+            WindowFrameBound::Following(None),
+        )?;
+        let values = sum(Some(frame))?;
+        assert_eq!((values[0], values[1], values[2], values[3]), (8, 13, 4, 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sliding_frames_of_large_partitions() -> Result<()> {
+        // the frames are evaluated incrementally, so that the time to evaluate them does
+        // not grow with the square of the number of rows of a partition
+        let n = 100_000;
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(
+                (0..n as i32).collect::<Vec<_>>(),
+            ))],
+        )?;
+        let evaluate = |aggregate: Arc<dyn AggregateExpr>,
+                        start_bound,
+                        end_bound|
+         -> Result<ArrayRef> {
+            let frame =
+                WindowFrame::try_new(WindowFrameUnits::Rows, start_bound, end_bound)?;
+            let expr = AggregateWindowExpr::new(
+                "w",
+                aggregate,
+                vec![],
+                vec![PhysicalSortExpr {
+                    expr: col(0, &schema),
+                    options: SortOptions::default(),
+                }],
+                Some(frame),
+            );
+            expr.evaluate(&batch)
+        };
+
+        // values are retracted from the sums of the frames as they slide
+        let sums = evaluate(
+            Arc::new(Sum::new(col(0, &schema))),
+            WindowFrameBound::Preceding(Some(3)),
+            WindowFrameBound::CurrentRow,
+        )?;
+        let sums = sums.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!((sums.value(0), sums.value(2)), (0, 3));
+        assert_eq!(sums.value(n - 1), 4 * (n as i64 - 1) - 6);
+
+        let counts = evaluate(
+            Arc::new(Count::new(col(0, &schema))),
+            WindowFrameBound::CurrentRow,
+            WindowFrameBound::Following(None),
+        )?;
+        let counts = counts.as_any().downcast_ref::<UInt64Array>().unwrap();
+        assert_eq!((counts.value(0), counts.value(n - 1)), (n as u64, 1));
+
+        let averages = evaluate(
+            Arc::new(Avg::new(col(0, &schema))),
+            WindowFrameBound::Preceding(Some(1)),
+            WindowFrameBound::Following(Some(1)),
+        )?;
+        let averages = averages.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!((averages.value(0), averages.value(5)), (0.5, 5.0));
+
+        // frames that end with the partition are accumulated in reverse by aggregates
+        // that cannot retract values
+        let maxima = evaluate(
+            Arc::new(Max::new(col(0, &schema))),
+            WindowFrameBound::CurrentRow,
+            WindowFrameBound::Following(None),
+        )?;
+        let maxima = maxima.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(
+            (maxima.value(0), maxima.value(n - 1)),
+            (n as i32 - 1, n as i32 - 1)
+        );
+        let maxima = evaluate(
+            Arc::new(Max::new(col(0, &schema))),
+            WindowFrameBound::Preceding(Some(2)),
+            WindowFrameBound::Preceding(Some(1)),
+        )?;
+        assert!(maxima.is_null(0));
+        let maxima = maxima.as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(maxima.value(n - 1), n as i32 - 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn value_functions() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 2, 1])),
+                Arc::new(Int32Array::from(vec![3, 1, 4, 2])),
+            ],
+        )?;
+
+        // the value of b, partitioned by a and ordered by b
+        let value = |value_type, default, window_frame| -> Result<Vec<Option<i32>>> {
+            let mut expr = ValueExpr::new(
+                "value",
+                value_type,
+                col(1, &schema),
+                vec![col(0, &schema)],
+                vec![PhysicalSortExpr {
+                    expr: col(1, &schema),
+                    options: SortOptions::default(),
+                }],
+                window_frame,
+            );
+            if let Some(default) = default {
+                expr = expr.with_default(lit(ScalarValue::Int32(default)));
+            }
+            let values = expr.evaluate(&batch)?;
+            let values = values.as_any().downcast_ref::<Int32Array>().unwrap();
+            Ok((0..values.len())
+                .map(|i| {
+                    if values.is_null(i) {
+                        None
+                    } else {
+                        Some(values.value(i))
+                    }
+                })
+                .collect())
+        };
+
+        assert_eq!(
+            value(ValueType::Lag(1), None, None)?,
+            vec![Some(2), None, None, Some(1)]
+        );
+        assert_eq!(
+            value(ValueType::Lead(1), Some(0), None)?,
+            vec![Some(0), Some(2), Some(0), Some(3)]
+        );
+        assert_eq!(
+            value(ValueType::Lag(2), None, None)?,
+            vec![Some(1), None, None, None]
+        );
+        assert_eq!(
+            value(ValueType::FirstValue, None, None)?,
+            vec![Some(1), Some(1), Some(4), Some(1)]
+        );
+        // the default frame ends at the current row
+        assert_eq!(
+            value(ValueType::LastValue, None, None)?,
+            vec![Some(3), Some(1), Some(4), Some(2)]
+        );
+        let frame = WindowFrame::try_new(
+            WindowFrameUnits::Rows,
+            WindowFrameBound::CurrentRow,
+            WindowFrameBound::Following(None),
+        )?;
+        assert_eq!(
+            value(ValueType::LastValue, None, Some(frame))?,
+            vec![Some(3), Some(3), Some(4), Some(3)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn window_agg() -> Result<()> {
+        let schema = test::aggr_test_schema();
+        let partitions = 4;
+        let path = test::create_partitioned_csv("aggregate_test_100.csv", partitions)?;
+        let csv = CsvExec::try_new(&path, schema.clone(), true, None, 1024)?;
+
+        // number the rows of each value of c1
+        let window = WindowAggExec::try_new(
+            vec![Arc::new(RankExpr::new(
+                "ROW_NUMBER",
+                RankType::RowNumber,
+                vec![col(0, &schema)],
+                vec![],
+            ))],
+            Arc::new(csv),
+        )?;
+
+        // the output of WindowAggExec has a single partition with an additional column
+        assert_eq!(window.partitions()?.len(), 1);
+        assert_eq!(window.schema().fields().len(), schema.fields().len() + 1);
+
+        let batches = test::execute(&window)?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 100);
+
+        let row_number = batches[0]
+            .column(schema.fields().len())
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        let mut values = row_number.value_slice(0, 100).to_vec();
+        values.sort();
+        // each of the five values of c1 starts with row number 1
+        assert_eq!(values[..5], [1, 1, 1, 1, 1]);
+        assert_eq!(values[5], 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Limits the number of partitions that execute concurrently across all queries of a
+//! context, so that many small queries can run without oversubscribing the CPU.
+//!
+//! A thread holds a slot while it executes a partition of a query, whichever operator
+//! the partition belongs to. Partitions that are executed on new threads with
+//! `common::spawn` wait for a slot of the same scheduler, and a thread releases its
+//! slot while it waits for other partitions, so that it never holds a slot that the
+//! partitions it waits for need.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::error::{ExecutionError, Result};
+
+/// Grants a limited number of execution slots to partitions of queries. When partitions
+/// are waiting, a free slot goes to the partition of the query with the fewest running
+/// partitions, and to the partition that has waited longest among those, so that a
+/// query with many partitions cannot starve other queries.
+#[derive(Debug)]
+pub struct Scheduler {
+    /// The maximum number of partitions that can run at the same time
+    limit: usize,
+    state: Mutex<SchedulerState>,
+    slot_released: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerState {
+    /// The number of running partitions of each query that has any
+    running: HashMap<u64, usize>,
+    /// The query and ticket of each waiting partition, in the order of arrival
+    waiting: VecDeque<(u64, u64)>,
+    next_ticket: u64,
+}
+
+impl SchedulerState {
+    fn total_running(&self) -> usize {
+        self.running.values().sum()
+    }
+
+    /// The ticket of the waiting partition that gets the next free slot
+    fn next_in_line(&self) -> Option<u64> {
+        let mut next: Option<(usize, u64)> = None;
+        for (query, ticket) in &self.waiting {
+            let running = self.running.get(query).cloned().unwrap_or(0);
+            match next {
+                Some((fewest, _)) if fewest <= running => {}
+                _ => next = Some((running, *ticket)),
+            }
+        }
+        next.map(|(_, ticket)| ticket)
+    }
+}
+
+impl Scheduler {
+    /// Create a scheduler that runs at most `limit` partitions at the same time. The
+    /// limit must be positive, as no partition could ever run otherwise.
+    pub fn try_new(limit: usize) -> Result<Self> {
+        if limit == 0 {
+            return Err(ExecutionError::General(
+                "The maximum number of concurrent partitions must be positive"
+                    .to_string(),
+            ));
+        }
+        Ok(Self {
+            limit,
+            state: Mutex::new(SchedulerState::default()),
+            slot_released: Condvar::new(),
+        })
+    }
+
+    /// Get the maximum number of partitions that can run at the same time
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get the number of partitions that are currently running
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().total_running()
+    }
+
+    fn acquire(&self, query: u64) {
+        let mut state = self.state.lock().unwrap();
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.waiting.push_back((query, ticket));
+        while state.total_running() >= self.limit || state.next_in_line() != Some(ticket)
+        {
+            state = self.slot_released.wait(state).unwrap();
+        }
+        state.waiting.retain(|(_, t)| *t != ticket);
+        *state.running.entry(query).or_insert(0) += 1;
+        // another partition may be next in line if more than one slot is free
+        self.slot_released.notify_all();
+    }
+
+    fn release(&self, query: u64) {
+        let mut state = self.state.lock().unwrap();
+        let remove = match state.running.get_mut(&query) {
+            Some(running) => {
+                *running -= 1;
+                *running == 0
+            }
+            None => false,
+        };
+        if remove {
+            state.running.remove(&query);
+        }
+        self.slot_released.notify_all();
+    }
+}
+
+/// A slot granted by a scheduler to a partition of a query, which is released when the
+/// permit is dropped
+#[derive(Debug)]
+pub struct SchedulerPermit {
+    scheduler: Arc<Scheduler>,
+    query: u64,
+}
+
+impl SchedulerPermit {
+    /// Wait until the scheduler grants a slot to a partition of the query
+    pub fn acquire(scheduler: Arc<Scheduler>, query: u64) -> Self {
+        scheduler.acquire(query);
+        Self { scheduler, query }
+    }
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        self.scheduler.release(self.query);
+    }
+}
+
+impl Default for Scheduler {
+    /// Create a scheduler that runs any number of partitions at the same time
+    fn default() -> Self {
+        Self {
+            limit: usize::max_value(),
+            state: Mutex::new(SchedulerState::default()),
+            slot_released: Condvar::new(),
+        }
+    }
+}
+
+/// The partition of a query that the current thread executes
+struct ThreadSlot {
+    scheduler: Arc<Scheduler>,
+    query: u64,
+    /// The slot of the thread, unless it is waiting for other partitions
+    permit: Option<SchedulerPermit>,
+}
+
+thread_local! {
+    static THREAD_SLOT: RefCell<Option<ThreadSlot>> = RefCell::new(None);
+}
+
+/// Clears the slot of the current thread when `run_partition` returns or panics
+struct ThreadSlotGuard;
+
+impl Drop for ThreadSlotGuard {
+    fn drop(&mut self) {
+        THREAD_SLOT.with(|slot| slot.borrow_mut().take());
+    }
+}
+
+/// Execute a partition of a query on the current thread while holding a slot of the
+/// scheduler. A thread that already executes a partition keeps the slot it holds, as
+/// the function is then part of the work of that partition.
+pub fn run_partition<T, F>(scheduler: &Arc<Scheduler>, query: u64, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    if THREAD_SLOT.with(|slot| slot.borrow().is_some()) {
+        return f();
+    }
+    let permit = SchedulerPermit::acquire(scheduler.clone(), query);
+    THREAD_SLOT.with(|slot| {
+        *slot.borrow_mut() = Some(ThreadSlot {
+            scheduler: scheduler.clone(),
+            query,
+            permit: Some(permit),
+        })
+    });
+    let _guard = ThreadSlotGuard;
+    f()
+}
+
+/// Get the scheduler and the query of the partition that the current thread executes,
+/// if any
+pub fn current_partition() -> Option<(Arc<Scheduler>, u64)> {
+    THREAD_SLOT.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map(|slot| (slot.scheduler.clone(), slot.query))
+    })
+}
+
+/// Release the slot of the current thread while it waits for other partitions, such as
+/// for threads to finish or for a lock that another partition holds while it waits for
+/// threads, and acquire it again afterwards
+pub fn wait<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let released = THREAD_SLOT.with(|slot| {
+        slot.borrow_mut().as_mut().and_then(|slot| {
+            slot.permit
+                .take()
+                .map(|_| (slot.scheduler.clone(), slot.query))
+        })
+    });
+    let result = f();
+    if let Some((scheduler, query)) = released {
+        let permit = SchedulerPermit::acquire(scheduler, query);
+        THREAD_SLOT.with(|slot| {
+            if let Some(slot) = slot.borrow_mut().as_mut() {
+                slot.permit = Some(permit);
+            }
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn limit_running_partitions() {
+        let scheduler = Arc::new(Scheduler::try_new(2).unwrap());
+        let first = SchedulerPermit::acquire(scheduler.clone(), 1);
+        let second = SchedulerPermit::acquire(scheduler.clone(), 1);
+        assert_eq!(scheduler.running(), 2);
+
+        let waiting = {
+            let scheduler = scheduler.clone();
+            thread::spawn(move || SchedulerPermit::acquire(scheduler, 2))
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(scheduler.running(), 2);
+
+        drop(first);
+        let third = waiting.join().unwrap();
+        assert_eq!(scheduler.running(), 2);
+        drop(second);
+        drop(third);
+        assert_eq!(scheduler.running(), 0);
+    }
+
+    #[test]
+    fn reject_zero_limit() {
+        assert!(Scheduler::try_new(0).is_err());
+    }
+
+    #[test]
+    fn release_slot_while_waiting() {
+        let scheduler = Arc::new(Scheduler::try_new(1).unwrap());
+        let running = run_partition(&scheduler, 1, || {
+            assert_eq!(scheduler.running(), 1);
+            // a nested partition runs with the slot of the thread
+            run_partition(&scheduler, 1, || assert_eq!(scheduler.running(), 1));
+            let other = {
+                let scheduler = scheduler.clone();
+                thread::spawn(move || {
+                    run_partition(&scheduler, 1, || scheduler.running())
+                })
+            };
+            // the other thread can only run while this one waits for it
+            let running = wait(|| other.join().unwrap());
+            assert_eq!(scheduler.running(), 1);
+            running
+        });
+        assert_eq!(running, 1);
+        assert_eq!(scheduler.running(), 0);
+        assert!(current_partition().is_none());
+    }
+
+    #[test]
+    fn prefer_queries_with_fewer_running_partitions() {
+        let mut state = SchedulerState::default();
+        state.running.insert(1, 3);
+        state.running.insert(2, 1);
+        state.waiting.push_back((1, 0));
+        state.waiting.push_back((2, 1));
+        state.waiting.push_back((3, 2));
+        state.waiting.push_back((3, 3));
+        assert_eq!(state.next_in_line(), Some(2));
+
+        state.running.insert(3, 1);
+        assert_eq!(state.next_in_line(), Some(1));
+    }
+}
@@ -19,7 +19,11 @@
 
 use std::sync::Arc;
 
+use serde_json::map::Map as JsonMap;
+use serde_json::Value;
+
 use crate::arrow::datatypes::DataType;
+use crate::arrow::json::writer::record_batches_to_json_rows;
 use crate::arrow::record_batch::RecordBatch;
 use crate::error::{ExecutionError, Result};
 use crate::execution::context::ExecutionContext;
@@ -89,6 +93,14 @@ impl Table for TableImpl {
         Ok(Arc::new(TableImpl::new(&plan)))
     }
 
+    /// Remove the rows whose keys are equal to those of the previous row
+    fn deduplicate(&self, keys: Vec<Expr>, keep_first: bool) -> Result<Arc<dyn Table>> {
+        let plan = LogicalPlanBuilder::from(&self.plan)
+            .deduplicate(keys, keep_first)?
+            .build()?;
+        Ok(Arc::new(TableImpl::new(&plan)))
+    }
+
     /// Return an expression representing a column within this table
     fn col(&self, name: &str) -> Result<Expr> {
         Ok(Expr::Column(self.plan.schema().index_of(name)?))
@@ -131,6 +143,15 @@ impl Table for TableImpl {
     ) -> Result<Vec<RecordBatch>> {
         ctx.collect_plan(&self.plan.clone(), batch_size)
     }
+
+    fn to_json_rows(
+        &self,
+        ctx: &mut ExecutionContext,
+        batch_size: usize,
+    ) -> Result<Vec<JsonMap<String, Value>>> {
+        let batches = self.collect(ctx, batch_size)?;
+        Ok(record_batches_to_json_rows(&batches)?)
+    }
 }
 
 impl TableImpl {
@@ -159,6 +180,9 @@ impl TableImpl {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::arrow::array::{Int32Array, StringArray};
+    use crate::arrow::datatypes::{Field, Schema};
+    use crate::datasource::MemTable;
     use crate::execution::context::ExecutionContext;
     use crate::test;
 
@@ -241,6 +265,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deduplicate() -> Result<()> {
+        let t = test_table().select_columns(vec!["c1", "c2"])?;
+        let plan = t.deduplicate(vec![t.col("c2")?], false)?.to_logical_plan();
+        let expected = "Deduplicate: keys=[#1], keep=last\
+        \n  Projection: #0, #1\
+        \n    TableScan: aggregate_test_100 projection=None";
+        assert_eq!(format!("{:?}", plan), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn to_json_rows() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c1", DataType::Utf8, true),
+            Field::new("c2", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+        )?;
+        let mut ctx = ExecutionContext::new();
+        ctx.register_table("t", Box::new(MemTable::new(schema, vec![batch])?));
+
+        let t = ctx.table("t")?.select_columns(vec!["c2", "c1"])?.limit(2)?;
+        let rows = t.to_json_rows(&mut ctx, 1024)?;
+        let rows: Vec<Value> = rows.into_iter().map(Value::Object).collect();
+        assert_eq!(
+            rows,
+            vec![
+                serde_json::json!({"c2": 1, "c1": "a"}),
+                serde_json::json!({"c2": 2, "c1": null}),
+            ]
+        );
+        Ok(())
+    }
+
     /// Compare the formatted string representation of two plans for equality
     fn assert_same_plan(plan1: &LogicalPlan, plan2: &LogicalPlan) {
         assert_eq!(format!("{:?}", plan1), format!("{:?}", plan2));
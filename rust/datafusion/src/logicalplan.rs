@@ -21,14 +21,20 @@
 //! Logical query plans can then be optimized and executed directly, or translated into
 //! physical query plans and executed.
 
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::sync::Arc;
 
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::array::Decimal128Array;
+use arrow::datatypes::{DataType, DateUnit, Field, IntervalUnit, Schema, TimeUnit};
 
 use crate::error::{ExecutionError, Result};
 use crate::optimizer::utils;
-use crate::sql::parser::FileType;
+use crate::sql::parser::{ExplainFormat, FileType};
+use fnv::FnvHasher;
 
 /// Enumeration of supported function types (Scalar and Aggregate)
 #[derive(Debug, Clone)]
@@ -86,7 +92,7 @@ impl FunctionMeta {
 }
 
 /// Operators applied to expressions
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Operator {
     /// Expressions are equal
     Eq,
@@ -120,12 +126,195 @@ pub enum Operator {
     Like,
     /// Does not match a wildcard pattern
     NotLike,
+    /// String concatenation, like `||`
+    StringConcat,
+    /// Matches a regular expression, like `~`
+    RegexMatch,
+    /// Matches a regular expression regardless of case, like `~*`
+    RegexIMatch,
+    /// Does not match a regular expression, like `!~`
+    RegexNotMatch,
+    /// Does not match a regular expression regardless of case, like `!~*`
+    RegexNotIMatch,
+}
+
+/// Join type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinType {
+    /// Inner join, only returning rows where the join keys match on both sides
+    Inner,
+    /// Left outer join, also returning the rows of the left input without a match,
+    /// with nulls for the columns of the right input
+    Left,
+    /// Right outer join, also returning the rows of the right input without a match,
+    /// with nulls for the columns of the left input
+    Right,
+    /// Full outer join, also returning the rows of either input without a match, with
+    /// nulls for the columns of the other input
+    Full,
+    /// Left semi join, returning each row of the left input that has at least one match
+    /// in the right input, without any columns of the right input
+    LeftSemi,
+    /// Left anti join, returning each row of the left input that has no match in the
+    /// right input, without any columns of the right input
+    LeftAnti,
+}
+
+/// Create the output schema of a join, containing the fields of the left input followed
+/// by the fields of the right input. The fields of the side that is padded with nulls
+/// in an outer join become nullable. Semi and anti joins only contain the fields of the
+/// left input.
+pub fn build_join_schema(left: &Schema, right: &Schema, join_type: &JoinType) -> Schema {
+    let (left_nullable, right_nullable) = match join_type {
+        JoinType::Inner => (false, false),
+        JoinType::Left => (false, true),
+        JoinType::Right => (true, false),
+        JoinType::Full => (true, true),
+        JoinType::LeftSemi | JoinType::LeftAnti => return left.clone(),
+    };
+    let left_fields = left.fields().iter().map(|f| nullable_if(f, left_nullable));
+    let right_fields = right
+        .fields()
+        .iter()
+        .map(|f| nullable_if(f, right_nullable));
+    Schema::new(left_fields.chain(right_fields).collect())
+}
+
+fn nullable_if(field: &Field, nullable: bool) -> Field {
+    Field::new(
+        field.name(),
+        field.data_type().clone(),
+        field.is_nullable() || nullable,
+    )
+}
+
+/// The units of the bounds of a window frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowFrameUnits {
+    /// The bounds are numbers of rows before or after the current row
+    Rows,
+    /// The bounds are based on the ORDER BY values, so that the current row is in the
+    /// frame together with its peers, the rows with equal ORDER BY values
+    Range,
+}
+
+/// A bound of a window frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowFrameBound {
+    /// The given number of rows before the current row, or the first row of the
+    /// partition if unbounded
+    Preceding(Option<u64>),
+    /// The current row, which for `RANGE` frames extends to its peers
+    CurrentRow,
+    /// The given number of rows after the current row, or the last row of the
+    /// partition if unbounded
+    Following(Option<u64>),
+}
+
+impl WindowFrameBound {
+    /// The position of the bound relative to the current row, for comparing bounds
+    fn offset(&self) -> i128 {
+        match self {
+            WindowFrameBound::Preceding(None) => i128::min_value(),
+            WindowFrameBound::Preceding(Some(n)) => -(*n as i128),
+            WindowFrameBound::CurrentRow => 0,
+            WindowFrameBound::Following(Some(n)) => *n as i128,
+            WindowFrameBound::Following(None) => i128::max_value(),
+        }
+    }
+}
+
+impl fmt::Display for WindowFrameBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WindowFrameBound::Preceding(None) => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(Some(n)) => write!(f, "{} PRECEDING", n),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(Some(n)) => write!(f, "{} FOLLOWING", n),
+            WindowFrameBound::Following(None) => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
+
+/// The rows of its partition that a window function is evaluated over for each row,
+/// e.g. "ROWS BETWEEN 3 PRECEDING AND CURRENT ROW"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowFrame {
+    /// The units of the bounds
+    pub units: WindowFrameUnits,
+    /// The first row of the frame
+    pub start_bound: WindowFrameBound,
+    /// The last row of the frame
+    pub end_bound: WindowFrameBound,
+}
+
+impl WindowFrame {
+    /// Create a window frame, checking that its start is not after its end. `RANGE`
+    /// frames only support unbounded bounds and the current row.
+    pub fn try_new(
+        units: WindowFrameUnits,
+        start_bound: WindowFrameBound,
+        end_bound: WindowFrameBound,
+    ) -> Result<Self> {
+        let has_offset = |bound: &WindowFrameBound| match bound {
+            WindowFrameBound::Preceding(Some(_))
+            | WindowFrameBound::Following(Some(_)) => true,
+            _ => false,
+        };
+        if units == WindowFrameUnits::Range
+            && (has_offset(&start_bound) || has_offset(&end_bound))
+        {
+            return Err(ExecutionError::NotImplemented(
+                "RANGE window frames with an offset are not supported".to_string(),
+            ));
+        }
+        if start_bound == WindowFrameBound::Following(None)
+            || end_bound == WindowFrameBound::Preceding(None)
+            || start_bound.offset() > end_bound.offset()
+        {
+            return Err(ExecutionError::General(format!(
+                "Invalid window frame BETWEEN {} AND {}",
+                start_bound, end_bound
+            )));
+        }
+        Ok(Self {
+            units,
+            start_bound,
+            end_bound,
+        })
+    }
+
+    /// The frame used when a window does not specify one, which is all rows of the
+    /// partition without an ORDER BY clause, and otherwise the rows up to the current
+    /// row and its peers
+    pub fn default_for(has_order_by: bool) -> Self {
+        Self {
+            units: WindowFrameUnits::Range,
+            start_bound: WindowFrameBound::Preceding(None),
+            end_bound: if has_order_by {
+                WindowFrameBound::CurrentRow
+            } else {
+                WindowFrameBound::Following(None)
+            },
+        }
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.units {
+            WindowFrameUnits::Rows => write!(f, "ROWS")?,
+            WindowFrameUnits::Range => write!(f, "RANGE")?,
+        }
+        write!(f, " BETWEEN {} AND {}", self.start_bound, self.end_bound)
+    }
 }
 
 /// ScalarValue enumeration
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum ScalarValue {
-    /// null value
+    /// untyped null value, which type coercion casts to the type required by the context
+    /// of its literal, and which is a string if there is none
     Null,
     /// true or false value
     Boolean(bool),
@@ -153,6 +342,103 @@ pub enum ScalarValue {
     Utf8(String),
     /// List of scalars packed as a struct
     Struct(Vec<ScalarValue>),
+    /// List of scalars of the same type
+    List(Vec<ScalarValue>),
+    /// number of days since the UNIX epoch
+    Date32(i32),
+    /// number of nanoseconds since the UNIX epoch
+    TimestampNanosecond(i64),
+    /// interval of a number of months
+    IntervalYearMonth(i32),
+    /// interval of a number of days in the upper and milliseconds in the lower 32 bits
+    IntervalDayTime(i64),
+    /// decimal of an unscaled integer, the precision and the scale
+    Decimal128(i128, usize, usize),
+}
+
+/// Number of milliseconds in a day
+const MILLISECONDS_PER_DAY: i64 = 86_400_000;
+
+/// Number of nanoseconds in a millisecond
+const NANOSECONDS_PER_MILLISECOND: i64 = 1_000_000;
+
+/// Number of nanoseconds in a day
+const NANOSECONDS_PER_DAY: i64 = MILLISECONDS_PER_DAY * NANOSECONDS_PER_MILLISECOND;
+
+impl PartialEq for ScalarValue {
+    /// Floating point values are equal if they have the same bits, so that equality is
+    /// reflexive for NaN and consistent with `Hash`
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ScalarValue::Null, ScalarValue::Null) => true,
+            (ScalarValue::Boolean(l), ScalarValue::Boolean(r)) => l == r,
+            (ScalarValue::Float32(l), ScalarValue::Float32(r)) => {
+                l.to_bits() == r.to_bits()
+            }
+            (ScalarValue::Float64(l), ScalarValue::Float64(r)) => {
+                l.to_bits() == r.to_bits()
+            }
+            (ScalarValue::Int8(l), ScalarValue::Int8(r)) => l == r,
+            (ScalarValue::Int16(l), ScalarValue::Int16(r)) => l == r,
+            (ScalarValue::Int32(l), ScalarValue::Int32(r)) => l == r,
+            (ScalarValue::Int64(l), ScalarValue::Int64(r)) => l == r,
+            (ScalarValue::UInt8(l), ScalarValue::UInt8(r)) => l == r,
+            (ScalarValue::UInt16(l), ScalarValue::UInt16(r)) => l == r,
+            (ScalarValue::UInt32(l), ScalarValue::UInt32(r)) => l == r,
+            (ScalarValue::UInt64(l), ScalarValue::UInt64(r)) => l == r,
+            (ScalarValue::Utf8(l), ScalarValue::Utf8(r)) => l == r,
+            (ScalarValue::Struct(l), ScalarValue::Struct(r)) => l == r,
+            (ScalarValue::List(l), ScalarValue::List(r)) => l == r,
+            (ScalarValue::Date32(l), ScalarValue::Date32(r)) => l == r,
+            (
+                ScalarValue::TimestampNanosecond(l),
+                ScalarValue::TimestampNanosecond(r),
+            ) => l == r,
+            (ScalarValue::IntervalYearMonth(l), ScalarValue::IntervalYearMonth(r)) => {
+                l == r
+            }
+            (ScalarValue::IntervalDayTime(l), ScalarValue::IntervalDayTime(r)) => l == r,
+            (
+                ScalarValue::Decimal128(l, l_precision, l_scale),
+                ScalarValue::Decimal128(r, r_precision, r_scale),
+            ) => l == r && l_precision == r_precision && l_scale == r_scale,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ScalarValue {}
+
+impl Hash for ScalarValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            ScalarValue::Null => {}
+            ScalarValue::Boolean(v) => v.hash(state),
+            ScalarValue::Float32(v) => v.to_bits().hash(state),
+            ScalarValue::Float64(v) => v.to_bits().hash(state),
+            ScalarValue::Int8(v) => v.hash(state),
+            ScalarValue::Int16(v) => v.hash(state),
+            ScalarValue::Int32(v) => v.hash(state),
+            ScalarValue::Int64(v) => v.hash(state),
+            ScalarValue::UInt8(v) => v.hash(state),
+            ScalarValue::UInt16(v) => v.hash(state),
+            ScalarValue::UInt32(v) => v.hash(state),
+            ScalarValue::UInt64(v) => v.hash(state),
+            ScalarValue::Utf8(v) => v.hash(state),
+            ScalarValue::Struct(v) => v.hash(state),
+            ScalarValue::List(v) => v.hash(state),
+            ScalarValue::Date32(v) => v.hash(state),
+            ScalarValue::TimestampNanosecond(v) => v.hash(state),
+            ScalarValue::IntervalYearMonth(v) => v.hash(state),
+            ScalarValue::IntervalDayTime(v) => v.hash(state),
+            ScalarValue::Decimal128(v, precision, scale) => {
+                v.hash(state);
+                precision.hash(state);
+                scale.hash(state);
+            }
+        }
+    }
 }
 
 impl ScalarValue {
@@ -171,13 +457,486 @@ impl ScalarValue {
             ScalarValue::Float32(_) => DataType::Float32,
             ScalarValue::Float64(_) => DataType::Float64,
             ScalarValue::Utf8(_) => DataType::Utf8,
+            ScalarValue::Date32(_) => DataType::Date32(DateUnit::Day),
+            ScalarValue::TimestampNanosecond(_) => {
+                DataType::Timestamp(TimeUnit::Nanosecond, None)
+            }
+            ScalarValue::IntervalYearMonth(_) => {
+                DataType::Interval(IntervalUnit::YearMonth)
+            }
+            ScalarValue::IntervalDayTime(_) => DataType::Interval(IntervalUnit::DayTime),
+            ScalarValue::Decimal128(_, precision, scale) => {
+                DataType::Decimal(precision, scale)
+            }
             _ => panic!("Cannot treat {:?} as scalar value", self),
         }
     }
+
+    /// Whether this is the null value
+    pub fn is_null(&self) -> bool {
+        match self {
+            ScalarValue::Null => true,
+            _ => false,
+        }
+    }
+
+    /// Create a day-time interval of a number of days and milliseconds
+    pub fn interval_day_time(days: i32, milliseconds: i32) -> ScalarValue {
+        ScalarValue::IntervalDayTime(((days as i64) << 32) | (milliseconds as u32 as i64))
+    }
+
+    /// Compare two values. Integers and decimals of any type are compared exactly and
+    /// other numbers as floating point values, dates are compared with timestamps as
+    /// midnight of their day and day-time intervals by their total length. Returns
+    /// `None` if either value is null or the values cannot be compared.
+    pub fn compare(&self, other: &ScalarValue) -> Option<Ordering> {
+        match (self, other) {
+            (ScalarValue::Utf8(l), ScalarValue::Utf8(r)) => Some(l.cmp(r)),
+            (ScalarValue::Boolean(l), ScalarValue::Boolean(r)) => Some(l.cmp(r)),
+            (ScalarValue::IntervalYearMonth(l), ScalarValue::IntervalYearMonth(r)) => {
+                Some(l.cmp(r))
+            }
+            (ScalarValue::IntervalDayTime(l), ScalarValue::IntervalDayTime(r)) => {
+                Some(day_time_milliseconds(*l).cmp(&day_time_milliseconds(*r)))
+            }
+            _ => match (self.to_timestamp(), other.to_timestamp()) {
+                (Some(l), Some(r)) => Some(l.cmp(&r)),
+                _ => match (self.to_decimal(), other.to_decimal()) {
+                    (Some((l, l_scale)), Some((r, r_scale))) => {
+                        let scale = l_scale.max(r_scale);
+                        match (
+                            Decimal128Array::rescale_value(l, l_scale, scale, 38),
+                            Decimal128Array::rescale_value(r, r_scale, scale, 38),
+                        ) {
+                            (Some(l), Some(r)) => Some(l.cmp(&r)),
+                            _ => self.to_f64()?.partial_cmp(&other.to_f64()?),
+                        }
+                    }
+                    _ => self.to_f64()?.partial_cmp(&other.to_f64()?),
+                },
+            },
+        }
+    }
+
+    /// Apply a binary operator to two values, with the result that the operator has
+    /// for arrays of these values. The operator returns null if either value is null.
+    pub fn binary(&self, op: &Operator, other: &ScalarValue) -> Result<ScalarValue> {
+        if self.is_null() || other.is_null() {
+            return Ok(ScalarValue::Null);
+        }
+        match op {
+            Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq => {
+                let ordering = self.compare(other).ok_or_else(|| {
+                    ExecutionError::General(format!(
+                        "Cannot compare {:?} with {:?}",
+                        self, other
+                    ))
+                })?;
+                Ok(ScalarValue::Boolean(match op {
+                    Operator::Eq => ordering == Ordering::Equal,
+                    Operator::NotEq => ordering != Ordering::Equal,
+                    Operator::Lt => ordering == Ordering::Less,
+                    Operator::LtEq => ordering != Ordering::Greater,
+                    Operator::Gt => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }))
+            }
+            Operator::And | Operator::Or => match (self, other) {
+                (ScalarValue::Boolean(l), ScalarValue::Boolean(r)) => {
+                    Ok(ScalarValue::Boolean(match op {
+                        Operator::And => *l && *r,
+                        _ => *l || *r,
+                    }))
+                }
+                _ => Err(ExecutionError::General(format!(
+                    "Operator {:?} cannot be applied to {:?} and {:?}",
+                    op, self, other
+                ))),
+            },
+            Operator::StringConcat => match (self, other) {
+                (ScalarValue::Utf8(l), ScalarValue::Utf8(r)) => {
+                    Ok(ScalarValue::Utf8(format!("{}{}", l, r)))
+                }
+                _ => Err(ExecutionError::General(format!(
+                    "Operator {:?} cannot be applied to {:?} and {:?}",
+                    op, self, other
+                ))),
+            },
+            Operator::Plus => self.add(other),
+            Operator::Minus => self.sub(other),
+            _ => self.arithmetic(op, other),
+        }
+    }
+
+    /// Add two values. Intervals can be added to each other and to dates and
+    /// timestamps, where adding months keeps the day of the month unless the month
+    /// is shorter.
+    pub fn add(&self, other: &ScalarValue) -> Result<ScalarValue> {
+        match (self, other) {
+            (ScalarValue::Null, _) | (_, ScalarValue::Null) => Ok(ScalarValue::Null),
+            (ScalarValue::IntervalYearMonth(l), ScalarValue::IntervalYearMonth(r)) => Ok(
+                ScalarValue::IntervalYearMonth(checked(l.checked_add(*r), self, other)?),
+            ),
+            (ScalarValue::IntervalDayTime(l), ScalarValue::IntervalDayTime(r)) => {
+                let (l_days, l_ms) = split_day_time(*l);
+                let (r_days, r_ms) = split_day_time(*r);
+                Ok(ScalarValue::interval_day_time(
+                    checked(l_days.checked_add(r_days), self, other)?,
+                    checked(l_ms.checked_add(r_ms), self, other)?,
+                ))
+            }
+            (_, ScalarValue::IntervalYearMonth(_))
+            | (_, ScalarValue::IntervalDayTime(_))
+                if self.to_timestamp().is_some() =>
+            {
+                add_interval(self, other)
+            }
+            (ScalarValue::IntervalYearMonth(_), _)
+            | (ScalarValue::IntervalDayTime(_), _)
+                if other.to_timestamp().is_some() =>
+            {
+                add_interval(other, self)
+            }
+            _ => self.arithmetic(&Operator::Plus, other),
+        }
+    }
+
+    /// Subtract a value from this value. Intervals can be subtracted from each other
+    /// and from dates and timestamps, and subtracting two dates or timestamps results
+    /// in the day-time interval between them.
+    pub fn sub(&self, other: &ScalarValue) -> Result<ScalarValue> {
+        match (self, other) {
+            (ScalarValue::Null, _) | (_, ScalarValue::Null) => Ok(ScalarValue::Null),
+            (ScalarValue::IntervalYearMonth(l), ScalarValue::IntervalYearMonth(r)) => Ok(
+                ScalarValue::IntervalYearMonth(checked(l.checked_sub(*r), self, other)?),
+            ),
+            (ScalarValue::IntervalDayTime(l), ScalarValue::IntervalDayTime(r)) => {
+                let (l_days, l_ms) = split_day_time(*l);
+                let (r_days, r_ms) = split_day_time(*r);
+                Ok(ScalarValue::interval_day_time(
+                    checked(l_days.checked_sub(r_days), self, other)?,
+                    checked(l_ms.checked_sub(r_ms), self, other)?,
+                ))
+            }
+            (_, ScalarValue::IntervalYearMonth(months))
+                if self.to_timestamp().is_some() =>
+            {
+                let months = checked(months.checked_neg(), self, other)?;
+                add_interval(self, &ScalarValue::IntervalYearMonth(months))
+            }
+            (_, ScalarValue::IntervalDayTime(interval))
+                if self.to_timestamp().is_some() =>
+            {
+                let (days, ms) = split_day_time(*interval);
+                let negated = ScalarValue::interval_day_time(
+                    checked(days.checked_neg(), self, other)?,
+                    checked(ms.checked_neg(), self, other)?,
+                );
+                add_interval(self, &negated)
+            }
+            (ScalarValue::Date32(l), ScalarValue::Date32(r)) => {
+                Ok(ScalarValue::interval_day_time(
+                    checked(l.checked_sub(*r), self, other)?,
+                    0,
+                ))
+            }
+            _ => match (self.to_timestamp(), other.to_timestamp()) {
+                (Some(l), Some(r)) => {
+                    let nanoseconds = checked(l.checked_sub(r), self, other)?;
+                    let days = nanoseconds / NANOSECONDS_PER_DAY;
+                    let ms =
+                        nanoseconds % NANOSECONDS_PER_DAY / NANOSECONDS_PER_MILLISECOND;
+                    Ok(ScalarValue::interval_day_time(
+                        checked(i32::try_from(days).ok(), self, other)?,
+                        ms as i32,
+                    ))
+                }
+                _ => self.arithmetic(&Operator::Minus, other),
+            },
+        }
+    }
+
+    /// Apply an arithmetic operator to two numbers, which are cast to their common
+    /// supertype. Integer overflow and division by zero are errors.
+    fn arithmetic(&self, op: &Operator, other: &ScalarValue) -> Result<ScalarValue> {
+        if self.to_f64().is_none() || other.to_f64().is_none() {
+            return Err(ExecutionError::General(format!(
+                "Operator {:?} cannot be applied to {:?} and {:?}",
+                op, self, other
+            )));
+        }
+        let data_type =
+            utils::get_supertype(&self.get_datatype(), &other.get_datatype())?;
+        match (&data_type, self.to_i128(), other.to_i128()) {
+            (DataType::Float32, _, _)
+            | (DataType::Float64, _, _)
+            | (_, None, _)
+            | (_, _, None) => {
+                let (l, r) = (self.to_f64().unwrap(), other.to_f64().unwrap());
+                let value = match op {
+                    Operator::Plus => l + r,
+                    Operator::Minus => l - r,
+                    Operator::Multiply => l * r,
+                    Operator::Divide if r != 0.0 => l / r,
+                    Operator::Modulus if r != 0.0 => l % r,
+                    Operator::Divide | Operator::Modulus => {
+                        return Err(ExecutionError::General(
+                            "Division by zero".to_string(),
+                        ))
+                    }
+                    _ => {
+                        return Err(ExecutionError::General(format!(
+                            "Operator {:?} cannot be applied to {:?} and {:?}",
+                            op, self, other
+                        )))
+                    }
+                };
+                match data_type {
+                    DataType::Float32 => Ok(ScalarValue::Float32(value as f32)),
+                    _ => Ok(ScalarValue::Float64(value)),
+                }
+            }
+            (data_type, Some(l), Some(r)) => {
+                let value = match op {
+                    Operator::Plus => l.checked_add(r),
+                    Operator::Minus => l.checked_sub(r),
+                    Operator::Multiply => l.checked_mul(r),
+                    Operator::Divide | Operator::Modulus if r == 0 => {
+                        return Err(ExecutionError::General(
+                            "Division by zero".to_string(),
+                        ))
+                    }
+                    Operator::Divide => l.checked_div(r),
+                    Operator::Modulus => l.checked_rem(r),
+                    _ => {
+                        return Err(ExecutionError::General(format!(
+                            "Operator {:?} cannot be applied to {:?} and {:?}",
+                            op, self, other
+                        )))
+                    }
+                };
+                checked(value.and_then(|v| from_i128(v, data_type)), self, other)
+            }
+        }
+    }
+
+    /// Cast a number to another numeric type, where floating point values are
+    /// truncated when cast to an integer type and rounded when cast to a decimal.
+    /// Returns `None` for other types and for values that are out of range of the type.
+    pub fn cast_to(&self, data_type: &DataType) -> Option<ScalarValue> {
+        match self {
+            ScalarValue::Null => return Some(ScalarValue::Null),
+            ScalarValue::Struct(_) | ScalarValue::List(_) => return None,
+            value if &value.get_datatype() == data_type => return Some(value.clone()),
+            _ => {}
+        }
+        match data_type {
+            DataType::Float32 => Some(ScalarValue::Float32(self.to_f64()? as f32)),
+            DataType::Float64 => Some(ScalarValue::Float64(self.to_f64()?)),
+            DataType::Decimal(precision, scale) => {
+                let value = match self.to_decimal() {
+                    Some((value, from_scale)) => Decimal128Array::rescale_value(
+                        value, from_scale, *scale, *precision,
+                    )?,
+                    None => {
+                        let value = (self.to_f64()? * 10f64.powi(*scale as i32)).round();
+                        if !value.is_finite() || value.abs() >= 1e38 {
+                            return None;
+                        }
+                        let value = value as i128;
+                        if !Decimal128Array::fits_precision(value, *precision) {
+                            return None;
+                        }
+                        value
+                    }
+                };
+                Some(ScalarValue::Decimal128(value, *precision, *scale))
+            }
+            _ => match self.to_i128() {
+                Some(value) => from_i128(value, data_type),
+                None => {
+                    let value = self.to_f64()?.trunc();
+                    if value.abs() < 2f64.powi(64) {
+                        from_i128(value as i128, data_type)
+                    } else {
+                        None
+                    }
+                }
+            },
+        }
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        match self {
+            ScalarValue::Int8(v) => Some(*v as i128),
+            ScalarValue::Int16(v) => Some(*v as i128),
+            ScalarValue::Int32(v) => Some(*v as i128),
+            ScalarValue::Int64(v) => Some(*v as i128),
+            ScalarValue::UInt8(v) => Some(*v as i128),
+            ScalarValue::UInt16(v) => Some(*v as i128),
+            ScalarValue::UInt32(v) => Some(*v as i128),
+            ScalarValue::UInt64(v) => Some(*v as i128),
+            _ => None,
+        }
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        match self {
+            ScalarValue::Float32(v) => Some(*v as f64),
+            ScalarValue::Float64(v) => Some(*v),
+            ScalarValue::Decimal128(v, _, scale) => {
+                Some(*v as f64 / 10f64.powi(*scale as i32))
+            }
+            _ => self.to_i128().map(|v| v as f64),
+        }
+    }
+
+    /// The unscaled integer and the scale of a decimal or integer
+    fn to_decimal(&self) -> Option<(i128, usize)> {
+        match self {
+            ScalarValue::Decimal128(v, _, scale) => Some((*v, *scale)),
+            _ => self.to_i128().map(|v| (v, 0)),
+        }
+    }
+
+    /// The number of nanoseconds since the UNIX epoch of a date or timestamp
+    fn to_timestamp(&self) -> Option<i64> {
+        match self {
+            ScalarValue::Date32(days) => (*days as i64).checked_mul(NANOSECONDS_PER_DAY),
+            ScalarValue::TimestampNanosecond(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// Convert an integer to a scalar value of an integer type, or `None` if it is out of
+/// range of the type
+fn from_i128(value: i128, data_type: &DataType) -> Option<ScalarValue> {
+    Some(match data_type {
+        DataType::Int8 => ScalarValue::Int8(i8::try_from(value).ok()?),
+        DataType::Int16 => ScalarValue::Int16(i16::try_from(value).ok()?),
+        DataType::Int32 => ScalarValue::Int32(i32::try_from(value).ok()?),
+        DataType::Int64 => ScalarValue::Int64(i64::try_from(value).ok()?),
+        DataType::UInt8 => ScalarValue::UInt8(u8::try_from(value).ok()?),
+        DataType::UInt16 => ScalarValue::UInt16(u16::try_from(value).ok()?),
+        DataType::UInt32 => ScalarValue::UInt32(u32::try_from(value).ok()?),
+        DataType::UInt64 => ScalarValue::UInt64(u64::try_from(value).ok()?),
+        _ => return None,
+    })
+}
+
+/// The result of a checked operation on two values, or an overflow error
+fn checked<T>(value: Option<T>, left: &ScalarValue, right: &ScalarValue) -> Result<T> {
+    value.ok_or_else(|| {
+        ExecutionError::General(format!(
+            "Arithmetic overflow in operation on {:?} and {:?}",
+            left, right
+        ))
+    })
+}
+
+/// The days and milliseconds of a day-time interval
+fn split_day_time(interval: i64) -> (i32, i32) {
+    ((interval >> 32) as i32, interval as i32)
+}
+
+/// The total length of a day-time interval in milliseconds
+fn day_time_milliseconds(interval: i64) -> i64 {
+    let (days, ms) = split_day_time(interval);
+    days as i64 * MILLISECONDS_PER_DAY + ms as i64
+}
+
+/// Add an interval to a date or timestamp. The result is a date if a date is moved by
+/// whole days or months, and a timestamp otherwise.
+fn add_interval(value: &ScalarValue, interval: &ScalarValue) -> Result<ScalarValue> {
+    let overflow = || {
+        ExecutionError::General(format!(
+            "Arithmetic overflow in operation on {:?} and {:?}",
+            value, interval
+        ))
+    };
+    let nanoseconds = value.to_timestamp().ok_or_else(overflow)?;
+    let day = nanoseconds.div_euclid(NANOSECONDS_PER_DAY);
+    let time = nanoseconds.rem_euclid(NANOSECONDS_PER_DAY);
+    let (day, time) = match interval {
+        ScalarValue::IntervalYearMonth(months) => (add_months(day, *months), time),
+        ScalarValue::IntervalDayTime(interval) => {
+            let (days, ms) = split_day_time(*interval);
+            let time = time + ms as i64 * NANOSECONDS_PER_MILLISECOND;
+            (
+                day + days as i64 + time.div_euclid(NANOSECONDS_PER_DAY),
+                time.rem_euclid(NANOSECONDS_PER_DAY),
+            )
+        }
+        _ => return Err(overflow()),
+    };
+    match value {
+        ScalarValue::Date32(_) if time == 0 => Ok(ScalarValue::Date32(
+            i32::try_from(day).map_err(|_| overflow())?,
+        )),
+        _ => day
+            .checked_mul(NANOSECONDS_PER_DAY)
+            .and_then(|v| v.checked_add(time))
+            .map(ScalarValue::TimestampNanosecond)
+            .ok_or_else(overflow),
+    }
+}
+
+/// Add a number of months to a day since the UNIX epoch, keeping its day of the month
+/// unless the resulting month is shorter
+fn add_months(day: i64, months: i32) -> i64 {
+    let (year, month, day_of_month) = civil_from_days(day);
+    let month = year * 12 + month as i64 - 1 + months as i64;
+    let (year, month) = (month.div_euclid(12), month.rem_euclid(12) as u32 + 1);
+    let last_day = days_from_civil(year + month as i64 / 12, month % 12 + 1, 1)
+        - days_from_civil(year, month, 1);
+    days_from_civil(year, month, day_of_month.min(last_day as u32))
+}
+
+/// The day since the UNIX epoch of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The year, month and day of a day since the UNIX epoch in the proleptic Gregorian
+/// calendar
+fn civil_from_days(day: i64) -> (i64, u32, u32) {
+    let day = day + 719_468;
+    let era = day.div_euclid(146_097);
+    let day_of_era = day - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day_of_month = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (
+        if month <= 2 { year + 1 } else { year },
+        month,
+        day_of_month,
+    )
 }
 
 /// Relation expression
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Expr {
     /// An aliased expression
     Alias(Arc<Expr>, String),
@@ -196,6 +955,49 @@ pub enum Expr {
         /// Right-hand side of the expression
         right: Arc<Expr>,
     },
+    /// range condition e.g. "age BETWEEN 21 AND 65", which includes both bounds
+    Between {
+        /// The expression to compare with the bounds
+        expr: Arc<Expr>,
+        /// Whether the condition is negated, as for "age NOT BETWEEN 21 AND 65"
+        negated: bool,
+        /// The lower bound
+        low: Arc<Expr>,
+        /// The upper bound
+        high: Arc<Expr>,
+    },
+    /// membership condition with a list of values e.g. "state IN ('CA', 'NY')"
+    InList {
+        /// The expression to compare with the values of the list
+        expr: Arc<Expr>,
+        /// The values of the list
+        list: Vec<Expr>,
+        /// Whether the condition is negated, as for "state NOT IN ('CA', 'NY')"
+        negated: bool,
+    },
+    /// pattern match e.g. "name LIKE 'J%'", where `%` matches any number of characters
+    /// and `_` a single one
+    Like {
+        /// The expression to match with the pattern
+        expr: Arc<Expr>,
+        /// The pattern
+        pattern: Arc<Expr>,
+        /// Whether the condition is negated, as for "name NOT LIKE 'J%'"
+        negated: bool,
+        /// Whether letters match regardless of their case, as for "name ILIKE 'j%'"
+        case_insensitive: bool,
+        /// The character that makes the following `%`, `_` or escape character of the
+        /// pattern match itself, as for "name LIKE 'J!%' ESCAPE '!'"
+        escape: Option<char>,
+    },
+    /// element of a list at an index starting at 1 e.g. "tags[1]", which is null if the
+    /// index is out of bounds
+    ElementAt {
+        /// The List or FixedSizeList expression
+        list: Arc<Expr>,
+        /// The index of the element
+        index: Arc<Expr>,
+    },
     /// unary NOT
     Not(Arc<Expr>),
     /// unary IS NOT NULL
@@ -215,6 +1017,8 @@ pub enum Expr {
         expr: Arc<Expr>,
         /// The direction of the sort
         asc: bool,
+        /// Whether to put nulls before non-null values
+        nulls_first: bool,
     },
     /// scalar function
     ScalarFunction {
@@ -234,11 +1038,189 @@ pub enum Expr {
         /// The `DataType` the expression will yield
         return_type: DataType,
     },
+    /// window function, which computes a value for each row from the rows of its
+    /// partition e.g. "RANK() OVER (PARTITION BY state ORDER BY age)"
+    WindowFunction {
+        /// Name of the function
+        name: String,
+        /// List of expressions to feed to the functions as arguments
+        args: Vec<Expr>,
+        /// The expressions that divide the rows into partitions
+        partition_by: Vec<Expr>,
+        /// The sort expressions that order the rows within each partition
+        order_by: Vec<Expr>,
+        /// The rows of the partition that the function is evaluated over, if given
+        window_frame: Option<WindowFrame>,
+        /// The `DataType` the expression will yield
+        return_type: DataType,
+    },
     /// Wildcard
     Wildcard,
+    /// searched CASE expression e.g. "CASE WHEN age > 21 THEN 'adult' ELSE 'minor' END".
+    /// A simple CASE expression with an operand is planned as a searched one.
+    Case {
+        /// The conditions and results of the branches, in the order they are checked
+        when_then_expr: Vec<(Expr, Expr)>,
+        /// The result when no condition is true, which is null if missing
+        else_expr: Option<Arc<Expr>>,
+    },
+    /// Whether a subquery returns any rows, as for `EXISTS (subquery)`
+    Exists {
+        /// The subquery
+        subquery: Subquery,
+        /// Whether the result is negated, as for `NOT EXISTS (subquery)`
+        negated: bool,
+    },
+    /// Whether the value of an expression is returned by a subquery with a single
+    /// column, as for `expr IN (subquery)`
+    InSubquery {
+        /// The expression to look up
+        expr: Arc<Expr>,
+        /// The subquery
+        subquery: Subquery,
+        /// Whether the result is negated, as for `expr NOT IN (subquery)`
+        negated: bool,
+    },
+    /// The value of a subquery that returns a single column and at most one row
+    ScalarSubquery(Subquery),
+    /// Reference from within a correlated subquery to a column of the input of the plan
+    /// node that contains the subquery
+    OuterColumn {
+        /// The index of the column in the input of the enclosing plan node
+        index: usize,
+        /// The `DataType` of the column
+        data_type: DataType,
+    },
+}
+
+/// The plan of a subquery that is used as an expression. The plan can refer to the
+/// columns of the enclosing query with `Expr::OuterColumn`, in which case the subquery
+/// is correlated.
+#[derive(Clone)]
+pub struct Subquery(pub Arc<LogicalPlan>);
+
+impl Subquery {
+    /// Create a subquery from its plan
+    pub fn new(plan: &LogicalPlan) -> Self {
+        Subquery(Arc::new(plan.clone()))
+    }
+
+    /// The plan of the subquery
+    pub fn plan(&self) -> &LogicalPlan {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for Subquery {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl Eq for Subquery {}
+
+impl Hash for Subquery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl fmt::Debug for Subquery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        self.0.fmt_inline(f)?;
+        write!(f, ")")
+    }
 }
 
 impl Expr {
+    /// Evaluate an expression that only consists of literals, such as `1 + 2` or
+    /// `CAST(5 AS DOUBLE) > 4.5`, without evaluating it on arrays. Returns `None` if the
+    /// expression references columns or cannot be evaluated at plan time.
+    pub fn evaluate_literal(&self) -> Option<ScalarValue> {
+        match self {
+            Expr::Literal(value) => Some(value.clone()),
+            Expr::Alias(expr, _) => expr.evaluate_literal(),
+            Expr::BinaryExpr { left, op, right } => left
+                .evaluate_literal()?
+                .binary(op, &right.evaluate_literal()?)
+                .ok(),
+            Expr::Not(expr) => match expr.evaluate_literal()? {
+                ScalarValue::Boolean(value) => Some(ScalarValue::Boolean(!value)),
+                ScalarValue::Null => Some(ScalarValue::Null),
+                _ => None,
+            },
+            Expr::IsNull(expr) => {
+                Some(ScalarValue::Boolean(expr.evaluate_literal()?.is_null()))
+            }
+            Expr::IsNotNull(expr) => {
+                Some(ScalarValue::Boolean(!expr.evaluate_literal()?.is_null()))
+            }
+            Expr::Cast { expr, data_type } => expr.evaluate_literal()?.cast_to(data_type),
+            _ => None,
+        }
+    }
+
+    /// Whether this is an untyped null literal, possibly with an alias
+    pub fn is_untyped_null(&self) -> bool {
+        match self {
+            Expr::Literal(ScalarValue::Null) => true,
+            Expr::Alias(expr, _) => expr.is_untyped_null(),
+            _ => false,
+        }
+    }
+
+    /// Get the expressions that this expression directly consists of, not including
+    /// those of subqueries
+    pub fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Alias(expr, _)
+            | Expr::Not(expr)
+            | Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::Cast { expr, .. }
+            | Expr::Sort { expr, .. }
+            | Expr::InSubquery { expr, .. } => vec![expr.as_ref()],
+            Expr::BinaryExpr { left, right, .. } => vec![left.as_ref(), right.as_ref()],
+            Expr::Between {
+                expr, low, high, ..
+            } => vec![expr.as_ref(), low.as_ref(), high.as_ref()],
+            Expr::InList { expr, list, .. } => {
+                std::iter::once(expr.as_ref()).chain(list.iter()).collect()
+            }
+            Expr::Like { expr, pattern, .. } => vec![expr.as_ref(), pattern.as_ref()],
+            Expr::ElementAt { list, index } => vec![list.as_ref(), index.as_ref()],
+            Expr::ScalarFunction { args, .. } | Expr::AggregateFunction { args, .. } => {
+                args.iter().collect()
+            }
+            Expr::WindowFunction {
+                args,
+                partition_by,
+                order_by,
+                ..
+            } => args
+                .iter()
+                .chain(partition_by.iter())
+                .chain(order_by.iter())
+                .collect(),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => when_then_expr
+                .iter()
+                .flat_map(|(when, then)| vec![when, then])
+                .chain(else_expr.iter().map(|e| e.as_ref()))
+                .collect(),
+            Expr::Column(_)
+            | Expr::UnresolvedColumn(_)
+            | Expr::Literal(_)
+            | Expr::Wildcard
+            | Expr::Exists { .. }
+            | Expr::ScalarSubquery(_)
+            | Expr::OuterColumn { .. } => vec![],
+        }
+    }
+
     /// Find the `DataType` for the expression
     pub fn get_type(&self, schema: &Schema) -> Result<DataType> {
         match self {
@@ -247,11 +1229,17 @@ impl Expr {
             Expr::UnresolvedColumn(name) => {
                 Ok(schema.field_with_name(&name)?.data_type().clone())
             }
+            Expr::Literal(ScalarValue::Null) => Ok(DataType::Utf8),
             Expr::Literal(l) => Ok(l.get_datatype()),
             Expr::Cast { data_type, .. } => Ok(data_type.clone()),
             Expr::ScalarFunction { return_type, .. } => Ok(return_type.clone()),
             Expr::AggregateFunction { return_type, .. } => Ok(return_type.clone()),
+            Expr::WindowFunction { return_type, .. } => Ok(return_type.clone()),
             Expr::Not(_) => Ok(DataType::Boolean),
+            Expr::Between { .. } => Ok(DataType::Boolean),
+            Expr::InList { .. } => Ok(DataType::Boolean),
+            Expr::Like { .. } => Ok(DataType::Boolean),
+            Expr::ElementAt { list, .. } => utils::element_type(&list.get_type(schema)?),
             Expr::IsNull(_) => Ok(DataType::Boolean),
             Expr::IsNotNull(_) => Ok(DataType::Boolean),
             Expr::BinaryExpr {
@@ -263,16 +1251,46 @@ impl Expr {
                 Operator::Lt | Operator::LtEq => Ok(DataType::Boolean),
                 Operator::Gt | Operator::GtEq => Ok(DataType::Boolean),
                 Operator::And | Operator::Or => Ok(DataType::Boolean),
-                _ => {
-                    let left_type = left.get_type(schema)?;
-                    let right_type = right.get_type(schema)?;
-                    utils::get_supertype(&left_type, &right_type)
+                Operator::RegexMatch | Operator::RegexIMatch => Ok(DataType::Boolean),
+                Operator::RegexNotMatch | Operator::RegexNotIMatch => {
+                    Ok(DataType::Boolean)
                 }
+                Operator::StringConcat => Ok(DataType::Utf8),
+                _ => utils::get_expr_supertype(&[left.as_ref(), right.as_ref()], schema),
             },
             Expr::Sort { ref expr, .. } => expr.get_type(schema),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => {
+                // the results are cast to their common supertype
+                let results: Vec<&Expr> = when_then_expr
+                    .iter()
+                    .map(|(_, then)| then)
+                    .chain(else_expr.iter().map(|e| e.as_ref()))
+                    .collect();
+                if results.is_empty() {
+                    return Err(ExecutionError::General(
+                        "CASE expression requires at least one WHEN clause".to_owned(),
+                    ));
+                }
+                utils::get_expr_supertype(&results, schema)
+            }
             Expr::Wildcard => Err(ExecutionError::General(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
+            Expr::Exists { .. } | Expr::InSubquery { .. } => Ok(DataType::Boolean),
+            Expr::ScalarSubquery(subquery) => {
+                let schema = subquery.plan().schema();
+                if schema.fields().len() != 1 {
+                    return Err(ExecutionError::General(format!(
+                        "Scalar subquery must return a single column but returns {}",
+                        schema.fields().len()
+                    )));
+                }
+                Ok(schema.field(0).data_type().clone())
+            }
+            Expr::OuterColumn { data_type, .. } => Ok(data_type.clone()),
         }
     }
 
@@ -283,7 +1301,7 @@ impl Expr {
         let this_type = self.get_type(schema)?;
         if this_type == *cast_to_type {
             Ok(self.clone())
-        } else if can_coerce_from(cast_to_type, &this_type) {
+        } else if self.is_untyped_null() || can_coerce_from(cast_to_type, &this_type) {
             Ok(Expr::Cast {
                 expr: Arc::new(self.clone()),
                 data_type: cast_to_type.clone(),
@@ -434,16 +1452,76 @@ impl fmt::Debug for Expr {
                 write!(f, "CAST({:?} AS {:?})", expr, data_type)
             }
             Expr::Not(expr) => write!(f, "NOT {:?}", expr),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                write!(f, "{:?} ", expr)?;
+                if *negated {
+                    write!(f, "NOT ")?;
+                }
+                write!(f, "BETWEEN {:?} AND {:?}", low, high)
+            }
+            Expr::ElementAt { list, index } => write!(f, "{:?}[{:?}]", list, index),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                write!(f, "{:?} ", expr)?;
+                if *negated {
+                    write!(f, "NOT ")?;
+                }
+                write!(f, "IN (")?;
+                for (i, value) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", value)?;
+                }
+                write!(f, ")")
+            }
+            Expr::Like {
+                expr,
+                pattern,
+                negated,
+                case_insensitive,
+                escape,
+            } => {
+                write!(f, "{:?} ", expr)?;
+                if *negated {
+                    write!(f, "NOT ")?;
+                }
+                let op = if *case_insensitive { "ILIKE" } else { "LIKE" };
+                write!(f, "{} {:?}", op, pattern)?;
+                if let Some(escape) = escape {
+                    write!(f, " ESCAPE {:?}", escape)?;
+                }
+                Ok(())
+            }
             Expr::IsNull(expr) => write!(f, "{:?} IS NULL", expr),
             Expr::IsNotNull(expr) => write!(f, "{:?} IS NOT NULL", expr),
             Expr::BinaryExpr { left, op, right } => {
                 write!(f, "{:?} {:?} {:?}", left, op, right)
             }
-            Expr::Sort { expr, asc } => {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => {
                 if *asc {
-                    write!(f, "{:?} ASC", expr)
+                    write!(f, "{:?} ASC", expr)?;
                 } else {
-                    write!(f, "{:?} DESC", expr)
+                    write!(f, "{:?} DESC", expr)?;
+                }
+                // the placement of nulls is only shown when they are not sorted as if
+                // they were larger than any other value
+                match (*asc, *nulls_first) {
+                    (true, true) => write!(f, " NULLS FIRST"),
+                    (false, false) => write!(f, " NULLS LAST"),
+                    _ => Ok(()),
                 }
             }
             Expr::ScalarFunction { name, ref args, .. } => {
@@ -468,21 +1546,98 @@ impl fmt::Debug for Expr {
 
                 write!(f, ")")
             }
-            Expr::Wildcard => write!(f, "*"),
-        }
-    }
-}
-
-/// The LogicalPlan represents different types of relations (such as Projection,
-/// Selection, etc) and can be created by the SQL query planner and the DataFrame API.
-#[derive(Clone)]
-pub enum LogicalPlan {
-    /// A Projection (essentially a SELECT with an expression list)
-    Projection {
-        /// The list of expressions
-        expr: Vec<Expr>,
-        /// The incoming logic plan
-        input: Arc<LogicalPlan>,
+            Expr::WindowFunction {
+                name,
+                ref args,
+                ref partition_by,
+                ref order_by,
+                ref window_frame,
+                ..
+            } => {
+                write!(f, "{}(", name)?;
+                for i in 0..args.len() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", args[i])?;
+                }
+                write!(f, ") OVER (")?;
+                if !partition_by.is_empty() {
+                    write!(f, "PARTITION BY ")?;
+                    for i in 0..partition_by.len() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{:?}", partition_by[i])?;
+                    }
+                }
+                if !order_by.is_empty() {
+                    if !partition_by.is_empty() {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "ORDER BY ")?;
+                    for i in 0..order_by.len() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{:?}", order_by[i])?;
+                    }
+                }
+                if let Some(window_frame) = window_frame {
+                    if !partition_by.is_empty() || !order_by.is_empty() {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", window_frame)?;
+                }
+                write!(f, ")")
+            }
+            Expr::Wildcard => write!(f, "*"),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => {
+                write!(f, "CASE")?;
+                for (when, then) in when_then_expr {
+                    write!(f, " WHEN {:?} THEN {:?}", when, then)?;
+                }
+                if let Some(else_expr) = else_expr {
+                    write!(f, " ELSE {:?}", else_expr)?;
+                }
+                write!(f, " END")
+            }
+            Expr::Exists { subquery, negated } => {
+                if *negated {
+                    write!(f, "NOT ")?;
+                }
+                write!(f, "EXISTS {:?}", subquery)
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                write!(f, "{:?} ", expr)?;
+                if *negated {
+                    write!(f, "NOT ")?;
+                }
+                write!(f, "IN {:?}", subquery)
+            }
+            Expr::ScalarSubquery(subquery) => write!(f, "{:?}", subquery),
+            Expr::OuterColumn { index, .. } => write!(f, "outer#{}", index),
+        }
+    }
+}
+
+/// The LogicalPlan represents different types of relations (such as Projection,
+/// Selection, etc) and can be created by the SQL query planner and the DataFrame API.
+#[derive(Clone, PartialEq, Eq)]
+pub enum LogicalPlan {
+    /// A Projection (essentially a SELECT with an expression list)
+    Projection {
+        /// The list of expressions
+        expr: Vec<Expr>,
+        /// The incoming logic plan
+        input: Arc<LogicalPlan>,
         /// The schema description
         schema: Arc<Schema>,
     },
@@ -504,6 +1659,16 @@ pub enum LogicalPlan {
         /// The schema description
         schema: Arc<Schema>,
     },
+    /// Evaluates window functions over the rows of a relation, appending a column for
+    /// each of them to the columns of the input
+    Window {
+        /// The incoming logic plan
+        input: Arc<LogicalPlan>,
+        /// The window function expressions
+        window_expr: Vec<Expr>,
+        /// The schema description
+        schema: Arc<Schema>,
+    },
     /// Represents a list of sort expressions to be applied to a relation
     Sort {
         /// The sort expressions
@@ -513,6 +1678,19 @@ pub enum LogicalPlan {
         /// The schema description
         schema: Arc<Schema>,
     },
+    /// Removes the rows whose keys are equal to those of an adjacent row, keeping the
+    /// first or last row of each run of rows with equal keys. The input must be sorted
+    /// by the keys to remove all duplicates.
+    Deduplicate {
+        /// The key expressions
+        keys: Vec<Expr>,
+        /// Whether the first row of each run is kept rather than the last
+        keep_first: bool,
+        /// The incoming logic plan
+        input: Arc<LogicalPlan>,
+        /// The schema description
+        schema: Arc<Schema>,
+    },
     /// A table scan against a table that has been registered on a context
     TableScan {
         /// The name of the schema
@@ -540,6 +1718,39 @@ pub enum LogicalPlan {
         /// The schema description
         schema: Arc<Schema>,
     },
+    /// Join two logical plans on one or more join columns
+    Join {
+        /// Left input
+        left: Arc<LogicalPlan>,
+        /// Right input
+        right: Arc<LogicalPlan>,
+        /// Equijoin clause expressed as pairs of (left, right) column indices, where the
+        /// right index is relative to the schema of the right input
+        on: Vec<(usize, usize)>,
+        /// Join type
+        join_type: JoinType,
+        /// Whether null keys match each other, as they do for `IS NOT DISTINCT FROM`
+        null_equals_null: bool,
+        /// The output schema, containing fields from the left and right inputs
+        schema: Arc<Schema>,
+    },
+    /// Produces the cartesian product of two relations
+    CrossJoin {
+        /// Left input
+        left: Arc<LogicalPlan>,
+        /// Right input
+        right: Arc<LogicalPlan>,
+        /// The output schema, containing fields from the left and right inputs
+        schema: Arc<Schema>,
+    },
+    /// Appends the rows of several relations, as done by `UNION ALL`
+    Union {
+        /// The inputs, whose columns are matched by position
+        inputs: Vec<Arc<LogicalPlan>>,
+        /// The schema description, with the column names of the first input and the
+        /// common type of the columns of all inputs
+        schema: Arc<Schema>,
+    },
     /// Represents a create external table expression.
     CreateExternalTable {
         /// The table schema
@@ -553,6 +1764,82 @@ pub enum LogicalPlan {
         /// Whether the CSV file contains a header
         header_row: bool,
     },
+    /// Scans a table to collect statistics about its data
+    AnalyzeTable {
+        /// The table name
+        name: String,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Removes a table from the execution context
+    DropTable {
+        /// The table name
+        name: String,
+        /// Whether a missing table is ignored rather than an error
+        if_exists: bool,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Changes a configuration variable of the session
+    SetVariable {
+        /// The variable name
+        variable: String,
+        /// The new value of the variable
+        value: String,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Shows the value of a configuration variable of the session, or of all variables
+    ShowVariable {
+        /// The variable name, or `None` for all variables
+        variable: Option<String>,
+        /// The schema description of the rows with the names and values of variables
+        schema: Arc<Schema>,
+    },
+    /// Appends the rows of a query to a registered table
+    Insert {
+        /// The table name
+        table_name: String,
+        /// The query producing the rows, whose columns are the columns of the table
+        input: Arc<LogicalPlan>,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Replaces the rows of a registered table with the rows of a query, as planned for
+    /// MERGE, UPDATE and DELETE statements
+    Overwrite {
+        /// The table name
+        table_name: String,
+        /// The query producing the new rows, whose columns are the columns of the table
+        input: Arc<LogicalPlan>,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Writes the rows of a query to a file
+    Copy {
+        /// The query producing the rows
+        input: Arc<LogicalPlan>,
+        /// Path of the file
+        location: String,
+        /// File type
+        file_type: FileType,
+        /// The schema description, which is empty since no rows are produced
+        schema: Arc<Schema>,
+    },
+    /// Describes the plan of a query instead of executing it
+    Explain {
+        /// Whether to execute the plan and describe the metrics of its physical plan
+        analyze: bool,
+        /// Whether to annotate each plan node with its output schema and the casts
+        /// added by type coercion
+        verbose: bool,
+        /// The format of the described plans
+        format: ExplainFormat,
+        /// The plan being explained
+        plan: Arc<LogicalPlan>,
+        /// The schema description of the rows describing the plan
+        schema: Arc<Schema>,
+    },
 }
 
 impl LogicalPlan {
@@ -566,9 +1853,191 @@ impl LogicalPlan {
             LogicalPlan::Projection { schema, .. } => &schema,
             LogicalPlan::Selection { input, .. } => input.schema(),
             LogicalPlan::Aggregate { schema, .. } => &schema,
+            LogicalPlan::Window { schema, .. } => &schema,
             LogicalPlan::Sort { schema, .. } => &schema,
+            LogicalPlan::Deduplicate { schema, .. } => &schema,
             LogicalPlan::Limit { schema, .. } => &schema,
+            LogicalPlan::Join { schema, .. } => &schema,
+            LogicalPlan::CrossJoin { schema, .. } => &schema,
+            LogicalPlan::Union { schema, .. } => &schema,
             LogicalPlan::CreateExternalTable { schema, .. } => &schema,
+            LogicalPlan::AnalyzeTable { schema, .. } => &schema,
+            LogicalPlan::DropTable { schema, .. } => &schema,
+            LogicalPlan::SetVariable { schema, .. } => &schema,
+            LogicalPlan::ShowVariable { schema, .. } => &schema,
+            LogicalPlan::Insert { schema, .. } => &schema,
+            LogicalPlan::Overwrite { schema, .. } => &schema,
+            LogicalPlan::Copy { schema, .. } => &schema,
+            LogicalPlan::Explain { schema, .. } => &schema,
+        }
+    }
+
+    /// Get the inputs of this plan node
+    pub fn inputs(&self) -> Vec<&LogicalPlan> {
+        match self {
+            LogicalPlan::Projection { input, .. }
+            | LogicalPlan::Selection { input, .. }
+            | LogicalPlan::Aggregate { input, .. }
+            | LogicalPlan::Window { input, .. }
+            | LogicalPlan::Sort { input, .. }
+            | LogicalPlan::Deduplicate { input, .. }
+            | LogicalPlan::Limit { input, .. }
+            | LogicalPlan::Insert { input, .. }
+            | LogicalPlan::Overwrite { input, .. }
+            | LogicalPlan::Copy { input, .. } => vec![input.as_ref()],
+            LogicalPlan::Join { left, right, .. }
+            | LogicalPlan::CrossJoin { left, right, .. } => {
+                vec![left.as_ref(), right.as_ref()]
+            }
+            LogicalPlan::Union { inputs, .. } => {
+                inputs.iter().map(|p| p.as_ref()).collect()
+            }
+            LogicalPlan::Explain { plan, .. } => vec![plan.as_ref()],
+            LogicalPlan::EmptyRelation { .. }
+            | LogicalPlan::TableScan { .. }
+            | LogicalPlan::CreateExternalTable { .. }
+            | LogicalPlan::AnalyzeTable { .. }
+            | LogicalPlan::DropTable { .. }
+            | LogicalPlan::SetVariable { .. }
+            | LogicalPlan::ShowVariable { .. } => vec![],
+        }
+    }
+
+    /// Get the expressions evaluated by this plan node, not including those of its
+    /// inputs
+    pub fn expressions(&self) -> Vec<&Expr> {
+        match self {
+            LogicalPlan::Projection { expr, .. } | LogicalPlan::Sort { expr, .. } => {
+                expr.iter().collect()
+            }
+            LogicalPlan::Selection { expr, .. } | LogicalPlan::Limit { expr, .. } => {
+                vec![expr]
+            }
+            LogicalPlan::Aggregate {
+                group_expr,
+                aggr_expr,
+                ..
+            } => group_expr.iter().chain(aggr_expr.iter()).collect(),
+            LogicalPlan::Window { window_expr, .. } => window_expr.iter().collect(),
+            LogicalPlan::Deduplicate { keys, .. } => keys.iter().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Whether a column of this plan only contains untyped nulls, such as the column of
+    /// `SELECT NULL` or a column of a VALUES list that is NULL in every row
+    pub fn is_untyped_null_column(&self, column: usize) -> bool {
+        match self {
+            LogicalPlan::Projection { expr, .. } => {
+                expr.get(column).map_or(false, |e| e.is_untyped_null())
+            }
+            LogicalPlan::Union { inputs, .. } => inputs
+                .iter()
+                .all(|input| input.is_untyped_null_column(column)),
+            _ => false,
+        }
+    }
+
+    /// Format the plan as one line per node, indented by depth and annotated with the
+    /// output schema of each node. If `uncoerced` is given, it must be the same plan
+    /// before type coercion was applied, and each node is also annotated with the casts
+    /// that type coercion added to its expressions.
+    pub fn explain_lines(&self, uncoerced: Option<&LogicalPlan>) -> Vec<String> {
+        let mut lines = vec![];
+        self.explain_with_indent(uncoerced, 0, &mut lines);
+        lines
+    }
+
+    /// A hash of the nodes and expressions of the plan that is the same in every
+    /// process, which identifies queries with the same plan in logs independently of
+    /// their SQL text
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Describe the plan as a directed graph in the DOT language of Graphviz, with a
+    /// node for each plan node and an edge from each input to the node that it is an
+    /// input of
+    pub fn to_graphviz(&self) -> String {
+        let mut lines = vec!["digraph LogicalPlan {".to_string()];
+        self.graphviz_nodes(&mut 0, &mut lines);
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Add the nodes and edges of the plan to a graph, numbering the nodes from the
+    /// given id. Returns the id of the node of this plan.
+    fn graphviz_nodes(&self, next_id: &mut usize, lines: &mut Vec<String>) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let escape = |s: String| s.replace('\\', "\\\\").replace('"', "\\\"");
+        // the description of the node and its schema are on separate lines
+        lines.push(format!(
+            "  node{} [shape=box label=\"{}\\nschema={}\"]",
+            id,
+            escape(PlanNode(self).to_string()),
+            escape(format_schema(self.schema()))
+        ));
+        for input in self.inputs() {
+            let input_id = input.graphviz_nodes(next_id, lines);
+            lines.push(format!("  node{} -> node{}", input_id, id));
+        }
+        id
+    }
+
+    /// Describe the plan as a JSON object with the description of the node, the fields
+    /// of its output schema and its inputs as nested objects
+    pub fn to_json(&self) -> serde_json::Value {
+        let schema: Vec<serde_json::Value> = self
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| {
+                serde_json::json!({
+                    "name": field.name(),
+                    "data_type": format!("{:?}", field.data_type()),
+                    "nullable": field.is_nullable(),
+                })
+            })
+            .collect();
+        let inputs: Vec<serde_json::Value> =
+            self.inputs().iter().map(|input| input.to_json()).collect();
+        serde_json::json!({
+            "node": PlanNode(self).to_string(),
+            "schema": schema,
+            "inputs": inputs,
+        })
+    }
+
+    fn explain_with_indent(
+        &self,
+        uncoerced: Option<&LogicalPlan>,
+        indent: usize,
+        lines: &mut Vec<String>,
+    ) {
+        let mut line = format!(
+            "{}{} schema={}",
+            "  ".repeat(indent),
+            PlanNode(self),
+            format_schema(self.schema())
+        );
+        if let Some(uncoerced) = uncoerced {
+            let casts = implicit_casts(&uncoerced.expressions(), &self.expressions());
+            if !casts.is_empty() {
+                line.push_str(&format!(" implicit_casts=[{}]", casts.join(", ")));
+            }
+        }
+        lines.push(line);
+
+        let uncoerced_inputs = uncoerced.map(|p| p.inputs()).unwrap_or_default();
+        for (i, input) in self.inputs().into_iter().enumerate() {
+            input.explain_with_indent(
+                uncoerced_inputs.get(i).cloned(),
+                indent + 1,
+                lines,
+            );
         }
     }
 }
@@ -581,6 +2050,38 @@ impl LogicalPlan {
                 write!(f, "  ")?;
             }
         }
+        self.fmt_node(f)?;
+        for input in self.inputs() {
+            input.fmt_with_indent(f, indent + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Format the plan on a single line, with the inputs of each node following an
+    /// arrow, as done for subqueries within expressions
+    fn fmt_inline(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_node(f)?;
+        match self.inputs().as_slice() {
+            [] => Ok(()),
+            [input] => {
+                write!(f, " <- ")?;
+                input.fmt_inline(f)
+            }
+            inputs => {
+                write!(f, " <- [")?;
+                for (i, input) in inputs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    input.fmt_inline(f)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+
+    /// Format this node of the plan, without its inputs
+    fn fmt_node(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             LogicalPlan::EmptyRelation { .. } => write!(f, "EmptyRelation"),
             LogicalPlan::TableScan {
@@ -588,11 +2089,7 @@ impl LogicalPlan {
                 ref projection,
                 ..
             } => write!(f, "TableScan: {} projection={:?}", table_name, projection),
-            LogicalPlan::Projection {
-                ref expr,
-                ref input,
-                ..
-            } => {
+            LogicalPlan::Projection { ref expr, .. } => {
                 write!(f, "Projection: ")?;
                 for i in 0..expr.len() {
                     if i > 0 {
@@ -600,34 +2097,22 @@ impl LogicalPlan {
                     }
                     write!(f, "{:?}", expr[i])?;
                 }
-                input.fmt_with_indent(f, indent + 1)
-            }
-            LogicalPlan::Selection {
-                ref expr,
-                ref input,
-                ..
-            } => {
-                write!(f, "Selection: {:?}", expr)?;
-                input.fmt_with_indent(f, indent + 1)
+                Ok(())
             }
+            LogicalPlan::Selection { ref expr, .. } => write!(f, "Selection: {:?}", expr),
             LogicalPlan::Aggregate {
-                ref input,
                 ref group_expr,
                 ref aggr_expr,
                 ..
-            } => {
-                write!(
-                    f,
-                    "Aggregate: groupBy=[{:?}], aggr=[{:?}]",
-                    group_expr, aggr_expr
-                )?;
-                input.fmt_with_indent(f, indent + 1)
-            }
-            LogicalPlan::Sort {
-                ref input,
-                ref expr,
-                ..
-            } => {
+            } => write!(
+                f,
+                "Aggregate: groupBy=[{:?}], aggr=[{:?}]",
+                group_expr, aggr_expr
+            ),
+            LogicalPlan::Window {
+                ref window_expr, ..
+            } => write!(f, "Window: windowExpr=[{:?}]", window_expr),
+            LogicalPlan::Sort { ref expr, .. } => {
                 write!(f, "Sort: ")?;
                 for i in 0..expr.len() {
                     if i > 0 {
@@ -635,19 +2120,331 @@ impl LogicalPlan {
                     }
                     write!(f, "{:?}", expr[i])?;
                 }
-                input.fmt_with_indent(f, indent + 1)
+                Ok(())
             }
-            LogicalPlan::Limit {
-                ref input,
-                ref expr,
+            LogicalPlan::Deduplicate {
+                ref keys,
+                keep_first,
+                ..
+            } => write!(
+                f,
+                "Deduplicate: keys={:?}, keep={}",
+                keys,
+                if keep_first { "first" } else { "last" }
+            ),
+            LogicalPlan::Limit { ref expr, .. } => write!(f, "Limit: {:?}", expr),
+            LogicalPlan::Join {
+                ref on,
+                ref join_type,
+                null_equals_null,
                 ..
             } => {
-                write!(f, "Limit: {:?}", expr)?;
-                input.fmt_with_indent(f, indent + 1)
+                write!(f, "Join: type={:?}, on=[", join_type)?;
+                for i in 0..on.len() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "#{} = #{}", on[i].0, on[i].1)?;
+                }
+                write!(f, "]")?;
+                if null_equals_null {
+                    write!(f, ", null_equals_null")?;
+                }
+                Ok(())
             }
+            LogicalPlan::CrossJoin { .. } => write!(f, "CrossJoin:"),
+            LogicalPlan::Union { .. } => write!(f, "Union:"),
             LogicalPlan::CreateExternalTable { ref name, .. } => {
                 write!(f, "CreateExternalTable: {:?}", name)
             }
+            LogicalPlan::AnalyzeTable { ref name, .. } => {
+                write!(f, "AnalyzeTable: {:?}", name)
+            }
+            LogicalPlan::DropTable { ref name, .. } => {
+                write!(f, "DropTable: {:?}", name)
+            }
+            LogicalPlan::SetVariable {
+                ref variable,
+                ref value,
+                ..
+            } => write!(f, "SetVariable: {} = {:?}", variable, value),
+            LogicalPlan::ShowVariable { ref variable, .. } => match variable {
+                Some(variable) => write!(f, "ShowVariable: {}", variable),
+                None => write!(f, "ShowVariable: ALL"),
+            },
+            LogicalPlan::Insert { ref table_name, .. } => {
+                write!(f, "Insert: {:?}", table_name)
+            }
+            LogicalPlan::Overwrite { ref table_name, .. } => {
+                write!(f, "Overwrite: {:?}", table_name)
+            }
+            LogicalPlan::Copy {
+                ref location,
+                ref file_type,
+                ..
+            } => write!(f, "Copy: {:?} format={:?}", location, file_type),
+            LogicalPlan::Explain {
+                analyze,
+                verbose,
+                format,
+                ..
+            } => {
+                write!(f, "Explain:")?;
+                if analyze {
+                    write!(f, " analyze")?;
+                }
+                if verbose {
+                    write!(f, " verbose")?;
+                }
+                if format != ExplainFormat::Text {
+                    write!(f, " format={:?}", format)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Format the fields of a schema as a list of `name:type` pairs
+pub fn format_schema(schema: &Schema) -> String {
+    let fields: Vec<String> = schema
+        .fields()
+        .iter()
+        .map(|f| format!("{}:{:?}", f.name(), f.data_type()))
+        .collect();
+    format!("[{}]", fields.join(", "))
+}
+
+/// Displays a single node of a logical plan, without its inputs
+struct PlanNode<'a>(&'a LogicalPlan);
+
+impl<'a> fmt::Display for PlanNode<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_node(f)
+    }
+}
+
+/// Find the casts in `coerced` that are not present in `uncoerced`, which are the
+/// casts added by type coercion
+fn implicit_casts(uncoerced: &[&Expr], coerced: &[&Expr]) -> Vec<String> {
+    let mut explicit = vec![];
+    uncoerced
+        .iter()
+        .for_each(|e| collect_casts(e, &mut explicit));
+    let mut casts = vec![];
+    coerced.iter().for_each(|e| collect_casts(e, &mut casts));
+    casts
+        .into_iter()
+        .filter(|cast| match explicit.iter().position(|e| e == cast) {
+            Some(i) => {
+                explicit.remove(i);
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+/// Collect the formatted casts in an expression
+fn collect_casts(expr: &Expr, casts: &mut Vec<String>) {
+    match expr {
+        Expr::Cast { expr: inner, .. } => {
+            casts.push(format!("{:?}", expr));
+            collect_casts(inner, casts);
+        }
+        Expr::Alias(expr, _)
+        | Expr::Not(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Sort { expr, .. }
+        | Expr::InSubquery { expr, .. } => collect_casts(expr, casts),
+        Expr::BinaryExpr { left, right, .. } => {
+            collect_casts(left, casts);
+            collect_casts(right, casts);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            collect_casts(expr, casts);
+            collect_casts(low, casts);
+            collect_casts(high, casts);
+        }
+        Expr::ElementAt { list, index } => {
+            collect_casts(list, casts);
+            collect_casts(index, casts);
+        }
+        Expr::InList { expr, list, .. } => {
+            collect_casts(expr, casts);
+            list.iter().for_each(|e| collect_casts(e, casts))
+        }
+        Expr::Like { expr, pattern, .. } => {
+            collect_casts(expr, casts);
+            collect_casts(pattern, casts);
+        }
+        Expr::ScalarFunction { args, .. } | Expr::AggregateFunction { args, .. } => {
+            args.iter().for_each(|e| collect_casts(e, casts))
+        }
+        Expr::WindowFunction {
+            args,
+            partition_by,
+            order_by,
+            ..
+        } => args
+            .iter()
+            .chain(partition_by.iter())
+            .chain(order_by.iter())
+            .for_each(|e| collect_casts(e, casts)),
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => {
+            for (when, then) in when_then_expr {
+                collect_casts(when, casts);
+                collect_casts(then, casts);
+            }
+            if let Some(else_expr) = else_expr {
+                collect_casts(else_expr, casts);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Hash for LogicalPlan {
+    /// Hashes the nodes and expressions of the plan but not the schemas, which are
+    /// derived from them, so that equal plans have equal hashes
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            LogicalPlan::Projection { expr, input, .. } => {
+                expr.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::Selection { expr, input } => {
+                expr.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                ..
+            } => {
+                input.hash(state);
+                group_expr.hash(state);
+                aggr_expr.hash(state);
+            }
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => {
+                input.hash(state);
+                window_expr.hash(state);
+            }
+            LogicalPlan::Sort { expr, input, .. } => {
+                expr.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::Deduplicate {
+                keys,
+                keep_first,
+                input,
+                ..
+            } => {
+                keys.hash(state);
+                keep_first.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::TableScan {
+                schema_name,
+                table_name,
+                projection,
+                ..
+            } => {
+                schema_name.hash(state);
+                table_name.hash(state);
+                projection.hash(state);
+            }
+            LogicalPlan::EmptyRelation { .. } => {}
+            LogicalPlan::Limit { expr, input, .. } => {
+                expr.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => {
+                left.hash(state);
+                right.hash(state);
+                on.hash(state);
+                join_type.hash(state);
+                null_equals_null.hash(state);
+            }
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                left.hash(state);
+                right.hash(state);
+            }
+            LogicalPlan::Union { inputs, .. } => inputs.hash(state),
+            LogicalPlan::CreateExternalTable {
+                name,
+                location,
+                file_type,
+                header_row,
+                ..
+            } => {
+                name.hash(state);
+                location.hash(state);
+                file_type.hash(state);
+                header_row.hash(state);
+            }
+            LogicalPlan::AnalyzeTable { name, .. } => name.hash(state),
+            LogicalPlan::DropTable {
+                name, if_exists, ..
+            } => {
+                name.hash(state);
+                if_exists.hash(state);
+            }
+            LogicalPlan::SetVariable {
+                variable, value, ..
+            } => {
+                variable.hash(state);
+                value.hash(state);
+            }
+            LogicalPlan::ShowVariable { variable, .. } => variable.hash(state),
+            LogicalPlan::Insert {
+                table_name, input, ..
+            }
+            | LogicalPlan::Overwrite {
+                table_name, input, ..
+            } => {
+                table_name.hash(state);
+                input.hash(state);
+            }
+            LogicalPlan::Copy {
+                input,
+                location,
+                file_type,
+                ..
+            } => {
+                input.hash(state);
+                location.hash(state);
+                file_type.hash(state);
+            }
+            LogicalPlan::Explain {
+                analyze,
+                verbose,
+                format,
+                plan,
+                ..
+            } => {
+                analyze.hash(state);
+                verbose.hash(state);
+                format.hash(state);
+                plan.hash(state);
+            }
         }
     }
 }
@@ -661,6 +2458,10 @@ impl fmt::Debug for LogicalPlan {
 /// Verify a given type cast can be performed
 pub fn can_coerce_from(type_into: &DataType, type_from: &DataType) -> bool {
     use self::DataType::*;
+    // dictionaries are coerced by coercing their values, whatever their key type
+    if let Dictionary(_, ref value_type) = type_from {
+        return **value_type == *type_into || can_coerce_from(type_into, value_type);
+    }
     match type_into {
         Int8 => match type_from {
             Int8 => true,
@@ -805,6 +2606,18 @@ impl LogicalPlanBuilder {
         }))
     }
 
+    /// Remove the rows whose keys are equal to those of the previous row, keeping the
+    /// first or last row of each run. The plan must be sorted by the keys to remove all
+    /// duplicates.
+    pub fn deduplicate(&self, keys: Vec<Expr>, keep_first: bool) -> Result<Self> {
+        Ok(Self::from(&LogicalPlan::Deduplicate {
+            keys,
+            keep_first,
+            input: Arc::new(self.plan.clone()),
+            schema: self.plan.schema().clone(),
+        }))
+    }
+
     /// Apply an aggregate
     pub fn aggregate(&self, group_expr: Vec<Expr>, aggr_expr: Vec<Expr>) -> Result<Self> {
         let mut all_fields: Vec<Expr> = group_expr.clone();
@@ -821,6 +2634,201 @@ impl LogicalPlanBuilder {
         }))
     }
 
+    /// Apply window functions, whose values are appended to the columns of the input
+    pub fn window(&self, window_expr: Vec<Expr>) -> Result<Self> {
+        let mut fields = self.plan.schema().fields().clone();
+        fields.extend(utils::exprlist_to_fields(&window_expr, self.plan.schema())?);
+
+        Ok(Self::from(&LogicalPlan::Window {
+            input: Arc::new(self.plan.clone()),
+            window_expr,
+            schema: Arc::new(Schema::new(fields)),
+        }))
+    }
+
+    /// Apply a join with the given right input, joining on pairs of (left, right) column
+    /// indices. Rows with null keys do not match any other row.
+    pub fn join(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        on: Vec<(usize, usize)>,
+    ) -> Result<Self> {
+        self.join_detailed(right, join_type, on, false)
+    }
+
+    /// Apply a join with the given right input, joining on pairs of (left, right) column
+    /// indices. When `null_equals_null` is true, null keys match each other instead of
+    /// never matching.
+    pub fn join_detailed(
+        &self,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        on: Vec<(usize, usize)>,
+        null_equals_null: bool,
+    ) -> Result<Self> {
+        let left_schema = self.plan.schema();
+        let right_schema = right.schema();
+
+        for (l, r) in &on {
+            if *l >= left_schema.fields().len() || *r >= right_schema.fields().len() {
+                return Err(ExecutionError::General(format!(
+                    "Invalid join column pair (#{}, #{})",
+                    l, r
+                )));
+            }
+            let left_type = left_schema.field(*l).data_type();
+            let right_type = right_schema.field(*r).data_type();
+            if left_type != right_type {
+                return Err(ExecutionError::General(format!(
+                    "Join keys must have the same type but found {:?} and {:?}",
+                    left_type, right_type
+                )));
+            }
+        }
+
+        Ok(Self::from(&LogicalPlan::Join {
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            on,
+            join_type,
+            null_equals_null,
+            schema: Arc::new(build_join_schema(&left_schema, &right_schema, &join_type)),
+        }))
+    }
+
+    /// Apply a cross join, producing every combination of the rows of both plans
+    pub fn cross_join(&self, right: &LogicalPlan) -> Result<Self> {
+        let schema =
+            build_join_schema(&self.plan.schema(), &right.schema(), &JoinType::Inner);
+        Ok(Self::from(&LogicalPlan::CrossJoin {
+            left: Arc::new(self.plan.clone()),
+            right: Arc::new(right.clone()),
+            schema: Arc::new(schema),
+        }))
+    }
+
+    /// Apply a union, appending the rows of the given plan to the rows of this plan.
+    /// Columns are matched by position and take their names from this plan, and columns
+    /// of different types are coerced to a common type.
+    pub fn union(&self, other: &LogicalPlan) -> Result<Self> {
+        // a chain of unions is flattened into a single union
+        let mut inputs = match &self.plan {
+            LogicalPlan::Union { inputs, .. } => inputs.clone(),
+            plan => vec![Arc::new(plan.clone())],
+        };
+        inputs.push(Arc::new(other.clone()));
+
+        let schema = self.plan.schema();
+        let mut fields = schema.fields().clone();
+        // columns of untyped nulls take the type of the other inputs
+        let mut untyped: Vec<bool> = (0..fields.len())
+            .map(|i| self.plan.is_untyped_null_column(i))
+            .collect();
+        for input in &inputs[1..] {
+            let input_fields = input.schema().fields();
+            if input_fields.len() != fields.len() {
+                return Err(ExecutionError::General(format!(
+                    "Inputs of a union must have the same number of columns but found \
+                     {} and {}",
+                    fields.len(),
+                    input_fields.len()
+                )));
+            }
+            for (i, (field, input_field)) in
+                fields.iter_mut().zip(input_fields.iter()).enumerate()
+            {
+                let input_untyped = input.is_untyped_null_column(i);
+                let data_type =
+                    if field.data_type() == input_field.data_type() || input_untyped {
+                        field.data_type().clone()
+                    } else if untyped[i] {
+                        input_field.data_type().clone()
+                    } else {
+                        utils::get_supertype(field.data_type(), input_field.data_type())?
+                    };
+                *field = Field::new(
+                    field.name(),
+                    data_type,
+                    field.is_nullable() || input_field.is_nullable(),
+                );
+                untyped[i] = untyped[i] && input_untyped;
+            }
+        }
+
+        Ok(Self::from(&LogicalPlan::Union {
+            inputs,
+            schema: Arc::new(Schema::new(fields)),
+        }))
+    }
+
+    /// Remove duplicate rows by grouping on all columns
+    pub fn distinct(&self) -> Result<Self> {
+        let group_expr = (0..self.plan.schema().fields().len())
+            .map(col_index)
+            .collect();
+        self.aggregate(group_expr, vec![])
+    }
+
+    /// Apply an intersection, keeping the distinct rows of this plan that also occur in
+    /// the given plan. Columns are matched and coerced as for a union, and null values
+    /// are considered equal to each other.
+    pub fn intersect(&self, other: &LogicalPlan) -> Result<Self> {
+        self.distinct_semi_join(other, JoinType::LeftSemi)
+    }
+
+    /// Apply a set difference, keeping the distinct rows of this plan that do not occur
+    /// in the given plan. Columns are matched and coerced as for a union, and null
+    /// values are considered equal to each other.
+    pub fn except(&self, other: &LogicalPlan) -> Result<Self> {
+        self.distinct_semi_join(other, JoinType::LeftAnti)
+    }
+
+    /// Join the distinct rows of this plan with the given plan on all columns using a
+    /// semi or anti join
+    fn distinct_semi_join(
+        &self,
+        other: &LogicalPlan,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        // the columns of both plans are coerced to the types of a union of the plans
+        let schema = self.union(other)?.plan.schema().clone();
+        let left = Self::from(&self.plan).coerce_columns(&schema)?.distinct()?;
+        let right = Self::from(other).coerce_columns(&schema)?.build()?;
+        let on = (0..schema.fields().len()).map(|i| (i, i)).collect();
+        left.join_detailed(&right, join_type, on, true)
+    }
+
+    /// Cast the columns of this plan to the types of the columns at the same position
+    /// in the given schema, keeping their names
+    fn coerce_columns(&self, schema: &Schema) -> Result<Self> {
+        let input_schema = self.plan.schema();
+        if input_schema
+            .fields()
+            .iter()
+            .zip(schema.fields().iter())
+            .all(|(f, target)| f.data_type() == target.data_type())
+        {
+            return Ok(Self::from(&self.plan));
+        }
+        let expr = input_schema
+            .fields()
+            .iter()
+            .zip(schema.fields().iter())
+            .enumerate()
+            .map(|(i, (f, target))| {
+                if f.data_type() == target.data_type() {
+                    Ok(col_index(i))
+                } else {
+                    Ok(col_index(i)
+                        .cast_to(target.data_type(), &input_schema)?
+                        .alias(f.name()))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.project(expr)
+    }
+
     /// Build the plan
     pub fn build(&self) -> Result<LogicalPlan> {
         Ok(self.plan.clone())
@@ -899,6 +2907,405 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_join() -> Result<()> {
+        let right = LogicalPlanBuilder::scan(
+            "default",
+            "department.csv",
+            &department_schema(),
+            None,
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .join(&right, JoinType::Inner, vec![(3, 0)])?
+        .project(vec![col_index(0), col_index(6)])?
+        .build()?;
+
+        let expected = "Projection: #0, #6\
+        \n  Join: type=Inner, on=[#3 = #0]\
+        \n    TableScan: employee.csv projection=None\
+        \n    TableScan: department.csv projection=None";
+
+        assert_eq!(expected, format!("{:?}", plan));
+        assert_eq!(7, plan.schema().fields().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_left_join_schema() -> Result<()> {
+        let right = LogicalPlanBuilder::scan(
+            "default",
+            "department.csv",
+            &department_schema(),
+            None,
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .join(&right, JoinType::Left, vec![(3, 0)])?
+        .build()?;
+
+        // only the fields of the right input can be null
+        let schema = plan.schema();
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(5).is_nullable());
+        assert!(schema.field(6).is_nullable());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_cross_join() -> Result<()> {
+        let right = LogicalPlanBuilder::scan(
+            "default",
+            "department.csv",
+            &department_schema(),
+            None,
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .cross_join(&right)?
+        .build()?;
+
+        let expected = "CrossJoin:\
+        \n  TableScan: employee.csv projection=None\
+        \n  TableScan: department.csv projection=None";
+
+        assert_eq!(expected, format!("{:?}", plan));
+        assert_eq!(7, plan.schema().fields().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_join_type_mismatch() -> Result<()> {
+        let right = LogicalPlanBuilder::scan(
+            "default",
+            "department.csv",
+            &department_schema(),
+            None,
+        )?
+        .build()?;
+
+        let result = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .join(&right, JoinType::Inner, vec![(0, 0)]);
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_union() -> Result<()> {
+        let other_schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        let other =
+            LogicalPlanBuilder::scan("default", "other.csv", &other_schema, None)?
+                .build()?;
+
+        let plan = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .project(vec![col("id"), col("salary")])?
+        .union(&other)?
+        .union(&other)?
+        .build()?;
+
+        let expected = "Union:\
+        \n  Projection: #id, #salary\
+        \n    TableScan: employee.csv projection=None\
+        \n  TableScan: other.csv projection=None\
+        \n  TableScan: other.csv projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        let expected_schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("salary", DataType::Float64, true),
+        ]);
+        assert_eq!(&expected_schema, plan.schema().as_ref());
+
+        // the inputs must have the same number of columns
+        let result = LogicalPlanBuilder::from(&other).union(
+            &LogicalPlanBuilder::scan(
+                "default",
+                "department.csv",
+                &department_schema(),
+                Some(vec![0]),
+            )?
+            .build()?,
+        );
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_intersect_except() -> Result<()> {
+        let other_schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Float64, true),
+        ]);
+        let other =
+            LogicalPlanBuilder::scan("default", "other.csv", &other_schema, None)?
+                .build()?;
+        let employees = LogicalPlanBuilder::scan(
+            "default",
+            "employee.csv",
+            &employee_schema(),
+            None,
+        )?
+        .project(vec![col("id"), col("salary")])?;
+
+        let plan = employees.intersect(&other)?.build()?;
+        let expected = "Join: type=LeftSemi, on=[#0 = #0, #1 = #1], null_equals_null\
+        \n  Aggregate: groupBy=[[#0, #1]], aggr=[[]]\
+        \n    Projection: CAST(#0 AS Int64) AS id, CAST(#1 AS Float64) AS salary\
+        \n      Projection: #id, #salary\
+        \n        TableScan: employee.csv projection=None\
+        \n  TableScan: other.csv projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        let expected_schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("salary", DataType::Float64, true),
+        ]);
+        assert_eq!(&expected_schema, plan.schema().as_ref());
+
+        // the columns of the other plan are cast when their type differs
+        let plan = LogicalPlanBuilder::from(&other)
+            .except(&employees.build()?)?
+            .build()?;
+        let expected = "Join: type=LeftAnti, on=[#0 = #0, #1 = #1], null_equals_null\
+        \n  Aggregate: groupBy=[[#0, #1]], aggr=[[]]\
+        \n    TableScan: other.csv projection=None\
+        \n  Projection: CAST(#0 AS Int64) AS id, CAST(#1 AS Float64) AS salary\
+        \n    Projection: #id, #salary\
+        \n      TableScan: employee.csv projection=None";
+        assert_eq!(expected, format!("{:?}", plan));
+
+        Ok(())
+    }
+
+    fn department_schema() -> Schema {
+        Schema::new(vec![
+            Field::new("state", DataType::Utf8, false),
+            Field::new("manager", DataType::Utf8, false),
+        ])
+    }
+
+    #[test]
+    fn plans_are_compared_and_hashed_structurally() -> Result<()> {
+        let plan = |state: &str| {
+            LogicalPlanBuilder::scan(
+                "default",
+                "employee.csv",
+                &employee_schema(),
+                Some(vec![0, 3]),
+            )?
+            .filter(col("state").eq(&lit_str(state)))?
+            .project(vec![col("id")])?
+            .build()
+        };
+        let hash = |plan: &LogicalPlan| {
+            let mut hasher = FnvHasher::default();
+            plan.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(plan("CO")?, plan("CO")?);
+        assert_eq!(hash(&plan("CO")?), hash(&plan("CO")?));
+        assert_eq!(plan("CO")?.fingerprint(), plan("CO")?.fingerprint());
+        assert_ne!(plan("CO")?, plan("CA")?);
+        assert_ne!(hash(&plan("CO")?), hash(&plan("CA")?));
+
+        // subqueries are compared by their plans
+        let exists = |state: &str| -> Result<Expr> {
+            Ok(Expr::Exists {
+                subquery: Subquery::new(&plan(state)?),
+                negated: false,
+            })
+        };
+        assert_eq!(exists("CO")?, exists("CO")?);
+        assert_ne!(exists("CO")?, exists("CA")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_values_are_compared_by_bits() {
+        let nan = ScalarValue::Float64(std::f64::NAN);
+        assert_eq!(nan, nan.clone());
+        assert_ne!(ScalarValue::Float32(0.0), ScalarValue::Float32(-0.0));
+        assert_ne!(ScalarValue::Int32(1), ScalarValue::Int64(1));
+    }
+
+    #[test]
+    fn scalar_value_arithmetic() -> Result<()> {
+        let sum =
+            ScalarValue::Int32(5).binary(&Operator::Plus, &ScalarValue::UInt8(3))?;
+        assert_eq!(sum, ScalarValue::Int32(8));
+        let quotient = ScalarValue::Int64(7)
+            .binary(&Operator::Divide, &ScalarValue::Float64(2.0))?;
+        assert_eq!(quotient, ScalarValue::Float64(3.5));
+        let remainder =
+            ScalarValue::UInt32(7).binary(&Operator::Modulus, &ScalarValue::UInt32(4))?;
+        assert_eq!(remainder, ScalarValue::UInt32(3));
+        let null = ScalarValue::Null.binary(&Operator::Minus, &ScalarValue::Int32(1))?;
+        assert_eq!(null, ScalarValue::Null);
+
+        assert!(ScalarValue::Int8(100)
+            .binary(&Operator::Multiply, &ScalarValue::Int8(2))
+            .is_err());
+        assert!(ScalarValue::Int32(1)
+            .binary(&Operator::Divide, &ScalarValue::Int32(0))
+            .is_err());
+        assert!(ScalarValue::Utf8("a".to_string())
+            .binary(&Operator::Plus, &ScalarValue::Int32(1))
+            .is_err());
+
+        let less =
+            ScalarValue::UInt64(3).binary(&Operator::Lt, &ScalarValue::Float32(3.5))?;
+        assert_eq!(less, ScalarValue::Boolean(true));
+        let equal = ScalarValue::Utf8("a".to_string())
+            .binary(&Operator::Eq, &ScalarValue::Utf8("b".to_string()))?;
+        assert_eq!(equal, ScalarValue::Boolean(false));
+        Ok(())
+    }
+
+    #[test]
+    fn temporal_arithmetic() -> Result<()> {
+        // 2020-01-31 and 2020-02-29
+        let date = ScalarValue::Date32(18292);
+        let month = ScalarValue::IntervalYearMonth(1);
+        assert_eq!(date.add(&month)?, ScalarValue::Date32(18321));
+        assert_eq!(month.add(&date)?, ScalarValue::Date32(18321));
+        assert_eq!(
+            ScalarValue::Date32(18321).sub(&ScalarValue::IntervalYearMonth(13))?,
+            ScalarValue::Date32(17925)
+        );
+
+        let hour = ScalarValue::interval_day_time(0, 3_600_000);
+        let timestamp = date.add(&hour)?;
+        assert_eq!(
+            timestamp,
+            ScalarValue::TimestampNanosecond(
+                18292 * NANOSECONDS_PER_DAY + 3_600_000 * NANOSECONDS_PER_MILLISECOND
+            )
+        );
+        assert_eq!(date.compare(&timestamp), Some(Ordering::Less));
+        assert_eq!(timestamp.sub(&hour)?.compare(&date), Some(Ordering::Equal));
+        assert_eq!(
+            ScalarValue::Date32(18292).sub(&ScalarValue::Date32(18262))?,
+            ScalarValue::interval_day_time(30, 0)
+        );
+        assert_eq!(
+            timestamp.sub(&ScalarValue::Date32(18291))?,
+            ScalarValue::interval_day_time(1, 3_600_000)
+        );
+
+        let day = ScalarValue::interval_day_time(1, 0);
+        assert_eq!(
+            day.compare(&ScalarValue::interval_day_time(0, 86_400_001)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            day.add(&hour)?,
+            ScalarValue::interval_day_time(1, 3_600_000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decimal_scalar_values() {
+        let price = ScalarValue::Decimal128(1050, 10, 2);
+        assert_eq!(price.get_datatype(), DataType::Decimal(10, 2));
+        assert_eq!(
+            price.compare(&ScalarValue::Decimal128(105, 5, 1)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(price.compare(&ScalarValue::Int64(11)), Some(Ordering::Less));
+        assert_eq!(
+            price.compare(&ScalarValue::Float64(10.25)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            ScalarValue::Float64(0.1).cast_to(&DataType::Decimal(5, 2)),
+            Some(ScalarValue::Decimal128(10, 5, 2))
+        );
+        assert_eq!(
+            ScalarValue::Int32(7).cast_to(&DataType::Decimal(5, 2)),
+            Some(ScalarValue::Decimal128(700, 5, 2))
+        );
+        assert_eq!(
+            price.cast_to(&DataType::Decimal(3, 1)),
+            Some(ScalarValue::Decimal128(105, 3, 1))
+        );
+        assert_eq!(price.cast_to(&DataType::Decimal(3, 2)), None);
+        assert_eq!(
+            price.cast_to(&DataType::Float64),
+            Some(ScalarValue::Float64(10.5))
+        );
+        assert_eq!(
+            price.cast_to(&DataType::Int32),
+            Some(ScalarValue::Int32(10))
+        );
+    }
+
+    #[test]
+    fn evaluate_literal_expressions() {
+        let expr = Expr::BinaryExpr {
+            left: Arc::new(Expr::Cast {
+                expr: Arc::new(Expr::Literal(ScalarValue::Int32(5))),
+                data_type: DataType::Float64,
+            }),
+            op: Operator::Gt,
+            right: Arc::new(Expr::Literal(ScalarValue::Float64(4.5))),
+        };
+        assert_eq!(expr.evaluate_literal(), Some(ScalarValue::Boolean(true)));
+        let not = Expr::Not(Arc::new(expr));
+        assert_eq!(not.evaluate_literal(), Some(ScalarValue::Boolean(false)));
+
+        let cast = Expr::Cast {
+            expr: Arc::new(Expr::Literal(ScalarValue::Float64(300.7))),
+            data_type: DataType::UInt8,
+        };
+        assert_eq!(cast.evaluate_literal(), None);
+        let column = Expr::BinaryExpr {
+            left: Arc::new(Expr::Column(0)),
+            op: Operator::Plus,
+            right: Arc::new(Expr::Literal(ScalarValue::Int32(1))),
+        };
+        assert_eq!(column.evaluate_literal(), None);
+    }
+
     fn employee_schema() -> Schema {
         Schema::new(vec![
             Field::new("id", DataType::Int32, false),
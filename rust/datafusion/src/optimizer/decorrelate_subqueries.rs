@@ -0,0 +1,952 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule that rewrites subqueries into joins with the outer query, since
+//! subqueries cannot be executed as expressions
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field};
+
+use crate::error::{ExecutionError, Result};
+use crate::logicalplan::{
+    Expr, JoinType, LogicalPlan, LogicalPlanBuilder, Operator, Subquery,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::optimizer::utils::{self, conjunction, split_conjunction};
+
+/// Rewrites subqueries into joins with the input of the plan node that contains them:
+///
+/// * `EXISTS` and `NOT EXISTS` conditions of a selection become semi and anti joins on
+///   the equality conditions that correlate the subquery with the outer query.
+/// * `IN` conditions of a selection become semi joins that also match the expression
+///   with the column of the subquery. `NOT IN` conditions only become anti joins when
+///   neither side is nullable, since a null value makes the condition null instead of
+///   true.
+/// * Correlated scalar subqueries that compute aggregates without grouping become left
+///   joins with the aggregates grouped by the correlated columns. Uncorrelated scalar
+///   subqueries are left as they are, since the physical planner executes them once per
+///   query and substitutes their value as a literal.
+///
+/// Subqueries that are correlated through other conditions than equalities, or that
+/// are correlated scalar subqueries computing `COUNT`, are rejected. Subqueries that are
+/// not conditions of a selection or part of a projection, such as those in an `OR`, are
+/// left as they are and cannot be executed.
+pub struct DecorrelateSubqueries {}
+
+impl OptimizerRule for DecorrelateSubqueries {
+    fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        self.decorrelate(plan)
+    }
+}
+
+impl DecorrelateSubqueries {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn decorrelate(&self, plan: &LogicalPlan) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Selection { expr, input } => {
+                self.decorrelate_selection(expr, &self.decorrelate(input)?)
+            }
+            LogicalPlan::Projection { expr, input, .. } => {
+                self.decorrelate_projection(expr, &self.decorrelate(input)?)
+            }
+            LogicalPlan::Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                ..
+            } => LogicalPlanBuilder::from(&self.decorrelate(input)?)
+                .aggregate(group_expr.clone(), aggr_expr.clone())?
+                .build(),
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => LogicalPlanBuilder::from(&self.decorrelate(input)?)
+                .window(window_expr.clone())?
+                .build(),
+            LogicalPlan::Sort { expr, input, .. } => {
+                LogicalPlanBuilder::from(&self.decorrelate(input)?)
+                    .sort(expr.clone())?
+                    .build()
+            }
+            LogicalPlan::Deduplicate {
+                keys,
+                keep_first,
+                input,
+                ..
+            } => LogicalPlanBuilder::from(&self.decorrelate(input)?)
+                .deduplicate(keys.clone(), *keep_first)?
+                .build(),
+            LogicalPlan::Limit { expr, input, .. } => {
+                LogicalPlanBuilder::from(&self.decorrelate(input)?)
+                    .limit(expr.clone())?
+                    .build()
+            }
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => LogicalPlanBuilder::from(&self.decorrelate(left)?)
+                .join_detailed(
+                    &self.decorrelate(right)?,
+                    *join_type,
+                    on.clone(),
+                    *null_equals_null,
+                )?
+                .build(),
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                LogicalPlanBuilder::from(&self.decorrelate(left)?)
+                    .cross_join(&self.decorrelate(right)?)?
+                    .build()
+            }
+            LogicalPlan::Union { inputs, .. } => {
+                let inputs = inputs
+                    .iter()
+                    .map(|p| self.decorrelate(p))
+                    .collect::<Result<Vec<_>>>()?;
+                inputs[1..]
+                    .iter()
+                    .try_fold(LogicalPlanBuilder::from(&inputs[0]), |b, p| b.union(p))?
+                    .build()
+            }
+            _ => Ok(plan.clone()),
+        }
+    }
+
+    /// Rewrite the subqueries in the conditions of a selection into joins with its input
+    fn decorrelate_selection(
+        &self,
+        expr: &Expr,
+        input: &LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        if !contains_subquery(expr) {
+            return LogicalPlanBuilder::from(input)
+                .filter(expr.clone())?
+                .build();
+        }
+
+        let mut conjuncts = vec![];
+        split_conjunction(expr, &mut conjuncts);
+
+        let mut plan = input.clone();
+        let mut filters = vec![];
+        for conjunct in conjuncts {
+            match as_subquery_condition(conjunct) {
+                Some((expr, subquery, negated)) => {
+                    plan = self.join_subquery_condition(&plan, expr, subquery, negated)?
+                }
+                None => {
+                    filters.push(self.replace_scalar_subqueries(conjunct, &mut plan)?)
+                }
+            }
+        }
+        if let Some(filter) = conjunction(filters) {
+            plan = LogicalPlanBuilder::from(&plan).filter(filter)?.build()?;
+        }
+
+        // remove the columns that were added for the joins
+        let len = input.schema().fields().len();
+        if plan.schema().fields().len() == len {
+            Ok(plan)
+        } else {
+            LogicalPlanBuilder::from(&plan)
+                .project((0..len).map(Expr::Column).collect())?
+                .build()
+        }
+    }
+
+    /// Rewrite the scalar subqueries in the expressions of a projection into joins with
+    /// its input
+    fn decorrelate_projection(
+        &self,
+        expr: &[Expr],
+        input: &LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        let mut plan = input.clone();
+        let mut projected = Vec::with_capacity(expr.len());
+        for e in expr {
+            if contains_subquery(e) {
+                // keep the name of the column, which would otherwise be the name of the
+                // column of the subquery
+                let name = utils::expr_to_field(e, input.schema())?.name().clone();
+                let rewritten = self.replace_scalar_subqueries(e, &mut plan)?;
+                if utils::expr_to_field(&rewritten, plan.schema())?.name() == &name {
+                    projected.push(rewritten);
+                } else {
+                    projected.push(rewritten.alias(&name));
+                }
+            } else {
+                projected.push(e.clone());
+            }
+        }
+        LogicalPlanBuilder::from(&plan).project(projected)?.build()
+    }
+
+    /// Filter the rows of a plan by an `EXISTS` or `IN` condition, using a semi join or,
+    /// if the condition is negated, an anti join. The returned plan may have more
+    /// columns than the given plan.
+    fn join_subquery_condition(
+        &self,
+        plan: &LogicalPlan,
+        expr: Option<&Expr>,
+        subquery: &Subquery,
+        negated: bool,
+    ) -> Result<LogicalPlan> {
+        let subquery_plan = self.decorrelate(subquery.plan())?;
+        let (inner, mut on) = pull_up_correlation(&subquery_plan, false)?;
+
+        let mut plan = plan.clone();
+        match expr {
+            Some(expr) => {
+                let value_field = single_field(&subquery_plan)?;
+                if negated
+                    && (utils::expr_to_field(expr, plan.schema())?.is_nullable()
+                        || value_field.is_nullable())
+                {
+                    return Err(ExecutionError::NotImplemented(format!(
+                        "NOT IN subqueries are only supported for values that are not \
+                         nullable: {:?}",
+                        expr
+                    )));
+                }
+                // the value is matched with the first column of the subquery, which
+                // is followed by the correlated columns
+                let index = match expr {
+                    Expr::Column(i) => *i,
+                    _ => {
+                        let len = plan.schema().fields().len();
+                        let mut projected: Vec<Expr> =
+                            (0..len).map(Expr::Column).collect();
+                        projected.push(expr.clone());
+                        plan = LogicalPlanBuilder::from(&plan)
+                            .project(projected)?
+                            .build()?;
+                        len
+                    }
+                };
+                on.push((index, 0));
+            }
+            None if on.is_empty() => {
+                return Err(ExecutionError::NotImplemented(
+                    "EXISTS subqueries that are not correlated are not supported"
+                        .to_owned(),
+                ))
+            }
+            None => {}
+        }
+
+        let (left, right, on) = coerce_join_keys(&plan, &inner, on)?;
+        let join_type = if negated {
+            JoinType::LeftAnti
+        } else {
+            JoinType::LeftSemi
+        };
+        LogicalPlanBuilder::from(&left)
+            .join(&right, join_type, on)?
+            .build()
+    }
+
+    /// Replace the correlated scalar subqueries in an expression by columns of joins
+    /// with the plan, which is updated to include the joins
+    fn replace_scalar_subqueries(
+        &self,
+        expr: &Expr,
+        plan: &mut LogicalPlan,
+    ) -> Result<Expr> {
+        match expr {
+            Expr::ScalarSubquery(subquery) if !plan_references_outer(subquery.plan()) => {
+                single_field(subquery.plan())?;
+                Ok(expr.clone())
+            }
+            Expr::ScalarSubquery(subquery) => {
+                let (joined, index) = self.join_scalar_subquery(plan, subquery)?;
+                *plan = joined;
+                Ok(Expr::Column(index))
+            }
+            _ => map_children(expr, &mut |e| self.replace_scalar_subqueries(e, plan)),
+        }
+    }
+
+    /// Join a plan with the result of a scalar subquery, returning the joined plan and
+    /// the index of the column that holds the value of the subquery
+    fn join_scalar_subquery(
+        &self,
+        plan: &LogicalPlan,
+        subquery: &Subquery,
+    ) -> Result<(LogicalPlan, usize)> {
+        let subquery_plan = self.decorrelate(subquery.plan())?;
+        single_field(&subquery_plan)?;
+        if !returns_single_row(&subquery_plan) {
+            return Err(ExecutionError::NotImplemented(
+                "Scalar subqueries are only supported if they compute aggregates without \
+                 GROUP BY"
+                    .to_owned(),
+            ));
+        }
+
+        let (inner, on) = pull_up_correlation(&subquery_plan, true)?;
+        if on.is_empty() {
+            let index = plan.schema().fields().len();
+            let joined = LogicalPlanBuilder::from(plan).cross_join(&inner)?.build()?;
+            Ok((joined, index))
+        } else {
+            // outer rows without a group get a null value, which is the value of the
+            // aggregates other than COUNT for an empty input
+            let (left, right, on) = coerce_join_keys(plan, &inner, on)?;
+            let index = left.schema().fields().len();
+            let joined = LogicalPlanBuilder::from(&left)
+                .join(&right, JoinType::Left, on)?
+                .build()?;
+            Ok((joined, index))
+        }
+    }
+}
+
+/// Remove the conditions that correlate the plan of a subquery with the outer query,
+/// returning the plan without them and the pairs of (outer, inner) columns that they
+/// compare. The inner columns are appended to the columns of the plan if needed.
+///
+/// If `scalar` is true, an aggregate without grouping is grouped by the inner columns
+/// instead, which computes the aggregates for each row of the outer query.
+fn pull_up_correlation(
+    plan: &LogicalPlan,
+    scalar: bool,
+) -> Result<(LogicalPlan, Vec<(usize, usize)>)> {
+    if !plan_references_outer(plan) {
+        return Ok((plan.clone(), vec![]));
+    }
+    match plan {
+        LogicalPlan::Projection { expr, input, .. } if !exprs_reference_outer(expr) => {
+            let (input, keys) = pull_up_correlation(input, scalar)?;
+            let mut expr = expr.clone();
+            let keys = keys
+                .into_iter()
+                .map(|(outer, inner)| {
+                    expr.push(Expr::Column(inner));
+                    (outer, expr.len() - 1)
+                })
+                .collect();
+            let plan = LogicalPlanBuilder::from(&input).project(expr)?.build()?;
+            Ok((plan, keys))
+        }
+        LogicalPlan::Sort { expr, input, .. } if !exprs_reference_outer(expr) => {
+            let (input, keys) = pull_up_correlation(input, scalar)?;
+            let plan = LogicalPlanBuilder::from(&input)
+                .sort(expr.clone())?
+                .build()?;
+            Ok((plan, keys))
+        }
+        LogicalPlan::Selection { expr, input } => {
+            let (input, mut keys) = pull_up_correlation(input, scalar)?;
+            let mut conjuncts = vec![];
+            split_conjunction(expr, &mut conjuncts);
+            let mut filters = vec![];
+            for conjunct in conjuncts {
+                match correlation_key(conjunct) {
+                    Some(key) => keys.push(key),
+                    None if expr_references_outer(conjunct) => {
+                        return Err(ExecutionError::NotImplemented(format!(
+                            "Subqueries can only be correlated by equality conditions \
+                             between columns: {:?}",
+                            conjunct
+                        )))
+                    }
+                    None => filters.push(conjunct.clone()),
+                }
+            }
+            let plan = match conjunction(filters) {
+                Some(filter) => {
+                    LogicalPlanBuilder::from(&input).filter(filter)?.build()?
+                }
+                None => input,
+            };
+            Ok((plan, keys))
+        }
+        LogicalPlan::Aggregate {
+            input,
+            group_expr,
+            aggr_expr,
+            ..
+        } if scalar && group_expr.is_empty() && !exprs_reference_outer(aggr_expr) => {
+            if aggr_expr.iter().any(|e| contains(e, &is_count)) {
+                return Err(ExecutionError::NotImplemented(
+                    "Correlated scalar subqueries computing COUNT are not supported"
+                        .to_owned(),
+                ));
+            }
+            let (input, keys) = pull_up_correlation(input, false)?;
+            let group_expr = keys.iter().map(|(_, inner)| Expr::Column(*inner)).collect();
+            // move the group columns after the aggregates, which keep their positions
+            let (n, m) = (keys.len(), aggr_expr.len());
+            let plan = LogicalPlanBuilder::from(&input)
+                .aggregate(group_expr, aggr_expr.clone())?
+                .project((n..n + m).chain(0..n).map(Expr::Column).collect())?
+                .build()?;
+            let keys = keys
+                .into_iter()
+                .enumerate()
+                .map(|(i, (outer, _))| (outer, m + i))
+                .collect();
+            Ok((plan, keys))
+        }
+        _ => Err(ExecutionError::NotImplemented(format!(
+            "Correlated subquery is not supported: {:?}",
+            plan
+        ))),
+    }
+}
+
+/// Make the types of the join keys of both plans equal, appending the keys cast to
+/// their common type to the columns of the plans where the types differ
+fn coerce_join_keys(
+    left: &LogicalPlan,
+    right: &LogicalPlan,
+    on: Vec<(usize, usize)>,
+) -> Result<(LogicalPlan, LogicalPlan, Vec<(usize, usize)>)> {
+    let (left_schema, right_schema) = (left.schema(), right.schema());
+    let mut left_expr: Vec<Expr> =
+        (0..left_schema.fields().len()).map(Expr::Column).collect();
+    let mut right_expr: Vec<Expr> =
+        (0..right_schema.fields().len()).map(Expr::Column).collect();
+    let on = on
+        .into_iter()
+        .map(|(l, r)| {
+            let left_type = left_schema.field(l).data_type();
+            let right_type = right_schema.field(r).data_type();
+            if left_type == right_type {
+                return Ok((l, r));
+            }
+            let data_type = utils::get_supertype(left_type, right_type)?;
+            Ok((
+                cast_key(&mut left_expr, l, left_type, &data_type),
+                cast_key(&mut right_expr, r, right_type, &data_type),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let project = |plan: &LogicalPlan, expr: Vec<Expr>| {
+        if expr.len() == plan.schema().fields().len() {
+            Ok(plan.clone())
+        } else {
+            LogicalPlanBuilder::from(plan).project(expr)?.build()
+        }
+    };
+    Ok((project(left, left_expr)?, project(right, right_expr)?, on))
+}
+
+/// Append a cast of a key column to a list of projected columns if its type differs
+/// from the given type, returning the index of the key with that type
+fn cast_key(expr: &mut Vec<Expr>, index: usize, from: &DataType, to: &DataType) -> usize {
+    if from == to {
+        index
+    } else {
+        expr.push(Expr::Cast {
+            expr: Arc::new(Expr::Column(index)),
+            data_type: to.clone(),
+        });
+        expr.len() - 1
+    }
+}
+
+/// Get the field of a subquery that must return a single column
+fn single_field(plan: &LogicalPlan) -> Result<&Field> {
+    let fields = plan.schema().fields();
+    if fields.len() == 1 {
+        Ok(&fields[0])
+    } else {
+        Err(ExecutionError::General(format!(
+            "Subquery must return a single column but returns {}",
+            fields.len()
+        )))
+    }
+}
+
+/// Whether a plan returns at most one row because it computes aggregates without
+/// grouping
+fn returns_single_row(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Aggregate { group_expr, .. } => group_expr.is_empty(),
+        LogicalPlan::Projection { input, .. }
+        | LogicalPlan::Selection { input, .. }
+        | LogicalPlan::Sort { input, .. } => returns_single_row(input),
+        _ => false,
+    }
+}
+
+/// Get the expression, subquery and negation of an `EXISTS` or `IN` condition
+fn as_subquery_condition(expr: &Expr) -> Option<(Option<&Expr>, &Subquery, bool)> {
+    match expr {
+        Expr::Exists { subquery, negated } => Some((None, subquery, *negated)),
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated,
+        } => Some((Some(expr.as_ref()), subquery, *negated)),
+        Expr::Not(expr) => as_subquery_condition(expr)
+            .map(|(expr, subquery, negated)| (expr, subquery, !negated)),
+        _ => None,
+    }
+}
+
+/// Get the (outer, inner) columns of a condition that compares a column of a
+/// subquery with a column of the outer query
+fn correlation_key(expr: &Expr) -> Option<(usize, usize)> {
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        } => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(inner), Expr::OuterColumn { index, .. })
+            | (Expr::OuterColumn { index, .. }, Expr::Column(inner)) => {
+                Some((*index, *inner))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the plan references columns of an outer query, not including the references
+/// of the subqueries it contains
+fn plan_references_outer(plan: &LogicalPlan) -> bool {
+    plan.expressions().into_iter().any(expr_references_outer)
+        || plan.inputs().into_iter().any(plan_references_outer)
+}
+
+fn exprs_reference_outer(expr: &[Expr]) -> bool {
+    expr.iter().any(expr_references_outer)
+}
+
+fn expr_references_outer(expr: &Expr) -> bool {
+    contains(expr, &|e| match e {
+        Expr::OuterColumn { .. } => true,
+        _ => false,
+    })
+}
+
+fn contains_subquery(expr: &Expr) -> bool {
+    contains(expr, &|e| match e {
+        Expr::Exists { .. } | Expr::InSubquery { .. } | Expr::ScalarSubquery(_) => true,
+        _ => false,
+    })
+}
+
+fn is_count(expr: &Expr) -> bool {
+    match expr {
+        Expr::AggregateFunction { name, .. } => name.eq_ignore_ascii_case("count"),
+        _ => false,
+    }
+}
+
+/// Whether an expression or any of its children satisfies a predicate, not including
+/// the expressions of subqueries
+fn contains(expr: &Expr, predicate: &dyn Fn(&Expr) -> bool) -> bool {
+    if predicate(expr) {
+        return true;
+    }
+    match expr {
+        Expr::Alias(expr, _)
+        | Expr::Not(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. }
+        | Expr::Sort { expr, .. }
+        | Expr::InSubquery { expr, .. } => contains(expr, predicate),
+        Expr::BinaryExpr { left, right, .. } => {
+            contains(left, predicate) || contains(right, predicate)
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            contains(expr, predicate)
+                || contains(low, predicate)
+                || contains(high, predicate)
+        }
+        Expr::ElementAt { list, index } => {
+            contains(list, predicate) || contains(index, predicate)
+        }
+        Expr::InList { expr, list, .. } => {
+            contains(expr, predicate) || list.iter().any(|e| contains(e, predicate))
+        }
+        Expr::Like { expr, pattern, .. } => {
+            contains(expr, predicate) || contains(pattern, predicate)
+        }
+        Expr::ScalarFunction { args, .. } | Expr::AggregateFunction { args, .. } => {
+            args.iter().any(|e| contains(e, predicate))
+        }
+        Expr::WindowFunction {
+            args,
+            partition_by,
+            order_by,
+            ..
+        } => args
+            .iter()
+            .chain(partition_by.iter())
+            .chain(order_by.iter())
+            .any(|e| contains(e, predicate)),
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => {
+            when_then_expr.iter().any(|(when, then)| {
+                contains(when, predicate) || contains(then, predicate)
+            }) || else_expr.iter().any(|e| contains(e, predicate))
+        }
+        _ => false,
+    }
+}
+
+/// Rebuild an expression with each of its children rewritten by a function
+fn map_children(expr: &Expr, f: &mut dyn FnMut(&Expr) -> Result<Expr>) -> Result<Expr> {
+    Ok(match expr {
+        Expr::Alias(expr, alias) => Expr::Alias(Arc::new(f(expr)?), alias.clone()),
+        Expr::Not(expr) => Expr::Not(Arc::new(f(expr)?)),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Expr::Between {
+            expr: Arc::new(f(expr)?),
+            negated: *negated,
+            low: Arc::new(f(low)?),
+            high: Arc::new(f(high)?),
+        },
+        Expr::ElementAt { list, index } => Expr::ElementAt {
+            list: Arc::new(f(list)?),
+            index: Arc::new(f(index)?),
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Expr::InList {
+            expr: Arc::new(f(expr)?),
+            list: list.iter().map(|e| f(e)).collect::<Result<Vec<_>>>()?,
+            negated: *negated,
+        },
+        Expr::Like {
+            expr,
+            pattern,
+            negated,
+            case_insensitive,
+            escape,
+        } => Expr::Like {
+            expr: Arc::new(f(expr)?),
+            pattern: Arc::new(f(pattern)?),
+            negated: *negated,
+            case_insensitive: *case_insensitive,
+            escape: *escape,
+        },
+        Expr::IsNull(expr) => Expr::IsNull(Arc::new(f(expr)?)),
+        Expr::IsNotNull(expr) => Expr::IsNotNull(Arc::new(f(expr)?)),
+        Expr::Cast { expr, data_type } => Expr::Cast {
+            expr: Arc::new(f(expr)?),
+            data_type: data_type.clone(),
+        },
+        Expr::Sort {
+            expr,
+            asc,
+            nulls_first,
+        } => Expr::Sort {
+            expr: Arc::new(f(expr)?),
+            asc: *asc,
+            nulls_first: *nulls_first,
+        },
+        Expr::BinaryExpr { left, op, right } => Expr::BinaryExpr {
+            left: Arc::new(f(left)?),
+            op: op.clone(),
+            right: Arc::new(f(right)?),
+        },
+        Expr::ScalarFunction {
+            name,
+            args,
+            return_type,
+        } => Expr::ScalarFunction {
+            name: name.clone(),
+            args: args.iter().map(|e| f(e)).collect::<Result<Vec<_>>>()?,
+            return_type: return_type.clone(),
+        },
+        Expr::AggregateFunction {
+            name,
+            args,
+            return_type,
+        } => Expr::AggregateFunction {
+            name: name.clone(),
+            args: args.iter().map(|e| f(e)).collect::<Result<Vec<_>>>()?,
+            return_type: return_type.clone(),
+        },
+        Expr::WindowFunction {
+            name,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+            return_type,
+        } => Expr::WindowFunction {
+            name: name.clone(),
+            args: args.iter().map(|e| f(e)).collect::<Result<Vec<_>>>()?,
+            partition_by: partition_by
+                .iter()
+                .map(|e| f(e))
+                .collect::<Result<Vec<_>>>()?,
+            order_by: order_by.iter().map(|e| f(e)).collect::<Result<Vec<_>>>()?,
+            window_frame: *window_frame,
+            return_type: return_type.clone(),
+        },
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated,
+        } => Expr::InSubquery {
+            expr: Arc::new(f(expr)?),
+            subquery: subquery.clone(),
+            negated: *negated,
+        },
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => Expr::Case {
+            when_then_expr: when_then_expr
+                .iter()
+                .map(|(when, then)| Ok((f(when)?, f(then)?)))
+                .collect::<Result<Vec<_>>>()?,
+            else_expr: match else_expr {
+                Some(else_expr) => Some(Arc::new(f(else_expr)?)),
+                None => None,
+            },
+        },
+        _ => expr.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logicalplan::aggregate_expr;
+    use crate::test::*;
+    use arrow::datatypes::Schema;
+
+    fn outer(index: usize) -> Expr {
+        Expr::OuterColumn {
+            index,
+            data_type: DataType::UInt32,
+        }
+    }
+
+    fn subquery(plan: LogicalPlan) -> Subquery {
+        Subquery::new(&plan)
+    }
+
+    fn and(left: Expr, right: Expr) -> Expr {
+        conjunction(vec![left, right]).unwrap()
+    }
+
+    #[test]
+    fn correlated_exists() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(and(
+                Expr::Column(1).eq(&outer(0)),
+                Expr::Column(2).gt(&Expr::Column(0)),
+            ))?
+            .project(vec![Expr::Column(0)])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(Expr::Exists {
+                subquery: subquery(inner),
+                negated: false,
+            })?
+            .build()?;
+
+        let expected = "Join: type=LeftSemi, on=[#0 = #1]\
+        \n  TableScan: test projection=None\
+        \n  Projection: #0, #1\
+        \n    Selection: #2 Gt #0\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_not_exists_with_other_conditions() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(outer(2).eq(&Expr::Column(0)))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(and(
+                Expr::Exists {
+                    subquery: subquery(inner),
+                    negated: false,
+                }
+                .not(),
+                Expr::Column(1).gt(&Expr::Column(0)),
+            ))?
+            .build()?;
+
+        let expected = "Selection: #1 Gt #0\
+        \n  Join: type=LeftAnti, on=[#2 = #0]\
+        \n    TableScan: test projection=None\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn in_subquery() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(Expr::Column(2).eq(&outer(2)))?
+            .project(vec![Expr::Column(1)])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(Expr::InSubquery {
+                expr: Arc::new(Expr::Column(0)),
+                subquery: subquery(inner),
+                negated: false,
+            })?
+            .build()?;
+
+        let expected = "Join: type=LeftSemi, on=[#2 = #1, #0 = #0]\
+        \n  TableScan: test projection=None\
+        \n  Projection: #1, #2\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn not_in_subquery_with_nullable_values() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::UInt32, true)]);
+        let table_scan =
+            LogicalPlanBuilder::scan("default", "t", &schema, None)?.build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(Expr::InSubquery {
+                expr: Arc::new(Expr::Column(0)),
+                subquery: subquery(table_scan.clone()),
+                negated: true,
+            })?
+            .build()?;
+
+        assert!(optimize(&plan).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn uncorrelated_scalar_subquery() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .aggregate(vec![], vec![max(Expr::Column(1))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(Expr::Column(1).gt(&Expr::ScalarSubquery(subquery(inner))))?
+            .build()?;
+
+        // the subquery is executed once by the physical planner
+        let expected = "Selection: #1 Gt (Aggregate: groupBy=[[]], aggr=[[MAX(#1)]] \
+        <- TableScan: test projection=None)\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_scalar_subquery_in_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(Expr::Column(0).eq(&outer(0)))?
+            .aggregate(vec![], vec![max(Expr::Column(1))])?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![
+                Expr::Column(0),
+                Expr::ScalarSubquery(subquery(inner)).alias("max_b"),
+            ])?
+            .build()?;
+
+        let expected = "Projection: #0, #3 AS max_b\
+        \n  Join: type=Left, on=[#0 = #1]\
+        \n    TableScan: test projection=None\
+        \n    Projection: #1, #0\
+        \n      Aggregate: groupBy=[[#0]], aggr=[[MAX(#1)]]\
+        \n        TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn correlated_scalar_subquery_with_count() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(Expr::Column(0).eq(&outer(0)))?
+            .aggregate(
+                vec![],
+                vec![aggregate_expr("COUNT", Expr::Column(1), DataType::UInt64)],
+            )?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![Expr::ScalarSubquery(subquery(inner))])?
+            .build()?;
+
+        assert!(optimize(&plan).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_correlation() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let inner = LogicalPlanBuilder::from(&test_table_scan()?)
+            .filter(Expr::Column(0).gt(&outer(0)))?
+            .build()?;
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .filter(Expr::Exists {
+                subquery: subquery(inner),
+                negated: false,
+            })?
+            .build()?;
+
+        assert!(optimize(&plan).is_err());
+
+        Ok(())
+    }
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let optimized_plan = optimize(plan).expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+    }
+
+    fn optimize(plan: &LogicalPlan) -> Result<LogicalPlan> {
+        let mut rule = DecorrelateSubqueries::new();
+        rule.optimize(plan)
+    }
+}
@@ -18,6 +18,7 @@
 //! This module contains a query optimizer that operates against a logical plan and applies
 //! some simple rules to a logical plan, such as "Projection Push Down" and "Type Coercion".
 
+pub mod decorrelate_subqueries;
 pub mod optimizer;
 pub mod projection_push_down;
 pub mod resolve_columns;
@@ -33,7 +33,8 @@ pub struct ProjectionPushDown {}
 
 impl OptimizerRule for ProjectionPushDown {
     fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
-        let mut accum: HashSet<usize> = HashSet::new();
+        // all columns of the result of the query are referenced
+        let mut accum: HashSet<usize> = (0..plan.schema().fields().len()).collect();
         let mut mapping: HashMap<usize, usize> = HashMap::new();
         self.optimize_plan(plan, &mut accum, &mut mapping)
     }
@@ -53,11 +54,36 @@ impl ProjectionPushDown {
     ) -> Result<LogicalPlan> {
         match plan {
             LogicalPlan::Projection { expr, input, .. } => {
-                // collect all columns referenced by projection expressions
-                utils::exprlist_to_column_indices(&expr, accum)?;
+                // only keep the expressions that are referenced by the parent plans,
+                // which matters for projections of nested queries, but always keep at
+                // least one so that the number of rows is preserved
+                let mut kept: Vec<usize> =
+                    accum.iter().cloned().filter(|i| *i < expr.len()).collect();
+                kept.sort();
+                if kept.is_empty() {
+                    kept.push(0);
+                }
+                let expr: Vec<Expr> = kept.iter().map(|i| expr[*i].clone()).collect();
 
-                LogicalPlanBuilder::from(&self.optimize_plan(&input, accum, mapping)?)
-                    .project(self.rewrite_expr_list(expr, mapping)?)?
+                if mapping.len() != 0 {
+                    return Err(ExecutionError::InternalError(
+                        "illegal state".to_string(),
+                    ));
+                }
+                for (j, i) in kept.iter().enumerate() {
+                    mapping.insert(*i, j);
+                }
+
+                // the input has its own column index space, so it is optimized for the
+                // columns referenced by the kept expressions with its own mapping
+                let mut input_accum: HashSet<usize> = HashSet::new();
+                let mut input_mapping: HashMap<usize, usize> = HashMap::new();
+                utils::exprlist_to_column_indices(&expr, &mut input_accum)?;
+                let input =
+                    self.optimize_plan(&input, &mut input_accum, &mut input_mapping)?;
+
+                LogicalPlanBuilder::from(&input)
+                    .project(self.rewrite_expr_list(&expr, &input_mapping)?)?
                     .build()
             }
             LogicalPlan::Selection { expr, input } => {
@@ -74,17 +100,62 @@ impl ProjectionPushDown {
                 aggr_expr,
                 ..
             } => {
-                // collect all columns referenced by grouping and aggregate expressions
-                utils::exprlist_to_column_indices(&group_expr, accum)?;
-                utils::exprlist_to_column_indices(&aggr_expr, accum)?;
+                // the output columns of the aggregate are kept as they are
+                if mapping.len() != 0 {
+                    return Err(ExecutionError::InternalError(
+                        "illegal state".to_string(),
+                    ));
+                }
+                for i in 0..group_expr.len() + aggr_expr.len() {
+                    mapping.insert(i, i);
+                }
 
-                LogicalPlanBuilder::from(&self.optimize_plan(&input, accum, mapping)?)
+                // collect all columns referenced by grouping and aggregate expressions,
+                // which are columns of the input
+                let mut input_accum: HashSet<usize> = HashSet::new();
+                let mut input_mapping: HashMap<usize, usize> = HashMap::new();
+                utils::exprlist_to_column_indices(&group_expr, &mut input_accum)?;
+                utils::exprlist_to_column_indices(&aggr_expr, &mut input_accum)?;
+                let input =
+                    self.optimize_plan(&input, &mut input_accum, &mut input_mapping)?;
+
+                LogicalPlanBuilder::from(&input)
                     .aggregate(
-                        self.rewrite_expr_list(group_expr, mapping)?,
-                        self.rewrite_expr_list(aggr_expr, mapping)?,
+                        self.rewrite_expr_list(group_expr, &input_mapping)?,
+                        self.rewrite_expr_list(aggr_expr, &input_mapping)?,
                     )?
                     .build()
             }
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => {
+                // the referenced columns of the input are loaded along with the columns
+                // referenced by the window expressions, whose columns are all kept
+                let input_len = input.schema().fields().len();
+                let mut input_accum: HashSet<usize> =
+                    accum.iter().cloned().filter(|i| *i < input_len).collect();
+                let mut input_mapping: HashMap<usize, usize> = HashMap::new();
+                utils::exprlist_to_column_indices(&window_expr, &mut input_accum)?;
+                let input =
+                    self.optimize_plan(&input, &mut input_accum, &mut input_mapping)?;
+
+                if mapping.len() != 0 {
+                    return Err(ExecutionError::InternalError(
+                        "illegal state".to_string(),
+                    ));
+                }
+                for (i, j) in &input_mapping {
+                    mapping.insert(*i, *j);
+                }
+                let new_input_len = input.schema().fields().len();
+                for k in 0..window_expr.len() {
+                    mapping.insert(input_len + k, new_input_len + k);
+                }
+
+                LogicalPlanBuilder::from(&input)
+                    .window(self.rewrite_expr_list(window_expr, &input_mapping)?)?
+                    .build()
+            }
             LogicalPlan::Sort { expr, input, .. } => {
                 // collect all columns referenced by sort expressions
                 utils::exprlist_to_column_indices(&expr, accum)?;
@@ -93,6 +164,19 @@ impl ProjectionPushDown {
                     .sort(self.rewrite_expr_list(expr, mapping)?)?
                     .build()
             }
+            LogicalPlan::Deduplicate {
+                keys,
+                keep_first,
+                input,
+                ..
+            } => {
+                // collect all columns referenced by the keys
+                utils::exprlist_to_column_indices(&keys, accum)?;
+
+                LogicalPlanBuilder::from(&self.optimize_plan(&input, accum, mapping)?)
+                    .deduplicate(self.rewrite_expr_list(keys, mapping)?, *keep_first)?
+                    .build()
+            }
             LogicalPlan::EmptyRelation { schema } => Ok(LogicalPlan::EmptyRelation {
                 schema: schema.clone(),
             }),
@@ -158,6 +242,67 @@ impl ProjectionPushDown {
                     projection: Some(projection),
                 })
             }
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => {
+                let (new_left, new_right, left_mapping, right_mapping) =
+                    self.optimize_join_inputs(left, right, on, accum, mapping)?;
+
+                let on = on
+                    .iter()
+                    .map(|(l, r)| {
+                        Ok((
+                            self.new_index(&left_mapping, l)?,
+                            self.new_index(&right_mapping, r)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                LogicalPlanBuilder::from(&new_left)
+                    .join_detailed(&new_right, *join_type, on, *null_equals_null)?
+                    .build()
+            }
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                let (new_left, new_right, _, _) =
+                    self.optimize_join_inputs(left, right, &vec![], accum, mapping)?;
+
+                LogicalPlanBuilder::from(&new_left)
+                    .cross_join(&new_right)?
+                    .build()
+            }
+            LogicalPlan::Union { inputs, schema } => {
+                // every column of the union is kept, since the inputs must keep producing
+                // the same columns, so each input is optimized as if all of its columns
+                // were referenced
+                let inputs = inputs
+                    .iter()
+                    .map(|input| {
+                        let mut input_accum: HashSet<usize> =
+                            (0..input.schema().fields().len()).collect();
+                        let mut input_mapping: HashMap<usize, usize> = HashMap::new();
+                        self.optimize_plan(input, &mut input_accum, &mut input_mapping)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                if mapping.len() != 0 {
+                    return Err(ExecutionError::InternalError(
+                        "illegal state".to_string(),
+                    ));
+                }
+                for i in 0..schema.fields().len() {
+                    mapping.insert(i, i);
+                }
+
+                inputs[1..]
+                    .iter()
+                    .try_fold(LogicalPlanBuilder::from(&inputs[0]), |b, p| b.union(p))?
+                    .build()
+            }
             LogicalPlan::Limit { expr, input, .. } => {
                 // Note that limit expressions are scalar values so there is no need to
                 // rewrite them but we do need to optimize the input to the limit plan
@@ -178,7 +323,71 @@ impl ProjectionPushDown {
                 file_type: file_type.clone(),
                 header_row: *header_row,
             }),
+            LogicalPlan::AnalyzeTable { .. } => Ok(plan.clone()),
+            LogicalPlan::DropTable { .. } => Ok(plan.clone()),
+            LogicalPlan::SetVariable { .. } => Ok(plan.clone()),
+            LogicalPlan::ShowVariable { .. } => Ok(plan.clone()),
+            LogicalPlan::Explain { .. } => Ok(plan.clone()),
+            LogicalPlan::Insert { .. } => Ok(plan.clone()),
+            LogicalPlan::Overwrite { .. } => Ok(plan.clone()),
+            LogicalPlan::Copy { .. } => Ok(plan.clone()),
+        }
+    }
+
+    /// Optimize the inputs of a join, returning the new inputs along with the mappings
+    /// of their columns. The mapping of the join output is updated to combine both.
+    fn optimize_join_inputs(
+        &self,
+        left: &LogicalPlan,
+        right: &LogicalPlan,
+        on: &Vec<(usize, usize)>,
+        accum: &HashSet<usize>,
+        mapping: &mut HashMap<usize, usize>,
+    ) -> Result<(
+        LogicalPlan,
+        LogicalPlan,
+        HashMap<usize, usize>,
+        HashMap<usize, usize>,
+    )> {
+        // split the referenced columns between the two inputs, making sure that the
+        // join columns are always loaded
+        let left_len = left.schema().fields().len();
+        let mut left_accum: HashSet<usize> = HashSet::new();
+        let mut right_accum: HashSet<usize> = HashSet::new();
+        for i in accum.iter() {
+            if *i < left_len {
+                left_accum.insert(*i);
+            } else {
+                right_accum.insert(*i - left_len);
+            }
+        }
+        for (l, r) in on {
+            left_accum.insert(*l);
+            right_accum.insert(*r);
+        }
+
+        // each input has its own column index space so is optimized with its own mapping
+        let mut left_mapping: HashMap<usize, usize> = HashMap::new();
+        let mut right_mapping: HashMap<usize, usize> = HashMap::new();
+        let new_left = self.optimize_plan(left, &mut left_accum, &mut left_mapping)?;
+        let new_right =
+            self.optimize_plan(right, &mut right_accum, &mut right_mapping)?;
+
+        if mapping.len() != 0 {
+            return Err(ExecutionError::InternalError("illegal state".to_string()));
+        }
+
+        // combine the mappings so that expressions referencing the output of the join
+        // can be rewritten
+        let new_left_len = new_left.schema().fields().len();
+        for (i, j) in &left_mapping {
+            mapping.insert(*i, *j);
         }
+        for (i, j) in &right_mapping {
+            mapping.insert(*i + left_len, *j + new_left_len);
+        }
+
+        Ok((new_left, new_right, left_mapping, right_mapping))
     }
 
     fn rewrite_expr_list(
@@ -204,6 +413,43 @@ impl ProjectionPushDown {
             )),
             Expr::Literal(_) => Ok(expr.clone()),
             Expr::Not(e) => Ok(Expr::Not(Arc::new(self.rewrite_expr(e, mapping)?))),
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => Ok(Expr::Between {
+                expr: Arc::new(self.rewrite_expr(expr, mapping)?),
+                negated: *negated,
+                low: Arc::new(self.rewrite_expr(low, mapping)?),
+                high: Arc::new(self.rewrite_expr(high, mapping)?),
+            }),
+            Expr::ElementAt { list, index } => Ok(Expr::ElementAt {
+                list: Arc::new(self.rewrite_expr(list, mapping)?),
+                index: Arc::new(self.rewrite_expr(index, mapping)?),
+            }),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => Ok(Expr::InList {
+                expr: Arc::new(self.rewrite_expr(expr, mapping)?),
+                list: self.rewrite_expr_list(list, mapping)?,
+                negated: *negated,
+            }),
+            Expr::Like {
+                expr,
+                pattern,
+                negated,
+                case_insensitive,
+                escape,
+            } => Ok(Expr::Like {
+                expr: Arc::new(self.rewrite_expr(expr, mapping)?),
+                pattern: Arc::new(self.rewrite_expr(pattern, mapping)?),
+                negated: *negated,
+                case_insensitive: *case_insensitive,
+                escape: *escape,
+            }),
             Expr::IsNull(e) => Ok(Expr::IsNull(Arc::new(self.rewrite_expr(e, mapping)?))),
             Expr::IsNotNull(e) => {
                 Ok(Expr::IsNotNull(Arc::new(self.rewrite_expr(e, mapping)?)))
@@ -217,9 +463,14 @@ impl ProjectionPushDown {
                 expr: Arc::new(self.rewrite_expr(expr, mapping)?),
                 data_type: data_type.clone(),
             }),
-            Expr::Sort { expr, asc } => Ok(Expr::Sort {
+            Expr::Sort {
+                expr,
+                asc,
+                nulls_first,
+            } => Ok(Expr::Sort {
                 expr: Arc::new(self.rewrite_expr(expr, mapping)?),
                 asc: *asc,
+                nulls_first: *nulls_first,
             }),
             Expr::AggregateFunction {
                 name,
@@ -239,9 +490,56 @@ impl ProjectionPushDown {
                 args: self.rewrite_expr_list(args, mapping)?,
                 return_type: return_type.clone(),
             }),
+            Expr::WindowFunction {
+                name,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+                return_type,
+            } => Ok(Expr::WindowFunction {
+                name: name.to_string(),
+                args: self.rewrite_expr_list(args, mapping)?,
+                partition_by: self.rewrite_expr_list(partition_by, mapping)?,
+                order_by: self.rewrite_expr_list(order_by, mapping)?,
+                window_frame: *window_frame,
+                return_type: return_type.clone(),
+            }),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => Ok(Expr::Case {
+                when_then_expr: when_then_expr
+                    .iter()
+                    .map(|(when, then)| {
+                        Ok((
+                            self.rewrite_expr(when, mapping)?,
+                            self.rewrite_expr(then, mapping)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                else_expr: match else_expr {
+                    Some(else_expr) => {
+                        Some(Arc::new(self.rewrite_expr(else_expr, mapping)?))
+                    }
+                    None => None,
+                },
+            }),
             Expr::Wildcard => Err(ExecutionError::General(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Ok(Expr::InSubquery {
+                expr: Arc::new(self.rewrite_expr(expr, mapping)?),
+                subquery: subquery.clone(),
+                negated: *negated,
+            }),
+            Expr::Exists { .. } | Expr::ScalarSubquery(_) | Expr::OuterColumn { .. } => {
+                Ok(expr.clone())
+            }
         }
     }
 
@@ -260,7 +558,7 @@ mod tests {
 
     use super::*;
     use crate::logicalplan::Expr::*;
-    use crate::logicalplan::ScalarValue;
+    use crate::logicalplan::{JoinType, ScalarValue};
     use crate::test::*;
     use arrow::datatypes::DataType;
     use std::sync::Arc;
@@ -376,6 +674,148 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn join() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&left)
+            .join(&right, JoinType::Inner, vec![(0, 1)])?
+            .project(vec![Column(2), Column(5)])?
+            .build()?;
+
+        let expected = "Projection: #1, #3\
+        \n  Join: type=Inner, on=[#0 = #0]\
+        \n    TableScan: test projection=Some([0, 2])\
+        \n    TableScan: test projection=Some([1, 2])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn join_null_equals_null() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&left)
+            .join_detailed(&right, JoinType::Inner, vec![(0, 1)], true)?
+            .project(vec![Column(2)])?
+            .build()?;
+
+        let expected = "Projection: #1\
+        \n  Join: type=Inner, on=[#0 = #0], null_equals_null\
+        \n    TableScan: test projection=Some([0, 2])\
+        \n    TableScan: test projection=Some([1])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn semi_join() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&left)
+            .join(&right, JoinType::LeftSemi, vec![(0, 1)])?
+            .project(vec![Column(2)])?
+            .build()?;
+
+        // only the join column of the right input is loaded
+        let expected = "Projection: #1\
+        \n  Join: type=LeftSemi, on=[#0 = #0]\
+        \n    TableScan: test projection=Some([0, 2])\
+        \n    TableScan: test projection=Some([1])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cross_join() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&left)
+            .cross_join(&right)?
+            .project(vec![Column(1), Column(5)])?
+            .build()?;
+
+        let expected = "Projection: #0, #1\
+        \n  CrossJoin:\
+        \n    TableScan: test projection=Some([1])\
+        \n    TableScan: test projection=Some([2])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn nested_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        // the outer projection only references the second column of the inner one
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![Column(0), Column(2)])?
+            .filter(Column(1))?
+            .project(vec![Column(1)])?
+            .build()?;
+
+        let expected = "Projection: #0\
+        \n  Selection: #0\
+        \n    Projection: #0\
+        \n      TableScan: test projection=Some([2])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sort_over_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![Column(1), Column(2)])?
+            .sort(vec![Sort {
+                expr: Arc::new(Column(1)),
+                asc: true,
+                nulls_first: true,
+            }])?
+            .build()?;
+
+        let expected = "Sort: #1 ASC NULLS FIRST\
+        \n  Projection: #0, #1\
+        \n    TableScan: test projection=Some([1, 2])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_over_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+
+        let plan = LogicalPlanBuilder::from(&table_scan)
+            .project(vec![Column(0), Column(2)])?
+            .aggregate(vec![], vec![max(Column(1))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[]], aggr=[[MAX(#0)]]\
+        \n  Projection: #0\
+        \n    TableScan: test projection=Some([2])";
+
+        assert_optimized_plan_eq(&plan, expected);
+
+        Ok(())
+    }
+
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
         let optimized_plan = optimize(plan).expect("failed to optimize plan");
         let formatted_plan = format!("{:?}", optimized_plan);
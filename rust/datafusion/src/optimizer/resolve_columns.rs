@@ -19,7 +19,7 @@
 
 use crate::error::Result;
 use crate::logicalplan::LogicalPlan;
-use crate::logicalplan::{Expr, LogicalPlanBuilder};
+use crate::logicalplan::{Expr, LogicalPlanBuilder, Subquery};
 use crate::optimizer::optimizer::OptimizerRule;
 use arrow::datatypes::Schema;
 use std::sync::Arc;
@@ -56,9 +56,52 @@ impl OptimizerRule for ResolveColumnsRule {
                     rewrite_expr_list(aggr_expr, &input.schema())?,
                 )?
                 .build()?),
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => Ok(LogicalPlanBuilder::from(&self.optimize(input.as_ref())?)
+                .window(rewrite_expr_list(window_expr, &input.schema())?)?
+                .build()?),
             LogicalPlan::Sort { input, expr, .. } => Ok(LogicalPlanBuilder::from(input)
                 .sort(rewrite_expr_list(expr, &input.schema())?)?
                 .build()?),
+            LogicalPlan::Deduplicate {
+                input,
+                keys,
+                keep_first,
+                ..
+            } => Ok(LogicalPlanBuilder::from(&self.optimize(input.as_ref())?)
+                .deduplicate(rewrite_expr_list(keys, &input.schema())?, *keep_first)?
+                .build()?),
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => Ok(LogicalPlanBuilder::from(&self.optimize(left)?)
+                .join_detailed(
+                    &self.optimize(right)?,
+                    *join_type,
+                    on.clone(),
+                    *null_equals_null,
+                )?
+                .build()?),
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                Ok(LogicalPlanBuilder::from(&self.optimize(left)?)
+                    .cross_join(&self.optimize(right)?)?
+                    .build()?)
+            }
+            LogicalPlan::Union { inputs, .. } => {
+                let inputs = inputs
+                    .iter()
+                    .map(|p| self.optimize(p))
+                    .collect::<Result<Vec<_>>>()?;
+                inputs[1..]
+                    .iter()
+                    .try_fold(LogicalPlanBuilder::from(&inputs[0]), |b, p| b.union(p))?
+                    .build()
+            }
             _ => Ok(plan.clone()),
         }
     }
@@ -80,6 +123,43 @@ fn rewrite_expr(expr: &Expr, schema: &Schema) -> Result<Expr> {
             right: Arc::new(rewrite_expr(&right, schema)?),
         }),
         Expr::Not(expr) => Ok(Expr::Not(Arc::new(rewrite_expr(&expr, schema)?))),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Ok(Expr::Between {
+            expr: Arc::new(rewrite_expr(&expr, schema)?),
+            negated: *negated,
+            low: Arc::new(rewrite_expr(&low, schema)?),
+            high: Arc::new(rewrite_expr(&high, schema)?),
+        }),
+        Expr::ElementAt { list, index } => Ok(Expr::ElementAt {
+            list: Arc::new(rewrite_expr(&list, schema)?),
+            index: Arc::new(rewrite_expr(&index, schema)?),
+        }),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Ok(Expr::InList {
+            expr: Arc::new(rewrite_expr(&expr, schema)?),
+            list: rewrite_expr_list(&list, schema)?,
+            negated: *negated,
+        }),
+        Expr::Like {
+            expr,
+            pattern,
+            negated,
+            case_insensitive,
+            escape,
+        } => Ok(Expr::Like {
+            expr: Arc::new(rewrite_expr(&expr, schema)?),
+            pattern: Arc::new(rewrite_expr(&pattern, schema)?),
+            negated: *negated,
+            case_insensitive: *case_insensitive,
+            escape: *escape,
+        }),
         Expr::IsNotNull(expr) => {
             Ok(Expr::IsNotNull(Arc::new(rewrite_expr(&expr, schema)?)))
         }
@@ -88,9 +168,14 @@ fn rewrite_expr(expr: &Expr, schema: &Schema) -> Result<Expr> {
             expr: Arc::new(rewrite_expr(&expr, schema)?),
             data_type: data_type.clone(),
         }),
-        Expr::Sort { expr, asc } => Ok(Expr::Sort {
+        Expr::Sort {
+            expr,
+            asc,
+            nulls_first,
+        } => Ok(Expr::Sort {
             expr: Arc::new(rewrite_expr(&expr, schema)?),
             asc: asc.clone(),
+            nulls_first: nulls_first.clone(),
         }),
         Expr::ScalarFunction {
             name,
@@ -110,10 +195,63 @@ fn rewrite_expr(expr: &Expr, schema: &Schema) -> Result<Expr> {
             args: rewrite_expr_list(args, schema)?,
             return_type: return_type.clone(),
         }),
+        Expr::WindowFunction {
+            name,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+            return_type,
+        } => Ok(Expr::WindowFunction {
+            name: name.clone(),
+            args: rewrite_expr_list(args, schema)?,
+            partition_by: rewrite_expr_list(partition_by, schema)?,
+            order_by: rewrite_expr_list(order_by, schema)?,
+            window_frame: *window_frame,
+            return_type: return_type.clone(),
+        }),
+        Expr::Exists { subquery, negated } => Ok(Expr::Exists {
+            subquery: resolve_subquery(subquery)?,
+            negated: *negated,
+        }),
+        Expr::InSubquery {
+            expr,
+            subquery,
+            negated,
+        } => Ok(Expr::InSubquery {
+            expr: Arc::new(rewrite_expr(&expr, schema)?),
+            subquery: resolve_subquery(subquery)?,
+            negated: *negated,
+        }),
+        Expr::ScalarSubquery(subquery) => {
+            Ok(Expr::ScalarSubquery(resolve_subquery(subquery)?))
+        }
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => Ok(Expr::Case {
+            when_then_expr: when_then_expr
+                .iter()
+                .map(|(when, then)| {
+                    Ok((rewrite_expr(when, schema)?, rewrite_expr(then, schema)?))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            else_expr: match else_expr {
+                Some(else_expr) => Some(Arc::new(rewrite_expr(else_expr, schema)?)),
+                None => None,
+            },
+        }),
         _ => Ok(expr.clone()),
     }
 }
 
+/// Resolve the columns of the plan of a subquery against its own inputs
+fn resolve_subquery(subquery: &Subquery) -> Result<Subquery> {
+    Ok(Subquery::new(
+        &ResolveColumnsRule::new().optimize(subquery.plan())?,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -23,12 +23,12 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 
 use crate::error::{ExecutionError, Result};
 use crate::execution::physical_plan::udf::ScalarFunction;
 use crate::logicalplan::LogicalPlan;
-use crate::logicalplan::{Expr, LogicalPlanBuilder};
+use crate::logicalplan::{Expr, LogicalPlanBuilder, Operator, ScalarValue};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
 
@@ -60,14 +60,33 @@ impl<'a> TypeCoercionRule<'a> {
                 let right = self.rewrite_expr(right, schema)?;
                 let left_type = left.get_type(schema)?;
                 let right_type = right.get_type(schema)?;
-                if left_type == right_type {
+                let string_operands = match op {
+                    Operator::StringConcat
+                    | Operator::RegexMatch
+                    | Operator::RegexIMatch
+                    | Operator::RegexNotMatch
+                    | Operator::RegexNotIMatch => true,
+                    _ => false,
+                };
+                if string_operands {
+                    // the operands of a concatenation or regular expression match are
+                    // converted to strings
+                    Ok(Expr::BinaryExpr {
+                        left: Arc::new(left.cast_to(&DataType::Utf8, schema)?),
+                        op: op.clone(),
+                        right: Arc::new(right.cast_to(&DataType::Utf8, schema)?),
+                    })
+                } else if left_type == right_type {
                     Ok(Expr::BinaryExpr {
                         left: Arc::new(left),
                         op: op.clone(),
                         right: Arc::new(right),
                     })
                 } else {
-                    let super_type = utils::get_supertype(&left_type, &right_type)?;
+                    let super_type = utils::get_supertype(
+                        &coercion_type(&left, left_type.clone(), &right_type),
+                        &coercion_type(&right, right_type.clone(), &left_type),
+                    )?;
                     Ok(Expr::BinaryExpr {
                         left: Arc::new(left.cast_to(&super_type, schema)?),
                         op: op.clone(),
@@ -75,6 +94,70 @@ impl<'a> TypeCoercionRule<'a> {
                     })
                 }
             }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+            } => {
+                // the expression and the bounds are cast to their common supertype
+                let expr = self.rewrite_expr(expr, schema)?;
+                let low = self.rewrite_expr(low, schema)?;
+                let high = self.rewrite_expr(high, schema)?;
+                let super_type =
+                    utils::get_expr_supertype(&[&expr, &low, &high], schema)?;
+                Ok(Expr::Between {
+                    expr: Arc::new(expr.cast_to(&super_type, schema)?),
+                    negated: *negated,
+                    low: Arc::new(low.cast_to(&super_type, schema)?),
+                    high: Arc::new(high.cast_to(&super_type, schema)?),
+                })
+            }
+            Expr::ElementAt { list, index } => {
+                // the index is cast to a 64-bit integer
+                let index = self.rewrite_expr(index, schema)?;
+                Ok(Expr::ElementAt {
+                    list: Arc::new(self.rewrite_expr(list, schema)?),
+                    index: Arc::new(index.cast_to(&DataType::Int64, schema)?),
+                })
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                // the expression and the values are cast to their common supertype
+                let expr = self.rewrite_expr(expr, schema)?;
+                let list = self.rewrite_expr_list(list, schema)?;
+                let values: Vec<&Expr> = Some(&expr).into_iter().chain(&list).collect();
+                let super_type = utils::get_expr_supertype(&values, schema)?;
+                Ok(Expr::InList {
+                    expr: Arc::new(expr.cast_to(&super_type, schema)?),
+                    list: list
+                        .iter()
+                        .map(|value| value.cast_to(&super_type, schema))
+                        .collect::<Result<Vec<_>>>()?,
+                    negated: *negated,
+                })
+            }
+            Expr::Like {
+                expr,
+                pattern,
+                negated,
+                case_insensitive,
+                escape,
+            } => {
+                // the expression and the pattern are matched as strings
+                let expr = self.rewrite_expr(expr, schema)?;
+                let pattern = self.rewrite_expr(pattern, schema)?;
+                Ok(Expr::Like {
+                    expr: Arc::new(expr.cast_to(&DataType::Utf8, schema)?),
+                    pattern: Arc::new(pattern.cast_to(&DataType::Utf8, schema)?),
+                    negated: *negated,
+                    case_insensitive: *case_insensitive,
+                    escape: *escape,
+                })
+            }
             Expr::IsNull(e) => Ok(Expr::IsNull(Arc::new(self.rewrite_expr(e, schema)?))),
             Expr::IsNotNull(e) => {
                 Ok(Expr::IsNotNull(Arc::new(self.rewrite_expr(e, schema)?)))
@@ -95,6 +178,8 @@ impl<'a> TypeCoercionRule<'a> {
                             let required_type = field.data_type();
                             if &actual_type == required_type {
                                 func_args.push(expr)
+                            } else if expr.is_untyped_null() {
+                                func_args.push(expr.cast_to(required_type, schema)?);
                             } else {
                                 let super_type =
                                     utils::get_supertype(&actual_type, required_type)?;
@@ -126,6 +211,43 @@ impl<'a> TypeCoercionRule<'a> {
                     .collect::<Result<Vec<_>>>()?,
                 return_type: return_type.clone(),
             }),
+            Expr::WindowFunction {
+                name,
+                args,
+                partition_by,
+                order_by,
+                window_frame,
+                return_type,
+            } => Ok(Expr::WindowFunction {
+                name: name.clone(),
+                args: self.rewrite_expr_list(args, schema)?,
+                partition_by: self.rewrite_expr_list(partition_by, schema)?,
+                order_by: self.rewrite_expr_list(order_by, schema)?,
+                window_frame: *window_frame,
+                return_type: return_type.clone(),
+            }),
+            Expr::Case {
+                when_then_expr,
+                else_expr,
+            } => {
+                // cast the results to their common supertype
+                let data_type = expr.get_type(schema)?;
+                let cast_result = |e: &Expr| -> Result<Expr> {
+                    self.rewrite_expr(e, schema)?.cast_to(&data_type, schema)
+                };
+                Ok(Expr::Case {
+                    when_then_expr: when_then_expr
+                        .iter()
+                        .map(|(when, then)| {
+                            Ok((self.rewrite_expr(when, schema)?, cast_result(then)?))
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                    else_expr: match else_expr {
+                        Some(else_expr) => Some(Arc::new(cast_result(else_expr)?)),
+                        None => None,
+                    },
+                })
+            }
             Expr::Cast { .. } => Ok(expr.clone()),
             Expr::Column(_) => Ok(expr.clone()),
             Expr::Alias(expr, alias) => Ok(Expr::Alias(
@@ -139,10 +261,52 @@ impl<'a> TypeCoercionRule<'a> {
             Expr::Wildcard { .. } => Err(ExecutionError::General(
                 "Wildcard expressions are not valid in a logical query plan".to_owned(),
             )),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => Ok(Expr::InSubquery {
+                expr: Arc::new(self.rewrite_expr(expr, schema)?),
+                subquery: subquery.clone(),
+                negated: *negated,
+            }),
+            Expr::Exists { .. } | Expr::ScalarSubquery(_) | Expr::OuterColumn { .. } => {
+                Ok(expr.clone())
+            }
         }
     }
 }
 
+/// The type of an expression for coercion with an expression of `other_type`. Untyped
+/// nulls take the other type. Floating point literals that meet a decimal are treated as
+/// decimals of their digits, so that `price > 10.25` compares decimals exactly instead
+/// of as floating point values.
+fn coercion_type(expr: &Expr, data_type: DataType, other_type: &DataType) -> DataType {
+    if expr.is_untyped_null() {
+        return other_type.clone();
+    }
+    let value = match (expr, other_type) {
+        (Expr::Literal(ScalarValue::Float32(v)), DataType::Decimal(_, _)) => *v as f64,
+        (Expr::Literal(ScalarValue::Float64(v)), DataType::Decimal(_, _)) => *v,
+        _ => return data_type,
+    };
+    if !value.is_finite() {
+        return data_type;
+    }
+    // floats are displayed with the fewest digits that parse to the same value
+    let digits = value.abs().to_string();
+    let (integer, fraction) = match digits.find('.') {
+        Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+        None => (digits.as_str(), ""),
+    };
+    let precision = integer.trim_start_matches('0').len() + fraction.len();
+    if precision > 38 {
+        data_type
+    } else {
+        DataType::Decimal(precision.max(1), fraction.len())
+    }
+}
+
 impl<'a> OptimizerRule for TypeCoercionRule<'a> {
     fn optimize(&mut self, plan: &LogicalPlan) -> Result<LogicalPlan> {
         match plan {
@@ -167,10 +331,74 @@ impl<'a> OptimizerRule for TypeCoercionRule<'a> {
                     self.rewrite_expr_list(aggr_expr, input.schema())?,
                 )?
                 .build(),
+            LogicalPlan::Window {
+                input, window_expr, ..
+            } => LogicalPlanBuilder::from(&self.optimize(input)?)
+                .window(self.rewrite_expr_list(window_expr, input.schema())?)?
+                .build(),
+            LogicalPlan::Sort { input, expr, .. } => {
+                LogicalPlanBuilder::from(&self.optimize(input)?)
+                    .sort(self.rewrite_expr_list(expr, input.schema())?)?
+                    .build()
+            }
+            LogicalPlan::Deduplicate {
+                input,
+                keys,
+                keep_first,
+                ..
+            } => LogicalPlanBuilder::from(&self.optimize(input)?)
+                .deduplicate(self.rewrite_expr_list(keys, input.schema())?, *keep_first)?
+                .build(),
+            LogicalPlan::Join {
+                left,
+                right,
+                on,
+                join_type,
+                null_equals_null,
+                ..
+            } => LogicalPlanBuilder::from(&self.optimize(left)?)
+                .join_detailed(
+                    &self.optimize(right)?,
+                    *join_type,
+                    on.clone(),
+                    *null_equals_null,
+                )?
+                .build(),
+            LogicalPlan::CrossJoin { left, right, .. } => {
+                LogicalPlanBuilder::from(&self.optimize(left)?)
+                    .cross_join(&self.optimize(right)?)?
+                    .build()
+            }
+            LogicalPlan::Union { inputs, .. } => {
+                let inputs = inputs
+                    .iter()
+                    .map(|p| self.optimize(p))
+                    .collect::<Result<Vec<_>>>()?;
+                let union = inputs[1..]
+                    .iter()
+                    .try_fold(LogicalPlanBuilder::from(&inputs[0]), |b, p| b.union(p))?
+                    .build()?;
+                // columns of untyped nulls are cast to the types of the union
+                let types: Vec<DataType> = union
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| f.data_type().clone())
+                    .collect();
+                utils::cast_untyped_null_columns(&union, &types)
+            }
             LogicalPlan::TableScan { .. } => Ok(plan.clone()),
             LogicalPlan::EmptyRelation { .. } => Ok(plan.clone()),
             LogicalPlan::Limit { .. } => Ok(plan.clone()),
             LogicalPlan::CreateExternalTable { .. } => Ok(plan.clone()),
+            LogicalPlan::AnalyzeTable { .. } => Ok(plan.clone()),
+            LogicalPlan::DropTable { .. } => Ok(plan.clone()),
+            LogicalPlan::SetVariable { .. } => Ok(plan.clone()),
+            LogicalPlan::ShowVariable { .. } => Ok(plan.clone()),
+            LogicalPlan::Explain { .. } => Ok(plan.clone()),
+            LogicalPlan::Insert { .. } => Ok(plan.clone()),
+            LogicalPlan::Overwrite { .. } => Ok(plan.clone()),
+            LogicalPlan::Copy { .. } => Ok(plan.clone()),
             other => Err(ExecutionError::NotImplemented(format!(
                 "Type coercion optimizer rule does not support relation: {:?}",
                 other
@@ -243,6 +471,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compare_decimal_with_float_literal() {
+        let schema = Schema::new(vec![Field::new("c0", DataType::Decimal(10, 1), true)]);
+        let expr = Expr::BinaryExpr {
+            left: Arc::new(Column(0)),
+            op: Operator::Gt,
+            right: Arc::new(Literal(ScalarValue::Float64(10.25))),
+        };
+
+        let ctx = ExecutionContext::new();
+        let rule = TypeCoercionRule::new(ctx.scalar_functions());
+
+        let expr2 = rule.rewrite_expr(&expr, &schema).unwrap();
+
+        assert_eq!(
+            "CAST(#0 AS Decimal(11, 2)) Gt CAST(Float64(10.25) AS Decimal(11, 2))",
+            format!("{:?}", expr2)
+        );
+    }
+
+    #[test]
+    fn test_untyped_null_takes_type_of_context() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("c0", DataType::UInt32, true)]);
+        let ctx = ExecutionContext::new();
+        let rule = TypeCoercionRule::new(ctx.scalar_functions());
+
+        let expr = Expr::BinaryExpr {
+            left: Arc::new(Literal(ScalarValue::Null)),
+            op: Operator::Plus,
+            right: Arc::new(Column(0)),
+        };
+        let expr = rule.rewrite_expr(&expr, &schema)?;
+        assert_eq!("CAST(Null AS UInt32) Plus #0", format!("{:?}", expr));
+        assert_eq!(DataType::UInt32, expr.get_type(&schema)?);
+
+        let expr = Expr::InList {
+            expr: Arc::new(Column(0)),
+            list: vec![Literal(ScalarValue::Int64(1)), Literal(ScalarValue::Null)],
+            negated: false,
+        };
+        let expr = rule.rewrite_expr(&expr, &schema)?;
+        assert_eq!(
+            "CAST(#0 AS Int64) IN (Int64(1), CAST(Null AS Int64))",
+            format!("{:?}", expr)
+        );
+        Ok(())
+    }
+
     fn binary_cast_test(left_type: DataType, right_type: DataType, expected: &str) {
         let schema = Schema::new(vec![
             Field::new("c0", left_type, true),
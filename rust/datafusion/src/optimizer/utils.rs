@@ -18,11 +18,12 @@
 //! Collection of utility functions that are leveraged by the query optimizer rules
 
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use arrow::datatypes::{DataType, Field, Schema};
 
 use crate::error::{ExecutionError, Result};
-use crate::logicalplan::Expr;
+use crate::logicalplan::{Expr, LogicalPlan, LogicalPlanBuilder, Operator, ScalarValue};
 
 /// Recursively walk a list of expression trees, collecting the unique set of column
 /// indexes referenced in the expression
@@ -60,16 +61,90 @@ pub fn expr_to_column_indices(expr: &Expr, accum: &mut HashSet<usize>) -> Result
             expr_to_column_indices(right, accum)?;
             Ok(())
         }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            expr_to_column_indices(expr, accum)?;
+            expr_to_column_indices(low, accum)?;
+            expr_to_column_indices(high, accum)
+        }
+        Expr::ElementAt { list, index } => {
+            expr_to_column_indices(list, accum)?;
+            expr_to_column_indices(index, accum)
+        }
+        Expr::InList { expr, list, .. } => {
+            expr_to_column_indices(expr, accum)?;
+            exprlist_to_column_indices(list, accum)
+        }
+        Expr::Like { expr, pattern, .. } => {
+            expr_to_column_indices(expr, accum)?;
+            expr_to_column_indices(pattern, accum)
+        }
         Expr::Cast { expr, .. } => expr_to_column_indices(expr, accum),
         Expr::Sort { expr, .. } => expr_to_column_indices(expr, accum),
         Expr::AggregateFunction { args, .. } => exprlist_to_column_indices(args, accum),
         Expr::ScalarFunction { args, .. } => exprlist_to_column_indices(args, accum),
+        Expr::WindowFunction {
+            args,
+            partition_by,
+            order_by,
+            ..
+        } => {
+            exprlist_to_column_indices(args, accum)?;
+            exprlist_to_column_indices(partition_by, accum)?;
+            exprlist_to_column_indices(order_by, accum)
+        }
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => {
+            for (when, then) in when_then_expr {
+                expr_to_column_indices(when, accum)?;
+                expr_to_column_indices(then, accum)?;
+            }
+            match else_expr {
+                Some(else_expr) => expr_to_column_indices(else_expr, accum),
+                None => Ok(()),
+            }
+        }
         Expr::Wildcard => Err(ExecutionError::General(
             "Wildcard expressions are not valid in a logical query plan".to_owned(),
         )),
+        Expr::InSubquery { expr, .. } => expr_to_column_indices(expr, accum),
+        Expr::Exists { .. } | Expr::ScalarSubquery(_) | Expr::OuterColumn { .. } => {
+            // columns of subqueries are not columns of the input
+            Ok(())
+        }
+    }
+}
+
+/// Split an expression into the operands of its top-level AND operators
+pub fn split_conjunction<'a>(expr: &'a Expr, exprs: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        } => {
+            split_conjunction(left, exprs);
+            split_conjunction(right, exprs);
+        }
+        other => exprs.push(other),
     }
 }
 
+/// Combine expressions with AND operators, returning `None` if there are none
+pub fn conjunction(exprs: Vec<Expr>) -> Option<Expr> {
+    exprs.into_iter().fold(None, |acc, e| match acc {
+        Some(acc) => Some(Expr::BinaryExpr {
+            left: Arc::new(acc),
+            op: Operator::And,
+            right: Arc::new(e),
+        }),
+        None => Some(e),
+    })
+}
+
 /// Create field meta-data from an expression, for use in a result set schema
 pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Result<Field> {
     match e {
@@ -88,7 +163,7 @@ pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Result<Field> {
                 )))
             }
         }
-        Expr::Literal(ref lit) => Ok(Field::new("lit", lit.get_datatype(), true)),
+        Expr::Literal(_) => Ok(Field::new("lit", e.get_type(input_schema)?, true)),
         Expr::ScalarFunction {
             ref name,
             ref return_type,
@@ -99,22 +174,33 @@ pub fn expr_to_field(e: &Expr, input_schema: &Schema) -> Result<Field> {
             ref return_type,
             ..
         } => Ok(Field::new(&name, return_type.clone(), true)),
+        Expr::WindowFunction {
+            ref name,
+            ref return_type,
+            ..
+        } => Ok(Field::new(&name, return_type.clone(), true)),
         Expr::Cast { ref data_type, .. } => {
             Ok(Field::new("cast", data_type.clone(), true))
         }
+        Expr::ScalarSubquery(_) => {
+            Ok(Field::new("subquery", e.get_type(input_schema)?, true))
+        }
+        Expr::Case { .. } => Ok(Field::new("case", e.get_type(input_schema)?, true)),
+        Expr::Between { .. } => Ok(Field::new("between", DataType::Boolean, true)),
+        Expr::InList { .. } => Ok(Field::new("in_list", DataType::Boolean, true)),
+        Expr::Like { .. } => Ok(Field::new("like", DataType::Boolean, true)),
+        Expr::ElementAt { .. } => {
+            Ok(Field::new("element_at", e.get_type(input_schema)?, true))
+        }
         Expr::BinaryExpr {
             ref left,
             ref right,
             ..
-        } => {
-            let left_type = left.get_type(input_schema)?;
-            let right_type = right.get_type(input_schema)?;
-            Ok(Field::new(
-                "binary_expr",
-                get_supertype(&left_type, &right_type).unwrap(),
-                true,
-            ))
-        }
+        } => Ok(Field::new(
+            "binary_expr",
+            get_expr_supertype(&[left.as_ref(), right.as_ref()], input_schema)?,
+            true,
+        )),
         _ => Err(ExecutionError::NotImplemented(format!(
             "Cannot determine schema type for expression {:?}",
             e
@@ -129,6 +215,19 @@ pub fn exprlist_to_fields(expr: &Vec<Expr>, input_schema: &Schema) -> Result<Vec
         .collect()
 }
 
+/// Get the type of the elements of a List or FixedSizeList type
+pub fn element_type(list_type: &DataType) -> Result<DataType> {
+    match list_type {
+        DataType::List(data_type) | DataType::FixedSizeList(data_type, _) => {
+            Ok(data_type.as_ref().clone())
+        }
+        _ => Err(ExecutionError::General(format!(
+            "Cannot get an element of a value of type {:?}",
+            list_type
+        ))),
+    }
+}
+
 /// Given two datatypes, determine the supertype that both types can safely be cast to
 pub fn get_supertype(l: &DataType, r: &DataType) -> Result<DataType> {
     match _get_supertype(l, r) {
@@ -142,10 +241,90 @@ pub fn get_supertype(l: &DataType, r: &DataType) -> Result<DataType> {
     }
 }
 
+/// Determine the supertype of the types of expressions. Untyped null literals take the
+/// type of the other expressions, and are strings if there are no other expressions.
+pub fn get_expr_supertype(exprs: &[&Expr], schema: &Schema) -> Result<DataType> {
+    let mut super_type: Option<DataType> = None;
+    for expr in exprs.iter().filter(|expr| !expr.is_untyped_null()) {
+        let data_type = expr.get_type(schema)?;
+        super_type = Some(match super_type {
+            Some(super_type) if super_type != data_type => {
+                get_supertype(&super_type, &data_type)?
+            }
+            _ => data_type,
+        });
+    }
+    match super_type {
+        Some(super_type) => Ok(super_type),
+        None => Ok(DataType::Utf8),
+    }
+}
+
+/// Cast the columns of untyped nulls of a plan to the given types of its columns. The
+/// columns of untyped nulls are those of projections and of unions of them.
+pub fn cast_untyped_null_columns(
+    plan: &LogicalPlan,
+    types: &[DataType],
+) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::Projection { expr, input, .. } => {
+            let expr = expr
+                .iter()
+                .enumerate()
+                .map(|(i, e)| match types.get(i) {
+                    Some(data_type) if e.is_untyped_null() => Ok(Expr::Alias(
+                        Arc::new(
+                            Expr::Literal(ScalarValue::Null)
+                                .cast_to(data_type, input.schema())?,
+                        ),
+                        plan.schema().field(i).name().clone(),
+                    )),
+                    _ => Ok(e.clone()),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            LogicalPlanBuilder::from(input).project(expr)?.build()
+        }
+        LogicalPlan::Union { inputs, .. } => {
+            let inputs = inputs
+                .iter()
+                .map(|input| cast_untyped_null_columns(input, types))
+                .collect::<Result<Vec<_>>>()?;
+            inputs[1..]
+                .iter()
+                .try_fold(LogicalPlanBuilder::from(&inputs[0]), |b, p| b.union(p))?
+                .build()
+        }
+        _ => Ok(plan.clone()),
+    }
+}
+
+/// The number of decimal digits of the largest values of an integer type
+fn integer_digits(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::Int8 | DataType::UInt8 => Some(3),
+        DataType::Int16 | DataType::UInt16 => Some(5),
+        DataType::Int32 | DataType::UInt32 => Some(10),
+        DataType::Int64 => Some(19),
+        DataType::UInt64 => Some(20),
+        _ => None,
+    }
+}
+
+/// The decimal type that can hold the values of two decimals of precisions and scales
+fn decimal_supertype(l: (usize, usize), r: (usize, usize)) -> DataType {
+    let scale = l.1.max(r.1);
+    let integer_digits = (l.0.saturating_sub(l.1)).max(r.0.saturating_sub(r.1));
+    DataType::Decimal((integer_digits + scale).min(38), scale)
+}
+
 /// Given two datatypes, determine the supertype that both types can safely be cast to
 fn _get_supertype(l: &DataType, r: &DataType) -> Option<DataType> {
     use arrow::datatypes::DataType::*;
     match (l, r) {
+        // dictionaries are coerced to the type of their values, whatever their key type
+        (Dictionary(_, value_type), _) => get_supertype(value_type, r).ok(),
+        (_, Dictionary(_, value_type)) => get_supertype(l, value_type).ok(),
+
         (UInt8, Int8) => Some(Int8),
         (UInt8, Int16) => Some(Int16),
         (UInt8, Int32) => Some(Int32),
@@ -235,11 +414,33 @@ fn _get_supertype(l: &DataType, r: &DataType) -> Option<DataType> {
         (Float64, Float32) => Some(Float64),
         (Float64, Float64) => Some(Float64),
 
+        // decimals are coerced to the larger scale with enough integer digits for either
+        // side, up to the maximum precision, and to floating point with floats
+        (Decimal(l_precision, l_scale), Decimal(r_precision, r_scale)) => Some(
+            decimal_supertype((*l_precision, *l_scale), (*r_precision, *r_scale)),
+        ),
+        (Decimal(_, _), Float32) | (Decimal(_, _), Float64) => Some(Float64),
+        (Decimal(precision, scale), _) => integer_digits(r)
+            .map(|digits| decimal_supertype((*precision, *scale), (digits, 0))),
+
         (Utf8, _) => Some(Utf8),
         (_, Utf8) => Some(Utf8),
 
         (Boolean, Boolean) => Some(Boolean),
 
+        // timestamps are instants in UTC, which are coerced to the finer unit and to the
+        // time zone of either side, or to UTC when their time zones differ
+        (Timestamp(l_unit, l_tz), Timestamp(r_unit, r_tz)) => {
+            let tz = match (l_tz, r_tz) {
+                (Some(l_tz), Some(r_tz)) if l_tz != r_tz => {
+                    Some(Arc::new("UTC".to_string()))
+                }
+                (Some(tz), _) | (_, Some(tz)) => Some(tz.clone()),
+                (None, None) => None,
+            };
+            Some(Timestamp(l_unit.clone().max(r_unit.clone()), tz))
+        }
+
         _ => None,
     }
 }
@@ -248,7 +449,7 @@ fn _get_supertype(l: &DataType, r: &DataType) -> Option<DataType> {
 mod tests {
     use super::*;
     use crate::logicalplan::Expr;
-    use arrow::datatypes::DataType;
+    use arrow::datatypes::{DataType, TimeUnit};
     use std::collections::HashSet;
     use std::sync::Arc;
 
@@ -273,4 +474,81 @@ mod tests {
         assert!(accum.contains(&3));
         Ok(())
     }
+
+    #[test]
+    fn test_timestamp_supertype() -> Result<()> {
+        let timestamp = |unit: TimeUnit, tz: Option<&str>| {
+            DataType::Timestamp(unit, tz.map(|tz| Arc::new(tz.to_string())))
+        };
+        assert_eq!(
+            get_supertype(
+                &timestamp(TimeUnit::Second, Some("+02:00")),
+                &timestamp(TimeUnit::Nanosecond, None)
+            )?,
+            timestamp(TimeUnit::Nanosecond, Some("+02:00"))
+        );
+        assert_eq!(
+            get_supertype(
+                &timestamp(TimeUnit::Millisecond, Some("+02:00")),
+                &timestamp(TimeUnit::Millisecond, Some("-05:00"))
+            )?,
+            timestamp(TimeUnit::Millisecond, Some("UTC"))
+        );
+        assert!(
+            get_supertype(&timestamp(TimeUnit::Second, None), &DataType::Int64).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimal_supertype() -> Result<()> {
+        assert_eq!(
+            get_supertype(&DataType::Decimal(10, 2), &DataType::Decimal(5, 4))?,
+            DataType::Decimal(12, 4)
+        );
+        assert_eq!(
+            get_supertype(&DataType::Int32, &DataType::Decimal(10, 2))?,
+            DataType::Decimal(12, 2)
+        );
+        assert_eq!(
+            get_supertype(&DataType::Decimal(38, 10), &DataType::Int64)?,
+            DataType::Decimal(38, 10)
+        );
+        assert_eq!(
+            get_supertype(&DataType::Decimal(10, 2), &DataType::Float32)?,
+            DataType::Float64
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_dictionary_supertype() -> Result<()> {
+        let dictionary = |key_type: DataType, value_type: DataType| {
+            DataType::Dictionary(Box::new(key_type), Box::new(value_type))
+        };
+        for key_type in vec![DataType::Int8, DataType::UInt16, DataType::Int64] {
+            assert_eq!(
+                get_supertype(
+                    &dictionary(key_type.clone(), DataType::Utf8),
+                    &DataType::Utf8
+                )?,
+                DataType::Utf8
+            );
+            assert_eq!(
+                get_supertype(
+                    &DataType::Int64,
+                    &dictionary(key_type.clone(), DataType::Int32)
+                )?,
+                DataType::Int64
+            );
+        }
+        assert_eq!(
+            get_supertype(
+                &dictionary(DataType::Int8, DataType::Utf8),
+                &dictionary(DataType::UInt32, DataType::Utf8)
+            )?,
+            DataType::Utf8
+        );
+        Ok(())
+    }
 }
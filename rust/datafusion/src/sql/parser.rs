@@ -19,6 +19,13 @@
 //!
 //! Note that most SQL parsing is now delegated to the sqlparser crate, which handles ANSI
 //! SQL but this module contains DataFusion-specific SQL extensions.
+//!
+//! Syntax that the ANSI SQL parser does not support is rewritten on the tokens of a
+//! statement before it is parsed. Each such construct becomes a call of a sentinel
+//! function whose name starts with `__`, for example `expr IN (value, ...)` becomes
+//! `expr = __in_list(value, ...)`, which the ANSI SQL parser accepts. The query planner
+//! recognizes the sentinel functions by the names defined in this module and plans the
+//! original construct from their arguments.
 
 use sqlparser::dialect::*;
 use sqlparser::sqlast::*;
@@ -31,8 +38,151 @@ macro_rules! parser_err {
     };
 }
 
+/// Name of the function that an ORDER BY expression followed by `NULLS FIRST` is
+/// wrapped in
+pub const NULLS_FIRST_FUNCTION: &str = "__nulls_first";
+
+/// Name of the function that an ORDER BY expression followed by `NULLS LAST` is
+/// wrapped in
+pub const NULLS_LAST_FUNCTION: &str = "__nulls_last";
+
+/// Name of the function that a window function call is wrapped in. A call
+/// `f(args) OVER (PARTITION BY p, ... ORDER BY o, ...)` is rewritten to
+/// `__window(f(args), __partition_by(p, ...), __order_by(o, ...))`.
+pub const WINDOW_FUNCTION: &str = "__window";
+
+/// Name of the function that the PARTITION BY expressions of a window are wrapped in
+pub const PARTITION_BY_FUNCTION: &str = "__partition_by";
+
+/// Name of the function that the ORDER BY expressions of a window are wrapped in
+pub const ORDER_BY_FUNCTION: &str = "__order_by";
+
+/// Name of the function that an ORDER BY expression of a window followed by `DESC` is
+/// wrapped in
+pub const DESC_FUNCTION: &str = "__desc";
+
+/// Name of the function that the frame clause of a window is rewritten to, with the
+/// units and the bounds of the frame as string arguments, e.g.
+/// `__window_frame('ROWS', '3 PRECEDING', 'CURRENT ROW')`
+pub const WINDOW_FRAME_FUNCTION: &str = "__window_frame";
+
+/// Name of the function that the subquery of an `expr IN (subquery)` condition is
+/// wrapped in. The condition is rewritten to `expr = __in_subquery(subquery)`.
+pub const IN_SUBQUERY_FUNCTION: &str = "__in_subquery";
+
+/// Name of the function that the subquery of an `expr NOT IN (subquery)` condition is
+/// wrapped in
+pub const NOT_IN_SUBQUERY_FUNCTION: &str = "__not_in_subquery";
+
+/// Name of the function that the values of an `expr IN (value, ...)` condition are
+/// wrapped in. The condition is rewritten to `expr = __in_list(value, ...)`.
+pub const IN_LIST_FUNCTION: &str = "__in_list";
+
+/// Name of the function that the values of an `expr NOT IN (value, ...)` condition are
+/// wrapped in
+pub const NOT_IN_LIST_FUNCTION: &str = "__not_in_list";
+
+/// Name of the function that the bounds of an `expr BETWEEN low AND high` condition are
+/// wrapped in. The condition is rewritten to `expr = __between(low, high)`.
+pub const BETWEEN_FUNCTION: &str = "__between";
+
+/// Name of the function that the bounds of an `expr NOT BETWEEN low AND high` condition
+/// are wrapped in
+pub const NOT_BETWEEN_FUNCTION: &str = "__not_between";
+
+/// Name of the function that a qualified wildcard `t.*` in the SELECT list is rewritten
+/// to, as `__qualified_wildcard(t)`
+pub const QUALIFIED_WILDCARD_FUNCTION: &str = "__qualified_wildcard";
+
+/// Name of the function that a `(VALUES (...), ...)` list in the FROM clause is
+/// rewritten to. Each row is rewritten to `__values_row(...)` and the column names of
+/// an alias `AS t(a, b)` to a leading `__values_columns(a, b)` argument.
+pub const VALUES_FUNCTION: &str = "__values";
+
+/// Name of the function that each row of a VALUES list is rewritten to
+pub const VALUES_ROW_FUNCTION: &str = "__values_row";
+
+/// Name of the function that the column names of the alias of a VALUES list are
+/// rewritten to
+pub const VALUES_COLUMNS_FUNCTION: &str = "__values_columns";
+
+/// Name of the function that the pattern of an `expr [NOT] ILIKE pattern` condition is
+/// wrapped in. The condition is rewritten to `expr [NOT] LIKE __ilike(pattern)`, and a
+/// trailing `ESCAPE escape` to a second `escape` argument.
+pub const ILIKE_FUNCTION: &str = "__ilike";
+
+/// Name of the function that the pattern of an `expr [NOT] LIKE pattern ESCAPE escape`
+/// condition is wrapped in. The condition is rewritten to
+/// `expr [NOT] LIKE __like_escape(pattern, escape)`.
+pub const LIKE_ESCAPE_FUNCTION: &str = "__like_escape";
+
+/// Name of the function that the pattern of an `expr ~ pattern` or `expr !~ pattern`
+/// regular expression match is wrapped in. The match is rewritten to
+/// `expr [NOT] LIKE __regex_match(pattern)`.
+pub const REGEX_MATCH_FUNCTION: &str = "__regex_match";
+
+/// Name of the function that the pattern of a case-insensitive `expr ~* pattern` or
+/// `expr !~* pattern` regular expression match is wrapped in, as for `~`
+pub const REGEX_IMATCH_FUNCTION: &str = "__regex_imatch";
+
+/// Words that the `~` and `~*` operators outside of quotes are replaced with before a
+/// statement is tokenized, since the tokenizer does not accept `~`. The negated `!~` and
+/// `!~*` operators are replaced with `NOT` followed by the word.
+const REGEX_MATCH_OPERATOR: &str = "__regex_match_operator";
+const REGEX_IMATCH_OPERATOR: &str = "__regex_imatch_operator";
+
+/// Words that end the upper bound of a `BETWEEN` condition
+const BETWEEN_TERMINATORS: &[&str] = &[
+    "AND",
+    "OR",
+    "IS",
+    "AS",
+    "ASC",
+    "DESC",
+    "THEN",
+    "WHEN",
+    "ELSE",
+    "END",
+    "FROM",
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "UNION",
+    "EXCEPT",
+    "INTERSECT",
+    "JOIN",
+    "ON",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "CROSS",
+];
+
+/// Name of the function that an `EXISTS (subquery)` condition is rewritten to
+pub const EXISTS_FUNCTION: &str = "__exists";
+
+/// Name of the function that the `DISTINCT ON (a, b)` clause of a SELECT statement is
+/// rewritten to, as a leading `__distinct_on(a, b)` item of the SELECT list
+pub const DISTINCT_ON_FUNCTION: &str = "__distinct_on";
+
+/// Name of the function that the right operand of a string concatenation `a || b` is
+/// wrapped in. The concatenation is rewritten to `a + __string_concat(b)`, where the
+/// operand extends to the next operator that does not bind more tightly than `||`.
+pub const STRING_CONCAT_FUNCTION: &str = "__string_concat";
+
+/// Word that each `||` operator outside of quotes is replaced with before a statement is
+/// tokenized, since the tokenizer does not accept `|`
+const STRING_CONCAT_OPERATOR: &str = "__string_concat_operator";
+
+/// Operators that end the right operand of a string concatenation, since they do not
+/// bind more tightly than `||`
+const STRING_CONCAT_TERMINATORS: &[&str] = &["=", "!=", "<>", "<", ">", "<=", ">="];
+
 /// Types of files to parse as DataFrames
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FileType {
     /// Newline-delimited JSON
     NdJson,
@@ -42,6 +192,87 @@ pub enum FileType {
     CSV,
 }
 
+/// Formats of the plans described by EXPLAIN
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExplainFormat {
+    /// A line for each plan node, indented below the node that it is an input of
+    Text,
+    /// A directed graph in the DOT language of Graphviz
+    Graphviz,
+    /// A JSON object for each plan node, with its inputs nested in it
+    Json,
+}
+
+/// How the case of identifiers that are not quoted is normalized. Quoted identifiers,
+/// such as `"MixedCase Col"`, always keep their case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdentifierCase {
+    /// Identifiers keep the case they are written in, so that they match column and
+    /// table names case-sensitively
+    Preserve,
+    /// Identifiers are converted to lowercase, as in PostgreSQL
+    Lowercase,
+    /// Identifiers are converted to uppercase, as in the SQL standard
+    Uppercase,
+}
+
+/// Operators that combine the rows of two queries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOperator {
+    /// Rows of either query
+    Union,
+    /// Rows of the left query that are also rows of the right query
+    Intersect,
+    /// Rows of the left query that are not rows of the right query
+    Except,
+}
+
+/// The queries of a statement with set operations
+#[derive(Debug, Clone)]
+pub enum SetExpr {
+    /// A SELECT statement, without ORDER BY and LIMIT clauses
+    Select(ASTNode),
+    /// The rows of two queries combined by a set operator
+    SetOperation {
+        /// The set operator
+        op: SetOperator,
+        /// Whether duplicate rows are kept (`ALL`) or removed (`DISTINCT`)
+        all: bool,
+        /// The left query
+        left: Box<SetExpr>,
+        /// The right query
+        right: Box<SetExpr>,
+    },
+}
+
+/// An action of a `WHEN [NOT] MATCHED` clause of a MERGE statement
+#[derive(Debug, Clone)]
+pub enum MergeClause {
+    /// `WHEN MATCHED [AND predicate] THEN UPDATE SET column = value, ...`
+    Update {
+        /// Condition that a matched row must meet for the clause to apply
+        predicate: Option<ASTNode>,
+        /// The columns of the target table and their new values
+        assignments: Vec<(String, ASTNode)>,
+    },
+    /// `WHEN MATCHED [AND predicate] THEN DELETE`
+    Delete {
+        /// Condition that a matched row must meet for the clause to apply
+        predicate: Option<ASTNode>,
+    },
+    /// `WHEN NOT MATCHED [AND predicate] THEN INSERT [(column, ...)] VALUES (value, ...)`
+    Insert {
+        /// Condition that a source row without a match must meet for the clause to
+        /// apply
+        predicate: Option<ASTNode>,
+        /// Names of the columns that the values are inserted into, or empty for all
+        /// columns of the target table in order
+        columns: Vec<String>,
+        /// The values of the inserted row
+        values: Vec<ASTNode>,
+    },
+}
+
 /// DataFrame AST Node representations.
 ///
 /// Tokens parsed by `DFParser` are converted into these values.
@@ -62,6 +293,114 @@ pub enum DFASTNode {
         /// Path to file
         location: String,
     },
+    /// Scan a table to collect statistics about its data
+    AnalyzeTable {
+        /// Table name
+        name: String,
+    },
+    /// Remove a table from the execution context
+    DropTable {
+        /// Table name
+        name: String,
+        /// Whether a missing table is ignored rather than an error
+        if_exists: bool,
+    },
+    /// Queries combined by `UNION`, `INTERSECT` or `EXCEPT`
+    SetOperation {
+        /// The combined queries
+        body: SetExpr,
+        /// ORDER BY clause applied to the combined rows
+        order_by: Option<Vec<SQLOrderByExpr>>,
+        /// LIMIT clause applied to the combined rows
+        limit: Option<Box<ASTNode>>,
+    },
+    /// Change a configuration variable of the session with `SET name = value`
+    SetVariable {
+        /// Variable name, in lowercase
+        variable: String,
+        /// New value of the variable
+        value: String,
+    },
+    /// Show a configuration variable of the session with `SHOW name`, or all of them
+    /// with `SHOW ALL`
+    ShowVariable {
+        /// Variable name, in lowercase, or `None` for all variables
+        variable: Option<String>,
+    },
+    /// List the registered tables with `SHOW TABLES`
+    ShowTables,
+    /// List the columns of a table with `SHOW COLUMNS FROM name` or `DESCRIBE name`
+    ShowColumns {
+        /// Table name
+        table_name: String,
+    },
+    /// Append the rows of a query to a registered table with
+    /// `INSERT INTO name [(column, ...)] { SELECT ... | VALUES (...), ... }`
+    Insert {
+        /// Table name
+        table_name: String,
+        /// Names of the columns that the columns of the query are inserted into, or
+        /// empty for all columns of the table in order
+        columns: Vec<String>,
+        /// The query producing the rows to insert
+        source: Box<DFASTNode>,
+    },
+    /// Update, delete and insert the rows of a registered table depending on whether
+    /// they match the rows of a source relation with
+    /// `MERGE INTO name [[AS] alias] USING source [[AS] alias] ON condition WHEN ...`
+    Merge {
+        /// Target table name
+        table_name: String,
+        /// Alias of the target table
+        alias: Option<String>,
+        /// The source relation, which is a table, a subquery or a VALUES list and may
+        /// have an alias
+        source: ASTNode,
+        /// The condition that matches the rows of the target and source relations
+        on: ASTNode,
+        /// The `WHEN [NOT] MATCHED` clauses, in the order they are checked
+        clauses: Vec<MergeClause>,
+    },
+    /// Change the values of columns of the rows of a registered table with
+    /// `UPDATE name SET column = value, ... [WHERE condition]`
+    Update {
+        /// Table name
+        table_name: String,
+        /// The columns and their new values
+        assignments: Vec<(String, ASTNode)>,
+        /// The condition that the updated rows meet, or `None` to update all rows
+        selection: Option<ASTNode>,
+    },
+    /// Remove the rows of a registered table with `DELETE FROM name [WHERE condition]`
+    Delete {
+        /// Table name
+        table_name: String,
+        /// The condition that the removed rows meet, or `None` to remove all rows
+        selection: Option<ASTNode>,
+    },
+    /// Write the rows of a query to a file with
+    /// `COPY (query) TO 'path' [(FORMAT CSV | PARQUET | JSON)]`
+    Copy {
+        /// The query producing the rows to write
+        source: Box<DFASTNode>,
+        /// Path of the file
+        location: String,
+        /// File type, which is CSV if no format is given
+        file_type: FileType,
+    },
+    /// Describe the plan of a statement instead of executing it
+    Explain {
+        /// Whether to execute the statement and include the metrics collected by the
+        /// physical plan
+        analyze: bool,
+        /// Whether to include the output schema of each plan node and the casts added
+        /// by type coercion
+        verbose: bool,
+        /// The format of the described plans
+        format: ExplainFormat,
+        /// The statement to explain
+        statement: Box<DFASTNode>,
+    },
 }
 
 /// SQL Parser
@@ -72,20 +411,82 @@ pub struct DFParser {
 impl DFParser {
     /// Parse the specified tokens
     pub fn new(sql: String) -> Result<Self, ParserError> {
+        Self::new_with_identifier_case(sql, IdentifierCase::Preserve)
+    }
+
+    /// Parse the specified tokens, normalizing the case of identifiers that are not
+    /// quoted
+    pub fn new_with_identifier_case(
+        sql: String,
+        identifier_case: IdentifierCase,
+    ) -> Result<Self, ParserError> {
         let dialect = GenericSqlDialect {};
+        let sql = replace_operators(&sql);
         let mut tokenizer = Tokenizer::new(&dialect, &sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_values(tokenizer.tokenize()?)?;
+        let tokens = rewrite_identifiers(tokens, identifier_case);
+        let tokens = rewrite_string_concat(tokens)?;
+        let tokens = rewrite_subscripts(tokens)?;
+        let tokens = rewrite_null_ordering(tokens)?;
+        let tokens = rewrite_window_functions(tokens)?;
+        let tokens = rewrite_in_subqueries(tokens);
+        let tokens = rewrite_in_lists(tokens);
+        let tokens = rewrite_exists_subqueries(tokens);
+        let tokens = rewrite_between(tokens)?;
+        let tokens = rewrite_like(tokens)?;
+        let tokens = rewrite_regex_match(tokens)?;
+        let tokens = rewrite_simple_case(tokens)?;
+        let tokens = rewrite_qualified_wildcards(tokens);
+        let tokens = rewrite_distinct_on(tokens)?;
         Ok(DFParser {
             parser: Parser::new(tokens),
         })
     }
 
+    /// Split a script into its statements, which are separated by semicolons outside of
+    /// quoted strings and identifiers. Statements that only consist of whitespace are
+    /// skipped.
+    pub fn split_statements(sql: &str) -> Vec<&str> {
+        let mut statements = vec![];
+        let mut quote = None;
+        let mut start = 0;
+        for (i, c) in sql.char_indices() {
+            match (quote, c) {
+                // a quote that is escaped by doubling it ends and starts a string
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {}
+                (None, '\'') | (None, '"') => quote = Some(c),
+                (None, ';') => {
+                    statements.push(&sql[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        statements.push(&sql[start..]);
+        statements
+            .into_iter()
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty())
+            .collect()
+    }
+
     /// Parse a SQL statement and produce an Abstract Syntax Tree (AST)
     pub fn parse_sql(sql: String) -> Result<DFASTNode, ParserError> {
         let mut parser = DFParser::new(sql)?;
         parser.parse()
     }
 
+    /// Parse a SQL statement and produce an Abstract Syntax Tree (AST), normalizing the
+    /// case of identifiers that are not quoted
+    pub fn parse_sql_with_identifier_case(
+        sql: String,
+        identifier_case: IdentifierCase,
+    ) -> Result<DFASTNode, ParserError> {
+        let mut parser = DFParser::new_with_identifier_case(sql, identifier_case)?;
+        parser.parse()
+    }
+
     /// Parse a new expression
     pub fn parse(&mut self) -> Result<DFASTNode, ParserError> {
         self.parse_expr(0)
@@ -122,7 +523,7 @@ impl DFParser {
                             if let Some(Token::Identifier(column_name)) =
                                 self.parser.next_token()
                             {
-                                if let Ok(data_type) = self.parser.parse_data_type() {
+                                if let Ok(data_type) = self.parse_column_data_type() {
                                     let allow_null = if self
                                         .parser
                                         .parse_keywords(vec!["NOT", "NULL"])
@@ -163,10 +564,14 @@ impl DFParser {
                     }
 
                     let mut headers = true;
-                    let file_type: FileType = if self
-                        .parser
-                        .parse_keywords(vec!["STORED", "AS", "CSV"])
-                    {
+                    if !self.parser.parse_keywords(vec!["STORED", "AS"]) {
+                        return parser_err!(format!(
+                            "Expected 'STORED AS' clause, found {:?}",
+                            self.parser.peek_token()
+                        ));
+                    }
+                    // the file type may or may not be tokenized as a keyword
+                    let file_type: FileType = if self.parse_word("CSV") {
                         if self.parser.parse_keywords(vec!["WITH", "HEADER", "ROW"]) {
                             headers = true;
                         } else if self
@@ -176,14 +581,14 @@ impl DFParser {
                             headers = false;
                         }
                         FileType::CSV
-                    } else if self.parser.parse_keywords(vec!["STORED", "AS", "NDJSON"]) {
+                    } else if self.parse_word("NDJSON") || self.parse_word("JSON") {
                         FileType::NdJson
-                    } else if self.parser.parse_keywords(vec!["STORED", "AS", "PARQUET"])
-                    {
+                    } else if self.parse_word("PARQUET") {
                         FileType::Parquet
                     } else {
                         return parser_err!(format!(
-                            "Expected 'STORED AS' clause, found {:?}",
+                            "Expected CSV, NDJSON, JSON or PARQUET after 'STORED AS', \
+                             found {:?}",
                             self.parser.peek_token()
                         ));
                     };
@@ -208,8 +613,637 @@ impl DFParser {
                     self.parser.peek_token()
                 )),
             }
+        } else if self.parse_word("ANALYZE") {
+            if !self.parser.parse_keyword("TABLE") {
+                return parser_err!("Expected TABLE after ANALYZE");
+            }
+            match self.parser.next_token() {
+                Some(Token::Identifier(id)) => Ok(DFASTNode::AnalyzeTable { name: id }),
+                _ => parser_err!(format!(
+                    "Unexpected token after ANALYZE TABLE: {:?}",
+                    self.parser.peek_token()
+                )),
+            }
+        } else if self.parse_word("DROP") {
+            if !self.parser.parse_keyword("TABLE") {
+                return parser_err!("Expected TABLE after DROP");
+            }
+            let if_exists = if self.parse_word("IF") {
+                if !self.parse_word("EXISTS") {
+                    return parser_err!("Expected EXISTS after DROP TABLE IF");
+                }
+                true
+            } else {
+                false
+            };
+            match self.parser.next_token() {
+                Some(Token::Identifier(id)) => Ok(DFASTNode::DropTable {
+                    name: id,
+                    if_exists,
+                }),
+                _ => parser_err!(format!(
+                    "Unexpected token after DROP TABLE: {:?}",
+                    self.parser.peek_token()
+                )),
+            }
+        } else if self.parse_word("SET") {
+            let variable = self.parse_variable_name()?;
+            if !self.parser.consume_token(&Token::Eq) && !self.parse_word("TO") {
+                return parser_err!(format!(
+                    "Expected '=' or TO after SET {}, found {:?}",
+                    variable,
+                    self.parser.peek_token()
+                ));
+            }
+            let value = match self.parser.next_token() {
+                Some(Token::Number(value))
+                | Some(Token::SingleQuotedString(value))
+                | Some(Token::Identifier(value))
+                | Some(Token::Keyword(value)) => value,
+                _ => {
+                    return parser_err!(format!(
+                        "Expected a value for SET {}, found {:?}",
+                        variable,
+                        self.parser.peek_token()
+                    ))
+                }
+            };
+            Ok(DFASTNode::SetVariable { variable, value })
+        } else if self.parse_word("SHOW") {
+            if self.parse_word("TABLES") {
+                return Ok(DFASTNode::ShowTables);
+            }
+            if self.parse_word("COLUMNS") {
+                if !self.parser.parse_keyword("FROM") && !self.parse_word("IN") {
+                    return parser_err!("Expected FROM after SHOW COLUMNS");
+                }
+                let table_name = self.parse_table_name("SHOW COLUMNS FROM")?;
+                return Ok(DFASTNode::ShowColumns { table_name });
+            }
+            let variable = if self.parse_word("ALL") {
+                None
+            } else {
+                Some(self.parse_variable_name()?)
+            };
+            Ok(DFASTNode::ShowVariable { variable })
+        } else if self.parse_word("DESCRIBE") || self.parse_word("DESC") {
+            self.parser.parse_keyword("TABLE");
+            let table_name = self.parse_table_name("DESCRIBE")?;
+            Ok(DFASTNode::ShowColumns { table_name })
+        } else if self.parse_word("INSERT") {
+            if !self.parse_word("INTO") {
+                return parser_err!("Expected INTO after INSERT");
+            }
+            let table_name = match self.parser.next_token() {
+                Some(Token::Identifier(id)) => id,
+                other => {
+                    return parser_err!(format!(
+                        "Expected a table name after INSERT INTO, found {:?}",
+                        other
+                    ))
+                }
+            };
+            let mut columns = vec![];
+            if self.parser.consume_token(&Token::LParen) {
+                loop {
+                    match self.parser.next_token() {
+                        Some(Token::Identifier(column)) => columns.push(column),
+                        other => {
+                            return parser_err!(format!(
+                                "Expected a column name in INSERT, found {:?}",
+                                other
+                            ))
+                        }
+                    }
+                    match self.parser.next_token() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => return parser_err!("Expected ',' or ')' after column name"),
+                    }
+                }
+            }
+            let source = self.parse_prefix()?;
+            match source {
+                DFASTNode::ANSI(ASTNode::SQLSelect { .. })
+                | DFASTNode::SetOperation { .. } => Ok(DFASTNode::Insert {
+                    table_name,
+                    columns,
+                    source: Box::new(source),
+                }),
+                _ => parser_err!("Expected a query or VALUES in INSERT"),
+            }
+        } else if self.parse_word("MERGE") {
+            self.parse_merge()
+        } else if self.parse_word("UPDATE") {
+            let table_name = match self.parser.next_token() {
+                Some(Token::Identifier(id)) => id,
+                other => {
+                    return parser_err!(format!(
+                        "Expected a table name after UPDATE, found {:?}",
+                        other
+                    ))
+                }
+            };
+            if !self.parse_word("SET") {
+                return parser_err!(format!("Expected SET after UPDATE {}", table_name));
+            }
+            let assignments = self.parse_assignments()?;
+            let selection = self.parse_optional_where()?;
+            Ok(DFASTNode::Update {
+                table_name,
+                assignments,
+                selection,
+            })
+        } else if self.parse_word("DELETE") {
+            if !self.parser.parse_keyword("FROM") {
+                return parser_err!("Expected FROM after DELETE");
+            }
+            let table_name = match self.parser.next_token() {
+                Some(Token::Identifier(id)) => id,
+                other => {
+                    return parser_err!(format!(
+                        "Expected a table name after DELETE FROM, found {:?}",
+                        other
+                    ))
+                }
+            };
+            let selection = self.parse_optional_where()?;
+            Ok(DFASTNode::Delete {
+                table_name,
+                selection,
+            })
+        } else if self.parse_word("COPY") {
+            self.parse_copy()
+        } else if self.parse_word("EXPLAIN") {
+            let mut analyze = self.parse_word("ANALYZE");
+            let mut verbose = self.parse_word("VERBOSE");
+            let mut format = ExplainFormat::Text;
+            // options may also be given in parentheses, as in
+            // `EXPLAIN (ANALYZE, FORMAT JSON) ...`
+            if self.parser.consume_token(&Token::LParen) {
+                loop {
+                    if self.parse_word("ANALYZE") {
+                        analyze = true;
+                    } else if self.parse_word("VERBOSE") {
+                        verbose = true;
+                    } else if self.parse_word("FORMAT") {
+                        format = self.parse_explain_format()?;
+                    } else {
+                        return parser_err!(format!(
+                            "Expected an EXPLAIN option, found {:?}",
+                            self.parser.peek_token()
+                        ));
+                    }
+                    match self.parser.next_token() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => {
+                            return parser_err!(
+                                "Expected ',' or ')' after EXPLAIN option"
+                            )
+                        }
+                    }
+                }
+            }
+            Ok(DFASTNode::Explain {
+                analyze,
+                verbose,
+                format,
+                statement: Box::new(self.parse_prefix()?),
+            })
         } else {
-            Ok(DFASTNode::ANSI(self.parser.parse_prefix()?))
+            let query = self.parser.parse_prefix()?;
+            match self.parse_set_operator() {
+                Some(op) => self.parse_set_operation(query, op),
+                None => Ok(DFASTNode::ANSI(query)),
+            }
+        }
+    }
+
+    /// Parse a COPY statement after the COPY keyword
+    fn parse_copy(&mut self) -> Result<DFASTNode, ParserError> {
+        if !self.parser.consume_token(&Token::LParen) {
+            return parser_err!("Expected a query in parentheses after COPY");
+        }
+        let source = self.parse_prefix()?;
+        match source {
+            DFASTNode::ANSI(ASTNode::SQLSelect { .. })
+            | DFASTNode::SetOperation { .. } => {}
+            _ => return parser_err!("Expected a query in COPY"),
+        }
+        if !self.parser.consume_token(&Token::RParen) {
+            return parser_err!(format!(
+                "Expected ')' after the query of COPY, found {:?}",
+                self.parser.peek_token()
+            ));
+        }
+        if !self.parse_word("TO") {
+            return parser_err!(format!(
+                "Expected TO after the query of COPY, found {:?}",
+                self.parser.peek_token()
+            ));
+        }
+        let location = self.parser.parse_literal_string()?;
+        let mut file_type = FileType::CSV;
+        if self.parser.consume_token(&Token::LParen) {
+            if !self.parse_word("FORMAT") {
+                return parser_err!(format!(
+                    "Expected FORMAT in the options of COPY, found {:?}",
+                    self.parser.peek_token()
+                ));
+            }
+            file_type = if self.parse_word("CSV") {
+                FileType::CSV
+            } else if self.parse_word("PARQUET") {
+                FileType::Parquet
+            } else if self.parse_word("JSON") || self.parse_word("NDJSON") {
+                FileType::NdJson
+            } else {
+                return parser_err!(format!(
+                    "Expected CSV, PARQUET or JSON after FORMAT, found {:?}",
+                    self.parser.peek_token()
+                ));
+            };
+            if !self.parser.consume_token(&Token::RParen) {
+                return parser_err!("Expected ')' after the options of COPY");
+            }
+        }
+        Ok(DFASTNode::Copy {
+            source: Box::new(source),
+            location,
+            file_type,
+        })
+    }
+
+    /// Parse a MERGE statement after the MERGE keyword
+    fn parse_merge(&mut self) -> Result<DFASTNode, ParserError> {
+        if !self.parse_word("INTO") {
+            return parser_err!("Expected INTO after MERGE");
+        }
+        let table_name = match self.parser.next_token() {
+            Some(Token::Identifier(id)) => id,
+            other => {
+                return parser_err!(format!(
+                    "Expected a table name after MERGE INTO, found {:?}",
+                    other
+                ))
+            }
+        };
+        let alias = self.parse_merge_alias()?;
+        if !self.parse_word("USING") {
+            return parser_err!(format!(
+                "Expected USING after MERGE INTO {}, found {:?}",
+                table_name,
+                self.parser.peek_token()
+            ));
+        }
+        let source = self.parser.parse_prefix()?;
+        let source = match self.parse_merge_alias()? {
+            Some(alias) => ASTNode::SQLAliasedExpr(Box::new(source), alias),
+            None => source,
+        };
+        if !self.parser.parse_keyword("ON") {
+            return parser_err!(format!(
+                "Expected ON after the source of MERGE, found {:?}",
+                self.parser.peek_token()
+            ));
+        }
+        let on = self.parser.parse_expr(0)?;
+
+        let mut clauses = vec![];
+        while self.parse_word("WHEN") {
+            let matched = !self.parse_word("NOT");
+            if !self.parse_word("MATCHED") {
+                return parser_err!("Expected MATCHED or NOT MATCHED after WHEN");
+            }
+            let predicate = if self.parser.parse_keyword("AND") {
+                Some(self.parser.parse_expr(0)?)
+            } else {
+                None
+            };
+            if !self.parse_word("THEN") {
+                return parser_err!(format!(
+                    "Expected THEN in WHEN clause of MERGE, found {:?}",
+                    self.parser.peek_token()
+                ));
+            }
+            let clause = if matched && self.parse_word("UPDATE") {
+                if !self.parse_word("SET") {
+                    return parser_err!("Expected SET after UPDATE");
+                }
+                let assignments = self.parse_assignments()?;
+                MergeClause::Update {
+                    predicate,
+                    assignments,
+                }
+            } else if matched && self.parse_word("DELETE") {
+                MergeClause::Delete { predicate }
+            } else if !matched && self.parse_word("INSERT") {
+                let columns = if self.parser.consume_token(&Token::LParen) {
+                    self.parse_identifier_list()?
+                } else {
+                    vec![]
+                };
+                if !self.parse_word("VALUES")
+                    || !self.parser.consume_token(&Token::LParen)
+                {
+                    return parser_err!("Expected VALUES (...) after INSERT in MERGE");
+                }
+                let mut values = vec![];
+                loop {
+                    values.push(self.parser.parse_expr(0)?);
+                    match self.parser.next_token() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        _ => return parser_err!("Expected ',' or ')' after value"),
+                    }
+                }
+                MergeClause::Insert {
+                    predicate,
+                    columns,
+                    values,
+                }
+            } else if matched {
+                return parser_err!("Expected UPDATE or DELETE after WHEN MATCHED THEN");
+            } else {
+                return parser_err!("Expected INSERT after WHEN NOT MATCHED THEN");
+            };
+            clauses.push(clause);
+        }
+        if clauses.is_empty() {
+            return parser_err!(format!(
+                "Expected WHEN clause in MERGE, found {:?}",
+                self.parser.peek_token()
+            ));
+        }
+        Ok(DFASTNode::Merge {
+            table_name,
+            alias,
+            source,
+            on,
+            clauses,
+        })
+    }
+
+    /// Parse the assignments `column = value, ...` of the SET clause of an UPDATE
+    fn parse_assignments(&mut self) -> Result<Vec<(String, ASTNode)>, ParserError> {
+        let mut assignments = vec![];
+        loop {
+            let column = match self.parser.next_token() {
+                Some(Token::Identifier(column)) => column,
+                other => {
+                    return parser_err!(format!(
+                        "Expected a column name in UPDATE SET, found {:?}",
+                        other
+                    ))
+                }
+            };
+            if !self.parser.consume_token(&Token::Eq) {
+                return parser_err!(format!(
+                    "Expected '=' after column {} in UPDATE SET",
+                    column
+                ));
+            }
+            assignments.push((column, self.parser.parse_expr(0)?));
+            if !self.parser.consume_token(&Token::Comma) {
+                return Ok(assignments);
+            }
+        }
+    }
+
+    /// Parse the optional WHERE clause of an UPDATE or DELETE statement, which must end
+    /// the statement
+    fn parse_optional_where(&mut self) -> Result<Option<ASTNode>, ParserError> {
+        let selection = if self.parser.parse_keyword("WHERE") {
+            Some(self.parser.parse_expr(0)?)
+        } else {
+            None
+        };
+        // the statement would otherwise apply to all rows if a misspelled WHERE
+        // keyword was ignored
+        match self.parser.peek_token() {
+            None => Ok(selection),
+            Some(token) => parser_err!(format!("Unexpected token {:?}", token)),
+        }
+    }
+
+    /// Parse the optional alias of a relation of a MERGE statement, which is an
+    /// identifier that is optionally preceded by AS
+    fn parse_merge_alias(&mut self) -> Result<Option<String>, ParserError> {
+        let has_as = self.parser.parse_keyword("AS");
+        match self.parser.peek_token() {
+            Some(Token::Identifier(alias)) => {
+                self.parser.next_token();
+                Ok(Some(alias))
+            }
+            other if has_as => {
+                parser_err!(format!("Expected an alias after AS, found {:?}", other))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse a list of identifiers separated by commas, after its opening parenthesis
+    /// up to and including the closing parenthesis
+    fn parse_identifier_list(&mut self) -> Result<Vec<String>, ParserError> {
+        let mut identifiers = vec![];
+        loop {
+            match self.parser.next_token() {
+                Some(Token::Identifier(id)) => identifiers.push(id),
+                other => {
+                    return parser_err!(format!(
+                        "Expected a column name, found {:?}",
+                        other
+                    ))
+                }
+            }
+            match self.parser.next_token() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => return Ok(identifiers),
+                _ => return parser_err!("Expected ',' or ')' after column name"),
+            }
+        }
+    }
+
+    /// Parse a set operator along with its optional `ALL` or `DISTINCT` quantifier
+    fn parse_set_operator(&mut self) -> Option<(SetOperator, bool)> {
+        let op = if self.parse_word("UNION") {
+            SetOperator::Union
+        } else if self.parse_word("INTERSECT") {
+            SetOperator::Intersect
+        } else if self.parse_word("EXCEPT") {
+            SetOperator::Except
+        } else {
+            return None;
+        };
+        let all = self.parse_word("ALL");
+        if !all {
+            self.parse_word("DISTINCT");
+        }
+        Some((op, all))
+    }
+
+    /// Parse the queries following the first set operator of a statement. The ANSI SQL
+    /// parser attaches a trailing ORDER BY or LIMIT clause to the last query, but they
+    /// apply to the rows of the whole statement, so they are moved to the statement.
+    /// `INTERSECT` binds more tightly than `UNION` and `EXCEPT`, which are evaluated
+    /// from left to right.
+    fn parse_set_operation(
+        &mut self,
+        first: ASTNode,
+        first_op: (SetOperator, bool),
+    ) -> Result<DFASTNode, ParserError> {
+        let mut queries = vec![first];
+        let mut ops = vec![first_op];
+        loop {
+            queries.push(self.parser.parse_prefix()?);
+            match self.parse_set_operator() {
+                Some(op) => ops.push(op),
+                None => break,
+            }
+        }
+
+        let (order_by, limit) = match queries.last_mut() {
+            Some(ASTNode::SQLSelect {
+                ref mut order_by,
+                ref mut limit,
+                ..
+            }) => (order_by.take(), limit.take()),
+            _ => return parser_err!("Expected SELECT after set operator"),
+        };
+        for query in &queries {
+            match query {
+                ASTNode::SQLSelect {
+                    order_by: None,
+                    limit: None,
+                    ..
+                } => {}
+                ASTNode::SQLSelect { .. } => {
+                    return parser_err!(
+                        "ORDER BY and LIMIT are only allowed after the last query of a \
+                         set operation"
+                    )
+                }
+                _ => return parser_err!("Expected SELECT in set operation"),
+            }
+        }
+
+        // combine the operands of each INTERSECT first
+        let mut queries = queries.into_iter().map(SetExpr::Select);
+        let mut terms = vec![queries.next().unwrap()];
+        let mut term_ops = vec![];
+        for ((op, all), query) in ops.into_iter().zip(queries) {
+            if op == SetOperator::Intersect {
+                let left = terms.pop().unwrap();
+                terms.push(SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(query),
+                });
+            } else {
+                term_ops.push((op, all));
+                terms.push(query);
+            }
+        }
+
+        let mut terms = terms.into_iter();
+        let first = terms.next().unwrap();
+        let body =
+            term_ops
+                .into_iter()
+                .zip(terms)
+                .fold(first, |left, ((op, all), right)| SetExpr::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                });
+
+        Ok(DFASTNode::SetOperation {
+            body,
+            order_by,
+            limit,
+        })
+    }
+
+    /// Parse the name of a configuration variable, which may be a keyword
+    fn parse_variable_name(&mut self) -> Result<String, ParserError> {
+        match self.parser.next_token() {
+            Some(Token::Identifier(name)) | Some(Token::Keyword(name)) => {
+                Ok(name.to_lowercase())
+            }
+            other => parser_err!(format!("Expected a variable name, found {:?}", other)),
+        }
+    }
+
+    /// Parse the name of a table the same way as a relation of the FROM clause, so
+    /// that quoted names keep their case and a table of a schema, such as
+    /// `information_schema.tables`, is named by its qualified name
+    fn parse_table_name(&mut self, context: &str) -> Result<String, ParserError> {
+        match self.parser.parse_expr(0)? {
+            ASTNode::SQLIdentifier(name) => Ok(name),
+            ASTNode::SQLCompoundIdentifier(names) => Ok(names.join(".")),
+            other => parser_err!(format!(
+                "Expected a table name after {}, found {:?}",
+                context, other
+            )),
+        }
+    }
+
+    /// Parse the data type of a column, which may be an interval of years and months,
+    /// such as `INTERVAL YEAR TO MONTH`, or of days and time, such as `INTERVAL` or
+    /// `INTERVAL DAY TO SECOND`. Intervals are returned as custom types with the
+    /// qualifier spelled out.
+    fn parse_column_data_type(&mut self) -> Result<SQLType, ParserError> {
+        if !self.parse_word("INTERVAL") {
+            return self.parser.parse_data_type();
+        }
+        let qualifier = if self.parse_word("YEAR") {
+            if self.parse_word("TO") && !self.parse_word("MONTH") {
+                return parser_err!("Expected MONTH after INTERVAL YEAR TO");
+            }
+            "YEAR TO MONTH"
+        } else if self.parse_word("MONTH") {
+            "YEAR TO MONTH"
+        } else if self.parse_word("DAY") {
+            if self.parse_word("TO") && !self.parse_word("SECOND") {
+                return parser_err!("Expected SECOND after INTERVAL DAY TO");
+            }
+            "DAY TO SECOND"
+        } else {
+            "DAY TO SECOND"
+        };
+        Ok(SQLType::Custom(format!("INTERVAL {}", qualifier)))
+    }
+
+    /// Parse the format of `EXPLAIN (FORMAT name)`
+    fn parse_explain_format(&mut self) -> Result<ExplainFormat, ParserError> {
+        if self.parse_word("TEXT") {
+            Ok(ExplainFormat::Text)
+        } else if self.parse_word("GRAPHVIZ") {
+            Ok(ExplainFormat::Graphviz)
+        } else if self.parse_word("JSON") {
+            Ok(ExplainFormat::Json)
+        } else {
+            parser_err!(format!(
+                "Expected TEXT, GRAPHVIZ or JSON after FORMAT, found {:?}",
+                self.parser.peek_token()
+            ))
+        }
+    }
+
+    /// Consume the next token if it is the given word, regardless of whether the word
+    /// is a keyword of the SQL dialect
+    fn parse_word(&mut self, expected: &str) -> bool {
+        match self.parser.peek_token() {
+            Some(Token::Keyword(ref word)) | Some(Token::Identifier(ref word))
+                if word.eq_ignore_ascii_case(expected) =>
+            {
+                self.parser.next_token();
+                true
+            }
+            _ => false,
         }
     }
 
@@ -222,3 +1256,1008 @@ impl DFParser {
         unimplemented!()
     }
 }
+
+/// Rewrite each `expr [ASC | DESC] NULLS FIRST` item of an ORDER BY clause to
+/// `__nulls_first(expr) [ASC | DESC]`, and likewise for `NULLS LAST`
+fn rewrite_null_ordering(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    // for each level of parentheses, the position of the start of the current ORDER BY
+    // item, if the tokens at that level are part of an ORDER BY clause
+    let mut item_start: Vec<Option<usize>> = vec![None];
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::LParen => item_start.push(None),
+            Token::RParen => {
+                // unbalanced parentheses are reported by the ANSI SQL parser
+                if item_start.len() > 1 {
+                    item_start.pop();
+                }
+            }
+            Token::Comma => {
+                if let Some(start) = item_start.last_mut().unwrap() {
+                    *start = i + 1;
+                }
+            }
+            _ if is_word(&tokens[i], "BY")
+                && previous_token(&tokens, 0, i)
+                    .map_or(false, |j| is_word(&tokens[j], "ORDER")) =>
+            {
+                *item_start.last_mut().unwrap() = Some(i + 1);
+            }
+            _ if ["LIMIT", "UNION", "EXCEPT", "INTERSECT"]
+                .iter()
+                .any(|word| is_word(&tokens[i], word)) =>
+            {
+                *item_start.last_mut().unwrap() = None;
+            }
+            _ if is_word(&tokens[i], "NULLS") => {
+                let start = match item_start.last().unwrap() {
+                    Some(start) => *start,
+                    None => {
+                        return parser_err!("NULLS FIRST or LAST outside of ORDER BY")
+                    }
+                };
+                let (end, function) = match next_token(&tokens, i + 1) {
+                    Some(j) if is_word(&tokens[j], "FIRST") => (j, NULLS_FIRST_FUNCTION),
+                    Some(j) if is_word(&tokens[j], "LAST") => (j, NULLS_LAST_FUNCTION),
+                    _ => return parser_err!("Expected FIRST or LAST after NULLS"),
+                };
+                tokens.drain(i..=end);
+
+                // the expression ends before the sort direction, if there is one
+                let expr_end = match previous_token(&tokens, start, i) {
+                    Some(j)
+                        if is_word(&tokens[j], "ASC") || is_word(&tokens[j], "DESC") =>
+                    {
+                        j
+                    }
+                    _ => i,
+                };
+                tokens.insert(expr_end, Token::RParen);
+                tokens.insert(start, Token::LParen);
+                tokens.insert(start, sentinel(function));
+                // continue after the tokens that replaced the null ordering
+                i += 3;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each `f(args) OVER (...)` window function call to
+/// `__window(f(args), __partition_by(...), __order_by(...))`. An ORDER BY expression
+/// followed by `DESC` is wrapped in `__desc`, and a frame clause becomes a fourth
+/// `__window_frame` argument. Null ordering clauses must have been rewritten before.
+fn rewrite_window_functions(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let open = match next_token(&tokens, i + 1) {
+            Some(j) if is_word(&tokens[i], "OVER") && tokens[j] == Token::LParen => j,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        // find the start of the function call before OVER
+        let call_end = match previous_token(&tokens, 0, i) {
+            Some(j) if tokens[j] == Token::RParen => j,
+            _ => return parser_err!("Expected a function call before OVER"),
+        };
+        let call_open = match matching_paren(&tokens, call_end) {
+            Some(j) => j,
+            None => return parser_err!("Unbalanced parentheses before OVER"),
+        };
+        let name = match previous_token(&tokens, 0, call_open) {
+            Some(j) => match &tokens[j] {
+                Token::Keyword(word) | Token::Identifier(word) => {
+                    // function names such as RANK can be keywords of the dialect
+                    tokens[j] = Token::Identifier(word.clone());
+                    j
+                }
+                _ => return parser_err!("Expected a function name before OVER"),
+            },
+            None => return parser_err!("Expected a function name before OVER"),
+        };
+
+        // split the window specification into its clauses
+        let mut depth = 0;
+        let mut close = None;
+        let mut partition_by = vec![];
+        let mut order_by = vec![];
+        let mut frame = vec![];
+        let mut clause: Option<&mut Vec<Token>> = None;
+        let mut k = open + 1;
+        while k < tokens.len() {
+            let token = &tokens[k];
+            if depth == 0 {
+                let followed_by_by = next_token(&tokens, k + 1)
+                    .map_or(false, |j| is_word(&tokens[j], "BY"));
+                if *token == Token::RParen {
+                    close = Some(k);
+                    break;
+                } else if is_word(token, "PARTITION") && followed_by_by {
+                    clause = Some(&mut partition_by);
+                    k = next_token(&tokens, k + 1).unwrap() + 1;
+                    continue;
+                } else if is_word(token, "ORDER") && followed_by_by {
+                    clause = Some(&mut order_by);
+                    k = next_token(&tokens, k + 1).unwrap() + 1;
+                    continue;
+                } else if is_word(token, "ROWS") || is_word(token, "RANGE") {
+                    clause = Some(&mut frame);
+                }
+            }
+            if *token == Token::LParen {
+                depth += 1;
+            } else if *token == Token::RParen {
+                depth -= 1;
+            }
+            match clause {
+                Some(ref mut clause) => clause.push(token.clone()),
+                None if token.to_string().trim().is_empty() => {}
+                None => {
+                    return parser_err!(format!("Unexpected {} in OVER clause", token))
+                }
+            }
+            k += 1;
+        }
+        let close = match close {
+            Some(close) => close,
+            None => return parser_err!("Expected ) at the end of an OVER clause"),
+        };
+
+        let mut window =
+            vec![Token::Comma, sentinel(PARTITION_BY_FUNCTION), Token::LParen];
+        window.extend(partition_by);
+        window.extend(vec![
+            Token::RParen,
+            Token::Comma,
+            sentinel(ORDER_BY_FUNCTION),
+            Token::LParen,
+        ]);
+        for (n, item) in split_list(&order_by).into_iter().enumerate() {
+            if n > 0 {
+                window.push(Token::Comma);
+            }
+            match previous_token(item, 0, item.len()) {
+                Some(j) if is_word(&item[j], "DESC") => {
+                    window.push(sentinel(DESC_FUNCTION));
+                    window.push(Token::LParen);
+                    window.extend(item[..j].iter().cloned());
+                    window.push(Token::RParen);
+                }
+                Some(j) if is_word(&item[j], "ASC") => {
+                    window.extend(item[..j].iter().cloned())
+                }
+                _ => window.extend(item.iter().cloned()),
+            }
+        }
+        window.push(Token::RParen);
+        if !frame.is_empty() {
+            window.extend(rewrite_window_frame(&frame)?);
+        }
+        window.push(Token::RParen);
+
+        tokens.splice(call_end + 1..=close, window);
+        tokens.splice(name..name, vec![sentinel(WINDOW_FUNCTION), Token::LParen]);
+        i = name + 2;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite a window frame clause `{ROWS | RANGE} [BETWEEN start AND end | start]` to
+/// the tokens `, __window_frame(units, start, end)`. The end of a frame without
+/// `BETWEEN` is the current row.
+fn rewrite_window_frame(frame: &[Token]) -> Result<Vec<Token>, ParserError> {
+    let words: Vec<String> = frame
+        .iter()
+        .map(|token| token.to_string().to_uppercase())
+        .filter(|word| !word.trim().is_empty())
+        .collect();
+    let (start, end) = if words.get(1).map_or(false, |word| word == "BETWEEN") {
+        match words.iter().position(|word| word == "AND") {
+            Some(and) => (words[2..and].join(" "), words[and + 1..].join(" ")),
+            None => {
+                return parser_err!("Expected AND between the bounds of a window frame")
+            }
+        }
+    } else {
+        (words[1..].join(" "), "CURRENT ROW".to_string())
+    };
+    Ok(vec![
+        Token::Comma,
+        sentinel(WINDOW_FRAME_FUNCTION),
+        Token::LParen,
+        Token::SingleQuotedString(words[0].clone()),
+        Token::Comma,
+        Token::SingleQuotedString(start),
+        Token::Comma,
+        Token::SingleQuotedString(end),
+        Token::RParen,
+    ])
+}
+
+/// Find the position of the parenthesis that opens the one closed at position `end`
+fn matching_paren(tokens: &[Token], end: usize) -> Option<usize> {
+    let mut depth = 0;
+    for j in (0..=end).rev() {
+        if tokens[j] == Token::RParen {
+            depth += 1;
+        } else if tokens[j] == Token::LParen {
+            depth -= 1;
+            if depth == 0 {
+                return Some(j);
+            }
+        }
+    }
+    None
+}
+
+/// Split a list of tokens at the commas that are not nested in parentheses
+fn split_list(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (j, token) in tokens.iter().enumerate() {
+        if *token == Token::LParen {
+            depth += 1;
+        } else if *token == Token::RParen {
+            depth -= 1;
+        } else if *token == Token::Comma && depth == 0 {
+            items.push(&tokens[start..j]);
+            start = j + 1;
+        }
+    }
+    if previous_token(tokens, start, tokens.len()).is_some() {
+        items.push(&tokens[start..]);
+    }
+    items
+}
+
+/// Rewrite each `expr [NOT] IN (SELECT ...)` condition to
+/// `expr = __[not_]in_subquery(SELECT ...)`
+fn rewrite_in_subqueries(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_word(&tokens[i], "IN") && is_followed_by_subquery(&tokens, i) {
+            let (start, function) = match previous_token(&tokens, 0, i) {
+                Some(j) if is_word(&tokens[j], "NOT") => (j, NOT_IN_SUBQUERY_FUNCTION),
+                _ => (i, IN_SUBQUERY_FUNCTION),
+            };
+            tokens.splice(start..=i, vec![Token::Eq, sentinel(function)]);
+            i = start + 2;
+            continue;
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Rewrite each `expr [NOT] IN (value, ...)` condition to
+/// `expr = __[not_]in_list(value, ...)`. Conditions with subqueries must have been
+/// rewritten before, so that each remaining `IN` is followed by a list of values.
+fn rewrite_in_lists(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_list = match next_token(&tokens, i + 1) {
+            Some(j) => tokens[j] == Token::LParen,
+            None => false,
+        };
+        if is_word(&tokens[i], "IN") && is_list {
+            let (start, function) = match previous_token(&tokens, 0, i) {
+                Some(j) if is_word(&tokens[j], "NOT") => (j, NOT_IN_LIST_FUNCTION),
+                _ => (i, IN_LIST_FUNCTION),
+            };
+            tokens.splice(start..=i, vec![Token::Eq, sentinel(function)]);
+            i = start + 2;
+            continue;
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Rewrite each quoted identifier `"name"` to an identifier that keeps its case and
+/// may contain spaces, and normalize the case of the other identifiers. The names of
+/// functions keep their case, since functions are registered under a fixed name. The
+/// table name of `INSERT INTO name (column, ...)` is not a function.
+fn rewrite_identifiers(
+    mut tokens: Vec<Token>,
+    identifier_case: IdentifierCase,
+) -> Vec<Token> {
+    for i in 0..tokens.len() {
+        let is_function = next_token(&tokens, i + 1)
+            .map_or(false, |j| tokens[j] == Token::LParen)
+            && !previous_token(&tokens, 0, i)
+                .map_or(false, |j| is_word(&tokens[j], "INTO"));
+        tokens[i] = match tokens[i].clone() {
+            Token::DoubleQuotedString(name) => Token::Identifier(name),
+            Token::Identifier(name) if !is_function => {
+                Token::Identifier(match identifier_case {
+                    IdentifierCase::Preserve => name,
+                    IdentifierCase::Lowercase => name.to_lowercase(),
+                    IdentifierCase::Uppercase => name.to_uppercase(),
+                })
+            }
+            token => token,
+        };
+    }
+    tokens
+}
+
+/// Rewrite each qualified wildcard `t.*` to `__qualified_wildcard(t)`, which the query
+/// planner expands to the columns of relation `t`
+fn rewrite_qualified_wildcards(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] == Token::Mult {
+            let period =
+                previous_token(&tokens, 0, i).filter(|j| tokens[*j] == Token::Period);
+            let name = period.and_then(|j| previous_token(&tokens, 0, j));
+            if let Some(start) = name {
+                if let Token::Identifier(name) = tokens[start].clone() {
+                    tokens.splice(
+                        start..=i,
+                        vec![
+                            sentinel(QUALIFIED_WILDCARD_FUNCTION),
+                            Token::LParen,
+                            Token::Identifier(name),
+                            Token::RParen,
+                        ],
+                    );
+                    i = start + 4;
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Rewrite each `(VALUES (1, 'a'), (2, 'b')) AS t(id, name)` relation to
+/// `__values(__values_columns(id, name), __values_row(1, 'a'), __values_row(2, 'b'))
+/// AS t`, which the query planner plans as an inline table. A VALUES list that is not
+/// in parentheses, such as the rows of an INSERT statement, is rewritten to the query
+/// `SELECT * FROM __values(...)`, except for the values of the INSERT clauses of a
+/// MERGE statement, which are expressions over the source relation.
+fn rewrite_values(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let is_merge = next_token(&tokens, 0).map_or(false, |i| is_word(&tokens[i], "MERGE"));
+    let mut i = 0;
+    while i < tokens.len() {
+        let is_relation = tokens[i] == Token::LParen
+            && next_token(&tokens, i + 1)
+                .map_or(false, |j| is_word(&tokens[j], "VALUES"));
+        let (rows_start, rows_end, end) = if is_relation {
+            let end = match closing_paren(&tokens, i) {
+                Some(end) => end,
+                None => return parser_err!("Expected ')' after VALUES list"),
+            };
+            (next_token(&tokens, i + 1).unwrap() + 1, end, end)
+        } else if is_word(&tokens[i], "VALUES") && !is_merge {
+            // the rows are the parenthesized lists separated by commas
+            let mut row = next_token(&tokens, i + 1);
+            let end = loop {
+                let end = match row {
+                    Some(start) if tokens[start] == Token::LParen => {
+                        closing_paren(&tokens, start)
+                    }
+                    _ => None,
+                };
+                let end = match end {
+                    Some(end) => end,
+                    None => return parser_err!("Expected a parenthesized row of VALUES"),
+                };
+                match next_token(&tokens, end + 1) {
+                    Some(k) if tokens[k] == Token::Comma => {
+                        row = next_token(&tokens, k + 1)
+                    }
+                    _ => break end,
+                }
+            };
+            (i + 1, end + 1, end)
+        } else {
+            i += 1;
+            continue;
+        };
+
+        // the column names follow the alias, which is optionally preceded by AS
+        let alias = next_token(&tokens, end + 1)
+            .filter(|_| is_relation)
+            .and_then(|j| {
+                if is_word(&tokens[j], "AS") {
+                    next_token(&tokens, j + 1)
+                } else {
+                    Some(j)
+                }
+            });
+        let columns = alias
+            .filter(|j| match tokens[*j] {
+                Token::Identifier(_) | Token::DoubleQuotedString(_) => true,
+                _ => false,
+            })
+            .and_then(|j| next_token(&tokens, j + 1))
+            .filter(|k| tokens[*k] == Token::LParen);
+        let mut args = vec![];
+        if let Some(start) = columns {
+            let columns_end = match closing_paren(&tokens, start) {
+                Some(columns_end) => columns_end,
+                None => return parser_err!("Expected ')' after column names of VALUES"),
+            };
+            args.push(sentinel(VALUES_COLUMNS_FUNCTION));
+            args.extend(tokens.drain(start..=columns_end));
+        }
+
+        for row in split_list(&tokens[rows_start..rows_end]) {
+            let first = next_token(row, 0);
+            let last = previous_token(row, 0, row.len());
+            match (first, last) {
+                (Some(first), Some(last))
+                    if row[first] == Token::LParen && row[last] == Token::RParen =>
+                {
+                    if !args.is_empty() {
+                        args.push(Token::Comma);
+                    }
+                    args.push(sentinel(VALUES_ROW_FUNCTION));
+                    args.extend(row[first..=last].iter().cloned());
+                }
+                _ => return parser_err!("Expected a parenthesized row of VALUES"),
+            }
+        }
+
+        let mut rewritten = if is_relation {
+            vec![]
+        } else {
+            vec![
+                Token::Keyword("SELECT".to_string()),
+                Token::Mult,
+                Token::Keyword("FROM".to_string()),
+            ]
+        };
+        rewritten.push(sentinel(VALUES_FUNCTION));
+        rewritten.push(Token::LParen);
+        rewritten.extend(args);
+        rewritten.push(Token::RParen);
+        let len = rewritten.len();
+        tokens.splice(i..=end, rewritten);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// Find the position of the parenthesis that closes the one opened at position `start`
+fn closing_paren(tokens: &[Token], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for j in start..tokens.len() {
+        if tokens[j] == Token::LParen {
+            depth += 1;
+        } else if tokens[j] == Token::RParen {
+            depth -= 1;
+            if depth == 0 {
+                return Some(j);
+            }
+        }
+    }
+    None
+}
+
+/// Rewrite each `EXISTS (SELECT ...)` condition to `__exists(SELECT ...)`. A preceding
+/// `NOT` is kept and negates the condition.
+fn rewrite_exists_subqueries(mut tokens: Vec<Token>) -> Vec<Token> {
+    for i in 0..tokens.len() {
+        if is_word(&tokens[i], "EXISTS") && is_followed_by_subquery(&tokens, i) {
+            tokens[i] = sentinel(EXISTS_FUNCTION);
+        }
+    }
+    tokens
+}
+
+/// Rewrite each `SELECT DISTINCT ON (a, b) ...` clause to
+/// `SELECT __distinct_on(a, b), ...`
+fn rewrite_distinct_on(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let distinct =
+            next_token(&tokens, i + 1).filter(|j| is_word(&tokens[*j], "DISTINCT"));
+        let on = distinct
+            .and_then(|j| next_token(&tokens, j + 1))
+            .filter(|j| is_word(&tokens[*j], "ON"));
+        let paren = on
+            .and_then(|j| next_token(&tokens, j + 1))
+            .filter(|j| tokens[*j] == Token::LParen);
+        if let (true, Some(start), Some(paren)) =
+            (is_word(&tokens[i], "SELECT"), distinct, paren)
+        {
+            let end = match closing_paren(&tokens, paren) {
+                Some(end) => end,
+                None => return parser_err!("Expected ) after DISTINCT ON keys"),
+            };
+            tokens.insert(end + 1, Token::Comma);
+            tokens.splice(start..paren, vec![sentinel(DISTINCT_ON_FUNCTION)]);
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Replace each `||` operator and regular expression match operator outside of quoted
+/// strings and identifiers with words that the tokenizer accepts
+fn replace_operators(sql: &str) -> String {
+    let mut result = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            // a quote that is escaped by doubling it ends and starts a string
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '|') if chars.peek() == Some(&'|') => {
+                chars.next();
+                result.push(' ');
+                result.push_str(STRING_CONCAT_OPERATOR);
+                result.push(' ');
+                continue;
+            }
+            (None, '~') | (None, '!') if c == '~' || chars.peek() == Some(&'~') => {
+                if c == '!' {
+                    chars.next();
+                    result.push_str(" NOT");
+                }
+                let operator = if chars.peek() == Some(&'*') {
+                    chars.next();
+                    REGEX_IMATCH_OPERATOR
+                } else {
+                    REGEX_MATCH_OPERATOR
+                };
+                result.push(' ');
+                result.push_str(operator);
+                result.push(' ');
+                continue;
+            }
+            _ => {}
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Rewrite each string concatenation `a || b` to `a + __string_concat(b)`. Since `||`
+/// binds less tightly than arithmetic operators, the right operand extends up to the
+/// next comparison, concatenation or keyword that ends an expression, while the left
+/// operand is the left operand of the addition.
+fn rewrite_string_concat(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if !is_word(&tokens[i], STRING_CONCAT_OPERATOR) {
+            i += 1;
+            continue;
+        }
+        if previous_token(&tokens, 0, i).is_none() {
+            return parser_err!("Expected an expression before ||");
+        }
+        let start = match next_token(&tokens, i + 1) {
+            Some(start) => start,
+            None => return parser_err!("Expected an expression after ||"),
+        };
+
+        // find the end of the right operand, skipping nested parentheses and CASE
+        // expressions
+        let mut depth = 0;
+        let mut end = tokens.len();
+        for k in start..tokens.len() {
+            let token = &tokens[k];
+            if *token == Token::LParen || is_word(token, "CASE") {
+                depth += 1;
+            } else if depth > 0 {
+                if *token == Token::RParen || is_word(token, "END") {
+                    depth -= 1;
+                }
+            } else if STRING_CONCAT_TERMINATORS.contains(&token.to_string().as_str())
+                || is_word(token, STRING_CONCAT_OPERATOR)
+                || is_word(token, REGEX_MATCH_OPERATOR)
+                || is_word(token, REGEX_IMATCH_OPERATOR)
+                || ["LIKE", "ILIKE", "ESCAPE", "NOT", "IN"]
+                    .iter()
+                    .any(|word| is_word(token, word))
+                || *token == Token::RParen
+                || *token == Token::Comma
+                || *token == Token::SemiColon
+                || BETWEEN_TERMINATORS.iter().any(|word| is_word(token, word))
+            {
+                end = k;
+                break;
+            }
+        }
+        if end == start {
+            return parser_err!("Expected an expression after ||");
+        }
+
+        tokens.insert(end, Token::RParen);
+        tokens.splice(
+            start..start,
+            vec![sentinel(STRING_CONCAT_FUNCTION), Token::LParen],
+        );
+        tokens[i] = Token::Plus;
+        i = start + 2;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each subscript `list[index]` to `element_at(list, index)`. The list is a
+/// possibly qualified column, a function call or a parenthesized expression, and may
+/// itself be a subscript.
+fn rewrite_subscripts(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i] != Token::LBracket {
+            i += 1;
+            continue;
+        }
+        let mut start = match previous_token(&tokens, 0, i) {
+            Some(j) => j,
+            None => return parser_err!("Expected an expression before ["),
+        };
+        match tokens[start] {
+            Token::RParen => {
+                // the parenthesized expression or the arguments of a function call
+                let mut depth = 0;
+                loop {
+                    if tokens[start] == Token::RParen {
+                        depth += 1;
+                    } else if tokens[start] == Token::LParen {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    if start == 0 {
+                        return parser_err!("Expected ( before [");
+                    }
+                    start -= 1;
+                }
+                if let Some(j) = previous_token(&tokens, 0, start) {
+                    if let Token::Identifier(_) = tokens[j] {
+                        start = j;
+                    }
+                }
+            }
+            Token::Identifier(_) => {
+                // the qualifiers of a column
+                while let Some(period) = previous_token(&tokens, 0, start)
+                    .filter(|j| tokens[*j] == Token::Period)
+                {
+                    match previous_token(&tokens, 0, period).map(|j| (j, &tokens[j])) {
+                        Some((j, Token::Identifier(_))) => start = j,
+                        _ => break,
+                    }
+                }
+            }
+            _ => return parser_err!("Expected a column or an expression before ["),
+        }
+
+        let mut depth = 0;
+        let end = (i..tokens.len()).find(|j| {
+            if tokens[*j] == Token::LBracket {
+                depth += 1;
+            } else if tokens[*j] == Token::RBracket {
+                depth -= 1;
+            }
+            depth == 0
+        });
+        let end = match end {
+            Some(end) => end,
+            None => return parser_err!("Expected ] after subscript"),
+        };
+        tokens[end] = Token::RParen;
+        tokens[i] = Token::Comma;
+        tokens.splice(
+            start..start,
+            vec![Token::Identifier("element_at".to_string()), Token::LParen],
+        );
+        i += 2;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each `expr [NOT] BETWEEN low AND high` condition to
+/// `expr = __[not_]between(low, high)`
+fn rewrite_between(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        if !is_word(&tokens[i], "BETWEEN") {
+            i += 1;
+            continue;
+        }
+        let (start, function) = match previous_token(&tokens, 0, i) {
+            Some(j) if is_word(&tokens[j], "NOT") => (j, NOT_BETWEEN_FUNCTION),
+            _ => (i, BETWEEN_FUNCTION),
+        };
+
+        // find the AND between the bounds and the end of the upper bound, skipping
+        // nested parentheses and CASE expressions
+        let mut depth = 0;
+        let mut separator = None;
+        let mut end = tokens.len();
+        for k in i + 1..tokens.len() {
+            let token = &tokens[k];
+            if *token == Token::LParen || is_word(token, "CASE") {
+                depth += 1;
+            } else if depth > 0 {
+                if *token == Token::RParen || is_word(token, "END") {
+                    depth -= 1;
+                }
+            } else if separator.is_none() && is_word(token, "AND") {
+                separator = Some(k);
+            } else if *token == Token::RParen
+                || *token == Token::Comma
+                || *token == Token::SemiColon
+                || BETWEEN_TERMINATORS.iter().any(|word| is_word(token, word))
+            {
+                end = k;
+                break;
+            }
+        }
+        let separator = match separator {
+            Some(separator) => separator,
+            None => return parser_err!("Expected AND between the bounds of BETWEEN"),
+        };
+
+        tokens.insert(end, Token::RParen);
+        tokens[separator] = Token::Comma;
+        tokens.splice(
+            start..=i,
+            vec![Token::Eq, sentinel(function), Token::LParen],
+        );
+        i = start + 3;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each `expr [NOT] ILIKE pattern [ESCAPE escape]` condition to
+/// `expr [NOT] LIKE __ilike(pattern[, escape])` and each
+/// `expr [NOT] LIKE pattern ESCAPE escape` condition to
+/// `expr [NOT] LIKE __like_escape(pattern, escape)`
+fn rewrite_like(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let case_insensitive = is_word(&tokens[i], "ILIKE");
+        if !case_insensitive && !is_word(&tokens[i], "LIKE") {
+            i += 1;
+            continue;
+        }
+        let start = match next_token(&tokens, i + 1) {
+            Some(start) => start,
+            None => return parser_err!("Expected a pattern after LIKE"),
+        };
+
+        // find the end of the pattern and the ESCAPE clause, skipping nested
+        // parentheses and CASE expressions
+        let mut depth = 0;
+        let mut escape = None;
+        let mut end = tokens.len();
+        for k in start..tokens.len() {
+            let token = &tokens[k];
+            if *token == Token::LParen || is_word(token, "CASE") {
+                depth += 1;
+            } else if depth > 0 {
+                if *token == Token::RParen || is_word(token, "END") {
+                    depth -= 1;
+                }
+            } else if is_word(token, "ESCAPE") {
+                escape = Some(k);
+                end = k;
+                break;
+            } else if STRING_CONCAT_TERMINATORS.contains(&token.to_string().as_str())
+                || *token == Token::RParen
+                || *token == Token::Comma
+                || *token == Token::SemiColon
+                || BETWEEN_TERMINATORS.iter().any(|word| is_word(token, word))
+            {
+                end = k;
+                break;
+            }
+        }
+        if previous_token(&tokens, start, end).is_none() {
+            return parser_err!("Expected a pattern after LIKE");
+        }
+
+        let function = match escape {
+            Some(escape) => {
+                let value = match next_token(&tokens, escape + 1) {
+                    Some(value) => value,
+                    None => {
+                        return parser_err!("Expected an escape character after ESCAPE")
+                    }
+                };
+                tokens.insert(value + 1, Token::RParen);
+                tokens[escape] = Token::Comma;
+                if case_insensitive {
+                    ILIKE_FUNCTION
+                } else {
+                    LIKE_ESCAPE_FUNCTION
+                }
+            }
+            None if case_insensitive => {
+                tokens.insert(end, Token::RParen);
+                ILIKE_FUNCTION
+            }
+            None => {
+                i = end;
+                continue;
+            }
+        };
+        tokens.splice(start..start, vec![sentinel(function), Token::LParen]);
+        tokens[i] = Token::Keyword("LIKE".to_string());
+        i = start + 2;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each regular expression match `expr [!]~[*] pattern` to
+/// `expr [NOT] LIKE __regex_[i]match(pattern)`. The pattern extends
+/// up to the next comparison or keyword that ends an expression.
+fn rewrite_regex_match(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let function = if is_word(&tokens[i], REGEX_MATCH_OPERATOR) {
+            REGEX_MATCH_FUNCTION
+        } else if is_word(&tokens[i], REGEX_IMATCH_OPERATOR) {
+            REGEX_IMATCH_FUNCTION
+        } else {
+            i += 1;
+            continue;
+        };
+        if previous_token(&tokens, 0, i).is_none() {
+            return parser_err!("Expected an expression before ~");
+        }
+        let start = match next_token(&tokens, i + 1) {
+            Some(start) => start,
+            None => return parser_err!("Expected a pattern after ~"),
+        };
+
+        // find the end of the pattern, skipping nested parentheses and CASE expressions
+        let mut depth = 0;
+        let mut end = tokens.len();
+        for k in start..tokens.len() {
+            let token = &tokens[k];
+            if *token == Token::LParen || is_word(token, "CASE") {
+                depth += 1;
+            } else if depth > 0 {
+                if *token == Token::RParen || is_word(token, "END") {
+                    depth -= 1;
+                }
+            } else if STRING_CONCAT_TERMINATORS.contains(&token.to_string().as_str())
+                || is_word(token, REGEX_MATCH_OPERATOR)
+                || is_word(token, REGEX_IMATCH_OPERATOR)
+                || ["LIKE", "NOT", "IN"]
+                    .iter()
+                    .any(|word| is_word(token, word))
+                || *token == Token::RParen
+                || *token == Token::Comma
+                || *token == Token::SemiColon
+                || BETWEEN_TERMINATORS.iter().any(|word| is_word(token, word))
+            {
+                end = k;
+                break;
+            }
+        }
+        if previous_token(&tokens, start, end).is_none() {
+            return parser_err!("Expected a pattern after ~");
+        }
+
+        tokens.insert(end, Token::RParen);
+        tokens.splice(start..start, vec![sentinel(function), Token::LParen]);
+        tokens[i] = Token::Keyword("LIKE".to_string());
+        i = start + 2;
+    }
+    Ok(tokens)
+}
+
+/// Rewrite each simple `CASE operand WHEN value THEN ... END` expression to the searched
+/// form `CASE WHEN (operand) = (value) THEN ... END`, since the ANSI SQL parser only
+/// supports searched CASE expressions. The operand is repeated in each condition.
+fn rewrite_simple_case(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut i = 0;
+    while i < tokens.len() {
+        let operand_start = match next_token(&tokens, i + 1) {
+            Some(j) if is_word(&tokens[i], "CASE") && !is_word(&tokens[j], "WHEN") => j,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let mut operand = vec![];
+        let mut rewritten = vec![];
+        // nesting of parentheses and CASE expressions within this expression
+        let mut depth = 0;
+        let mut end = None;
+        for k in operand_start..tokens.len() {
+            let token = tokens[k].clone();
+            if depth == 0 {
+                if is_word(&token, "END") {
+                    end = Some(k);
+                    break;
+                } else if is_word(&token, "WHEN") {
+                    rewritten.push(token);
+                    rewritten.push(Token::LParen);
+                    rewritten.extend(operand.iter().cloned());
+                    rewritten.extend(vec![Token::RParen, Token::Eq, Token::LParen]);
+                    continue;
+                } else if is_word(&token, "THEN") {
+                    rewritten.push(Token::RParen);
+                    rewritten.push(token);
+                    continue;
+                }
+            }
+            if token == Token::LParen || is_word(&token, "CASE") {
+                depth += 1;
+            } else if token == Token::RParen || is_word(&token, "END") {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            // the operand is followed by the first WHEN
+            if rewritten.is_empty() {
+                operand.push(token);
+            } else {
+                rewritten.push(token);
+            }
+        }
+
+        match end {
+            Some(end) => {
+                tokens.splice(operand_start..end, rewritten);
+            }
+            None => return parser_err!("Expected END at the end of a CASE expression"),
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// Whether the token at position `i` is followed by a parenthesized `SELECT`
+fn is_followed_by_subquery(tokens: &[Token], i: usize) -> bool {
+    match next_token(tokens, i + 1) {
+        Some(j) if tokens[j] == Token::LParen => {
+            next_token(tokens, j + 1).map_or(false, |k| is_word(&tokens[k], "SELECT"))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a token is the given word, regardless of whether the word is a keyword of
+/// the SQL dialect
+fn is_word(token: &Token, expected: &str) -> bool {
+    match token {
+        Token::Keyword(word) | Token::Identifier(word) => {
+            word.eq_ignore_ascii_case(expected)
+        }
+        _ => false,
+    }
+}
+
+/// The token of the name of a sentinel function that a construct is rewritten to a call
+/// of, as described in the module documentation
+fn sentinel(function: &str) -> Token {
+    Token::Identifier(function.to_string())
+}
+
+/// Find the position of the first token at or after `from` that is not whitespace
+fn next_token(tokens: &[Token], from: usize) -> Option<usize> {
+    (from..tokens.len()).find(|i| !tokens[*i].to_string().trim().is_empty())
+}
+
+/// Find the position of the last token between `start` and `end` (exclusive) that is
+/// not whitespace
+fn previous_token(tokens: &[Token], start: usize, end: usize) -> Option<usize> {
+    (start..end)
+        .rev()
+        .find(|i| !tokens[*i].to_string().trim().is_empty())
+}
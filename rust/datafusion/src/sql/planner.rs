@@ -17,16 +17,36 @@
 
 //! SQL Query Planner (produces logical plan from SQL AST)
 
+use std::cell::{Cell, RefCell};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::datetime_expressions::parse_timezone;
+use crate::execution::physical_plan::expressions::{
+    histogram_buckets, histogram_data_type, reservoir_sample_size,
+};
 use crate::logicalplan::{
-    Expr, FunctionMeta, LogicalPlan, LogicalPlanBuilder, Operator, ScalarValue,
+    Expr, FunctionMeta, JoinType, LogicalPlan, LogicalPlanBuilder, Operator, ScalarValue,
+    Subquery, WindowFrame, WindowFrameBound, WindowFrameUnits,
 };
 
 use arrow::datatypes::*;
 
 use crate::logicalplan::Expr::Alias;
+use crate::optimizer::utils::{
+    conjunction, element_type, exprlist_to_fields, split_conjunction,
+};
+use crate::sql::parser::{
+    MergeClause, SetExpr, SetOperator, BETWEEN_FUNCTION, DESC_FUNCTION,
+    DISTINCT_ON_FUNCTION, EXISTS_FUNCTION, ILIKE_FUNCTION, IN_LIST_FUNCTION,
+    IN_SUBQUERY_FUNCTION, LIKE_ESCAPE_FUNCTION, NOT_BETWEEN_FUNCTION,
+    NOT_IN_LIST_FUNCTION, NOT_IN_SUBQUERY_FUNCTION, NULLS_FIRST_FUNCTION,
+    NULLS_LAST_FUNCTION, ORDER_BY_FUNCTION, PARTITION_BY_FUNCTION,
+    QUALIFIED_WILDCARD_FUNCTION, REGEX_IMATCH_FUNCTION, REGEX_MATCH_FUNCTION,
+    STRING_CONCAT_FUNCTION, VALUES_COLUMNS_FUNCTION, VALUES_FUNCTION,
+    VALUES_ROW_FUNCTION, WINDOW_FRAME_FUNCTION, WINDOW_FUNCTION,
+};
 use sqlparser::sqlast::*;
 
 /// The SchemaProvider trait allows the query planner to obtain meta-data about tables and
@@ -38,23 +58,471 @@ pub trait SchemaProvider {
     fn get_function_meta(&self, name: &str) -> Option<Arc<FunctionMeta>>;
 }
 
+/// The placement of nulls by ORDER BY when neither `NULLS FIRST` nor `NULLS LAST` is
+/// specified
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NullOrdering {
+    /// Nulls sort as if they were larger than any other value, so that they come last
+    /// in ascending order and first in descending order, as in PostgreSQL
+    NullsLargest,
+    /// Nulls sort as if they were smaller than any other value
+    NullsSmallest,
+}
+
+impl NullOrdering {
+    /// Whether nulls come before non-null values when sorting in the given direction
+    pub fn nulls_first(&self, asc: bool) -> bool {
+        match self {
+            NullOrdering::NullsLargest => !asc,
+            NullOrdering::NullsSmallest => asc,
+        }
+    }
+}
+
+/// A named relation in the FROM clause of the query being planned, along with the
+/// positions of its columns in the combined input schema
+#[derive(Debug, Clone)]
+struct RelationRef {
+    name: String,
+    columns: Vec<usize>,
+}
+
+/// The columns of a query that contains a subquery, which the subquery can reference
+#[derive(Debug, Clone)]
+struct OuterScope {
+    /// The schema that the expression containing the subquery is evaluated against
+    schema: Schema,
+    /// The relations of the query in scope for resolving qualified column references
+    relations: Vec<RelationRef>,
+}
+
 /// SQL query planner
 pub struct SqlToRel<S: SchemaProvider> {
     schema_provider: S,
+    /// The relations in scope for resolving qualified column references
+    relations: RefCell<Vec<RelationRef>>,
+    /// Whether the relations went out of scope because the projection of the query
+    /// replaced their columns, after which a qualified name refers to the output column
+    /// with the same name
+    projected: Cell<bool>,
+    /// The enclosing queries of the subquery being planned, innermost last
+    outer_scopes: RefCell<Vec<OuterScope>>,
+    /// The placement of nulls when an ORDER BY expression does not specify it
+    null_ordering: NullOrdering,
+    /// The time that `now()` returns, in nanoseconds since the epoch
+    statement_time: i64,
 }
 
 impl<S: SchemaProvider> SqlToRel<S> {
     /// Create a new query planner
     pub fn new(schema_provider: S) -> Self {
-        SqlToRel { schema_provider }
+        SqlToRel {
+            schema_provider,
+            relations: RefCell::new(vec![]),
+            projected: Cell::new(false),
+            outer_scopes: RefCell::new(vec![]),
+            null_ordering: NullOrdering::NullsLargest,
+            statement_time: current_time(),
+        }
+    }
+
+    /// Set the time that `now()` returns, in nanoseconds since the epoch. By default it
+    /// is the time the planner was created, so that every call in a statement agrees.
+    pub fn with_statement_time(mut self, statement_time: i64) -> Self {
+        self.statement_time = statement_time;
+        self
+    }
+
+    /// Set the placement of nulls when an ORDER BY expression does not specify it
+    pub fn with_null_ordering(mut self, null_ordering: NullOrdering) -> Self {
+        self.null_ordering = null_ordering;
+        self
     }
 
     /// Generate a logic plan from a SQL AST node
     pub fn sql_to_rel(&self, sql: &ASTNode) -> Result<LogicalPlan> {
+        match *sql {
+            ASTNode::SQLSelect { .. } => {
+                // relations of an enclosing query are not visible to this query
+                let outer_relations = self.relations.replace(vec![]);
+                let outer_projected = self.projected.replace(false);
+                let plan = self.select_to_rel(sql);
+                self.relations.replace(outer_relations);
+                self.projected.set(outer_projected);
+                plan
+            }
+
+            ASTNode::SQLFunction { ref id, ref args } if id == VALUES_FUNCTION => {
+                let outer_relations = self.relations.replace(vec![]);
+                let outer_projected = self.projected.replace(false);
+                let plan = self.values_to_rel(args);
+                self.relations.replace(outer_relations);
+                self.projected.set(outer_projected);
+                plan
+            }
+
+            ASTNode::SQLIdentifier(ref id) => self.table_scan(id),
+
+            // a table of a schema, such as `information_schema.tables`, is looked up by
+            // its qualified name
+            ASTNode::SQLCompoundIdentifier(ref ids) => self.table_scan(&ids.join(".")),
+
+            _ => Err(ExecutionError::ExecutionError(format!(
+                "sql_to_rel does not support this relation: {:?}",
+                sql
+            ))),
+        }
+    }
+
+    /// Generate a logical plan that scans a table
+    fn table_scan(&self, name: &str) -> Result<LogicalPlan> {
+        match self.schema_provider.get_table_meta(name) {
+            Some(schema) => {
+                Ok(
+                    LogicalPlanBuilder::scan("default", name, schema.as_ref(), None)?
+                        .build()?,
+                )
+            }
+            None => Err(ExecutionError::General(format!(
+                "no schema found for table {}",
+                name
+            ))),
+        }
+    }
+
+    /// Generate a logic plan from the rows of a VALUES list, which is the union of a
+    /// projection of each row. The columns are named by the column names of the alias
+    /// of the list, or `column1`, `column2` and so on, and their types are coerced as
+    /// for a union.
+    fn values_to_rel(&self, args: &[ASTNode]) -> Result<LogicalPlan> {
+        let (names, rows) = match args.split_first() {
+            Some((ASTNode::SQLFunction { id, args: names }, rows))
+                if id == VALUES_COLUMNS_FUNCTION =>
+            {
+                let names = names
+                    .iter()
+                    .map(|name| match name {
+                        ASTNode::SQLIdentifier(name) => Ok(name.clone()),
+                        _ => Err(ExecutionError::General(format!(
+                            "Invalid column name of VALUES: {:?}",
+                            name
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (Some(names), rows)
+            }
+            _ => (None, args),
+        };
+
+        let empty_schema = Schema::empty();
+        let mut plan: Option<LogicalPlanBuilder> = None;
+        for row in rows {
+            let values = match row {
+                ASTNode::SQLFunction { id, args } if id == VALUES_ROW_FUNCTION => args,
+                _ => {
+                    return Err(ExecutionError::InternalError(format!(
+                        "Invalid row of VALUES: {:?}",
+                        row
+                    )))
+                }
+            };
+            let names = match names {
+                Some(ref names) if names.len() != values.len() => {
+                    return Err(ExecutionError::General(format!(
+                        "VALUES has {} columns but {} column names are specified",
+                        values.len(),
+                        names.len()
+                    )))
+                }
+                Some(ref names) => names.clone(),
+                None => (1..=values.len()).map(|i| format!("column{}", i)).collect(),
+            };
+            let expr = values
+                .iter()
+                .zip(names)
+                .map(|(value, name)| {
+                    Ok(Alias(
+                        Arc::new(self.sql_to_rex(value, &empty_schema)?),
+                        name,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let row = LogicalPlanBuilder::empty().project(expr)?.build()?;
+            plan = Some(match plan {
+                Some(plan) => plan.union(&row)?,
+                None => LogicalPlanBuilder::from(&row),
+            });
+        }
+        match plan {
+            Some(plan) => plan.build(),
+            None => Err(ExecutionError::General(
+                "VALUES must have at least one row".to_string(),
+            )),
+        }
+    }
+
+    /// Generate a logic plan from queries combined by set operators, with the ORDER BY
+    /// and LIMIT clauses that apply to the combined rows
+    pub fn set_operation_to_rel(
+        &self,
+        body: &SetExpr,
+        order_by: &Option<Vec<SQLOrderByExpr>>,
+        limit: &Option<Box<ASTNode>>,
+    ) -> Result<LogicalPlan> {
+        let plan = self.set_expr_to_rel(body)?;
+        let plan = self.order_by(&plan, order_by, None)?;
+        self.limit(&plan, limit)
+    }
+
+    /// Generate a logical plan that produces the rows of a table after a MERGE statement.
+    ///
+    /// The target and source relations are combined by a full outer join, in which the
+    /// matched rows and the rows of either side without a match are told apart by a
+    /// column added to each side. The first clause whose condition is met by a row
+    /// decides whether the row is kept and the values of its columns. Matched rows
+    /// without such a clause and target rows without a match are kept as they are, and
+    /// a target row that matches several source rows is kept once for each of them.
+    pub fn merge_to_rel(
+        &self,
+        table_name: &str,
+        alias: &Option<String>,
+        source: &ASTNode,
+        on: &ASTNode,
+        clauses: &[MergeClause],
+    ) -> Result<LogicalPlan> {
+        let target = ASTNode::SQLIdentifier(table_name.to_string());
+        let target = match alias {
+            Some(alias) => ASTNode::SQLAliasedExpr(Box::new(target), alias.clone()),
+            None => target,
+        };
+        let target = self.relation(&target, 0)?;
+        let target_schema = target.schema().clone();
+        let target_len = target_schema.fields().len();
+        // the added column of the target shifts the columns of the source by one
+        let source = self.relation(source, target_len + 1)?;
+        let source_len = source.schema().fields().len();
+
+        let with_marker = |plan: &LogicalPlan, name: &str| -> Result<LogicalPlan> {
+            let mut expr: Vec<Expr> = (0..plan.schema().fields().len())
+                .map(Expr::Column)
+                .collect();
+            expr.push(Alias(
+                Arc::new(Expr::Literal(ScalarValue::Boolean(true))),
+                name.to_string(),
+            ));
+            LogicalPlanBuilder::from(plan).project(expr)?.build()
+        };
+        let plan = self.join_on(
+            &with_marker(&target, "__merge_target")?,
+            &with_marker(&source, "__merge_source")?,
+            JoinType::Full,
+            on,
+        )?;
+        let schema = plan.schema().clone();
+        // the added columns are only null for the rows of the other side without a match
+        let target_marker = Arc::new(Expr::Column(target_len));
+        let source_marker = Arc::new(Expr::Column(target_len + 1 + source_len));
+        let matched = Expr::BinaryExpr {
+            left: Arc::new(Expr::IsNotNull(target_marker.clone())),
+            op: Operator::And,
+            right: Arc::new(Expr::IsNotNull(source_marker)),
+        };
+        let not_matched = Expr::IsNull(target_marker.clone());
+
+        let column_index = |name: &str| {
+            target_schema.index_of(name).map_err(|_| {
+                ExecutionError::General(format!(
+                    "Table '{}' has no column named '{}'",
+                    table_name, name
+                ))
+            })
+        };
+
+        // the conditions of the clauses along with whether they keep the row and the
+        // new values of each column
+        let mut keep = vec![];
+        let mut columns: Vec<Vec<(Expr, Expr)>> = vec![vec![]; target_len];
+        for clause in clauses {
+            let (side, predicate) = match clause {
+                MergeClause::Update { predicate, .. }
+                | MergeClause::Delete { predicate } => (&matched, predicate),
+                MergeClause::Insert { predicate, .. } => (&not_matched, predicate),
+            };
+            let condition = match predicate {
+                Some(predicate) => {
+                    conjunction(vec![side.clone(), self.sql_to_rex(predicate, &schema)?])
+                        .unwrap()
+                }
+                None => side.clone(),
+            };
+            // the columns of the target are null for source rows without a match, which
+            // are the values of the columns that are not inserted into
+            let mut row: Vec<Expr> = (0..target_len).map(Expr::Column).collect();
+            match clause {
+                MergeClause::Update { assignments, .. } => {
+                    for (name, value) in assignments {
+                        row[column_index(name)?] = self.sql_to_rex(value, &schema)?;
+                    }
+                }
+                MergeClause::Delete { .. } => {}
+                MergeClause::Insert {
+                    columns: names,
+                    values,
+                    ..
+                } => {
+                    let names: Vec<&str> = if names.is_empty() {
+                        target_schema
+                            .fields()
+                            .iter()
+                            .map(|f| f.name().as_str())
+                            .collect()
+                    } else {
+                        names.iter().map(|name| name.as_str()).collect()
+                    };
+                    if names.len() != values.len() {
+                        return Err(ExecutionError::General(format!(
+                            "INSERT into {} columns but {} values are given",
+                            names.len(),
+                            values.len()
+                        )));
+                    }
+                    for (name, value) in names.iter().zip(values) {
+                        row[column_index(name)?] = self.sql_to_rex(value, &schema)?;
+                    }
+                }
+            }
+            let is_delete = match clause {
+                MergeClause::Delete { .. } => true,
+                _ => false,
+            };
+            keep.push((
+                condition.clone(),
+                Expr::Literal(ScalarValue::Boolean(!is_delete)),
+            ));
+            for (i, value) in row.into_iter().enumerate() {
+                let value = cast_to_field(value, target_schema.field(i), &schema)?;
+                columns[i].push((condition.clone(), value));
+            }
+        }
+
+        // source rows without a match are only kept if a clause inserts them
+        let keep = Expr::Case {
+            when_then_expr: keep,
+            else_expr: Some(Arc::new(Expr::IsNotNull(target_marker))),
+        };
+        let expr = columns
+            .into_iter()
+            .enumerate()
+            .map(|(i, when_then_expr)| {
+                let expr = Expr::Case {
+                    when_then_expr,
+                    else_expr: Some(Arc::new(Expr::Column(i))),
+                };
+                Alias(Arc::new(expr), target_schema.field(i).name().clone())
+            })
+            .collect();
+        LogicalPlanBuilder::from(&plan)
+            .filter(keep)?
+            .project(expr)?
+            .build()
+    }
+
+    /// Generate a logical plan that produces the rows of a table after an UPDATE
+    /// statement, in which the rows that meet the condition have the new values
+    pub fn update_to_rel(
+        &self,
+        table_name: &str,
+        assignments: &[(String, ASTNode)],
+        selection: &Option<ASTNode>,
+    ) -> Result<LogicalPlan> {
+        let plan = self.relation(&ASTNode::SQLIdentifier(table_name.to_string()), 0)?;
+        let schema = plan.schema().clone();
+        let mut expr: Vec<Expr> = (0..schema.fields().len()).map(Expr::Column).collect();
+        for (name, value) in assignments {
+            let i = schema.index_of(name).map_err(|_| {
+                ExecutionError::General(format!(
+                    "Table '{}' has no column named '{}'",
+                    table_name, name
+                ))
+            })?;
+            let value = self.sql_to_rex(value, &schema)?;
+            let value = cast_to_field(value, schema.field(i), &schema)?;
+            expr[i] = match selection {
+                Some(selection) => Expr::Case {
+                    when_then_expr: vec![(self.sql_to_rex(selection, &schema)?, value)],
+                    else_expr: Some(Arc::new(Expr::Column(i))),
+                },
+                None => value,
+            };
+        }
+        let expr = expr
+            .into_iter()
+            .zip(schema.fields())
+            .map(|(e, field)| Alias(Arc::new(e), field.name().clone()))
+            .collect();
+        self.project(&plan, expr)
+    }
+
+    /// Generate a logical plan that produces the rows of a table after a DELETE
+    /// statement, which are the rows that do not meet the condition
+    pub fn delete_to_rel(
+        &self,
+        table_name: &str,
+        selection: &Option<ASTNode>,
+    ) -> Result<LogicalPlan> {
+        let plan = self.relation(&ASTNode::SQLIdentifier(table_name.to_string()), 0)?;
+        let selection = match selection {
+            Some(selection) => self.sql_to_rex(selection, &plan.schema())?,
+            None => Expr::Literal(ScalarValue::Boolean(true)),
+        };
+        // the rows for which the condition is null are kept
+        let keep = Expr::Case {
+            when_then_expr: vec![(selection, Expr::Literal(ScalarValue::Boolean(false)))],
+            else_expr: Some(Arc::new(Expr::Literal(ScalarValue::Boolean(true)))),
+        };
+        LogicalPlanBuilder::from(&plan).filter(keep)?.build()
+    }
+
+    /// Generate a logic plan from a query or a set operation. Set operations that
+    /// remove duplicate rows are rewritten into aggregates and joins.
+    fn set_expr_to_rel(&self, body: &SetExpr) -> Result<LogicalPlan> {
+        match *body {
+            SetExpr::Select(ref select) => self.sql_to_rel(select),
+            SetExpr::SetOperation {
+                op,
+                all,
+                ref left,
+                ref right,
+            } => {
+                let left = LogicalPlanBuilder::from(&self.set_expr_to_rel(left)?);
+                let right = self.set_expr_to_rel(right)?;
+                match (op, all) {
+                    (SetOperator::Union, true) => left.union(&right)?.build(),
+                    (SetOperator::Union, false) => {
+                        left.union(&right)?.distinct()?.build()
+                    }
+                    (SetOperator::Intersect, false) => left.intersect(&right)?.build(),
+                    (SetOperator::Except, false) => left.except(&right)?.build(),
+                    (SetOperator::Intersect, true) => {
+                        Err(ExecutionError::NotImplemented(
+                            "INTERSECT ALL is not supported".to_string(),
+                        ))
+                    }
+                    (SetOperator::Except, true) => Err(ExecutionError::NotImplemented(
+                        "EXCEPT ALL is not supported".to_string(),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Generate a logic plan from a SELECT statement
+    fn select_to_rel(&self, sql: &ASTNode) -> Result<LogicalPlan> {
         match *sql {
             ASTNode::SQLSelect {
                 ref projection,
                 ref relation,
+                ref joins,
                 ref selection,
                 ref order_by,
                 ref limit,
@@ -62,66 +530,435 @@ impl<S: SchemaProvider> SqlToRel<S> {
                 ref having,
                 ..
             } => {
-                if having.is_some() {
-                    return Err(ExecutionError::NotImplemented(
-                        "HAVING is not implemented yet".to_string(),
-                    ));
-                }
-
                 // parse the input relation so we have access to the row type
                 let plan = match *relation {
-                    Some(ref r) => self.sql_to_rel(r)?,
+                    Some(ref r) => {
+                        let plan = self.relation(r, 0)?;
+                        joins
+                            .iter()
+                            .try_fold(plan, |plan, join| self.join(&plan, join))?
+                    }
                     None => LogicalPlanBuilder::empty().build()?,
                 };
 
                 // selection first
                 let plan = self.filter(&plan, selection)?;
 
-                let projection_expr: Vec<Expr> = projection
-                    .iter()
-                    .map(|e| self.sql_to_rex(&e, &plan.schema()))
-                    .collect::<Result<Vec<Expr>>>()?;
+                // the SQL parser rewrites DISTINCT ON to a leading item of the SELECT list
+                let (distinct_on, projection) = match projection.split_first() {
+                    Some((ASTNode::SQLFunction { id, args }, projection))
+                        if id == DISTINCT_ON_FUNCTION =>
+                    {
+                        (Some(args), projection)
+                    }
+                    _ => (None, projection.as_slice()),
+                };
+
+                let mut projection_expr: Vec<Expr> = vec![];
+                for e in projection {
+                    match self.qualified_wildcard_columns(e)? {
+                        Some(columns) => projection_expr.extend(columns),
+                        None => {
+                            projection_expr.push(self.sql_to_rex(&e, &plan.schema())?)
+                        }
+                    }
+                }
 
                 let aggr_expr: Vec<Expr> = projection_expr
                     .iter()
                     .filter(|e| is_aggregate_expr(e))
-                    .map(|e| e.clone())
+                    .map(|e| unalias(e).clone())
                     .collect();
 
                 // apply projection or aggregate
-                let plan = if group_by.is_some() || aggr_expr.len() > 0 {
-                    self.aggregate(&plan, projection_expr, group_by, aggr_expr)?
+                let is_aggregate =
+                    group_by.is_some() || aggr_expr.len() > 0 || having.is_some();
+                if is_aggregate && projection_expr.iter().any(is_window_expr) {
+                    return Err(ExecutionError::General(
+                        "Window functions are not supported in aggregate queries"
+                            .to_owned(),
+                    ));
+                }
+                let (plan, projection_expr) = self.window(&plan, projection_expr)?;
+                let projected = if is_aggregate {
+                    self.aggregate(&plan, projection_expr, group_by, aggr_expr, having)?
                 } else {
                     self.project(&plan, projection_expr)?
                 };
 
-                // apply ORDER BY
-                let plan = self.order_by(&plan, order_by)?;
+                // the projection replaces the columns of the input relations, so only
+                // unqualified names can be referenced from here on
+                let relations = self.relations.replace(vec![]);
+                self.projected.set(true);
+
+                // apply ORDER BY, which can also sort by expressions over the input of a
+                // projection
+                let input = if is_aggregate {
+                    None
+                } else {
+                    Some((&plan, relations))
+                };
+                let order_by = match distinct_on {
+                    Some(keys) => Some(self.distinct_on_order_by(keys, order_by)?),
+                    None => order_by.clone(),
+                };
+                let plan = self.order_by(&projected, &order_by, input)?;
+
+                // apply DISTINCT ON, which keeps the first row of each group of rows
+                // with equal keys in the order of the ORDER BY clause
+                let plan = match distinct_on {
+                    Some(keys) => self.distinct_on(&plan, keys.len())?,
+                    None => plan,
+                };
 
                 // apply LIMIT
                 self.limit(&plan, limit)
             }
+            _ => Err(ExecutionError::InternalError(format!(
+                "select_to_rel called with non-select: {:?}",
+                sql
+            ))),
+        }
+    }
+
+    /// The ORDER BY clause of a SELECT statement with `DISTINCT ON (keys)`, whose leading
+    /// expressions must be the keys. Without an ORDER BY clause, the rows are sorted by
+    /// the keys and the row kept of each group is not defined.
+    fn distinct_on_order_by(
+        &self,
+        keys: &[ASTNode],
+        order_by: &Option<Vec<SQLOrderByExpr>>,
+    ) -> Result<Vec<SQLOrderByExpr>> {
+        let order_by = match order_by {
+            Some(order_by) => order_by,
+            None => {
+                return Ok(keys
+                    .iter()
+                    .map(|key| SQLOrderByExpr {
+                        expr: Box::new(key.clone()),
+                        asc: true,
+                    })
+                    .collect())
+            }
+        };
+        // the keys are compared without the placement of nulls
+        let leading_expr = order_by.iter().take(keys.len()).map(|e| match &*e.expr {
+            ASTNode::SQLFunction { id, args }
+                if (id == NULLS_FIRST_FUNCTION || id == NULLS_LAST_FUNCTION)
+                    && args.len() == 1 =>
+            {
+                &args[0]
+            }
+            expr => expr,
+        });
+        if !leading_expr.eq(keys.iter()) {
+            return Err(ExecutionError::General(
+                "SELECT DISTINCT ON expressions must match the leading ORDER BY \
+                 expressions"
+                    .to_string(),
+            ));
+        }
+        Ok(order_by.clone())
+    }
 
-            ASTNode::SQLIdentifier(ref id) => {
-                match self.schema_provider.get_table_meta(id.as_ref()) {
-                    Some(schema) => Ok(LogicalPlanBuilder::scan(
-                        "default",
-                        id,
-                        schema.as_ref(),
-                        None,
-                    )?
-                    .build()?),
+    /// Plan `DISTINCT ON (keys)` over the plan of a SELECT statement, which must be
+    /// sorted by the keys first. The keys are the leading expressions of the sort, so
+    /// that they are resolved like ORDER BY expressions and can refer to columns that
+    /// are not in the SELECT list.
+    fn distinct_on(&self, plan: &LogicalPlan, key_count: usize) -> Result<LogicalPlan> {
+        let deduplicate = |sort: &LogicalPlan| -> Result<LogicalPlan> {
+            let keys = match sort {
+                LogicalPlan::Sort { expr, .. } => expr
+                    .iter()
+                    .take(key_count)
+                    .map(|e| match e {
+                        Expr::Sort { expr, .. } => expr.as_ref().clone(),
+                        e => e.clone(),
+                    })
+                    .collect(),
+                _ => {
+                    return Err(ExecutionError::InternalError(
+                        "DISTINCT ON requires a sorted input".to_string(),
+                    ))
+                }
+            };
+            LogicalPlanBuilder::from(sort)
+                .deduplicate(keys, true)?
+                .build()
+        };
+        match plan {
+            // the sort can be followed by a projection that removes the columns that
+            // were only added to sort by them
+            LogicalPlan::Projection { expr, input, .. } => {
+                LogicalPlanBuilder::from(&deduplicate(input)?)
+                    .project(expr.clone())?
+                    .build()
+            }
+            plan => deduplicate(plan),
+        }
+    }
+
+    /// The columns of the relation of a qualified wildcard `t.*` in the SELECT list, or
+    /// `None` if the expression is not a qualified wildcard
+    fn qualified_wildcard_columns(&self, sql: &ASTNode) -> Result<Option<Vec<Expr>>> {
+        match sql {
+            ASTNode::SQLFunction { id, args } if id == QUALIFIED_WILDCARD_FUNCTION => {
+                let name = match args.as_slice() {
+                    [ASTNode::SQLIdentifier(name)] => name,
+                    _ => {
+                        return Err(ExecutionError::InternalError(format!(
+                            "Invalid arguments of {}: {:?}",
+                            id, args
+                        )))
+                    }
+                };
+                match self.relations.borrow().iter().find(|r| &r.name == name) {
+                    Some(relation) => Ok(Some(
+                        relation.columns.iter().map(|i| Expr::Column(*i)).collect(),
+                    )),
                     None => Err(ExecutionError::General(format!(
-                        "no schema found for table {}",
-                        id
+                        "Invalid relation '{}' in '{}.*'",
+                        name, name
                     ))),
                 }
             }
+            _ => Ok(None),
+        }
+    }
 
-            _ => Err(ExecutionError::ExecutionError(format!(
-                "sql_to_rel does not support this relation: {:?}",
-                sql
-            ))),
+    /// Plan a relation in the FROM clause whose columns start at `offset` in the
+    /// combined input schema, registering its name for qualified column references.
+    ///
+    /// The relation is either a table or a subquery (derived table), and is referenced
+    /// by its alias if it has one. A subquery without an alias can only be referenced
+    /// by unqualified column names.
+    fn relation(&self, relation: &ASTNode, offset: usize) -> Result<LogicalPlan> {
+        let (relation, alias) = match *relation {
+            ASTNode::SQLAliasedExpr(ref relation, ref alias) => {
+                (relation.as_ref(), Some(alias.clone()))
+            }
+            _ => (relation, None),
+        };
+        let plan = self.sql_to_rel(relation)?;
+        let name = match (alias, relation) {
+            (Some(alias), _) => Some(alias),
+            (None, ASTNode::SQLIdentifier(ref name)) => Some(name.clone()),
+            (None, ASTNode::SQLCompoundIdentifier(ref names)) => names.last().cloned(),
+            _ => None,
+        };
+        if let Some(name) = name {
+            if self.relations.borrow().iter().any(|r| r.name == name) {
+                return Err(ExecutionError::General(format!(
+                    "Relation name '{}' is specified more than once, use an alias to \
+                     distinguish the relations",
+                    name
+                )));
+            }
+            self.relations.borrow_mut().push(RelationRef {
+                name,
+                columns: (offset..offset + plan.schema().fields().len()).collect(),
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Join a relation to the plan
+    fn join(&self, left: &LogicalPlan, join: &Join) -> Result<LogicalPlan> {
+        let left_len = left.schema().fields().len();
+        let right = self.relation(&join.relation, left_len)?;
+        let (join_type, constraint) = match join.join_operator {
+            JoinOperator::Inner(ref constraint) => (JoinType::Inner, constraint),
+            JoinOperator::LeftOuter(ref constraint) => (JoinType::Left, constraint),
+            JoinOperator::RightOuter(ref constraint) => (JoinType::Right, constraint),
+            JoinOperator::FullOuter(ref constraint) => (JoinType::Full, constraint),
+            // relations separated by commas are joined without a condition, which
+            // the WHERE clause is then applied to
+            JoinOperator::Cross | JoinOperator::Implicit => {
+                return LogicalPlanBuilder::from(left).cross_join(&right)?.build();
+            }
+        };
+        match constraint {
+            JoinConstraint::On(ref expr) => self.join_on(left, &right, join_type, expr),
+            JoinConstraint::Using(ref idents) => {
+                let names = idents
+                    .iter()
+                    .map(|ident| match ident {
+                        ASTNode::SQLIdentifier(ref name) => Ok(name.clone()),
+                        other => Err(ExecutionError::General(format!(
+                            "Invalid column in USING clause: {:?}",
+                            other
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                self.join_using(left, &right, join_type, &names)
+            }
+            JoinConstraint::Natural => {
+                // a natural join uses all columns with the same name in both relations
+                let right_schema = right.schema();
+                let names: Vec<String> = left
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| f.name().clone())
+                    .filter(|name| right_schema.fields().iter().any(|f| f.name() == name))
+                    .collect();
+                if names.is_empty() {
+                    if join_type == JoinType::Inner {
+                        return LogicalPlanBuilder::from(left)
+                            .cross_join(&right)?
+                            .build();
+                    }
+                    return Err(ExecutionError::NotImplemented(
+                        "Natural outer joins without common columns are not supported"
+                            .to_string(),
+                    ));
+                }
+                self.join_using(left, &right, join_type, &names)
+            }
+        }
+    }
+
+    /// Join two plans on the columns with the given names, which must exist in both
+    /// plans. The output contains a single column for each pair of join columns,
+    /// followed by the remaining columns of the left and right plans.
+    fn join_using(
+        &self,
+        left: &LogicalPlan,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        names: &Vec<String>,
+    ) -> Result<LogicalPlan> {
+        // the join columns would have to be merged from both sides, which requires
+        // COALESCE
+        if join_type == JoinType::Full {
+            return Err(ExecutionError::NotImplemented(
+                "Full outer joins with USING or NATURAL are not supported".to_string(),
+            ));
+        }
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let left_len = left_schema.fields().len();
+        let right_len = right_schema.fields().len();
+
+        let on = names
+            .iter()
+            .map(|name| {
+                let l = left_schema.fields().iter().position(|f| f.name() == name);
+                let r = right_schema.fields().iter().position(|f| f.name() == name);
+                match (l, r) {
+                    (Some(l), Some(r)) => Ok((l, r)),
+                    _ => Err(ExecutionError::General(format!(
+                        "Column '{}' in USING clause must exist in both relations",
+                        name
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let plan = LogicalPlanBuilder::from(left)
+            .join(right, join_type, on.clone())?
+            .build()?;
+
+        // the join columns are taken from the side that is preserved by the join, so
+        // that they are not null for unmatched rows
+        let mut columns: Vec<usize> = on
+            .iter()
+            .map(|(l, r)| match join_type {
+                JoinType::Right => *r + left_len,
+                _ => *l,
+            })
+            .collect();
+        columns.extend((0..left_len).filter(|i| !on.iter().any(|(l, _)| l == i)));
+        columns.extend(
+            (0..right_len)
+                .filter(|i| !on.iter().any(|(_, r)| r == i))
+                .map(|i| i + left_len),
+        );
+
+        // qualified references to the join columns of either side refer to the merged
+        // column, and references to other columns follow them to their new position
+        let mut mapping = vec![0; left_len + right_len];
+        for (new_index, old_index) in columns.iter().enumerate() {
+            mapping[*old_index] = new_index;
+        }
+        for (i, (l, r)) in on.iter().enumerate() {
+            mapping[*l] = i;
+            mapping[*r + left_len] = i;
+        }
+        for relation in self.relations.borrow_mut().iter_mut() {
+            relation.columns = relation.columns.iter().map(|i| mapping[*i]).collect();
+        }
+
+        self.project(&plan, columns.into_iter().map(Expr::Column).collect())
+    }
+
+    /// Join two plans on a SQL join condition. Equality conditions between columns of
+    /// both sides become join keys and any other conditions are applied as a filter
+    /// on the output of the join.
+    fn join_on(
+        &self,
+        left: &LogicalPlan,
+        right: &LogicalPlan,
+        join_type: JoinType,
+        sql: &ASTNode,
+    ) -> Result<LogicalPlan> {
+        let left_len = left.schema().fields().len();
+        let mut fields = left.schema().fields().clone();
+        fields.extend_from_slice(right.schema().fields());
+        let join_schema = Schema::new(fields);
+
+        let expr = self.sql_to_rex(sql, &join_schema)?;
+        let mut conjuncts = vec![];
+        split_conjunction(&expr, &mut conjuncts);
+
+        let mut on = vec![];
+        let mut filters = vec![];
+        for e in conjuncts {
+            match e {
+                Expr::BinaryExpr {
+                    left: l,
+                    op: Operator::Eq,
+                    right: r,
+                } => match (l.as_ref(), r.as_ref()) {
+                    (Expr::Column(a), Expr::Column(b))
+                        if *a < left_len && *b >= left_len =>
+                    {
+                        on.push((*a, *b - left_len))
+                    }
+                    (Expr::Column(a), Expr::Column(b))
+                        if *b < left_len && *a >= left_len =>
+                    {
+                        on.push((*b, *a - left_len))
+                    }
+                    _ => filters.push(e.clone()),
+                },
+                _ => filters.push(e.clone()),
+            }
+        }
+
+        if on.is_empty() {
+            return Err(ExecutionError::NotImplemented(format!(
+                "Join condition must contain at least one equality between columns of \
+                 both relations: {:?}",
+                expr
+            )));
+        }
+
+        // filtering the output of an outer join is not equivalent to applying the
+        // condition while joining, since rows without a match would be dropped
+        if join_type != JoinType::Inner && !filters.is_empty() {
+            return Err(ExecutionError::NotImplemented(format!(
+                "Outer joins only support equality conditions between columns of both \
+                 relations: {:?}",
+                expr
+            )));
+        }
+
+        let builder = LogicalPlanBuilder::from(left).join(right, join_type, on)?;
+        match conjunction(filters) {
+            Some(filter) => builder.filter(filter)?.build(),
+            None => builder.build(),
         }
     }
 
@@ -144,18 +981,56 @@ impl<S: SchemaProvider> SqlToRel<S> {
         LogicalPlanBuilder::from(input).project(expr)?.build()
     }
 
-    /// Wrap a plan in an aggregate
+    /// Wrap a plan in a window plan that evaluates the window functions of the SELECT
+    /// list, if there are any, returning the plan along with the SELECT list rewritten
+    /// to refer to the columns of the window functions
+    fn window(
+        &self,
+        input: &LogicalPlan,
+        projection_expr: Vec<Expr>,
+    ) -> Result<(LogicalPlan, Vec<Expr>)> {
+        let mut window_expr: Vec<Expr> = vec![];
+        for e in projection_expr.iter().filter(|e| is_window_expr(e)) {
+            if !window_expr.contains(unalias(e)) {
+                window_expr.push(unalias(e).clone());
+            }
+        }
+        if window_expr.is_empty() {
+            return Ok((input.clone(), projection_expr));
+        }
+
+        let input_len = input.schema().fields().len();
+        let projection_expr = projection_expr
+            .into_iter()
+            .map(
+                |e| match window_expr.iter().position(|w| w == unalias(&e)) {
+                    Some(k) => match e {
+                        Alias(_, ref name) => Expr::Column(input_len + k).alias(name),
+                        _ => Expr::Column(input_len + k),
+                    },
+                    None => e,
+                },
+            )
+            .collect();
+        let plan = LogicalPlanBuilder::from(input)
+            .window(window_expr)?
+            .build()?;
+        Ok((plan, projection_expr))
+    }
+
+    /// Wrap a plan in an aggregate, followed by a filter for the HAVING clause
     fn aggregate(
         &self,
         input: &LogicalPlan,
         projection_expr: Vec<Expr>,
         group_by: &Option<Vec<ASTNode>>,
         aggr_expr: Vec<Expr>,
+        having: &Option<Box<ASTNode>>,
     ) -> Result<LogicalPlan> {
         let group_expr: Vec<Expr> = match group_by {
             Some(gbe) => gbe
                 .iter()
-                .map(|e| self.sql_to_rex(&e, &input.schema()))
+                .map(|e| self.group_expr_to_rex(&e, &projection_expr, &input.schema()))
                 .collect::<Result<Vec<Expr>>>()?,
             None => vec![],
         };
@@ -169,44 +1044,122 @@ impl<S: SchemaProvider> SqlToRel<S> {
             ));
         }
 
-        let plan = LogicalPlanBuilder::from(&input)
-            .aggregate(group_expr, aggr_expr)?
-            .build()?;
-
         // wrap in projection to preserve final order of fields
         let mut projected_fields = Vec::with_capacity(group_by_count + aggr_count);
         let mut group_expr_index = 0;
         let mut aggr_expr_index = 0;
         for i in 0..projection_expr.len() {
-            if is_aggregate_expr(&projection_expr[i]) {
-                projected_fields.push(group_by_count + aggr_expr_index);
+            let column = if is_aggregate_expr(&projection_expr[i]) {
                 aggr_expr_index += 1;
+                Expr::Column(group_by_count + aggr_expr_index - 1)
             } else {
-                projected_fields.push(group_expr_index);
                 group_expr_index += 1;
-            }
+                // the group expression that is selected, or otherwise the group
+                // expression at the same position
+                let expr = unalias(&projection_expr[i]);
+                let index = group_expr
+                    .iter()
+                    .position(|e| e == expr)
+                    .unwrap_or(group_expr_index - 1);
+                Expr::Column(index)
+            };
+            projected_fields.push(match projection_expr[i] {
+                Alias(_, ref name) => column.alias(name),
+                _ => column,
+            });
         }
 
-        // determine if projection is needed or not
-        // NOTE this would be better done later in a query optimizer rule
-        let mut projection_needed = false;
-        for i in 0..projected_fields.len() {
-            if projected_fields[i] != i {
-                projection_needed = true;
-                break;
+        // the HAVING clause can reference the columns of the input as well as the output
+        // columns by name, such as aliases of aggregate expressions
+        let mut aggr_expr = aggr_expr;
+        let having_expr = match having {
+            Some(having) => {
+                let mut fields = input.schema().fields().clone();
+                fields.extend(exprlist_to_fields(&projection_expr, input.schema())?);
+                let expr = self.sql_to_rex(having, &Schema::new(fields))?;
+                Some(rewrite_having(
+                    &expr,
+                    input.schema().fields().len(),
+                    &projected_fields,
+                    &group_expr,
+                    &mut aggr_expr,
+                )?)
             }
+            None => None,
+        };
+
+        let mut plan =
+            LogicalPlanBuilder::from(&input).aggregate(group_expr, aggr_expr)?;
+        if let Some(having_expr) = having_expr {
+            plan = plan.filter(having_expr)?;
         }
+        let plan = plan.build()?;
+
+        // determine if projection is needed or not, which is also the case when the
+        // HAVING clause computes aggregates that are not projected
+        // NOTE this would be better done later in a query optimizer rule
+        let projection_needed = plan.schema().fields().len() != projected_fields.len()
+            || projected_fields
+                .iter()
+                .enumerate()
+                .any(|(i, e)| *e != Expr::Column(i));
 
         if projection_needed {
-            self.project(
-                &plan,
-                projected_fields.iter().map(|i| Expr::Column(*i)).collect(),
-            )
+            self.project(&plan, projected_fields)
         } else {
             Ok(plan)
         }
     }
 
+    /// Plan an expression of the GROUP BY clause. Besides expressions over the input,
+    /// positive integers refer to the position of an expression in the SELECT list and
+    /// names that are not columns of the input refer to aliases in the SELECT list.
+    fn group_expr_to_rex(
+        &self,
+        sql: &ASTNode,
+        projection_expr: &[Expr],
+        schema: &Schema,
+    ) -> Result<Expr> {
+        let expr = match *sql {
+            ASTNode::SQLValue(sqlparser::sqlast::Value::Long(n)) => {
+                if n < 1 || n as usize > projection_expr.len() {
+                    return Err(ExecutionError::General(format!(
+                        "GROUP BY position {} is not in the SELECT list",
+                        n
+                    )));
+                }
+                let expr = &projection_expr[n as usize - 1];
+                if is_aggregate_expr(expr) {
+                    return Err(ExecutionError::General(format!(
+                        "GROUP BY position {} refers to an aggregate expression",
+                        n
+                    )));
+                }
+                expr
+            }
+            ASTNode::SQLIdentifier(ref id)
+                if !schema.fields().iter().any(|f| f.name() == id) =>
+            {
+                let aliased = projection_expr.iter().find(|e| match e {
+                    Alias(_, name) => name == id,
+                    _ => false,
+                });
+                match aliased {
+                    Some(expr) if !is_aggregate_expr(expr) => expr,
+                    Some(_) => {
+                        return Err(ExecutionError::General(format!(
+                            "GROUP BY alias '{}' refers to an aggregate expression",
+                            id
+                        )))
+                    }
+                    None => return self.sql_to_rex(sql, schema),
+                }
+            }
+            _ => return self.sql_to_rex(sql, schema),
+        };
+        Ok(unalias(expr).clone())
+    }
+
     /// Wrap a plan in a limit
     fn limit(
         &self,
@@ -230,35 +1183,336 @@ impl<S: SchemaProvider> SqlToRel<S> {
         }
     }
 
-    /// Wrap the logical in a sort
+    /// Wrap the logical plan in a sort. ORDER BY expressions are resolved against the
+    /// output of the SELECT list, where positive integers refer to the position of an
+    /// output column. If `input` is the input of the projection in `plan` along with
+    /// its relations, expressions that cannot be resolved against the output are
+    /// planned against the input, added to the projection before the sort and removed
+    /// again after it.
     fn order_by(
         &self,
-        group_by_plan: &LogicalPlan,
+        plan: &LogicalPlan,
         order_by: &Option<Vec<SQLOrderByExpr>>,
+        input: Option<(&LogicalPlan, Vec<RelationRef>)>,
     ) -> Result<LogicalPlan> {
-        match *order_by {
-            Some(ref order_by_expr) => {
-                let input_schema = group_by_plan.schema();
-                let order_by_rex: Result<Vec<Expr>> = order_by_expr
-                    .iter()
-                    .map(|e| {
-                        Ok(Expr::Sort {
-                            expr: Arc::new(
-                                self.sql_to_rex(&e.expr, &input_schema).unwrap(),
-                            ),
-                            asc: e.asc,
-                        })
-                    })
-                    .collect();
+        let order_by_expr = match *order_by {
+            Some(ref order_by_expr) => order_by_expr,
+            _ => return Ok(plan.clone()),
+        };
 
-                LogicalPlanBuilder::from(&group_by_plan)
-                    .sort(order_by_rex?)?
-                    .build()
+        let schema = plan.schema();
+        let field_count = schema.fields().len();
+        let mut sort_expr = Vec::with_capacity(order_by_expr.len());
+        // expressions that are added to the projection in order to sort by them
+        let mut missing_expr = vec![];
+        for e in order_by_expr {
+            // the SQL parser wraps expressions that specify the placement of nulls in a
+            // marker function
+            let expr: &ASTNode = &e.expr;
+            let (expr, nulls_first) = match *expr {
+                ASTNode::SQLFunction { ref id, ref args }
+                    if id == NULLS_FIRST_FUNCTION && args.len() == 1 =>
+                {
+                    (&args[0], true)
+                }
+                ASTNode::SQLFunction { ref id, ref args }
+                    if id == NULLS_LAST_FUNCTION && args.len() == 1 =>
+                {
+                    (&args[0], false)
+                }
+                ref expr => (expr, self.null_ordering.nulls_first(e.asc)),
+            };
+
+            let expr = match (expr, &input) {
+                (ASTNode::SQLValue(sqlparser::sqlast::Value::Long(n)), _) => {
+                    let n = *n;
+                    if n < 1 || n as usize > field_count {
+                        return Err(ExecutionError::General(format!(
+                            "ORDER BY position {} is not in the SELECT list",
+                            n
+                        )));
+                    }
+                    Expr::Column(n as usize - 1)
+                }
+                // qualified columns are resolved against the relations of the input,
+                // since the output can contain several columns with the same name, such
+                // as the columns of both sides of a self-join
+                (ASTNode::SQLCompoundIdentifier(_), Some((input, relations))) => {
+                    let expr = self.sql_to_rex_in_relations(expr, input, relations)?;
+                    let position = match plan {
+                        LogicalPlan::Projection {
+                            expr: projected, ..
+                        } => projected.iter().position(|e| unalias(e) == &expr),
+                        _ => None,
+                    };
+                    match position {
+                        Some(index) => Expr::Column(index),
+                        None => {
+                            missing_expr.push(expr);
+                            Expr::Column(field_count + missing_expr.len() - 1)
+                        }
+                    }
+                }
+                _ => match (self.sql_to_rex(expr, &schema), &input) {
+                    (Ok(expr), _) => expr,
+                    (Err(_), Some((input, relations))) => {
+                        missing_expr
+                            .push(self.sql_to_rex_in_relations(expr, input, relations)?);
+                        Expr::Column(field_count + missing_expr.len() - 1)
+                    }
+                    (Err(e), None) => return Err(e),
+                },
+            };
+            sort_expr.push(Expr::Sort {
+                expr: Arc::new(expr),
+                asc: e.asc,
+                nulls_first,
+            });
+        }
+
+        match (plan, input) {
+            (LogicalPlan::Projection { expr, .. }, Some((input, _)))
+                if !missing_expr.is_empty() =>
+            {
+                let mut expr = expr.clone();
+                expr.extend(missing_expr);
+                LogicalPlanBuilder::from(input)
+                    .project(expr)?
+                    .sort(sort_expr)?
+                    .project((0..field_count).map(Expr::Column).collect())?
+                    .build()
+            }
+            _ => LogicalPlanBuilder::from(plan).sort(sort_expr)?.build(),
+        }
+    }
+
+    /// Generate a relational expression against the schema of a plan whose relations
+    /// are not in scope anymore
+    fn sql_to_rex_in_relations(
+        &self,
+        sql: &ASTNode,
+        plan: &LogicalPlan,
+        relations: &[RelationRef],
+    ) -> Result<Expr> {
+        let outer_relations = self.relations.replace(relations.to_vec());
+        let outer_projected = self.projected.replace(false);
+        let expr = self.sql_to_rex(sql, plan.schema());
+        self.relations.replace(outer_relations);
+        self.projected.set(outer_projected);
+        expr
+    }
+
+    /// Plan a window function call that was rewritten by the SQL parser to
+    /// `__window(f(args), __partition_by(...), __order_by(...)[, __window_frame(...)])`.
+    /// Besides the ranking functions, any aggregate function can be evaluated over a
+    /// window.
+    fn window_function_to_rex(&self, args: &[ASTNode], schema: &Schema) -> Result<Expr> {
+        let (partition_by, order_by) = match (args.get(1), args.get(2)) {
+            (
+                Some(ASTNode::SQLFunction {
+                    id: partition_id,
+                    args: partition_by,
+                }),
+                Some(ASTNode::SQLFunction {
+                    id: order_id,
+                    args: order_by,
+                }),
+            ) if partition_id == PARTITION_BY_FUNCTION
+                && order_id == ORDER_BY_FUNCTION
+                && args.len() <= 4 =>
+            {
+                (partition_by, order_by)
+            }
+            _ => {
+                return Err(ExecutionError::InternalError(format!(
+                    "Invalid window function call {:?}",
+                    args
+                )))
+            }
+        };
+        let window_frame = match args.get(3) {
+            Some(frame) => Some(window_frame(frame)?),
+            None => None,
+        };
+
+        let (name, args, return_type) = match &args[0] {
+            ASTNode::SQLFunction { id, args }
+                if ["row_number", "rank", "dense_rank"]
+                    .contains(&id.to_lowercase().as_str()) =>
+            {
+                if !args.is_empty() {
+                    return Err(ExecutionError::General(format!(
+                        "Function '{}' does not take arguments",
+                        id
+                    )));
+                }
+                (id.to_uppercase(), vec![], DataType::UInt64)
+            }
+            ASTNode::SQLFunction { id, args }
+                if ["lag", "lead", "first_value", "last_value", "ntile"]
+                    .contains(&id.to_lowercase().as_str()) =>
+            {
+                let (args, return_type) = self.value_function_args(id, args, schema)?;
+                (id.to_uppercase(), args, return_type)
+            }
+            function => match self.sql_to_rex(function, schema)? {
+                Expr::AggregateFunction {
+                    name,
+                    args,
+                    return_type,
+                } => (name, args, return_type),
+                _ => {
+                    return Err(ExecutionError::General(format!(
+                        "Invalid window function {:?}",
+                        function
+                    )))
+                }
+            },
+        };
+
+        let partition_by = partition_by
+            .iter()
+            .map(|e| self.sql_to_rex(e, schema))
+            .collect::<Result<Vec<_>>>()?;
+        let order_by = order_by
+            .iter()
+            .map(|e| {
+                // the SQL parser wraps expressions in marker functions for the sort
+                // direction, and within it for the placement of nulls
+                let (e, asc) = match e {
+                    ASTNode::SQLFunction { id, args }
+                        if id == DESC_FUNCTION && args.len() == 1 =>
+                    {
+                        (&args[0], false)
+                    }
+                    _ => (e, true),
+                };
+                let (e, nulls_first) = match e {
+                    ASTNode::SQLFunction { id, args }
+                        if id == NULLS_FIRST_FUNCTION && args.len() == 1 =>
+                    {
+                        (&args[0], true)
+                    }
+                    ASTNode::SQLFunction { id, args }
+                        if id == NULLS_LAST_FUNCTION && args.len() == 1 =>
+                    {
+                        (&args[0], false)
+                    }
+                    _ => (e, self.null_ordering.nulls_first(asc)),
+                };
+                Ok(Expr::Sort {
+                    expr: Arc::new(self.sql_to_rex(e, schema)?),
+                    asc,
+                    nulls_first,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Expr::WindowFunction {
+            name,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+            return_type,
+        })
+    }
+
+    /// Plan the arguments of the LAG, LEAD, FIRST_VALUE, LAST_VALUE and NTILE window
+    /// functions and get their return type. The offset of LAG and LEAD, which defaults
+    /// to 1, is always included in their arguments, and their default value is cast to
+    /// the type of their expression, so that e.g. integer literals can be the default of
+    /// unsigned columns.
+    fn value_function_args(
+        &self,
+        name: &str,
+        args: &[ASTNode],
+        schema: &Schema,
+    ) -> Result<(Vec<Expr>, DataType)> {
+        // offsets and numbers of buckets must be integer literals
+        let integer = |arg: Option<&ASTNode>, default: i64| match arg {
+            Some(ASTNode::SQLValue(sqlparser::sqlast::Value::Long(n))) => Ok(*n),
+            None => Ok(default),
+            Some(other) => Err(ExecutionError::General(format!(
+                "Function '{}' expects an integer literal but got {:?}",
+                name, other
+            ))),
+        };
+        match name.to_lowercase().as_str() {
+            "lag" | "lead" if !args.is_empty() && args.len() <= 3 => {
+                let expr = self.sql_to_rex(&args[0], schema)?;
+                let return_type = expr.get_type(schema)?;
+                let mut exprs = vec![
+                    expr,
+                    Expr::Literal(ScalarValue::Int64(integer(args.get(1), 1)?)),
+                ];
+                if let Some(default) = args.get(2) {
+                    let default = self.sql_to_rex(default, schema)?;
+                    exprs.push(if default.get_type(schema)? == return_type {
+                        default
+                    } else {
+                        Expr::Cast {
+                            expr: Arc::new(default),
+                            data_type: return_type.clone(),
+                        }
+                    });
+                }
+                Ok((exprs, return_type))
             }
-            _ => Ok(group_by_plan.clone()),
+            "first_value" | "last_value" if args.len() == 1 => {
+                let expr = self.sql_to_rex(&args[0], schema)?;
+                let return_type = expr.get_type(schema)?;
+                Ok((vec![expr], return_type))
+            }
+            "ntile" if args.len() == 1 => match integer(args.get(0), 0)? {
+                n if n > 0 => {
+                    Ok((vec![Expr::Literal(ScalarValue::Int64(n))], DataType::UInt64))
+                }
+                _ => Err(ExecutionError::General(
+                    "The number of buckets of NTILE must be positive".to_string(),
+                )),
+            },
+            _ => Err(ExecutionError::General(format!(
+                "Invalid number of arguments for function '{}'",
+                name
+            ))),
         }
     }
 
+    /// Plan a subquery of an expression that is evaluated against the given schema,
+    /// whose columns the subquery can reference
+    fn subquery_to_rel(&self, sql: &ASTNode, schema: &Schema) -> Result<Subquery> {
+        self.outer_scopes.borrow_mut().push(OuterScope {
+            schema: schema.clone(),
+            relations: self.relations.borrow().clone(),
+        });
+        let plan = self.sql_to_rel(sql);
+        self.outer_scopes.borrow_mut().pop();
+        Ok(Subquery::new(&plan?))
+    }
+
+    /// Resolve a column reference that is not a column of the query being planned
+    /// against the enclosing query, if the query is a subquery
+    fn outer_column(&self, relation: Option<&str>, name: &str) -> Option<Expr> {
+        let outer_scopes = self.outer_scopes.borrow();
+        let scope = outer_scopes.last()?;
+        let index = match relation {
+            Some(relation) => scope
+                .relations
+                .iter()
+                .find(|r| r.name == relation)?
+                .columns
+                .iter()
+                .cloned()
+                .find(|i| scope.schema.field(*i).name() == name),
+            None => scope.schema.fields().iter().position(|f| f.name() == name),
+        }?;
+        Some(Expr::OuterColumn {
+            index,
+            data_type: scope.schema.field(index).data_type().clone(),
+        })
+    }
+
     /// Generate a relational expression from a SQL expression
     pub fn sql_to_rex(&self, sql: &ASTNode, schema: &Schema) -> Result<Expr> {
         match *sql {
@@ -271,6 +1525,10 @@ impl<S: SchemaProvider> SqlToRel<S> {
             ASTNode::SQLValue(sqlparser::sqlast::Value::SingleQuotedString(ref s)) => {
                 Ok(Expr::Literal(ScalarValue::Utf8(s.clone())))
             }
+            // the type of NULL is determined by its context during type coercion
+            ASTNode::SQLValue(sqlparser::sqlast::Value::Null) => {
+                Ok(Expr::Literal(ScalarValue::Null))
+            }
 
             ASTNode::SQLAliasedExpr(ref expr, ref alias) => Ok(Alias(
                 Arc::new(self.sql_to_rex(&expr, schema)?),
@@ -278,11 +1536,47 @@ impl<S: SchemaProvider> SqlToRel<S> {
             )),
 
             ASTNode::SQLIdentifier(ref id) => {
-                match schema.fields().iter().position(|c| c.name().eq(id)) {
+                match find_column(schema, 0..schema.fields().len(), id)? {
+                    Some(index) => Ok(Expr::Column(index)),
+                    None => self.outer_column(None, id).ok_or_else(|| {
+                        ExecutionError::ExecutionError(format!(
+                            "Invalid identifier '{}' for schema {}",
+                            id,
+                            schema.to_string()
+                        ))
+                    }),
+                }
+            }
+
+            ASTNode::SQLCompoundIdentifier(ref ids) if ids.len() == 2 => {
+                let relation = self
+                    .relations
+                    .borrow()
+                    .iter()
+                    .find(|r| r.name == ids[0])
+                    .cloned();
+                let position = match relation {
+                    Some(r) => find_column(schema, r.columns.into_iter(), &ids[1])?,
+                    None => {
+                        // a relation of an enclosing query takes precedence over a
+                        // column of this query with the same name
+                        if let Some(expr) = self.outer_column(Some(&ids[0]), &ids[1]) {
+                            return Ok(expr);
+                        }
+                        // a qualifier that names no relation is only ignored once the
+                        // relations went out of scope after the projection
+                        if self.projected.get() {
+                            find_column(schema, 0..schema.fields().len(), &ids[1])?
+                        } else {
+                            None
+                        }
+                    }
+                };
+                match position {
                     Some(index) => Ok(Expr::Column(index)),
                     None => Err(ExecutionError::ExecutionError(format!(
                         "Invalid identifier '{}' for schema {}",
-                        id,
+                        ids.join("."),
                         schema.to_string()
                     ))),
                 }
@@ -290,6 +1584,33 @@ impl<S: SchemaProvider> SqlToRel<S> {
 
             ASTNode::SQLWildcard => Ok(Expr::Wildcard),
 
+            ASTNode::SQLSelect { .. } => {
+                Ok(Expr::ScalarSubquery(self.subquery_to_rel(sql, schema)?))
+            }
+
+            ASTNode::SQLCase {
+                ref conditions,
+                ref results,
+                ref else_result,
+            } => Ok(Expr::Case {
+                when_then_expr: conditions
+                    .iter()
+                    .zip(results.iter())
+                    .map(|(when, then)| {
+                        Ok((
+                            self.sql_to_rex(when, schema)?,
+                            self.sql_to_rex(then, schema)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                else_expr: match else_result {
+                    Some(else_result) => {
+                        Some(Arc::new(self.sql_to_rex(else_result, schema)?))
+                    }
+                    None => None,
+                },
+            }),
+
             ASTNode::SQLCast {
                 ref expr,
                 ref data_type,
@@ -310,9 +1631,13 @@ impl<S: SchemaProvider> SqlToRel<S> {
                 ref operator,
                 ref expr,
             } => match *operator {
-                SQLOperator::Not => {
-                    Ok(Expr::Not(Arc::new(self.sql_to_rex(expr, schema)?)))
-                }
+                SQLOperator::Not => match self.sql_to_rex(expr, schema)? {
+                    Expr::Exists { subquery, negated } => Ok(Expr::Exists {
+                        subquery,
+                        negated: !negated,
+                    }),
+                    expr => Ok(Expr::Not(Arc::new(expr))),
+                },
                 _ => Err(ExecutionError::InternalError(format!(
                     "SQL binary operator cannot be interpreted as a unary operator"
                 ))),
@@ -323,6 +1648,80 @@ impl<S: SchemaProvider> SqlToRel<S> {
                 ref op,
                 ref right,
             } => {
+                if let Some((low, high, negated)) = between(right) {
+                    return Ok(Expr::Between {
+                        expr: Arc::new(self.sql_to_rex(left, schema)?),
+                        negated,
+                        low: Arc::new(self.sql_to_rex(low, schema)?),
+                        high: Arc::new(self.sql_to_rex(high, schema)?),
+                    });
+                }
+                if let SQLOperator::Plus = *op {
+                    if let Some(right) = string_concat(right) {
+                        return Ok(Expr::BinaryExpr {
+                            left: Arc::new(self.sql_to_rex(left, schema)?),
+                            op: Operator::StringConcat,
+                            right: Arc::new(self.sql_to_rex(right, schema)?),
+                        });
+                    }
+                }
+                if let Some((list, negated)) = in_list(right) {
+                    return Ok(Expr::InList {
+                        expr: Arc::new(self.sql_to_rex(left, schema)?),
+                        list: list
+                            .iter()
+                            .map(|value| self.sql_to_rex(value, schema))
+                            .collect::<Result<Vec<_>>>()?,
+                        negated,
+                    });
+                }
+                if let Some((subquery, negated)) = in_subquery(right) {
+                    return Ok(Expr::InSubquery {
+                        expr: Arc::new(self.sql_to_rex(left, schema)?),
+                        subquery: self.subquery_to_rel(subquery, schema)?,
+                        negated,
+                    });
+                }
+                if let Some((pattern, case_insensitive, escape)) = like_pattern(right) {
+                    let negated = match *op {
+                        SQLOperator::Like => false,
+                        SQLOperator::NotLike => true,
+                        _ => {
+                            return Err(ExecutionError::InternalError(
+                                "ILIKE or ESCAPE used without LIKE".to_string(),
+                            ))
+                        }
+                    };
+                    return Ok(Expr::Like {
+                        expr: Arc::new(self.sql_to_rex(left, schema)?),
+                        pattern: Arc::new(self.sql_to_rex(pattern, schema)?),
+                        negated,
+                        case_insensitive,
+                        escape: match escape {
+                            Some(escape) => like_escape(escape)?,
+                            None => None,
+                        },
+                    });
+                }
+                if let Some((pattern, case_insensitive)) = regex_pattern(right) {
+                    let operator = match (op, case_insensitive) {
+                        (SQLOperator::Like, false) => Operator::RegexMatch,
+                        (SQLOperator::Like, true) => Operator::RegexIMatch,
+                        (SQLOperator::NotLike, false) => Operator::RegexNotMatch,
+                        (SQLOperator::NotLike, true) => Operator::RegexNotIMatch,
+                        _ => {
+                            return Err(ExecutionError::InternalError(
+                                "Regular expression match used without LIKE".to_string(),
+                            ))
+                        }
+                    };
+                    return Ok(Expr::BinaryExpr {
+                        left: Arc::new(self.sql_to_rex(left, schema)?),
+                        op: operator,
+                        right: Arc::new(self.sql_to_rex(pattern, schema)?),
+                    });
+                }
+
                 let operator = match *op {
                     SQLOperator::Gt => Operator::Gt,
                     SQLOperator::GtEq => Operator::GtEq,
@@ -358,9 +1757,48 @@ impl<S: SchemaProvider> SqlToRel<S> {
             //                expr: Arc::new(self.sql_to_rex(&expr, &schema)?),
             //                asc,
             //            }),
+            ASTNode::SQLFunction { ref id, ref args } if id == WINDOW_FUNCTION => {
+                self.window_function_to_rex(args, schema)
+            }
+
+            ASTNode::SQLFunction { ref id, ref args }
+                if id == EXISTS_FUNCTION && args.len() == 1 =>
+            {
+                Ok(Expr::Exists {
+                    subquery: self.subquery_to_rel(&args[0], schema)?,
+                    negated: false,
+                })
+            }
+
             ASTNode::SQLFunction { ref id, ref args } => {
                 //TODO: fix this hack
                 match id.to_lowercase().as_ref() {
+                    "coalesce" => {
+                        // the first argument that is not null, as the results of a CASE
+                        // expression, which are cast to their common supertype
+                        let mut rex_args = args
+                            .iter()
+                            .map(|a| self.sql_to_rex(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        let else_expr = match rex_args.pop() {
+                            Some(else_expr) => else_expr,
+                            None => {
+                                return Err(ExecutionError::General(
+                                    "COALESCE requires at least one argument".to_string(),
+                                ))
+                            }
+                        };
+                        if rex_args.is_empty() {
+                            return Ok(else_expr);
+                        }
+                        Ok(Expr::Case {
+                            when_then_expr: rex_args
+                                .into_iter()
+                                .map(|arg| (Expr::IsNotNull(Arc::new(arg.clone())), arg))
+                                .collect(),
+                            else_expr: Some(Arc::new(else_expr)),
+                        })
+                    }
                     "min" | "max" | "sum" | "avg" => {
                         let rex_args = args
                             .iter()
@@ -377,6 +1815,141 @@ impl<S: SchemaProvider> SqlToRel<S> {
                             return_type,
                         })
                     }
+                    "bool_and" | "every" | "bool_or" | "any" => {
+                        let rex_args = args
+                            .iter()
+                            .map(|a| self.sql_to_rex(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        if rex_args.len() != 1
+                            || rex_args[0].get_type(schema)? != DataType::Boolean
+                        {
+                            return Err(ExecutionError::General(format!(
+                                "Function '{}' expects a single boolean argument",
+                                id
+                            )));
+                        }
+
+                        // EVERY and ANY are the ANSI SQL names of BOOL_AND and BOOL_OR
+                        let name = match id.to_lowercase().as_ref() {
+                            "bool_and" | "every" => "BOOL_AND",
+                            _ => "BOOL_OR",
+                        };
+
+                        Ok(Expr::AggregateFunction {
+                            name: name.to_string(),
+                            args: rex_args,
+                            return_type: DataType::Boolean,
+                        })
+                    }
+                    "max_by" | "min_by" => {
+                        let rex_args = args
+                            .iter()
+                            .map(|a| self.sql_to_rex(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        if rex_args.len() != 2 {
+                            return Err(ExecutionError::General(format!(
+                                "Function '{}' expects a value and an order argument",
+                                id
+                            )));
+                        }
+
+                        // return type is the type of the value argument
+                        let return_type = rex_args[0].get_type(schema)?.clone();
+
+                        Ok(Expr::AggregateFunction {
+                            name: id.to_uppercase(),
+                            args: rex_args,
+                            return_type,
+                        })
+                    }
+                    "histogram" => {
+                        let rex_args = args
+                            .iter()
+                            .map(|a| self.sql_to_rex(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        if rex_args.len() != 4 {
+                            return Err(ExecutionError::General(
+                                "Function 'histogram' expects a value, two bounds and \
+                                 a number of buckets"
+                                    .to_string(),
+                            ));
+                        }
+
+                        let buckets = histogram_buckets(&rex_args[1..])?;
+                        Ok(Expr::AggregateFunction {
+                            name: id.to_uppercase(),
+                            args: rex_args,
+                            return_type: histogram_data_type(buckets.num_buckets()),
+                        })
+                    }
+                    "reservoir_sample" => {
+                        let rex_args = args
+                            .iter()
+                            .map(|a| self.sql_to_rex(a, schema))
+                            .collect::<Result<Vec<Expr>>>()?;
+                        if rex_args.len() != 2 {
+                            return Err(ExecutionError::General(
+                                "Function 'reservoir_sample' expects a value and a \
+                                 sample size"
+                                    .to_string(),
+                            ));
+                        }
+
+                        reservoir_sample_size(&rex_args[1])?;
+                        let value_type = rex_args[0].get_type(schema)?;
+                        Ok(Expr::AggregateFunction {
+                            name: id.to_uppercase(),
+                            args: rex_args,
+                            return_type: DataType::List(Box::new(value_type)),
+                        })
+                    }
+                    "now" if args.is_empty() => Ok(Expr::Literal(
+                        ScalarValue::TimestampNanosecond(self.statement_time),
+                    )),
+                    "to_timestamp" if args.len() == 1 || args.len() == 2 => {
+                        // the time zone is a literal, because it is part of the type of
+                        // the result
+                        let tz = match args.get(1) {
+                            Some(ASTNode::SQLValue(
+                                sqlparser::sqlast::Value::SingleQuotedString(tz),
+                            )) => {
+                                parse_timezone(tz)?;
+                                Some(Arc::new(tz.clone()))
+                            }
+                            Some(_) => {
+                                return Err(ExecutionError::General(
+                                    "The time zone of to_timestamp must be a string \
+                                     literal"
+                                        .to_string(),
+                                ))
+                            }
+                            None => None,
+                        };
+                        Ok(Expr::ScalarFunction {
+                            name: "to_timestamp".to_string(),
+                            args: vec![
+                                self.sql_to_rex(&args[0], schema)?
+                                    .cast_to(&DataType::Utf8, schema)?,
+                                Expr::Literal(ScalarValue::Utf8(
+                                    tz.as_ref()
+                                        .map_or("".to_string(), |tz| tz.as_ref().clone()),
+                                )),
+                            ],
+                            return_type: DataType::Timestamp(TimeUnit::Nanosecond, tz),
+                        })
+                    }
+                    "element_at" if args.len() == 2 => {
+                        let list = self.sql_to_rex(&args[0], schema)?;
+                        // fail early if the first argument is not a list
+                        element_type(&list.get_type(schema)?)?;
+                        Ok(Expr::ElementAt {
+                            list: Arc::new(list),
+                            index: Arc::new(
+                                self.sql_to_rex(&args[1], schema)?
+                                    .cast_to(&DataType::Int64, schema)?,
+                            ),
+                        })
+                    }
                     "count" => {
                         let rex_args = args
                             .iter()
@@ -434,14 +2007,365 @@ impl<S: SchemaProvider> SqlToRel<S> {
     }
 }
 
-/// Determine if an expression is an aggregate expression or not
+/// The current time in nanoseconds since the epoch, or 0 on targets without a clock,
+/// such as `wasm32-unknown-unknown`, where `now()` needs the time of the statement to be
+/// set with `with_statement_time`
+fn current_time() -> i64 {
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return 0;
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as i64)
+}
+
+/// Get the subquery and negation of a SQL expression that is the right side of a
+/// rewritten `[NOT] IN (subquery)` condition
+fn in_subquery(sql: &ASTNode) -> Option<(&ASTNode, bool)> {
+    match sql {
+        ASTNode::SQLFunction { id, args } if args.len() == 1 => {
+            if id == IN_SUBQUERY_FUNCTION {
+                Some((&args[0], false))
+            } else if id == NOT_IN_SUBQUERY_FUNCTION {
+                Some((&args[0], true))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the values and negation of a SQL expression that is the right side of a
+/// rewritten `[NOT] IN (value, ...)` condition
+fn in_list(sql: &ASTNode) -> Option<(&[ASTNode], bool)> {
+    match sql {
+        ASTNode::SQLFunction { id, args } => {
+            if id == IN_LIST_FUNCTION {
+                Some((args, false))
+            } else if id == NOT_IN_LIST_FUNCTION {
+                Some((args, true))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the bounds and negation of a SQL expression that is the right side of a
+/// rewritten `[NOT] BETWEEN low AND high` condition
+fn between(sql: &ASTNode) -> Option<(&ASTNode, &ASTNode, bool)> {
+    match sql {
+        ASTNode::SQLFunction { id, args } if args.len() == 2 => {
+            if id == BETWEEN_FUNCTION {
+                Some((&args[0], &args[1], false))
+            } else if id == NOT_BETWEEN_FUNCTION {
+                Some((&args[0], &args[1], true))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the right operand of a SQL expression that is the right side of a rewritten
+/// `left || right` concatenation
+fn string_concat(sql: &ASTNode) -> Option<&ASTNode> {
+    match sql {
+        ASTNode::SQLFunction { id, args }
+            if id == STRING_CONCAT_FUNCTION && args.len() == 1 =>
+        {
+            Some(&args[0])
+        }
+        _ => None,
+    }
+}
+
+/// Get the pattern, case insensitivity and escape character of a SQL expression that is
+/// the right side of a rewritten `[NOT] ILIKE pattern [ESCAPE escape]` or
+/// `[NOT] LIKE pattern ESCAPE escape` condition
+fn like_pattern(sql: &ASTNode) -> Option<(&ASTNode, bool, Option<&ASTNode>)> {
+    match sql {
+        ASTNode::SQLFunction { id, args }
+            if id == ILIKE_FUNCTION && (args.len() == 1 || args.len() == 2) =>
+        {
+            Some((&args[0], true, args.get(1)))
+        }
+        ASTNode::SQLFunction { id, args }
+            if id == LIKE_ESCAPE_FUNCTION && args.len() == 2 =>
+        {
+            Some((&args[0], false, Some(&args[1])))
+        }
+        _ => None,
+    }
+}
+
+/// Get the pattern and case insensitivity of a SQL expression that is the right side of a
+/// rewritten `[!]~[*] pattern` regular expression match
+fn regex_pattern(sql: &ASTNode) -> Option<(&ASTNode, bool)> {
+    match sql {
+        ASTNode::SQLFunction { id, args } if args.len() == 1 => {
+            if id == REGEX_MATCH_FUNCTION {
+                Some((&args[0], false))
+            } else if id == REGEX_IMATCH_FUNCTION {
+                Some((&args[0], true))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get the escape character of a LIKE condition, which must be a string of a single
+/// character. An empty string disables escaping.
+fn like_escape(sql: &ASTNode) -> Result<Option<char>> {
+    match sql {
+        ASTNode::SQLValue(sqlparser::sqlast::Value::SingleQuotedString(s)) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (None, _) => Ok(None),
+                (Some(escape), None) => Ok(Some(escape)),
+                _ => Err(ExecutionError::General(format!(
+                    "Invalid escape string '{}', which must be a single character",
+                    s
+                ))),
+            }
+        }
+        _ => Err(ExecutionError::General(format!(
+            "Invalid escape {:?}, which must be a string literal",
+            sql
+        ))),
+    }
+}
+
+/// Determine if an expression is an aggregate expression, or an alias of one
 fn is_aggregate_expr(e: &Expr) -> bool {
-    match e {
+    match unalias(e) {
         Expr::AggregateFunction { .. } => true,
         _ => false,
     }
 }
 
+/// Get the frame of a window that was rewritten by the SQL parser to
+/// `__window_frame(units, start, end)`
+fn window_frame(sql: &ASTNode) -> Result<WindowFrame> {
+    let strings = match sql {
+        ASTNode::SQLFunction { id, args } if id == WINDOW_FRAME_FUNCTION => args
+            .iter()
+            .map(|arg| match arg {
+                ASTNode::SQLValue(sqlparser::sqlast::Value::SingleQuotedString(s)) => {
+                    Some(s.as_str())
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+    match strings.as_ref().map(|s| s.as_slice()) {
+        Some([units, start, end]) => {
+            let units = match *units {
+                "ROWS" => WindowFrameUnits::Rows,
+                "RANGE" => WindowFrameUnits::Range,
+                _ => {
+                    return Err(ExecutionError::General(format!(
+                        "Invalid window frame units '{}'",
+                        units
+                    )))
+                }
+            };
+            WindowFrame::try_new(
+                units,
+                window_frame_bound(start)?,
+                window_frame_bound(end)?,
+            )
+        }
+        _ => Err(ExecutionError::InternalError(format!(
+            "Invalid window frame {:?}",
+            sql
+        ))),
+    }
+}
+
+/// Parse a bound of a window frame such as `3 PRECEDING`
+fn window_frame_bound(bound: &str) -> Result<WindowFrameBound> {
+    let words: Vec<&str> = bound.split_whitespace().collect();
+    let offset = |n: &str| match n.parse::<u64>() {
+        Ok(n) => Ok(Some(n)),
+        Err(_) if n == "UNBOUNDED" => Ok(None),
+        Err(_) => Err(ExecutionError::General(format!(
+            "Invalid window frame bound '{}'",
+            bound
+        ))),
+    };
+    match words.as_slice() {
+        ["CURRENT", "ROW"] => Ok(WindowFrameBound::CurrentRow),
+        [n, "PRECEDING"] => Ok(WindowFrameBound::Preceding(offset(*n)?)),
+        [n, "FOLLOWING"] => Ok(WindowFrameBound::Following(offset(*n)?)),
+        _ => Err(ExecutionError::General(format!(
+            "Invalid window frame bound '{}'",
+            bound
+        ))),
+    }
+}
+
+/// Determine if an expression is a window function, or an alias of one
+fn is_window_expr(e: &Expr) -> bool {
+    match unalias(e) {
+        Expr::WindowFunction { .. } => true,
+        _ => false,
+    }
+}
+
+/// The position of the only column with the given name among some columns of a schema,
+/// or an error if several of them have the name
+fn find_column(
+    schema: &Schema,
+    columns: impl Iterator<Item = usize>,
+    name: &str,
+) -> Result<Option<usize>> {
+    let mut matches = columns.filter(|i| schema.field(*i).name() == name);
+    match (matches.next(), matches.next()) {
+        (Some(_), Some(_)) => Err(ExecutionError::General(format!(
+            "Ambiguous reference to column '{}'",
+            name
+        ))),
+        (index, _) => Ok(index),
+    }
+}
+
+/// Cast an expression over a schema to the type of a field of a table, unless it has
+/// that type already
+fn cast_to_field(expr: Expr, field: &Field, schema: &Schema) -> Result<Expr> {
+    if expr.get_type(schema)? == *field.data_type() {
+        Ok(expr)
+    } else {
+        Ok(Expr::Cast {
+            expr: Arc::new(expr),
+            data_type: field.data_type().clone(),
+        })
+    }
+}
+
+/// Remove the alias from an expression, if it has one
+fn unalias(e: &Expr) -> &Expr {
+    match e {
+        Alias(expr, _) => unalias(expr),
+        _ => e,
+    }
+}
+
+/// Rewrite a HAVING expression that was planned against the columns of the input of an
+/// aggregate, followed by the columns of the query output, into an expression over the
+/// output of the aggregate. Aggregate functions that are not computed by the aggregate
+/// yet are appended to `aggr_expr`.
+fn rewrite_having(
+    expr: &Expr,
+    input_len: usize,
+    projected_fields: &[Expr],
+    group_expr: &[Expr],
+    aggr_expr: &mut Vec<Expr>,
+) -> Result<Expr> {
+    if let Some(index) = group_expr.iter().position(|e| e == expr) {
+        return Ok(Expr::Column(index));
+    }
+    let mut rewrite =
+        |e: &Expr| rewrite_having(e, input_len, projected_fields, group_expr, aggr_expr);
+    match expr {
+        Expr::Column(i) if *i >= input_len => {
+            Ok(unalias(&projected_fields[*i - input_len]).clone())
+        }
+        Expr::Column(_) => Err(ExecutionError::General(
+            "HAVING references non-aggregate values".to_owned(),
+        )),
+        Expr::AggregateFunction { .. } => {
+            let index = match aggr_expr.iter().position(|e| e == expr) {
+                Some(index) => index,
+                None => {
+                    aggr_expr.push(expr.clone());
+                    aggr_expr.len() - 1
+                }
+            };
+            Ok(Expr::Column(group_expr.len() + index))
+        }
+        Expr::Alias(expr, _) => rewrite(expr.as_ref()),
+        Expr::BinaryExpr { left, op, right } => Ok(Expr::BinaryExpr {
+            left: Arc::new(rewrite(left.as_ref())?),
+            op: op.clone(),
+            right: Arc::new(rewrite(right.as_ref())?),
+        }),
+        Expr::Not(expr) => Ok(Expr::Not(Arc::new(rewrite(expr.as_ref())?))),
+        Expr::IsNotNull(expr) => Ok(Expr::IsNotNull(Arc::new(rewrite(expr.as_ref())?))),
+        Expr::IsNull(expr) => Ok(Expr::IsNull(Arc::new(rewrite(expr.as_ref())?))),
+        Expr::Cast { expr, data_type } => Ok(Expr::Cast {
+            expr: Arc::new(rewrite(expr.as_ref())?),
+            data_type: data_type.clone(),
+        }),
+        Expr::ScalarFunction {
+            name,
+            args,
+            return_type,
+        } => Ok(Expr::ScalarFunction {
+            name: name.clone(),
+            args: args.iter().map(rewrite).collect::<Result<Vec<_>>>()?,
+            return_type: return_type.clone(),
+        }),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Ok(Expr::Between {
+            expr: Arc::new(rewrite(expr.as_ref())?),
+            negated: *negated,
+            low: Arc::new(rewrite(low.as_ref())?),
+            high: Arc::new(rewrite(high.as_ref())?),
+        }),
+        Expr::ElementAt { list, index } => Ok(Expr::ElementAt {
+            list: Arc::new(rewrite(list.as_ref())?),
+            index: Arc::new(rewrite(index.as_ref())?),
+        }),
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Ok(Expr::InList {
+            expr: Arc::new(rewrite(expr.as_ref())?),
+            list: list.iter().map(rewrite).collect::<Result<Vec<_>>>()?,
+            negated: *negated,
+        }),
+        Expr::Like {
+            expr,
+            pattern,
+            negated,
+            case_insensitive,
+            escape,
+        } => Ok(Expr::Like {
+            expr: Arc::new(rewrite(expr.as_ref())?),
+            pattern: Arc::new(rewrite(pattern.as_ref())?),
+            negated: *negated,
+            case_insensitive: *case_insensitive,
+            escape: *escape,
+        }),
+        Expr::Case {
+            when_then_expr,
+            else_expr,
+        } => Ok(Expr::Case {
+            when_then_expr: when_then_expr
+                .iter()
+                .map(|(when, then)| Ok((rewrite(when)?, rewrite(then)?)))
+                .collect::<Result<Vec<_>>>()?,
+            else_expr: match else_expr {
+                Some(else_expr) => Some(Arc::new(rewrite(else_expr.as_ref())?)),
+                None => None,
+            },
+        }),
+        _ => Ok(expr.clone()),
+    }
+}
+
 /// Convert SQL data type to relational representation of data type
 pub fn convert_data_type(sql: &SQLType) -> Result<DataType> {
     match sql {
@@ -451,7 +2375,11 @@ pub fn convert_data_type(sql: &SQLType) -> Result<DataType> {
         SQLType::BigInt => Ok(DataType::Int64),
         SQLType::Float(_) | SQLType::Real => Ok(DataType::Float64),
         SQLType::Double => Ok(DataType::Float64),
+        SQLType::Decimal(precision, scale) => convert_decimal_type(*precision, *scale),
         SQLType::Char(_) | SQLType::Varchar(_) => Ok(DataType::Utf8),
+        SQLType::Binary(_) | SQLType::Varbinary(_) | SQLType::Bytea => {
+            Ok(DataType::Binary)
+        }
         SQLType::Timestamp => Ok(DataType::Timestamp(TimeUnit::Nanosecond, None)),
         other => Err(ExecutionError::NotImplemented(format!(
             "Unsupported SQL type {:?}",
@@ -460,12 +2388,30 @@ pub fn convert_data_type(sql: &SQLType) -> Result<DataType> {
     }
 }
 
+/// Convert SQL `DECIMAL(p,s)` to a decimal type, where the precision defaults to the
+/// maximum of 38 digits and the scale to 0
+pub fn convert_decimal_type(
+    precision: Option<usize>,
+    scale: Option<usize>,
+) -> Result<DataType> {
+    let precision = precision.unwrap_or(38);
+    let scale = scale.unwrap_or(0);
+    if precision == 0 || precision > 38 || scale > precision {
+        return Err(ExecutionError::General(format!(
+            "Invalid DECIMAL({}, {}): the precision must be between 1 and 38 and not \
+             less than the scale",
+            precision, scale
+        )));
+    }
+    Ok(DataType::Decimal(precision, scale))
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
     use crate::logicalplan::FunctionType;
-    use sqlparser::sqlparser::*;
+    use crate::sql::parser::{DFASTNode, DFParser};
 
     #[test]
     fn select_no_relation() {
@@ -485,6 +2431,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_now_no_relation() -> Result<()> {
+        let planner = SqlToRel::new(MockSchemaProvider {}).with_statement_time(42);
+        let ast = match DFParser::parse_sql("SELECT 1 + 2, NOW()".to_string())? {
+            DFASTNode::ANSI(ast) => ast,
+            _ => panic!("Expected an ANSI SQL statement"),
+        };
+        let plan = planner.sql_to_rel(&ast)?;
+        assert_eq!(
+            "Projection: Int64(1) Plus Int64(2), TimestampNanosecond(42)\
+             \n  EmptyRelation",
+            format!("{:?}", plan)
+        );
+        Ok(())
+    }
+
     #[test]
     fn select_simple_selection() {
         let sql = "SELECT id, first_name, last_name \
@@ -566,20 +2528,81 @@ mod tests {
     }
 
     #[test]
-    fn select_simple_aggregate_with_groupby() {
+    fn select_aggregate_having() {
         quick_test(
-            "SELECT state, MIN(age), MAX(age) FROM person GROUP BY state",
-            "Aggregate: groupBy=[[#4]], aggr=[[MIN(#3), MAX(#3)]]\
-             \n  TableScan: person projection=None",
+            "SELECT state, COUNT(*) FROM person GROUP BY state HAVING COUNT(*) > 5",
+            "Selection: #1 Gt Int64(5)\
+             \n  Aggregate: groupBy=[[#4]], aggr=[[COUNT(UInt8(1))]]\
+             \n    TableScan: person projection=None",
         );
     }
 
     #[test]
-    fn test_wildcard() {
+    fn select_aggregate_having_alias_and_unprojected_aggregate() {
         quick_test(
-            "SELECT * from person",
-            "Projection: #0, #1, #2, #3, #4, #5, #6\
-            \n  TableScan: person projection=None",
+            "SELECT state, MIN(age) AS youngest FROM person GROUP BY state \
+             HAVING youngest > 18 AND MAX(age) < 65",
+            "Projection: #0, #1 AS youngest\
+             \n  Selection: #1 Gt Int64(18) And #2 Lt Int64(65)\
+             \n    Aggregate: groupBy=[[#4]], aggr=[[MIN(#3), MAX(#3)]]\
+             \n      TableScan: person projection=None",
+        );
+    }
+
+    #[test]
+    fn select_aggregate_having_non_aggregate() {
+        let sql = "SELECT state FROM person GROUP BY state HAVING age > 18";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"HAVING references non-aggregate values\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_max_by_aggregate() {
+        quick_test(
+            "SELECT state, max_by(first_name, age), MIN_BY(salary, age) FROM person \
+             GROUP BY state",
+            "Aggregate: groupBy=[[#4]], aggr=[[MAX_BY(#1, #3), MIN_BY(#5, #3)]]\
+             \n  TableScan: person projection=None",
+        );
+    }
+
+    #[test]
+    fn select_bool_aggregate() {
+        quick_test(
+            "SELECT state, EVERY(age > 21), ANY(salary > 1000.0) FROM person GROUP BY state",
+            "Aggregate: groupBy=[[#4]], aggr=[[BOOL_AND(#3 Gt Int64(21)), BOOL_OR(#5 Gt Float64(1000.0))]]\
+             \n  TableScan: person projection=None",
+        );
+    }
+
+    #[test]
+    fn select_bool_aggregate_invalid_argument() {
+        let sql = "SELECT BOOL_AND(age) FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Function 'BOOL_AND' expects a single boolean argument\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_simple_aggregate_with_groupby() {
+        quick_test(
+            "SELECT state, MIN(age), MAX(age) FROM person GROUP BY state",
+            "Aggregate: groupBy=[[#4]], aggr=[[MIN(#3), MAX(#3)]]\
+             \n  TableScan: person projection=None",
+        );
+    }
+
+    #[test]
+    fn test_wildcard() {
+        quick_test(
+            "SELECT * from person",
+            "Projection: #0, #1, #2, #3, #4, #5, #6\
+            \n  TableScan: person projection=None",
         );
     }
 
@@ -607,6 +2630,21 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_cast_to_decimal() {
+        let sql = "SELECT CAST(age AS DECIMAL(10, 2)), CAST(age AS DECIMAL) FROM person";
+        let expected =
+            "Projection: CAST(#3 AS Decimal(10, 2)), CAST(#3 AS Decimal(38, 0))\
+             \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_cast_to_invalid_decimal() {
+        let sql = "SELECT CAST(age AS DECIMAL(40, 2)) FROM person";
+        assert!(logical_plan(sql).is_err());
+    }
+
     #[test]
     fn select_aliased_scalar_func() {
         let sql = "SELECT sqrt(age) AS square_people FROM person";
@@ -633,6 +2671,93 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_order_by_ordinal() {
+        let sql = "SELECT id, age FROM person ORDER BY 2 DESC, 1";
+        let expected = "Sort: #1 DESC, #0 ASC\
+                        \n  Projection: #0, #3\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_order_by_invalid_ordinal() {
+        let sql = "SELECT id, age FROM person ORDER BY 3";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"ORDER BY position 3 is not in the SELECT list\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_order_by_expr_not_projected() {
+        let sql = "SELECT id FROM person ORDER BY sqrt(age) DESC, id";
+        let expected = "Projection: #0\
+                        \n  Sort: #1 DESC, #0 ASC\
+                        \n    Projection: #0, sqrt(CAST(#3 AS Float64))\
+                        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_order_by_nulls() {
+        let sql = "SELECT id, first_name FROM person \
+                   ORDER BY id NULLS FIRST, first_name DESC NULLS LAST";
+        let expected = "Sort: #0 ASC NULLS FIRST, #1 DESC NULLS LAST\
+                        \n  Projection: #0, #1\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // nulls sort as the largest values by default
+        let sql = "SELECT id FROM person ORDER BY id NULLS LAST, id DESC NULLS FIRST";
+        let expected = "Sort: #0 ASC, #0 DESC\
+                        \n  Projection: #0\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_distinct_on() {
+        let sql = "SELECT DISTINCT ON (state) state, id FROM person \
+                   ORDER BY state, age DESC";
+        let expected = "Projection: #0, #1\
+                        \n  Deduplicate: keys=[#0], keep=first\
+                        \n    Sort: #0 ASC, #2 DESC\
+                        \n      Projection: #4, #0, #3\
+                        \n        TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // without ORDER BY, the rows are sorted by the keys
+        let sql = "SELECT DISTINCT ON (state, id) * FROM person";
+        let expected = "Deduplicate: keys=[#4, #0], keep=first\
+                        \n  Sort: #4 ASC, #0 ASC\
+                        \n    Projection: #0, #1, #2, #3, #4, #5, #6\
+                        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_element_of_non_list() {
+        let sql = "SELECT state[1] FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Cannot get an element of a value of type Utf8\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_distinct_on_order_by_mismatch() {
+        let sql = "SELECT DISTINCT ON (state) state, id FROM person ORDER BY id, state";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"SELECT DISTINCT ON expressions must match the leading ORDER BY \
+             expressions\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn select_group_by() {
         let sql = "SELECT state FROM person GROUP BY state";
@@ -642,6 +2767,43 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn select_group_by_alias() {
+        let sql = "SELECT sqrt(age) AS root, COUNT(*) FROM person GROUP BY root";
+        let expected = "Projection: #0 AS root, #1\
+                        \n  Aggregate: groupBy=[[sqrt(CAST(#3 AS Float64))]], aggr=[[COUNT(UInt8(1))]]\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_group_by_position() {
+        quick_test(
+            "SELECT state, COUNT(*) FROM person GROUP BY 1",
+            "Aggregate: groupBy=[[#4]], aggr=[[COUNT(UInt8(1))]]\
+             \n  TableScan: person projection=None",
+        );
+        quick_test(
+            "SELECT COUNT(*), state, age FROM person GROUP BY 3, 2",
+            "Projection: #2, #1, #0\
+             \n  Aggregate: groupBy=[[#3, #4]], aggr=[[COUNT(UInt8(1))]]\
+             \n    TableScan: person projection=None",
+        );
+
+        let err = logical_plan("SELECT state, COUNT(*) FROM person GROUP BY 3")
+            .expect_err("query should have failed");
+        assert_eq!(
+            "General(\"GROUP BY position 3 is not in the SELECT list\")",
+            format!("{:?}", err)
+        );
+        let err = logical_plan("SELECT state, COUNT(*) FROM person GROUP BY 2")
+            .expect_err("query should have failed");
+        assert_eq!(
+            "General(\"GROUP BY position 2 refers to an aggregate expression\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn select_7480_1() {
         let sql = "SELECT c1, MIN(c12) FROM aggregate_test_100 GROUP BY c1, c13";
@@ -662,15 +2824,580 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_join() {
+        let sql = "SELECT id, order_id FROM person JOIN orders ON id = customer_id";
+        let expected = "Projection: #0, #7\
+                        \n  Join: type=Inner, on=[#0 = #1]\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_join_qualified_with_filter() {
+        let sql = "SELECT person.id, orders.qty FROM person \
+                   JOIN orders ON orders.customer_id = person.id AND person.age > 30";
+        let expected = "Projection: #0, #10\
+                        \n  Selection: #3 Gt Int64(30)\
+                        \n    Join: type=Inner, on=[#0 = #1]\
+                        \n      TableScan: person projection=None\
+                        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_quoted_identifiers() {
+        let sql = "SELECT \"first_name\" AS \"First Name\" FROM \"person\"";
+        let expected = "Projection: #1 AS First Name\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_self_join() {
+        let sql = "SELECT a.first_name, b.first_name FROM person a \
+                   JOIN person b ON a.id = b.id WHERE a.age > b.age \
+                   ORDER BY b.first_name";
+        let expected = "Sort: #1 ASC\
+                        \n  Projection: #1, #8\
+                        \n    Selection: #3 Gt #10\
+                        \n      Join: type=Inner, on=[#0 = #0]\
+                        \n        TableScan: person projection=None\
+                        \n        TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_self_join_ambiguous_column() {
+        let sql = "SELECT first_name FROM person a JOIN person b ON a.id = b.id";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Ambiguous reference to column 'first_name'\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT id FROM person JOIN person ON id = id";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Relation name 'person' is specified more than once, use an alias \
+             to distinguish the relations\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_qualified_wildcard() {
+        let sql = "SELECT orders.*, person.id FROM person \
+                   JOIN orders ON orders.customer_id = person.id";
+        let expected = "Projection: #7, #8, #9, #10, #11, #0\
+                        \n  Join: type=Inner, on=[#0 = #1]\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_qualified_wildcard_of_unknown_relation() {
+        let sql = "SELECT lineitem.* FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Invalid relation 'lineitem' in 'lineitem.*'\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_qualified_column_of_unknown_relation() {
+        let sql = "SELECT lineitem.id FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(format!("{:?}", err).starts_with(
+            "ExecutionError(\"Invalid identifier 'lineitem.id' for schema "
+        ));
+    }
+
+    #[test]
+    fn select_left_join() {
+        let sql = "SELECT id, order_id FROM person LEFT JOIN orders ON id = customer_id";
+        let expected = "Projection: #0, #7\
+                        \n  Join: type=Left, on=[#0 = #1]\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_right_join() {
+        let sql = "SELECT id, order_id FROM person RIGHT JOIN orders ON id = customer_id";
+        let expected = "Projection: #0, #7\
+                        \n  Join: type=Right, on=[#0 = #1]\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_full_join() {
+        let sql =
+            "SELECT id, order_id FROM person FULL OUTER JOIN orders ON id = customer_id";
+        let expected = "Projection: #0, #7\
+                        \n  Join: type=Full, on=[#0 = #1]\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_left_join_with_filter() {
+        let sql =
+            "SELECT id FROM person LEFT JOIN orders ON id = customer_id AND age > 30";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        match err {
+            ExecutionError::NotImplemented(_) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn select_join_using() {
+        let sql = "SELECT order_id, o_item, l_item FROM orders \
+                   JOIN lineitem USING (order_id)";
+        let expected = "Projection: #0, #2, #5\
+                        \n  Projection: #0, #1, #2, #3, #4, #6, #7\
+                        \n    Join: type=Inner, on=[#0 = #0]\
+                        \n      TableScan: orders projection=None\
+                        \n      TableScan: lineitem projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_natural_join_qualified() {
+        let sql = "SELECT lineitem.order_id, lineitem.l_qty FROM orders \
+                   NATURAL JOIN lineitem";
+        let expected = "Projection: #0, #6\
+                        \n  Projection: #0, #1, #2, #3, #4, #6, #7\
+                        \n    Join: type=Inner, on=[#0 = #0]\
+                        \n      TableScan: orders projection=None\
+                        \n      TableScan: lineitem projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_right_join_using() {
+        let sql = "SELECT order_id, qty FROM orders RIGHT JOIN lineitem USING (order_id)";
+        let expected = "Projection: #0, #3\
+                        \n  Projection: #5, #1, #2, #3, #4, #6, #7\
+                        \n    Join: type=Right, on=[#0 = #0]\
+                        \n      TableScan: orders projection=None\
+                        \n      TableScan: lineitem projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_full_join_using() {
+        let sql = "SELECT order_id FROM orders FULL JOIN lineitem USING (order_id)";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        match err {
+            ExecutionError::NotImplemented(_) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn select_join_without_equality() {
+        let sql = "SELECT id FROM person JOIN orders ON age > qty";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        match err {
+            ExecutionError::NotImplemented(_) => {}
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn select_cross_join() {
+        let sql = "SELECT id, order_id FROM person CROSS JOIN orders";
+        let expected = "Projection: #0, #7\
+                        \n  CrossJoin:\
+                        \n    TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_implicit_cross_join() {
+        let sql = "SELECT person.id, order_id FROM person, orders \
+                   WHERE person.id = orders.customer_id";
+        let expected = "Projection: #0, #7\
+                        \n  Selection: #0 Eq #8\
+                        \n    CrossJoin:\
+                        \n      TableScan: person projection=None\
+                        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_union_all() {
+        let sql = "SELECT id, first_name FROM person \
+                   UNION ALL SELECT id, last_name FROM person WHERE age > 20 \
+                   UNION ALL SELECT customer_id, o_item FROM orders \
+                   ORDER BY id LIMIT 10";
+        let expected = "Limit: UInt32(10)\
+                        \n  Sort: #0 ASC\
+                        \n    Union:\
+                        \n      Projection: #0, #1\
+                        \n        TableScan: person projection=None\
+                        \n      Projection: #0, #2\
+                        \n        Selection: #3 Gt Int64(20)\
+                        \n          TableScan: person projection=None\
+                        \n      Projection: #1, #2\
+                        \n        TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_union_all_column_count_mismatch() {
+        let sql = "SELECT id, first_name FROM person UNION ALL SELECT id FROM person";
+        assert!(logical_plan(sql).is_err());
+    }
+
+    #[test]
+    fn select_from_values() {
+        let sql = "SELECT t.name FROM (VALUES (1, 'a'), (2, 'b')) AS t(id, name) \
+                   WHERE id > 1";
+        let expected = "Projection: #1\
+                        \n  Selection: #0 Gt Int64(1)\
+                        \n    Union:\
+                        \n      Projection: Int64(1) AS id, Utf8(\"a\") AS name\
+                        \n        EmptyRelation\
+                        \n      Projection: Int64(2) AS id, Utf8(\"b\") AS name\
+                        \n        EmptyRelation";
+        quick_test(sql, expected);
+
+        // columns are named column1, column2 and so on without column names
+        let plan = logical_plan("SELECT column2 FROM (VALUES (1, 2 * 3))").unwrap();
+        assert_eq!(
+            "Projection: #1\
+             \n  Projection: Int64(1) AS column1, Int64(2) Multiply Int64(3) AS column2\
+             \n    EmptyRelation",
+            format!("{:?}", plan)
+        );
+    }
+
+    #[test]
+    fn select_from_values_invalid() {
+        // rows with different numbers of values
+        assert!(logical_plan("SELECT * FROM (VALUES (1, 2), (3)) AS t").is_err());
+        // fewer column names than values
+        assert!(logical_plan("SELECT * FROM (VALUES (1, 2)) AS t(a)").is_err());
+        // values must not reference columns
+        assert!(logical_plan("SELECT * FROM (VALUES (id)) AS t").is_err());
+    }
+
+    #[test]
+    fn select_union_distinct() {
+        let sql = "SELECT id FROM person UNION ALL SELECT customer_id FROM orders \
+                   UNION SELECT order_id FROM orders";
+        let expected = "Aggregate: groupBy=[[#0]], aggr=[[]]\
+                        \n  Union:\
+                        \n    Projection: #0\
+                        \n      TableScan: person projection=None\
+                        \n    Projection: #1\
+                        \n      TableScan: orders projection=None\
+                        \n    Projection: #0\
+                        \n      TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_intersect_except() {
+        // INTERSECT is evaluated before EXCEPT
+        let sql = "SELECT id FROM person EXCEPT SELECT customer_id FROM orders \
+                   INTERSECT DISTINCT SELECT order_id FROM lineitem";
+        let expected = "Join: type=LeftAnti, on=[#0 = #0], null_equals_null\
+                        \n  Aggregate: groupBy=[[#0]], aggr=[[]]\
+                        \n    Projection: #0\
+                        \n      TableScan: person projection=None\
+                        \n  Join: type=LeftSemi, on=[#0 = #0], null_equals_null\
+                        \n    Aggregate: groupBy=[[#0]], aggr=[[]]\
+                        \n      Projection: #1\
+                        \n        TableScan: orders projection=None\
+                        \n    Projection: #0\
+                        \n      TableScan: lineitem projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_intersect_all_not_supported() {
+        let sql = "SELECT id FROM person INTERSECT ALL SELECT customer_id FROM orders";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "NotImplemented(\"INTERSECT ALL is not supported\")",
+            format!("{:?}", err)
+        );
+    }
+
     fn logical_plan(sql: &str) -> Result<LogicalPlan> {
-        use sqlparser::dialect::*;
-        let dialect = GenericSqlDialect {};
         let planner = SqlToRel::new(MockSchemaProvider {});
-        let ast = Parser::parse_sql(&dialect, sql.to_string()).unwrap();
-        planner.sql_to_rel(&ast)
+        match DFParser::parse_sql(sql.to_string()).unwrap() {
+            DFASTNode::ANSI(ast) => planner.sql_to_rel(&ast),
+            DFASTNode::SetOperation {
+                body,
+                order_by,
+                limit,
+            } => planner.set_operation_to_rel(&body, &order_by, &limit),
+            _ => panic!("Expected an ANSI SQL statement"),
+        }
     }
 
     /// Create logical plan, write with formatter, compare to expected output
+    #[test]
+    fn select_from_derived_table() {
+        let sql = "SELECT sub.id, name \
+                   FROM (SELECT id, first_name AS name FROM person WHERE age > 20) AS sub \
+                   WHERE sub.id > 5";
+        let expected = "Projection: #0, #1\
+                        \n  Selection: #0 Gt Int64(5)\
+                        \n    Projection: #0, #1 AS name\
+                        \n      Selection: #3 Gt Int64(20)\
+                        \n        TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_from_derived_table_join() {
+        let sql = "SELECT p.id, o.qty \
+                   FROM (SELECT id, age FROM person) AS p \
+                   JOIN orders AS o ON p.id = o.customer_id";
+        let expected = "Projection: #0, #5\
+                        \n  Join: type=Inner, on=[#0 = #1]\
+                        \n    Projection: #0, #3\
+                        \n      TableScan: person projection=None\
+                        \n    TableScan: orders projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_where_in_subquery() {
+        let sql = "SELECT id FROM person \
+                   WHERE id IN (SELECT customer_id FROM orders WHERE qty = person.age)";
+        let expected = "Projection: #0\
+                        \n  Selection: #0 IN (Projection: #1 <- Selection: #3 Eq outer#3 \
+                        <- TableScan: orders projection=None)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql =
+            "SELECT id FROM person WHERE id NOT IN (SELECT customer_id FROM orders)";
+        let expected = "Projection: #0\
+                        \n  Selection: #0 NOT IN (Projection: #1 \
+                        <- TableScan: orders projection=None)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_string_concat_and_modulus() {
+        let sql = "SELECT first_name || ' ' || last_name, 'age: ' || age % 10 + 1 \
+                   FROM person WHERE first_name || '|' = 'a||'";
+        let expected = "Projection: #1 StringConcat Utf8(\" \") StringConcat #2, \
+                        Utf8(\"age: \") StringConcat #3 Modulus Int64(10) Plus Int64(1)\
+                        \n  Selection: #1 StringConcat Utf8(\"|\") Eq Utf8(\"a||\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_between() {
+        let sql = "SELECT id FROM person \
+                   WHERE age BETWEEN 21 AND 65 AND state = 'CO' \
+                   AND salary NOT BETWEEN (1 + 1) AND 10 * 10";
+        let expected = "Projection: #0\
+                        \n  Selection: #3 BETWEEN Int64(21) AND Int64(65) \
+                        And #4 Eq Utf8(\"CO\") \
+                        And #5 NOT BETWEEN Int64(1) Plus Int64(1) \
+                        AND Int64(10) Multiply Int64(10)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_like() {
+        let sql = "SELECT id FROM person WHERE first_name ILIKE 'j%' \
+                   AND last_name NOT LIKE '%!_%' ESCAPE '!' \
+                   OR state NOT ILIKE first_name || '%' ESCAPE ''";
+        let expected = "Projection: #0\
+                        \n  Selection: #1 ILIKE Utf8(\"j%\") \
+                        And #2 NOT LIKE Utf8(\"%!_%\") ESCAPE '!' \
+                        Or #4 NOT ILIKE #1 StringConcat Utf8(\"%\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // the escape must be a single character
+        let sql = "SELECT id FROM person WHERE first_name LIKE 'j%' ESCAPE 'ab'";
+        assert!(logical_plan(sql).is_err());
+    }
+
+    #[test]
+    fn select_regex_match() {
+        let sql = "SELECT id FROM person WHERE first_name ~ '^J' \
+                   AND last_name !~* 'x' || 'y' \
+                   OR state ~* 'c!~' AND state!~'a' AND age != 1";
+        let expected = "Projection: #0\
+                        \n  Selection: #1 RegexMatch Utf8(\"^J\") \
+                        And #2 RegexNotIMatch Utf8(\"x\") StringConcat Utf8(\"y\") \
+                        Or #4 RegexIMatch Utf8(\"c!~\") \
+                        And #4 RegexNotMatch Utf8(\"a\") And #3 NotEq Int64(1)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // the operators need two operands
+        assert!(logical_plan("SELECT id FROM person WHERE ~ 'a'").is_err());
+        assert!(logical_plan("SELECT id FROM person WHERE state ~").is_err());
+    }
+
+    #[test]
+    fn select_null_and_coalesce() {
+        let sql = "SELECT COALESCE(NULL, age, 0) FROM person WHERE state <> NULL";
+        let expected = "Projection: CASE WHEN Null IS NOT NULL THEN Null \
+                        WHEN #3 IS NOT NULL THEN #3 ELSE Int64(0) END\
+                        \n  Selection: #4 NotEq Null\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // the type of NULL is that of the other results
+        let plan = logical_plan(sql).unwrap();
+        assert_eq!(plan.schema().field(0).data_type(), &DataType::Int64);
+    }
+
+    #[test]
+    fn select_in_list() {
+        let sql = "SELECT id FROM person \
+                   WHERE state IN ('CO', 'CA') AND age NOT IN (21, 1 + 1)";
+        let expected = "Projection: #0\
+                        \n  Selection: #4 IN (Utf8(\"CO\"), Utf8(\"CA\")) \
+                        And #3 NOT IN (Int64(21), Int64(1) Plus Int64(1))\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_case() {
+        let sql = "SELECT CASE WHEN age > 21 THEN 'adult' ELSE 'minor' END FROM person";
+        let expected = "Projection: CASE WHEN #3 Gt Int64(21) THEN Utf8(\"adult\") \
+                        ELSE Utf8(\"minor\") END\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // the simple form is planned as a searched CASE expression
+        let sql = "SELECT CASE state WHEN 'CO' THEN 1 WHEN 'CA' THEN 2 END FROM person";
+        let expected = "Projection: CASE WHEN #4 Eq Utf8(\"CO\") THEN Int64(1) \
+                        WHEN #4 Eq Utf8(\"CA\") THEN Int64(2) END\
+                        \n  TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_window_functions() {
+        let sql = "SELECT id, RANK() OVER (PARTITION BY state ORDER BY age DESC) AS r, \
+                   ROW_NUMBER() OVER (ORDER BY id) FROM person ORDER BY r";
+        let expected = "Sort: #1 ASC\
+                        \n  Projection: #0, #7 AS r, #8\
+                        \n    Window: windowExpr=[[RANK() OVER (PARTITION BY #4 ORDER BY #3 DESC), \
+                        ROW_NUMBER() OVER (ORDER BY #0 ASC)]]\
+                        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql =
+            "SELECT state, RANK() OVER (ORDER BY COUNT(*)) FROM person GROUP BY state";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Window functions are not supported in aggregate queries\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_value_window_functions() {
+        let sql = "SELECT LAG(age) OVER (ORDER BY id), \
+                   LEAD(age, 2, 0) OVER (PARTITION BY state ORDER BY id), \
+                   NTILE(4) OVER (ORDER BY age) FROM person";
+        let expected = "Projection: #7, #8, #9\
+                        \n  Window: windowExpr=[[LAG(#3, Int64(1)) OVER (ORDER BY #0 ASC), \
+                        LEAD(#3, Int64(2), CAST(Int64(0) AS Int32)) OVER (PARTITION BY #4 \
+                        ORDER BY #0 ASC), NTILE(Int64(4)) OVER (ORDER BY #3 ASC)]]\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT NTILE(0) OVER (ORDER BY age) FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"The number of buckets of NTILE must be positive\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT LAG(age, id) OVER (ORDER BY age) FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert!(format!("{:?}", err).contains("expects an integer literal"));
+    }
+
+    #[test]
+    fn select_window_frame() {
+        let sql = "SELECT id, SUM(age) OVER (PARTITION BY state ORDER BY id \
+                   ROWS BETWEEN 3 PRECEDING AND CURRENT ROW) FROM person";
+        let expected = "Projection: #0, #7\
+                        \n  Window: windowExpr=[[SUM(#3) OVER (PARTITION BY #4 ORDER BY #0 ASC \
+                        ROWS BETWEEN 3 PRECEDING AND CURRENT ROW)]]\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        // a frame without BETWEEN ends at the current row
+        let sql =
+            "SELECT MAX(age) OVER (ORDER BY id ROWS UNBOUNDED PRECEDING) FROM person";
+        let expected = "Projection: #7\
+                        \n  Window: windowExpr=[[MAX(#3) OVER (ORDER BY #0 ASC \
+                        ROWS BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW)]]\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT SUM(age) OVER (ORDER BY id ROWS BETWEEN CURRENT ROW AND 1 PRECEDING) \
+                   FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "General(\"Invalid window frame BETWEEN CURRENT ROW AND 1 PRECEDING\")",
+            format!("{:?}", err)
+        );
+    }
+
+    #[test]
+    fn select_where_exists() {
+        let sql = "SELECT id FROM person \
+                   WHERE EXISTS (SELECT * FROM orders WHERE customer_id = id)";
+        let expected = "Projection: #0\
+                        \n  Selection: EXISTS (Projection: #0, #1, #2, #3, #4 \
+                        <- Selection: #1 Eq outer#0 <- TableScan: orders projection=None)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT id FROM person WHERE age > 21 AND NOT EXISTS \
+                   (SELECT order_id FROM orders WHERE customer_id = person.id)";
+        let expected = "Projection: #0\
+                        \n  Selection: #3 Gt Int64(21) And NOT EXISTS (Projection: #0 \
+                        <- Selection: #1 Eq outer#0 <- TableScan: orders projection=None)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_where_scalar_subquery() {
+        let sql = "SELECT first_name FROM person \
+                   WHERE salary > (SELECT MAX(price) FROM orders WHERE customer_id = id)";
+        let expected = "Projection: #1\
+                        \n  Selection: #5 Gt (Aggregate: groupBy=[[]], aggr=[[MAX(#4)]] \
+                        <- Selection: #1 Eq outer#0 <- TableScan: orders projection=None)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
     fn quick_test(sql: &str, expected: &str) {
         let plan = logical_plan(sql).unwrap();
         assert_eq!(expected, format!("{:?}", plan));
@@ -709,6 +3436,18 @@ mod tests {
                     Field::new("c12", DataType::Float64, false),
                     Field::new("c13", DataType::Utf8, false),
                 ]))),
+                "orders" => Some(Arc::new(Schema::new(vec![
+                    Field::new("order_id", DataType::UInt32, false),
+                    Field::new("customer_id", DataType::UInt32, false),
+                    Field::new("o_item", DataType::Utf8, false),
+                    Field::new("qty", DataType::Int32, false),
+                    Field::new("price", DataType::Float64, false),
+                ]))),
+                "lineitem" => Some(Arc::new(Schema::new(vec![
+                    Field::new("order_id", DataType::UInt32, false),
+                    Field::new("l_item", DataType::Utf8, false),
+                    Field::new("l_qty", DataType::Int32, false),
+                ]))),
                 _ => None,
             }
         }
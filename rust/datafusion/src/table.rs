@@ -22,6 +22,8 @@ use crate::arrow::record_batch::RecordBatch;
 use crate::error::Result;
 use crate::execution::context::ExecutionContext;
 use crate::logicalplan::{Expr, LogicalPlan};
+use serde_json::map::Map as JsonMap;
+use serde_json::Value;
 use std::sync::Arc;
 
 /// Table is an abstraction of a logical query plan
@@ -45,6 +47,11 @@ pub trait Table {
     /// limit the number of rows
     fn limit(&self, n: u32) -> Result<Arc<dyn Table>>;
 
+    /// Remove the rows whose keys are equal to those of the previous row, keeping the
+    /// first or last row of each run. The rows must be sorted by the keys to remove all
+    /// duplicates.
+    fn deduplicate(&self, keys: Vec<Expr>, keep_first: bool) -> Result<Arc<dyn Table>>;
+
     /// Return the logical plan
     fn to_logical_plan(&self) -> LogicalPlan;
 
@@ -72,4 +79,12 @@ pub trait Table {
         ctx: &mut ExecutionContext,
         batch_size: usize,
     ) -> Result<Vec<RecordBatch>>;
+
+    /// Collects the result as a vector of JSON objects with a key for each column,
+    /// such as to return the rows of a query from a web service.
+    fn to_json_rows(
+        &self,
+        ctx: &mut ExecutionContext,
+        batch_size: usize,
+    ) -> Result<Vec<JsonMap<String, Value>>>;
 }
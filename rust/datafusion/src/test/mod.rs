@@ -22,7 +22,7 @@ use crate::execution::context::ExecutionContext;
 use crate::execution::physical_plan::ExecutionPlan;
 use crate::logicalplan::{Expr, LogicalPlan, LogicalPlanBuilder};
 use arrow::array;
-use arrow::datatypes::{DataType, Field, Schema};
+use arrow::datatypes::{DataType, Field, IntervalUnit, Schema};
 use arrow::record_batch::RecordBatch;
 use std::env;
 use std::fs::File;
@@ -115,7 +115,19 @@ pub fn format_batch(batch: &RecordBatch) -> Vec<String> {
                 s.push(',');
             }
             let array = batch.column(column_index);
+            if array.is_null(row_index) {
+                s.push_str("NULL");
+                continue;
+            }
             match array.data_type() {
+                DataType::Boolean => s.push_str(&format!(
+                    "{:?}",
+                    array
+                        .as_any()
+                        .downcast_ref::<array::BooleanArray>()
+                        .unwrap()
+                        .value(row_index)
+                )),
                 DataType::Int8 => s.push_str(&format!(
                     "{:?}",
                     array
@@ -196,6 +208,34 @@ pub fn format_batch(batch: &RecordBatch) -> Vec<String> {
                         .unwrap()
                         .value(row_index)
                 )),
+                DataType::Utf8 => s.push_str(
+                    array
+                        .as_any()
+                        .downcast_ref::<array::StringArray>()
+                        .unwrap()
+                        .value(row_index),
+                ),
+                DataType::Decimal(_, _) => s.push_str(
+                    &array
+                        .as_any()
+                        .downcast_ref::<array::Decimal128Array>()
+                        .unwrap()
+                        .value_as_string(row_index),
+                ),
+                DataType::Interval(IntervalUnit::YearMonth) => s.push_str(
+                    &array
+                        .as_any()
+                        .downcast_ref::<array::IntervalYearMonthArray>()
+                        .unwrap()
+                        .value_as_iso8601(row_index),
+                ),
+                DataType::Interval(IntervalUnit::DayTime) => s.push_str(
+                    &array
+                        .as_any()
+                        .downcast_ref::<array::IntervalDayTimeArray>()
+                        .unwrap()
+                        .value_as_iso8601(row_index),
+                ),
                 _ => s.push('?'),
             }
         }
@@ -15,16 +15,18 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Utilities for printing record batches
+//! Utilities for printing and comparing record batches
 
-use arrow::array;
-use arrow::datatypes::{DataType, TimeUnit};
+use arrow::array::{self, ArrayRef};
+use arrow::datatypes::{DataType, IntervalUnit, Schema, TimeUnit};
 use arrow::record_batch::RecordBatch;
+use chrono::{FixedOffset, NaiveDateTime, TimeZone};
 
 use prettytable::format;
 use prettytable::{Cell, Row, Table};
 
 use crate::error::{ExecutionError, Result};
+use crate::execution::physical_plan::datetime_expressions::parse_timezone;
 
 ///! Print a series of record batches to stdout
 pub fn print_batches(results: &Vec<RecordBatch>) -> Result<()> {
@@ -74,7 +76,20 @@ macro_rules! make_string {
     }};
 }
 
-/// Get the value at the given row in an array as a string
+/// Format an instant in UTC as RFC 3339 in a time zone, or in UTC if the time zone is
+/// not supported
+fn datetime_to_string(datetime: Option<NaiveDateTime>, tz: &str) -> Result<String> {
+    let offset = parse_timezone(tz).unwrap_or_else(|_| FixedOffset::east(0));
+    match datetime {
+        Some(datetime) => Ok(offset.from_utc_datetime(&datetime).to_rfc3339()),
+        None => Err(ExecutionError::General(
+            "Timestamp is out of range".to_string(),
+        )),
+    }
+}
+
+/// Get the value at the given row in an array as a string. Timestamps with a time zone
+/// are formatted as RFC 3339 in their time zone.
 pub fn array_value_to_string(column: array::ArrayRef, row: usize) -> Result<String> {
     match column.data_type() {
         DataType::Utf8 => Ok(column
@@ -83,6 +98,19 @@ pub fn array_value_to_string(column: array::ArrayRef, row: usize) -> Result<Stri
             .unwrap()
             .value(row)
             .to_string()),
+        DataType::Binary => Ok(column
+            .as_any()
+            .downcast_ref::<array::BinaryArray>()
+            .unwrap()
+            .value(row)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()),
+        DataType::Decimal(_, _) => Ok(column
+            .as_any()
+            .downcast_ref::<array::Decimal128Array>()
+            .unwrap()
+            .value_as_string(row)),
         DataType::Boolean => make_string!(array::BooleanArray, column, row),
         DataType::Int16 => make_string!(array::Int16Array, column, row),
         DataType::Int32 => make_string!(array::Int32Array, column, row),
@@ -94,6 +122,31 @@ pub fn array_value_to_string(column: array::ArrayRef, row: usize) -> Result<Stri
         DataType::Float16 => make_string!(array::Float32Array, column, row),
         DataType::Float32 => make_string!(array::Float32Array, column, row),
         DataType::Float64 => make_string!(array::Float64Array, column, row),
+        DataType::Timestamp(unit, Some(tz)) => {
+            let datetime = match unit {
+                TimeUnit::Second => column
+                    .as_any()
+                    .downcast_ref::<array::TimestampSecondArray>()
+                    .unwrap()
+                    .value_as_datetime(row),
+                TimeUnit::Millisecond => column
+                    .as_any()
+                    .downcast_ref::<array::TimestampMillisecondArray>()
+                    .unwrap()
+                    .value_as_datetime(row),
+                TimeUnit::Microsecond => column
+                    .as_any()
+                    .downcast_ref::<array::TimestampMicrosecondArray>()
+                    .unwrap()
+                    .value_as_datetime(row),
+                TimeUnit::Nanosecond => column
+                    .as_any()
+                    .downcast_ref::<array::TimestampNanosecondArray>()
+                    .unwrap()
+                    .value_as_datetime(row),
+            };
+            datetime_to_string(datetime, tz)
+        }
         DataType::Timestamp(unit, _) if *unit == TimeUnit::Second => {
             make_string!(array::TimestampSecondArray, column, row)
         }
@@ -120,6 +173,33 @@ pub fn array_value_to_string(column: array::ArrayRef, row: usize) -> Result<Stri
         DataType::Time64(unit) if *unit == TimeUnit::Nanosecond => {
             make_string!(array::Time64NanosecondArray, column, row)
         }
+        DataType::Interval(IntervalUnit::YearMonth) => Ok(column
+            .as_any()
+            .downcast_ref::<array::IntervalYearMonthArray>()
+            .unwrap()
+            .value_as_iso8601(row)),
+        DataType::Interval(IntervalUnit::DayTime) => Ok(column
+            .as_any()
+            .downcast_ref::<array::IntervalDayTimeArray>()
+            .unwrap()
+            .value_as_iso8601(row)),
+        DataType::List(_) => {
+            let list = column
+                .as_any()
+                .downcast_ref::<array::ListArray>()
+                .unwrap()
+                .value(row);
+            let values = (0..list.len())
+                .map(|i| {
+                    if list.is_null(i) {
+                        Ok("NULL".to_string())
+                    } else {
+                        array_value_to_string(list.clone(), i)
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(format!("[{}]", values.join(", ")))
+        }
         _ => Err(ExecutionError::ExecutionError(format!(
             "Unsupported {:?} type for repl.",
             column.data_type()
@@ -127,13 +207,179 @@ pub fn array_value_to_string(column: array::ArrayRef, row: usize) -> Result<Stri
     }
 }
 
+/// The maximum number of differing rows listed by `diff_batches`
+pub const MAX_DIFF_ROWS: usize = 10;
+
+/// Get the value at the given row in an array as a string, or `None` if it is null
+fn optional_value_to_string(column: &ArrayRef, row: usize) -> Result<Option<String>> {
+    if column.is_null(row) {
+        Ok(None)
+    } else {
+        array_value_to_string(column.clone(), row).map(Some)
+    }
+}
+
+/// Get the rows at which two arrays differ. The values are compared as strings, so that
+/// arrays of any type supported by `array_value_to_string` can be compared, and null
+/// values are only equal to null values. The rows past the end of the shorter array
+/// differ.
+pub fn diff_arrays(expected: &ArrayRef, actual: &ArrayRef) -> Result<Vec<usize>> {
+    let mut rows = Vec::new();
+    for row in 0..expected.len().max(actual.len()) {
+        if row >= expected.len()
+            || row >= actual.len()
+            || optional_value_to_string(expected, row)?
+                != optional_value_to_string(actual, row)?
+        {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+/// Get the values of the rows of a series of record batches as strings
+fn batch_rows(batches: &[RecordBatch]) -> Result<Vec<Vec<Option<String>>>> {
+    let mut rows = Vec::new();
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            rows.push(
+                batch
+                    .columns()
+                    .iter()
+                    .map(|column| optional_value_to_string(column, row))
+                    .collect::<Result<Vec<_>>>()?,
+            );
+        }
+    }
+    Ok(rows)
+}
+
+/// The names and types of the columns of a schema
+fn schema_columns(schema: &Schema) -> Vec<String> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| format!("{}: {:?}", field.name(), field.data_type()))
+        .collect()
+}
+
+/// Compare two series of record batches row by row, regardless of how their rows are
+/// split into batches. Returns `None` if they have the same columns and values, or a
+/// report of the differences otherwise. The report lists the first `MAX_DIFF_ROWS`
+/// differing rows as a table with the expected and the actual values of each, where a
+/// row that only exists on one side has a single line.
+pub fn diff_batches(
+    expected: &[RecordBatch],
+    actual: &[RecordBatch],
+) -> Result<Option<String>> {
+    let expected_schema = expected.first().map(|batch| batch.schema());
+    let actual_schema = actual.first().map(|batch| batch.schema());
+    if let (Some(expected_schema), Some(actual_schema)) =
+        (&expected_schema, &actual_schema)
+    {
+        let expected_columns = schema_columns(expected_schema);
+        let actual_columns = schema_columns(actual_schema);
+        if expected_columns != actual_columns {
+            return Ok(Some(format!(
+                "expected columns {:?}, found columns {:?}",
+                expected_columns, actual_columns
+            )));
+        }
+    }
+
+    let expected_rows = batch_rows(expected)?;
+    let actual_rows = batch_rows(actual)?;
+    let differing: Vec<usize> = (0..expected_rows.len().max(actual_rows.len()))
+        .filter(|&row| expected_rows.get(row) != actual_rows.get(row))
+        .collect();
+    if differing.is_empty() {
+        return Ok(None);
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+    let mut header = vec![Cell::new("row"), Cell::new("side")];
+    if let Some(schema) = expected_schema.or(actual_schema) {
+        for field in schema.fields() {
+            header.push(Cell::new(field.name()));
+        }
+    }
+    table.set_titles(Row::new(header));
+    for &row in differing.iter().take(MAX_DIFF_ROWS) {
+        let sides = vec![
+            ("expected", expected_rows.get(row)),
+            ("actual", actual_rows.get(row)),
+        ];
+        // the row number is only shown on the first line of each row
+        let mut label = row.to_string();
+        for (side, values) in sides {
+            if let Some(values) = values {
+                let mut cells = vec![Cell::new(&label), Cell::new(side)];
+                for value in values {
+                    cells.push(Cell::new(value.as_ref().map_or("NULL", |v| v.as_str())));
+                }
+                table.add_row(Row::new(cells));
+                label.clear();
+            }
+        }
+    }
+
+    Ok(Some(format!(
+        "expected {} rows, found {} rows, {} rows differ{}:\n{}",
+        expected_rows.len(),
+        actual_rows.len(),
+        differing.len(),
+        if differing.len() > MAX_DIFF_ROWS {
+            format!(" (showing the first {})", MAX_DIFF_ROWS)
+        } else {
+            String::new()
+        },
+        table
+    )))
+}
+
+/// Assert that two series of record batches have the same columns and values, as
+/// compared by `diff_batches`, or panic with a table of the first differing rows.
+///
+/// ```ignore
+/// let actual = ctx.collect(plan.as_ref())?;
+/// assert_batches_eq!(expected, actual);
+/// ```
+#[macro_export]
+macro_rules! assert_batches_eq {
+    ($expected:expr, $actual:expr) => {
+        match $crate::utils::diff_batches(&$expected, &$actual) {
+            Ok(None) => {}
+            Ok(Some(report)) => panic!("record batches are not equal, {}", report),
+            Err(e) => panic!("record batches cannot be compared: {:?}", e),
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use arrow::array;
     use arrow::datatypes::{DataType, Field, Schema};
+    use std::convert::TryFrom;
     use std::sync::Arc;
 
+    #[test]
+    fn timestamp_with_timezone_to_string() -> Result<()> {
+        let noon_utc = 1_590_969_600 + 12 * 3600;
+        let tz = |tz: &str| Some(Arc::new(tz.to_string()));
+        let string = |tz| {
+            let array = array::TimestampSecondArray::from_vec(vec![noon_utc], tz);
+            array_value_to_string(Arc::new(array), 0)
+        };
+        assert_eq!(string(tz("+02:00"))?, "2020-06-01T14:00:00+02:00");
+        assert_eq!(string(tz("UTC"))?, "2020-06-01T12:00:00+00:00");
+        // time zones that are not supported are formatted in UTC
+        assert_eq!(string(tz("Europe/Berlin"))?, "2020-06-01T12:00:00+00:00");
+        assert_eq!(string(None)?, noon_utc.to_string());
+        Ok(())
+    }
+
     #[test]
     fn table() -> Result<()> {
         // define a schema.
@@ -170,4 +416,85 @@ mod tests {
 
         Ok(())
     }
+
+    fn batch(a: Vec<Option<&str>>, b: Vec<i32>) -> Result<RecordBatch> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(array::StringArray::try_from(a)?),
+                Arc::new(array::Int32Array::from(b)),
+            ],
+        )?)
+    }
+
+    #[test]
+    fn diff_arrays_with_nulls() -> Result<()> {
+        let expected: ArrayRef = Arc::new(array::StringArray::try_from(vec![
+            Some("a"),
+            None,
+            Some(""),
+        ])?);
+        let actual: ArrayRef = Arc::new(array::StringArray::try_from(vec![
+            Some("a"),
+            None,
+            None,
+            Some("d"),
+        ])?);
+        // an empty string is not equal to null
+        assert_eq!(diff_arrays(&expected, &actual)?, vec![2, 3]);
+        assert!(diff_arrays(&expected, &expected)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_batches_report() -> Result<()> {
+        let expected = vec![batch(vec![Some("a"), Some("b"), None], vec![1, 2, 3])?];
+        // the rows are compared regardless of how they are split into batches
+        let same = vec![
+            batch(vec![Some("a")], vec![1])?,
+            batch(vec![Some("b"), None], vec![2, 3])?,
+        ];
+        assert_eq!(diff_batches(&expected, &same)?, None);
+        assert_batches_eq!(expected, same);
+
+        let actual = vec![
+            batch(vec![Some("a"), Some("x")], vec![1, 2])?,
+            batch(vec![None, Some("d")], vec![3, 4])?,
+        ];
+        let report = diff_batches(&expected, &actual)?.unwrap();
+        let expected_report = vec![
+            "expected 3 rows, found 4 rows, 2 rows differ:",
+            "+-----+----------+---+---+",
+            "| row | side     | a | b |",
+            "+-----+----------+---+---+",
+            "| 1   | expected | b | 2 |",
+            "|     | actual   | x | 2 |",
+            "| 3   | actual   | d | 4 |",
+            "+-----+----------+---+---+",
+        ];
+        assert_eq!(report.lines().collect::<Vec<_>>(), expected_report);
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let other = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(array::StringArray::from(vec!["a"]))],
+        )?;
+        assert_eq!(
+            diff_batches(&expected, &[other])?.unwrap(),
+            "expected columns [\"a: Utf8\", \"b: Int32\"], found columns [\"a: Utf8\"]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "record batches are not equal")]
+    fn assert_batches_eq_panics() {
+        let expected = vec![batch(vec![Some("a")], vec![1]).unwrap()];
+        let actual = vec![batch(vec![Some("a")], vec![2]).unwrap()];
+        assert_batches_eq!(expected, actual);
+    }
 }
@@ -97,6 +97,38 @@ fn parquet_query() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parquet_query_by_sql() {
+    let mut ctx = ExecutionContext::new();
+    let testdata = env::var("PARQUET_TEST_DATA").expect("PARQUET_TEST_DATA not defined");
+    // the schema is read from the parquet file
+    ctx.sql(
+        &format!(
+            "CREATE EXTERNAL TABLE alltypes_plain STORED AS PARQUET \
+             LOCATION '{}/alltypes_plain.parquet'",
+            testdata
+        ),
+        1024,
+    )
+    .unwrap();
+    let sql = "SELECT id, bool_col FROM alltypes_plain LIMIT 2";
+    let actual = execute(&mut ctx, sql).join("\n");
+    let expected = "4\ttrue\n5\tfalse".to_string();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parquet_binary_query() {
+    let mut ctx = ExecutionContext::new();
+    register_alltypes_parquet(&mut ctx);
+    // string_col is read as binary, which can be cast to a string and back
+    let sql = "SELECT id, string_col, CAST(CAST(string_col AS varchar) AS bytea) \
+               FROM alltypes_plain LIMIT 2";
+    let actual = execute(&mut ctx, sql).join("\n");
+    let expected = "4\t[48]\t[48]\n5\t[49]\t[49]".to_string();
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parquet_single_nan_schema() {
     let mut ctx = ExecutionContext::new();
@@ -114,6 +146,34 @@ fn parquet_single_nan_schema() {
     }
 }
 
+#[test]
+fn parquet_explain_analyze() {
+    let mut ctx = ExecutionContext::new();
+    register_alltypes_parquet(&mut ctx);
+    let sql = "EXPLAIN ANALYZE SELECT id FROM alltypes_plain WHERE id > 5 LIMIT 1";
+    let results = ctx.sql(&sql, DEFAULT_BATCH_SIZE).unwrap();
+    let batch = &results[0];
+    let plan_types = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let lines = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let metrics: Vec<&str> = (0..batch.num_rows())
+        .filter(|i| plan_types.value(*i) == "physical_plan_metrics")
+        .map(|i| lines.value(i))
+        .collect();
+    // the predicate and the limit are applied while the file is read
+    assert_eq!(metrics.len(), 1);
+    assert!(metrics[0].starts_with("ParquetExec: file="));
+    assert!(metrics[0]
+        .contains("alltypes_plain.parquet batches=1 rows_read=8 rows_produced=1"));
+}
+
 #[test]
 fn csv_count_star() {
     let mut ctx = ExecutionContext::new();
@@ -527,6 +587,11 @@ fn result_str(results: &Vec<RecordBatch>) -> Vec<String> {
 
                         str.push_str(&format!("{:?}", s));
                     }
+                    DataType::Binary => {
+                        let array =
+                            column.as_any().downcast_ref::<BinaryArray>().unwrap();
+                        str.push_str(&format!("{:?}", array.value(row_index)));
+                    }
                     _ => str.push_str("???"),
                 }
             }
@@ -28,15 +28,16 @@ use std::vec::Vec;
 
 use arrow::array::{
     ArrayDataBuilder, ArrayDataRef, ArrayRef, BooleanBufferBuilder, BufferBuilderTrait,
-    Int16BufferBuilder, StructArray,
+    Decimal128Array, Int16BufferBuilder, StructArray,
 };
 use arrow::buffer::{Buffer, MutableBuffer};
 use arrow::datatypes::{DataType as ArrowType, Field, IntervalUnit};
 
 use crate::arrow::converter::{
-    BinaryConverter, BoolConverter, Converter, Float32Converter, Float64Converter,
-    Int16Converter, Int32Converter, Int64Converter, Int8Converter, Int96Converter,
-    UInt16Converter, UInt32Converter, UInt64Converter, UInt8Converter, Utf8Converter,
+    BinaryConverter, BoolConverter, Converter, DecimalConverter, Float32Converter,
+    Float64Converter, Int16Converter, Int32Converter, Int64Converter, Int8Converter,
+    Int96Converter, UInt16Converter, UInt32Converter, UInt64Converter, UInt8Converter,
+    Utf8Converter,
 };
 use crate::arrow::record_reader::RecordReader;
 use crate::arrow::schema::parquet_to_arrow_field;
@@ -44,8 +45,8 @@ use crate::basic::{LogicalType, Repetition, Type as PhysicalType};
 use crate::column::page::PageIterator;
 use crate::column::reader::ColumnReaderImpl;
 use crate::data_type::{
-    BoolType, ByteArrayType, DataType, DoubleType, FloatType, Int32Type, Int64Type,
-    Int96Type,
+    BoolType, ByteArrayType, DataType, DoubleType, FixedLenByteArrayType, FloatType,
+    Int32Type, Int64Type, Int96Type,
 };
 use crate::errors::{ParquetError, ParquetError::ArrowError, Result};
 use crate::file::reader::{FilePageIterator, FileReader};
@@ -369,9 +370,12 @@ where
                     cur_data_buf,
                 )?;
 
-            // Fill space
+            // Fill space. A value is present only if its definition level is the
+            // maximum of the column, lower levels are nulls of the column or of any
+            // optional group that it is nested in.
             if levels_read > data_read {
-                def_levels_buffer.iter().for_each(|cur_def_levels_buf| {
+                def_levels_buffer.iter().for_each(|def_levels_buf| {
+                    let cur_def_levels_buf = &def_levels_buf[num_read..];
                     let (mut level_pos, mut data_pos) = (levels_read, data_read);
                     while level_pos > 0 && data_pos > 0 {
                         if cur_def_levels_buf[level_pos - 1]
@@ -422,7 +426,24 @@ where
             data_buffer.into_iter().map(|t| Some(t)).collect()
         };
 
-        C::convert(data)
+        let array = C::convert(data)?;
+        match (array.data_type(), &self.data_type) {
+            // the converter cannot know the precision and scale of the column
+            (ArrowType::Decimal(_, _), ArrowType::Decimal(_, _)) => {
+                let data = array.data();
+                let mut builder = ArrayDataBuilder::new(self.data_type.clone())
+                    .len(data.len())
+                    .offset(data.offset())
+                    .buffers(data.buffers().to_vec());
+                if let Some(null_buffer) = data.null_buffer() {
+                    builder = builder
+                        .null_count(data.null_count())
+                        .null_bit_buffer(null_buffer.clone());
+                }
+                Ok(Arc::new(Decimal128Array::from(builder.build())))
+            }
+            _ => Ok(array),
+        }
     }
 
     fn get_def_levels(&self) -> Option<&[i16]> {
@@ -895,6 +916,14 @@ impl<'a> ArrayReaderBuilder {
                     >::new(
                         page_iterator, column_desc
                     )?))
+                } else if cur_type.get_basic_info().logical_type() == LogicalType::DECIMAL
+                {
+                    Ok(Box::new(ComplexObjectArrayReader::<
+                        ByteArrayType,
+                        DecimalConverter,
+                    >::new(
+                        page_iterator, column_desc
+                    )?))
                 } else {
                     Ok(Box::new(ComplexObjectArrayReader::<
                         ByteArrayType,
@@ -904,6 +933,14 @@ impl<'a> ArrayReaderBuilder {
                     )?))
                 }
             }
+            PhysicalType::FIXED_LEN_BYTE_ARRAY
+                if cur_type.get_basic_info().logical_type() == LogicalType::DECIMAL =>
+            {
+                Ok(Box::new(ComplexObjectArrayReader::<
+                    FixedLenByteArrayType,
+                    DecimalConverter,
+                >::new(page_iterator, column_desc)?))
+            }
             other => Err(ArrowError(format!(
                 "Unable to create primitive array reader for parquet physical type {}",
                 other
@@ -948,8 +985,10 @@ impl<'a> ArrayReaderBuilder {
 #[cfg(test)]
 mod tests {
     use crate::arrow::array_reader::{
-        build_array_reader, ArrayReader, PrimitiveArrayReader, StructArrayReader,
+        build_array_reader, ArrayReader, ComplexObjectArrayReader, PrimitiveArrayReader,
+        StructArrayReader,
     };
+    use crate::arrow::converter::{ArrayRefConverter, FromConverter};
     use crate::basic::{Encoding, Type as PhysicalType};
     use crate::column::page::Page;
     use crate::data_type::{DataType, Int32Type, Int64Type};
@@ -1292,6 +1331,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_complex_object_array_reader_nested_optional() {
+        // the leaf is null at definition level 1 and its parent group at level 0
+        let message_type = "
+        message test_schema {
+            OPTIONAL Group test_mid {
+                OPTIONAL INT32 leaf;
+            }
+        }
+        ";
+
+        let schema = parse_message_type(message_type)
+            .map(|t| Rc::new(SchemaDescriptor::new(Rc::new(t))))
+            .unwrap();
+
+        let column_desc = schema.column(0);
+        assert_eq!(2, column_desc.max_def_level());
+
+        let mut def_levels = Vec::new();
+        let mut values = Vec::new();
+        let mut page_lists = Vec::new();
+        make_column_chuncks::<Int32Type>(
+            column_desc.clone(),
+            Encoding::PLAIN,
+            100,
+            1,
+            200,
+            &mut def_levels,
+            &mut Vec::new(),
+            &mut values,
+            &mut page_lists,
+            true,
+            2,
+        );
+
+        let page_iterator =
+            InMemoryPageIterator::new(schema.clone(), column_desc.clone(), page_lists);
+
+        type Int32ArrayConverter = ArrayRefConverter<
+            Vec<Option<i32>>,
+            PrimitiveArray<ArrowInt32>,
+            FromConverter<Vec<Option<i32>>, PrimitiveArray<ArrowInt32>>,
+        >;
+        let mut array_reader =
+            ComplexObjectArrayReader::<Int32Type, Int32ArrayConverter>::new(
+                Box::new(page_iterator),
+                column_desc.clone(),
+            )
+            .unwrap();
+
+        let mut values = values.into_iter();
+        let expected: Vec<Option<i32>> = def_levels
+            .iter()
+            .map(
+                |def_level| {
+                    if *def_level == 2 {
+                        values.next()
+                    } else {
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        // the second batch spans both column chunks
+        let mut accu_len: usize = 0;
+        for batch_size in &[50, 100, 100] {
+            let array = array_reader.next_batch(*batch_size).unwrap();
+            let array = array
+                .as_any()
+                .downcast_ref::<PrimitiveArray<ArrowInt32>>()
+                .unwrap();
+            assert_eq!(
+                Some(&def_levels[accu_len..(accu_len + array.len())]),
+                array_reader.get_def_levels()
+            );
+            assert_eq!(
+                &PrimitiveArray::<ArrowInt32>::from(
+                    expected[accu_len..(accu_len + array.len())].to_vec()
+                ),
+                array
+            );
+            accu_len += array.len();
+        }
+        assert_eq!(200, accu_len);
+    }
+
     /// Array reader for test.
     struct InMemoryArrayReader {
         data_type: ArrowType,
@@ -213,8 +213,11 @@ mod tests {
     use crate::file::writer::{FileWriter, SerializedFileWriter};
     use crate::schema::parser::parse_message_type;
     use crate::schema::types::TypePtr;
-    use crate::util::test_common::{get_temp_filename, RandGen};
-    use arrow::array::{Array, BooleanArray, StringArray, StructArray};
+    use crate::util::test_common::{self, get_temp_filename, RandGen};
+    use arrow::array::{
+        Array, BinaryArray, BooleanArray, Decimal128Array, StringArray, StructArray,
+    };
+    use arrow::datatypes::DataType as ArrowDataType;
     use arrow::record_batch::RecordBatchReader;
     use serde_json::Value::Array as JArray;
     use std::cmp::min;
@@ -266,6 +269,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_fixed_length_decimals() {
+        for (file_name, precision) in &[
+            ("fixed_length_decimal.parquet", 25),
+            ("fixed_length_decimal_legacy.parquet", 13),
+        ] {
+            let file_reader: Rc<dyn FileReader> = Rc::new(
+                SerializedFileReader::new(test_common::get_test_file(file_name))
+                    .expect("Failed to create serialized reader"),
+            );
+            let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
+            let mut record_batch_reader = arrow_reader
+                .get_record_reader(100)
+                .expect("Failed to read into array!");
+            let batch = record_batch_reader
+                .next_batch()
+                .expect("Failed to read record batch!")
+                .expect("Expected a record batch");
+
+            assert_eq!(
+                &ArrowDataType::Decimal(*precision, 2),
+                batch.schema().field(0).data_type()
+            );
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Decimal128Array>()
+                .unwrap();
+            // the values are 1.00 to 24.00
+            assert_eq!(24, column.len());
+            for i in 0..24 {
+                assert_eq!((i as i128 + 1) * 100, column.value(i));
+            }
+            assert_eq!("24.00", column.value_as_string(23));
+        }
+    }
+
     #[test]
     fn test_bool_single_column_reader_test() {
         let message_type = "
@@ -306,6 +346,48 @@ mod tests {
         >(2, 100, 2, message_type, 15, 50);
     }
 
+    #[test]
+    fn test_binary_single_column_reader_test() {
+        // byte arrays without the UTF8 logical type are read as binary, since they need
+        // not be valid UTF-8
+        let message_type = "
+        message test_schema {
+          REQUIRED BINARY leaf;
+        }
+        ";
+        let schema = parse_message_type(message_type)
+            .map(|t| Rc::new(t))
+            .unwrap();
+        let values: Vec<Vec<u8>> = vec![vec![0xff, 0xfe], vec![0x00], vec![0xc3, 0x28]];
+        let path = get_temp_filename();
+        generate_single_column_file_with_data::<ByteArrayType>(
+            &vec![values.iter().map(|v| v.clone().into()).collect()],
+            path.as_path(),
+            schema,
+        )
+        .unwrap();
+
+        let parquet_reader =
+            SerializedFileReader::try_from(File::open(&path).unwrap()).unwrap();
+        let mut arrow_reader = ParquetFileArrowReader::new(Rc::new(parquet_reader));
+        assert_eq!(
+            &ArrowDataType::Binary,
+            arrow_reader.get_schema().unwrap().field(0).data_type()
+        );
+
+        let mut record_reader = arrow_reader.get_record_reader(1024).unwrap();
+        let batch = record_reader.next_batch().unwrap().unwrap();
+        let column = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!(values.len(), column.len());
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(&value[..], column.value(i));
+        }
+    }
+
     fn single_column_reader_test<T, A, C, G>(
         num_row_groups: usize,
         num_rows: usize,
@@ -0,0 +1,334 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains writer which writes arrow record batches to a parquet file, one row group
+//! per record batch. Only flat schemas are supported, see `arrow_to_parquet_schema`.
+
+use std::rc::Rc;
+
+use arrow::array::*;
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::arrow::schema::arrow_to_parquet_schema;
+use crate::column::writer::ColumnWriter;
+use crate::data_type::{ByteArray, Int96};
+use crate::errors::{ParquetError::ArrowError, Result};
+use crate::file::properties::WriterProperties;
+use crate::file::writer::{FileWriter, ParquetWriter, SerializedFileWriter};
+
+/// The Julian day of the Unix epoch, which INT96 timestamps count days from
+const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
+
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// Writer of arrow record batches to a parquet file
+pub struct ArrowWriter<W: ParquetWriter> {
+    writer: SerializedFileWriter<W>,
+    arrow_schema: SchemaRef,
+}
+
+impl<W: 'static + ParquetWriter> ArrowWriter<W> {
+    /// Create a writer of record batches with the given schema, using the default
+    /// writer properties if none are given
+    pub fn try_new(
+        writer: W,
+        arrow_schema: SchemaRef,
+        props: Option<WriterProperties>,
+    ) -> Result<Self> {
+        let schema = arrow_to_parquet_schema(&arrow_schema)?;
+        let props = props.unwrap_or_else(|| WriterProperties::builder().build());
+        Ok(Self {
+            writer: SerializedFileWriter::new(writer, Rc::new(schema), Rc::new(props))?,
+            arrow_schema,
+        })
+    }
+
+    /// Write a record batch to a new row group. The columns of the batch must have the
+    /// data types of the fields of the schema of the writer. Empty batches are skipped.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        let fields = self.arrow_schema.fields();
+        if batch.num_columns() != fields.len()
+            || batch
+                .columns()
+                .iter()
+                .zip(fields)
+                .any(|(array, field)| array.data_type() != field.data_type())
+        {
+            return Err(ArrowError(
+                "Record batch schema does not match the schema of the writer".to_string(),
+            ));
+        }
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let mut row_group_writer = self.writer.next_row_group()?;
+        for (field, array) in self.arrow_schema.fields().iter().zip(batch.columns()) {
+            let column_writer = match row_group_writer.next_column()? {
+                Some(column_writer) => column_writer,
+                None => return Err(general_err!("Expected a column writer")),
+            };
+            let column_writer =
+                write_column(column_writer, array.as_ref(), field.is_nullable())?;
+            row_group_writer.close_column(column_writer)?;
+        }
+        self.writer.close_row_group(row_group_writer)
+    }
+
+    /// Close the writer, writing the metadata of the file
+    pub fn close(&mut self) -> Result<()> {
+        self.writer.close()
+    }
+}
+
+/// Collect the values of the valid slots of an array, converted to the values of a
+/// column writer
+macro_rules! values {
+    ($ARRAY:expr, $VALID:expr, $ARRAY_TYPE:ident, $CONVERT:expr) => {{
+        let array = $ARRAY
+            .as_any()
+            .downcast_ref::<$ARRAY_TYPE>()
+            .ok_or_else(|| ArrowError("Failed to downcast array".to_string()))?;
+        $VALID
+            .iter()
+            .map(|i| $CONVERT(array.value(*i)))
+            .collect::<Vec<_>>()
+    }};
+}
+
+/// Write the values of an array with the column writer of the parquet type that its
+/// data type converts to
+fn write_column(
+    mut writer: ColumnWriter,
+    array: &dyn Array,
+    nullable: bool,
+) -> Result<ColumnWriter> {
+    // only the values of valid slots are written, with a definition level of 1
+    let valid: Vec<usize> = (0..array.len()).filter(|i| array.is_valid(*i)).collect();
+    let def_levels: Option<Vec<i16>> = if nullable {
+        Some((0..array.len()).map(|i| array.is_valid(i) as i16).collect())
+    } else if valid.len() < array.len() {
+        return Err(ArrowError(
+            "Unable to write null values to a required column".to_string(),
+        ));
+    } else {
+        None
+    };
+    let def_levels = def_levels.as_ref().map(|levels| levels.as_slice());
+    let unsupported = || {
+        Err(ArrowError(format!(
+            "Unable to write arrow type {:?} to parquet",
+            array.data_type()
+        )))
+    };
+
+    match writer {
+        ColumnWriter::BoolColumnWriter(ref mut typed) => {
+            let values = values!(array, valid, BooleanArray, |v| v);
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+            let values = match array.data_type() {
+                DataType::Int8 => values!(array, valid, Int8Array, |v| v as i32),
+                DataType::Int16 => values!(array, valid, Int16Array, |v| v as i32),
+                DataType::Int32 => values!(array, valid, Int32Array, |v| v),
+                DataType::UInt8 => values!(array, valid, UInt8Array, |v| v as i32),
+                DataType::UInt16 => values!(array, valid, UInt16Array, |v| v as i32),
+                DataType::UInt32 => values!(array, valid, UInt32Array, |v| v as i32),
+                DataType::Date32(_) => values!(array, valid, Date32Array, |v| v),
+                _ => return unsupported(),
+            };
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::Int64ColumnWriter(ref mut typed) => {
+            let values = match array.data_type() {
+                DataType::Int64 => values!(array, valid, Int64Array, |v| v),
+                DataType::UInt64 => values!(array, valid, UInt64Array, |v| v as i64),
+                DataType::Timestamp(TimeUnit::Second, _) => {
+                    values!(array, valid, TimestampSecondArray, |v| v * 1000)
+                }
+                DataType::Timestamp(TimeUnit::Millisecond, _) => {
+                    values!(array, valid, TimestampMillisecondArray, |v| v)
+                }
+                DataType::Timestamp(TimeUnit::Microsecond, _) => {
+                    values!(array, valid, TimestampMicrosecondArray, |v| v)
+                }
+                _ => return unsupported(),
+            };
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::Int96ColumnWriter(ref mut typed) => {
+            let values = match array.data_type() {
+                DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+                    values!(array, valid, TimestampNanosecondArray, nanos_to_int96)
+                }
+                _ => return unsupported(),
+            };
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::FloatColumnWriter(ref mut typed) => {
+            let values = values!(array, valid, Float32Array, |v| v);
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::DoubleColumnWriter(ref mut typed) => {
+            let values = values!(array, valid, Float64Array, |v| v);
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::ByteArrayColumnWriter(ref mut typed) => {
+            let values = match array.data_type() {
+                DataType::Utf8 => values!(array, valid, StringArray, ByteArray::from),
+                DataType::Binary => {
+                    values!(array, valid, BinaryArray, |v: &[u8]| ByteArray::from(
+                        v.to_vec()
+                    ))
+                }
+                _ => return unsupported(),
+            };
+            typed.write_batch(&values, def_levels, None)?;
+        }
+        ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed) => {
+            // decimals are stored as 16 byte big-endian two's complement integers
+            let values = match array.data_type() {
+                DataType::Decimal(..) => {
+                    values!(array, valid, Decimal128Array, |v: i128| ByteArray::from(
+                        v.to_be_bytes().to_vec()
+                    ))
+                }
+                _ => return unsupported(),
+            };
+            typed.write_batch(&values, def_levels, None)?;
+        }
+    }
+    Ok(writer)
+}
+
+/// Convert nanoseconds since the epoch to an INT96 timestamp, which stores the
+/// nanoseconds of the day in its first 8 bytes and the Julian day in its last 4 bytes
+fn nanos_to_int96(nanos: i64) -> Int96 {
+    let day = nanos.div_euclid(NANOS_PER_DAY) + JULIAN_DAY_OF_EPOCH;
+    let nanos_of_day = nanos.rem_euclid(NANOS_PER_DAY);
+    let mut value = Int96::new();
+    value.set_data(nanos_of_day as u32, (nanos_of_day >> 32) as u32, day as u32);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use arrow::datatypes::{Field, Schema};
+    use arrow::record_batch::RecordBatchReader;
+
+    use crate::arrow::arrow_reader::{ArrowReader, ParquetFileArrowReader};
+    use crate::file::reader::SerializedFileReader;
+    use crate::util::test_common::get_temp_file;
+
+    #[test]
+    fn test_write_and_read_record_batches() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Float64, true),
+            Field::new("d", DataType::Timestamp(TimeUnit::Millisecond, None), true),
+        ]));
+        let batch = |a: Vec<i32>, b: Vec<Option<&str>>, c: Vec<Option<f64>>| {
+            let d: Vec<i64> = a.iter().map(|v| *v as i64 * 1000).collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(a)),
+                    Arc::new(StringArray::from(b)),
+                    Arc::new(Float64Array::from(c)),
+                    Arc::new(TimestampMillisecondArray::from_vec(d, None)),
+                ],
+            )
+            .unwrap()
+        };
+        let batches = vec![
+            batch(vec![1, 2], vec![Some("one"), None], vec![None, Some(2.5)]),
+            batch(vec![], vec![], vec![]),
+            batch(vec![3], vec![Some("three")], vec![Some(3.5)]),
+        ];
+
+        let file = get_temp_file("test_arrow_writer.parquet", &[]);
+        let mut writer =
+            ArrowWriter::try_new(file.try_clone().unwrap(), schema.clone(), None)
+                .unwrap();
+        for batch in &batches {
+            writer.write(batch).unwrap();
+        }
+        writer.close().unwrap();
+
+        let reader = SerializedFileReader::new(file).unwrap();
+        // the empty batch is skipped
+        assert_eq!(reader.metadata().num_row_groups(), 2);
+        let mut arrow_reader = ParquetFileArrowReader::new(Rc::new(reader));
+        assert_eq!(&arrow_reader.get_schema().unwrap(), schema.as_ref());
+
+        let mut record_reader = arrow_reader.get_record_reader(10).unwrap();
+        let batch = record_reader.next_batch().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert!(record_reader.next_batch().unwrap().is_none());
+        let a = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(
+            (0..3).map(|i| a.value(i)).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let b = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(b.value(0), "one");
+        assert!(b.is_null(1));
+        assert_eq!(b.value(2), "three");
+        let c = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert!(c.is_null(0));
+        assert_eq!(c.value(1), 2.5);
+        let d = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<TimestampMillisecondArray>()
+            .unwrap();
+        assert_eq!(d.value(2), 3000);
+    }
+
+    #[test]
+    fn test_nanos_to_int96() {
+        for nanos in &[0, 1_500_000_000_123_456_789, -1] {
+            let value = nanos_to_int96(*nanos);
+            // the reader converts to milliseconds, truncating towards zero
+            if *nanos >= 0 {
+                assert_eq!(value.to_i64(), nanos / 1_000_000);
+            }
+            let data = value.data();
+            let nanos_of_day = ((data[1] as i64) << 32) + data[0] as i64;
+            let day = data[2] as i64 - JULIAN_DAY_OF_EPOCH;
+            assert_eq!(day * NANOS_PER_DAY + nanos_of_day, *nanos);
+        }
+    }
+}
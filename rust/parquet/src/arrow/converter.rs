@@ -19,7 +19,7 @@ use crate::arrow::record_reader::RecordReader;
 use crate::data_type::{ByteArray, DataType, Int96};
 use arrow::array::{
     Array, ArrayRef, BinaryBuilder, BooleanArray, BooleanBufferBuilder,
-    BufferBuilderTrait, StringBuilder, TimestampNanosecondBuilder,
+    BufferBuilderTrait, Decimal128Array, Decimal128Builder,
 };
 use arrow::compute::cast;
 use std::convert::From;
@@ -112,15 +112,11 @@ pub struct Int96ArrayConverter {}
 
 impl Converter<Vec<Option<Int96>>, TimestampNanosecondArray> for Int96ArrayConverter {
     fn convert(source: Vec<Option<Int96>>) -> Result<TimestampNanosecondArray> {
-        let mut builder = TimestampNanosecondBuilder::new(source.len());
-        for v in source {
-            match v {
-                Some(array) => builder.append_value(array.to_i64() * 1000000),
-                None => builder.append_null(),
-            }?
-        }
-
-        Ok(builder.finish())
+        let iter = source
+            .into_iter()
+            .map(|v| v.map(|array| array.to_i64() * 1000000));
+        // the iterator over a vector reports its exact length
+        Ok(unsafe { TimestampNanosecondArray::from_trusted_len_iter(iter) })
     }
 }
 
@@ -128,10 +124,23 @@ pub struct Utf8ArrayConverter {}
 
 impl Converter<Vec<Option<ByteArray>>, StringArray> for Utf8ArrayConverter {
     fn convert(source: Vec<Option<ByteArray>>) -> Result<StringArray> {
-        let mut builder = StringBuilder::new(source.len());
+        let values = source
+            .iter()
+            .map(|v| v.as_ref().map(|array| array.as_utf8()).transpose())
+            .collect::<Result<Vec<_>>>()?;
+        // the iterator over a vector reports its exact length
+        Ok(unsafe { StringArray::from_trusted_len_iter(values) })
+    }
+}
+
+pub struct BinaryArrayConverter {}
+
+impl Converter<Vec<Option<ByteArray>>, BinaryArray> for BinaryArrayConverter {
+    fn convert(source: Vec<Option<ByteArray>>) -> Result<BinaryArray> {
+        let mut builder = BinaryBuilder::new(source.len());
         for v in source {
             match v {
-                Some(array) => builder.append_value(array.as_utf8()?),
+                Some(array) => builder.append_value(array.data()),
                 None => builder.append_null(),
             }?
         }
@@ -140,14 +149,24 @@ impl Converter<Vec<Option<ByteArray>>, StringArray> for Utf8ArrayConverter {
     }
 }
 
-pub struct BinaryArrayConverter {}
+/// Converts the big-endian two's complement integers that parquet stores decimals as
+/// into a `Decimal128Array`. The converter does not know the precision and scale of
+/// the column, so the array has the maximum precision and a scale of zero, and the
+/// array reader replaces them with those of the column.
+pub struct DecimalArrayConverter {}
 
-impl Converter<Vec<Option<ByteArray>>, BinaryArray> for BinaryArrayConverter {
-    fn convert(source: Vec<Option<ByteArray>>) -> Result<BinaryArray> {
-        let mut builder = BinaryBuilder::new(source.len());
+impl DecimalArrayConverter {
+    /// The largest number of digits of a 128-bit decimal
+    pub const MAX_PRECISION: usize = 38;
+}
+
+impl Converter<Vec<Option<ByteArray>>, Decimal128Array> for DecimalArrayConverter {
+    fn convert(source: Vec<Option<ByteArray>>) -> Result<Decimal128Array> {
+        let mut builder =
+            Decimal128Builder::new(source.len(), DecimalArrayConverter::MAX_PRECISION, 0);
         for v in source {
             match v {
-                Some(array) => builder.append_value(array.data()),
+                Some(array) => builder.append_value(decimal_from_be_bytes(array.data())?),
                 None => builder.append_null(),
             }?
         }
@@ -156,6 +175,20 @@ impl Converter<Vec<Option<ByteArray>>, BinaryArray> for BinaryArrayConverter {
     }
 }
 
+/// Sign-extends a big-endian two's complement integer of at most 16 bytes to an i128
+fn decimal_from_be_bytes(bytes: &[u8]) -> Result<i128> {
+    if bytes.len() > 16 {
+        return Err(general_err!(
+            "Unable to read a decimal of {} bytes into a 128-bit integer",
+            bytes.len()
+        ));
+    }
+    let negative = bytes.first().map_or(false, |b| b & 0x80 != 0);
+    let mut extended = if negative { [0xff; 16] } else { [0; 16] };
+    extended[16 - bytes.len()..].copy_from_slice(bytes);
+    Ok(i128::from_be_bytes(extended))
+}
+
 pub type BoolConverter<'a> = ArrayRefConverter<
     &'a mut RecordReader<BoolType>,
     BooleanArray,
@@ -175,6 +208,8 @@ pub type Utf8Converter =
     ArrayRefConverter<Vec<Option<ByteArray>>, StringArray, Utf8ArrayConverter>;
 pub type BinaryConverter =
     ArrayRefConverter<Vec<Option<ByteArray>>, BinaryArray, BinaryArrayConverter>;
+pub type DecimalConverter =
+    ArrayRefConverter<Vec<Option<ByteArray>>, Decimal128Array, DecimalArrayConverter>;
 pub type Int96Converter =
     ArrayRefConverter<Vec<Option<Int96>>, TimestampNanosecondArray, Int96ArrayConverter>;
 
@@ -291,6 +326,27 @@ mod tests {
         assert!(array.equals(&PrimitiveArray::<Int32Type>::from(raw_data)));
     }
 
+    #[test]
+    fn test_decimal_converter() {
+        let source = vec![
+            Some(ByteArray::from(vec![0x04, 0xd2])),
+            None,
+            Some(ByteArray::from(vec![0xfb, 0x2e])),
+            Some(ByteArray::from(vec![0xff; 16])),
+            Some(ByteArray::from(vec![0x00, 0x00, 0x80])),
+        ];
+        let array = DecimalArrayConverter::convert(source).unwrap();
+        assert_eq!(5, array.len());
+        assert_eq!(1234, array.value(0));
+        assert!(array.is_null(1));
+        assert_eq!(-1234, array.value(2));
+        assert_eq!(-1, array.value(3));
+        assert_eq!(128, array.value(4));
+
+        let too_long = vec![Some(ByteArray::from(vec![0; 17]))];
+        assert!(DecimalArrayConverter::convert(too_long).is_err());
+    }
+
     fn build_record_reader<T: DataType>(
         message_type: &str,
         values: &[T::T],
@@ -48,13 +48,37 @@
 //!    }
 //!}
 //! ```
+//!
+//! # Example of writing arrow record batches to a parquet file
+//!
+//! ```rust, no_run
+//! use arrow::array::Int32Array;
+//! use arrow::datatypes::{DataType, Field, Schema};
+//! use arrow::record_batch::RecordBatch;
+//! use parquet::arrow::ArrowWriter;
+//! use std::fs::File;
+//! use std::sync::Arc;
+//!
+//! let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+//! let a = Int32Array::from(vec![Some(1), None]);
+//! let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(a)]).unwrap();
+//!
+//! let file = File::create("parquet.file").unwrap();
+//! let mut writer = ArrowWriter::try_new(file, schema, None).unwrap();
+//! writer.write(&batch).unwrap();
+//! writer.close().unwrap();
+//! ```
 
 pub(in crate::arrow) mod array_reader;
 pub mod arrow_reader;
+pub mod arrow_writer;
 pub(in crate::arrow) mod converter;
 pub(in crate::arrow) mod record_reader;
 pub mod schema;
 
 pub use self::arrow_reader::ArrowReader;
 pub use self::arrow_reader::ParquetFileArrowReader;
-pub use self::schema::{parquet_to_arrow_schema, parquet_to_arrow_schema_by_columns};
+pub use self::arrow_writer::ArrowWriter;
+pub use self::schema::{
+    arrow_to_parquet_schema, parquet_to_arrow_schema, parquet_to_arrow_schema_by_columns,
+};
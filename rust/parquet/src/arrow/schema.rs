@@ -21,10 +21,13 @@
 //! `parquet_to_arrow_schema`, `parquet_to_arrow_schema_by_columns` and
 //! `parquet_to_arrow_field`.
 //!
-//! The interfaces for converting arrow schema to parquet schema is coming.
+//! Arrow schemas of flat, non-nested columns are converted to parquet schemas with
+//! `arrow_to_parquet_schema`.
 
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
+use crate::arrow::converter::DecimalArrayConverter;
 use crate::basic::{LogicalType, Repetition, Type as PhysicalType};
 use crate::errors::{ParquetError::ArrowError, Result};
 use crate::schema::types::{ColumnDescriptor, SchemaDescriptor, Type};
@@ -82,6 +85,76 @@ where
         .map(|fields| Schema::new_with_metadata(fields, metadata))
 }
 
+/// Convert arrow schema to parquet schema. Only flat schemas of primitive, string and
+/// decimal columns are supported. Nullable fields are optional, and timestamps with a
+/// nanosecond unit are stored as INT96, since parquet has no such logical type.
+pub fn arrow_to_parquet_schema(schema: &Schema) -> Result<Type> {
+    let mut fields = schema
+        .fields()
+        .iter()
+        .map(|field| arrow_to_parquet_type(field).map(Rc::new))
+        .collect::<Result<Vec<_>>>()?;
+    Type::group_type_builder("arrow_schema")
+        .with_fields(&mut fields)
+        .build()
+}
+
+/// Convert arrow field to parquet primitive type.
+fn arrow_to_parquet_type(field: &Field) -> Result<Type> {
+    let (physical_type, logical_type) = match field.data_type() {
+        DataType::Boolean => (PhysicalType::BOOLEAN, LogicalType::NONE),
+        DataType::Int8 => (PhysicalType::INT32, LogicalType::INT_8),
+        DataType::Int16 => (PhysicalType::INT32, LogicalType::INT_16),
+        DataType::Int32 => (PhysicalType::INT32, LogicalType::NONE),
+        DataType::Int64 => (PhysicalType::INT64, LogicalType::NONE),
+        DataType::UInt8 => (PhysicalType::INT32, LogicalType::UINT_8),
+        DataType::UInt16 => (PhysicalType::INT32, LogicalType::UINT_16),
+        DataType::UInt32 => (PhysicalType::INT32, LogicalType::UINT_32),
+        DataType::UInt64 => (PhysicalType::INT64, LogicalType::UINT_64),
+        DataType::Float32 => (PhysicalType::FLOAT, LogicalType::NONE),
+        DataType::Float64 => (PhysicalType::DOUBLE, LogicalType::NONE),
+        DataType::Utf8 => (PhysicalType::BYTE_ARRAY, LogicalType::UTF8),
+        DataType::Binary => (PhysicalType::BYTE_ARRAY, LogicalType::NONE),
+        DataType::Date32(DateUnit::Day) => (PhysicalType::INT32, LogicalType::DATE),
+        DataType::Timestamp(TimeUnit::Second, None)
+        | DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            (PhysicalType::INT64, LogicalType::TIMESTAMP_MICROS)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            (PhysicalType::INT96, LogicalType::NONE)
+        }
+        DataType::Decimal(..) => {
+            (PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::DECIMAL)
+        }
+        other => {
+            return Err(ArrowError(format!(
+                "Unable to convert arrow type {:?} of field '{}' to parquet",
+                other,
+                field.name()
+            )))
+        }
+    };
+    let repetition = if field.is_nullable() {
+        Repetition::OPTIONAL
+    } else {
+        Repetition::REQUIRED
+    };
+    let builder = Type::primitive_type_builder(field.name(), physical_type)
+        .with_repetition(repetition)
+        .with_logical_type(logical_type);
+    match field.data_type() {
+        DataType::Decimal(precision, scale) => builder
+            .with_length(16)
+            .with_precision(*precision as i32)
+            .with_scale(*scale as i32)
+            .build(),
+        _ => builder.build(),
+    }
+}
+
 fn parse_key_value_metadata(
     key_value_metadata: &Option<Vec<KeyValue>>,
 ) -> Option<HashMap<String, String>> {
@@ -228,6 +301,7 @@ impl ParquetTypeConverter<'_> {
             PhysicalType::FLOAT => Ok(DataType::Float32),
             PhysicalType::DOUBLE => Ok(DataType::Float64),
             PhysicalType::BYTE_ARRAY => self.from_byte_array(),
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => self.from_fixed_len_byte_array(),
             other => Err(ArrowError(format!(
                 "Unable to convert parquet physical type {}",
                 other
@@ -276,6 +350,7 @@ impl ParquetTypeConverter<'_> {
         match self.schema.get_basic_info().logical_type() {
             LogicalType::NONE => Ok(DataType::Binary),
             LogicalType::UTF8 => Ok(DataType::Utf8),
+            LogicalType::DECIMAL => self.to_decimal(),
             other => Err(ArrowError(format!(
                 "Unable to convert parquet BYTE_ARRAY logical type {}",
                 other
@@ -283,6 +358,34 @@ impl ParquetTypeConverter<'_> {
         }
     }
 
+    fn from_fixed_len_byte_array(&self) -> Result<DataType> {
+        match self.schema.get_basic_info().logical_type() {
+            LogicalType::DECIMAL => self.to_decimal(),
+            other => Err(ArrowError(format!(
+                "Unable to convert parquet FIXED_LEN_BYTE_ARRAY logical type {}",
+                other
+            ))),
+        }
+    }
+
+    /// Converts a decimal whose unscaled value is stored as a big-endian two's
+    /// complement integer to a 128-bit arrow decimal.
+    fn to_decimal(&self) -> Result<DataType> {
+        match *self.schema {
+            Type::PrimitiveType {
+                precision, scale, ..
+            } if precision <= DecimalArrayConverter::MAX_PRECISION as i32 => {
+                Ok(DataType::Decimal(precision as usize, scale as usize))
+            }
+            Type::PrimitiveType { precision, .. } => Err(ArrowError(format!(
+                "Unable to convert parquet DECIMAL with precision {}, the maximum is {}",
+                precision,
+                DecimalArrayConverter::MAX_PRECISION
+            ))),
+            _ => Err(ArrowError("Expected a primitive type".to_string())),
+        }
+    }
+
     // Functions for group types.
 
     /// Entry point for converting parquet group type.
@@ -391,10 +494,10 @@ mod tests {
 
     use crate::schema::{parser::parse_message_type, types::SchemaDescriptor};
 
-    use arrow::datatypes::{DataType, DateUnit, Field, TimeUnit};
+    use arrow::datatypes::{DataType, DateUnit, Field, Schema, TimeUnit};
 
     use super::{
-        parquet_to_arrow_field, parquet_to_arrow_schema,
+        arrow_to_parquet_schema, parquet_to_arrow_field, parquet_to_arrow_schema,
         parquet_to_arrow_schema_by_columns,
     };
     use crate::file::metadata::KeyValue;
@@ -434,6 +537,72 @@ mod tests {
         assert_eq!(&arrow_fields, converted_arrow_schema.fields());
     }
 
+    #[test]
+    fn test_arrow_to_parquet_schema() {
+        let arrow_fields = vec![
+            Field::new("boolean", DataType::Boolean, false),
+            Field::new("int8", DataType::Int8, false),
+            Field::new("uint32", DataType::UInt32, true),
+            Field::new("int64", DataType::Int64, true),
+            Field::new("double", DataType::Float64, true),
+            Field::new("string", DataType::Utf8, true),
+            Field::new("date", DataType::Date32(DateUnit::Day), true),
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                true,
+            ),
+            Field::new("decimal", DataType::Decimal(20, 2), true),
+        ];
+        let arrow_schema = Schema::new(arrow_fields.clone());
+        let parquet_schema = SchemaDescriptor::new(Rc::new(
+            arrow_to_parquet_schema(&arrow_schema).unwrap(),
+        ));
+
+        // the parquet schema converts back to the arrow schema
+        let converted_arrow_schema =
+            parquet_to_arrow_schema(&parquet_schema, &None).unwrap();
+        assert_eq!(&arrow_fields, converted_arrow_schema.fields());
+
+        // nested types are not supported
+        let arrow_schema = Schema::new(vec![Field::new(
+            "list",
+            DataType::List(Box::new(DataType::Int32)),
+            true,
+        )]);
+        assert!(arrow_to_parquet_schema(&arrow_schema).is_err());
+    }
+
+    #[test]
+    fn test_decimals() {
+        let message_type = "
+        message test_schema {
+            REQUIRED FIXED_LEN_BYTE_ARRAY (11) fixed_decimal (DECIMAL(25, 2));
+            OPTIONAL BINARY binary_decimal (DECIMAL(38, 10));
+        }
+        ";
+        let parquet_group_type = parse_message_type(message_type).unwrap();
+
+        let parquet_schema = SchemaDescriptor::new(Rc::new(parquet_group_type));
+        let converted_arrow_schema =
+            parquet_to_arrow_schema(&parquet_schema, &None).unwrap();
+
+        let arrow_fields = vec![
+            Field::new("fixed_decimal", DataType::Decimal(25, 2), false),
+            Field::new("binary_decimal", DataType::Decimal(38, 10), true),
+        ];
+        assert_eq!(&arrow_fields, converted_arrow_schema.fields());
+
+        let message_type = "
+        message test_schema {
+            REQUIRED BINARY decimal (DECIMAL(40, 2));
+        }
+        ";
+        let parquet_group_type = parse_message_type(message_type).unwrap();
+        let parquet_schema = SchemaDescriptor::new(Rc::new(parquet_group_type));
+        assert!(parquet_to_arrow_schema(&parquet_schema, &None).is_err());
+    }
+
     #[test]
     fn test_duplicate_fields() {
         let message_type = "